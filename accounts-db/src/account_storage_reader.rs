@@ -1,7 +1,7 @@
 use {
     crate::{
         account_info::Offset, account_storage_entry::AccountStorageEntry,
-        accounts_file::OpenFileForArchive,
+        accounts_file::OpenFileForArchive, compressed_file::AppendVecLayoutReader,
     },
     agave_fs::{
         buffered_reader::{self, FileBufRead},
@@ -78,21 +78,55 @@ pub enum TombstonesFilter {
 /// The caller is responsible for activating the storage's file on `file_reader`
 /// via `set_file` (typically using a file opened with [`open_storage_files`])
 /// before constructing the reader.
+///
+/// Compressed storages are read in their AppendVec representation, and do not
+/// use `file_reader`; their file does not need to be set.
 pub struct AccountStorageReader<'r, R> {
     sorted_excluded_accounts: Vec<(Offset, usize)>,
-    reader: &'r mut R,
+    source: StorageSource<'r, R>,
     num_alive_bytes: usize,
     num_total_bytes: usize,
 }
 
+/// Where the bytes of the storage come from
+enum StorageSource<'r, R> {
+    /// The storage's file, as is
+    File(&'r mut R),
+    /// The AppendVec representation of a compressed storage
+    Compressed(AppendVecLayoutReader<'r>),
+}
+
+impl<'a, R: FileBufRead<'a>> StorageSource<'_, R> {
+    fn get_file_offset(&self) -> usize {
+        match self {
+            Self::File(reader) => reader.get_file_offset() as usize,
+            Self::Compressed(reader) => reader.get_file_offset(),
+        }
+    }
+
+    fn consume_or_skip(&mut self, amt: usize) {
+        match self {
+            Self::File(reader) => reader.consume_or_skip(amt),
+            Self::Compressed(reader) => reader.consume_or_skip(amt),
+        }
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::File(reader) => reader.read(buf),
+            Self::Compressed(reader) => reader.read(buf),
+        }
+    }
+}
+
 impl<'a, 'r, R: FileBufRead<'a>> AccountStorageReader<'r, R> {
     /// Creates a new `AccountStorageReader` from an `AccountStorageEntry`.
     /// The excluded accounts list is sorted during initialization.
     ///
     /// Expects that the caller has already attached the storage's file to
-    /// `file_reader` via `set_file`.
+    /// `file_reader` via `set_file`, unless the storage is compressed.
     pub fn new(
-        storage: &AccountStorageEntry,
+        storage: &'r AccountStorageEntry,
         snapshot_slot: Option<Slot>,
         tombstones_filter: TombstonesFilter,
         file_reader: &'r mut R,
@@ -128,9 +162,14 @@ impl<'a, 'r, R: FileBufRead<'a>> AccountStorageReader<'r, R> {
         sorted_excluded_accounts
             .sort_unstable_by(|(a_offset, _), (b_offset, _)| b_offset.cmp(a_offset));
 
+        let source = match storage.accounts.append_vec_layout_reader() {
+            Some(reader) => StorageSource::Compressed(reader),
+            None => StorageSource::File(file_reader),
+        };
+
         Ok(Self {
             sorted_excluded_accounts,
-            reader: file_reader,
+            source,
             num_alive_bytes,
             num_total_bytes,
         })
@@ -152,12 +191,12 @@ impl<'a, R: FileBufRead<'a>> Read for AccountStorageReader<'_, R> {
 
         while total_read < buf_len {
             let next_excluded_account = self.sorted_excluded_accounts.last();
-            let file_offset = self.reader.get_file_offset() as usize;
+            let file_offset = self.source.get_file_offset();
             if let Some(&(excluded_start, excluded_size)) = next_excluded_account
                 && file_offset == excluded_start
             {
                 let skip_len = excluded_size.min(self.num_total_bytes - excluded_start);
-                self.reader.consume_or_skip(skip_len);
+                self.source.consume_or_skip(skip_len);
                 self.sorted_excluded_accounts.pop();
                 continue;
             }
@@ -174,7 +213,7 @@ impl<'a, R: FileBufRead<'a>> Read for AccountStorageReader<'_, R> {
 
             let bytes_to_read = bytes_left_in_buffer.min(bytes_to_read_from_file);
 
-            let read_size = self.reader.read(&mut buf[total_read..][..bytes_to_read])?;
+            let read_size = self.source.read(&mut buf[total_read..][..bytes_to_read])?;
 
             if read_size == 0 {
                 break; // EOF
@@ -224,6 +263,7 @@ mod tests {
     }
 
     #[test_case(AccountsFileProvider::AppendVec)]
+    #[test_case(AccountsFileProvider::Compressed)]
     fn test_account_storage_reader_no_obsolete_accounts(provider: AccountsFileProvider) {
         let (storage, _temp_dirs) = create_storage_for_storage_reader(0, provider);
 
@@ -256,28 +296,88 @@ mod tests {
         assert_eq!(reader.len(), storage.accounts.len());
     }
 
-    #[test_case(0, 0, 0, TombstonesFilter::Include)]
-    #[test_case(1, 0, 0, TombstonesFilter::Include)]
-    #[test_case(1, 1, 0, TombstonesFilter::Include)]
-    #[test_case(1, 1, 0, TombstonesFilter::Exclude)]
-    #[test_case(1, 0, 1, TombstonesFilter::Include)]
-    #[test_case(100, 0, 0, TombstonesFilter::Include)]
-    #[test_case(100, 0, 10, TombstonesFilter::Include)]
-    #[test_case(100, 0, 100, TombstonesFilter::Include)]
-    #[test_case(100, 10, 0, TombstonesFilter::Include)]
-    #[test_case(100, 10, 0, TombstonesFilter::Exclude)]
-    #[test_case(100, 100, 0, TombstonesFilter::Include)]
-    #[test_case(100, 100, 0, TombstonesFilter::Exclude)]
-    #[test_case(100, 10, 10, TombstonesFilter::Include)]
-    #[test_case(100, 10, 10, TombstonesFilter::Exclude)]
+    #[test_case(0, 0, 0, TombstonesFilter::Include, AccountsFileProvider::AppendVec)]
+    #[test_case(1, 0, 0, TombstonesFilter::Include, AccountsFileProvider::AppendVec)]
+    #[test_case(1, 1, 0, TombstonesFilter::Include, AccountsFileProvider::AppendVec)]
+    #[test_case(1, 1, 0, TombstonesFilter::Exclude, AccountsFileProvider::AppendVec)]
+    #[test_case(1, 0, 1, TombstonesFilter::Include, AccountsFileProvider::AppendVec)]
+    #[test_case(100, 0, 0, TombstonesFilter::Include, AccountsFileProvider::AppendVec)]
+    #[test_case(100, 0, 10, TombstonesFilter::Include, AccountsFileProvider::AppendVec)]
+    #[test_case(
+        100,
+        0,
+        100,
+        TombstonesFilter::Include,
+        AccountsFileProvider::AppendVec
+    )]
+    #[test_case(100, 10, 0, TombstonesFilter::Include, AccountsFileProvider::AppendVec)]
+    #[test_case(100, 10, 0, TombstonesFilter::Exclude, AccountsFileProvider::AppendVec)]
+    #[test_case(
+        100,
+        100,
+        0,
+        TombstonesFilter::Include,
+        AccountsFileProvider::AppendVec
+    )]
+    #[test_case(
+        100,
+        100,
+        0,
+        TombstonesFilter::Exclude,
+        AccountsFileProvider::AppendVec
+    )]
+    #[test_case(
+        100,
+        10,
+        10,
+        TombstonesFilter::Include,
+        AccountsFileProvider::AppendVec
+    )]
+    #[test_case(
+        100,
+        10,
+        10,
+        TombstonesFilter::Exclude,
+        AccountsFileProvider::AppendVec
+    )]
+    #[test_case(0, 0, 0, TombstonesFilter::Include, AccountsFileProvider::Compressed)]
+    #[test_case(100, 0, 0, TombstonesFilter::Include, AccountsFileProvider::Compressed)]
+    #[test_case(
+        100,
+        10,
+        10,
+        TombstonesFilter::Include,
+        AccountsFileProvider::Compressed
+    )]
+    #[test_case(
+        100,
+        10,
+        10,
+        TombstonesFilter::Exclude,
+        AccountsFileProvider::Compressed
+    )]
+    #[test_case(
+        100,
+        100,
+        0,
+        TombstonesFilter::Exclude,
+        AccountsFileProvider::Compressed
+    )]
+    #[test_case(
+        100,
+        0,
+        100,
+        TombstonesFilter::Include,
+        AccountsFileProvider::Compressed
+    )]
     fn test_account_storage_reader_with_excluded_accounts(
         total_accounts: usize,
         num_tombstones: usize,
         num_obsolete: usize,
         tombstones_filter: TombstonesFilter,
+        provider: AccountsFileProvider,
     ) {
-        let (storage, _temp_dirs) =
-            create_storage_for_storage_reader(0, AccountsFileProvider::AppendVec);
+        let (storage, _temp_dirs) = create_storage_for_storage_reader(0, provider);
 
         let slot = 0;
 
@@ -9,6 +9,7 @@ use {
         accounts_index::IndexKey,
         accounts_scan::{ScanConfig, ScanError, ScanResult},
         ancestors::Ancestors,
+        historical_view::{HistoricalView, HistoricalViewError},
         is_loadable::IsLoadable as _,
         storable_accounts::StorableAccounts,
     },
@@ -202,6 +203,14 @@ impl Accounts {
         )
     }
 
+    /// Opens a read-only view of account state as of the rooted `slot`
+    pub fn historical_view(
+        &self,
+        slot: Slot,
+    ) -> std::result::Result<HistoricalView, HistoricalViewError> {
+        HistoricalView::new(Arc::clone(&self.accounts_db), slot)
+    }
+
     /// scans underlying accounts_db for this delta (slot) with a map function
     ///   from LoadedAccount to B
     /// returns only the latest/current version of B for this slot
@@ -46,9 +46,9 @@ use {
         accounts_file::AccountsFileProvider,
         accounts_hash::{AccountLtHash, AccountsLtHash, ZERO_LAMPORT_ACCOUNT_LT_HASH},
         accounts_index::{
-            AccountSecondaryIndexes, AccountsIndex, IndexKey, ReclaimsSlotList,
-            ReclaimsWithNewestSlot, RefCount, ScanFilter, SlotList, Startup, UpsertReclaim,
-            in_mem_accounts_index::StartupStats,
+            AccountIndex, AccountSecondaryIndexes, AccountsIndex, IndexKey, ReclaimsSlotList,
+            ReclaimsWithNewestSlot, RefCount, ScanFilter, SecondaryIndexFileError, SlotList,
+            Startup, UpsertReclaim, in_mem_accounts_index::StartupStats,
         },
         accounts_scan::{ScanConfig, ScanError, ScanGuard, ScanResult, ScanTracker},
         accounts_update_notifier_interface::{AccountForGeyser, AccountsUpdateNotifier},
@@ -84,7 +84,7 @@ use {
         borrow::Cow,
         boxed::Box,
         collections::{BTreeSet, HashMap, HashSet, VecDeque},
-        io, iter, mem,
+        fs, io, iter, mem,
         num::Saturating,
         ops::RangeBounds,
         path::{Path, PathBuf},
@@ -918,6 +918,10 @@ pub struct AccountsDb {
 
     pub account_indexes: AccountSecondaryIndexes,
 
+    /// Secondary indexes loaded from a bank snapshot by `load_secondary_indexes()`, which index
+    /// generation does not need to build
+    loaded_secondary_indexes: HashSet<AccountIndex>,
+
     /// Set of unique keys per slot which is used
     /// to drive clean_accounts
     /// Populated when flushing the accounts write cache
@@ -958,6 +962,9 @@ pub struct AccountsDb {
     /// storage format to use for new storages
     accounts_file_provider: AccountsFileProvider,
 
+    /// storage format to use for storages written by ancient packing
+    pub(crate) ancient_accounts_file_provider: AccountsFileProvider,
+
     /// index scan filtering for shrinking
     scan_filter_for_shrinking: ScanFilter,
 
@@ -982,6 +989,10 @@ pub struct AccountsDb {
 
     /// The largest slot that has been added as a root via `add_root`.
     max_root: AtomicU64,
+
+    /// Number of slots behind `max_root` that clean holds back from, see
+    /// `AccountsDbConfig::historical_retention_slots`
+    historical_retention_slots: Option<u64>,
 }
 
 pub fn quarter_thread_count() -> usize {
@@ -1101,6 +1112,7 @@ impl AccountsDb {
                 .unwrap_or(DEFAULT_MAX_ANCIENT_STORAGES),
             scan_tracker: ScanTracker::default(),
             account_indexes: accounts_db_config.account_indexes.unwrap_or_default(),
+            loaded_secondary_indexes: HashSet::default(),
             shrink_ratio: accounts_db_config.shrink_ratio,
             accounts_update_notifier,
             read_only_accounts_cache: ReadOnlyAccountsCache::new(
@@ -1138,10 +1150,12 @@ impl AccountsDb {
             zero_lamport_accounts_to_purge_after_full_snapshot: DashSet::default(),
             latest_full_snapshot_slot_advanced_since_clean: AtomicBool::default(),
             accounts_file_provider: accounts_db_config.accounts_file_provider,
+            ancient_accounts_file_provider: accounts_db_config.ancient_accounts_file_provider,
             latest_full_snapshot_slot: SeqLock::new(None),
             last_swept_full_snapshot_slot: AtomicU64::new(0),
             best_ancient_slots_to_shrink: RwLock::default(),
             max_root: AtomicU64::new(0),
+            historical_retention_slots: accounts_db_config.historical_retention_slots,
         };
 
         {
@@ -1457,17 +1471,19 @@ impl AccountsDb {
     }
 
     fn max_clean_root(&self, proposed_clean_root: Option<Slot>) -> Option<Slot> {
-        match (
-            self.scan_tracker.min_ongoing_scan_root(),
-            proposed_clean_root,
-        ) {
-            (None, None) => None,
-            (Some(min_scan_root), None) => Some(min_scan_root),
-            (None, Some(proposed_clean_root)) => Some(proposed_clean_root),
-            (Some(min_scan_root), Some(proposed_clean_root)) => {
-                Some(std::cmp::min(min_scan_root, proposed_clean_root))
+        // Hold clean back from the retention window so historical views can be opened there
+        let proposed_clean_root = match self.historical_retention_slots {
+            Some(retention_slots) => {
+                let retained_root = self.max_root().saturating_sub(retention_slots);
+                Some(
+                    proposed_clean_root.map_or(retained_root, |proposed_clean_root| {
+                        proposed_clean_root.min(retained_root)
+                    }),
+                )
             }
-        }
+            None => proposed_clean_root,
+        };
+        self.scan_tracker.max_clean_root(proposed_clean_root)
     }
 
     /// get the oldest slot that is within one epoch of the highest known root.
@@ -2926,7 +2942,18 @@ impl AccountsDb {
         old_store: Arc<AccountStorageEntry>,
         size: u64,
     ) -> ShrinkInProgress<'_> {
-        let shrunken_store = Arc::new(self.create_store(slot, size));
+        self.get_store_for_shrink_with_provider(slot, old_store, size, self.accounts_file_provider)
+    }
+
+    /// Like `get_store_for_shrink()`, but the new storage has the format of `provider`
+    pub(crate) fn get_store_for_shrink_with_provider(
+        &self,
+        slot: Slot,
+        old_store: Arc<AccountStorageEntry>,
+        size: u64,
+        provider: AccountsFileProvider,
+    ) -> ShrinkInProgress<'_> {
+        let shrunken_store = Arc::new(self.create_store_with_provider(slot, size, provider));
         self.storage
             .shrinking_in_progress(slot, old_store, shrunken_store)
     }
@@ -3333,12 +3360,14 @@ impl AccountsDb {
     where
         F: FnMut(Option<(&Pubkey, AccountSharedData, Slot)>),
     {
-        let key = match &index_key {
-            IndexKey::ProgramId(key) => key,
-            IndexKey::SplTokenMint(key) => key,
-            IndexKey::SplTokenOwner(key) => key,
+        let is_key_indexed = match &index_key {
+            IndexKey::ProgramId(key)
+            | IndexKey::SplTokenMint(key)
+            | IndexKey::SplTokenOwner(key) => self.account_indexes.include_key(key),
+            // data slice indexes are not restricted by the included or excluded keys
+            IndexKey::DataSlice(..) => true,
         };
-        if !self.account_indexes.include_key(key) {
+        if !is_key_indexed {
             // the requested key was not indexed in the secondary index, so do a normal scan
             let used_index = false;
             self.scan_accounts(ancestors, bank_id, scan_func, config)?;
@@ -3846,6 +3875,15 @@ impl AccountsDb {
     }
 
     fn create_store(&self, slot: Slot, size: u64) -> AccountStorageEntry {
+        self.create_store_with_provider(slot, size, self.accounts_file_provider)
+    }
+
+    fn create_store_with_provider(
+        &self,
+        slot: Slot,
+        size: u64,
+        provider: AccountsFileProvider,
+    ) -> AccountStorageEntry {
         self.stats
             .create_store_count
             .fetch_add(1, Ordering::Relaxed);
@@ -3856,7 +3894,7 @@ impl AccountsDb {
             slot,
             self.next_id(),
             size,
-            self.accounts_file_provider,
+            provider,
         )
     }
 
@@ -5622,6 +5660,7 @@ impl AccountsDb {
         cache_time.stop();
 
         self.max_root.fetch_max(slot, Ordering::Relaxed);
+        self.scan_tracker.add_historical_root(slot);
 
         AccountsAddRootTiming {
             cache_us: cache_time.as_us(),
@@ -5677,12 +5716,63 @@ impl AccountsDb {
             .store(slot, Ordering::Relaxed);
     }
 
+    /// Saves the secondary indexes to `dir`, alongside the bank snapshot for `slot`.
+    ///
+    /// Accounts are only removed from the secondary indexes once they are dead at or before the
+    /// latest full snapshot slot. So the saved indexes hold every account alive at `slot`, plus
+    /// possibly newer ones, as long as the latest full snapshot slot does not pass `slot` before
+    /// saving completes. If it does, the files are removed and an error is returned.
+    ///
+    /// Returns the number of inner keys saved across all the secondary indexes.
+    pub fn save_secondary_indexes(
+        &self,
+        dir: &Path,
+        slot: Slot,
+    ) -> Result<u64, SecondaryIndexFileError> {
+        if self.account_indexes.is_empty() {
+            return Ok(0);
+        }
+        let is_complete = || {
+            self.latest_full_snapshot_slot()
+                .is_some_and(|latest_full_snapshot_slot| latest_full_snapshot_slot <= slot)
+        };
+        if !is_complete() {
+            return Err(SecondaryIndexFileError::Incomplete(slot));
+        }
+        let mut result =
+            self.accounts_index
+                .save_secondary_indexes(dir, slot, &self.account_indexes);
+        if result.is_ok() && !is_complete() {
+            result = Err(SecondaryIndexFileError::Incomplete(slot));
+        }
+        if result.is_err() {
+            // Never leave files behind that a restart could load
+            let _ = fs::remove_dir_all(dir);
+        }
+        result
+    }
+
+    /// Loads the secondary indexes that were saved by `save_secondary_indexes()` for `slot` in
+    /// `dir`.
+    ///
+    /// Must be called before `generate_index()`, which then only generates the secondary indexes
+    /// that could not be loaded.
+    pub fn load_secondary_indexes(&mut self, dir: &Path, slot: Slot) {
+        if self.account_indexes.is_empty() {
+            return;
+        }
+        self.loaded_secondary_indexes =
+            self.accounts_index
+                .load_secondary_indexes(dir, slot, &self.account_indexes);
+    }
+
     fn generate_index_for_slot<'a>(
         &self,
         reader: &mut impl RequiredLenBufFileRead<'a>,
         accum: &mut IndexGenerationAccumulator,
         storage_index: usize,
         storage: &'a AccountStorageEntry,
+        account_indexes: &AccountSecondaryIndexes,
     ) {
         let slot = storage.slot();
         let store_id = storage.id();
@@ -5734,11 +5824,11 @@ impl AccountsDb {
                     ),
                 ));
 
-                if !self.account_indexes.is_empty() {
+                if !account_indexes.is_empty() {
                     self.accounts_index.update_secondary_indexes(
                         account.pubkey,
                         &account,
-                        &self.account_indexes,
+                        account_indexes,
                     );
                 }
 
@@ -5850,10 +5940,24 @@ impl AccountsDb {
         // `storages` is sorted by slot, so the last one is the highest root.
         if let Some(storage) = storages.last() {
             self.max_root.fetch_max(storage.slot(), Ordering::Relaxed);
+            // Older roots were cleaned before the snapshot was taken
+            self.scan_tracker.set_min_historical_root(storage.slot());
+            self.scan_tracker.add_historical_root(storage.slot());
         }
 
         self.accounts_index.set_startup(Startup::Startup);
 
+        // Only generate the secondary indexes that were not loaded from the bank snapshot
+        let account_indexes = AccountSecondaryIndexes {
+            keys: self.account_indexes.keys.clone(),
+            indexes: self
+                .account_indexes
+                .indexes
+                .difference(&self.loaded_secondary_indexes)
+                .cloned()
+                .collect(),
+        };
+
         let mut total_accum = IndexGenerationAccumulator::with_slots_capacity(num_storages);
         let storages_orderer =
             AccountStoragesOrderer::with_random_order(&storages).into_concurrent_consumer();
@@ -5878,6 +5982,7 @@ impl AccountsDb {
                                     &mut thread_accum,
                                     next_item.original_index,
                                     storage,
+                                    &account_indexes,
                                 );
                                 num_processed.fetch_add(1, Ordering::Relaxed);
                             }
@@ -6142,6 +6247,8 @@ impl AccountsDb {
         timings.total_time_us = total_time.as_us();
         timings.report(self.accounts_index.get_startup_stats());
 
+        self.accounts_index
+            .remove_unknown_keys_from_secondary_indexes(&self.loaded_secondary_indexes);
         self.accounts_index.log_secondary_indexes();
 
         // Now that the index is generated, get the total length and capacity of the in-mem maps
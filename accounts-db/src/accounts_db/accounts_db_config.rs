@@ -43,6 +43,12 @@ pub struct AccountsDbConfig {
     /// Number of threads for foreground operations (`thread_pool_foreground`)
     pub num_foreground_threads: Option<NonZeroUsize>,
     pub accounts_file_provider: AccountsFileProvider,
+    /// Storage format for storages written by ancient packing
+    pub ancient_accounts_file_provider: AccountsFileProvider,
+    /// Number of slots behind the max root that clean holds back from, so account
+    /// state as of those roots stays readable by historical views.
+    /// If None, clean is not held back.
+    pub historical_retention_slots: Option<u64>,
 }
 
 #[cfg(feature = "dev-context-only-utils")]
@@ -86,6 +92,8 @@ pub const ACCOUNTS_DB_CONFIG_FOR_TESTING: AccountsDbConfig = AccountsDbConfig {
     num_background_threads: None,
     num_foreground_threads: None,
     accounts_file_provider: AccountsFileProvider::AppendVec,
+    ancient_accounts_file_provider: AccountsFileProvider::AppendVec,
+    historical_retention_slots: None,
 };
 
 pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig {
@@ -107,6 +115,8 @@ pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig
     num_background_threads: None,
     num_foreground_threads: None,
     accounts_file_provider: AccountsFileProvider::AppendVec,
+    ancient_accounts_file_provider: AccountsFileProvider::AppendVec,
+    historical_retention_slots: None,
 };
 
 #[cfg(all(test, feature = "dev-context-only-utils"))]
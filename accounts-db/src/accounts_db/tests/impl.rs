@@ -135,7 +135,7 @@ fn test_generate_index_duplicates_within_slot() {
     let storage = db.get_storage_for_slot(slot0).unwrap();
     let mut reader = crate::append_vec::new_scan_accounts_reader();
     let mut accum = IndexGenerationAccumulator::with_slots_capacity(1);
-    db.generate_index_for_slot(&mut reader, &mut accum, 0, &storage);
+    db.generate_index_for_slot(&mut reader, &mut accum, 0, &storage, &db.account_indexes);
 }
 
 #[test]
@@ -171,6 +171,78 @@ fn test_generate_index_for_single_ref_zero_lamport_slot() {
     );
 }
 
+#[test]
+fn test_generate_index_with_loaded_secondary_indexes() {
+    let owner = Pubkey::new_unique();
+    let account = AccountSharedData::new(1, 0, &owner);
+    let pubkey = Pubkey::new_unique();
+    let loaded_pubkey = Pubkey::new_unique();
+    let unknown_pubkey = Pubkey::new_unique();
+    let temp_dir = TempDir::new().unwrap();
+    let secondary_indexes_dir = temp_dir.path().join("secondary_indexes");
+
+    let saved_db = AccountsDb {
+        account_indexes: program_id_index_enabled(),
+        ..AccountsDb::new_for_tests_with_config(Vec::new(), DEFAULT_ACCOUNTS_DB_CONFIG)
+    };
+    saved_db.store_for_tests((
+        1,
+        [(&loaded_pubkey, &account), (&unknown_pubkey, &account)].as_slice(),
+    ));
+
+    // Saving requires that no accounts alive at the slot may have been purged yet
+    assert!(matches!(
+        saved_db.save_secondary_indexes(&secondary_indexes_dir, 1),
+        Err(SecondaryIndexFileError::Incomplete(1)),
+    ));
+    saved_db.set_latest_full_snapshot_slot(2);
+    assert!(matches!(
+        saved_db.save_secondary_indexes(&secondary_indexes_dir, 1),
+        Err(SecondaryIndexFileError::Incomplete(1)),
+    ));
+    assert!(!secondary_indexes_dir.exists());
+    saved_db
+        .save_secondary_indexes(&secondary_indexes_dir, 2)
+        .unwrap();
+
+    // `pubkey` is only in the storage, so it shows whether the loaded index was regenerated
+    let mut db = AccountsDb {
+        account_indexes: program_id_index_enabled(),
+        ..AccountsDb::new_for_tests_with_config(Vec::new(), DEFAULT_ACCOUNTS_DB_CONFIG)
+    };
+    let store = db.create_store(2, 1000);
+    store.accounts.write_accounts(&(
+        2,
+        [(&pubkey, &account), (&loaded_pubkey, &account)].as_slice(),
+    ));
+    db.storage.insert(Arc::new(store));
+    db.load_secondary_indexes(&secondary_indexes_dir, 2);
+    db.generate_index(None, false);
+    assert_eq!(
+        db.accounts_index
+            .get_index_key_pubkeys(&IndexKey::ProgramId(owner)),
+        vec![loaded_pubkey],
+    );
+
+    // Without the saved index it is generated from the storage
+    let mut db = AccountsDb {
+        account_indexes: program_id_index_enabled(),
+        ..AccountsDb::new_for_tests_with_config(Vec::new(), DEFAULT_ACCOUNTS_DB_CONFIG)
+    };
+    let store = db.create_store(2, 1000);
+    store
+        .accounts
+        .write_accounts(&(2, [(&pubkey, &account)].as_slice()));
+    db.storage.insert(Arc::new(store));
+    db.load_secondary_indexes(&temp_dir.path().join("missing"), 2);
+    db.generate_index(None, false);
+    assert_eq!(
+        db.accounts_index
+            .get_index_key_pubkeys(&IndexKey::ProgramId(owner)),
+        vec![pubkey],
+    );
+}
+
 pub(crate) fn append_single_account_with_default_hash(
     storage: &AccountStorageEntry,
     pubkey: &Pubkey,
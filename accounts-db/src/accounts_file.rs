@@ -4,6 +4,7 @@ use {
         account_storage::stored_account_info::{StoredAccountInfo, StoredAccountInfoWithoutData},
         accounts_db::AccountsFileId,
         append_vec::{AppendVec, AppendVecError},
+        compressed_file::{AppendVecLayoutReader, CompressedFile, CompressedFileError},
         storable_accounts::StorableAccounts,
     },
     agave_fs::{FileInfo, buffered_reader::RequiredLenBufFileRead, file_io::open_for_reading},
@@ -39,6 +40,9 @@ pub enum AccountsFileError {
 
     #[error("AppendVecError: {0}")]
     AppendVecError(#[from] AppendVecError),
+
+    #[error("CompressedFileError: {0}")]
+    CompressedFileError(#[from] CompressedFileError),
 }
 
 #[derive(Debug)]
@@ -46,6 +50,8 @@ pub enum AccountsFileError {
 /// under different formats.
 pub enum AccountsFile {
     AppendVec(AppendVec),
+    /// Read-only, block-compressed storage; see [`CompressedFile`]
+    Compressed(CompressedFile),
 }
 
 impl AccountsFile {
@@ -55,6 +61,12 @@ impl AccountsFile {
     /// accounts file.
     #[cfg(feature = "dev-context-only-utils")]
     pub fn new_from_file(path: impl Into<PathBuf>, current_len: usize) -> Result<(Self, usize)> {
+        let path = path.into();
+        let file_info = FileInfo::new_from_path(&path)?;
+        if CompressedFile::is_compressed_file(&file_info)? {
+            let (compressed_file, num_accounts) = CompressedFile::new_from_file(path)?;
+            return Ok((Self::Compressed(compressed_file), num_accounts));
+        }
         let (av, num_accounts) = AppendVec::new_from_file(path, current_len)?;
         Ok((Self::AppendVec(av), num_accounts))
     }
@@ -64,7 +76,14 @@ impl AccountsFile {
     /// This version of `new()` may only be called when reconstructing storages as part of startup.
     /// The storage length is taken to be the full file size; this is trusted and relies on later
     /// index generation or accounts verification to ensure it is valid.
+    ///
+    /// Compressed files are recognized by their footer and take their length from it.
     pub fn new_for_startup(file_info: FileInfo) -> Result<Self> {
+        if CompressedFile::is_compressed_file(&file_info)? {
+            return Ok(Self::Compressed(CompressedFile::new_for_startup(
+                file_info,
+            )?));
+        }
         let av = AppendVec::new_for_startup(file_info)?;
         Ok(Self::AppendVec(av))
     }
@@ -73,6 +92,8 @@ impl AccountsFile {
     pub(crate) fn reopen_as_readonly(&self) -> Option<Self> {
         match self {
             Self::AppendVec(av) => av.reopen_as_readonly_file_io().map(Self::AppendVec),
+            // compressed files are always read only
+            Self::Compressed(_) => None,
         }
     }
 
@@ -81,6 +102,7 @@ impl AccountsFile {
     pub fn disable_remove_on_drop(&self) {
         match self {
             Self::AppendVec(av) => av.disable_remove_on_drop(),
+            Self::Compressed(cf) => cf.disable_remove_on_drop(),
         }
     }
 
@@ -89,6 +111,7 @@ impl AccountsFile {
     pub(crate) fn dead_bytes_due_to_zero_lamport_single_ref(&self, count: usize) -> usize {
         match self {
             Self::AppendVec(av) => av.dead_bytes_due_to_zero_lamport_single_ref(count),
+            Self::Compressed(cf) => cf.dead_bytes_due_to_zero_lamport_single_ref(count),
         }
     }
 
@@ -96,6 +119,7 @@ impl AccountsFile {
     pub fn flush(&self) -> Result<()> {
         match self {
             Self::AppendVec(av) => av.flush()?,
+            Self::Compressed(cf) => cf.flush()?,
         }
         Ok(())
     }
@@ -104,12 +128,30 @@ impl AccountsFile {
     pub fn len(&self) -> usize {
         match self {
             Self::AppendVec(av) => av.len(),
+            Self::Compressed(cf) => cf.len(),
         }
     }
 
     pub fn is_empty(&self) -> bool {
         match self {
             Self::AppendVec(av) => av.is_empty(),
+            Self::Compressed(cf) => cf.is_empty(),
+        }
+    }
+
+    /// Returns true if this is a read-only, compressed storage.
+    ///
+    /// Compressed storages are archived in the AppendVec format, see
+    /// [`Self::append_vec_layout_reader`].
+    pub fn is_compressed(&self) -> bool {
+        matches!(self, Self::Compressed(_))
+    }
+
+    /// Returns a reader of the AppendVec representation of a compressed storage.
+    pub(crate) fn append_vec_layout_reader(&self) -> Option<AppendVecLayoutReader<'_>> {
+        match self {
+            Self::AppendVec(_) => None,
+            Self::Compressed(cf) => Some(cf.append_vec_layout_reader()),
         }
     }
 
@@ -131,6 +173,7 @@ impl AccountsFile {
     ) -> Option<Ret> {
         match self {
             Self::AppendVec(av) => av.get_stored_account_without_data_callback(offset, callback),
+            Self::Compressed(cf) => cf.get_stored_account_without_data_callback(offset, callback),
         }
     }
 
@@ -148,6 +191,7 @@ impl AccountsFile {
     ) -> Option<Ret> {
         match self {
             Self::AppendVec(av) => av.get_stored_account_callback(offset, callback),
+            Self::Compressed(cf) => cf.get_stored_account_callback(offset, callback),
         }
     }
 
@@ -155,6 +199,7 @@ impl AccountsFile {
     pub(crate) fn get_account_shared_data(&self, offset: usize) -> Option<AccountSharedData> {
         match self {
             Self::AppendVec(av) => av.get_account_shared_data(offset),
+            Self::Compressed(cf) => cf.get_account_shared_data(offset),
         }
     }
 
@@ -162,6 +207,7 @@ impl AccountsFile {
     pub fn path(&self) -> &Path {
         match self {
             Self::AppendVec(av) => av.path(),
+            Self::Compressed(cf) => cf.path(),
         }
     }
 
@@ -178,6 +224,7 @@ impl AccountsFile {
     ) -> Result<()> {
         match self {
             Self::AppendVec(av) => av.scan_accounts_without_data(callback)?,
+            Self::Compressed(cf) => cf.scan_accounts_without_data(callback)?,
         }
         Ok(())
     }
@@ -197,6 +244,7 @@ impl AccountsFile {
     ) -> Result<()> {
         match self {
             Self::AppendVec(av) => av.scan_accounts(reader, callback)?,
+            Self::Compressed(cf) => cf.scan_accounts(callback)?,
         }
         Ok(())
     }
@@ -206,6 +254,7 @@ impl AccountsFile {
     pub(crate) fn calculate_stored_size(&self, data_len: usize) -> usize {
         match self {
             Self::AppendVec(_) => AppendVec::calculate_stored_size(data_len),
+            Self::Compressed(_) => CompressedFile::calculate_stored_size(data_len),
         }
     }
 
@@ -213,6 +262,7 @@ impl AccountsFile {
     pub(crate) fn get_account_data_lens(&self, sorted_offsets: &[usize]) -> Vec<usize> {
         match self {
             Self::AppendVec(av) => av.get_account_data_lens(sorted_offsets),
+            Self::Compressed(cf) => cf.get_account_data_lens(sorted_offsets),
         }
     }
 
@@ -220,6 +270,7 @@ impl AccountsFile {
     pub fn scan_pubkeys(&self, callback: impl FnMut(&Pubkey)) -> Result<()> {
         match self {
             Self::AppendVec(av) => av.scan_pubkeys(callback)?,
+            Self::Compressed(cf) => cf.scan_pubkeys(callback)?,
         }
         Ok(())
     }
@@ -237,6 +288,7 @@ impl AccountsFile {
     ) -> Option<StoredAccountsInfo> {
         match self {
             Self::AppendVec(av) => av.append_accounts(accounts),
+            Self::Compressed(cf) => cf.write_accounts(accounts),
         }
     }
 
@@ -249,6 +301,7 @@ impl AccountsFile {
         } else {
             Ok(match self {
                 Self::AppendVec(av) => av.open_file_for_archive(),
+                Self::Compressed(cf) => cf.open_file_for_archive(),
             })
         }
     }
@@ -259,12 +312,15 @@ impl AccountsFile {
 pub enum AccountsFileProvider {
     #[default]
     AppendVec,
+    /// Read-only, block-compressed storage; only used for ancient storages
+    Compressed,
 }
 
 impl AccountsFileProvider {
     pub fn new_writable(&self, path: impl Into<PathBuf>, file_size: u64) -> AccountsFile {
         match self {
             Self::AppendVec => AccountsFile::AppendVec(AppendVec::new(path, file_size as usize)),
+            Self::Compressed => AccountsFile::Compressed(CompressedFile::new(path)),
         }
     }
 }
@@ -4,6 +4,7 @@ mod bucket_map_holder;
 pub(crate) mod in_mem_accounts_index;
 mod iter;
 mod secondary;
+mod secondary_index_file;
 mod stats;
 use {
     crate::{
@@ -16,6 +17,7 @@ use {
     account_map_entry::{AccountMapEntry, PreAllocatedAccountMapEntry, SlotListWriteGuard},
     accounts_index_storage::AccountsIndexStorage,
     bucket_map_holder::Age,
+    dashmap::{DashMap, mapref::one::Ref},
     in_mem_accounts_index::{
         ExistedLocation, InMemAccountsIndex, InsertNewEntryResults, StartupStats,
     },
@@ -30,20 +32,25 @@ use {
     solana_pubkey::Pubkey,
     stats::Stats,
     std::{
+        collections::HashSet,
         fmt::Debug,
+        fs,
         num::NonZeroUsize,
-        path::PathBuf,
+        path::{Path, PathBuf},
         sync::{
             Arc,
             atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
         },
+        thread,
     },
 };
 pub use {
     bucket_map_holder::{DEFAULT_NUM_ENTRIES_OVERHEAD, DEFAULT_NUM_ENTRIES_TO_EVICT},
     secondary::{
-        AccountIndex, AccountSecondaryIndexes, AccountSecondaryIndexesIncludeExclude, IndexKey,
+        AccountIndex, AccountSecondaryIndexes, AccountSecondaryIndexesIncludeExclude,
+        DataSliceIndex, DataSliceIndexError, IndexKey,
     },
+    secondary_index_file::SecondaryIndexFileError,
 };
 
 pub const BINS_DEFAULT: usize = 8192;
@@ -195,6 +202,8 @@ pub struct AccountsIndex<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> {
     program_id_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
     spl_token_mint_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
     spl_token_owner_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
+    /// user-defined indexes, created when their first account is inserted
+    data_slice_indexes: DashMap<DataSliceIndex, SecondaryIndex<RwLockSecondaryIndexEntry>>,
 
     storage: AccountsIndexStorage<T, U>,
 
@@ -222,6 +231,7 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             spl_token_owner_index: SecondaryIndex::<RwLockSecondaryIndexEntry>::new(
                 "spl_token_owner_index_stats",
             ),
+            data_slice_indexes: DashMap::default(),
             storage,
         }
     }
@@ -379,6 +389,11 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             IndexKey::ProgramId(key) => self.program_id_index.get(key),
             IndexKey::SplTokenMint(key) => self.spl_token_mint_index.get(key),
             IndexKey::SplTokenOwner(key) => self.spl_token_owner_index.get(key),
+            IndexKey::DataSlice(index, key) => self
+                .data_slice_indexes
+                .get(index)
+                .map(|data_slice_index| data_slice_index.get(key))
+                .unwrap_or_default(),
         }
     }
 
@@ -592,6 +607,12 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
                 .index
                 .get(index_key)
                 .map(|x| x.len()),
+            AccountIndex::DataSlice(index) => self
+                .data_slice_indexes
+                .get(index)?
+                .index
+                .get(index_key)
+                .map(|x| x.len()),
         }
     }
 
@@ -609,6 +630,12 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             info!("secondary index: {:?}", AccountIndex::SplTokenOwner);
             self.spl_token_owner_index.log_contents();
         }
+        for data_slice_index in self.data_slice_indexes.iter() {
+            if !data_slice_index.index.is_empty() {
+                info!("secondary index: {}", data_slice_index.key());
+                data_slice_index.log_contents();
+            }
+        }
     }
 
     pub(crate) fn update_secondary_indexes(
@@ -629,6 +656,13 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         {
             self.program_id_index.insert(account_owner, pubkey);
         }
+        for data_slice_index in account_indexes.data_slice_indexes() {
+            if data_slice_index.program_id == *account_owner
+                && let Some(key) = data_slice_index.key(account_data)
+            {
+                self.data_slice_index(data_slice_index).insert(&key, pubkey);
+            }
+        }
         // Note because of the below check below on the account data length, when an
         // account hits zero lamports and is reset to AccountSharedData::Default, then we skip
         // the below updates to the secondary indexes.
@@ -874,6 +908,172 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             self.spl_token_mint_index
                 .remove_by_inner_key_if(inner_key, &should_remove);
         }
+
+        for data_slice_index in account_indexes.data_slice_indexes() {
+            if let Some(data_slice_index) = self.data_slice_indexes.get(data_slice_index) {
+                data_slice_index.remove_by_inner_key_if(inner_key, &should_remove);
+            }
+        }
+    }
+
+    /// Returns the data slice index for `data_slice_index`, creating it if it does not exist yet
+    fn data_slice_index(
+        &self,
+        data_slice_index: &DataSliceIndex,
+    ) -> Ref<'_, DataSliceIndex, SecondaryIndex<RwLockSecondaryIndexEntry>> {
+        self.data_slice_indexes
+            .get(data_slice_index)
+            .unwrap_or_else(|| {
+                self.data_slice_indexes
+                    .entry(*data_slice_index)
+                    .or_insert_with(|| SecondaryIndex::new("data_slice_index_stats"))
+                    .downgrade()
+            })
+    }
+
+    /// Calls `f` with the secondary index that backs `account_index`
+    fn with_secondary_index<R>(
+        &self,
+        account_index: &AccountIndex,
+        f: impl FnOnce(&SecondaryIndex<RwLockSecondaryIndexEntry>) -> R,
+    ) -> R {
+        match account_index {
+            AccountIndex::ProgramId => f(&self.program_id_index),
+            AccountIndex::SplTokenMint => f(&self.spl_token_mint_index),
+            AccountIndex::SplTokenOwner => f(&self.spl_token_owner_index),
+            AccountIndex::DataSlice(data_slice_index) => {
+                f(&self.data_slice_index(data_slice_index))
+            }
+        }
+    }
+
+    /// Saves each secondary index in `account_indexes` to its own file in `dir`
+    ///
+    /// Returns the total number of inner keys saved across all the indexes.
+    pub(crate) fn save_secondary_indexes(
+        &self,
+        dir: &Path,
+        slot: Slot,
+        account_indexes: &AccountSecondaryIndexes,
+    ) -> Result<u64, SecondaryIndexFileError> {
+        fs::create_dir_all(dir)?;
+        let mut num_inner_keys = 0u64;
+        for account_index in &account_indexes.indexes {
+            let path = dir.join(secondary_index_file::file_name(account_index));
+            let config_hash = secondary_index_file::config_hash(account_index, account_indexes);
+            let num_saved = self.with_secondary_index(account_index, |secondary_index| {
+                secondary_index_file::write(&path, slot, &config_hash, secondary_index)
+            })?;
+            num_inner_keys = num_inner_keys.saturating_add(num_saved);
+        }
+        Ok(num_inner_keys)
+    }
+
+    /// Loads the secondary indexes in `account_indexes` that were saved for `slot` in `dir`.
+    ///
+    /// Returns the indexes that were loaded. The others are left empty and must be generated.
+    pub(crate) fn load_secondary_indexes(
+        &self,
+        dir: &Path,
+        slot: Slot,
+        account_indexes: &AccountSecondaryIndexes,
+    ) -> HashSet<AccountIndex> {
+        thread::scope(|s| {
+            let thread_handles = account_indexes
+                .indexes
+                .iter()
+                .enumerate()
+                .map(|(i, account_index)| {
+                    thread::Builder::new()
+                        .name(format!("solLoadSecIdx{i:02}"))
+                        .spawn_scoped(s, move || {
+                            self.load_secondary_index(dir, slot, account_index, account_indexes)
+                                .then(|| account_index.clone())
+                        })
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            thread_handles
+                .into_iter()
+                .filter_map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    }
+
+    /// Returns true if `account_index` was loaded from `dir`
+    fn load_secondary_index(
+        &self,
+        dir: &Path,
+        slot: Slot,
+        account_index: &AccountIndex,
+        account_indexes: &AccountSecondaryIndexes,
+    ) -> bool {
+        let path = dir.join(secondary_index_file::file_name(account_index));
+        if !path.exists() {
+            info!(
+                "secondary index {account_index:?} was not saved at '{}'",
+                path.display()
+            );
+            return false;
+        }
+        let config_hash = secondary_index_file::config_hash(account_index, account_indexes);
+        self.with_secondary_index(
+            account_index,
+            |secondary_index| match secondary_index_file::read(
+                &path,
+                slot,
+                &config_hash,
+                secondary_index,
+            ) {
+                Ok(num_inner_keys) => {
+                    info!(
+                        "secondary index {account_index:?}: loaded {num_inner_keys} accounts \
+                         from '{}'",
+                        path.display()
+                    );
+                    true
+                }
+                Err(err) => {
+                    warn!(
+                        "secondary index {account_index:?}: failed to load '{}', it will be \
+                         generated: {err}",
+                        path.display()
+                    );
+                    secondary_index.clear();
+                    false
+                }
+            },
+        )
+    }
+
+    /// Removes the accounts that are not in the primary index from `account_indexes`.
+    ///
+    /// Secondary indexes loaded from a bank snapshot may hold accounts that were only stored
+    /// after the snapshot's slot, e.g. on forks that were never rooted.
+    pub(crate) fn remove_unknown_keys_from_secondary_indexes(
+        &self,
+        account_indexes: &HashSet<AccountIndex>,
+    ) {
+        for account_index in account_indexes {
+            self.with_secondary_index(account_index, |secondary_index| {
+                // Collect first, removing takes the reverse index entry lock
+                let unknown_keys = secondary_index
+                    .reverse_index
+                    .iter()
+                    .map(|entry| *entry.key())
+                    .filter(|key| !self.contains(key))
+                    .collect::<Vec<_>>();
+                for key in &unknown_keys {
+                    secondary_index.remove_by_inner_key_if(key, || !self.contains(key));
+                }
+                if !unknown_keys.is_empty() {
+                    info!(
+                        "secondary index {account_index:?}: removed {} unknown accounts",
+                        unknown_keys.len()
+                    );
+                }
+            });
+        }
     }
 
     /// Reclaims every entry older than the newest entry at or below the clean root.
@@ -2372,6 +2572,157 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_data_slice_secondary_index() {
+        let index = AccountsIndex::<bool, bool>::default_for_tests();
+        let program_id = Pubkey::new_unique();
+        let data_slice_index = DataSliceIndex::new(program_id, 8, 32).unwrap();
+        let secondary_indexes = AccountSecondaryIndexes {
+            keys: None,
+            indexes: HashSet::from([AccountIndex::DataSlice(data_slice_index)]),
+        };
+        let account_key = Pubkey::new_unique();
+        let index_key = Pubkey::new_unique();
+        let mut account_data = vec![0; 48];
+        account_data[8..40].copy_from_slice(index_key.as_ref());
+        let index_key = IndexKey::DataSlice(data_slice_index, index_key);
+        let new_account = |data: &[u8], owner| {
+            AccountSharedData::create_from_existing_shared_data(
+                1,
+                Arc::new(data.to_vec()),
+                owner,
+                false,
+                0,
+            )
+        };
+
+        // Wrong program id
+        index.update_secondary_indexes(
+            &account_key,
+            &new_account(&account_data, Pubkey::new_unique()),
+            &secondary_indexes,
+        );
+        assert!(index.get_index_key_pubkeys(&index_key).is_empty());
+
+        // Data too short to hold the key
+        index.update_secondary_indexes(
+            &account_key,
+            &new_account(&account_data[..39], program_id),
+            &secondary_indexes,
+        );
+        assert!(index.get_index_key_pubkeys(&index_key).is_empty());
+
+        // Inserting the same account multiple times should be ok
+        for _ in 0..2 {
+            index.update_secondary_indexes(
+                &account_key,
+                &new_account(&account_data, program_id),
+                &secondary_indexes,
+            );
+            assert_eq!(index.get_index_key_pubkeys(&index_key), vec![account_key]);
+        }
+        let IndexKey::DataSlice(_, key) = index_key else {
+            unreachable!()
+        };
+        assert_eq!(
+            index.get_index_key_size(&AccountIndex::DataSlice(data_slice_index), &key),
+            Some(1)
+        );
+
+        // Only enabled data slice indexes are maintained
+        let other_index = DataSliceIndex::new(program_id, 0, 8).unwrap();
+        assert!(
+            index
+                .get_index_key_pubkeys(&IndexKey::DataSlice(
+                    other_index,
+                    other_index.key(&account_data).unwrap()
+                ))
+                .is_empty()
+        );
+
+        index.purge_secondary_indexes_by_inner_key_if(&account_key, &secondary_indexes, || true);
+        assert!(index.get_index_key_pubkeys(&index_key).is_empty());
+        assert!(
+            index
+                .data_slice_indexes
+                .get(&data_slice_index)
+                .unwrap()
+                .reverse_index
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_save_load_secondary_indexes() {
+        let index = AccountsIndex::<bool, bool>::default_for_tests();
+        let program_id = Pubkey::new_unique();
+        let data_slice_index = DataSliceIndex::new(program_id, 0, 8).unwrap();
+        let secondary_indexes = AccountSecondaryIndexes {
+            keys: None,
+            indexes: HashSet::from([
+                AccountIndex::ProgramId,
+                AccountIndex::DataSlice(data_slice_index),
+            ]),
+        };
+        let mut account_keys = (0..3).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        account_keys.sort_unstable();
+        for account_key in &account_keys {
+            let account = AccountSharedData::create_from_existing_shared_data(
+                1,
+                Arc::new(vec![7; 8]),
+                program_id,
+                false,
+                0,
+            );
+            index.update_secondary_indexes(account_key, &account, &secondary_indexes);
+        }
+        let data_slice_key =
+            IndexKey::DataSlice(data_slice_index, data_slice_index.key(&[7; 8]).unwrap());
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let num_saved = index
+            .save_secondary_indexes(temp_dir.path(), 5, &secondary_indexes)
+            .unwrap();
+        // every account is in both indexes
+        assert_eq!(num_saved, 6);
+
+        // Only files saved for the same slot are loaded
+        let loaded_index = AccountsIndex::<bool, bool>::default_for_tests();
+        assert!(
+            loaded_index
+                .load_secondary_indexes(temp_dir.path(), 6, &secondary_indexes)
+                .is_empty()
+        );
+        assert!(loaded_index.program_id_index.index.is_empty());
+        assert_eq!(
+            loaded_index.load_secondary_indexes(temp_dir.path(), 5, &secondary_indexes),
+            secondary_indexes.indexes,
+        );
+        for index_key in [IndexKey::ProgramId(program_id), data_slice_key] {
+            let mut keys = loaded_index.get_index_key_pubkeys(&index_key);
+            keys.sort_unstable();
+            assert_eq!(keys, account_keys);
+        }
+
+        // Accounts that are not in the primary index are removed
+        let mut gc = ReclaimsSlotList::new();
+        loaded_index.upsert(
+            5,
+            5,
+            &account_keys[0],
+            true,
+            &mut gc,
+            UPSERT_RECLAIM_TEST_DEFAULT,
+        );
+        loaded_index.remove_unknown_keys_from_secondary_indexes(&secondary_indexes.indexes);
+        for index_key in [IndexKey::ProgramId(program_id), data_slice_key] {
+            assert_eq!(
+                loaded_index.get_index_key_pubkeys(&index_key),
+                vec![account_keys[0]]
+            );
+        }
+    }
+
     fn run_test_secondary_indexes_same_slot_and_forks<
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
     >(
@@ -5,12 +5,14 @@ use {
     solana_time_utils::AtomicInterval,
     std::{
         collections::HashSet,
-        fmt::Debug,
+        fmt::{self, Debug, Display},
+        str::FromStr,
         sync::{
             RwLock,
             atomic::{AtomicU64, Ordering},
         },
     },
+    thiserror::Error,
 };
 
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -32,6 +34,29 @@ impl AccountSecondaryIndexes {
             None => true, // include all keys
         }
     }
+    /// Iterates over the enabled data slice indexes
+    pub fn data_slice_indexes(&self) -> impl Iterator<Item = &DataSliceIndex> {
+        self.indexes.iter().filter_map(|index| match index {
+            AccountIndex::DataSlice(data_slice_index) => Some(data_slice_index),
+            _ => None,
+        })
+    }
+    /// Returns the index key for a lookup of accounts owned by `program_id` that contain `bytes`
+    /// at `offset`, if a data slice index covers exactly that range.
+    pub fn data_slice_index_key(
+        &self,
+        program_id: &Pubkey,
+        offset: usize,
+        bytes: &[u8],
+    ) -> Option<IndexKey> {
+        self.data_slice_indexes()
+            .find(|index| {
+                index.program_id == *program_id
+                    && index.offset == offset
+                    && index.length == bytes.len()
+            })
+            .map(|index| IndexKey::DataSlice(*index, index.key_from_bytes(bytes)))
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -45,6 +70,7 @@ pub enum AccountIndex {
     ProgramId,
     SplTokenMint,
     SplTokenOwner,
+    DataSlice(DataSliceIndex),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -52,6 +78,100 @@ pub enum IndexKey {
     ProgramId(Pubkey),
     SplTokenMint(Pubkey),
     SplTokenOwner(Pubkey),
+    /// Accounts whose data holds the key's bytes in the index's range
+    DataSlice(DataSliceIndex, Pubkey),
+}
+
+/// A user-defined index of the accounts owned by `program_id`, keyed on the `length` bytes at
+/// `offset` in their data.
+///
+/// Keys are stored as a `Pubkey`, zero padded when `length` is less than 32 bytes. Accounts with
+/// data too short to contain the range are not indexed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DataSliceIndex {
+    pub program_id: Pubkey,
+    pub offset: usize,
+    pub length: usize,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DataSliceIndexError {
+    #[error("expected PROGRAM_ID:OFFSET:LENGTH")]
+    InvalidFormat,
+    #[error("invalid program id: {0}")]
+    InvalidProgramId(String),
+    #[error("invalid offset: {0}")]
+    InvalidOffset(String),
+    #[error("length must be between 1 and {max}, got {0}", max = DataSliceIndex::MAX_LENGTH)]
+    InvalidLength(String),
+}
+
+impl DataSliceIndex {
+    /// The largest key that fits in the secondary index
+    pub const MAX_LENGTH: usize = size_of::<Pubkey>();
+
+    pub fn new(
+        program_id: Pubkey,
+        offset: usize,
+        length: usize,
+    ) -> Result<Self, DataSliceIndexError> {
+        if !(1..=Self::MAX_LENGTH).contains(&length) {
+            return Err(DataSliceIndexError::InvalidLength(length.to_string()));
+        }
+        if offset.checked_add(length).is_none() {
+            return Err(DataSliceIndexError::InvalidOffset(offset.to_string()));
+        }
+        Ok(Self {
+            program_id,
+            offset,
+            length,
+        })
+    }
+
+    /// Returns the key of an account with `data`, or None if `data` is too short.
+    ///
+    /// The caller is responsible for checking the account is owned by `program_id`.
+    pub fn key(&self, data: &[u8]) -> Option<Pubkey> {
+        data.get(self.offset..self.offset + self.length)
+            .map(|bytes| self.key_from_bytes(bytes))
+    }
+
+    /// Returns the key for `bytes`, which must be `length` bytes long.
+    pub fn key_from_bytes(&self, bytes: &[u8]) -> Pubkey {
+        debug_assert_eq!(bytes.len(), self.length);
+        let mut key = [0; Self::MAX_LENGTH];
+        key[..bytes.len()].copy_from_slice(bytes);
+        Pubkey::new_from_array(key)
+    }
+}
+
+impl FromStr for DataSliceIndex {
+    type Err = DataSliceIndexError;
+
+    /// Parses `PROGRAM_ID:OFFSET:LENGTH`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let (Some(program_id), Some(offset), Some(length), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(DataSliceIndexError::InvalidFormat);
+        };
+        let program_id = Pubkey::from_str(program_id)
+            .map_err(|_| DataSliceIndexError::InvalidProgramId(program_id.to_string()))?;
+        let offset = offset
+            .parse()
+            .map_err(|_| DataSliceIndexError::InvalidOffset(offset.to_string()))?;
+        let length = length
+            .parse()
+            .map_err(|_| DataSliceIndexError::InvalidLength(length.to_string()))?;
+        Self::new(program_id, offset, length)
+    }
+}
+
+impl Display for DataSliceIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.program_id, self.offset, self.length)
+    }
 }
 
 // The only cases where an inner key should map to a different outer key is
@@ -249,6 +369,13 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
             .fetch_sub(num_removed, Ordering::Relaxed);
     }
 
+    /// Removes all entries
+    pub fn clear(&self) {
+        self.reverse_index.clear();
+        self.index.clear();
+        self.stats.num_inner_keys.store(0, Ordering::Relaxed);
+    }
+
     pub fn get(&self, key: &Pubkey) -> Vec<Pubkey> {
         if let Some(inner_keys_map) = self.index.get(key) {
             inner_keys_map.keys()
@@ -411,6 +538,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_data_slice_index_from_str() {
+        let program_id = Pubkey::new_unique();
+        let index = DataSliceIndex::from_str(&format!("{program_id}:8:32")).unwrap();
+        assert_eq!(index, DataSliceIndex::new(program_id, 8, 32).unwrap());
+        assert_eq!(index.to_string(), format!("{program_id}:8:32"));
+
+        assert_eq!(
+            DataSliceIndex::from_str(&format!("{program_id}:8")),
+            Err(DataSliceIndexError::InvalidFormat)
+        );
+        assert_eq!(
+            DataSliceIndex::from_str(&format!("{program_id}:8:32:1")),
+            Err(DataSliceIndexError::InvalidFormat)
+        );
+        assert!(matches!(
+            DataSliceIndex::from_str("not-a-pubkey:8:32"),
+            Err(DataSliceIndexError::InvalidProgramId(_))
+        ));
+        assert!(matches!(
+            DataSliceIndex::from_str(&format!("{program_id}:-1:32")),
+            Err(DataSliceIndexError::InvalidOffset(_))
+        ));
+        assert!(matches!(
+            DataSliceIndex::from_str(&format!("{program_id}:8:0")),
+            Err(DataSliceIndexError::InvalidLength(_))
+        ));
+        assert!(matches!(
+            DataSliceIndex::from_str(&format!("{program_id}:8:33")),
+            Err(DataSliceIndexError::InvalidLength(_))
+        ));
+    }
+
+    #[test]
+    fn test_data_slice_index_key() {
+        let index = DataSliceIndex::new(Pubkey::new_unique(), 2, 4).unwrap();
+        let data = [0, 1, 2, 3, 4, 5, 6];
+        let mut expected = [0; 32];
+        expected[..4].copy_from_slice(&[2, 3, 4, 5]);
+        assert_eq!(index.key(&data), Some(Pubkey::from(expected)));
+        assert_eq!(index.key(&data[..6]), Some(Pubkey::from(expected)));
+        assert_eq!(index.key(&data[..5]), None);
+        assert_eq!(index.key_from_bytes(&[2, 3, 4, 5]), Pubkey::from(expected));
+    }
+
+    #[test]
+    fn test_data_slice_index_key_lookup() {
+        let program_id = Pubkey::new_unique();
+        let index = DataSliceIndex::new(program_id, 8, 32).unwrap();
+        let account_indexes = AccountSecondaryIndexes {
+            keys: None,
+            indexes: HashSet::from([AccountIndex::ProgramId, AccountIndex::DataSlice(index)]),
+        };
+        assert_eq!(
+            account_indexes.data_slice_indexes().collect::<Vec<_>>(),
+            vec![&index]
+        );
+
+        let key = Pubkey::new_unique();
+        assert!(matches!(
+            account_indexes.data_slice_index_key(&program_id, 8, key.as_ref()),
+            Some(IndexKey::DataSlice(found, found_key)) if found == index && found_key == key
+        ));
+        // the filter must cover exactly the indexed range of the indexed program
+        assert!(
+            account_indexes
+                .data_slice_index_key(&program_id, 0, key.as_ref())
+                .is_none()
+        );
+        assert!(
+            account_indexes
+                .data_slice_index_key(&program_id, 8, &key.as_ref()[..31])
+                .is_none()
+        );
+        assert!(
+            account_indexes
+                .data_slice_index_key(&Pubkey::new_unique(), 8, key.as_ref())
+                .is_none()
+        );
+    }
+
     /// Regression guard for reverse-index entry capacity. Each entry must be
     /// created with capacity 1. An empty Vec plus push() would over-allocate to
     /// capacity 4 (Rust RawVec rule), wasting 96 bytes per entry.
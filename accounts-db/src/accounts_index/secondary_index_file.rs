@@ -0,0 +1,392 @@
+//! On-disk format of a secondary index saved with a bank snapshot.
+//!
+//! A node restarting from the bank snapshot loads the file instead of generating the index from
+//! every account in the storages. A file is only used for the slot and index configuration it
+//! was saved with; any mismatch or corruption causes the index to be generated as usual.
+//!
+//! File layout:
+//!
+//! | section | contents                                                           |
+//! |---------|--------------------------------------------------------------------|
+//! | header  | [`MAGIC`], version, slot and a hash of the index configuration     |
+//! | entries | per outer key: the key, its number of inner keys, then inner keys  |
+//! | trailer | blake3 hash of everything before it                                |
+//!
+//! All integers are little endian.
+
+use {
+    super::secondary::{
+        AccountIndex, AccountSecondaryIndexes, SecondaryIndex, SecondaryIndexEntry,
+    },
+    solana_clock::Slot,
+    solana_pubkey::Pubkey,
+    std::{
+        fs::{self, File},
+        io::{self, BufReader, BufWriter, Read, Write},
+        path::{Path, PathBuf},
+    },
+    thiserror::Error,
+};
+
+/// Identifies a secondary index file; stored in its first 8 bytes.
+const MAGIC: u64 = u64::from_le_bytes(*b"AGVSIDX\0");
+
+const FORMAT_VERSION: u64 = 1;
+
+const HEADER_SIZE: u64 = (3 * size_of::<u64>() + blake3::OUT_LEN) as u64;
+const TRAILER_SIZE: u64 = blake3::OUT_LEN as u64;
+
+const IO_BUFFER_SIZE: usize = 1024 * 1024;
+
+pub type Result<T> = std::result::Result<T, SecondaryIndexFileError>;
+
+/// An enum for secondary index file related errors.
+#[derive(Error, Debug)]
+pub enum SecondaryIndexFileError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("{} is not a secondary index file", .0.display())]
+    InvalidMagic(PathBuf),
+
+    #[error("unsupported secondary index file version {0}")]
+    UnsupportedVersion(u64),
+
+    #[error("secondary index file is for slot {found}, expected slot {expected}")]
+    SlotMismatch { expected: Slot, found: Slot },
+
+    #[error("secondary index file was saved with a different index configuration")]
+    ConfigMismatch,
+
+    #[error("secondary index file at path {} is corrupt", .0.display())]
+    Corrupt(PathBuf),
+
+    #[error("secondary indexes may not contain every account alive at slot {0}")]
+    Incomplete(Slot),
+}
+
+/// Returns the name of the file that `account_index` is saved to
+pub(super) fn file_name(account_index: &AccountIndex) -> String {
+    match account_index {
+        AccountIndex::ProgramId => "program_id".to_string(),
+        AccountIndex::SplTokenMint => "spl_token_mint".to_string(),
+        AccountIndex::SplTokenOwner => "spl_token_owner".to_string(),
+        AccountIndex::DataSlice(data_slice_index) => format!(
+            "data_slice_{}_{}_{}",
+            data_slice_index.program_id, data_slice_index.offset, data_slice_index.length,
+        ),
+    }
+}
+
+/// Hashes everything that determines which accounts `account_index` holds.
+///
+/// A file is rejected when loaded with a configuration that hashes differently, e.g. after
+/// changing the include/exclude keys.
+pub(super) fn config_hash(
+    account_index: &AccountIndex,
+    account_indexes: &AccountSecondaryIndexes,
+) -> blake3::Hash {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(file_name(account_index).as_bytes());
+    if let Some(keys) = &account_indexes.keys {
+        let mut sorted_keys = keys.keys.iter().collect::<Vec<_>>();
+        sorted_keys.sort_unstable();
+        hasher.update(&[u8::from(keys.exclude)]);
+        for key in sorted_keys {
+            hasher.update(key.as_ref());
+        }
+    }
+    hasher.finalize()
+}
+
+/// Writes `secondary_index` to `path`, replacing any existing file, and returns the number of
+/// inner keys written.
+///
+/// The index may be updated concurrently; each outer key's inner keys are written as of when
+/// that key is visited.
+pub(super) fn write<T: SecondaryIndexEntry + Default + Sync + Send>(
+    path: &Path,
+    slot: Slot,
+    config_hash: &blake3::Hash,
+    secondary_index: &SecondaryIndex<T>,
+) -> Result<u64> {
+    // Write to a temporary file first, so a crash never leaves a truncated file at `path`
+    let temp_path = path.with_extension("tmp");
+    let file = File::create(&temp_path)?;
+    let mut writer = HashingWriter::new(BufWriter::with_capacity(IO_BUFFER_SIZE, file));
+
+    writer.write_all(&MAGIC.to_le_bytes())?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    writer.write_all(&slot.to_le_bytes())?;
+    writer.write_all(config_hash.as_bytes())?;
+    let mut num_inner_keys = 0u64;
+    for entry in secondary_index.index.iter() {
+        let inner_keys = entry.value().keys();
+        if inner_keys.is_empty() {
+            continue;
+        }
+        writer.write_all(entry.key().as_ref())?;
+        writer.write_all(&(inner_keys.len() as u64).to_le_bytes())?;
+        for inner_key in &inner_keys {
+            writer.write_all(inner_key.as_ref())?;
+        }
+        num_inner_keys = num_inner_keys.saturating_add(inner_keys.len() as u64);
+    }
+    let HashingWriter { mut inner, hasher } = writer;
+    inner.write_all(hasher.finalize().as_bytes())?;
+    inner.into_inner().map_err(io::Error::from)?.sync_all()?;
+
+    fs::rename(&temp_path, path)?;
+    Ok(num_inner_keys)
+}
+
+/// Inserts the entries saved at `path` into `secondary_index` and returns the number of inner
+/// keys read.
+///
+/// Entries are inserted while the file is read, so on error `secondary_index` may hold part of
+/// the file and must be cleared by the caller.
+pub(super) fn read<T: SecondaryIndexEntry + Default + Sync + Send>(
+    path: &Path,
+    slot: Slot,
+    config_hash: &blake3::Hash,
+    secondary_index: &SecondaryIndex<T>,
+) -> Result<u64> {
+    let file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    if file_len < HEADER_SIZE + TRAILER_SIZE {
+        return Err(SecondaryIndexFileError::Corrupt(path.to_path_buf()));
+    }
+    let entries_end = file_len - TRAILER_SIZE;
+    let mut reader = HashingReader::new(BufReader::with_capacity(IO_BUFFER_SIZE, file));
+
+    if reader.read_u64()? != MAGIC {
+        return Err(SecondaryIndexFileError::InvalidMagic(path.to_path_buf()));
+    }
+    let version = reader.read_u64()?;
+    if version != FORMAT_VERSION {
+        return Err(SecondaryIndexFileError::UnsupportedVersion(version));
+    }
+    let found_slot = reader.read_u64()?;
+    if found_slot != slot {
+        return Err(SecondaryIndexFileError::SlotMismatch {
+            expected: slot,
+            found: found_slot,
+        });
+    }
+    let mut found_config_hash = [0; blake3::OUT_LEN];
+    reader.read_exact(&mut found_config_hash)?;
+    if blake3::Hash::from_bytes(found_config_hash) != *config_hash {
+        return Err(SecondaryIndexFileError::ConfigMismatch);
+    }
+
+    let mut num_inner_keys = 0;
+    while reader.position < entries_end {
+        let outer_key = reader.read_pubkey()?;
+        let num_entry_inner_keys = reader.read_u64()?;
+        // Reject counts the file cannot hold before reading the keys
+        let max_inner_keys =
+            entries_end.saturating_sub(reader.position) / size_of::<Pubkey>() as u64;
+        if num_entry_inner_keys > max_inner_keys {
+            return Err(SecondaryIndexFileError::Corrupt(path.to_path_buf()));
+        }
+        for _ in 0..num_entry_inner_keys {
+            let inner_key = reader.read_pubkey()?;
+            secondary_index.insert(&outer_key, &inner_key);
+        }
+        num_inner_keys += num_entry_inner_keys;
+    }
+    if reader.position != entries_end {
+        return Err(SecondaryIndexFileError::Corrupt(path.to_path_buf()));
+    }
+
+    let HashingReader {
+        mut inner, hasher, ..
+    } = reader;
+    let mut checksum = [0; blake3::OUT_LEN];
+    inner.read_exact(&mut checksum)?;
+    if blake3::Hash::from_bytes(checksum) != hasher.finalize() {
+        return Err(SecondaryIndexFileError::Corrupt(path.to_path_buf()));
+    }
+    Ok(num_inner_keys)
+}
+
+/// Hashes everything written through it
+struct HashingWriter<W> {
+    inner: W,
+    hasher: blake3::Hasher,
+}
+
+impl<W: Write> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+        }
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.hasher.update(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Hashes everything read through it and tracks the position in the file
+struct HashingReader<R> {
+    inner: R,
+    hasher: blake3::Hasher,
+    position: u64,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+            position: 0,
+        }
+    }
+
+    fn read_u64(&mut self) -> io::Result<u64> {
+        let mut buf = [0; size_of::<u64>()];
+        self.read_exact(&mut buf)?;
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    fn read_pubkey(&mut self) -> io::Result<Pubkey> {
+        let mut buf = [0; size_of::<Pubkey>()];
+        self.read_exact(&mut buf)?;
+        Ok(Pubkey::new_from_array(buf))
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.hasher.update(&buf[..len]);
+        self.position += len as u64;
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::accounts_index::{
+            AccountSecondaryIndexesIncludeExclude, secondary::RwLockSecondaryIndexEntry,
+        },
+        std::collections::HashSet,
+    };
+
+    fn new_secondary_index() -> SecondaryIndex<RwLockSecondaryIndexEntry> {
+        SecondaryIndex::new("test_secondary_index")
+    }
+
+    fn sorted_keys(
+        secondary_index: &SecondaryIndex<RwLockSecondaryIndexEntry>,
+        key: &Pubkey,
+    ) -> Vec<Pubkey> {
+        let mut keys = secondary_index.get(key);
+        keys.sort_unstable();
+        keys
+    }
+
+    #[test]
+    fn test_write_read_roundtrip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir
+            .path()
+            .join(file_name(&AccountIndex::SplTokenOwner));
+        let config_hash = config_hash(
+            &AccountIndex::SplTokenOwner,
+            &AccountSecondaryIndexes::default(),
+        );
+
+        let secondary_index = new_secondary_index();
+        let owners = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut accounts = (0..10).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        for (i, account) in accounts.iter().enumerate() {
+            secondary_index.insert(&owners[i % 2], account);
+        }
+        write(&path, 42, &config_hash, &secondary_index).unwrap();
+        assert!(!path.with_extension("tmp").exists());
+
+        let loaded_index = new_secondary_index();
+        assert_eq!(read(&path, 42, &config_hash, &loaded_index).unwrap(), 10);
+        let mut loaded_accounts = owners
+            .iter()
+            .flat_map(|owner| sorted_keys(&loaded_index, owner))
+            .collect::<Vec<_>>();
+        loaded_accounts.sort_unstable();
+        accounts.sort_unstable();
+        assert_eq!(loaded_accounts, accounts);
+        for owner in &owners {
+            assert_eq!(
+                sorted_keys(&loaded_index, owner),
+                sorted_keys(&secondary_index, owner)
+            );
+        }
+        assert_eq!(loaded_index.reverse_index.len(), accounts.len());
+    }
+
+    #[test]
+    fn test_read_rejects_mismatches() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(file_name(&AccountIndex::ProgramId));
+        let account_indexes = AccountSecondaryIndexes::default();
+        let config_hash = config_hash(&AccountIndex::ProgramId, &account_indexes);
+
+        let secondary_index = new_secondary_index();
+        secondary_index.insert(&Pubkey::new_unique(), &Pubkey::new_unique());
+        write(&path, 42, &config_hash, &secondary_index).unwrap();
+
+        assert!(matches!(
+            read(&path, 43, &config_hash, &new_secondary_index()),
+            Err(SecondaryIndexFileError::SlotMismatch {
+                expected: 43,
+                found: 42
+            }),
+        ));
+
+        let account_indexes_with_keys = AccountSecondaryIndexes {
+            keys: Some(AccountSecondaryIndexesIncludeExclude {
+                exclude: true,
+                keys: HashSet::from([Pubkey::new_unique()]),
+            }),
+            ..account_indexes
+        };
+        let other_config_hash =
+            super::config_hash(&AccountIndex::ProgramId, &account_indexes_with_keys);
+        assert_ne!(other_config_hash, config_hash);
+        assert!(matches!(
+            read(&path, 42, &other_config_hash, &new_secondary_index()),
+            Err(SecondaryIndexFileError::ConfigMismatch),
+        ));
+
+        // flip a bit in the entries
+        let mut contents = fs::read(&path).unwrap();
+        contents[HEADER_SIZE as usize] ^= 1;
+        fs::write(&path, &contents).unwrap();
+        assert!(matches!(
+            read(&path, 42, &config_hash, &new_secondary_index()),
+            Err(SecondaryIndexFileError::Corrupt(_)),
+        ));
+
+        // truncate the file
+        fs::write(&path, &contents[..contents.len() - 1]).unwrap();
+        assert!(read(&path, 42, &config_hash, &new_secondary_index()).is_err());
+
+        fs::write(&path, [0; 128]).unwrap();
+        assert!(matches!(
+            read(&path, 42, &config_hash, &new_secondary_index()),
+            Err(SecondaryIndexFileError::InvalidMagic(_)),
+        ));
+    }
+}
@@ -2,7 +2,7 @@ use {
     crate::ancestors::Ancestors,
     solana_clock::{BankId, Slot},
     std::{
-        collections::{HashSet, btree_map::BTreeMap},
+        collections::{BTreeSet, HashSet, btree_map::BTreeMap},
         sync::{
             Arc, Mutex, RwLock,
             atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
@@ -74,6 +74,12 @@ pub struct ScanTracker {
     pub active_scans: AtomicUsize,
     /// # of slots between latest max and latest scan
     pub max_distance_to_min_scan_slot: AtomicU64,
+    /// Highest root clean may have reclaimed superseded account versions up to.
+    /// Account state as of any root below this may be incomplete.
+    min_historical_root: AtomicU64,
+    /// Roots at or above `min_historical_root`, i.e. the roots historical views may be opened at.
+    /// Slots between them were skipped or are on abandoned forks.
+    historical_roots: RwLock<BTreeSet<Slot>>,
 }
 
 impl ScanTracker {
@@ -84,6 +90,82 @@ impl ScanTracker {
     pub fn min_ongoing_scan_root(&self) -> Option<Slot> {
         Self::min_ongoing_scan_root_from_btree(&self.ongoing_scan_roots.read().unwrap())
     }
+
+    /// Caps `proposed_clean_root` at the oldest pinned root and records the
+    /// result as the oldest root historical views may still be opened at.
+    ///
+    /// `None` means clean is unbounded, after which no historical root is retained.
+    pub(crate) fn max_clean_root(&self, proposed_clean_root: Option<Slot>) -> Option<Slot> {
+        // Hold the lock while raising `min_historical_root` so a concurrent
+        // `try_pin_root()` either sees the new floor or is seen by this clean.
+        let ongoing_scan_roots = self.ongoing_scan_roots.read().unwrap();
+        let max_clean_root = match (
+            Self::min_ongoing_scan_root_from_btree(&ongoing_scan_roots),
+            proposed_clean_root,
+        ) {
+            (None, None) => None,
+            (Some(min_scan_root), None) => Some(min_scan_root),
+            (None, Some(proposed_clean_root)) => Some(proposed_clean_root),
+            (Some(min_scan_root), Some(proposed_clean_root)) => {
+                Some(std::cmp::min(min_scan_root, proposed_clean_root))
+            }
+        };
+        self.min_historical_root
+            .fetch_max(max_clean_root.unwrap_or(Slot::MAX), Ordering::Relaxed);
+        // Views can no longer be opened at the roots below the new floor
+        let min_historical_root = self.min_historical_root();
+        let mut historical_roots = self.historical_roots.write().unwrap();
+        *historical_roots = historical_roots.split_off(&min_historical_root);
+        max_clean_root
+    }
+
+    /// The oldest root whose account state has not been cleaned.
+    pub(crate) fn min_historical_root(&self) -> Slot {
+        self.min_historical_root.load(Ordering::Relaxed)
+    }
+
+    /// Marks account state older than `root` as incomplete, e.g. after loading
+    /// storages from a snapshot taken at `root`.
+    pub(crate) fn set_min_historical_root(&self, root: Slot) {
+        self.min_historical_root.fetch_max(root, Ordering::Relaxed);
+    }
+
+    /// Records `root` as a root historical views may be opened at, until clean advances past it.
+    pub(crate) fn add_historical_root(&self, root: Slot) {
+        let mut historical_roots = self.historical_roots.write().unwrap();
+        if root >= self.min_historical_root() {
+            historical_roots.insert(root);
+        }
+    }
+
+    /// Returns true if `slot` is a root that clean has not advanced past.
+    pub(crate) fn is_historical_root(&self, slot: Slot) -> bool {
+        self.historical_roots.read().unwrap().contains(&slot)
+    }
+
+    /// Pins `root` so clean keeps every account version needed to read state as of it.
+    ///
+    /// Returns the oldest root that can still be pinned if `root` may already have
+    /// been cleaned. Pinned roots must be released with `unpin_root()`.
+    pub(crate) fn try_pin_root(&self, root: Slot) -> Result<(), Slot> {
+        let mut ongoing_scan_roots = self.ongoing_scan_roots.write().unwrap();
+        let min_historical_root = self.min_historical_root();
+        if root < min_historical_root {
+            return Err(min_historical_root);
+        }
+        *ongoing_scan_roots.entry(root).or_default() += 1;
+        Ok(())
+    }
+
+    /// Releases a root pinned by `try_pin_root()` or a `ScanGuard`.
+    pub(crate) fn unpin_root(&self, root: Slot) {
+        let mut ongoing_scan_roots = self.ongoing_scan_roots.write().unwrap();
+        let count = ongoing_scan_roots.get_mut(&root).unwrap();
+        *count -= 1;
+        if *count == 0 {
+            ongoing_scan_roots.remove(&root);
+        }
+    }
 }
 
 /// Guard that protects account state during an accounts scan.
@@ -249,12 +331,7 @@ impl Drop for ScanGuard<'_> {
         self.scan_tracker
             .active_scans
             .fetch_sub(1, Ordering::Relaxed);
-        let mut ongoing_scan_roots = self.scan_tracker.ongoing_scan_roots.write().unwrap();
-        let count = ongoing_scan_roots.get_mut(&self.max_root).unwrap();
-        *count -= 1;
-        if *count == 0 {
-            ongoing_scan_roots.remove(&self.max_root);
-        }
+        self.scan_tracker.unpin_root(self.max_root);
     }
 }
 
@@ -378,4 +455,26 @@ mod tests {
         let guard = ScanGuard::try_new(&tracker, 0, || 42).unwrap();
         assert!(!guard.should_use_ancestors(&ancestors));
     }
+
+    #[test]
+    fn test_pin_root_below_max_clean_root() {
+        let tracker = ScanTracker::default();
+        assert_eq!(tracker.min_historical_root(), 0);
+
+        assert_eq!(tracker.max_clean_root(Some(10)), Some(10));
+        assert_eq!(tracker.min_historical_root(), 10);
+        assert_eq!(tracker.try_pin_root(9), Err(10));
+
+        // a pinned root caps later cleans and keeps the floor from passing it
+        tracker.try_pin_root(12).unwrap();
+        assert_eq!(tracker.max_clean_root(Some(20)), Some(12));
+        assert_eq!(tracker.max_clean_root(None), Some(12));
+        assert_eq!(tracker.min_historical_root(), 12);
+        tracker.unpin_root(12);
+        assert!(tracker.min_ongoing_scan_root().is_none());
+
+        // an unbounded clean leaves no root to pin
+        assert_eq!(tracker.max_clean_root(None), None);
+        assert_eq!(tracker.try_pin_root(20), Err(Slot::MAX));
+    }
 }
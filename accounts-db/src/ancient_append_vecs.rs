@@ -552,7 +552,12 @@ impl AccountsDb {
             .get_slot_storage_entry_shrinking_in_progress_ok(target_slot)
             .expect("ancient shrink target slot must already have a storage");
         let (shrink_in_progress, create_and_insert_store_elapsed_us) =
-            measure_us!(self.get_store_for_shrink(target_slot, old_store, bytes));
+            measure_us!(self.get_store_for_shrink_with_provider(
+                target_slot,
+                old_store,
+                bytes,
+                self.ancient_accounts_file_provider,
+            ));
         let (store_accounts_stats, rewrite_elapsed_us) = measure_us!(
             self.store_accounts_for_squash(accounts_to_write, shrink_in_progress.new_storage())
         );
@@ -1122,9 +1127,15 @@ mod tests {
     use {
         super::*,
         crate::{
+            ObsoleteAccounts,
             account_info::{AccountInfo, StorageLocation},
+            account_storage_reader::{
+                ACCOUNT_STORAGE_MAX_BUFFER_SIZE, AccountStorageReader, TombstonesFilter,
+                storage_file_buf_reader,
+            },
             accounts_db::{
-                ShrinkCollectRefs,
+                LoadHint, PopulateReadCache, ShrinkCollectRefs,
+                accounts_db_config::{ACCOUNTS_DB_CONFIG_FOR_TESTING, AccountsDbConfig},
                 tests::{
                     append_single_account_with_default_hash, compare_all_accounts,
                     create_db_with_storages_and_index, create_storages_and_update_index,
@@ -1132,15 +1143,18 @@ mod tests {
                     remove_account_for_tests,
                 },
             },
+            accounts_file::{AccountsFile, AccountsFileProvider},
             accounts_index::{ReclaimsSlotList, UpsertReclaim},
+            ancestors::Ancestors,
             append_vec::{self, AppendVec},
             storable_accounts::StorableAccountsBySlot,
             utils::create_account_shared_data,
         },
+        agave_fs::{FileInfo, io_setup::IoSetupState},
         rand::seq::SliceRandom as _,
         solana_account::{AccountSharedData, ReadableAccount, WritableAccount},
         solana_pubkey::Pubkey,
-        std::{collections::HashSet, ops::Range},
+        std::{collections::HashSet, fs::File, io, ops::Range},
         strum::IntoEnumIterator,
         strum_macros::EnumIter,
     };
@@ -3592,6 +3606,98 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pack_ancient_storages_compressed() {
+        let db = AccountsDb::new_for_tests_with_config(
+            Vec::new(),
+            AccountsDbConfig {
+                ancient_accounts_file_provider: AccountsFileProvider::Compressed,
+                ..ACCOUNTS_DB_CONFIG_FOR_TESTING
+            },
+        );
+        let slot1 = 1;
+        let num_slots = 4;
+        create_storages_and_update_index(&db, slot1, num_slots, true, None);
+        let slots = slot1..(slot1 + num_slots as Slot);
+        let initial_accounts = get_all_accounts(&db, slots.clone());
+
+        combine_ancient_slots_packed_for_tests(&db, slots.clone().collect());
+
+        let storages = slots
+            .clone()
+            .filter_map(|slot| db.storage.get_slot_storage_entry(slot))
+            .collect::<Vec<_>>();
+        assert!(!storages.is_empty());
+        assert!(
+            storages
+                .iter()
+                .all(|storage| storage.accounts.is_compressed())
+        );
+        compare_all_accounts(&initial_accounts, &get_all_accounts(&db, slots.clone()));
+
+        // the packed accounts must be loadable through the index
+        let ancestors = Ancestors::from(slots.collect::<Vec<_>>());
+        let load_all_accounts = |db: &AccountsDb| {
+            initial_accounts
+                .iter()
+                .map(|(pubkey, _)| {
+                    let (account, _slot) = db
+                        .load(
+                            &ancestors,
+                            pubkey,
+                            LoadHint::Unspecified,
+                            PopulateReadCache::False,
+                        )
+                        .unwrap();
+                    (*pubkey, account)
+                })
+                .collect::<Vec<_>>()
+        };
+        compare_all_accounts(&initial_accounts, &load_all_accounts(&db));
+
+        // archive the storages the way a snapshot does, then rebuild a db from the archived files
+        let archive_dir = tempfile::tempdir().unwrap();
+        let rebuilt_db =
+            AccountsDb::new_for_tests_with_config(Vec::new(), ACCOUNTS_DB_CONFIG_FOR_TESTING);
+        let mut file_reader = storage_file_buf_reader(
+            ACCOUNT_STORAGE_MAX_BUFFER_SIZE,
+            false,
+            &IoSetupState::default(),
+        )
+        .unwrap();
+        for storage in &storages {
+            // compressed storages are read in their AppendVec representation, not from their file
+            let mut reader = AccountStorageReader::new(
+                storage,
+                None,
+                TombstonesFilter::Include,
+                &mut file_reader,
+            )
+            .unwrap();
+            let path = archive_dir
+                .path()
+                .join(AccountsFile::file_name(storage.slot(), storage.id()));
+            let mut file = File::create(&path).unwrap();
+            let bytes_written = io::copy(&mut reader, &mut file).unwrap();
+            assert_eq!(bytes_written as usize, reader.len());
+            drop(file);
+
+            let accounts =
+                AccountsFile::new_for_startup(FileInfo::new_from_path(path).unwrap()).unwrap();
+            assert!(!accounts.is_compressed());
+            rebuilt_db
+                .storage
+                .insert(Arc::new(AccountStorageEntry::new_existing(
+                    storage.slot(),
+                    storage.id(),
+                    accounts,
+                    ObsoleteAccounts::default(),
+                )));
+        }
+        rebuilt_db.generate_index(None, false);
+        compare_all_accounts(&initial_accounts, &load_all_accounts(&rebuilt_db));
+    }
+
     #[test]
     fn test_shrink_collect_alive_add() {
         let num_slots = 1;
@@ -858,6 +858,35 @@ impl AppendVec {
         u64_align!(STORE_META_OVERHEAD + data_len)
     }
 
+    /// Appends `account` to `buffer` in the layout `append_accounts()` writes to the file,
+    /// including the padding up to where the next account would start.
+    ///
+    /// Used to reproduce the AppendVec representation of accounts held in other formats.
+    pub(crate) fn serialize_account(
+        buffer: &mut Vec<u8>,
+        pubkey: &Pubkey,
+        account: &impl ReadableAccount,
+    ) {
+        let start = buffer.len();
+        let data = account.data();
+        // StoredMeta
+        buffer.extend_from_slice(&0u64.to_ne_bytes());
+        buffer.extend_from_slice(&(data.len() as u64).to_ne_bytes());
+        buffer.extend_from_slice(pubkey.as_ref());
+        // AccountMeta, zero filling its trailing padding
+        buffer.extend_from_slice(&account.lamports().to_ne_bytes());
+        buffer.extend_from_slice(&account.rent_epoch().to_ne_bytes());
+        buffer.extend_from_slice(account.owner().as_ref());
+        buffer.push(account.executable().into());
+        buffer.resize(
+            start + mem::size_of::<StoredMeta>() + mem::size_of::<AccountMeta>(),
+            0,
+        );
+        buffer.extend_from_slice(&ObsoleteAccountHash::ZEROED.0);
+        buffer.extend_from_slice(data);
+        buffer.resize(start + Self::calculate_stored_size(data.len()), 0);
+    }
+
     /// Checked variant of [`calculate_stored_size`].
     #[inline(always)]
     fn calculate_stored_size_checked(data_len: usize) -> Option<usize> {
@@ -1581,6 +1610,38 @@ mod tests {
         assert_eq!(num_account, 1);
     }
 
+    #[test]
+    fn test_serialize_account() {
+        let file = get_append_vec_path("test_serialize_account");
+        let path = &file.path;
+        let accounts = (0..10)
+            .map(|i| {
+                let mut account =
+                    AccountSharedData::new(i + 1, i as usize * 3, &Pubkey::new_unique());
+                account.set_executable(i % 2 == 0);
+                account.set_rent_epoch(i * 7);
+                (Pubkey::new_unique(), account)
+            })
+            .collect::<Vec<_>>();
+        let mut buffer = vec![];
+        for (pubkey, account) in &accounts {
+            AppendVec::serialize_account(&mut buffer, pubkey, account);
+        }
+        std::fs::write(path, &buffer).unwrap();
+
+        let (av, num_accounts) = AppendVec::new_from_file(path, buffer.len()).unwrap();
+        assert_eq!(num_accounts, accounts.len());
+        let mut offset = 0;
+        for (pubkey, account) in &accounts {
+            assert_eq!(
+                av.get_account_test(offset).unwrap(),
+                (*pubkey, account.clone())
+            );
+            offset += AppendVec::calculate_stored_size(account.data().len());
+        }
+        assert_eq!(offset, buffer.len());
+    }
+
     #[test]
     fn test_append_vec_reopen_as_readonly() {
         let file = get_append_vec_path("test_append_vec_flush");
@@ -0,0 +1,1293 @@
+//! Read-only, block-compressed storage for cold accounts.
+//!
+//! Ancient packing can write its output in this format instead of as an [`AppendVec`]. Account
+//! data is grouped into zstd compressed blocks, while the fixed sized fields of every account are
+//! kept uncompressed in an index so that pubkey scans and index generation never decompress data.
+//! Owners are deduplicated into a table that the index refers to.
+//!
+//! A file is written once, by a single call to [`CompressedFile::write_accounts`], and is
+//! read-only afterwards.
+//!
+//! Accounts are addressed by the offset they would have had in an [`AppendVec`] holding the
+//! same accounts, and [`CompressedFile::len`] is that AppendVec's length. This keeps alive and
+//! dead byte accounting identical between the two formats, and lets snapshot archives contain
+//! the AppendVec representation of a compressed file (see [`AppendVecLayoutReader`]).
+//!
+//! File layout:
+//!
+//! | section     | contents                                                   |
+//! |-------------|------------------------------------------------------------|
+//! | blocks      | zstd compressed account data, accounts never span blocks   |
+//! | index       | one [`IndexEntry`] per account, in storage order            |
+//! | owners      | distinct owners, referred to by index in [`IndexEntry`]     |
+//! | block table | one [`BlockEntry`] per block                                |
+//! | footer      | section offsets and counts, then [`MAGIC`]                  |
+//!
+//! All integers are little endian.
+
+use {
+    crate::{
+        account_info::Offset,
+        account_storage::stored_account_info::{StoredAccountInfo, StoredAccountInfoWithoutData},
+        accounts_file::{OpenFileForArchive, StoredAccountsInfo},
+        append_vec::AppendVec,
+        storable_accounts::StorableAccounts,
+    },
+    agave_fs::{
+        FileInfo, FileSize,
+        file_io::{read_into_buffer, write_buffer_to_file},
+    },
+    log::*,
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_clock::Epoch,
+    solana_pubkey::Pubkey,
+    std::{
+        collections::HashMap,
+        fs::{File, OpenOptions, remove_file},
+        io::{self, Read},
+        path::{Path, PathBuf},
+        sync::{
+            Arc, Mutex, OnceLock,
+            atomic::{AtomicBool, Ordering},
+        },
+    },
+    thiserror::Error,
+};
+
+/// Identifies a compressed file; stored in the last 8 bytes of the file.
+const MAGIC: u64 = u64::from_le_bytes(*b"AGVCOLD\0");
+
+const FORMAT_VERSION: u64 = 1;
+
+/// Accounts are added to a block until its uncompressed data would exceed this size.
+/// Larger accounts get a block of their own.
+const TARGET_BLOCK_SIZE: usize = 64 * 1024;
+
+/// zstd level used for the blocks. Files are written in the background, once, so favor ratio.
+const COMPRESSION_LEVEL: i32 = 9;
+
+/// Index entries are encoded into a buffer of about this size before being written.
+const INDEX_WRITE_BUFFER_SIZE: usize = 1024 * 1024;
+
+const FOOTER_SIZE: usize = 9 * size_of::<u64>();
+const BLOCK_ENTRY_SIZE: usize = 2 * size_of::<u64>() + 4 * size_of::<u32>();
+const INDEX_ENTRY_SIZE: usize =
+    size_of::<Pubkey>() + 3 * size_of::<u64>() + 3 * size_of::<u32>() + size_of::<u64>();
+
+pub type Result<T> = std::result::Result<T, CompressedFileError>;
+
+/// An enum for CompressedFile related errors.
+#[derive(Error, Debug)]
+pub enum CompressedFileError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("{} is not a compressed accounts file", .0.display())]
+    InvalidMagic(PathBuf),
+
+    #[error("unsupported compressed accounts file version {0}")]
+    UnsupportedVersion(u64),
+
+    #[error("incorrect layout of the compressed accounts file at path {}", .0.display())]
+    IncorrectLayout(PathBuf),
+}
+
+/// Location of the sections of a compressed file, stored at its end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Footer {
+    index_offset: u64,
+    num_accounts: u64,
+    owners_offset: u64,
+    num_owners: u64,
+    blocks_offset: u64,
+    num_blocks: u64,
+    /// Length of the equivalent AppendVec
+    append_vec_len: u64,
+    version: u64,
+    magic: u64,
+}
+
+impl Footer {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        for value in [
+            self.index_offset,
+            self.num_accounts,
+            self.owners_offset,
+            self.num_owners,
+            self.blocks_offset,
+            self.num_blocks,
+            self.append_vec_len,
+            self.version,
+            self.magic,
+        ] {
+            buffer.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    fn decode(bytes: &[u8; FOOTER_SIZE]) -> Self {
+        let mut decoder = Decoder::new(bytes);
+        Self {
+            index_offset: decoder.u64(),
+            num_accounts: decoder.u64(),
+            owners_offset: decoder.u64(),
+            num_owners: decoder.u64(),
+            blocks_offset: decoder.u64(),
+            num_blocks: decoder.u64(),
+            append_vec_len: decoder.u64(),
+            version: decoder.u64(),
+            magic: decoder.u64(),
+        }
+    }
+}
+
+/// A compressed block of account data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BlockEntry {
+    /// Position of the compressed bytes in the file
+    file_offset: u64,
+    /// AppendVec offset of the first account in the block
+    first_account_offset: u64,
+    compressed_len: u32,
+    uncompressed_len: u32,
+    /// Position of the block's first account in the index
+    first_account: u32,
+    num_accounts: u32,
+}
+
+impl BlockEntry {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&self.file_offset.to_le_bytes());
+        buffer.extend_from_slice(&self.first_account_offset.to_le_bytes());
+        buffer.extend_from_slice(&self.compressed_len.to_le_bytes());
+        buffer.extend_from_slice(&self.uncompressed_len.to_le_bytes());
+        buffer.extend_from_slice(&self.first_account.to_le_bytes());
+        buffer.extend_from_slice(&self.num_accounts.to_le_bytes());
+    }
+
+    fn decode(decoder: &mut Decoder) -> Self {
+        Self {
+            file_offset: decoder.u64(),
+            first_account_offset: decoder.u64(),
+            compressed_len: decoder.u32(),
+            uncompressed_len: decoder.u32(),
+            first_account: decoder.u32(),
+            num_accounts: decoder.u32(),
+        }
+    }
+}
+
+/// The fixed sized fields of an account, and where its data is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IndexEntry {
+    pubkey: Pubkey,
+    /// Offset of this account in the equivalent AppendVec
+    offset: u64,
+    lamports: u64,
+    rent_epoch: Epoch,
+    data_len: u32,
+    owner_index: u32,
+    /// Offset of the account's data within its uncompressed block
+    offset_in_block: u32,
+    executable: bool,
+}
+
+impl IndexEntry {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(self.pubkey.as_ref());
+        buffer.extend_from_slice(&self.offset.to_le_bytes());
+        buffer.extend_from_slice(&self.lamports.to_le_bytes());
+        buffer.extend_from_slice(&self.rent_epoch.to_le_bytes());
+        buffer.extend_from_slice(&self.data_len.to_le_bytes());
+        buffer.extend_from_slice(&self.owner_index.to_le_bytes());
+        buffer.extend_from_slice(&self.offset_in_block.to_le_bytes());
+        buffer.extend_from_slice(&u64::from(self.executable).to_le_bytes());
+    }
+
+    fn decode(decoder: &mut Decoder) -> Self {
+        Self {
+            pubkey: decoder.pubkey(),
+            offset: decoder.u64(),
+            lamports: decoder.u64(),
+            rent_epoch: decoder.u64(),
+            data_len: decoder.u32(),
+            owner_index: decoder.u32(),
+            offset_in_block: decoder.u32(),
+            executable: decoder.u64() != 0,
+        }
+    }
+
+    fn data_range(&self) -> std::ops::Range<usize> {
+        let start = self.offset_in_block as usize;
+        start..start + self.data_len as usize
+    }
+}
+
+/// Reads fixed sized little endian values from a buffer whose length was checked by the caller.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let (value, rest) = self.bytes.split_first_chunk().unwrap();
+        self.bytes = rest;
+        *value
+    }
+
+    fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.take())
+    }
+
+    fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.take())
+    }
+
+    fn pubkey(&mut self) -> Pubkey {
+        Pubkey::new_from_array(self.take())
+    }
+}
+
+/// What is known about a compressed file once it has been written or opened.
+#[derive(Debug)]
+struct Contents {
+    file_size: u64,
+    index_offset: u64,
+    num_accounts: usize,
+    append_vec_len: usize,
+    owners: Vec<Pubkey>,
+    blocks: Vec<BlockEntry>,
+}
+
+/// A write-once, block-compressed accounts file.
+#[derive(Debug)]
+pub struct CompressedFile {
+    /// The file path where the data is stored.
+    path: PathBuf,
+
+    /// the underlying file that backs this storage
+    file: File,
+
+    /// Set once the accounts have been written, or when opening an existing file
+    contents: OnceLock<Contents>,
+
+    /// Serializes the one write
+    write_lock: Mutex<()>,
+
+    /// if true, remove file when dropped
+    remove_file_on_drop: AtomicBool,
+
+    /// true if the file was written and has not been flushed since
+    is_dirty: AtomicBool,
+}
+
+impl Drop for CompressedFile {
+    fn drop(&mut self) {
+        if self.remove_file_on_drop.load(Ordering::Acquire)
+            && let Err(err) = remove_file(&self.path)
+        {
+            warn!(
+                "CompressedFile failed to remove {}: {err}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+impl CompressedFile {
+    /// Creates an empty CompressedFile at `path`, to be filled by `write_accounts()`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let _ignored = remove_file(&path);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .unwrap_or_else(|err| {
+                panic!("Unable to create data file {}: {err}", path.display());
+            });
+        Self {
+            path,
+            file,
+            contents: OnceLock::new(),
+            write_lock: Mutex::new(()),
+            remove_file_on_drop: AtomicBool::new(true),
+            is_dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns true if `file_info` ends with the footer of a compressed file.
+    ///
+    /// Besides the magic, the footer must describe sections that exactly fill the file, so that
+    /// an AppendVec whose last account's data happens to end with the magic is not mistaken for
+    /// a compressed file.
+    pub fn is_compressed_file(file_info: &FileInfo) -> io::Result<bool> {
+        match Self::read_footer(&file_info.file, file_info.size, &file_info.path) {
+            Ok(_) | Err(CompressedFileError::UnsupportedVersion(_)) => Ok(true),
+            Err(CompressedFileError::Io(err)) if err.kind() != io::ErrorKind::UnexpectedEof => {
+                Err(err)
+            }
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Opens the existing compressed file at `file_info`.
+    ///
+    /// The footer, block table and owners are validated; the accounts themselves are trusted,
+    /// like they are for AppendVecs opened at startup.
+    pub fn new_for_startup(file_info: FileInfo) -> Result<Self> {
+        let FileInfo { file, size, path } = file_info;
+        let contents = Self::read_contents(&file, size, &path)?;
+        Ok(Self {
+            path,
+            file,
+            contents: OnceLock::from(contents),
+            write_lock: Mutex::new(()),
+            remove_file_on_drop: AtomicBool::new(true),
+            is_dirty: AtomicBool::new(false),
+        })
+    }
+
+    /// Opens the existing compressed file at `path` and returns it with its number of accounts.
+    #[cfg(feature = "dev-context-only-utils")]
+    pub fn new_from_file(path: impl Into<PathBuf>) -> Result<(Self, usize)> {
+        let new = Self::new_for_startup(FileInfo::new_from_path(path)?)?;
+        let num_accounts = new.contents.get().unwrap().num_accounts;
+        Ok((new, num_accounts))
+    }
+
+    /// Reads the footer and checks that the sections it describes fill the file.
+    fn read_footer(file: &File, file_size: FileSize, path: &Path) -> Result<Footer> {
+        let incorrect_layout = || CompressedFileError::IncorrectLayout(path.to_path_buf());
+        let footer_offset = file_size
+            .checked_sub(FOOTER_SIZE as FileSize)
+            .ok_or_else(incorrect_layout)?;
+        let mut footer = [0; FOOTER_SIZE];
+        read_exact_at(file, file_size, footer_offset, &mut footer)?;
+        let footer = Footer::decode(&footer);
+        if footer.magic != MAGIC {
+            return Err(CompressedFileError::InvalidMagic(path.to_path_buf()));
+        }
+        if footer.version != FORMAT_VERSION {
+            return Err(CompressedFileError::UnsupportedVersion(footer.version));
+        }
+
+        // The sections must follow each other in order and end at the footer
+        let index_len = footer.num_accounts.checked_mul(INDEX_ENTRY_SIZE as u64);
+        let owners_len = footer.num_owners.checked_mul(size_of::<Pubkey>() as u64);
+        let blocks_len = footer.num_blocks.checked_mul(BLOCK_ENTRY_SIZE as u64);
+        let is_valid_layout = index_len
+            .and_then(|len| footer.index_offset.checked_add(len))
+            .is_some_and(|end| end == footer.owners_offset)
+            && owners_len
+                .and_then(|len| footer.owners_offset.checked_add(len))
+                .is_some_and(|end| end == footer.blocks_offset)
+            && blocks_len
+                .and_then(|len| footer.blocks_offset.checked_add(len))
+                .is_some_and(|end| end == footer_offset);
+        if !is_valid_layout {
+            return Err(incorrect_layout());
+        }
+        Ok(footer)
+    }
+
+    fn read_contents(file: &File, file_size: FileSize, path: &Path) -> Result<Contents> {
+        let incorrect_layout = || CompressedFileError::IncorrectLayout(path.to_path_buf());
+        let footer = Self::read_footer(file, file_size, path)?;
+        let footer_offset = file_size - FOOTER_SIZE as FileSize;
+
+        let mut owners_bytes = vec![0; (footer.blocks_offset - footer.owners_offset) as usize];
+        read_exact_at(file, file_size, footer.owners_offset, &mut owners_bytes)?;
+        let owners = owners_bytes
+            .chunks_exact(size_of::<Pubkey>())
+            .map(|bytes| Pubkey::try_from(bytes).unwrap())
+            .collect();
+
+        let mut blocks_bytes = vec![0; (footer_offset - footer.blocks_offset) as usize];
+        read_exact_at(file, file_size, footer.blocks_offset, &mut blocks_bytes)?;
+        let blocks = blocks_bytes
+            .chunks_exact(BLOCK_ENTRY_SIZE)
+            .map(|bytes| BlockEntry::decode(&mut Decoder::new(bytes)))
+            .collect::<Vec<_>>();
+
+        // Blocks must cover the index and the data section without gaps, so that reads never
+        // go beyond the section they expect
+        let mut next_account = 0u64;
+        let mut next_file_offset = 0u64;
+        for block in &blocks {
+            if u64::from(block.first_account) != next_account
+                || block.file_offset != next_file_offset
+            {
+                return Err(incorrect_layout());
+            }
+            next_account += u64::from(block.num_accounts);
+            next_file_offset += u64::from(block.compressed_len);
+        }
+        if next_account != footer.num_accounts || next_file_offset != footer.index_offset {
+            return Err(incorrect_layout());
+        }
+
+        Ok(Contents {
+            file_size,
+            index_offset: footer.index_offset,
+            num_accounts: footer.num_accounts as usize,
+            append_vec_len: footer.append_vec_len as usize,
+            owners,
+            blocks,
+        })
+    }
+
+    pub fn dead_bytes_due_to_zero_lamport_single_ref(&self, count: usize) -> usize {
+        Self::calculate_stored_size(0) * count
+    }
+
+    /// Flushes contents to disk
+    pub fn flush(&self) -> Result<()> {
+        if self.is_dirty.swap(false, Ordering::AcqRel) {
+            self.file.sync_all()?;
+        }
+        Ok(())
+    }
+
+    /// Detach the on-disk file from this CompressedFile's lifetime so dropping it no longer
+    /// removes the file.
+    pub fn disable_remove_on_drop(&self) {
+        self.remove_file_on_drop.store(false, Ordering::Release);
+    }
+
+    /// Returns the length of the equivalent AppendVec, *not* the size of the file.
+    pub fn len(&self) -> usize {
+        self.contents
+            .get()
+            .map_or(0, |contents| contents.append_vec_len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of bytes the file takes on disk.
+    pub fn file_size(&self) -> u64 {
+        self.contents.get().map_or(0, |contents| contents.file_size)
+    }
+
+    /// Returns the path to the file where the data is stored
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// Accounts are accounted for with the size they take in an AppendVec.
+    pub fn calculate_stored_size(data_len: usize) -> usize {
+        AppendVec::calculate_stored_size(data_len)
+    }
+
+    /// Calls `callback` with the stored account at `offset`.
+    ///
+    /// Returns `None` if there is no account at `offset`, otherwise returns the result of
+    /// `callback` in `Some`.
+    ///
+    /// This fn does *not* decompress the account's data.
+    pub fn get_stored_account_without_data_callback<Ret>(
+        &self,
+        offset: usize,
+        mut callback: impl for<'local> FnMut(StoredAccountInfoWithoutData<'local>) -> Ret,
+    ) -> Option<Ret> {
+        let contents = self.contents.get()?;
+        let (_block, entry) = self.get_index_entry(contents, offset)?;
+        let owner = contents.owners.get(entry.owner_index as usize)?;
+        Some(callback(StoredAccountInfoWithoutData {
+            pubkey: &entry.pubkey,
+            lamports: entry.lamports,
+            owner,
+            data_len: entry.data_len as usize,
+            executable: entry.executable,
+            rent_epoch: entry.rent_epoch,
+        }))
+    }
+
+    /// Calls `callback` with the stored account at `offset`.
+    ///
+    /// Returns `None` if there is no account at `offset`, otherwise returns the result of
+    /// `callback` in `Some`.
+    ///
+    /// This fn decompresses the block holding the account's data.  If the data is not needed,
+    /// use `get_stored_account_without_data_callback()` instead.
+    pub fn get_stored_account_callback<Ret>(
+        &self,
+        offset: usize,
+        mut callback: impl for<'local> FnMut(StoredAccountInfo<'local>) -> Ret,
+    ) -> Option<Ret> {
+        let contents = self.contents.get()?;
+        let (block, entry) = self.get_index_entry(contents, offset)?;
+        let owner = contents.owners.get(entry.owner_index as usize)?;
+        let block_data = self.read_block_data(contents, block).ok()?;
+        Some(callback(StoredAccountInfo {
+            pubkey: &entry.pubkey,
+            lamports: entry.lamports,
+            owner,
+            data: block_data.get(entry.data_range())?,
+            executable: entry.executable,
+            rent_epoch: entry.rent_epoch,
+        }))
+    }
+
+    /// return an `AccountSharedData` for an account at `offset`, if any.  Otherwise return None.
+    pub fn get_account_shared_data(&self, offset: usize) -> Option<AccountSharedData> {
+        let contents = self.contents.get()?;
+        let (block, entry) = self.get_index_entry(contents, offset)?;
+        let owner = contents.owners.get(entry.owner_index as usize)?;
+        let mut block_data = self.read_block_data(contents, block).ok()?;
+        let data_range = entry.data_range();
+        if data_range.end > block_data.len() {
+            return None;
+        }
+        // reuse the decompressed block's allocation for the account's data
+        block_data.truncate(data_range.end);
+        block_data.drain(..data_range.start);
+        Some(AccountSharedData::create_from_existing_shared_data(
+            entry.lamports,
+            Arc::new(block_data),
+            *owner,
+            entry.executable,
+            entry.rent_epoch,
+        ))
+    }
+
+    /// Iterate over all accounts and call `callback` with each account.
+    ///
+    /// Only the index is read; no account data is decompressed.
+    pub fn scan_accounts_without_data(
+        &self,
+        mut callback: impl for<'local> FnMut(Offset, StoredAccountInfoWithoutData<'local>),
+    ) -> Result<()> {
+        let Some(contents) = self.contents.get() else {
+            return Ok(());
+        };
+        for block in &contents.blocks {
+            for entry in self.read_index_entries(contents, block)? {
+                let owner = self.owner(contents, &entry)?;
+                callback(
+                    entry.offset as Offset,
+                    StoredAccountInfoWithoutData {
+                        pubkey: &entry.pubkey,
+                        lamports: entry.lamports,
+                        owner,
+                        data_len: entry.data_len as usize,
+                        executable: entry.executable,
+                        rent_epoch: entry.rent_epoch,
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Iterate over all accounts and call `callback` with each account, decompressing one
+    /// block at a time.
+    pub fn scan_accounts(
+        &self,
+        mut callback: impl for<'local> FnMut(Offset, StoredAccountInfo<'local>),
+    ) -> Result<()> {
+        let Some(contents) = self.contents.get() else {
+            return Ok(());
+        };
+        for block in &contents.blocks {
+            let entries = self.read_index_entries(contents, block)?;
+            let block_data = self.read_block_data(contents, block)?;
+            for entry in entries {
+                let owner = self.owner(contents, &entry)?;
+                let data = block_data
+                    .get(entry.data_range())
+                    .ok_or_else(|| CompressedFileError::IncorrectLayout(self.path.clone()))?;
+                callback(
+                    entry.offset as Offset,
+                    StoredAccountInfo {
+                        pubkey: &entry.pubkey,
+                        lamports: entry.lamports,
+                        owner,
+                        data,
+                        executable: entry.executable,
+                        rent_epoch: entry.rent_epoch,
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// for each offset in `sorted_offsets`, get the data size
+    pub(crate) fn get_account_data_lens(&self, sorted_offsets: &[usize]) -> Vec<usize> {
+        let mut account_sizes = Vec::with_capacity(sorted_offsets.len());
+        let Some(contents) = self.contents.get() else {
+            return account_sizes;
+        };
+        // offsets are sorted, so consecutive ones are likely to be in the same block
+        let mut cached_block: Option<(usize, Vec<IndexEntry>)> = None;
+        for &offset in sorted_offsets {
+            let Some(block_index) = Self::find_block(contents, offset) else {
+                break;
+            };
+            if cached_block
+                .as_ref()
+                .is_none_or(|(cached_index, _)| *cached_index != block_index)
+            {
+                let Ok(entries) = self.read_index_entries(contents, &contents.blocks[block_index])
+                else {
+                    break;
+                };
+                cached_block = Some((block_index, entries));
+            }
+            let (_, entries) = cached_block.as_ref().unwrap();
+            let Some(entry) = Self::find_entry(entries, offset) else {
+                break;
+            };
+            account_sizes.push(entry.data_len as usize);
+        }
+        account_sizes
+    }
+
+    /// iterate over all pubkeys and call `callback`.
+    pub fn scan_pubkeys(&self, mut callback: impl FnMut(&Pubkey)) -> Result<()> {
+        let Some(contents) = self.contents.get() else {
+            return Ok(());
+        };
+        for block in &contents.blocks {
+            for entry in self.read_index_entries(contents, block)? {
+                callback(&entry.pubkey);
+            }
+        }
+        Ok(())
+    }
+
+    /// Compresses and writes all of `accounts`.
+    ///
+    /// Returns None if there are no accounts, or if the file has already been written; a
+    /// CompressedFile is never appended to.
+    pub fn write_accounts<'a>(
+        &self,
+        accounts: &impl StorableAccounts<'a>,
+    ) -> Option<StoredAccountsInfo> {
+        let _lock = self.write_lock.lock().unwrap();
+        if self.contents.get().is_some() || accounts.is_empty() {
+            return None;
+        }
+
+        let mut writer = Writer::new(&self.file);
+        for i in 0..accounts.len() {
+            accounts.account_default_if_zero_lamport(i, |account| {
+                writer.add_account(account.pubkey(), &account);
+            });
+        }
+        let (contents, offsets) = writer
+            .finish()
+            .expect("must write accounts to compressed file");
+        let size = contents.append_vec_len;
+        self.contents.set(contents).unwrap();
+        self.is_dirty.store(true, Ordering::Release);
+
+        Some(StoredAccountsInfo { offsets, size })
+    }
+
+    /// Returns the way to access this accounts file when archiving
+    pub(crate) fn open_file_for_archive(&self) -> OpenFileForArchive<'_> {
+        OpenFileForArchive::Borrowed(&self.file)
+    }
+
+    /// Returns a reader of the AppendVec representation of all the accounts in this file.
+    pub(crate) fn append_vec_layout_reader(&self) -> AppendVecLayoutReader<'_> {
+        AppendVecLayoutReader {
+            file: self,
+            next_block: 0,
+            buffer: Vec::new(),
+            buffer_offset: 0,
+            offset: 0,
+        }
+    }
+
+    fn owner<'c>(&self, contents: &'c Contents, entry: &IndexEntry) -> Result<&'c Pubkey> {
+        contents
+            .owners
+            .get(entry.owner_index as usize)
+            .ok_or_else(|| CompressedFileError::IncorrectLayout(self.path.clone()))
+    }
+
+    /// Returns the index of the block that would hold the account at `offset`
+    fn find_block(contents: &Contents, offset: usize) -> Option<usize> {
+        if offset >= contents.append_vec_len {
+            return None;
+        }
+        contents
+            .blocks
+            .partition_point(|block| block.first_account_offset <= offset as u64)
+            .checked_sub(1)
+    }
+
+    fn find_entry(entries: &[IndexEntry], offset: usize) -> Option<&IndexEntry> {
+        entries
+            .binary_search_by_key(&(offset as u64), |entry| entry.offset)
+            .ok()
+            .map(|index| &entries[index])
+    }
+
+    fn get_index_entry<'c>(
+        &self,
+        contents: &'c Contents,
+        offset: usize,
+    ) -> Option<(&'c BlockEntry, IndexEntry)> {
+        let block = &contents.blocks[Self::find_block(contents, offset)?];
+        let entries = self.read_index_entries(contents, block).ok()?;
+        let entry = Self::find_entry(&entries, offset)?.clone();
+        Some((block, entry))
+    }
+
+    fn read_index_entries(
+        &self,
+        contents: &Contents,
+        block: &BlockEntry,
+    ) -> io::Result<Vec<IndexEntry>> {
+        let mut bytes = vec![0; block.num_accounts as usize * INDEX_ENTRY_SIZE];
+        let start =
+            contents.index_offset + u64::from(block.first_account) * INDEX_ENTRY_SIZE as u64;
+        read_exact_at(&self.file, contents.file_size, start, &mut bytes)?;
+        Ok(bytes
+            .chunks_exact(INDEX_ENTRY_SIZE)
+            .map(|bytes| IndexEntry::decode(&mut Decoder::new(bytes)))
+            .collect())
+    }
+
+    fn read_block_data(&self, contents: &Contents, block: &BlockEntry) -> io::Result<Vec<u8>> {
+        let mut compressed = vec![0; block.compressed_len as usize];
+        read_exact_at(
+            &self.file,
+            contents.file_size,
+            block.file_offset,
+            &mut compressed,
+        )?;
+        let data = zstd::bulk::decompress(&compressed, block.uncompressed_len as usize)?;
+        if data.len() != block.uncompressed_len as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "block decompressed to {} bytes, expected {}",
+                    data.len(),
+                    block.uncompressed_len
+                ),
+            ));
+        }
+        Ok(data)
+    }
+}
+
+/// Builds a compressed file, writing each block as soon as it is full.
+struct Writer<'a> {
+    file: &'a File,
+    owners: Vec<Pubkey>,
+    owner_indexes: HashMap<Pubkey, u32>,
+    entries: Vec<IndexEntry>,
+    blocks: Vec<BlockEntry>,
+    /// uncompressed data of the block being filled
+    block_data: Vec<u8>,
+    /// index of the first account of the block being filled
+    block_first_account: usize,
+    /// where the next block is written
+    file_offset: u64,
+    /// AppendVec offset of the next account
+    append_vec_offset: usize,
+}
+
+impl<'a> Writer<'a> {
+    fn new(file: &'a File) -> Self {
+        Self {
+            file,
+            owners: Vec::new(),
+            owner_indexes: HashMap::new(),
+            entries: Vec::new(),
+            blocks: Vec::new(),
+            block_data: Vec::with_capacity(TARGET_BLOCK_SIZE),
+            block_first_account: 0,
+            file_offset: 0,
+            append_vec_offset: 0,
+        }
+    }
+
+    fn add_account(&mut self, pubkey: &Pubkey, account: &impl ReadableAccount) {
+        let data = account.data();
+        if !self.block_data.is_empty() && self.block_data.len() + data.len() > TARGET_BLOCK_SIZE {
+            self.write_block()
+                .expect("must write block to compressed file");
+        }
+        let owner_index = *self
+            .owner_indexes
+            .entry(*account.owner())
+            .or_insert_with(|| {
+                self.owners.push(*account.owner());
+                (self.owners.len() - 1) as u32
+            });
+        self.entries.push(IndexEntry {
+            pubkey: *pubkey,
+            offset: self.append_vec_offset as u64,
+            lamports: account.lamports(),
+            rent_epoch: account.rent_epoch(),
+            data_len: data.len() as u32,
+            owner_index,
+            offset_in_block: self.block_data.len() as u32,
+            executable: account.executable(),
+        });
+        self.block_data.extend_from_slice(data);
+        self.append_vec_offset += AppendVec::calculate_stored_size(data.len());
+    }
+
+    /// Compresses and writes the block being filled, if it holds any accounts.
+    fn write_block(&mut self) -> io::Result<()> {
+        let num_accounts = self.entries.len() - self.block_first_account;
+        if num_accounts == 0 {
+            return Ok(());
+        }
+        let compressed = zstd::bulk::compress(&self.block_data, COMPRESSION_LEVEL)?;
+        write_buffer_to_file(self.file, &compressed, self.file_offset)?;
+        self.blocks.push(BlockEntry {
+            file_offset: self.file_offset,
+            first_account_offset: self.entries[self.block_first_account].offset,
+            compressed_len: compressed.len() as u32,
+            uncompressed_len: self.block_data.len() as u32,
+            first_account: self.block_first_account as u32,
+            num_accounts: num_accounts as u32,
+        });
+        self.file_offset += compressed.len() as u64;
+        self.block_first_account = self.entries.len();
+        self.block_data.clear();
+        Ok(())
+    }
+
+    /// Writes the last block and the index, owners, block table and footer.
+    ///
+    /// Returns the contents of the written file and the offset of each account.
+    fn finish(mut self) -> io::Result<(Contents, Vec<Offset>)> {
+        self.write_block()?;
+
+        let index_offset = self.file_offset;
+        let mut buffer = Vec::with_capacity(INDEX_WRITE_BUFFER_SIZE + INDEX_ENTRY_SIZE);
+        for entry in &self.entries {
+            entry.encode(&mut buffer);
+            if buffer.len() >= INDEX_WRITE_BUFFER_SIZE {
+                self.write_buffer(&mut buffer)?;
+            }
+        }
+        self.write_buffer(&mut buffer)?;
+
+        let owners_offset = self.file_offset;
+        for owner in &self.owners {
+            buffer.extend_from_slice(owner.as_ref());
+        }
+        self.write_buffer(&mut buffer)?;
+
+        let blocks_offset = self.file_offset;
+        for block in &self.blocks {
+            block.encode(&mut buffer);
+        }
+        Footer {
+            index_offset,
+            num_accounts: self.entries.len() as u64,
+            owners_offset,
+            num_owners: self.owners.len() as u64,
+            blocks_offset,
+            num_blocks: self.blocks.len() as u64,
+            append_vec_len: self.append_vec_offset as u64,
+            version: FORMAT_VERSION,
+            magic: MAGIC,
+        }
+        .encode(&mut buffer);
+        self.write_buffer(&mut buffer)?;
+
+        let offsets = self
+            .entries
+            .iter()
+            .map(|entry| entry.offset as Offset)
+            .collect();
+        let contents = Contents {
+            file_size: self.file_offset,
+            index_offset,
+            num_accounts: self.entries.len(),
+            append_vec_len: self.append_vec_offset,
+            owners: self.owners,
+            blocks: self.blocks,
+        };
+        Ok((contents, offsets))
+    }
+
+    fn write_buffer(&mut self, buffer: &mut Vec<u8>) -> io::Result<()> {
+        write_buffer_to_file(self.file, buffer, self.file_offset)?;
+        self.file_offset += buffer.len() as u64;
+        buffer.clear();
+        Ok(())
+    }
+}
+
+/// Reads exactly `buffer.len()` bytes from `file` at `offset`.
+fn read_exact_at(
+    file: &File,
+    file_size: FileSize,
+    offset: FileSize,
+    buffer: &mut [u8],
+) -> io::Result<()> {
+    let bytes_read = read_into_buffer(file, file_size, offset, buffer)?;
+    if bytes_read != buffer.len() {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+/// Produces the bytes of an AppendVec holding the same accounts as a [`CompressedFile`],
+/// decompressing one block at a time.
+///
+/// Positions are AppendVec offsets, so accounts can be skipped by the same offsets and stored
+/// sizes that describe them in the compressed file.
+pub(crate) struct AppendVecLayoutReader<'a> {
+    file: &'a CompressedFile,
+    next_block: usize,
+    /// AppendVec representation of the accounts of the last block read
+    buffer: Vec<u8>,
+    /// AppendVec offset of the first byte of `buffer`
+    buffer_offset: usize,
+    /// AppendVec offset of the next byte to return
+    offset: usize,
+}
+
+impl AppendVecLayoutReader<'_> {
+    /// Returns the AppendVec offset of the next byte to be read.
+    pub(crate) fn get_file_offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Skips the next `amt` bytes.
+    pub(crate) fn consume_or_skip(&mut self, amt: usize) {
+        self.offset += amt;
+    }
+
+    /// Returns the bytes starting at the current offset, or an empty slice at the end.
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.offset >= self.buffer_offset + self.buffer.len() {
+            let Some(contents) = self.file.contents.get() else {
+                return Ok(&[]);
+            };
+            let Some(block) = contents.blocks.get(self.next_block) else {
+                return Ok(&[]);
+            };
+            self.next_block += 1;
+            if (block.first_account_offset as usize) < self.offset {
+                // Skip blocks that end before the current offset without decompressing them
+                let next_block_offset = contents
+                    .blocks
+                    .get(self.next_block)
+                    .map_or(contents.append_vec_len, |next| {
+                        next.first_account_offset as usize
+                    });
+                if next_block_offset <= self.offset {
+                    continue;
+                }
+            }
+            let entries = self.file.read_index_entries(contents, block)?;
+            let block_data = self.file.read_block_data(contents, block)?;
+            self.buffer.clear();
+            for entry in &entries {
+                let owner = self.file.owner(contents, entry).map_err(io::Error::other)?;
+                let data = block_data
+                    .get(entry.data_range())
+                    .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+                let account = StoredAccountInfo {
+                    pubkey: &entry.pubkey,
+                    lamports: entry.lamports,
+                    owner,
+                    data,
+                    executable: entry.executable,
+                    rent_epoch: entry.rent_epoch,
+                };
+                AppendVec::serialize_account(&mut self.buffer, &entry.pubkey, &account);
+            }
+            self.buffer_offset = block.first_account_offset as usize;
+        }
+        Ok(&self.buffer[self.offset - self.buffer_offset..])
+    }
+}
+
+impl Read for AppendVecLayoutReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.offset += len;
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::append_vec::test_utils::get_append_vec_path,
+        rand::{Rng, SeedableRng, rngs::StdRng},
+        solana_account::WritableAccount,
+        std::mem::ManuallyDrop,
+    };
+
+    /// Accounts with varied data sizes, a few owners, and some zero lamport accounts
+    fn create_test_accounts(count: usize) -> Vec<(Pubkey, AccountSharedData)> {
+        let mut rng = StdRng::seed_from_u64(count as u64);
+        let owners = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        (0..count)
+            .map(|i| {
+                let lamports = if i % 10 == 3 {
+                    0
+                } else {
+                    rng.random_range(1..1_000_000)
+                };
+                let data_len = match i % 7 {
+                    0 => 0,
+                    1 => TARGET_BLOCK_SIZE + 100,
+                    _ => rng.random_range(1..2_000),
+                };
+                let mut account = AccountSharedData::new(lamports, data_len, &owners[i % 3]);
+                account.data_as_mut_slice().fill((i % 251) as u8);
+                account.set_executable(i % 5 == 0);
+                account.set_rent_epoch(i as Epoch);
+                (Pubkey::new_unique(), account)
+            })
+            .collect()
+    }
+
+    /// The account as it is stored: zero lamport accounts are stored as default accounts
+    fn stored_account(account: &AccountSharedData) -> AccountSharedData {
+        if account.lamports() == 0 {
+            AccountSharedData::default()
+        } else {
+            account.clone()
+        }
+    }
+
+    fn write_test_file(
+        path: &Path,
+        accounts: &[(Pubkey, AccountSharedData)],
+    ) -> (CompressedFile, StoredAccountsInfo) {
+        let compressed_file = CompressedFile::new(path);
+        let stored_accounts_info = compressed_file.write_accounts(&(0, accounts)).unwrap();
+        (compressed_file, stored_accounts_info)
+    }
+
+    #[test]
+    fn test_write_and_read_accounts() {
+        let file = get_append_vec_path("test_write_and_read_accounts");
+        let accounts = create_test_accounts(100);
+        let (compressed_file, stored_accounts_info) = write_test_file(&file.path, &accounts);
+
+        // Offsets and sizes match those of an AppendVec holding the same accounts
+        let append_vec_file = get_append_vec_path("test_write_and_read_accounts_av");
+        let av = AppendVec::new(&append_vec_file.path, 16 * 1024 * 1024);
+        let av_stored_accounts_info = av.append_accounts(&(0, &accounts[..])).unwrap();
+        assert_eq!(
+            stored_accounts_info.offsets,
+            av_stored_accounts_info.offsets
+        );
+        assert_eq!(stored_accounts_info.size, av_stored_accounts_info.size);
+        assert_eq!(compressed_file.len(), av.len());
+        assert!(compressed_file.file_size() < av.len() as u64);
+
+        for ((pubkey, account), offset) in accounts.iter().zip(&stored_accounts_info.offsets) {
+            let expected = stored_account(account);
+            assert_eq!(
+                compressed_file.get_account_shared_data(*offset).unwrap(),
+                expected
+            );
+            compressed_file
+                .get_stored_account_callback(*offset, |stored| {
+                    assert_eq!(stored.pubkey(), pubkey);
+                    assert!(solana_account::accounts_equal(&stored, &expected));
+                })
+                .unwrap();
+            compressed_file
+                .get_stored_account_without_data_callback(*offset, |stored| {
+                    assert_eq!(stored.pubkey(), pubkey);
+                    assert_eq!(stored.data_len, expected.data().len());
+                    assert_eq!(stored.owner, expected.owner());
+                })
+                .unwrap();
+        }
+
+        // Offsets that are not the start of an account
+        assert!(compressed_file.get_account_shared_data(1).is_none());
+        assert!(
+            compressed_file
+                .get_account_shared_data(compressed_file.len())
+                .is_none()
+        );
+
+        // A compressed file is written exactly once
+        assert!(
+            compressed_file
+                .write_accounts(&(0, &accounts[..1]))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_scan_accounts() {
+        let file = get_append_vec_path("test_scan_accounts");
+        let accounts = create_test_accounts(50);
+        let (compressed_file, stored_accounts_info) = write_test_file(&file.path, &accounts);
+
+        let mut scanned = vec![];
+        compressed_file
+            .scan_accounts(|offset, account| {
+                scanned.push((offset, *account.pubkey(), account.data().to_vec()));
+            })
+            .unwrap();
+        let expected = accounts
+            .iter()
+            .zip(&stored_accounts_info.offsets)
+            .map(|((pubkey, account), offset)| {
+                (*offset, *pubkey, stored_account(account).data().to_vec())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(scanned, expected);
+
+        let mut scanned = vec![];
+        compressed_file
+            .scan_accounts_without_data(|offset, account| {
+                scanned.push((offset, *account.pubkey(), account.data_len));
+            })
+            .unwrap();
+        let expected = expected
+            .into_iter()
+            .map(|(offset, pubkey, data)| (offset, pubkey, data.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(scanned, expected);
+
+        let mut pubkeys = vec![];
+        compressed_file
+            .scan_pubkeys(|pubkey| pubkeys.push(*pubkey))
+            .unwrap();
+        assert_eq!(
+            pubkeys,
+            accounts
+                .iter()
+                .map(|(pubkey, _)| *pubkey)
+                .collect::<Vec<_>>()
+        );
+
+        let data_lens = compressed_file.get_account_data_lens(&stored_accounts_info.offsets);
+        assert_eq!(
+            data_lens,
+            expected.iter().map(|(_, _, len)| *len).collect::<Vec<_>>()
+        );
+        // stops at the first offset that is not an account
+        let data_lens = compressed_file.get_account_data_lens(&[
+            stored_accounts_info.offsets[0],
+            stored_accounts_info.offsets[1] + 8,
+            stored_accounts_info.offsets[2],
+        ]);
+        assert_eq!(data_lens, vec![expected[0].2]);
+    }
+
+    #[test]
+    fn test_new_for_startup() {
+        let file = get_append_vec_path("test_compressed_file_new_for_startup");
+        let accounts = create_test_accounts(30);
+        let (len, offsets) = {
+            let (compressed_file, stored_accounts_info) = write_test_file(&file.path, &accounts);
+            compressed_file.flush().unwrap();
+            let compressed_file = ManuallyDrop::new(compressed_file);
+            (compressed_file.len(), stored_accounts_info.offsets)
+        };
+
+        let file_info = FileInfo::new_from_path(&file.path).unwrap();
+        assert!(CompressedFile::is_compressed_file(&file_info).unwrap());
+        let compressed_file = CompressedFile::new_for_startup(file_info).unwrap();
+        assert_eq!(compressed_file.len(), len);
+        for ((_, account), offset) in accounts.iter().zip(offsets) {
+            assert_eq!(
+                compressed_file.get_account_shared_data(offset).unwrap(),
+                stored_account(account)
+            );
+        }
+    }
+
+    #[test]
+    fn test_new_for_startup_rejects_other_files() {
+        let file = get_append_vec_path("test_compressed_file_rejects_other_files");
+        {
+            let av = ManuallyDrop::new(AppendVec::new(&file.path, 1024 * 1024));
+            av.append_accounts(&(0, &create_test_accounts(3)[..]))
+                .unwrap();
+        }
+        let file_info = FileInfo::new_from_path(&file.path).unwrap();
+        assert!(!CompressedFile::is_compressed_file(&file_info).unwrap());
+        assert!(matches!(
+            CompressedFile::new_for_startup(file_info),
+            Err(CompressedFileError::InvalidMagic(_))
+        ));
+
+        // A truncated compressed file
+        let file = get_append_vec_path("test_compressed_file_truncated");
+        {
+            let (compressed_file, _) = write_test_file(&file.path, &create_test_accounts(10));
+            compressed_file.disable_remove_on_drop();
+        }
+        let mut bytes = std::fs::read(&file.path).unwrap();
+        let footer = bytes.split_off(bytes.len() - FOOTER_SIZE);
+        bytes.truncate(bytes.len() - 1);
+        bytes.extend_from_slice(&footer);
+        std::fs::write(&file.path, bytes).unwrap();
+        let file_info = FileInfo::new_from_path(&file.path).unwrap();
+        assert!(matches!(
+            CompressedFile::new_for_startup(file_info),
+            Err(CompressedFileError::IncorrectLayout(_))
+        ));
+    }
+
+    #[test]
+    fn test_append_vec_layout_reader() {
+        let file = get_append_vec_path("test_append_vec_layout_reader");
+        let accounts = create_test_accounts(40);
+        let (compressed_file, stored_accounts_info) = write_test_file(&file.path, &accounts);
+
+        let mut bytes = vec![];
+        compressed_file
+            .append_vec_layout_reader()
+            .read_to_end(&mut bytes)
+            .unwrap();
+        assert_eq!(bytes.len(), compressed_file.len());
+
+        let append_vec_file = get_append_vec_path("test_append_vec_layout_reader_av");
+        std::fs::write(&append_vec_file.path, &bytes).unwrap();
+        let (av, num_accounts) =
+            AppendVec::new_from_file(&append_vec_file.path, bytes.len()).unwrap();
+        assert_eq!(num_accounts, accounts.len());
+        for ((_, account), offset) in accounts.iter().zip(&stored_accounts_info.offsets) {
+            assert_eq!(
+                av.get_account_shared_data(*offset).unwrap(),
+                stored_account(account)
+            );
+        }
+
+        // Skipping lands on the same bytes as reading through
+        let skipped_account = 5;
+        let skip_from = stored_accounts_info.offsets[skipped_account];
+        let skip_to = stored_accounts_info.offsets[skipped_account + 20];
+        let mut reader = compressed_file.append_vec_layout_reader();
+        let mut head = vec![0; skip_from];
+        reader.read_exact(&mut head).unwrap();
+        reader.consume_or_skip(skip_to - skip_from);
+        assert_eq!(reader.get_file_offset(), skip_to);
+        let mut tail = vec![];
+        reader.read_to_end(&mut tail).unwrap();
+        assert_eq!(head, bytes[..skip_from]);
+        assert_eq!(tail, bytes[skip_to..]);
+    }
+}
@@ -0,0 +1,223 @@
+//! Read-only, point-in-time views of rooted account state.
+//!
+//! A `HistoricalView` pins a root so that clean keeps every account version
+//! needed to read state as of that root, for as long as the view is alive.
+//! Roots can only be pinned while clean has not yet advanced past them, which
+//! `AccountsDbConfig::historical_retention_slots` holds back by a window of slots.
+use {
+    crate::{
+        accounts_db::{AccountsDb, LoadHint, PopulateReadCache},
+        ancestors::Ancestors,
+    },
+    solana_account::AccountSharedData,
+    solana_clock::Slot,
+    solana_pubkey::Pubkey,
+    std::sync::Arc,
+    thiserror::Error,
+};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum HistoricalViewError {
+    #[error("slot {slot} is not rooted, the highest root is {max_root}")]
+    NotRooted { slot: Slot, max_root: Slot },
+    #[error("account state as of slot {slot} is no longer retained")]
+    NotRetained {
+        slot: Slot,
+        /// The oldest root a view can currently be opened at, if any
+        oldest_retained_root: Option<Slot>,
+    },
+}
+
+/// Account state as of a rooted slot
+#[derive(Debug)]
+pub struct HistoricalView {
+    accounts_db: Arc<AccountsDb>,
+    slot: Slot,
+    /// Only `slot` itself; older roots are resolved from it by the index and write cache
+    ancestors: Ancestors,
+}
+
+impl HistoricalView {
+    /// Pins `slot` for reading, which must be a root clean has not advanced past
+    pub fn new(accounts_db: Arc<AccountsDb>, slot: Slot) -> Result<Self, HistoricalViewError> {
+        let max_root = accounts_db.max_root();
+        if slot > max_root {
+            return Err(HistoricalViewError::NotRooted { slot, max_root });
+        }
+        accounts_db
+            .scan_tracker
+            .try_pin_root(slot)
+            .map_err(|min_historical_root| HistoricalViewError::NotRetained {
+                slot,
+                oldest_retained_root: (min_historical_root <= max_root)
+                    .then_some(min_historical_root),
+            })?;
+        // Skipped slots and slots on abandoned forks are below the highest root too, but the
+        // latter may still hold unrooted account state. Clean can't advance past a pinned slot,
+        // so a pinned root is still tracked.
+        if !accounts_db.scan_tracker.is_historical_root(slot) {
+            accounts_db.scan_tracker.unpin_root(slot);
+            return Err(HistoricalViewError::NotRooted { slot, max_root });
+        }
+        Ok(Self {
+            accounts_db,
+            slot,
+            ancestors: Ancestors::from(vec![slot]),
+        })
+    }
+
+    /// The root this view reads account state as of
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    /// Loads `pubkey` as of `slot()`, along with the slot it was last written in.
+    /// note this returns None for accounts with zero lamports
+    pub fn load(&self, pubkey: &Pubkey) -> Option<(AccountSharedData, Slot)> {
+        // Views are usually of old roots, don't evict the current working set
+        self.accounts_db.load(
+            &self.ancestors,
+            pubkey,
+            LoadHint::Unspecified,
+            PopulateReadCache::False,
+        )
+    }
+}
+
+impl Drop for HistoricalView {
+    fn drop(&mut self) {
+        self.accounts_db.scan_tracker.unpin_root(self.slot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::accounts_db::{ACCOUNTS_DB_CONFIG_FOR_TESTING, AccountsDbConfig},
+        solana_account::ReadableAccount,
+    };
+
+    fn new_accounts_db(historical_retention_slots: Option<u64>) -> Arc<AccountsDb> {
+        Arc::new(AccountsDb::new_for_tests_with_config(
+            Vec::new(),
+            AccountsDbConfig {
+                historical_retention_slots,
+                ..ACCOUNTS_DB_CONFIG_FOR_TESTING
+            },
+        ))
+    }
+
+    /// Stores `lamports` for `pubkey` in `slot`, then roots, flushes and cleans it
+    fn store_and_clean(accounts_db: &AccountsDb, slot: Slot, pubkey: &Pubkey, lamports: u64) {
+        let account = AccountSharedData::new(lamports, 0, &Pubkey::default());
+        accounts_db.store_for_tests((slot, [(pubkey, &account)].as_slice()));
+        accounts_db.add_root_and_flush_write_cache(slot);
+        accounts_db.clean_accounts_for_tests();
+    }
+
+    #[test]
+    fn test_historical_view_reads_retained_roots() {
+        let accounts_db = new_accounts_db(Some(2));
+        let pubkey = Pubkey::new_unique();
+        for slot in 1..=5 {
+            store_and_clean(&accounts_db, slot, &pubkey, slot * 10);
+        }
+
+        // roots 3..=5 are within the retention window
+        for slot in 3..=5 {
+            let view = HistoricalView::new(Arc::clone(&accounts_db), slot).unwrap();
+            let (account, found_slot) = view.load(&pubkey).unwrap();
+            assert_eq!(account.lamports(), slot * 10);
+            assert_eq!(found_slot, slot);
+        }
+        assert_eq!(
+            HistoricalView::new(Arc::clone(&accounts_db), 2).unwrap_err(),
+            HistoricalViewError::NotRetained {
+                slot: 2,
+                oldest_retained_root: Some(3),
+            }
+        );
+        assert_eq!(
+            HistoricalView::new(Arc::clone(&accounts_db), 6).unwrap_err(),
+            HistoricalViewError::NotRooted {
+                slot: 6,
+                max_root: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn test_historical_view_rejects_forked_slot() {
+        let accounts_db = new_accounts_db(Some(2));
+        let pubkey = Pubkey::new_unique();
+        store_and_clean(&accounts_db, 1, &pubkey, 10);
+
+        // slot 2 forks off root 1 and is abandoned once slot 3 is rooted on the other fork
+        let account = AccountSharedData::new(20, 0, &Pubkey::default());
+        accounts_db.store_for_tests((2, [(&pubkey, &account)].as_slice()));
+        let account = AccountSharedData::new(30, 0, &Pubkey::default());
+        accounts_db.store_for_tests((3, [(&pubkey, &account)].as_slice()));
+        accounts_db.add_root_and_flush_write_cache(3);
+
+        assert_eq!(
+            HistoricalView::new(Arc::clone(&accounts_db), 2).unwrap_err(),
+            HistoricalViewError::NotRooted {
+                slot: 2,
+                max_root: 3,
+            }
+        );
+        // the rejected slot was not left pinned
+        assert!(accounts_db.scan_tracker.min_ongoing_scan_root().is_none());
+        for (slot, lamports) in [(1, 10), (3, 30)] {
+            let view = HistoricalView::new(Arc::clone(&accounts_db), slot).unwrap();
+            let (account, found_slot) = view.load(&pubkey).unwrap();
+            assert_eq!(account.lamports(), lamports);
+            assert_eq!(found_slot, slot);
+        }
+    }
+
+    #[test]
+    fn test_historical_view_holds_back_clean() {
+        let accounts_db = new_accounts_db(Some(0));
+        let pubkey = Pubkey::new_unique();
+        store_and_clean(&accounts_db, 1, &pubkey, 10);
+
+        let view = HistoricalView::new(Arc::clone(&accounts_db), 1).unwrap();
+        for slot in 2..=4 {
+            store_and_clean(&accounts_db, slot, &pubkey, slot * 10);
+        }
+        // Newer roots don't leak into the view, and the pinned version survived clean
+        let (account, found_slot) = view.load(&pubkey).unwrap();
+        assert_eq!(account.lamports(), 10);
+        assert_eq!(found_slot, 1);
+        assert_eq!(accounts_db.scan_tracker.min_ongoing_scan_root(), Some(1));
+
+        // Once the view is dropped, clean catches up to the retention window
+        drop(view);
+        assert!(accounts_db.scan_tracker.min_ongoing_scan_root().is_none());
+        accounts_db.clean_accounts_for_tests();
+        assert_eq!(
+            HistoricalView::new(Arc::clone(&accounts_db), 1).unwrap_err(),
+            HistoricalViewError::NotRetained {
+                slot: 1,
+                oldest_retained_root: Some(4),
+            }
+        );
+    }
+
+    #[test]
+    fn test_historical_view_without_retention() {
+        let accounts_db = new_accounts_db(None);
+        let pubkey = Pubkey::new_unique();
+        store_and_clean(&accounts_db, 1, &pubkey, 10);
+
+        assert_eq!(
+            HistoricalView::new(Arc::clone(&accounts_db), 1).unwrap_err(),
+            HistoricalViewError::NotRetained {
+                slot: 1,
+                oldest_retained_root: None,
+            }
+        );
+    }
+}
@@ -23,7 +23,9 @@ pub mod append_vec;
 #[cfg(not(feature = "dev-context-only-utils"))]
 mod append_vec;
 pub mod blockhash_queue;
+pub mod compressed_file;
 pub mod contains;
+pub mod historical_view;
 pub mod is_loadable;
 mod is_zero_lamport;
 mod obsolete_accounts;
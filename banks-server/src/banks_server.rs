@@ -202,6 +202,7 @@ fn simulate_transaction(
         post_balances: _,
         pre_token_balances: _,
         post_token_balances: _,
+        execution_trace: _,
     } = bank.simulate_transaction_unchecked(&sanitized_transaction, true);
 
     let simulation_details = TransactionSimulationDetails {
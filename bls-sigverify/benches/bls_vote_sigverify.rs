@@ -4,7 +4,10 @@
 */
 
 use {
-    agave_bls_sigverify::bls_vote_sigverify::{UnverifiedVotePayload, verify_individual_votes},
+    agave_bls_sigverify::{
+        batch_verify::{BatchEntry, verify_batch},
+        bls_vote_sigverify::{UnverifiedVotePayload, verify_votes_batch},
+    },
     agave_votor_messages::{
         consensus_message::Block,
         unverified_vote_message::UnverifiedVoteMessage,
@@ -12,12 +15,13 @@ use {
         wire::{VotePayloadToSign, get_vote_payload_to_sign},
     },
     criterion::{BatchSize, Criterion, criterion_group, criterion_main},
-    rayon::{ThreadPool, ThreadPoolBuilder},
+    rayon::{
+        ThreadPool, ThreadPoolBuilder,
+        iter::{IntoParallelRefIterator, ParallelIterator},
+    },
     solana_bls_signatures::{Keypair as BLSKeypair, PreparedHashedMessage, VerifySignature},
-    solana_genesis_config::GenesisConfig,
     solana_hash::Hash,
     solana_keypair::Keypair,
-    solana_runtime::bank::{Bank, SlotLeader},
     solana_signer::Signer,
     std::{hint::black_box, num::NonZero},
 };
@@ -32,9 +36,12 @@ fn get_thread_pool() -> ThreadPool {
         .unwrap()
 }
 
+/// Generates `batch_size` votes, the first `num_invalid` of which carry a
+/// signature over a different payload.
 fn generate_test_data(
     shred_version: u16,
     batch_size: usize,
+    num_invalid: usize,
 ) -> (VotePayloadToSign, Vec<UnverifiedVotePayload>) {
     // Pre-calculate the payloads to ensure exact distinctness
     let slot = 100;
@@ -43,12 +50,17 @@ fn generate_test_data(
         block_id: Hash::new_unique(),
     });
     let payload = get_vote_payload_to_sign(vote, shred_version);
+    let invalid_payload = get_vote_payload_to_sign(Vote::new_skip_vote(slot), shred_version);
     (
         VotePayloadToSign::new_from_vote(vote, shred_version),
         (0..batch_size)
-            .map(|_| {
+            .map(|i| {
                 let bls_keypair = BLSKeypair::new();
-                let signature = bls_keypair.sign(&payload);
+                let signature = if i < num_invalid {
+                    bls_keypair.sign(&invalid_payload)
+                } else {
+                    bls_keypair.sign(&payload)
+                };
                 let vote_message = UnverifiedVoteMessage {
                     vote,
                     signature: signature.into(),
@@ -107,55 +119,109 @@ fn bench_verify_single_signature_with_prepared_message(c: &mut Criterion) {
 }
 
 // Individual Verification - verifies each signatures in parallel threads
+// This is what isolating invalid votes cost before bisection, kept for reference.
 // Message distinctness is irrelevant.
 fn bench_verify_individual_votes(c: &mut Criterion) {
     let shred_version = 134;
-    let mut group = c.benchmark_group("verify_votes_fallback");
+    let mut group = c.benchmark_group("verify_individual_votes");
     let thread_pool = get_thread_pool();
 
-    let leader = SlotLeader::new_unique();
-    let genesis_config = GenesisConfig::default();
-    let bank = Bank::new_with_paths_for_tests(&genesis_config, None, vec![], Some(leader));
-    assert_eq!(*bank.leader(), leader);
-    let (bank, _bank_forks) = bank.wrap_with_bank_forks_for_tests();
-
     for &batch_size in BATCH_SIZES {
         // Distinctness doesn't affect the cost of N individual verifications.
         let (vote_payload_to_sign, unverified_votes) =
-            generate_test_data(shred_version, batch_size);
+            generate_test_data(shred_version, batch_size, 0);
+        let serialized_vote = wincode::serialize(&vote_payload_to_sign).unwrap();
+        let prepared_hash_msg = PreparedHashedMessage::new(&serialized_vote);
+        let label = format!("batch_{batch_size}");
+
+        group.bench_function(&label, |b| {
+            b.iter(|| {
+                let res = thread_pool.install(|| {
+                    black_box(&unverified_votes)
+                        .par_iter()
+                        .map(|v| {
+                            v.sender_bls_pubkey.verify_signature_prepared(
+                                &v.vote_message.signature,
+                                &prepared_hash_msg,
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                });
+                black_box(res);
+            })
+        });
+    }
+    group.finish();
+}
+
+// Random linear combination verification of a batch with only valid signatures
+fn bench_verify_batch(c: &mut Criterion) {
+    let shred_version = 134;
+    let mut group = c.benchmark_group("verify_batch");
+    let thread_pool = get_thread_pool();
+
+    for &batch_size in BATCH_SIZES {
+        let (vote_payload_to_sign, unverified_votes) =
+            generate_test_data(shred_version, batch_size, 0);
+        let serialized_vote = wincode::serialize(&vote_payload_to_sign).unwrap();
+        let prepared_hash_msg = PreparedHashedMessage::new(&serialized_vote);
+        let entries = unverified_votes
+            .iter()
+            .map(|v| (&v.sender_bls_pubkey, &v.vote_message.signature))
+            .collect::<Vec<BatchEntry>>();
         let label = format!("batch_{batch_size}");
 
         group.bench_function(&label, |b| {
-            b.iter_batched(
-                || {
-                    let rank_map = bank
-                        .epoch_stakes_from_slot(unverified_votes[0].vote_message.vote.slot())
-                        .unwrap()
-                        .bls_pubkey_to_rank_map();
-                    let serialized_vote = wincode::serialize(&vote_payload_to_sign).unwrap();
-                    let prepared_hash_msg = PreparedHashedMessage::new(&serialized_vote);
-                    (unverified_votes.clone(), prepared_hash_msg, rank_map.len())
-                },
-                |(votes, prepared_hash_map, max_validators)| {
-                    let res = verify_individual_votes(
-                        max_validators,
-                        black_box(votes),
-                        black_box(prepared_hash_map),
-                        &thread_pool,
-                    );
-                    black_box(res);
-                },
-                BatchSize::SmallInput,
-            )
+            b.iter(|| {
+                let res = verify_batch(black_box(&entries), &prepared_hash_msg, &thread_pool);
+                black_box(res);
+            })
         });
     }
     group.finish();
 }
 
+// Verification of a batch with invalid votes, which bisects it to isolate them
+fn bench_verify_votes_batch(c: &mut Criterion) {
+    let shred_version = 134;
+    let mut group = c.benchmark_group("verify_votes_batch");
+    let thread_pool = get_thread_pool();
+
+    for &batch_size in BATCH_SIZES {
+        for num_invalid in [1, batch_size / 4] {
+            let (vote_payload_to_sign, unverified_votes) =
+                generate_test_data(shred_version, batch_size, num_invalid);
+            let label = format!("batch_{batch_size}_invalid_{num_invalid}");
+
+            group.bench_function(&label, |b| {
+                b.iter_batched(
+                    || {
+                        let serialized_vote = wincode::serialize(&vote_payload_to_sign).unwrap();
+                        let prepared_hash_msg = PreparedHashedMessage::new(&serialized_vote);
+                        (unverified_votes.clone(), prepared_hash_msg)
+                    },
+                    |(votes, prepared_hash_msg)| {
+                        let res = verify_votes_batch(
+                            black_box(votes),
+                            black_box(&prepared_hash_msg),
+                            &thread_pool,
+                        );
+                        black_box(res);
+                    },
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_verify_single_signature,
     bench_verify_single_signature_with_prepared_message,
-    bench_verify_individual_votes
+    bench_verify_individual_votes,
+    bench_verify_batch,
+    bench_verify_votes_batch
 );
 criterion_main!(benches);
@@ -14,6 +14,7 @@ use {
         VerifiedVoterSlotsSender,
         certificate::CertificateType,
         consensus_message::Block,
+        equivocation::{EquivocationProof, SignedVote},
         metric_types::ConsensusMetricsEventSender,
         migration::MigrationStatus,
         sig_verified_messages::SigVerifiedBatch,
@@ -24,11 +25,11 @@ use {
     },
     agave_votor_transport::endpoint::{BanSender, Datagram},
     crossbeam_channel::{Receiver, Sender, TryRecvError, select},
-    log::{error, info},
+    log::{error, info, warn},
     rayon::{ThreadPool, ThreadPoolBuilder},
     solana_clock::{Epoch, Slot},
     solana_gossip::cluster_info::ClusterInfo,
-    solana_ledger::leader_schedule_cache::LeaderScheduleCache,
+    solana_ledger::{blockstore::Blockstore, leader_schedule_cache::LeaderScheduleCache},
     solana_measure::measure_us,
     solana_perf::packet::packet_config,
     solana_pubkey::Pubkey,
@@ -78,6 +79,8 @@ pub struct SigVerifierContext {
     pub leader_schedule: Arc<LeaderScheduleCache>,
     pub num_threads: usize,
     pub generated_cert_types: Arc<GeneratedCertTypes>,
+    /// Equivocation proofs are persisted here.
+    pub blockstore: Arc<Blockstore>,
 }
 
 pub struct SigVerifierChannels {
@@ -128,6 +131,7 @@ struct SigVerifier {
     generated_cert_types: Arc<GeneratedCertTypes>,
     vote_pool: VotePool,
     rank_map_cache: HashMap<Epoch, Arc<BLSPubkeyToRankMap>>,
+    blockstore: Arc<Blockstore>,
 }
 
 impl SigVerifier {
@@ -141,6 +145,7 @@ impl SigVerifier {
             leader_schedule,
             num_threads,
             generated_cert_types,
+            blockstore,
         } = context;
         let thread_pool = ThreadPoolBuilder::new()
             .num_threads(num_threads)
@@ -164,6 +169,7 @@ impl SigVerifier {
             thread_pool,
             generated_cert_types,
             rank_map_cache: HashMap::new(),
+            blockstore,
         }
     }
 
@@ -447,6 +453,39 @@ impl SigVerifier {
                 rank,
             }),
             Err(VotePoolError::Duplicate) => None,
+            Err(VotePoolError::Equivocation(earlier)) => {
+                let proof = EquivocationProof {
+                    vote_account: entry.vote_account_pubkey,
+                    first: SignedVote::from(&earlier),
+                    second: SignedVote::from(&msg),
+                };
+                // Neither vote has been sigverified yet, so only keep proofs that hold up.
+                if let Err(err) = proof.verify(&entry.bls_pubkey) {
+                    info!(
+                        "bls_sigverifier: discarding equivocation proof against {}: {err}",
+                        entry.vote_account_pubkey
+                    );
+                } else {
+                    match self.blockstore.store_equivocation_proof(&proof) {
+                        Ok(true) => {
+                            self.stats.equivocation_proofs_stored += 1;
+                            warn!(
+                                "bls_sigverifier: validator {sender_identity_pubkey} equivocated \
+                                 in slot {vote_slot}"
+                            );
+                        }
+                        Ok(false) => (),
+                        Err(err) => error!("failed to store equivocation proof: {err}"),
+                    }
+                }
+                self.stats.invalid_vote_banning_validator += 1;
+                self.ban_sender.ban(sender_identity_pubkey, BAN_TIMEOUT);
+                info!(
+                    "bls_sigverifier: banned sender={sender_identity_pubkey} due to conflicting \
+                     vote"
+                );
+                None
+            }
             Err(VotePoolError::Invalid) => {
                 self.stats.invalid_vote_banning_validator += 1;
                 self.ban_sender.ban(sender_identity_pubkey, BAN_TIMEOUT);
@@ -517,6 +556,7 @@ mod tests {
         solana_gossip::contact_info::ContactInfo,
         solana_hash::Hash,
         solana_keypair::Keypair,
+        solana_ledger::get_tmp_ledger_path_auto_delete,
         solana_net_utils::SocketAddrSpace,
         solana_pubkey::Pubkey,
         solana_runtime::{
@@ -533,6 +573,7 @@ mod tests {
             num::NonZero,
             sync::RwLock,
         },
+        tempfile::TempDir,
         tokio::sync::mpsc,
     };
 
@@ -561,6 +602,7 @@ mod tests {
         generated_cert_types: Arc<GeneratedCertTypes>,
         _certificate_sender: Sender<(Slot, UnverifiedCertificate)>,
         _bank_forks: Arc<RwLock<BankForks>>,
+        _ledger_path: TempDir,
     }
 
     impl TestContext {
@@ -616,6 +658,8 @@ mod tests {
 
             let generated_cert_types = Arc::new(GeneratedCertTypes::default());
             let (ban_sender, ban_receiver) = stub_ban_channel_for_tests(1024);
+            let ledger_path = get_tmp_ledger_path_auto_delete!();
+            let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
             let highest_parent_ready = Arc::new(RwLock::new((
                 NUM_SLOTS_FOR_VERIFY,
                 Block {
@@ -633,6 +677,7 @@ mod tests {
                     leader_schedule,
                     num_threads: 4,
                     generated_cert_types: generated_cert_types.clone(),
+                    blockstore,
                 },
                 SigVerifierChannels {
                     packet_receiver,
@@ -655,6 +700,7 @@ mod tests {
                 generated_cert_types,
                 _certificate_sender: certificate_sender,
                 _bank_forks: bank_forks,
+                _ledger_path: ledger_path,
             }
         }
 
@@ -1650,6 +1696,8 @@ mod tests {
         let (_packet_sender, packet_receiver) = bounded(1024);
         let (_certificate_sender, certificate_receiver) = bounded(1024);
         let (ban_sender, _ban_receiver) = stub_ban_channel_for_tests(1024);
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
         let mut sig_verifier = SigVerifier::new(
             SigVerifierContext {
                 migration_status: Arc::new(MigrationStatus::default()),
@@ -1660,6 +1708,7 @@ mod tests {
                 leader_schedule,
                 num_threads: 4,
                 generated_cert_types: Arc::new(GeneratedCertTypes::default()),
+                blockstore,
             },
             SigVerifierChannels {
                 packet_receiver,
@@ -1979,6 +2028,154 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_invalid_vote_isolated_by_bisection() {
+        let mut ctx = TestContext::new();
+
+        let num_votes = ctx.validator_keypairs.len();
+        let invalid_index = 7;
+        let vote = Vote::new_skip_vote(42);
+        let valid_payload =
+            get_vote_payload_to_sign(vote, ctx.verifier.cluster_info.my_shred_version());
+        let invalid_payload = get_vote_payload_to_sign(
+            Vote::new_skip_vote(999),
+            ctx.verifier.cluster_info.my_shred_version(),
+        );
+        let messages: Vec<_> = ctx
+            .validator_keypairs
+            .iter()
+            .enumerate()
+            .map(|(i, keypair)| {
+                let signature = if i == invalid_index {
+                    keypair.bls_keypair.sign(&invalid_payload).into()
+                } else {
+                    keypair.bls_keypair.sign(&valid_payload).into()
+                };
+                let message = ConsensusMessage::Vote(VoteMessage {
+                    vote,
+                    signature,
+                    rank: i as u16,
+                    stake: NonZero::new(123).unwrap(),
+                });
+                (message, keypair.node_keypair.pubkey())
+            })
+            .collect();
+
+        ctx.verifier
+            .verify_and_send_datagrams(messages_to_datagrams(
+                &messages,
+                ctx.verifier.cluster_info.my_shred_version(),
+            ))
+            .unwrap();
+        let batches = ctx.pool_receiver.try_iter().collect::<Vec<_>>();
+        assert_eq!(batches.len(), 1);
+        match &batches[0] {
+            SigVerifiedBatch::Votes(aggregates) => {
+                assert_eq!(aggregates.len(), num_votes.saturating_sub(1));
+                assert!(
+                    aggregates
+                        .iter()
+                        .all(|aggregate| !*aggregate.ranks().get(invalid_index).unwrap())
+                );
+            }
+            rest => panic!("unexpected type: {rest:?}"),
+        }
+
+        // The half without the invalid vote is accepted by a single combined
+        // check, so fewer signatures than votes are verified individually.
+        let vote_stats = &ctx.verifier.stats.vote_stats;
+        assert_eq!(
+            vote_stats.num_fallback_verified.0,
+            (num_votes as u64).saturating_sub(1)
+        );
+        assert!(vote_stats.bisection_batch_checks.0 > 0);
+        assert!(vote_stats.bisection_individual_checks.0 < num_votes as u64);
+
+        let banned = ctx.banned_pubkeys();
+        assert_eq!(banned.len(), 1);
+        assert!(banned.contains(&messages[invalid_index].1));
+    }
+
+    #[test]
+    fn test_conflicting_votes_store_equivocation_proof() {
+        let mut ctx = TestContext::new();
+        let shred_version = ctx.verifier.cluster_info.my_shred_version();
+        let root_bank = ctx.verifier.sharable_banks.root();
+        let block = Block {
+            slot: 42,
+            block_id: Hash::new_unique(),
+        };
+        let sender = ctx.validator_keypairs[2].node_keypair.pubkey();
+        let vote_account = ctx.validator_keypairs[2].vote_keypair.pubkey();
+        let messages = [Vote::new_notarization_vote(block), Vote::new_skip_vote(42)].map(|vote| {
+            let vote_message = create_signed_vote_message(
+                &root_bank,
+                &ctx.validator_keypairs,
+                shred_version,
+                vote,
+                2,
+            );
+            (ConsensusMessage::Vote(vote_message), sender)
+        });
+
+        ctx.verifier
+            .verify_and_send_datagrams(messages_to_datagrams(&messages, shred_version))
+            .unwrap();
+
+        assert!(ctx.banned_pubkeys().contains(&sender));
+        let proof = ctx
+            .verifier
+            .blockstore
+            .get_equivocation_proof(42, vote_account)
+            .unwrap()
+            .unwrap();
+        assert_eq!(proof.first.vote(), Vote::new_notarization_vote(block));
+        assert_eq!(proof.second.vote(), Vote::new_skip_vote(42));
+        assert_eq!(
+            proof.verify(&ctx.validator_keypairs[2].bls_keypair.public),
+            Ok(())
+        );
+        assert_eq!(ctx.verifier.stats.equivocation_proofs_stored.0, 1);
+    }
+
+    #[test]
+    fn test_forged_conflicting_vote_is_not_stored() {
+        let mut ctx = TestContext::new();
+        let shred_version = ctx.verifier.cluster_info.my_shred_version();
+        let root_bank = ctx.verifier.sharable_banks.root();
+        let sender = ctx.validator_keypairs[2].node_keypair.pubkey();
+        let vote_account = ctx.validator_keypairs[2].vote_keypair.pubkey();
+        let skip = create_signed_vote_message(
+            &root_bank,
+            &ctx.validator_keypairs,
+            shred_version,
+            Vote::new_skip_vote(42),
+            2,
+        );
+        // Signed by another validator, so it does not prove anything about the sender
+        let finalize = create_signed_vote_message(
+            &root_bank,
+            &ctx.validator_keypairs,
+            shred_version,
+            Vote::new_finalization_vote(42),
+            3,
+        );
+        let messages = [skip, finalize].map(|msg| (ConsensusMessage::Vote(msg), sender));
+
+        ctx.verifier
+            .verify_and_send_datagrams(messages_to_datagrams(&messages, shred_version))
+            .unwrap();
+
+        assert!(ctx.banned_pubkeys().contains(&sender));
+        assert_eq!(
+            ctx.verifier
+                .blockstore
+                .get_equivocation_proof(42, vote_account)
+                .unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn test_banlist_updates_for_invalid_certificates() {
         let mut ctx = TestContext::new();
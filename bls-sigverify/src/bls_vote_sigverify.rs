@@ -4,6 +4,10 @@ use qualifier_attr::qualifiers;
 use std::collections::HashSet;
 use {
     crate::{
+        batch_verify::{
+            BatchEntry, BatchVerifyStats, OptimisticBatchOutcome, isolate_invalid_signatures,
+            verify_batch_optimistic,
+        },
         bls_sigverifier::{BAN_TIMEOUT, SigVerifierChannels},
         errors::SigVerifyVoteError,
         rewards::rewards_wants_vote,
@@ -23,13 +27,10 @@ use {
     },
     agave_votor_transport::endpoint::BanSender,
     log::info,
-    rayon::{
-        ThreadPool, current_thread_index,
-        iter::{Either, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator},
-    },
+    rayon::ThreadPool,
     solana_bls_signatures::{
-        BlsError, PreparedHashedMessage, PubkeyProjective, SignatureProjective,
-        pubkey::{PopVerified, PubkeyAffine as BlsPubkeyAffine, VerifySignature},
+        BlsError, PreparedHashedMessage, SignatureProjective,
+        pubkey::{PopVerified, PubkeyAffine as BlsPubkeyAffine},
     },
     solana_clock::{Epoch, Slot},
     solana_gossip::cluster_info::ClusterInfo,
@@ -43,7 +44,8 @@ use {
 #[cfg_attr(feature = "dev-context-only-utils", qualifiers(pub))]
 struct VerifiedVotePayload {
     vote_aggregate: VoteAggregate,
-    sender_vote_account_pubkeys: Vec<Pubkey>,
+    /// Vote account pubkey and stake of each sender included in the aggregate.
+    senders: Vec<(Pubkey, NonZero<u64>)>,
 }
 
 /// [`VoteMessage`] along with other information needed to sig verify it.
@@ -59,13 +61,8 @@ pub(super) struct UnverifiedVotePayload {
 }
 
 impl UnverifiedVotePayload {
-    fn verify(
-        &self,
-        max_validators: usize,
-        prepared_hashed_message: &PreparedHashedMessage,
-    ) -> Result<VerifiedVotePayload, BlsError> {
-        self.sender_bls_pubkey
-            .verify_signature_prepared(&self.vote_message.signature, prepared_hashed_message)?;
+    /// Converts a vote whose signature has already been verified.
+    fn into_verified(self, max_validators: usize) -> VerifiedVotePayload {
         let vote_msg = VoteMessage {
             vote: self.vote_message.vote,
             signature: self.vote_message.signature,
@@ -73,17 +70,17 @@ impl UnverifiedVotePayload {
             stake: self.stake,
         };
         let vote_aggregate = VoteAggregate::new_from_verified_vote(max_validators, vote_msg);
-        Ok(VerifiedVotePayload {
+        VerifiedVotePayload {
             vote_aggregate,
-            sender_vote_account_pubkeys: vec![self.sender_vote_account_pubkey],
-        })
+            senders: vec![(self.sender_vote_account_pubkey, self.stake)],
+        }
     }
 }
 
 /// Verifies votes and sends the verified votes to the consensus pool; and sends the desired subset
 /// to rewards container and repair.
 ///
-/// Any vote that fails fallback individual signature verification will have its sender banlisted.
+/// Any vote that the fallback verification identifies as invalid will have its sender banlisted.
 pub(super) fn verify_and_send_votes(
     unverified_votes: HashMap<VotePayloadToSign, Vec<UnverifiedVotePayload>>,
     rank_map_cache: &HashMap<Epoch, Arc<BLSPubkeyToRankMap>>,
@@ -143,7 +140,7 @@ fn inspect_for_repair(
     let vote_slot = vote.vote_aggregate.vote().slot();
     match vote.vote_aggregate.vote() {
         Vote::Notarize(_) | Vote::Finalize(_) | Vote::NotarizeFallback(_) => {
-            for pubkey in &vote.sender_vote_account_pubkeys {
+            for (pubkey, _) in &vote.senders {
                 msgs_for_repair.entry(*pubkey).or_default().push(vote_slot);
             }
         }
@@ -173,10 +170,11 @@ fn process_verified_votes(
     for payload in verified_votes {
         inspect_for_repair(&payload, &mut msgs_for_repair);
 
-        for pubkey in &payload.sender_vote_account_pubkeys {
+        for (pubkey, stake) in &payload.senders {
             votes_for_metrics.push(ConsensusMetricsEvent::Vote {
                 id: *pubkey,
                 vote: *payload.vote_aggregate.vote(),
+                stake: stake.get(),
             });
         }
         if rewards_wants_vote(
@@ -215,74 +213,6 @@ fn verify_votes(
     ban_sender: &BanSender,
     thread_pool: &ThreadPool,
 ) -> Vec<VerifiedVotePayload> {
-    // Try optimistic verification - fast to verify, but cannot identify invalid votes
-    let res = verify_votes_optimistic(vote_payload_to_sign, &unverified_votes, stats, thread_pool);
-
-    match res {
-        Either::Left(signature) => {
-            stats.optimistic_verification_succeeded += 1;
-            stats
-                .optimistic_batch
-                .add_sample(unverified_votes.len() as u64);
-            let vote_aggregate = VoteAggregate::new_from_verified_votes(
-                max_validators,
-                vote_payload_to_sign,
-                unverified_votes.iter().map(|v| (v.rank, v.stake)),
-                signature,
-            );
-            let sender_vote_account_pubkeys = unverified_votes
-                .into_iter()
-                .map(|v| v.sender_vote_account_pubkey)
-                .collect();
-            vec![VerifiedVotePayload {
-                vote_aggregate,
-                sender_vote_account_pubkeys,
-            }]
-        }
-        Either::Right(prepared_hash_msg) => {
-            // Fallback to individual verification
-            stats.optimistic_verification_failed += 1;
-            let ((verified_votes, invalid_remote_pubkeys), time_us) =
-                measure_us!(verify_individual_votes(
-                    max_validators,
-                    unverified_votes,
-                    prepared_hash_msg,
-                    thread_pool
-                ));
-            stats.num_individual_verified += verified_votes.len() as u64;
-            for (sender_identity_pubkey, error) in invalid_remote_pubkeys {
-                stats.banning_validator += 1;
-                ban_sender.ban(sender_identity_pubkey, BAN_TIMEOUT);
-                info!(
-                    "bls_vote_sigverify: banned sender={sender_identity_pubkey} due to failed \
-                     verification {error:?}"
-                );
-            }
-            stats.fn_verify_individual_votes_stats.add_sample(time_us);
-            verified_votes
-        }
-    }
-}
-
-#[cfg_attr(feature = "dev-context-only-utils", qualifiers(pub))]
-/// Attempts aggregate BLS verification across the full vote set.
-///
-/// This fast path aggregates all vote signatures and the public keys for each
-/// distinct vote payload, minimizing the number of pairing operations needed
-/// for verification. When aggregation or aggregate verification fails, the
-/// caller falls back to individual vote verification so invalid votes can be
-/// identified precisely.
-///
-/// Returns the optimistic verification outcome together with the distinct vote
-/// messages and their prepared payloads, which can be reused by the fallback
-/// path.
-#[must_use]
-fn verify_votes_optimistic(
-    vote_payload_to_sign: VotePayloadToSign,
-    unverified_votes: &[UnverifiedVotePayload],
-    stats: &mut SigVerifyVoteStats,
-    thread_pool: &ThreadPool,
-) -> Either<SignatureProjective, PreparedHashedMessage> {
     #[cfg(debug_assertions)]
     {
         let deduped = unverified_votes
@@ -292,98 +222,110 @@ fn verify_votes_optimistic(
         assert_eq!(deduped.len(), unverified_votes.len());
     }
 
-    let mut measure = Measure::start("verify_votes_optimistic");
-
-    // For BLS verification, minimizing the expensive pairing operation is key.
-    // Each BLS signature verification requires two pairings.
-    //
-    // However, the BLS verification formula allows us to:
-    // 1. Aggregate all signatures into a single signature.
-    // 2. Aggregate public keys for each unique message.
-    //
-    // By verifying the aggregated signature against the aggregated public keys,
-    // the number of pairings required is reduced to (1 + number of distinct messages).
-    let (signature_result, (prepared_hash_msg, pubkey_result)) = thread_pool.join(
-        || aggregate_signatures(unverified_votes),
-        || aggregate_pubkeys_by_payload(vote_payload_to_sign, unverified_votes),
+    let serialized_vote = wincode::serialize(&vote_payload_to_sign).unwrap();
+    let prepared_hash_msg = PreparedHashedMessage::new(&serialized_vote);
+    let num_votes = unverified_votes.len() as u64;
+    let ((verified_votes, invalid_remote_pubkeys, aggregate, batch_stats), time_us) = measure_us!(
+        verify_votes_batch(unverified_votes, &prepared_hash_msg, thread_pool)
     );
+    stats.bisection_batch_checks += batch_stats.batch_checks;
+    stats.bisection_individual_checks += batch_stats.individual_checks;
 
-    let Ok(aggregate_signature) = signature_result else {
-        return Either::Right(prepared_hash_msg);
-    };
-
-    let Ok(aggregate_pubkey) = pubkey_result else {
-        return Either::Right(prepared_hash_msg);
-    };
-
-    let verified = aggregate_pubkey
-        .verify_signature_prepared(&aggregate_signature, &prepared_hash_msg)
-        .is_ok();
-
-    measure.stop();
-    stats
-        .fn_verify_votes_optimistic_stats
-        .add_sample(measure.as_us());
-    if verified {
-        Either::Left(aggregate_signature)
-    } else {
-        Either::Right(prepared_hash_msg)
+    // Optimistic path - the aggregate of the full batch verified, so the votes
+    // can be forwarded as a single aggregate
+    if let Some(signature) = aggregate {
+        stats.optimistic_verification_succeeded += 1;
+        stats.optimistic_batch.add_sample(num_votes);
+        stats.fn_verify_votes_optimistic_stats.add_sample(time_us);
+        let vote_aggregate = VoteAggregate::new_from_verified_votes(
+            max_validators,
+            vote_payload_to_sign,
+            verified_votes.iter().map(|v| (v.rank, v.stake)),
+            signature,
+        );
+        let senders = verified_votes
+            .into_iter()
+            .map(|v| (v.sender_vote_account_pubkey, v.stake))
+            .collect();
+        return vec![VerifiedVotePayload {
+            vote_aggregate,
+            senders,
+        }];
     }
-}
 
-#[cfg_attr(feature = "dev-context-only-utils", qualifiers(pub))]
-fn aggregate_signatures(votes: &[UnverifiedVotePayload]) -> Result<SignatureProjective, BlsError> {
-    debug_assert!(current_thread_index().is_some());
-    let signatures = votes.par_iter().map(|v| &v.vote_message.signature);
-    // TODO(sam): Currently, `par_aggregate` performs full validation
-    // (on-curve + subgroup check) for every signature. Since the subgroup
-    // check is expensive, we can use an `unchecked` deserialization here
-    // (performing only the cheap on-curve check) and rely on a single subgroup
-    // check on the final aggregated signature. This should save more than 80%
-    // of the time for signature aggregation.
-    SignatureProjective::par_aggregate(signatures)
-}
-
-#[cfg_attr(feature = "dev-context-only-utils", qualifiers(pub))]
-fn aggregate_pubkeys_by_payload(
-    vote_payload_to_sign: VotePayloadToSign,
-    votes: &[UnverifiedVotePayload],
-) -> (
-    PreparedHashedMessage,
-    Result<PopVerified<PubkeyProjective>, BlsError>,
-) {
-    debug_assert!(current_thread_index().is_some());
-    let serialized_vote = wincode::serialize(&vote_payload_to_sign).unwrap();
-    let prepared_hash_msg = PreparedHashedMessage::new(&serialized_vote);
-    // converting aggregate pubkey to `PopVerified` is safe here
-    // since the pubkeys are all PoP verified in the vote account
-    let pubkey =
-        PubkeyProjective::par_aggregate(votes.into_par_iter().map(|v| &v.sender_bls_pubkey))
-            .map(|agg| unsafe { PopVerified::new_unchecked(*agg) });
-    (prepared_hash_msg, pubkey)
+    // Fallback - the invalid votes were isolated by bisecting the batch
+    stats.optimistic_verification_failed += 1;
+    stats.fn_verify_votes_bisection_stats.add_sample(time_us);
+    stats.num_fallback_verified += verified_votes.len() as u64;
+    for (sender_identity_pubkey, error) in invalid_remote_pubkeys {
+        stats.banning_validator += 1;
+        ban_sender.ban(sender_identity_pubkey, BAN_TIMEOUT);
+        info!(
+            "bls_vote_sigverify: banned sender={sender_identity_pubkey} due to failed \
+             verification {error:?}"
+        );
+    }
+    verified_votes
+        .into_iter()
+        .map(|v| v.into_verified(max_validators))
+        .collect()
 }
 
-/// Verifies votes individually on a thread pool.
+/// Verifies `unverified_votes`, which all sign `prepared_hash_msg`.
+///
+/// The plain aggregate of the batch is checked first, as it can be forwarded
+/// as is. When it fails, the invalid votes are isolated by bisecting the batch
+/// with random linear combinations, see [`verify_batch_optimistic`].
 ///
 /// Returns:
-/// - `Vec<VotePayload>`: votes that passed verification.
-/// - `Vec<Pubkey>`: senders' identity pubkeys for votes that failed verification.
+/// - `Vec<UnverifiedVotePayload>`: votes that passed verification.
+/// - `Vec<(Pubkey, BlsError)>`: senders' identity pubkeys for votes that failed verification.
+/// - `Option<SignatureProjective>`: the aggregate signature, if the optimistic check passed.
+/// - `BatchVerifyStats`: the verification work that was needed.
 #[cfg_attr(feature = "dev-context-only-utils", qualifiers(pub))]
-fn verify_individual_votes(
-    max_validators: usize,
+fn verify_votes_batch(
     unverified_votes: Vec<UnverifiedVotePayload>,
-    prepared_hash_msg: PreparedHashedMessage,
+    prepared_hash_msg: &PreparedHashedMessage,
     thread_pool: &ThreadPool,
-) -> (Vec<VerifiedVotePayload>, Vec<(Pubkey, BlsError)>) {
-    thread_pool.install(|| {
-        unverified_votes
-            .into_par_iter()
-            .partition_map(|unverified_vote| {
-                let sender_identity_pubkey = unverified_vote.sender_identity_pubkey;
-                match unverified_vote.verify(max_validators, &prepared_hash_msg) {
-                    Ok(vote) => Either::Left(vote),
-                    Err(e) => Either::Right((sender_identity_pubkey, e)),
+) -> (
+    Vec<UnverifiedVotePayload>,
+    Vec<(Pubkey, BlsError)>,
+    Option<SignatureProjective>,
+    BatchVerifyStats,
+) {
+    let entries = unverified_votes
+        .iter()
+        .map(|v| (&v.sender_bls_pubkey, &v.vote_message.signature))
+        .collect::<Vec<BatchEntry>>();
+    let (results, batch_stats) =
+        match verify_batch_optimistic(&entries, prepared_hash_msg, thread_pool) {
+            (OptimisticBatchOutcome::Aggregate(signature), batch_stats) => {
+                let mut aggregate_signature = SignatureProjective::identity();
+                if aggregate_signature
+                    .aggregate_with(std::iter::once(&signature))
+                    .is_ok()
+                {
+                    return (
+                        unverified_votes,
+                        vec![],
+                        Some(aggregate_signature),
+                        batch_stats,
+                    );
                 }
-            })
-    })
+                // Not expected, as the aggregate was encoded from a point that
+                // just verified, but the invalid votes can still be isolated
+                isolate_invalid_signatures(&entries, prepared_hash_msg, thread_pool)
+            }
+            (OptimisticBatchOutcome::Isolated(results), batch_stats) => (results, batch_stats),
+        };
+
+    let mut verified_votes = Vec::with_capacity(unverified_votes.len());
+    let mut invalid_remote_pubkeys = vec![];
+    for (unverified_vote, result) in unverified_votes.into_iter().zip(results) {
+        match result {
+            Ok(()) => verified_votes.push(unverified_vote),
+            Err(e) => invalid_remote_pubkeys.push((unverified_vote.sender_identity_pubkey, e)),
+        }
+    }
+    (verified_votes, invalid_remote_pubkeys, None, batch_stats)
 }
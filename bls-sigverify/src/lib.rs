@@ -1,5 +1,6 @@
 #![cfg(feature = "agave-unstable-api")]
 
+pub mod batch_verify;
 pub mod bls_cert_sigverify;
 pub mod bls_sigverifier;
 pub mod bls_vote_sigverify;
@@ -69,6 +69,8 @@ pub(super) struct SigVerifierStats {
     /// Number of times a vote was too far in the future and discarded.
     pub(super) vote_too_far_in_future: Saturating<u64>,
     pub(super) invalid_vote_banning_validator: Saturating<u64>,
+    /// Number of equivocation proofs written to blockstore.
+    pub(super) equivocation_proofs_stored: Saturating<u64>,
     /// Last time the stats were reported.
     last_report: Reporting,
 }
@@ -90,6 +92,7 @@ impl SigVerifierStats {
             vote_too_far_in_future: Saturating(0),
             verify_and_send_batch_us: WelfordStats::default(),
             invalid_vote_banning_validator: Saturating(0),
+            equivocation_proofs_stored: Saturating(0),
             last_report: Reporting::new(root_slot),
         }
     }
@@ -121,6 +124,7 @@ impl SigVerifierStats {
             verify_and_send_batch_us,
             vote_too_far_in_future,
             invalid_vote_banning_validator,
+            equivocation_proofs_stored,
             last_report: _,
         } = self;
 
@@ -183,6 +187,11 @@ impl SigVerifierStats {
                 invalid_vote_banning_validator.0,
                 i64
             ),
+            (
+                "equivocation_proofs_stored",
+                equivocation_proofs_stored.0,
+                i64
+            ),
             ("num_pkts_max", num_pkts.maximum().unwrap_or(0), i64),
             ("num_pkts_mean", num_pkts.mean().unwrap_or(0), i64),
             ("num_pkts_count", num_pkts.count(), i64),
@@ -307,14 +316,19 @@ pub(super) struct SigVerifyVoteStats {
     /// Number of votes [`verify_and_send_votes`] was requested to verify the signature of.
     pub(super) votes_to_sig_verify: Saturating<u64>,
 
-    /// Number of times optimistic verification succeeded
+    /// Number of times the combined check of a full batch succeeded
     pub(super) optimistic_verification_succeeded: Saturating<u64>,
-    /// Number of times optimistic verification failed
+    /// Number of times the combined check of a full batch failed
     pub(super) optimistic_verification_failed: Saturating<u64>,
     /// Stats on how many votes were in the batch when it succeeded.
     pub(super) optimistic_batch: WelfordStats,
-    /// Number of votes that were individually verified.
-    pub(super) num_individual_verified: Saturating<u64>,
+    /// Number of votes that were verified by the fallback after optimistic verification failed.
+    pub(super) num_fallback_verified: Saturating<u64>,
+    /// Number of random linear combination checks, including the check of each full batch.
+    pub(super) bisection_batch_checks: Saturating<u64>,
+    /// Number of signatures individually verified, while bisecting failed batches or because the
+    /// batch was too small for a combined check.
+    pub(super) bisection_individual_checks: Saturating<u64>,
 
     /// Number of times we are banning a validator.
     pub(super) banning_validator: Saturating<u64>,
@@ -340,11 +354,11 @@ pub(super) struct SigVerifyVoteStats {
 
     /// Stats for [`verify_and_send_votes`].
     pub(super) fn_verify_and_send_votes_stats: WelfordStats,
-    /// Stats for [`verify_votes_optimistic`].
+    /// Stats for [`verify_votes_batch`] on batches without invalid votes.
     pub(super) fn_verify_votes_optimistic_stats: WelfordStats,
 
-    /// Stats for [`verify_individual_votes`].
-    pub(super) fn_verify_individual_votes_stats: WelfordStats,
+    /// Stats for [`verify_votes_batch`] on batches with invalid votes.
+    pub(super) fn_verify_votes_bisection_stats: WelfordStats,
 
     /// Stats for number of distinct votes in batches.
     pub(super) distinct_votes_stats: WelfordStats,
@@ -357,7 +371,9 @@ impl SigVerifyVoteStats {
             optimistic_verification_succeeded,
             optimistic_verification_failed,
             optimistic_batch,
-            num_individual_verified,
+            num_fallback_verified,
+            bisection_batch_checks,
+            bisection_individual_checks,
             banning_validator,
             metrics_sent,
             metrics_channel_full,
@@ -370,14 +386,16 @@ impl SigVerifyVoteStats {
             pool_channel_full,
             fn_verify_and_send_votes_stats,
             fn_verify_votes_optimistic_stats,
-            fn_verify_individual_votes_stats,
+            fn_verify_votes_bisection_stats,
             distinct_votes_stats,
         } = other;
         self.votes_to_sig_verify += votes_to_sig_verify;
         self.optimistic_verification_succeeded += optimistic_verification_succeeded;
         self.optimistic_verification_failed += optimistic_verification_failed;
         self.optimistic_batch.merge(optimistic_batch);
-        self.num_individual_verified += num_individual_verified;
+        self.num_fallback_verified += num_fallback_verified;
+        self.bisection_batch_checks += bisection_batch_checks;
+        self.bisection_individual_checks += bisection_individual_checks;
         self.banning_validator += banning_validator;
         self.metrics_sent += metrics_sent;
         self.metrics_channel_full += metrics_channel_full;
@@ -392,8 +410,8 @@ impl SigVerifyVoteStats {
             .merge(fn_verify_and_send_votes_stats);
         self.fn_verify_votes_optimistic_stats
             .merge(fn_verify_votes_optimistic_stats);
-        self.fn_verify_individual_votes_stats
-            .merge(fn_verify_individual_votes_stats);
+        self.fn_verify_votes_bisection_stats
+            .merge(fn_verify_votes_bisection_stats);
         self.distinct_votes_stats.merge(distinct_votes_stats);
     }
 
@@ -403,7 +421,9 @@ impl SigVerifyVoteStats {
             optimistic_verification_succeeded,
             optimistic_verification_failed,
             optimistic_batch,
-            num_individual_verified,
+            num_fallback_verified,
+            bisection_batch_checks,
+            bisection_individual_checks,
             banning_validator,
             metrics_sent,
             metrics_channel_full,
@@ -416,7 +436,7 @@ impl SigVerifyVoteStats {
             pool_channel_full,
             fn_verify_and_send_votes_stats,
             fn_verify_votes_optimistic_stats,
-            fn_verify_individual_votes_stats,
+            fn_verify_votes_bisection_stats,
             distinct_votes_stats,
         } = self;
         datapoint_info!(
@@ -438,7 +458,13 @@ impl SigVerifyVoteStats {
                 optimistic_batch.mean().unwrap_or(0),
                 i64
             ),
-            ("num_individual_verified", num_individual_verified.0, i64),
+            ("num_fallback_verified", num_fallback_verified.0, i64),
+            ("bisection_batch_checks", bisection_batch_checks.0, i64),
+            (
+                "bisection_individual_checks",
+                bisection_individual_checks.0,
+                i64
+            ),
             ("banning_validator", banning_validator.0, i64),
             ("metrics_sent", metrics_sent.0, i64),
             ("metrics_channel_full", metrics_channel_full.0, i64),
@@ -470,13 +496,13 @@ impl SigVerifyVoteStats {
                 i64
             ),
             (
-                "fn_verify_individual_votes_count",
-                fn_verify_individual_votes_stats.count(),
+                "fn_verify_votes_bisection_count",
+                fn_verify_votes_bisection_stats.count(),
                 i64
             ),
             (
-                "fn_verify_individual_votes_mean",
-                fn_verify_individual_votes_stats.mean().unwrap_or(0),
+                "fn_verify_votes_bisection_mean",
+                fn_verify_votes_bisection_stats.mean().unwrap_or(0),
                 i64
             ),
             ("distinct_votes_count", distinct_votes_stats.count(), i64),
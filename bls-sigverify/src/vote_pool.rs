@@ -15,6 +15,8 @@ const MAX_NOTAR_FALLBACK_ENTRIES: usize = 3;
 pub(crate) enum VotePoolError {
     Invalid,
     Duplicate,
+    /// The vote conflicts with the contained vote previously added by the same validator.
+    Equivocation(UnverifiedVoteMessage),
 }
 
 fn default_bitvec(max_validators: usize) -> BitVec<u8> {
@@ -28,6 +30,8 @@ struct SlotEntry {
     genesis: Vec<Option<Hash>>,
     notar: Vec<Option<Hash>>,
     notar_fallback: Vec<SmallVec<[Hash; MAX_NOTAR_FALLBACK_ENTRIES]>>,
+    /// Votes added so far, indexed by rank, to build equivocation proofs from.
+    signed_votes: HashMap<usize, SmallVec<[UnverifiedVoteMessage; 2]>>,
 }
 
 impl SlotEntry {
@@ -39,6 +43,29 @@ impl SlotEntry {
             genesis: vec![None; max_validators],
             notar: vec![None; max_validators],
             notar_fallback: vec![SmallVec::new(); max_validators],
+            signed_votes: HashMap::new(),
+        }
+    }
+
+    fn add_vote(
+        &mut self,
+        msg: &UnverifiedVoteMessage,
+        rank: usize,
+        max_validators: usize,
+    ) -> Result<(), VotePoolError> {
+        match self.try_add_vote(msg, rank, max_validators) {
+            Ok(()) => {
+                self.signed_votes.entry(rank).or_default().push(msg.clone());
+                Ok(())
+            }
+            Err(VotePoolError::Invalid) => Err(self
+                .signed_votes
+                .get(&rank)
+                .and_then(|votes| votes.iter().find(|v| v.vote.conflicts_with(&msg.vote)))
+                .map_or(VotePoolError::Invalid, |v| {
+                    VotePoolError::Equivocation(v.clone())
+                })),
+            Err(err) => Err(err),
         }
     }
 
@@ -174,7 +201,7 @@ impl VotePool {
             .entries
             .entry(msg.vote.slot())
             .or_insert_with(|| SlotEntry::new(max_validators));
-        slot_entry.try_add_vote(msg, rank, max_validators)
+        slot_entry.add_vote(msg, rank, max_validators)
     }
 
     pub(super) fn prune(&mut self, root_slot: Slot) {
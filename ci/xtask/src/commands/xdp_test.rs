@@ -15,6 +15,7 @@ use {
 
 const DEFAULT_TESTS: &[&str] = &[
     "netlink_snapshot",
+    "receiver_smoke",
     "route_monitor",
     "router_snapshot",
     "transmitter_smoke",
@@ -1,9 +1,10 @@
 use {
     crate::{
         banking_stage::BankingControlMsg, cluster_slots_service::cluster_slots::ClusterSlots,
+        consensus::fork_tree_snapshot::ForkTreeSnapshotRequester,
         repair::repair_service::OutstandingShredRepairs,
     },
-    agave_votor::event::VotorEventSender,
+    agave_votor::{consensus_timeline::ConsensusTimeline, event::VotorEventSender},
     solana_gossip::{cluster_info::ClusterInfo, node::NodeMultihoming},
     solana_ledger::blockstore::Blockstore,
     solana_pubkey::Pubkey,
@@ -90,4 +91,6 @@ pub struct AdminRpcRequestMetadataPostInit {
     pub snapshot_controller: Arc<SnapshotController>,
     pub blockstore: Arc<Blockstore>,
     pub votor_event_sender: VotorEventSender,
+    pub consensus_timeline: Arc<ConsensusTimeline>,
+    pub fork_tree_snapshot_requester: ForkTreeSnapshotRequester,
 }
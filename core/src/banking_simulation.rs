@@ -1,5 +1,8 @@
 #![cfg(feature = "dev-context-only-utils")]
+pub mod report;
+
 use {
+    self::report::{BlockSummary, SimulationReport},
     crate::{
         banking_stage::{
             BankingStage, BankingStageHandle, LikeClusterInfo,
@@ -20,6 +23,8 @@ use {
     itertools::Itertools,
     log::*,
     solana_clock::{DEFAULT_MS_PER_SLOT, HOLD_TRANSACTIONS_SLOT_OFFSET, Slot},
+    solana_cost_model::cost_tracker::CostTrackerLimits,
+    solana_fee_structure::FeeStructure,
     solana_genesis_config::GenesisConfig,
     solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfoQuery, node::Node},
     solana_keypair::Keypair,
@@ -119,6 +124,10 @@ use {
 ///
 /// Warm-up starts at T=-WARMUP_DURATION (~ 13 secs). As soon as warm up is initiated, we invoke
 /// `BankingStage::new_num_threads()` as well to simulate the pre-leader slot's tx-buffering time.
+///
+/// The simulation can be run with a different configuration than the traced leader's (see
+/// `SimulationConfig`), to evaluate how the produced blocks would have changed. Once it is over,
+/// the simulated blocks are compared against the original ones in a `SimulationReport`.
 pub struct BankingSimulator {
     banking_trace_events: BankingTraceEvents,
     first_simulated_slot: Slot,
@@ -133,6 +142,17 @@ pub enum SimulateError {
     DeserializeError(#[from] bincode::Error),
 }
 
+/// What the simulated leader runs with, which may differ from what the traced leader ran with
+#[derive(Clone, Default)]
+pub struct SimulationConfig {
+    pub block_production_method: BlockProductionMethod,
+    pub scheduler_config: SchedulerConfig,
+    /// Overrides the cost limits of the simulated blocks
+    pub cost_limits: Option<CostTrackerLimits>,
+    /// Overrides the fee structure of the simulated blocks
+    pub fee_structure: Option<FeeStructure>,
+}
+
 // Defined to be enough to cover the holding phase prior to leader slots with some idling (+5 secs)
 const WARMUP_DURATION: Duration =
     Duration::from_millis(HOLD_TRANSACTIONS_SLOT_OFFSET * DEFAULT_MS_PER_SLOT + 5000);
@@ -173,13 +193,25 @@ impl BankingTraceEvents {
     }
 
     pub fn load(event_file_paths: &[PathBuf]) -> Result<Self, SimulateError> {
-        let mut event_count = 0;
         let mut events = Self::default();
+        Self::read_event_files(event_file_paths, |event| events.load_event(event))?;
+        Ok(events)
+    }
+
+    /// Reads the events of `event_file_paths` in order, passing each one to `callback` without
+    /// retaining them
+    ///
+    /// A file truncated by an unclean validator shutdown is read up to its last complete event.
+    pub fn read_event_files(
+        event_file_paths: &[PathBuf],
+        mut callback: impl FnMut(TimedTracedEvent),
+    ) -> Result<(), SimulateError> {
+        let mut event_count = 0;
         for event_file_path in event_file_paths {
             let old_event_count = event_count;
             let read_result = Self::read_event_file(event_file_path, |event| {
                 event_count += 1;
-                events.load_event(event);
+                callback(event);
             });
             info!(
                 "Read {} events from {:?}",
@@ -206,7 +238,7 @@ impl BankingTraceEvents {
             }
         }
 
-        Ok(events)
+        Ok(())
     }
 
     fn load_event(&mut self, TimedTracedEvent(event_time, event): TimedTracedEvent) {
@@ -421,6 +453,8 @@ struct SimulatorLoop {
     blockstore: Arc<Blockstore>,
     leader_schedule_cache: Arc<LeaderScheduleCache>,
     retransmit_slots_sender: Sender<Slot>,
+    cost_limits: Option<CostTrackerLimits>,
+    fee_structure: Option<FeeStructure>,
 }
 
 impl SimulatorLoop {
@@ -428,17 +462,19 @@ impl SimulatorLoop {
         self,
         base_simulation_time: SystemTime,
         sender_thread: EventSenderThread,
-    ) -> (EventSenderThread, Sender<Slot>) {
+    ) -> (EventSenderThread, Sender<Slot>, Vec<Slot>) {
         sleep(WARMUP_DURATION);
         info!("warmup done!");
         self.start(base_simulation_time, sender_thread)
     }
 
+    /// Returns the slots of the blocks produced by the simulated leader, along with the threads
+    /// to finish
     fn start(
         mut self,
         base_simulation_time: SystemTime,
         sender_thread: EventSenderThread,
-    ) -> (EventSenderThread, Sender<Slot>) {
+    ) -> (EventSenderThread, Sender<Slot>, Vec<Slot>) {
         let logger = SimulatorLoopLogger {
             simulated_leader: self.simulated_leader,
             base_event_time: self.base_event_time,
@@ -446,6 +482,7 @@ impl SimulatorLoop {
             freeze_time_by_slot: self.freeze_time_by_slot,
         };
         let (mut bank, mut bank_created) = (self.bank, Instant::now());
+        let mut simulated_slots = vec![];
         loop {
             if self.poh_recorder.read().unwrap().bank().is_none() {
                 let next_leader_slot = self.leader_schedule_cache.next_leader_slot(
@@ -466,6 +503,9 @@ impl SimulatorLoop {
                     assert_matches!(result, Ok(()));
                 }
                 bank.freeze();
+                if bank.slot() != self.parent_slot && *bank.leader_id() == self.simulated_leader {
+                    simulated_slots.push(bank.slot());
+                }
                 let new_slot = if bank.slot() == self.parent_slot {
                     info!("initial leader block!");
                     self.first_simulated_slot
@@ -486,8 +526,17 @@ impl SimulatorLoop {
                 } else {
                     info!("new leader bank slot: {new_slot}");
                 }
-                let new_bank =
+                let mut new_bank =
                     Bank::new_from_parent(bank.clone_without_scheduler(), new_leader, new_slot);
+                if let Some(fee_structure) = &self.fee_structure {
+                    new_bank.set_fee_structure(fee_structure);
+                }
+                if let Some(cost_limits) = self.cost_limits {
+                    new_bank
+                        .write_cost_tracker()
+                        .unwrap()
+                        .set_limits(cost_limits);
+                }
                 if *bank.leader_id() == self.simulated_leader {
                     logger.log_frozen_bank_cost(&bank, bank_created.elapsed());
                 }
@@ -518,7 +567,7 @@ impl SimulatorLoop {
             sleep(Duration::from_millis(10));
         }
 
-        (sender_thread, self.retransmit_slots_sender)
+        (sender_thread, self.retransmit_slots_sender, simulated_slots)
     }
 }
 
@@ -688,8 +737,13 @@ impl BankingSimulator {
         genesis_config: GenesisConfig,
         bank_forks: Arc<RwLock<BankForks>>,
         blockstore: Arc<Blockstore>,
-        block_production_method: BlockProductionMethod,
-    ) -> (SenderLoop, SimulatorLoop, SimulatorThreads) {
+        config: SimulationConfig,
+    ) -> (
+        SenderLoop,
+        SimulatorLoop,
+        SimulatorThreads,
+        BTreeMap<Slot, BlockSummary>,
+    ) {
         let parent_slot = self.parent_slot().unwrap();
         let mut packet_batches_by_time = self.banking_trace_events.packet_batches_by_time;
         let freeze_time_by_slot = self.banking_trace_events.freeze_time_by_slot;
@@ -709,12 +763,32 @@ impl BankingSimulator {
 
         let exit = Arc::new(AtomicBool::default());
 
+        let mut original_blocks = BTreeMap::new();
         if let Some(end_slot) = blockstore
             .slot_meta_iterator(self.first_simulated_slot)
             .unwrap()
             .map(|(s, _)| s)
             .last()
         {
+            // Keep what the traced leader produced to compare against before it is purged
+            let original_block_cost_limit = bank.read_cost_tracker().unwrap().get_block_limit();
+            for slot in (self.first_simulated_slot..=end_slot).take_while(|&slot| {
+                leader_schedule_cache
+                    .slot_leader_at(slot, None)
+                    .is_some_and(|leader| leader.id == simulated_leader)
+            }) {
+                match blockstore.get_slot_entries(slot, 0) {
+                    Ok(entries) if !entries.is_empty() => {
+                        original_blocks.insert(
+                            slot,
+                            BlockSummary::new(&bank, entries, original_block_cost_limit),
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(err) => warn!("failed to read the original block of slot {slot}: {err}"),
+                }
+            }
+
             info!("purging slots {}, {}", self.first_simulated_slot, end_slot);
             blockstore
                 .purge_slots_cleanup_chaining(self.first_simulated_slot, end_slot, PurgeType::Exact)
@@ -809,7 +883,7 @@ impl BankingSimulator {
 
         info!("Start banking stage!...");
         let banking_stage = BankingStage::new_num_threads(
-            block_production_method,
+            config.block_production_method,
             poh_recorder.clone(),
             transaction_recorder,
             non_vote_receiver,
@@ -817,7 +891,7 @@ impl BankingSimulator {
             gossip_vote_receiver,
             mpsc::channel(1).1,
             num_workers,
-            SchedulerConfig::default(),
+            config.scheduler_config,
             None,
             replay_vote_sender,
             None,
@@ -874,6 +948,8 @@ impl BankingSimulator {
             blockstore,
             leader_schedule_cache,
             retransmit_slots_sender,
+            cost_limits: config.cost_limits,
+            fee_structure: config.fee_structure,
         };
 
         let simulator_threads = SimulatorThreads {
@@ -883,34 +959,57 @@ impl BankingSimulator {
             exit,
         };
 
-        (sender_loop, simulator_loop, simulator_threads)
+        (
+            sender_loop,
+            simulator_loop,
+            simulator_threads,
+            original_blocks,
+        )
     }
 
+    /// Runs the simulation, returning how the simulated blocks compare to the original ones
     pub fn start(
         self,
         genesis_config: GenesisConfig,
         bank_forks: Arc<RwLock<BankForks>>,
         blockstore: Arc<Blockstore>,
-        block_production_method: BlockProductionMethod,
-    ) -> Result<(), SimulateError> {
-        let (sender_loop, simulator_loop, simulator_threads) = self.prepare_simulation(
-            genesis_config,
-            bank_forks,
-            blockstore,
-            block_production_method,
-        );
+        config: SimulationConfig,
+    ) -> Result<SimulationReport, SimulateError> {
+        let (sender_loop, simulator_loop, simulator_threads, original_blocks) = self
+            .prepare_simulation(
+                genesis_config,
+                bank_forks.clone(),
+                blockstore.clone(),
+                config,
+            );
 
         sender_loop.log_starting();
         let base_simulation_time = SystemTime::now();
         // Spawning and entering these two loops must be done at the same time as they're timed.
         // So, all the mundane setup must be done in advance.
         let sender_thread = sender_loop.spawn(base_simulation_time)?;
-        let (sender_thread, retransmit_slots_sender) =
+        let (sender_thread, retransmit_slots_sender, simulated_slots) =
             simulator_loop.enter(base_simulation_time, sender_thread);
 
         simulator_threads.finish(sender_thread, retransmit_slots_sender);
 
-        Ok(())
+        // The broadcast stage has been joined, so the simulated blocks are all in the blockstore
+        let simulated_blocks = simulated_slots
+            .into_iter()
+            .filter_map(|slot| {
+                let bank = bank_forks.read().unwrap().get(slot)?;
+                let entries = blockstore
+                    .get_slot_entries(slot, 0)
+                    .inspect_err(|err| {
+                        warn!("failed to read the simulated block of slot {slot}: {err}")
+                    })
+                    .ok()?;
+                let block_cost_limit = bank.read_cost_tracker().unwrap().get_block_limit();
+                Some((slot, BlockSummary::new(&bank, entries, block_cost_limit)))
+            })
+            .collect();
+
+        Ok(SimulationReport::new(original_blocks, simulated_blocks))
     }
 
     pub fn event_file_name(index: usize) -> String {
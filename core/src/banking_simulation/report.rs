@@ -0,0 +1,386 @@
+//! Compares the blocks produced by a simulation with the ones produced by the traced leader
+//!
+//! Both sides are summarized from the entries of their blocks in the same way: costs are estimated
+//! by the cost model from the requested compute units, like the scheduler does, and fees are
+//! calculated with the fee structure of the bank the block is summarized against. This keeps the
+//! comparison fair even though the original blocks were never executed by the simulation.
+use {
+    serde::Serialize,
+    solana_clock::Slot,
+    solana_cost_model::cost_model::CostModel,
+    solana_entry::entry::Entry,
+    solana_pubkey::Pubkey,
+    solana_runtime::bank::Bank,
+    solana_runtime_transaction::{
+        runtime_transaction::RuntimeTransaction, transaction_meta::TransactionMeta,
+    },
+    solana_signature::Signature,
+    solana_transaction::{TransactionVerificationMode, sanitized::SanitizedTransaction},
+    solana_transaction_error::TransactionError,
+    std::{
+        cmp::Reverse,
+        collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    },
+};
+
+/// Number of accounts listed in the contention part of a report
+const NUM_REPORTED_ACCOUNTS: usize = 10;
+
+/// How much a block's transactions competed for a writable account
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountContention {
+    pub num_transactions: u64,
+    pub cost: u64,
+}
+
+impl AccountContention {
+    fn accumulate(&mut self, other: &Self) {
+        self.num_transactions = self.num_transactions.saturating_add(other.num_transactions);
+        self.cost = self.cost.saturating_add(other.cost);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockStats {
+    pub num_transactions: u64,
+    pub num_vote_transactions: u64,
+    /// Transactions that could not be sanitized against the bank, which are left out of the
+    /// fees and costs
+    pub num_unresolved_transactions: u64,
+    pub transaction_fees: u64,
+    pub priority_fees: u64,
+    pub cost: u64,
+    pub block_cost_limit: u64,
+}
+
+impl BlockStats {
+    pub fn total_fees(&self) -> u64 {
+        self.transaction_fees.saturating_add(self.priority_fees)
+    }
+
+    /// Returns the share of the block cost limit used, in percent
+    pub fn cost_utilization(&self) -> f64 {
+        if self.block_cost_limit == 0 {
+            0.0
+        } else {
+            self.cost as f64 * 100.0 / self.block_cost_limit as f64
+        }
+    }
+
+    fn accumulate(&mut self, other: &Self) {
+        self.num_transactions = self.num_transactions.saturating_add(other.num_transactions);
+        self.num_vote_transactions = self
+            .num_vote_transactions
+            .saturating_add(other.num_vote_transactions);
+        self.num_unresolved_transactions = self
+            .num_unresolved_transactions
+            .saturating_add(other.num_unresolved_transactions);
+        self.transaction_fees = self.transaction_fees.saturating_add(other.transaction_fees);
+        self.priority_fees = self.priority_fees.saturating_add(other.priority_fees);
+        self.cost = self.cost.saturating_add(other.cost);
+        self.block_cost_limit = self.block_cost_limit.saturating_add(other.block_cost_limit);
+    }
+}
+
+/// What went into a block
+#[derive(Debug, Default)]
+pub struct BlockSummary {
+    stats: BlockStats,
+    signatures: HashSet<Signature>,
+    contention: HashMap<Pubkey, AccountContention>,
+}
+
+impl BlockSummary {
+    /// Summarizes the block made of `entries`, resolving its transactions against `bank`
+    pub fn new(bank: &Bank, entries: Vec<Entry>, block_cost_limit: u64) -> Self {
+        let mut summary = Self {
+            stats: BlockStats {
+                block_cost_limit,
+                ..BlockStats::default()
+            },
+            ..Self::default()
+        };
+        for transaction in entries.into_iter().flat_map(|entry| entry.transactions) {
+            summary.stats.num_transactions += 1;
+            if let Some(signature) = transaction.signatures.first() {
+                summary.signatures.insert(*signature);
+            }
+            let result = bank
+                .verify_transaction(transaction, TransactionVerificationMode::HashOnly)
+                .and_then(|transaction| summary.add_transaction(bank, &transaction));
+            if result.is_err() {
+                summary.stats.num_unresolved_transactions += 1;
+            }
+        }
+        summary
+    }
+
+    pub fn stats(&self) -> &BlockStats {
+        &self.stats
+    }
+
+    fn add_transaction(
+        &mut self,
+        bank: &Bank,
+        transaction: &RuntimeTransaction<SanitizedTransaction>,
+    ) -> Result<(), TransactionError> {
+        let configuration = transaction.transaction_configuration(&bank.feature_set)?;
+        let cost = CostModel::calculate_cost_for_executed_transaction(
+            transaction,
+            u64::from(configuration.compute_unit_limit),
+            configuration.loaded_accounts_data_size_limit,
+            &bank.feature_set,
+        );
+        let fee_details = solana_fee::calculate_fee_details(
+            transaction,
+            bank.fee_structure().lamports_per_signature,
+            configuration.priority_fee_lamports,
+            bank.fee_features(),
+        );
+
+        let stats = &mut self.stats;
+        if transaction.is_simple_vote_transaction() {
+            stats.num_vote_transactions += 1;
+        }
+        stats.transaction_fees = stats
+            .transaction_fees
+            .saturating_add(fee_details.transaction_fee());
+        stats.priority_fees = stats
+            .priority_fees
+            .saturating_add(fee_details.prioritization_fee());
+        stats.cost = stats.cost.saturating_add(cost.sum());
+        for account in cost.writable_accounts() {
+            self.contention
+                .entry(*account)
+                .or_default()
+                .accumulate(&AccountContention {
+                    num_transactions: 1,
+                    cost: cost.sum(),
+                });
+        }
+        Ok(())
+    }
+}
+
+/// One slot of the simulation
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotComparison {
+    pub slot: Slot,
+    /// None if the traced leader has no block for the slot
+    pub original: Option<BlockStats>,
+    /// None if the simulation did not produce a block for the slot
+    pub simulated: Option<BlockStats>,
+    /// Number of transactions included in both blocks
+    pub num_common_transactions: u64,
+}
+
+/// A writable account among the most contended ones of either side
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountComparison {
+    pub pubkey: String,
+    pub original: AccountContention,
+    pub simulated: AccountContention,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationReport {
+    pub slots: Vec<SlotComparison>,
+    /// Totals over the original blocks
+    pub original: BlockStats,
+    /// Totals over the simulated blocks
+    pub simulated: BlockStats,
+    pub num_common_transactions: u64,
+    /// The writable accounts with the highest cost on either side, most contended first
+    pub most_contended_accounts: Vec<AccountComparison>,
+}
+
+impl SimulationReport {
+    pub fn new(
+        original_blocks: BTreeMap<Slot, BlockSummary>,
+        simulated_blocks: BTreeMap<Slot, BlockSummary>,
+    ) -> Self {
+        let slots: BTreeSet<Slot> = original_blocks
+            .keys()
+            .chain(simulated_blocks.keys())
+            .copied()
+            .collect();
+        let mut report = Self {
+            slots: Vec::with_capacity(slots.len()),
+            original: BlockStats::default(),
+            simulated: BlockStats::default(),
+            num_common_transactions: 0,
+            most_contended_accounts: vec![],
+        };
+
+        for slot in slots {
+            let original = original_blocks.get(&slot);
+            let simulated = simulated_blocks.get(&slot);
+            let num_common_transactions = match (original, simulated) {
+                (Some(original), Some(simulated)) => original
+                    .signatures
+                    .intersection(&simulated.signatures)
+                    .count() as u64,
+                _ => 0,
+            };
+            report.slots.push(SlotComparison {
+                slot,
+                original: original.map(|block| block.stats),
+                simulated: simulated.map(|block| block.stats),
+                num_common_transactions,
+            });
+        }
+
+        // Transactions may have landed in a different slot of the simulation than originally
+        let mut contention = HashMap::<Pubkey, (AccountContention, AccountContention)>::new();
+        let mut original_signatures = HashSet::new();
+        for block in original_blocks.values() {
+            report.original.accumulate(&block.stats);
+            original_signatures.extend(&block.signatures);
+            for (pubkey, account_contention) in &block.contention {
+                contention
+                    .entry(*pubkey)
+                    .or_default()
+                    .0
+                    .accumulate(account_contention);
+            }
+        }
+        let mut simulated_signatures = HashSet::new();
+        for block in simulated_blocks.values() {
+            report.simulated.accumulate(&block.stats);
+            simulated_signatures.extend(&block.signatures);
+            for (pubkey, account_contention) in &block.contention {
+                contention
+                    .entry(*pubkey)
+                    .or_default()
+                    .1
+                    .accumulate(account_contention);
+            }
+        }
+        report.num_common_transactions = original_signatures
+            .intersection(&simulated_signatures)
+            .count() as u64;
+
+        let mut contention: Vec<_> = contention.into_iter().collect();
+        contention.sort_unstable_by_key(|(pubkey, (original, simulated))| {
+            (Reverse(original.cost.max(simulated.cost)), *pubkey)
+        });
+        report.most_contended_accounts = contention
+            .into_iter()
+            .take(NUM_REPORTED_ACCOUNTS)
+            .map(|(pubkey, (original, simulated))| AccountComparison {
+                pubkey: pubkey.to_string(),
+                original,
+                simulated,
+            })
+            .collect();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_hash::Hash,
+        solana_keypair::Keypair,
+        solana_ledger::genesis_utils::{GenesisConfigInfo, create_genesis_config},
+        solana_signer::Signer,
+        solana_transaction::versioned::VersionedTransaction,
+    };
+
+    #[test]
+    fn test_simulation_report() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(1_000_000_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let block_cost_limit = bank.read_cost_tracker().unwrap().get_block_limit();
+
+        let recipients: Vec<_> = (0..3).map(|_| Keypair::new().pubkey()).collect();
+        let transfers: Vec<_> = recipients
+            .iter()
+            .map(|recipient| {
+                VersionedTransaction::from(solana_system_transaction::transfer(
+                    &mint_keypair,
+                    recipient,
+                    1,
+                    bank.last_blockhash(),
+                ))
+            })
+            .collect();
+        let entries = |transactions: &[VersionedTransaction]| {
+            vec![Entry {
+                num_hashes: 1,
+                hash: Hash::new_unique(),
+                transactions: transactions.to_vec(),
+            }]
+        };
+
+        // The simulation included the last transfer instead of the first one, and produced an
+        // additional block
+        let original_blocks = BTreeMap::from([(
+            10,
+            BlockSummary::new(&bank, entries(&transfers[..2]), block_cost_limit),
+        )]);
+        let simulated_blocks = BTreeMap::from([
+            (
+                10,
+                BlockSummary::new(&bank, entries(&transfers[1..]), block_cost_limit),
+            ),
+            (11, BlockSummary::new(&bank, entries(&[]), block_cost_limit)),
+        ]);
+        let original_stats = *original_blocks[&10].stats();
+        assert_eq!(original_stats.num_transactions, 2);
+        assert_eq!(original_stats.num_unresolved_transactions, 0);
+        assert_eq!(
+            original_stats.transaction_fees,
+            2 * bank.fee_structure().lamports_per_signature
+        );
+        assert!(original_stats.cost > 0);
+
+        let report = SimulationReport::new(original_blocks, simulated_blocks);
+        assert_eq!(
+            report.slots,
+            vec![
+                SlotComparison {
+                    slot: 10,
+                    original: Some(original_stats),
+                    simulated: Some(original_stats),
+                    num_common_transactions: 1,
+                },
+                SlotComparison {
+                    slot: 11,
+                    original: None,
+                    simulated: Some(BlockStats {
+                        block_cost_limit,
+                        ..BlockStats::default()
+                    }),
+                    num_common_transactions: 0,
+                },
+            ]
+        );
+        assert_eq!(report.num_common_transactions, 1);
+        assert_eq!(report.original.block_cost_limit, block_cost_limit);
+        assert_eq!(report.simulated.block_cost_limit, 2 * block_cost_limit);
+
+        // The payer is written by every transfer, and is thus the most contended account
+        let payer = &report.most_contended_accounts[0];
+        assert_eq!(payer.pubkey, mint_keypair.pubkey().to_string());
+        assert_eq!(payer.original.num_transactions, 2);
+        assert_eq!(payer.simulated.num_transactions, 2);
+        let first_recipient = report
+            .most_contended_accounts
+            .iter()
+            .find(|account| account.pubkey == recipients[0].to_string())
+            .unwrap();
+        assert_eq!(first_recipient.original.num_transactions, 1);
+        assert_eq!(first_recipient.simulated, AccountContention::default());
+    }
+}
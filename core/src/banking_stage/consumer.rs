@@ -273,6 +273,7 @@ impl Consumer {
                 &mut error_counters,
                 TransactionProcessingConfig {
                     account_overrides: None,
+                    sysvar_cache_override: None,
                     check_program_deployment_slot: bank.check_program_deployment_slot(),
                     log_messages_bytes_limit: self.log_messages_bytes_limit,
                     limit_to_load_programs: true,
@@ -283,6 +284,7 @@ impl Consumer {
                     all_or_nothing: flags.all_or_nothing,
                     strict_nonce_size_check: true,
                     drop_noop_transactions: true,
+                    enable_execution_tracing: false,
                 }
             ));
         execute_and_commit_timings.load_execute_us = load_execute_us;
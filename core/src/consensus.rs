@@ -1,4 +1,5 @@
 pub mod fork_choice;
+pub mod fork_tree_snapshot;
 pub mod heaviest_subtree_fork_choice;
 pub(crate) mod latest_validator_votes_for_frozen_banks;
 pub mod progress_map;
@@ -20,6 +21,7 @@ use {
         tower1_14_11::Tower1_14_11,
     },
     crate::{consensus::progress_map::LockoutInterval, replay_stage::DUPLICATE_THRESHOLD},
+    agave_votor::remote_vote_storage::RemoteVoteStorageError,
     agave_votor_messages::{fraction::Fraction, migration::GENESIS_VOTE_THRESHOLD},
     chrono::prelude::*,
     solana_clock::{Slot, UnixTimestamp},
@@ -1717,6 +1719,9 @@ pub enum TowerError {
 
     #[error("The tower is useless because of new hard fork: {0}")]
     HardFork(Slot),
+
+    #[error("Remote vote storage error: {0}")]
+    RemoteStorage(#[from] RemoteVoteStorageError),
 }
 
 impl TowerError {
@@ -0,0 +1,385 @@
+//! A point-in-time copy of the fork tree tracked by replay.
+//!
+//! Replay owns fork choice and the progress map, so other threads cannot read
+//! them directly. Instead they send a request through a
+//! [`ForkTreeSnapshotRequester`], which replay answers between iterations of
+//! its main loop.
+
+use {
+    crate::consensus::{
+        Tower,
+        heaviest_subtree_fork_choice::HeaviestSubtreeForkChoice,
+        progress_map::{DeadSlotReason, ProgressMap},
+    },
+    crossbeam_channel::{Receiver, RecvTimeoutError, Sender, TrySendError, bounded},
+    log::warn,
+    solana_clock::Slot,
+    solana_hash::Hash,
+    solana_pubkey::Pubkey,
+    solana_runtime::bank_forks::BankForks,
+    std::{sync::RwLock, time::Duration},
+    thiserror::Error,
+};
+
+/// Number of snapshot requests that may be queued before new ones are refused.
+const CHANNEL_SIZE: usize = 4;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ForkTreeSnapshotError {
+    #[error("replay stage is not running")]
+    Disconnected,
+    #[error("too many fork tree snapshot requests are pending")]
+    Busy,
+    #[error("replay stage did not respond within {0:?}")]
+    Timeout(Duration),
+}
+
+/// One of this validator's own votes, as recorded in its tower.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TowerLockoutSnapshot {
+    pub slot: Slot,
+    pub confirmation_count: u32,
+    /// The last slot this vote locks out.
+    pub last_locked_out_slot: Slot,
+}
+
+/// A bank in the fork tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForkTreeNode {
+    pub slot: Slot,
+    /// `None` for the root, whose parent has been pruned.
+    pub parent_slot: Option<Slot>,
+    /// `None` until the bank is frozen.
+    pub bank_hash: Option<Hash>,
+    pub block_id: Option<Hash>,
+    pub leader: Pubkey,
+    pub is_frozen: bool,
+    pub is_dead: bool,
+    /// Whether the death is permanent, as opposed to a replay failure that an
+    /// `UpdateParent` marker could still make obsolete.
+    pub is_hard_dead: bool,
+    /// Stake of the validators whose latest vote is for exactly this bank.
+    pub stake_voted_at: Option<u64>,
+    /// Stake of the validators whose latest vote is for this bank or a
+    /// descendant.
+    pub stake_voted_subtree: Option<u64>,
+    /// Number of validators whose latest vote is for exactly this bank.
+    pub vote_count: usize,
+    pub is_duplicate_confirmed: bool,
+    /// Whether this bank was marked as a duplicate that has not been confirmed.
+    pub is_unconfirmed_duplicate: bool,
+    /// The latest ancestor, possibly this bank itself, excluded from fork
+    /// choice for being an unconfirmed duplicate.
+    pub latest_invalid_ancestor: Option<Slot>,
+    /// Whether this bank's fork is locked out by this validator's tower.
+    pub is_locked_out: bool,
+    /// Whether the vote stake threshold checks passed for this bank.
+    pub passed_vote_threshold: bool,
+    pub has_voted: bool,
+    /// `None` if this is not one of this validator's leader slots.
+    pub is_propagated: Option<bool>,
+    /// Stake observed voting for this bank or its descendants.
+    pub fork_stake: u64,
+    pub total_stake: u64,
+}
+
+/// The fork tree rooted at the current root bank.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ForkTreeSnapshot {
+    pub root: Slot,
+    /// The heaviest bank according to fork choice.
+    pub heaviest_slot: Option<Slot>,
+    pub last_voted_slot: Option<Slot>,
+    pub tower: Vec<TowerLockoutSnapshot>,
+    /// Nodes sorted by slot.
+    pub nodes: Vec<ForkTreeNode>,
+}
+
+impl ForkTreeSnapshot {
+    pub fn new(
+        bank_forks: &BankForks,
+        progress: &ProgressMap,
+        fork_choice: &HeaviestSubtreeForkChoice,
+        tower: &Tower,
+    ) -> Self {
+        let root = bank_forks.root();
+        let vote_counts = fork_choice.latest_vote_counts();
+        let mut nodes = bank_forks
+            .banks()
+            .values()
+            .map(|bank| {
+                let slot = bank.slot();
+                let is_frozen = bank.is_frozen();
+                let bank_hash = is_frozen.then(|| bank.hash());
+                let key = bank_hash.map(|hash| (slot, hash));
+                let fork_progress = progress.get(&slot);
+                let fork_stats = fork_progress.map(|progress| &progress.fork_stats);
+                let dead_reason = fork_progress.and_then(|progress| progress.dead_reason.as_ref());
+                ForkTreeNode {
+                    slot,
+                    parent_slot: (slot != root).then(|| bank.parent_slot()),
+                    bank_hash,
+                    block_id: bank.block_id(),
+                    leader: *bank.leader_id(),
+                    is_frozen,
+                    is_dead: dead_reason.is_some(),
+                    is_hard_dead: dead_reason == Some(&DeadSlotReason::Hard),
+                    stake_voted_at: key.and_then(|key| fork_choice.stake_voted_at(&key)),
+                    stake_voted_subtree: key.and_then(|key| fork_choice.stake_voted_subtree(&key)),
+                    vote_count: key
+                        .and_then(|key| vote_counts.get(&key).copied())
+                        .unwrap_or_default(),
+                    is_duplicate_confirmed: key
+                        .and_then(|key| fork_choice.is_duplicate_confirmed(&key))
+                        .or_else(|| progress.is_duplicate_confirmed(slot))
+                        .unwrap_or(false),
+                    is_unconfirmed_duplicate: key
+                        .and_then(|key| fork_choice.is_unconfirmed_duplicate(&key))
+                        .unwrap_or(false),
+                    latest_invalid_ancestor: key
+                        .and_then(|key| fork_choice.latest_invalid_ancestor(&key)),
+                    is_locked_out: fork_stats.is_some_and(|stats| stats.is_locked_out),
+                    passed_vote_threshold: fork_stats.is_some_and(|stats| {
+                        stats.computed
+                            && stats
+                                .vote_threshold
+                                .iter()
+                                .all(|decision| decision.passed())
+                    }),
+                    has_voted: fork_stats.is_some_and(|stats| stats.has_voted),
+                    is_propagated: fork_progress
+                        .filter(|progress| progress.propagated_stats.is_leader_slot)
+                        .map(|progress| progress.propagated_stats.is_propagated),
+                    fork_stake: fork_stats.map_or(0, |stats| stats.fork_stake),
+                    total_stake: fork_stats.map_or(0, |stats| stats.total_stake),
+                }
+            })
+            .collect::<Vec<_>>();
+        nodes.sort_unstable_by_key(|node| node.slot);
+
+        let heaviest_slot = (!fork_choice.is_empty()).then(|| fork_choice.best_overall_slot().0);
+        let tower_lockouts = tower
+            .vote_state
+            .votes
+            .iter()
+            .map(|lockout| TowerLockoutSnapshot {
+                slot: lockout.slot(),
+                confirmation_count: lockout.confirmation_count(),
+                last_locked_out_slot: lockout.last_locked_out_slot(),
+            })
+            .collect();
+
+        Self {
+            root,
+            heaviest_slot,
+            last_voted_slot: tower.last_voted_slot(),
+            tower: tower_lockouts,
+            nodes,
+        }
+    }
+}
+
+pub type ForkTreeSnapshotRequestReceiver = Receiver<Sender<ForkTreeSnapshot>>;
+
+/// Handle used by non-replay threads to request a [`ForkTreeSnapshot`].
+#[derive(Clone)]
+pub struct ForkTreeSnapshotRequester {
+    sender: Sender<Sender<ForkTreeSnapshot>>,
+}
+
+impl ForkTreeSnapshotRequester {
+    pub fn new() -> (Self, ForkTreeSnapshotRequestReceiver) {
+        let (sender, receiver) = bounded(CHANNEL_SIZE);
+        (Self { sender }, receiver)
+    }
+
+    /// Asks replay for a snapshot and waits up to `timeout` for it.
+    pub fn request(&self, timeout: Duration) -> Result<ForkTreeSnapshot, ForkTreeSnapshotError> {
+        let (response_sender, response_receiver) = bounded(1);
+        self.sender
+            .try_send(response_sender)
+            .map_err(|err| match err {
+                TrySendError::Full(_) => ForkTreeSnapshotError::Busy,
+                TrySendError::Disconnected(_) => ForkTreeSnapshotError::Disconnected,
+            })?;
+        response_receiver
+            .recv_timeout(timeout)
+            .map_err(|err| match err {
+                RecvTimeoutError::Timeout => ForkTreeSnapshotError::Timeout(timeout),
+                RecvTimeoutError::Disconnected => ForkTreeSnapshotError::Disconnected,
+            })
+    }
+}
+
+/// Answers all pending snapshot requests with a single snapshot.
+pub(crate) fn process_fork_tree_snapshot_requests(
+    receiver: &ForkTreeSnapshotRequestReceiver,
+    bank_forks: &RwLock<BankForks>,
+    progress: &ProgressMap,
+    fork_choice: &HeaviestSubtreeForkChoice,
+    tower: &Tower,
+) {
+    let response_senders = receiver.try_iter().collect::<Vec<_>>();
+    if response_senders.is_empty() {
+        return;
+    }
+    let snapshot = ForkTreeSnapshot::new(&bank_forks.read().unwrap(), progress, fork_choice, tower);
+    for response_sender in response_senders {
+        if response_sender.send(snapshot.clone()).is_err() {
+            warn!("fork tree snapshot requester dropped before receiving the response");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::vote_simulator::VoteSimulator,
+        std::{collections::HashMap, thread},
+        trees::tr,
+    };
+
+    fn setup_forks() -> VoteSimulator {
+        // Create the tree of banks
+        //       0
+        //     /   \
+        //    1     2
+        //    |
+        //    3
+        let mut vote_simulator = VoteSimulator::new(3);
+        vote_simulator.fill_bank_forks(tr(0) / (tr(1) / tr(3)) / tr(2), &HashMap::new(), true);
+        vote_simulator
+    }
+
+    #[test]
+    fn test_fork_tree_snapshot() {
+        let mut vote_simulator = setup_forks();
+        vote_simulator
+            .progress
+            .get_mut(&2)
+            .unwrap()
+            .mark_dead(DeadSlotReason::Hard);
+        let bank_forks = vote_simulator.bank_forks.read().unwrap();
+        let bank2 = bank_forks.get(2).unwrap();
+        let bank3 = bank_forks.get(3).unwrap();
+        let fork_choice = &vote_simulator.tbft_structs.heaviest_subtree_fork_choice;
+
+        let mut tower = Tower::default();
+        tower.record_vote(1, bank_forks.bank_hash(1).unwrap());
+        tower.record_vote(3, bank3.hash());
+
+        let snapshot =
+            ForkTreeSnapshot::new(&bank_forks, &vote_simulator.progress, fork_choice, &tower);
+
+        assert_eq!(snapshot.root, 0);
+        assert_eq!(snapshot.last_voted_slot, Some(3));
+        assert_eq!(
+            snapshot
+                .tower
+                .iter()
+                .map(|lockout| (lockout.slot, lockout.confirmation_count))
+                .collect::<Vec<_>>(),
+            vec![(1, 2), (3, 1)]
+        );
+        assert_eq!(
+            snapshot
+                .nodes
+                .iter()
+                .map(|node| (node.slot, node.parent_slot))
+                .collect::<Vec<_>>(),
+            vec![(0, None), (1, Some(0)), (2, Some(0)), (3, Some(1))]
+        );
+
+        let node2 = &snapshot.nodes[2];
+        assert!(node2.is_dead);
+        assert!(node2.is_hard_dead);
+        assert_eq!(node2.bank_hash, Some(bank2.hash()));
+
+        let node3 = &snapshot.nodes[3];
+        assert!(!node3.is_dead);
+        assert!(node3.is_frozen);
+        assert_eq!(node3.bank_hash, Some(bank3.hash()));
+        assert_eq!(node3.block_id, bank3.block_id());
+        assert_eq!(node3.stake_voted_at, Some(0));
+        assert_eq!(node3.vote_count, 0);
+    }
+
+    #[test]
+    fn test_fork_tree_snapshot_votes() {
+        let mut vote_simulator = setup_forks();
+        let bank_forks = vote_simulator.bank_forks.clone();
+        let bank_forks = bank_forks.read().unwrap();
+        let bank2 = bank_forks.get(2).unwrap();
+        let bank3 = bank_forks.get(3).unwrap();
+        let votes = [
+            (vote_simulator.vote_pubkeys[0], (2, bank2.hash())),
+            (vote_simulator.vote_pubkeys[1], (3, bank3.hash())),
+            (vote_simulator.vote_pubkeys[2], (3, bank3.hash())),
+        ];
+        vote_simulator
+            .tbft_structs
+            .heaviest_subtree_fork_choice
+            .add_votes(
+                votes.iter(),
+                bank3.epoch_stakes_map(),
+                bank3.epoch_schedule(),
+            );
+        let fork_choice = &vote_simulator.tbft_structs.heaviest_subtree_fork_choice;
+
+        let snapshot = ForkTreeSnapshot::new(
+            &bank_forks,
+            &vote_simulator.progress,
+            fork_choice,
+            &Tower::default(),
+        );
+
+        assert_eq!(snapshot.heaviest_slot, Some(3));
+        let node1 = &snapshot.nodes[1];
+        let node2 = &snapshot.nodes[2];
+        let node3 = &snapshot.nodes[3];
+        assert_eq!(node2.vote_count, 1);
+        assert_eq!(node3.vote_count, 2);
+        assert_eq!(node1.vote_count, 0);
+        assert_eq!(
+            node3.stake_voted_at,
+            fork_choice.stake_voted_at(&(3, bank3.hash()))
+        );
+        assert_eq!(node1.stake_voted_subtree, node3.stake_voted_subtree);
+        assert!(node3.stake_voted_at.unwrap() > node2.stake_voted_at.unwrap());
+    }
+
+    #[test]
+    fn test_fork_tree_snapshot_requester() {
+        let vote_simulator = setup_forks();
+        let fork_choice = &vote_simulator.tbft_structs.heaviest_subtree_fork_choice;
+        let tower = Tower::default();
+        let (requester, receiver) = ForkTreeSnapshotRequester::new();
+
+        thread::scope(|scope| {
+            let handle = scope.spawn(|| requester.request(Duration::from_secs(10)));
+            while receiver.is_empty() {
+                thread::yield_now();
+            }
+            process_fork_tree_snapshot_requests(
+                &receiver,
+                &vote_simulator.bank_forks,
+                &vote_simulator.progress,
+                fork_choice,
+                &tower,
+            );
+            let snapshot = handle.join().unwrap().unwrap();
+            assert_eq!(snapshot.nodes.len(), 4);
+        });
+
+        assert_eq!(
+            requester.request(Duration::from_millis(10)),
+            Err(ForkTreeSnapshotError::Timeout(Duration::from_millis(10)))
+        );
+        drop(receiver);
+        assert_eq!(
+            requester.request(Duration::from_millis(10)),
+            Err(ForkTreeSnapshotError::Disconnected)
+        );
+    }
+}
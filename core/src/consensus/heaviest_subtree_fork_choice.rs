@@ -570,6 +570,15 @@ impl HeaviestSubtreeForkChoice {
             .map(|(slot_hash, fork_info)| (slot_hash, fork_info.stake_voted_subtree))
     }
 
+    /// Returns the number of validators whose latest vote is for each block
+    pub fn latest_vote_counts(&self) -> HashMap<SlotHashKey, usize> {
+        let mut vote_counts = HashMap::new();
+        for slot_hash_key in self.latest_votes.values() {
+            *vote_counts.entry(*slot_hash_key).or_default() += 1;
+        }
+        vote_counts
+    }
+
     pub fn slots_iter(&self) -> impl Iterator<Item = Slot> + '_ {
         self.fork_infos.iter().map(|((slot, _), _)| slot).copied()
     }
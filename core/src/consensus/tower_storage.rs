@@ -3,6 +3,7 @@ use {
         Result, Tower, TowerError, TowerVersions, tower1_7_14::SavedTower1_7_14,
         tower1_14_11::Tower1_14_11,
     },
+    agave_votor::remote_vote_storage::{RecordKey, RecordKind, RemoteVoteStorageClient},
     serde::{Deserialize, Serialize},
     solana_pubkey::Pubkey,
     solana_signature::Signature,
@@ -10,7 +11,8 @@ use {
     std::{
         fs::{self, File},
         io::{self, BufReader},
-        path::PathBuf,
+        path::{Path, PathBuf},
+        sync::Arc,
     },
 };
 
@@ -22,34 +24,45 @@ pub enum SavedTowerVersions {
 }
 
 impl SavedTowerVersions {
-    fn try_into_tower(&self, node_pubkey: &Pubkey) -> Result<Tower> {
+    /// Verifies that the tower was signed by `node_pubkey` and belongs to it,
+    /// and decodes it.
+    pub fn try_into_tower(&self, node_pubkey: &Pubkey) -> Result<Tower> {
         // This method assumes that `self` was just deserialized
         assert_eq!(self.pubkey(), Pubkey::default());
 
+        if !self.verify_signature(node_pubkey) {
+            return Err(TowerError::InvalidSignature);
+        }
+        let tower = self.decode_unverified()?;
+        if tower.node_pubkey != *node_pubkey {
+            return Err(TowerError::WrongTower(format!(
+                "node_pubkey is {:?} but found tower for {:?}",
+                node_pubkey, tower.node_pubkey
+            )));
+        }
+        Ok(tower)
+    }
+
+    /// Whether the saved tower data was signed by `node_pubkey`
+    pub fn verify_signature(&self, node_pubkey: &Pubkey) -> bool {
+        match self {
+            SavedTowerVersions::V1_17_14(t) => t.signature.verify(node_pubkey.as_ref(), &t.data),
+            SavedTowerVersions::Current(t) => t.signature.verify(node_pubkey.as_ref(), &t.data),
+        }
+    }
+
+    /// Decodes the tower without checking who signed it. Only meant for
+    /// offline inspection, use [`Self::try_into_tower`] to load a tower.
+    pub fn decode_unverified(&self) -> Result<Tower> {
         let tv = match self {
             SavedTowerVersions::V1_17_14(t) => {
-                if !t.signature.verify(node_pubkey.as_ref(), &t.data) {
-                    return Err(TowerError::InvalidSignature);
-                }
                 bincode::deserialize(&t.data).map(TowerVersions::V1_7_14)
             }
             SavedTowerVersions::Current(t) => {
-                if !t.signature.verify(node_pubkey.as_ref(), &t.data) {
-                    return Err(TowerError::InvalidSignature);
-                }
                 bincode::deserialize(&t.data).map(TowerVersions::V1_14_11)
             }
-        };
-        tv.map_err(|e| e.into()).and_then(|tv: TowerVersions| {
-            let tower = tv.convert_to_current();
-            if tower.node_pubkey != *node_pubkey {
-                return Err(TowerError::WrongTower(format!(
-                    "node_pubkey is {:?} but found tower for {:?}",
-                    node_pubkey, tower.node_pubkey
-                )));
-            }
-            Ok(tower)
-        })
+        }?;
+        Ok(tv.convert_to_current())
     }
 
     fn serialize_into(&self, file: &mut File) -> Result<()> {
@@ -159,6 +172,22 @@ impl FileTowerStorage {
             .with_extension("bin")
     }
 
+    /// Reads the saved tower at `path` without verifying it, for offline
+    /// inspection
+    pub fn read_saved_tower(path: &Path) -> Result<SavedTowerVersions> {
+        let mut stream = BufReader::new(File::open(path)?);
+        bincode::deserialize_from(&mut stream).map_err(|e| e.into())
+    }
+
+    /// Reads the pre 1.9 saved tower at `path`, see [`Self::old_filename`],
+    /// without verifying it
+    pub fn read_old_saved_tower(path: &Path) -> Result<SavedTowerVersions> {
+        let mut stream = BufReader::new(File::open(path)?);
+        bincode::deserialize_from(&mut stream)
+            .map(|t: SavedTower1_7_14| SavedTowerVersions::from(t))
+            .map_err(|e| e.into())
+    }
+
     #[cfg(test)]
     fn store_old(&self, saved_tower: &SavedTower1_7_14) -> Result<()> {
         let pubkey = saved_tower.node_pubkey;
@@ -223,6 +252,48 @@ impl TowerStorage for FileTowerStorage {
     }
 }
 
+/// Tower storage shared with other validators through a vote storage server,
+/// see [`agave_votor::remote_vote_storage`]
+#[derive(Debug, Clone)]
+pub struct RemoteTowerStorage {
+    client: Arc<RemoteVoteStorageClient>,
+}
+
+impl RemoteTowerStorage {
+    pub fn new(client: Arc<RemoteVoteStorageClient>) -> Self {
+        Self { client }
+    }
+}
+
+impl TowerStorage for RemoteTowerStorage {
+    fn load(&self, node_pubkey: &Pubkey) -> Result<Tower> {
+        let key = RecordKey::new(RecordKind::Tower, *node_pubkey);
+        trace!("load {key} from {}", self.client.server_addr());
+        let Some(data) = self.client.load(key)? else {
+            return Err(TowerError::IoError(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no {key} on {}", self.client.server_addr()),
+            )));
+        };
+        bincode::deserialize(&data)
+            .map_err(|e| e.into())
+            .and_then(|t: SavedTowerVersions| t.try_into_tower(node_pubkey))
+    }
+
+    fn store(&self, saved_tower: &SavedTowerVersions) -> Result<()> {
+        let key = RecordKey::new(RecordKind::Tower, saved_tower.pubkey());
+        trace!("store {key} to {}", self.client.server_addr());
+        let tower = saved_tower.decode_unverified()?;
+        let watermark = tower
+            .last_voted_slot()
+            .unwrap_or_default()
+            .max(tower.root());
+        self.client
+            .store(key, watermark, bincode::serialize(saved_tower)?)?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 pub mod test {
     use {
@@ -231,6 +302,9 @@ pub mod test {
             BlockhashStatus, Tower,
             tower1_7_14::{SavedTower1_7_14, Tower1_7_14},
         },
+        agave_votor::remote_vote_storage::{
+            RemoteVoteStorageError, RemoteVoteStorageServer, SharedSecret,
+        },
         solana_hash::Hash,
         solana_keypair::Keypair,
         solana_vote::vote_transaction::VoteTransaction,
@@ -277,4 +351,106 @@ pub mod test {
         assert_eq!(loaded.vote_state.root_slot, Some(1));
         assert_eq!(loaded.stray_restored_slot(), None);
     }
+
+    #[test]
+    fn test_remote_tower_storage() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let secret = SharedSecret::new(vec![7; 32]).unwrap();
+        let server = RemoteVoteStorageServer::new(listener, None, secret.clone()).unwrap();
+        let connect = || {
+            RemoteTowerStorage::new(Arc::new(RemoteVoteStorageClient::new(
+                server.local_addr(),
+                secret.clone(),
+            )))
+        };
+        let primary = connect();
+        let spare = connect();
+        let identity_keypair = Keypair::new();
+        let node_pubkey = identity_keypair.pubkey();
+
+        assert!(
+            Tower::restore(&primary, &node_pubkey)
+                .unwrap_err()
+                .is_file_missing()
+        );
+        let mut tower = Tower::new_random(node_pubkey);
+        tower.save(&primary, &identity_keypair).unwrap();
+
+        let restored = Tower::restore(&spare, &node_pubkey).unwrap();
+        assert_eq!(restored.tower_slots(), tower.tower_slots());
+        assert_eq!(restored.last_voted_slot(), tower.last_voted_slot());
+        restored.save(&spare, &identity_keypair).unwrap();
+
+        // The primary lost the identity to the spare
+        let next_slot = tower.last_voted_slot().unwrap().saturating_add(1);
+        tower.record_vote(next_slot, Hash::default());
+        assert_matches!(
+            tower.save(&primary, &identity_keypair),
+            Err(TowerError::RemoteStorage(
+                RemoteVoteStorageError::VersionMismatch(_)
+            ))
+        );
+
+        // A tower behind the stored votes is refused even after loading
+        Tower::restore(&primary, &node_pubkey).unwrap();
+        let mut stale = Tower::default();
+        stale.node_pubkey = node_pubkey;
+        assert_matches!(
+            stale.save(&primary, &identity_keypair),
+            Err(TowerError::RemoteStorage(
+                RemoteVoteStorageError::WatermarkRegression(..)
+            ))
+        );
+
+        server.exit();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_read_saved_tower() {
+        let tower_path = TempDir::new().unwrap();
+        let tower_storage = FileTowerStorage::new(tower_path.path().to_path_buf());
+        let identity_keypair = Keypair::new();
+        let node_pubkey = identity_keypair.pubkey();
+        let other_pubkey = Keypair::new().pubkey();
+
+        let old_tower = Tower1_7_14 {
+            node_pubkey,
+            threshold_depth: 10,
+            threshold_size: 0.9,
+            vote_state: VoteState1_14_11::default(),
+            last_vote: Vote::default(),
+            last_timestamp: BlockTimestamp::default(),
+            last_vote_tx_blockhash: BlockhashStatus::Uninitialized,
+            stray_restored_slot: None,
+            last_switch_threshold_check: None,
+        };
+        let saved_tower = SavedTower1_7_14::new(&old_tower, &identity_keypair).unwrap();
+        tower_storage.store_old(&saved_tower).unwrap();
+        let read =
+            FileTowerStorage::read_old_saved_tower(&tower_storage.old_filename(&node_pubkey))
+                .unwrap();
+        assert!(matches!(read, SavedTowerVersions::V1_17_14(_)));
+        assert!(read.verify_signature(&node_pubkey));
+        assert!(!read.verify_signature(&other_pubkey));
+        assert_eq!(read.decode_unverified().unwrap().node_pubkey, node_pubkey);
+
+        let tower = Tower {
+            node_pubkey,
+            ..Tower::default()
+        };
+        let saved_tower = SavedTower::new(&tower, &identity_keypair).unwrap();
+        tower_storage
+            .store(&SavedTowerVersions::from(saved_tower))
+            .unwrap();
+        let read =
+            FileTowerStorage::read_saved_tower(&tower_storage.filename(&node_pubkey)).unwrap();
+        assert!(matches!(read, SavedTowerVersions::Current(_)));
+        assert!(read.verify_signature(&node_pubkey));
+        assert_eq!(read.decode_unverified().unwrap().node_pubkey, node_pubkey);
+        assert_matches!(
+            read.try_into_tower(&other_pubkey),
+            Err(TowerError::InvalidSignature)
+        );
+    }
 }
@@ -13,6 +13,9 @@ use {
             BlockhashStatus, ComputedBankState, SWITCH_FORK_THRESHOLD, Stake, SwitchForkDecision,
             Tower, TowerError, VotedStakes,
             fork_choice::{ForkChoice, SelectVoteAndResetForkResult, select_vote_and_reset_forks},
+            fork_tree_snapshot::{
+                ForkTreeSnapshotRequestReceiver, process_fork_tree_snapshot_requests,
+            },
             heaviest_subtree_fork_choice::HeaviestSubtreeForkChoice,
             latest_validator_votes_for_frozen_banks::LatestValidatorVotesForFrozenBanks,
             progress_map::{ForkProgress, ProgressMap, PropagatedStats},
@@ -477,6 +480,7 @@ pub struct ReplayReceivers {
     pub popular_pruned_forks_receiver: Receiver<Vec<u64>>,
     pub bank_forks_controller_receiver: BankForksCommandReceiver,
     pub latest_switch_request: LatestSwitchRequest,
+    pub fork_tree_snapshot_request_receiver: ForkTreeSnapshotRequestReceiver,
 }
 
 /// Timing information for the ReplayStage main processing loop
@@ -797,6 +801,7 @@ impl ReplayStage {
             popular_pruned_forks_receiver,
             bank_forks_controller_receiver,
             latest_switch_request,
+            fork_tree_snapshot_request_receiver,
         } = receivers;
 
         trace!("replay stage");
@@ -963,6 +968,14 @@ impl ReplayStage {
                     break;
                 }
 
+                process_fork_tree_snapshot_requests(
+                    &fork_tree_snapshot_request_receiver,
+                    &bank_forks,
+                    &progress,
+                    &tbft_structs.heaviest_subtree_fork_choice,
+                    &tower,
+                );
+
                 handle_update_parent_interrupts(
                     &my_pubkey,
                     &blockstore,
@@ -2,20 +2,30 @@
 
 use {
     crate::repair::{repair_service::OutstandingShredRepairs, serve_repair::ServeRepair},
+    agave_xdp::receiver::{
+        Receiver as XdpReceiver, ReceiverBuilder as XdpReceiverBuilder, RxPacket,
+    },
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::shred::{
         self,
         filter::{ShredFilterContext, TurbineMode},
     },
-    solana_perf::packet::{PacketBatch, PacketBatchRecycler, PacketFlags, PacketRef},
+    solana_packet::PACKET_DATA_SIZE,
+    solana_perf::packet::{
+        BytesPacket, BytesPacketBatch, Meta, PacketBatch, PacketBatchRecycler, PacketFlags,
+        PacketRef,
+    },
     solana_runtime::bank_forks::{BankForks, SharableBanks},
     solana_streamer::{
         evicting_sender::EvictingSender,
         streamer::{self, ChannelSend, PacketBatchReceiver, StreamerReceiveStats},
     },
     std::{
-        net::UdpSocket,
-        sync::{Arc, RwLock, atomic::AtomicBool},
+        net::{SocketAddr, UdpSocket},
+        sync::{
+            Arc, RwLock,
+            atomic::{AtomicBool, Ordering},
+        },
         thread::{self, Builder, JoinHandle},
         time::Duration,
     },
@@ -23,6 +33,7 @@ use {
 
 pub(crate) struct ShredFetchStage {
     thread_hdls: Vec<JoinHandle<()>>,
+    xdp_receiver: Option<XdpReceiver>,
 }
 
 /// Ingress limit for the shred fetch channel (in terms of packet _batches_).
@@ -33,6 +44,33 @@ pub(crate) struct ShredFetchStage {
 /// to future proof for increases of CU limits (e.g., a future 100k CU limit).
 pub(crate) const SHRED_FETCH_CHANNEL_SIZE: usize = 1024 * 64;
 
+/// Feeds packets received outside of the packet modifier's own streamers, i.e. via XDP, into
+/// the same channel and receive stats.
+#[derive(Clone)]
+struct PacketSink {
+    sender: EvictingSender<PacketBatch>,
+    stats: Arc<StreamerReceiveStats>,
+}
+
+impl PacketSink {
+    fn send(&self, packet_batch: BytesPacketBatch) {
+        let len = packet_batch.len();
+        self.stats.packets_count.fetch_add(len, Ordering::Relaxed);
+        self.stats
+            .packet_batches_count
+            .fetch_add(1, Ordering::Relaxed);
+        if self
+            .sender
+            .try_send(PacketBatch::Bytes(packet_batch))
+            .is_err()
+        {
+            self.stats
+                .num_packets_dropped
+                .fetch_add(len, Ordering::Relaxed);
+        }
+    }
+}
+
 #[derive(Clone)]
 struct RepairContext {
     repair_socket: Arc<UdpSocket>,
@@ -139,7 +177,7 @@ impl ShredFetchStage {
         flags: PacketFlags,
         repair_context: Option<RepairContext>,
         turbine_mode: TurbineMode,
-    ) -> (Vec<JoinHandle<()>>, JoinHandle<()>) {
+    ) -> (Vec<JoinHandle<()>>, JoinHandle<()>, PacketSink) {
         let sharable_banks = bank_forks.read().unwrap().sharable_banks();
         let (packet_sender, packet_receiver) =
             EvictingSender::new_bounded(SHRED_FETCH_CHANNEL_SIZE);
@@ -161,6 +199,10 @@ impl ShredFetchStage {
                 )
             })
             .collect();
+        let packet_sink = PacketSink {
+            sender: packet_sender,
+            stats: receiver_stats.clone(),
+        };
         let modifier_hdl = Builder::new()
             .name(modifier_thread_name.to_string())
             .spawn(move || {
@@ -177,7 +219,7 @@ impl ShredFetchStage {
                 )
             })
             .unwrap();
-        (streamers, modifier_hdl)
+        (streamers, modifier_hdl, packet_sink)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -190,16 +232,18 @@ impl ShredFetchStage {
         cluster_info: Arc<ClusterInfo>,
         outstanding_repair_requests: Arc<RwLock<OutstandingShredRepairs>>,
         turbine_mode: TurbineMode,
+        xdp_receiver_builder: Option<XdpReceiverBuilder>,
         exit: Arc<AtomicBool>,
     ) -> Self {
         let recycler = PacketBatchRecycler::new();
+        let repair_port = repair_socket.local_addr().unwrap().port();
         let repair_context = RepairContext {
             repair_socket: repair_socket.clone(),
             cluster_info,
             outstanding_repair_requests,
         };
 
-        let (mut tvu_threads, tvu_filter) = Self::packet_modifier(
+        let (mut tvu_threads, tvu_filter, tvu_sink) = Self::packet_modifier(
             "solRcvrShred",
             "solTvuPktMod",
             sockets,
@@ -215,7 +259,7 @@ impl ShredFetchStage {
             turbine_mode.clone(),
         );
 
-        let (repair_receiver, repair_handler) = Self::packet_modifier(
+        let (repair_receiver, repair_handler, repair_sink) = Self::packet_modifier(
             "solRcvrShredRep",
             "solTvuRepPktMod",
             vec![repair_socket],
@@ -231,15 +275,27 @@ impl ShredFetchStage {
             turbine_mode.clone(),
         );
 
+        // The regular streamers keep running alongside XDP, they still receive the packets
+        // arriving on queues that don't have an XDP socket.
+        let xdp_receiver = xdp_receiver_builder.map(|builder| {
+            builder.build(exit, move |packets| {
+                route_xdp_packets(packets, repair_port, &tvu_sink, &repair_sink)
+            })
+        });
+
         tvu_threads.extend(repair_receiver);
         tvu_threads.push(tvu_filter);
         tvu_threads.push(repair_handler);
         Self {
             thread_hdls: tvu_threads,
+            xdp_receiver,
         }
     }
 
     pub(crate) fn join(self) -> thread::Result<()> {
+        if let Some(xdp_receiver) = self.xdp_receiver {
+            xdp_receiver.join()?;
+        }
         for thread_hdl in self.thread_hdls {
             thread_hdl.join()?;
         }
@@ -247,6 +303,45 @@ impl ShredFetchStage {
     }
 }
 
+/// Splits packets received via XDP between the turbine and repair packet modifiers.
+fn route_xdp_packets(
+    packets: Vec<RxPacket>,
+    repair_port: u16,
+    tvu_sink: &PacketSink,
+    repair_sink: &PacketSink,
+) {
+    let mut tvu_batch = BytesPacketBatch::with_capacity(packets.len());
+    let mut repair_batch = BytesPacketBatch::new();
+    for RxPacket {
+        src_addr,
+        dst_addr,
+        payload,
+    } in packets
+    {
+        // recvmmsg would have truncated these, and no valid shred is this large
+        if payload.len() > PACKET_DATA_SIZE {
+            continue;
+        }
+        let mut meta = Meta {
+            size: payload.len(),
+            ..Meta::default()
+        };
+        meta.set_socket_addr(&SocketAddr::V4(src_addr));
+        let packet = BytesPacket::new(payload, meta);
+        if dst_addr.port() == repair_port {
+            repair_batch.push(packet);
+        } else {
+            tvu_batch.push(packet);
+        }
+    }
+    if !tvu_batch.is_empty() {
+        tvu_sink.send(tvu_batch);
+    }
+    if !repair_batch.is_empty() {
+        repair_sink.send(repair_batch);
+    }
+}
+
 // Returns false if repair nonce is invalid and packet should be discarded.
 #[must_use]
 fn verify_repair_nonce(
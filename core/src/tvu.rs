@@ -12,7 +12,9 @@ use {
         cluster_slots_service::{ClusterSlotsService, cluster_slots::ClusterSlots},
         commitment_service::AggregateCommitmentService,
         completed_data_sets_service::CompletedDataSetsSender,
-        consensus::{Tower, tower_storage::TowerStorage},
+        consensus::{
+            Tower, fork_tree_snapshot::ForkTreeSnapshotRequestReceiver, tower_storage::TowerStorage,
+        },
         cost_update_service::CostUpdateService,
         drop_bank_service::DropBankService,
         epoch_specs::EpochSpecs,
@@ -32,6 +34,7 @@ use {
         rewards::RewardInput,
     },
     agave_votor::{
+        consensus_timeline::ConsensusTimeline,
         event::{LatestSwitchRequest, LeaderWindowInfo, VotorEventReceiver, VotorEventSender},
         peer_list_updater::PeerListService,
         vote_history::VoteHistory,
@@ -44,6 +47,7 @@ use {
         metric_types::MAX_IN_FLIGHT_CONSENSUS_EVENTS,
     },
     agave_votor_transport::endpoint::QuicDatagramEndpoint,
+    agave_xdp::receiver::ReceiverBuilder as XdpReceiverBuilder,
     crossbeam_channel::{Receiver, Sender, bounded, unbounded},
     solana_client::connection_cache::ConnectionCache,
     solana_clock::Slot,
@@ -158,6 +162,8 @@ pub struct TvuConfig {
     pub bls_sigverify_threads: NonZeroUsize,
     pub turbine_xdp_sender: Option<TurbineXdpSender>,
     pub repair_xdp_sender: Option<PinnedXdpSender>,
+    // Receives turbine and repair shreds via XDP, in addition to the fetch and repair sockets
+    pub xdp_receiver_builder: Option<XdpReceiverBuilder>,
 }
 
 impl Default for TvuConfig {
@@ -174,6 +180,7 @@ impl Default for TvuConfig {
             bls_sigverify_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             turbine_xdp_sender: None,
             repair_xdp_sender: None,
+            xdp_receiver_builder: None,
         }
     }
 }
@@ -189,6 +196,8 @@ pub struct AlpenglowInitializationState {
     pub highest_finalized: Arc<RwLock<Option<ValidatedBlockFinalizationCert>>>,
     pub bank_forks_controller: Arc<dyn BankForksController>,
     pub bank_forks_controller_receiver: BankForksCommandReceiver,
+    // Shared with admin rpc
+    pub consensus_timeline: Arc<ConsensusTimeline>,
 
     // Main communication channel
     pub votor_event_sender: VotorEventSender,
@@ -256,6 +265,7 @@ impl Tvu {
         vote_connection_cache: Arc<ConnectionCache>,
         votor_init: AlpenglowInitializationState,
         reward_aggregates_sender: Sender<RewardInput>,
+        fork_tree_snapshot_request_receiver: ForkTreeSnapshotRequestReceiver,
     ) -> Result<Self, String> {
         let migration_status = bank_forks.read().unwrap().migration_status();
 
@@ -284,6 +294,7 @@ impl Tvu {
             #[cfg(feature = "dev-context-only-utils")]
             voting_service_test_override,
             highest_finalized,
+            consensus_timeline,
         } = votor_init;
 
         let (consensus_message_sender, consensus_message_receiver) =
@@ -316,10 +327,13 @@ impl Tvu {
         // so we can initialize the watch channel with a dummy value here.
         let (votor_peer_list_sender, votor_peer_list_receiver) =
             watch::channel(Arc::new(HashMap::new()));
+        let (votor_peer_stakes_sender, votor_peer_stakes_receiver) =
+            watch::channel(Arc::new(HashMap::new()));
         let sharable_banks = bank_forks.read().unwrap().sharable_banks();
         let peer_list_service = PeerListService::new(
             cluster_info.clone(),
             votor_peer_list_sender,
+            votor_peer_stakes_sender,
             sharable_banks.clone(),
             #[cfg(feature = "dev-context-only-utils")]
             voting_service_test_override,
@@ -331,6 +345,7 @@ impl Tvu {
             votor_client_socket,
             votor_ingress_sender,
             votor_peer_list_receiver,
+            votor_peer_stakes_receiver,
             VOTOR_RATE_LIMIT_PPS,
             cancel,
         )
@@ -357,6 +372,7 @@ impl Tvu {
                 leader_schedule: leader_schedule_cache.clone(),
                 num_threads: tvu_config.bls_sigverify_threads.get(),
                 generated_cert_types: generated_cert_types.clone(),
+                blockstore: blockstore.clone(),
             },
             SigVerifierChannels {
                 packet_receiver: votor_ingress_receiver,
@@ -383,6 +399,7 @@ impl Tvu {
             cluster_info.clone(),
             outstanding_repair_requests.clone(),
             turbine_mode,
+            tvu_config.xdp_receiver_builder,
             exit.clone(),
         );
 
@@ -529,11 +546,13 @@ impl Tvu {
             cluster_info: cluster_info.clone(),
             leader_schedule_cache: leader_schedule_cache.clone(),
             consensus_metrics_sender,
+            consensus_timeline,
             highest_finalized: highest_finalized.clone(),
             bank_forks_controller,
             bls_sender: bls_sender.clone(),
             commitment_sender: votor_commitment_sender,
             bank_notification_sender: bank_notification_sender.clone(),
+            rpc_subscriptions: rpc_subscriptions.clone(),
             leader_window_info_sender,
             highest_parent_ready,
             event_sender: votor_event_sender.clone(),
@@ -583,6 +602,7 @@ impl Tvu {
             popular_pruned_forks_receiver,
             bank_forks_controller_receiver,
             latest_switch_request,
+            fork_tree_snapshot_request_receiver,
         };
 
         let replay_stage_config = ReplayStageConfig {
@@ -745,10 +765,14 @@ pub mod tests {
     use {
         super::*,
         crate::{
-            admin_rpc_post_init::KeyUpdaters, block_creation_loop::ReplayHighestFrozen,
-            consensus::tower_storage::FileTowerStorage,
+            admin_rpc_post_init::KeyUpdaters,
+            block_creation_loop::ReplayHighestFrozen,
+            consensus::{
+                fork_tree_snapshot::ForkTreeSnapshotRequester, tower_storage::FileTowerStorage,
+            },
         },
         agave_votor::{
+            consensus_timeline::DEFAULT_CONSENSUS_TIMELINE_SLOTS,
             event::{VotorEventReceiver, VotorEventSender},
             vote_history::VoteHistory,
             vote_history_storage::NullVoteHistoryStorage,
@@ -851,6 +875,8 @@ pub mod tests {
             BankForksControllerHandle::new();
         let bank_forks_controller = Arc::new(bank_forks_controller);
         let (reward_vote_aggregates_sender, _reward_vote_aggregates_receiver) = bounded(1024);
+        let (_fork_tree_snapshot_requester, fork_tree_snapshot_request_receiver) =
+            ForkTreeSnapshotRequester::new();
 
         let tvu = Tvu::new(
             &vote_keypair.pubkey(),
@@ -923,8 +949,12 @@ pub mod tests {
                 highest_finalized: Arc::new(RwLock::new(None)),
                 bank_forks_controller,
                 bank_forks_controller_receiver,
+                consensus_timeline: Arc::new(ConsensusTimeline::new(
+                    DEFAULT_CONSENSUS_TIMELINE_SLOTS,
+                )),
             },
             reward_vote_aggregates_sender,
+            fork_tree_snapshot_request_receiver,
         )
         .expect("assume success");
         exit.store(true, Ordering::Relaxed);
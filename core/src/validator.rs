@@ -12,7 +12,9 @@ use {
         cluster_info_vote_listener::VoteTracker,
         completed_data_sets_service::CompletedDataSetsService,
         consensus::{
-            ExternalRootSource, Tower, reconcile_blockstore_roots_with_external_source,
+            ExternalRootSource, Tower,
+            fork_tree_snapshot::ForkTreeSnapshotRequester,
+            reconcile_blockstore_roots_with_external_source,
             tower_storage::{NullTowerStorage, TowerStorage},
         },
         forwarding_stage::ForwardingClientConfig,
@@ -35,11 +37,17 @@ use {
         snapshot_config::SnapshotConfig, snapshot_hash::StartingSnapshotHashes,
     },
     agave_votor::{
+        consensus_timeline::{
+            CONSENSUS_TIMELINE_FILE_NAME, ConsensusTimeline, DEFAULT_CONSENSUS_TIMELINE_SLOTS,
+        },
         vote_history::{VoteHistory, VoteHistoryError},
         vote_history_storage::{NullVoteHistoryStorage, VoteHistoryStorage},
         voting_service::VotingServiceOverride,
     },
-    agave_xdp::transmitter::{Transmitter, TransmitterBuilder},
+    agave_xdp::{
+        receiver::ReceiverBuilder,
+        transmitter::{Transmitter, TransmitterBuilder},
+    },
     anyhow::{Result, anyhow},
     crossbeam_channel::{Receiver, bounded, unbounded},
     serde::{Deserialize, Serialize},
@@ -540,6 +548,8 @@ pub enum ValidatorStartProgress {
 pub struct XdpTransmitSetup {
     pub transmitter_builder: TransmitterBuilder,
     pub src_ip: Ipv4Addr,
+    /// Receives turbine and repair shreds on the transmit interface, if enabled
+    pub receiver_builder: Option<ReceiverBuilder>,
 }
 
 struct BlockstoreRootScan {
@@ -1254,6 +1264,16 @@ impl Validator {
                     cancel.clone(),
                 )
             };
+            let rpc_subscriptions = Arc::new(RpcSubscriptions::new_with_config(
+                exit.clone(),
+                max_complete_transaction_status_slot.clone(),
+                blockstore.clone(),
+                bank_forks.clone(),
+                block_commitment_cache.clone(),
+                optimistically_confirmed_bank.clone(),
+                &config.pubsub_config,
+                None,
+            ));
             let rpc_svc_config = JsonRpcServiceConfig {
                 rpc_addr,
                 rpc_config: config.rpc_config.clone(),
@@ -1272,22 +1292,13 @@ impl Validator {
                 send_transaction_service_config: config.send_transaction_service_config.clone(),
                 max_slots: max_slots.clone(),
                 leader_schedule_cache: leader_schedule_cache.clone(),
-                max_complete_transaction_status_slot: max_complete_transaction_status_slot.clone(),
+                max_complete_transaction_status_slot,
                 prioritization_fee_cache: prioritization_fee_cache.clone(),
+                block_certificate_cache: rpc_subscriptions.block_certificate_cache().clone(),
                 rpc_tpu_client_args,
             };
             let json_rpc_service =
                 JsonRpcService::new_with_config(rpc_svc_config).map_err(ValidatorError::Other)?;
-            let rpc_subscriptions = Arc::new(RpcSubscriptions::new_with_config(
-                exit.clone(),
-                max_complete_transaction_status_slot,
-                blockstore.clone(),
-                bank_forks.clone(),
-                block_commitment_cache.clone(),
-                optimistically_confirmed_bank.clone(),
-                &config.pubsub_config,
-                None,
-            ));
             let pubsub_service = if !config.rpc_config.full_api {
                 None
             } else {
@@ -1414,9 +1425,11 @@ impl Validator {
             quic_xdp_sender,
             repair_xdp_sender,
             gossip_xdp_sender,
+            xdp_receiver_builder,
         ) = if let Some(XdpTransmitSetup {
             transmitter_builder,
             src_ip,
+            receiver_builder,
         }) = xdp_transmit_setup
         {
             let turbine_src_port = node.sockets.retransmit_sockets[0]
@@ -1452,9 +1465,10 @@ impl Validator {
                     sender,
                     SocketAddrV4::new(src_ip, gossip_src_port),
                 )),
+                receiver_builder,
             )
         } else {
-            (None, None, None, None, None)
+            (None, None, None, None, None, None)
         };
 
         let gossip_service = GossipService::new(
@@ -1521,6 +1535,11 @@ impl Validator {
         let highest_parent_ready = Arc::new(RwLock::default());
         // Shared state for highest finalized certificates (updated by Votor, read by block creation loop)
         let highest_finalized = Arc::new(RwLock::new(None));
+        // Per-slot record of consensus events, fed by votor and queried through admin rpc
+        let consensus_timeline = Arc::new(ConsensusTimeline::open(
+            ledger_path.join(CONSENSUS_TIMELINE_FILE_NAME),
+            DEFAULT_CONSENSUS_TIMELINE_SLOTS,
+        ));
         // This channel growing > ~1 indicates problems, so bound channel at a
         // small (but highly overprovisioned) number for performance and easier
         // debug if things go off the rails.
@@ -1597,6 +1616,9 @@ impl Validator {
         });
         // This channel backing up indicates a serious problem in votor
         let (votor_event_sender, votor_event_receiver) = bounded(1000);
+        // Lets admin rpc pull a view of the live fork tree out of replay
+        let (fork_tree_snapshot_requester, fork_tree_snapshot_request_receiver) =
+            ForkTreeSnapshotRequester::new();
 
         let tvu = Tvu::new(
             vote_account,
@@ -1647,6 +1669,7 @@ impl Validator {
                 bls_sigverify_threads: config.tvu_bls_sigverify_threads,
                 turbine_xdp_sender: turbine_xdp_sender.clone(),
                 repair_xdp_sender,
+                xdp_receiver_builder,
             },
             &max_slots,
             block_metadata_notifier,
@@ -1674,8 +1697,10 @@ impl Validator {
                 #[cfg(feature = "dev-context-only-utils")]
                 voting_service_test_override: config.voting_service_test_override.clone(),
                 highest_finalized,
+                consensus_timeline: consensus_timeline.clone(),
             },
             reward_aggregates_sender,
+            fork_tree_snapshot_request_receiver,
         )
         .map_err(ValidatorError::Other)?;
 
@@ -1787,6 +1812,8 @@ impl Validator {
             snapshot_controller,
             blockstore: blockstore.clone(),
             votor_event_sender,
+            consensus_timeline,
+            fork_tree_snapshot_requester,
         });
 
         Ok(Self {
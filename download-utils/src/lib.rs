@@ -73,6 +73,9 @@ pub fn download_snapshot_archive(
             config: ZstdConfig::default(),
         },
         ArchiveFormat::TarLz4,
+        ArchiveFormat::TarZstdSeekable {
+            config: ZstdConfig::default(),
+        },
     ] {
         let destination_path = match snapshot_kind {
             SnapshotArchiveKind::Full => snapshot_paths::build_full_snapshot_archive_path(
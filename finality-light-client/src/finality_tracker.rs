@@ -0,0 +1,432 @@
+//! Tracks finalized blocks and conflicting certificates from a stream of certificates.
+//!
+//! A block is finalized either on the fast path, by a single fast-finalize certificate, or on
+//! the slow path, by a finalize certificate for its slot together with a notarize certificate
+//! for the block. Certificates are verified against the validator set of the epoch of their
+//! slot before they are taken into account.
+
+use {
+    crate::validator_set::EpochValidatorSet,
+    agave_bls_cert_verify::cert_verify::{self, verify_certificate},
+    agave_votor_messages::{
+        certificate::{Certificate, CertificateType},
+        unverified_vote_message::UnverifiedCertificate,
+    },
+    solana_clock::{Epoch, Slot},
+    solana_epoch_schedule::EpochSchedule,
+    solana_hash::Hash,
+    std::collections::BTreeMap,
+    thiserror::Error,
+};
+
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum Error {
+    #[error("missing validator set for epoch {0}")]
+    MissingValidatorSet(Epoch),
+    #[error("certificate has shred version {actual}, expected {expected}")]
+    ShredVersionMismatch { expected: u16, actual: u16 },
+    #[error("certificate verification failed: {0}")]
+    CertVerify(#[from] cert_verify::Error),
+}
+
+/// Which path a block was finalized on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FinalizationKind {
+    /// A fast-finalize certificate for the block
+    Fast,
+    /// A finalize certificate for the slot and a notarize certificate for the block
+    Slow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FinalizedBlock {
+    pub slot: Slot,
+    pub block_id: Hash,
+    pub kind: FinalizationKind,
+}
+
+/// Two valid certificates that cannot both exist unless the safety assumptions of the protocol
+/// are broken, e.g. because more than 20% of the stake equivocated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    /// The certificate that was received first
+    pub existing: Certificate,
+    /// The certificate that conflicts with it
+    pub new: Certificate,
+}
+
+/// The outcome of processing a certificate
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CertificateOutcome {
+    /// The block finalized by the certificate, if it completed a finalization
+    pub finalized: Option<FinalizedBlock>,
+    /// Previously received certificates the new certificate conflicts with
+    pub conflicts: Vec<Conflict>,
+}
+
+#[derive(Debug, Default)]
+struct SlotState {
+    certificates: Vec<Certificate>,
+    finalized: Option<FinalizedBlock>,
+}
+
+pub struct FinalityTracker {
+    shred_version: u16,
+    epoch_schedule: EpochSchedule,
+    validator_sets: BTreeMap<Epoch, EpochValidatorSet>,
+    slots: BTreeMap<Slot, SlotState>,
+    highest_finalized: Option<FinalizedBlock>,
+}
+
+impl FinalityTracker {
+    pub fn new(shred_version: u16, epoch_schedule: EpochSchedule) -> Self {
+        Self {
+            shred_version,
+            epoch_schedule,
+            validator_sets: BTreeMap::new(),
+            slots: BTreeMap::new(),
+            highest_finalized: None,
+        }
+    }
+
+    /// Sets the validator set that signs certificates for slots in `epoch`
+    pub fn set_validator_set(&mut self, epoch: Epoch, validator_set: EpochValidatorSet) {
+        self.validator_sets.insert(epoch, validator_set);
+    }
+
+    /// Verifies `cert` and updates the finalized blocks with it.
+    ///
+    /// Certificates that were already processed are verified but otherwise ignored. Genesis
+    /// certificates are verified but do not finalize blocks.
+    pub fn process_certificate(
+        &mut self,
+        cert: UnverifiedCertificate,
+    ) -> Result<CertificateOutcome, Error> {
+        if cert.shred_version != self.shred_version {
+            return Err(Error::ShredVersionMismatch {
+                expected: self.shred_version,
+                actual: cert.shred_version,
+            });
+        }
+        let slot = cert.cert_type.slot();
+        let epoch = self.epoch_schedule.get_epoch(slot);
+        let validator_set = self
+            .validator_sets
+            .get(&epoch)
+            .ok_or(Error::MissingValidatorSet(epoch))?;
+        let cert = verify_certificate(
+            cert,
+            validator_set.len(),
+            validator_set.total_stake(),
+            |rank| validator_set.get(rank),
+        )?;
+
+        let slot_state = self.slots.entry(slot).or_default();
+        if slot_state
+            .certificates
+            .iter()
+            .any(|existing| existing.cert_type == cert.cert_type)
+        {
+            return Ok(CertificateOutcome::default());
+        }
+        let conflicts = slot_state
+            .certificates
+            .iter()
+            .filter(|existing| conflicting(&existing.cert_type, &cert.cert_type))
+            .map(|existing| Conflict {
+                existing: existing.clone(),
+                new: cert.clone(),
+            })
+            .collect();
+        slot_state.certificates.push(cert);
+
+        let finalized = if slot_state.finalized.is_none() {
+            slot_state.finalized = finalized_block(&slot_state.certificates);
+            slot_state.finalized
+        } else {
+            None
+        };
+        if let Some(finalized) = finalized
+            && self
+                .highest_finalized
+                .is_none_or(|highest| highest.slot < finalized.slot)
+        {
+            self.highest_finalized = Some(finalized);
+        }
+        Ok(CertificateOutcome {
+            finalized,
+            conflicts,
+        })
+    }
+
+    /// Returns the finalized block in `slot`, if it is known and has not been pruned
+    pub fn finalized_block(&self, slot: Slot) -> Option<&FinalizedBlock> {
+        self.slots.get(&slot)?.finalized.as_ref()
+    }
+
+    /// Returns the finalized block with the highest slot seen so far
+    pub fn highest_finalized(&self) -> Option<&FinalizedBlock> {
+        self.highest_finalized.as_ref()
+    }
+
+    /// Drops the certificates for slots below `slot` and the validator sets for earlier epochs.
+    ///
+    /// Conflicts with the dropped certificates can no longer be detected.
+    pub fn prune(&mut self, slot: Slot) {
+        self.slots = self.slots.split_off(&slot);
+        let epoch = self.epoch_schedule.get_epoch(slot);
+        self.validator_sets = self.validator_sets.split_off(&epoch);
+    }
+}
+
+/// Returns the block finalized by `certificates`, all for the same slot, if any
+fn finalized_block(certificates: &[Certificate]) -> Option<FinalizedBlock> {
+    let mut slow_finalized = false;
+    let mut notarized = None;
+    for cert in certificates {
+        match cert.cert_type {
+            CertificateType::FinalizeFast(block) => {
+                return Some(FinalizedBlock {
+                    slot: block.slot,
+                    block_id: block.block_id,
+                    kind: FinalizationKind::Fast,
+                });
+            }
+            CertificateType::Finalize(_) => slow_finalized = true,
+            CertificateType::Notarize(block) => notarized = Some(block),
+            CertificateType::NotarizeFallback(_)
+            | CertificateType::Skip(_)
+            | CertificateType::Genesis(_) => (),
+        }
+    }
+    let block = notarized.filter(|_| slow_finalized)?;
+    Some(FinalizedBlock {
+        slot: block.slot,
+        block_id: block.block_id,
+        kind: FinalizationKind::Slow,
+    })
+}
+
+/// Returns whether certificates of types `a` and `b` for the same slot cannot both exist
+fn conflicting(a: &CertificateType, b: &CertificateType) -> bool {
+    let conflicting_ordered = |a: &CertificateType, b: &CertificateType| match (a, b) {
+        // At most one block per slot can be notarized
+        (
+            CertificateType::Notarize(a) | CertificateType::FinalizeFast(a),
+            CertificateType::Notarize(b) | CertificateType::FinalizeFast(b),
+        ) => a.block_id != b.block_id,
+        // No other block can be notarized-fallback once a block is fast-finalized
+        (CertificateType::FinalizeFast(a), CertificateType::NotarizeFallback(b)) => {
+            a.block_id != b.block_id
+        }
+        // A slot cannot be both finalized and skipped
+        (
+            CertificateType::Finalize(_) | CertificateType::FinalizeFast(_),
+            CertificateType::Skip(_),
+        ) => true,
+        _ => false,
+    };
+    conflicting_ordered(a, b) || conflicting_ordered(b, a)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::validator_set::ValidatorStake,
+        agave_bls_cert_verify::cert_verify::test_create_base2_unverified_certificate,
+        agave_votor_messages::consensus_message::Block,
+        solana_bls_signatures::{
+            keypair::Keypair as BLSKeypair, pubkey::PubkeyCompressed as BLSPubkeyCompressed,
+        },
+        solana_pubkey::Pubkey,
+    };
+
+    const SHRED_VERSION: u16 = 42;
+    const OTHER_SHRED_VERSION: u16 = 43;
+    const NUM_VALIDATORS: usize = 10;
+
+    struct TestContext {
+        bls_keypairs: Vec<BLSKeypair>,
+        tracker: FinalityTracker,
+    }
+
+    impl TestContext {
+        fn new() -> Self {
+            let bls_keypairs: Vec<_> = (0..NUM_VALIDATORS).map(|_| BLSKeypair::new()).collect();
+            // Decreasing stakes, so that the rank of each validator is its index
+            let validator_set =
+                EpochValidatorSet::new(bls_keypairs.iter().enumerate().map(|(i, bls_keypair)| {
+                    ValidatorStake {
+                        vote_account_pubkey: Pubkey::new_unique(),
+                        node_pubkey: Pubkey::new_unique(),
+                        bls_pubkey: BLSPubkeyCompressed::from(*bls_keypair.public),
+                        stake: 100u64.saturating_sub(i as u64),
+                    }
+                }))
+                .unwrap();
+            let mut tracker = FinalityTracker::new(SHRED_VERSION, EpochSchedule::without_warmup());
+            tracker.set_validator_set(0, validator_set);
+            Self {
+                bls_keypairs,
+                tracker,
+            }
+        }
+
+        fn certificate(&self, cert_type: CertificateType) -> UnverifiedCertificate {
+            test_create_base2_unverified_certificate(
+                &self.bls_keypairs,
+                SHRED_VERSION,
+                cert_type,
+                &(0..NUM_VALIDATORS).collect::<Vec<_>>(),
+            )
+        }
+
+        fn process(&mut self, cert_type: CertificateType) -> CertificateOutcome {
+            let cert = self.certificate(cert_type);
+            self.tracker.process_certificate(cert).unwrap()
+        }
+    }
+
+    fn new_block(slot: Slot) -> Block {
+        Block {
+            slot,
+            block_id: Hash::new_unique(),
+        }
+    }
+
+    #[test]
+    fn test_fast_finalization() {
+        let mut context = TestContext::new();
+        let block = new_block(5);
+
+        let outcome = context.process(CertificateType::FinalizeFast(block));
+        let expected = FinalizedBlock {
+            slot: 5,
+            block_id: block.block_id,
+            kind: FinalizationKind::Fast,
+        };
+        assert_eq!(outcome.finalized, Some(expected));
+        assert!(outcome.conflicts.is_empty());
+        assert_eq!(context.tracker.finalized_block(5), Some(&expected));
+        assert_eq!(context.tracker.highest_finalized(), Some(&expected));
+
+        // Finalizing it again on the slow path is not reported again
+        assert_eq!(
+            context.process(CertificateType::Notarize(block)),
+            CertificateOutcome::default()
+        );
+        assert_eq!(
+            context.process(CertificateType::Finalize(5)),
+            CertificateOutcome::default()
+        );
+    }
+
+    #[test]
+    fn test_slow_finalization() {
+        let mut context = TestContext::new();
+        let block = new_block(7);
+
+        assert_eq!(
+            context.process(CertificateType::Finalize(7)),
+            CertificateOutcome::default()
+        );
+        assert_eq!(context.tracker.finalized_block(7), None);
+
+        let outcome = context.process(CertificateType::Notarize(block));
+        let expected = FinalizedBlock {
+            slot: 7,
+            block_id: block.block_id,
+            kind: FinalizationKind::Slow,
+        };
+        assert_eq!(outcome.finalized, Some(expected));
+        assert_eq!(context.tracker.highest_finalized(), Some(&expected));
+
+        // An older finalized block does not replace the highest one
+        context.process(CertificateType::FinalizeFast(new_block(6)));
+        assert_eq!(context.tracker.highest_finalized(), Some(&expected));
+    }
+
+    #[test]
+    fn test_conflicting_certificates() {
+        let mut context = TestContext::new();
+        let block = new_block(3);
+        let other_block = new_block(3);
+
+        context.process(CertificateType::FinalizeFast(block));
+        let outcome = context.process(CertificateType::Notarize(other_block));
+        assert_eq!(outcome.finalized, None);
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert_eq!(
+            outcome.conflicts[0].existing.cert_type,
+            CertificateType::FinalizeFast(block)
+        );
+        assert_eq!(
+            outcome.conflicts[0].new.cert_type,
+            CertificateType::Notarize(other_block)
+        );
+
+        let outcome = context.process(CertificateType::Skip(3));
+        assert_eq!(outcome.conflicts.len(), 1);
+        let outcome = context.process(CertificateType::NotarizeFallback(other_block));
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert!(
+            context
+                .process(CertificateType::NotarizeFallback(block))
+                .conflicts
+                .is_empty()
+        );
+        // The first certificate to complete a finalization wins
+        assert_eq!(
+            context.tracker.finalized_block(3).unwrap().block_id,
+            block.block_id
+        );
+    }
+
+    #[test]
+    fn test_process_certificate_errors() {
+        let mut context = TestContext::new();
+
+        let mut cert = context.certificate(CertificateType::Finalize(1));
+        cert.shred_version = OTHER_SHRED_VERSION;
+        assert_eq!(
+            context.tracker.process_certificate(cert).unwrap_err(),
+            Error::ShredVersionMismatch {
+                expected: SHRED_VERSION,
+                actual: OTHER_SHRED_VERSION,
+            }
+        );
+
+        let slot_in_epoch_1 = EpochSchedule::without_warmup().get_first_slot_in_epoch(1);
+        let cert = context.certificate(CertificateType::Finalize(slot_in_epoch_1));
+        assert_eq!(
+            context.tracker.process_certificate(cert).unwrap_err(),
+            Error::MissingValidatorSet(1)
+        );
+
+        // Signed by too little stake
+        let cert = test_create_base2_unverified_certificate(
+            &context.bls_keypairs,
+            SHRED_VERSION,
+            CertificateType::Finalize(1),
+            &[0, 1, 2],
+        );
+        assert!(matches!(
+            context.tracker.process_certificate(cert).unwrap_err(),
+            Error::CertVerify(cert_verify::Error::NotEnoughStake { .. })
+        ));
+        assert_eq!(context.tracker.finalized_block(1), None);
+    }
+
+    #[test]
+    fn test_prune() {
+        let mut context = TestContext::new();
+        context.process(CertificateType::FinalizeFast(new_block(1)));
+        context.process(CertificateType::FinalizeFast(new_block(2)));
+
+        context.tracker.prune(2);
+        assert_eq!(context.tracker.finalized_block(1), None);
+        assert!(context.tracker.finalized_block(2).is_some());
+        assert!(context.tracker.highest_finalized().is_some());
+    }
+}
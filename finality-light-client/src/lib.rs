@@ -0,0 +1,12 @@
+#![cfg(feature = "agave-unstable-api")]
+//! This crate tracks Alpenglow finality from a stream of certificates, without a bank.
+//!
+//! It is meant for off-chain consumers, such as bridge relayers, that need to check finality
+//! themselves but cannot depend on `solana-runtime`. The caller supplies the BLS keys and stakes
+//! of each epoch's validators, e.g. as read from the vote accounts over RPC, and feeds in
+//! certificates as they are received, e.g. from `certificateSubscribe`.
+//!
+//! The main entry point for this crate is [`finality_tracker::FinalityTracker`].
+
+pub mod finality_tracker;
+pub mod validator_set;
@@ -0,0 +1,187 @@
+//! The validators allowed to sign certificates in an epoch, in rank order.
+
+use {
+    solana_bls_signatures::pubkey::{
+        PopVerified, PubkeyAffine as BLSPubkeyAffine, PubkeyCompressed as BLSPubkeyCompressed,
+    },
+    solana_pubkey::Pubkey,
+    std::{collections::HashMap, num::NonZero},
+    thiserror::Error,
+};
+
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum Error {
+    #[error("validator set has no stake")]
+    NoStake,
+    #[error("validator set has more than {} validators", u16::MAX)]
+    TooManyValidators,
+}
+
+/// A validator's stake and keys, as recorded in its vote account.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidatorStake {
+    /// The address of the vote account
+    pub vote_account_pubkey: Pubkey,
+    /// The identity of the validator specified in the vote account
+    pub node_pubkey: Pubkey,
+    /// The BLS pubkey specified in the vote account
+    pub bls_pubkey: BLSPubkeyCompressed,
+    /// The stake delegated to the vote account for the epoch
+    pub stake: u64,
+}
+
+/// The BLS pubkeys and stakes of an epoch's validators, indexed by rank.
+///
+/// Certificate bitmaps refer to signers by rank, so the ranks must match the ones the cluster
+/// uses for the epoch; a validator with a smaller rank has a higher stake.
+#[derive(Clone, Debug)]
+pub struct EpochValidatorSet {
+    ranked: Vec<(NonZero<u64>, PopVerified<BLSPubkeyAffine>)>,
+    total_stake: NonZero<u64>,
+}
+
+impl EpochValidatorSet {
+    /// Ranks `validators` the way the cluster does.
+    ///
+    /// Validators without stake or with an invalid BLS pubkey are excluded, as are validators
+    /// that share their BLS pubkey or identity with another validator.
+    ///
+    /// The BLS pubkeys are trusted to have had their proof of possession verified, which the
+    /// vote program does before storing them in a vote account.
+    pub fn new(validators: impl IntoIterator<Item = ValidatorStake>) -> Result<Self, Error> {
+        let mut candidates = vec![];
+        let mut bls_pubkey_counts = HashMap::new();
+        let mut node_pubkey_counts = HashMap::new();
+        for validator in validators {
+            let Some(stake) = NonZero::new(validator.stake) else {
+                continue;
+            };
+            let Ok(bls_pubkey) = BLSPubkeyAffine::try_from(validator.bls_pubkey) else {
+                continue;
+            };
+            // SAFETY: see the function documentation; the vote program only stores BLS pubkeys
+            // whose proof of possession has been verified.
+            let bls_pubkey = unsafe { PopVerified::new_unchecked(bls_pubkey) };
+            let bls_pubkey_count = bls_pubkey_counts
+                .entry(validator.bls_pubkey)
+                .or_insert(0u32);
+            *bls_pubkey_count = bls_pubkey_count.saturating_add(1);
+            let node_pubkey_count = node_pubkey_counts
+                .entry(validator.node_pubkey)
+                .or_insert(0u32);
+            *node_pubkey_count = node_pubkey_count.saturating_add(1);
+            candidates.push((
+                stake,
+                validator.bls_pubkey,
+                validator.node_pubkey,
+                bls_pubkey,
+            ));
+        }
+        candidates.retain(|(_, bls_pubkey_compressed, node_pubkey, _)| {
+            bls_pubkey_counts[bls_pubkey_compressed] == 1 && node_pubkey_counts[node_pubkey] == 1
+        });
+        candidates.sort_by(|(a_stake, a_pubkey, ..), (b_stake, b_pubkey, ..)| {
+            b_stake.cmp(a_stake).then(a_pubkey.cmp(b_pubkey))
+        });
+        Self::from_ranked(
+            candidates
+                .into_iter()
+                .map(|(stake, _, _, bls_pubkey)| (stake, bls_pubkey))
+                .collect(),
+        )
+    }
+
+    /// Creates a validator set from stakes and BLS pubkeys that are already in rank order.
+    pub fn from_ranked(
+        ranked: Vec<(NonZero<u64>, PopVerified<BLSPubkeyAffine>)>,
+    ) -> Result<Self, Error> {
+        if ranked.len() > usize::from(u16::MAX) {
+            return Err(Error::TooManyValidators);
+        }
+        let total_stake = ranked
+            .iter()
+            .fold(0u64, |total, (stake, _)| total.saturating_add(stake.get()));
+        let total_stake = NonZero::new(total_stake).ok_or(Error::NoStake)?;
+        Ok(Self {
+            ranked,
+            total_stake,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranked.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranked.len()
+    }
+
+    pub fn total_stake(&self) -> NonZero<u64> {
+        self.total_stake
+    }
+
+    /// Returns the stake and BLS pubkey of the validator with `rank`
+    pub fn get(&self, rank: usize) -> Option<(NonZero<u64>, PopVerified<BLSPubkeyAffine>)> {
+        self.ranked.get(rank).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_bls_signatures::keypair::Keypair as BLSKeypair};
+
+    fn validator_stake(bls_keypair: &BLSKeypair, stake: u64) -> ValidatorStake {
+        ValidatorStake {
+            vote_account_pubkey: Pubkey::new_unique(),
+            node_pubkey: Pubkey::new_unique(),
+            bls_pubkey: BLSPubkeyCompressed::from(*bls_keypair.public),
+            stake,
+        }
+    }
+
+    #[test]
+    fn test_epoch_validator_set_ranks_by_stake() {
+        let bls_keypairs: Vec<_> = (0..5).map(|_| BLSKeypair::new()).collect();
+        let validator_set = EpochValidatorSet::new([
+            validator_stake(&bls_keypairs[0], 10),
+            validator_stake(&bls_keypairs[1], 30),
+            validator_stake(&bls_keypairs[2], 0),
+            validator_stake(&bls_keypairs[3], 20),
+        ])
+        .unwrap();
+
+        assert_eq!(validator_set.len(), 3);
+        assert_eq!(validator_set.total_stake().get(), 60);
+        let ranked: Vec<_> = (0..validator_set.len())
+            .map(|rank| validator_set.get(rank).unwrap())
+            .collect();
+        assert_eq!(
+            ranked,
+            [
+                (NonZero::new(30).unwrap(), bls_keypairs[1].public),
+                (NonZero::new(20).unwrap(), bls_keypairs[3].public),
+                (NonZero::new(10).unwrap(), bls_keypairs[0].public),
+            ]
+        );
+        assert_eq!(validator_set.get(3), None);
+    }
+
+    #[test]
+    fn test_epoch_validator_set_excludes_duplicates() {
+        let bls_keypairs: Vec<_> = (0..3).map(|_| BLSKeypair::new()).collect();
+        let shared_node_pubkey = Pubkey::new_unique();
+        let validator_set = EpochValidatorSet::new([
+            validator_stake(&bls_keypairs[0], 10),
+            validator_stake(&bls_keypairs[0], 20),
+            ValidatorStake {
+                node_pubkey: shared_node_pubkey,
+                ..validator_stake(&bls_keypairs[1], 30)
+            },
+            ValidatorStake {
+                node_pubkey: shared_node_pubkey,
+                ..validator_stake(&bls_keypairs[2], 40)
+            },
+        ]);
+        assert_eq!(validator_set.unwrap_err(), Error::NoStake);
+    }
+}
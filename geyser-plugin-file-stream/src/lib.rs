@@ -0,0 +1,46 @@
+//! A Geyser plugin recording the notifications it receives to rotating, compressed segment files
+//!
+//! The recorded stream can be replayed into another Geyser plugin, see [`replay::replay`], which
+//! allows developing and testing plugins against real validator output without running a
+//! validator.
+//!
+//! The plugin is configured by the following keys of its config file, next to `libpath`:
+//!
+//! ```json5
+//! {
+//!   libpath: "libagave_geyser_plugin_file_stream.so",
+//!   // Directory of the stream, relative to the config file unless absolute
+//!   path: "geyser-stream",
+//!   // Uncompressed bytes after which a new segment is started
+//!   segmentSize: 268435456,
+//!   // Oldest segments are removed beyond this many, all are kept when omitted
+//!   maxSegments: 64,
+//!   compressionLevel: 3,
+//!   accounts: true,
+//!   snapshotAccounts: false,
+//!   transactions: true,
+//!   entries: true,
+//!   // Notifications queued for the writer before the validator waits on it
+//!   channelCapacity: 10000,
+//! }
+//! ```
+
+pub mod plugin;
+pub mod record;
+pub mod replay;
+pub mod segment;
+
+use {
+    crate::plugin::FileStreamPlugin,
+    agave_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
+};
+
+#[unsafe(no_mangle)]
+#[allow(improper_ctypes_definitions)]
+/// # Safety
+///
+/// This function returns the FileStreamPlugin pointer as trait GeyserPlugin.
+pub unsafe extern "C" fn _create_plugin() -> *mut dyn GeyserPlugin {
+    let plugin: Box<dyn GeyserPlugin> = Box::new(FileStreamPlugin::default());
+    Box::into_raw(plugin)
+}
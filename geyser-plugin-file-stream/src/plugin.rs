@@ -0,0 +1,544 @@
+//! The Geyser plugin recording notifications to a stream directory
+use {
+    crate::{
+        record::{AccountRecord, BlockRecord, EntryRecord, Record, TransactionRecord},
+        segment::{
+            DEFAULT_COMPRESSION_LEVEL, DEFAULT_SEGMENT_SIZE, SegmentWriter, SegmentWriterConfig,
+        },
+    },
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions, ReplicaBlockInfoVersions,
+        ReplicaEntryInfoVersions, ReplicaEntryUpdateParentInfoVersions,
+        ReplicaTransactionInfoVersions, Result, SlotStatus,
+    },
+    crossbeam_channel::{Receiver, Sender, bounded},
+    log::*,
+    serde::Deserialize,
+    solana_clock::{BankId, Slot},
+    std::{
+        fs,
+        path::{Path, PathBuf},
+        thread::{self, JoinHandle},
+    },
+};
+
+const DEFAULT_CHANNEL_CAPACITY: usize = 10_000;
+
+/// The plugin specific keys of the plugin config file
+///
+/// Unknown keys are ignored, since the file also holds the keys read by the plugin manager.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct FileStreamConfig {
+    /// Directory holding the stream, relative to the config file unless absolute
+    path: Option<PathBuf>,
+    segment_size: u64,
+    max_segments: Option<usize>,
+    compression_level: i32,
+    accounts: bool,
+    snapshot_accounts: bool,
+    transactions: bool,
+    entries: bool,
+    /// Number of notifications that may be queued for the writer before notifying blocks
+    channel_capacity: usize,
+}
+
+impl Default for FileStreamConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            segment_size: DEFAULT_SEGMENT_SIZE,
+            max_segments: None,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+            accounts: true,
+            snapshot_accounts: false,
+            transactions: true,
+            entries: true,
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+        }
+    }
+}
+
+/// Records every notification it receives to rotating segment files
+///
+/// A recorded stream can be fed back to another plugin with [`crate::replay::replay`].
+#[derive(Debug, Default)]
+pub struct FileStreamPlugin {
+    config: FileStreamConfig,
+    sender: Option<Sender<Record>>,
+    writer_thread: Option<JoinHandle<()>>,
+}
+
+impl FileStreamPlugin {
+    fn send(&self, record: Record) -> Result<()> {
+        let Some(sender) = &self.sender else {
+            return Err(GeyserPluginError::Custom(
+                "file stream plugin is not loaded".into(),
+            ));
+        };
+        sender
+            .send(record)
+            .map_err(|_| GeyserPluginError::Custom("file stream writer has stopped".into()))
+    }
+
+    fn read_config(config_file: &str) -> Result<(FileStreamConfig, PathBuf)> {
+        let contents =
+            fs::read_to_string(config_file).map_err(GeyserPluginError::ConfigFileOpenError)?;
+        let config: FileStreamConfig =
+            json5::from_str(&contents).map_err(|err| GeyserPluginError::ConfigFileReadError {
+                msg: format!("failed to parse the file stream config: {err}"),
+            })?;
+        let path = config
+            .path
+            .as_ref()
+            .ok_or_else(|| GeyserPluginError::ConfigFileReadError {
+                msg: "the file stream config is missing \"path\"".to_string(),
+            })?;
+        let path = Path::new(config_file)
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(path);
+        Ok((config, path))
+    }
+
+    fn write_records(mut writer: SegmentWriter, receiver: Receiver<Record>) {
+        for record in receiver {
+            if let Err(err) = writer.write(&record) {
+                error!("failed to write to the file stream, stopping: {err}");
+                return;
+            }
+        }
+        if let Err(err) = writer.finish() {
+            error!("failed to complete the file stream segment: {err}");
+        }
+    }
+}
+
+impl GeyserPlugin for FileStreamPlugin {
+    fn setup_logger(&self, logger: &'static dyn log::Log, level: log::LevelFilter) -> Result<()> {
+        log::set_max_level(level);
+        if let Err(err) = log::set_logger(logger) {
+            return Err(GeyserPluginError::Custom(Box::new(err)));
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "file-stream"
+    }
+
+    fn on_load(&mut self, config_file: &str, _is_reload: bool) -> Result<()> {
+        let (config, path) = Self::read_config(config_file)?;
+        let writer = SegmentWriter::new(
+            &path,
+            SegmentWriterConfig {
+                segment_size: config.segment_size,
+                max_segments: config.max_segments,
+                compression_level: config.compression_level,
+            },
+        )
+        .map_err(|err| GeyserPluginError::Custom(Box::new(err)))?;
+        info!("recording geyser notifications to {}", path.display());
+
+        let (sender, receiver) = bounded(config.channel_capacity);
+        let writer_thread = thread::Builder::new()
+            .name("solGeyserFStrm".to_string())
+            .spawn(move || Self::write_records(writer, receiver))
+            .map_err(|err| GeyserPluginError::Custom(Box::new(err)))?;
+
+        self.config = config;
+        self.sender = Some(sender);
+        self.writer_thread = Some(writer_thread);
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        // Dropping the sender lets the writer complete the current segment and exit
+        self.sender = None;
+        if let Some(writer_thread) = self.writer_thread.take() {
+            if writer_thread.join().is_err() {
+                error!("file stream writer thread panicked");
+            }
+        }
+    }
+
+    fn update_account_from_snapshot(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        slot: Slot,
+    ) -> Result<()> {
+        self.send(Record::AccountFromSnapshot {
+            slot,
+            account: AccountRecord::from(account),
+        })
+    }
+
+    fn update_account_for_bank(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        slot: Slot,
+        bank_id: BankId,
+    ) -> Result<()> {
+        self.send(Record::AccountForBank {
+            slot,
+            bank_id,
+            account: AccountRecord::from(account),
+        })
+    }
+
+    fn notify_end_of_startup(&self) -> Result<()> {
+        self.send(Record::EndOfStartup)
+    }
+
+    fn update_slot_status(
+        &self,
+        slot: Slot,
+        parent: Option<Slot>,
+        status: &SlotStatus,
+    ) -> Result<()> {
+        self.send(Record::SlotStatus {
+            slot,
+            parent,
+            status: status.into(),
+        })
+    }
+
+    fn update_bank_status(
+        &self,
+        slot: Slot,
+        parent: Option<Slot>,
+        status: &SlotStatus,
+        bank_id: BankId,
+    ) -> Result<()> {
+        self.send(Record::BankStatus {
+            slot,
+            parent,
+            status: status.into(),
+            bank_id,
+        })
+    }
+
+    fn notify_transaction_for_bank(
+        &self,
+        transaction: ReplicaTransactionInfoVersions,
+        slot: Slot,
+        bank_id: BankId,
+    ) -> Result<()> {
+        self.send(Record::Transaction {
+            slot,
+            bank_id,
+            transaction: TransactionRecord::from(transaction),
+        })
+    }
+
+    fn notify_entry_for_bank(
+        &self,
+        entry: ReplicaEntryInfoVersions,
+        bank_id: BankId,
+    ) -> Result<()> {
+        self.send(Record::Entry {
+            bank_id,
+            entry: EntryRecord::from(entry),
+        })
+    }
+
+    fn notify_entry_update_parent(
+        &self,
+        update_parent: ReplicaEntryUpdateParentInfoVersions,
+    ) -> Result<()> {
+        let ReplicaEntryUpdateParentInfoVersions::V0_0_1(update_parent) = update_parent;
+        self.send(Record::EntryUpdateParent {
+            slot: update_parent.slot,
+            cleared_bank_id: update_parent.cleared_bank_id,
+            parent_slot: update_parent.parent_slot,
+            parent_block_id: *update_parent.parent_block_id,
+        })
+    }
+
+    fn notify_block_metadata_for_bank(
+        &self,
+        block: ReplicaBlockInfoVersions,
+        bank_id: BankId,
+    ) -> Result<()> {
+        self.send(Record::BlockMetadata {
+            bank_id,
+            block: BlockRecord::from(block),
+        })
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        self.config.accounts
+    }
+
+    fn account_data_snapshot_notifications_enabled(&self) -> bool {
+        self.config.snapshot_accounts
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        self.config.transactions
+    }
+
+    fn entry_notifications_enabled(&self) -> bool {
+        self.config.entries
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            record::SlotStatusRecord,
+            replay::{StreamReader, replay},
+        },
+        agave_geyser_plugin_interface::geyser_plugin_interface::{
+            ReplicaAccountInfoV3, ReplicaBlockInfoV4, ReplicaEntryInfoV2, ReplicaTransactionInfoV3,
+        },
+        solana_hash::Hash,
+        solana_message::{Message, VersionedMessage},
+        solana_pubkey::Pubkey,
+        solana_signature::Signature,
+        solana_transaction::versioned::VersionedTransaction,
+        solana_transaction_status::{RewardsAndNumPartitions, TransactionStatusMeta},
+        std::sync::Mutex,
+        tempfile::TempDir,
+    };
+
+    /// Records the notifications it receives, through the same conversions as the file stream
+    #[derive(Debug, Default)]
+    struct RecordingPlugin {
+        records: Mutex<Vec<Record>>,
+    }
+
+    impl RecordingPlugin {
+        fn push(&self, record: Record) -> Result<()> {
+            self.records.lock().unwrap().push(record);
+            Ok(())
+        }
+    }
+
+    impl GeyserPlugin for RecordingPlugin {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        fn update_account_for_bank(
+            &self,
+            account: ReplicaAccountInfoVersions,
+            slot: Slot,
+            bank_id: BankId,
+        ) -> Result<()> {
+            self.push(Record::AccountForBank {
+                slot,
+                bank_id,
+                account: account.into(),
+            })
+        }
+
+        fn notify_end_of_startup(&self) -> Result<()> {
+            self.push(Record::EndOfStartup)
+        }
+
+        fn update_bank_status(
+            &self,
+            slot: Slot,
+            parent: Option<Slot>,
+            status: &SlotStatus,
+            bank_id: BankId,
+        ) -> Result<()> {
+            self.push(Record::BankStatus {
+                slot,
+                parent,
+                status: status.into(),
+                bank_id,
+            })
+        }
+
+        fn notify_transaction_for_bank(
+            &self,
+            transaction: ReplicaTransactionInfoVersions,
+            slot: Slot,
+            bank_id: BankId,
+        ) -> Result<()> {
+            self.push(Record::Transaction {
+                slot,
+                bank_id,
+                transaction: transaction.into(),
+            })
+        }
+
+        fn notify_entry_for_bank(
+            &self,
+            entry: ReplicaEntryInfoVersions,
+            bank_id: BankId,
+        ) -> Result<()> {
+            self.push(Record::Entry {
+                bank_id,
+                entry: entry.into(),
+            })
+        }
+
+        fn notify_block_metadata_for_bank(
+            &self,
+            block: ReplicaBlockInfoVersions,
+            bank_id: BankId,
+        ) -> Result<()> {
+            self.push(Record::BlockMetadata {
+                bank_id,
+                block: block.into(),
+            })
+        }
+
+        fn entry_notifications_enabled(&self) -> bool {
+            true
+        }
+
+        fn transaction_notifications_enabled(&self) -> bool {
+            true
+        }
+    }
+
+    fn notify_slot(plugin: &dyn GeyserPlugin, slot: Slot) {
+        let bank_id = slot + 100;
+        let pubkey = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        plugin
+            .update_bank_status(slot, slot.checked_sub(1), &SlotStatus::CreatedBank, bank_id)
+            .unwrap();
+        plugin
+            .update_account_for_bank(
+                ReplicaAccountInfoVersions::V0_0_3(&ReplicaAccountInfoV3 {
+                    pubkey: pubkey.as_ref(),
+                    lamports: slot,
+                    owner: owner.as_ref(),
+                    executable: false,
+                    rent_epoch: u64::MAX,
+                    data: &[1, 2, 3],
+                    write_version: slot,
+                    txn: None,
+                }),
+                slot,
+                bank_id,
+            )
+            .unwrap();
+
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::from([slot as u8; 64])],
+            message: VersionedMessage::Legacy(Message::new(&[], Some(&pubkey))),
+        };
+        // The stream normalizes missing token balances and rewards to empty ones
+        let transaction_status_meta = TransactionStatusMeta {
+            fee: 5000,
+            pre_token_balances: Some(vec![]),
+            post_token_balances: Some(vec![]),
+            rewards: Some(vec![]),
+            ..TransactionStatusMeta::default()
+        };
+        plugin
+            .notify_transaction_for_bank(
+                ReplicaTransactionInfoVersions::V0_0_3(&ReplicaTransactionInfoV3 {
+                    signature: &transaction.signatures[0],
+                    message_hash: &Hash::new_unique(),
+                    is_vote: false,
+                    transaction: &transaction,
+                    transaction_status_meta: &transaction_status_meta,
+                    index: 0,
+                }),
+                slot,
+                bank_id,
+            )
+            .unwrap();
+        plugin
+            .notify_entry_for_bank(
+                ReplicaEntryInfoVersions::V0_0_2(&ReplicaEntryInfoV2 {
+                    slot,
+                    index: 0,
+                    num_hashes: 1,
+                    hash: Hash::new_unique().as_ref(),
+                    executed_transaction_count: 1,
+                    starting_transaction_index: 0,
+                }),
+                bank_id,
+            )
+            .unwrap();
+        plugin
+            .notify_block_metadata_for_bank(
+                ReplicaBlockInfoVersions::V0_0_4(&ReplicaBlockInfoV4 {
+                    parent_slot: slot.saturating_sub(1),
+                    parent_blockhash: &Hash::new_unique().to_string(),
+                    slot,
+                    blockhash: &Hash::new_unique().to_string(),
+                    rewards: &RewardsAndNumPartitions {
+                        rewards: vec![],
+                        num_partitions: None,
+                    },
+                    block_time: Some(1_700_000_000),
+                    block_height: Some(slot),
+                    executed_transaction_count: 1,
+                    entry_count: 1,
+                }),
+                bank_id,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let dir = TempDir::new().unwrap();
+        let config_file = dir.path().join("file-stream.json");
+        fs::write(
+            &config_file,
+            r#"{
+                libpath: "libagave_geyser_plugin_file_stream.so",
+                path: "stream",
+                segmentSize: 512,
+            }"#,
+        )
+        .unwrap();
+
+        let mut plugin = FileStreamPlugin::default();
+        plugin
+            .on_load(config_file.to_str().unwrap(), false)
+            .unwrap();
+        assert!(plugin.account_data_notifications_enabled());
+        assert!(!plugin.account_data_snapshot_notifications_enabled());
+        plugin.notify_end_of_startup().unwrap();
+        for slot in 1..=4 {
+            notify_slot(&plugin, slot);
+        }
+        plugin.on_unload();
+
+        let stream_dir = dir.path().join("stream");
+        let reader = StreamReader::open(&stream_dir).unwrap();
+        assert!(reader.index().segments.len() > 1);
+        let recorded: Vec<_> = reader
+            .records(0..=Slot::MAX)
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(recorded.len(), 1 + 4 * 5);
+        assert_eq!(recorded[0], Record::EndOfStartup);
+        assert_eq!(
+            recorded[1],
+            Record::BankStatus {
+                slot: 1,
+                parent: Some(0),
+                status: SlotStatusRecord::CreatedBank,
+                bank_id: 101,
+            }
+        );
+
+        // Replaying goes through the plugin interface again, so the recording plugin ends up with
+        // the same records if nothing was lost along the way
+        let recording = RecordingPlugin::default();
+        let stats = replay(&stream_dir, &recording, 2..=3).unwrap();
+        assert_eq!(stats.num_records, 1 + 2 * 5);
+        assert_eq!(stats.num_notified, stats.num_records);
+
+        let expected: Vec<_> = recorded
+            .into_iter()
+            .filter(|record| record.slot().is_none_or(|slot| (2..=3).contains(&slot)))
+            .collect();
+        assert_eq!(recording.records.into_inner().unwrap(), expected);
+    }
+}
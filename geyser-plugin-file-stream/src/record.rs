@@ -0,0 +1,749 @@
+//! Owned, serializable copies of the notifications sent to a Geyser plugin
+//!
+//! Each `Replica*InfoVersions` variant is recorded as its own version, so that replaying a record
+//! calls the plugin with the same version it was originally notified with.
+use {
+    crate::replay::ReplayError,
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, ReplicaAccountInfo, ReplicaAccountInfoV2, ReplicaAccountInfoV3,
+        ReplicaAccountInfoVersions, ReplicaBlockInfo, ReplicaBlockInfoV2, ReplicaBlockInfoV3,
+        ReplicaBlockInfoV4, ReplicaBlockInfoVersions, ReplicaEntryInfo, ReplicaEntryInfoV2,
+        ReplicaEntryInfoVersions, ReplicaEntryUpdateParentInfo,
+        ReplicaEntryUpdateParentInfoVersions, ReplicaTransactionInfo, ReplicaTransactionInfoV2,
+        ReplicaTransactionInfoV3, ReplicaTransactionInfoVersions, SlotStatus,
+    },
+    serde::{Deserialize, Serialize},
+    solana_clock::{BankId, Slot, UnixTimestamp},
+    solana_hash::Hash,
+    solana_message::{SimpleAddressLoader, v0::LoadedAddresses},
+    solana_signature::Signature,
+    solana_transaction::{
+        sanitized::{MessageHash, SanitizedTransaction},
+        versioned::VersionedTransaction,
+    },
+    solana_transaction_status::{Reward, RewardsAndNumPartitions, TransactionStatusMeta},
+    std::collections::HashSet,
+};
+
+/// A single notification received by the plugin
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Record {
+    AccountFromSnapshot {
+        slot: Slot,
+        account: AccountRecord,
+    },
+    AccountForBank {
+        slot: Slot,
+        bank_id: BankId,
+        account: AccountRecord,
+    },
+    EndOfStartup,
+    SlotStatus {
+        slot: Slot,
+        parent: Option<Slot>,
+        status: SlotStatusRecord,
+    },
+    BankStatus {
+        slot: Slot,
+        parent: Option<Slot>,
+        status: SlotStatusRecord,
+        bank_id: BankId,
+    },
+    Transaction {
+        slot: Slot,
+        bank_id: BankId,
+        transaction: TransactionRecord,
+    },
+    Entry {
+        bank_id: BankId,
+        entry: EntryRecord,
+    },
+    EntryUpdateParent {
+        slot: Slot,
+        cleared_bank_id: BankId,
+        parent_slot: Slot,
+        parent_block_id: Hash,
+    },
+    BlockMetadata {
+        bank_id: BankId,
+        block: BlockRecord,
+    },
+}
+
+impl Record {
+    /// Returns the slot the record belongs to, if any
+    pub fn slot(&self) -> Option<Slot> {
+        match self {
+            Self::AccountFromSnapshot { slot, .. }
+            | Self::AccountForBank { slot, .. }
+            | Self::SlotStatus { slot, .. }
+            | Self::BankStatus { slot, .. }
+            | Self::Transaction { slot, .. }
+            | Self::EntryUpdateParent { slot, .. } => Some(*slot),
+            Self::Entry { entry, .. } => Some(entry.slot),
+            Self::BlockMetadata { block, .. } => Some(block.slot()),
+            Self::EndOfStartup => None,
+        }
+    }
+
+    /// Sends the record to `plugin`, the same way the validator would have
+    ///
+    /// Returns false if the plugin is not interested in this kind of record.
+    pub fn notify(&self, plugin: &dyn GeyserPlugin) -> Result<bool, ReplayError> {
+        match self {
+            Self::AccountFromSnapshot { slot, account } => {
+                if !plugin.account_data_notifications_enabled()
+                    || !plugin.account_data_snapshot_notifications_enabled()
+                {
+                    return Ok(false);
+                }
+                account.notify(|account| plugin.update_account_from_snapshot(account, *slot))?;
+            }
+            Self::AccountForBank {
+                slot,
+                bank_id,
+                account,
+            } => {
+                if !plugin.account_data_notifications_enabled() {
+                    return Ok(false);
+                }
+                account
+                    .notify(|account| plugin.update_account_for_bank(account, *slot, *bank_id))?;
+            }
+            Self::EndOfStartup => plugin.notify_end_of_startup()?,
+            Self::SlotStatus {
+                slot,
+                parent,
+                status,
+            } => plugin.update_slot_status(*slot, *parent, &status.into())?,
+            Self::BankStatus {
+                slot,
+                parent,
+                status,
+                bank_id,
+            } => plugin.update_bank_status(*slot, *parent, &status.into(), *bank_id)?,
+            Self::Transaction {
+                slot,
+                bank_id,
+                transaction,
+            } => {
+                if !plugin.transaction_notifications_enabled() {
+                    return Ok(false);
+                }
+                transaction.notify(|transaction| {
+                    plugin.notify_transaction_for_bank(transaction, *slot, *bank_id)
+                })?;
+            }
+            Self::Entry { bank_id, entry } => {
+                if !plugin.entry_notifications_enabled() {
+                    return Ok(false);
+                }
+                entry.notify(|entry| plugin.notify_entry_for_bank(entry, *bank_id))?;
+            }
+            Self::EntryUpdateParent {
+                slot,
+                cleared_bank_id,
+                parent_slot,
+                parent_block_id,
+            } => plugin.notify_entry_update_parent(
+                ReplicaEntryUpdateParentInfoVersions::V0_0_1(&ReplicaEntryUpdateParentInfo {
+                    slot: *slot,
+                    cleared_bank_id: *cleared_bank_id,
+                    parent_slot: *parent_slot,
+                    parent_block_id,
+                }),
+            )?,
+            Self::BlockMetadata { bank_id, block } => {
+                block.notify(|block| plugin.notify_block_metadata_for_bank(block, *bank_id))?
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SlotStatusRecord {
+    Processed,
+    Rooted,
+    Confirmed,
+    FirstShredReceived,
+    Completed,
+    CreatedBank,
+    Dead(String),
+}
+
+impl From<&SlotStatus> for SlotStatusRecord {
+    fn from(status: &SlotStatus) -> Self {
+        match status {
+            SlotStatus::Processed => Self::Processed,
+            SlotStatus::Rooted => Self::Rooted,
+            SlotStatus::Confirmed => Self::Confirmed,
+            SlotStatus::FirstShredReceived => Self::FirstShredReceived,
+            SlotStatus::Completed => Self::Completed,
+            SlotStatus::CreatedBank => Self::CreatedBank,
+            SlotStatus::Dead(err) => Self::Dead(err.clone()),
+        }
+    }
+}
+
+impl From<&SlotStatusRecord> for SlotStatus {
+    fn from(status: &SlotStatusRecord) -> Self {
+        match status {
+            SlotStatusRecord::Processed => Self::Processed,
+            SlotStatusRecord::Rooted => Self::Rooted,
+            SlotStatusRecord::Confirmed => Self::Confirmed,
+            SlotStatusRecord::FirstShredReceived => Self::FirstShredReceived,
+            SlotStatusRecord::Completed => Self::Completed,
+            SlotStatusRecord::CreatedBank => Self::CreatedBank,
+            SlotStatusRecord::Dead(err) => Self::Dead(err.clone()),
+        }
+    }
+}
+
+/// The parts of a `SanitizedTransaction` needed to rebuild it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SanitizedTransactionRecord {
+    pub transaction: VersionedTransaction,
+    pub message_hash: Hash,
+    pub is_simple_vote: bool,
+    pub loaded_addresses: LoadedAddresses,
+}
+
+impl From<&SanitizedTransaction> for SanitizedTransactionRecord {
+    fn from(transaction: &SanitizedTransaction) -> Self {
+        Self {
+            transaction: transaction.to_versioned_transaction(),
+            message_hash: *transaction.message_hash(),
+            is_simple_vote: transaction.is_simple_vote_transaction(),
+            loaded_addresses: transaction.get_loaded_addresses(),
+        }
+    }
+}
+
+impl SanitizedTransactionRecord {
+    /// Rebuilds the sanitized transaction
+    ///
+    /// Reserved account keys are not recorded, so the writability of the rebuilt message's
+    /// accounts is not demoted for them.
+    pub fn to_sanitized_transaction(&self) -> Result<SanitizedTransaction, ReplayError> {
+        Ok(SanitizedTransaction::try_create(
+            self.transaction.clone(),
+            MessageHash::Precomputed(self.message_hash),
+            Some(self.is_simple_vote),
+            SimpleAddressLoader::Enabled(self.loaded_addresses.clone()),
+            &HashSet::new(),
+        )?)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountRecord {
+    pub pubkey: Vec<u8>,
+    pub lamports: u64,
+    pub owner: Vec<u8>,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub data: Vec<u8>,
+    pub write_version: u64,
+    pub version: AccountRecordVersion,
+}
+
+/// The version specific fields of an account update
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccountRecordVersion {
+    V0_0_1,
+    V0_0_2 {
+        txn_signature: Option<Signature>,
+    },
+    V0_0_3 {
+        txn: Option<SanitizedTransactionRecord>,
+    },
+}
+
+impl From<ReplicaAccountInfoVersions<'_>> for AccountRecord {
+    fn from(account: ReplicaAccountInfoVersions) -> Self {
+        match account {
+            ReplicaAccountInfoVersions::V0_0_1(account) => Self {
+                pubkey: account.pubkey.to_vec(),
+                lamports: account.lamports,
+                owner: account.owner.to_vec(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: account.data.to_vec(),
+                write_version: account.write_version,
+                version: AccountRecordVersion::V0_0_1,
+            },
+            ReplicaAccountInfoVersions::V0_0_2(account) => Self {
+                pubkey: account.pubkey.to_vec(),
+                lamports: account.lamports,
+                owner: account.owner.to_vec(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: account.data.to_vec(),
+                write_version: account.write_version,
+                version: AccountRecordVersion::V0_0_2 {
+                    txn_signature: account.txn_signature.copied(),
+                },
+            },
+            ReplicaAccountInfoVersions::V0_0_3(account) => Self {
+                pubkey: account.pubkey.to_vec(),
+                lamports: account.lamports,
+                owner: account.owner.to_vec(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: account.data.to_vec(),
+                write_version: account.write_version,
+                version: AccountRecordVersion::V0_0_3 {
+                    txn: account.txn.map(SanitizedTransactionRecord::from),
+                },
+            },
+        }
+    }
+}
+
+impl AccountRecord {
+    fn notify(
+        &self,
+        notify: impl FnOnce(ReplicaAccountInfoVersions) -> PluginResult<()>,
+    ) -> Result<(), ReplayError> {
+        match &self.version {
+            AccountRecordVersion::V0_0_1 => {
+                notify(ReplicaAccountInfoVersions::V0_0_1(&ReplicaAccountInfo {
+                    pubkey: &self.pubkey,
+                    lamports: self.lamports,
+                    owner: &self.owner,
+                    executable: self.executable,
+                    rent_epoch: self.rent_epoch,
+                    data: &self.data,
+                    write_version: self.write_version,
+                }))?
+            }
+            AccountRecordVersion::V0_0_2 { txn_signature } => {
+                notify(ReplicaAccountInfoVersions::V0_0_2(&ReplicaAccountInfoV2 {
+                    pubkey: &self.pubkey,
+                    lamports: self.lamports,
+                    owner: &self.owner,
+                    executable: self.executable,
+                    rent_epoch: self.rent_epoch,
+                    data: &self.data,
+                    write_version: self.write_version,
+                    txn_signature: txn_signature.as_ref(),
+                }))?
+            }
+            AccountRecordVersion::V0_0_3 { txn } => {
+                let txn = txn
+                    .as_ref()
+                    .map(SanitizedTransactionRecord::to_sanitized_transaction)
+                    .transpose()?;
+                notify(ReplicaAccountInfoVersions::V0_0_3(&ReplicaAccountInfoV3 {
+                    pubkey: &self.pubkey,
+                    lamports: self.lamports,
+                    owner: &self.owner,
+                    executable: self.executable,
+                    rent_epoch: self.rent_epoch,
+                    data: &self.data,
+                    write_version: self.write_version,
+                    txn: txn.as_ref(),
+                }))?
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionRecord {
+    pub signature: Signature,
+    pub is_vote: bool,
+    /// Stored in the blockstore's protobuf encoding, which records missing token balances and
+    /// rewards as empty ones
+    #[serde(with = "transaction_status_meta")]
+    pub transaction_status_meta: TransactionStatusMeta,
+    pub version: TransactionRecordVersion,
+}
+
+/// The version specific fields of a transaction notification
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionRecordVersion {
+    V0_0_1 {
+        transaction: SanitizedTransactionRecord,
+    },
+    V0_0_2 {
+        transaction: SanitizedTransactionRecord,
+        index: usize,
+    },
+    V0_0_3 {
+        transaction: VersionedTransaction,
+        message_hash: Hash,
+        index: usize,
+    },
+}
+
+impl From<ReplicaTransactionInfoVersions<'_>> for TransactionRecord {
+    fn from(transaction: ReplicaTransactionInfoVersions) -> Self {
+        match transaction {
+            ReplicaTransactionInfoVersions::V0_0_1(transaction) => Self {
+                signature: *transaction.signature,
+                is_vote: transaction.is_vote,
+                transaction_status_meta: transaction.transaction_status_meta.clone(),
+                version: TransactionRecordVersion::V0_0_1 {
+                    transaction: transaction.transaction.into(),
+                },
+            },
+            ReplicaTransactionInfoVersions::V0_0_2(transaction) => Self {
+                signature: *transaction.signature,
+                is_vote: transaction.is_vote,
+                transaction_status_meta: transaction.transaction_status_meta.clone(),
+                version: TransactionRecordVersion::V0_0_2 {
+                    transaction: transaction.transaction.into(),
+                    index: transaction.index,
+                },
+            },
+            ReplicaTransactionInfoVersions::V0_0_3(transaction) => Self {
+                signature: *transaction.signature,
+                is_vote: transaction.is_vote,
+                transaction_status_meta: transaction.transaction_status_meta.clone(),
+                version: TransactionRecordVersion::V0_0_3 {
+                    transaction: transaction.transaction.clone(),
+                    message_hash: *transaction.message_hash,
+                    index: transaction.index,
+                },
+            },
+        }
+    }
+}
+
+impl TransactionRecord {
+    fn notify(
+        &self,
+        notify: impl FnOnce(ReplicaTransactionInfoVersions) -> PluginResult<()>,
+    ) -> Result<(), ReplayError> {
+        match &self.version {
+            TransactionRecordVersion::V0_0_1 { transaction } => {
+                let transaction = transaction.to_sanitized_transaction()?;
+                notify(ReplicaTransactionInfoVersions::V0_0_1(
+                    &ReplicaTransactionInfo {
+                        signature: &self.signature,
+                        is_vote: self.is_vote,
+                        transaction: &transaction,
+                        transaction_status_meta: &self.transaction_status_meta,
+                    },
+                ))?
+            }
+            TransactionRecordVersion::V0_0_2 { transaction, index } => {
+                let transaction = transaction.to_sanitized_transaction()?;
+                notify(ReplicaTransactionInfoVersions::V0_0_2(
+                    &ReplicaTransactionInfoV2 {
+                        signature: &self.signature,
+                        is_vote: self.is_vote,
+                        transaction: &transaction,
+                        transaction_status_meta: &self.transaction_status_meta,
+                        index: *index,
+                    },
+                ))?
+            }
+            TransactionRecordVersion::V0_0_3 {
+                transaction,
+                message_hash,
+                index,
+            } => notify(ReplicaTransactionInfoVersions::V0_0_3(
+                &ReplicaTransactionInfoV3 {
+                    signature: &self.signature,
+                    message_hash,
+                    is_vote: self.is_vote,
+                    transaction,
+                    transaction_status_meta: &self.transaction_status_meta,
+                    index: *index,
+                },
+            ))?,
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryRecord {
+    pub slot: Slot,
+    pub index: usize,
+    pub num_hashes: u64,
+    pub hash: Vec<u8>,
+    pub executed_transaction_count: u64,
+    pub version: EntryRecordVersion,
+}
+
+/// The version specific fields of an entry notification
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntryRecordVersion {
+    V0_0_1,
+    V0_0_2 { starting_transaction_index: usize },
+}
+
+impl From<ReplicaEntryInfoVersions<'_>> for EntryRecord {
+    fn from(entry: ReplicaEntryInfoVersions) -> Self {
+        match entry {
+            ReplicaEntryInfoVersions::V0_0_1(entry) => Self {
+                slot: entry.slot,
+                index: entry.index,
+                num_hashes: entry.num_hashes,
+                hash: entry.hash.to_vec(),
+                executed_transaction_count: entry.executed_transaction_count,
+                version: EntryRecordVersion::V0_0_1,
+            },
+            ReplicaEntryInfoVersions::V0_0_2(entry) => Self {
+                slot: entry.slot,
+                index: entry.index,
+                num_hashes: entry.num_hashes,
+                hash: entry.hash.to_vec(),
+                executed_transaction_count: entry.executed_transaction_count,
+                version: EntryRecordVersion::V0_0_2 {
+                    starting_transaction_index: entry.starting_transaction_index,
+                },
+            },
+        }
+    }
+}
+
+impl EntryRecord {
+    fn notify(
+        &self,
+        notify: impl FnOnce(ReplicaEntryInfoVersions) -> PluginResult<()>,
+    ) -> Result<(), ReplayError> {
+        match self.version {
+            EntryRecordVersion::V0_0_1 => {
+                notify(ReplicaEntryInfoVersions::V0_0_1(&ReplicaEntryInfo {
+                    slot: self.slot,
+                    index: self.index,
+                    num_hashes: self.num_hashes,
+                    hash: &self.hash,
+                    executed_transaction_count: self.executed_transaction_count,
+                }))?
+            }
+            EntryRecordVersion::V0_0_2 {
+                starting_transaction_index,
+            } => notify(ReplicaEntryInfoVersions::V0_0_2(&ReplicaEntryInfoV2 {
+                slot: self.slot,
+                index: self.index,
+                num_hashes: self.num_hashes,
+                hash: &self.hash,
+                executed_transaction_count: self.executed_transaction_count,
+                starting_transaction_index,
+            }))?,
+        }
+        Ok(())
+    }
+}
+
+/// A block metadata notification, in each of its versions
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockRecord {
+    V0_0_1 {
+        slot: Slot,
+        blockhash: String,
+        rewards: Vec<Reward>,
+        block_time: Option<UnixTimestamp>,
+        block_height: Option<u64>,
+    },
+    V0_0_2 {
+        parent_slot: Slot,
+        parent_blockhash: String,
+        slot: Slot,
+        blockhash: String,
+        rewards: Vec<Reward>,
+        block_time: Option<UnixTimestamp>,
+        block_height: Option<u64>,
+        executed_transaction_count: u64,
+    },
+    V0_0_3 {
+        parent_slot: Slot,
+        parent_blockhash: String,
+        slot: Slot,
+        blockhash: String,
+        rewards: Vec<Reward>,
+        block_time: Option<UnixTimestamp>,
+        block_height: Option<u64>,
+        executed_transaction_count: u64,
+        entry_count: u64,
+    },
+    V0_0_4 {
+        parent_slot: Slot,
+        parent_blockhash: String,
+        slot: Slot,
+        blockhash: String,
+        rewards: RewardsAndNumPartitions,
+        block_time: Option<UnixTimestamp>,
+        block_height: Option<u64>,
+        executed_transaction_count: u64,
+        entry_count: u64,
+    },
+}
+
+impl From<ReplicaBlockInfoVersions<'_>> for BlockRecord {
+    fn from(block: ReplicaBlockInfoVersions) -> Self {
+        match block {
+            ReplicaBlockInfoVersions::V0_0_1(block) => Self::V0_0_1 {
+                slot: block.slot,
+                blockhash: block.blockhash.to_string(),
+                rewards: block.rewards.to_vec(),
+                block_time: block.block_time,
+                block_height: block.block_height,
+            },
+            ReplicaBlockInfoVersions::V0_0_2(block) => Self::V0_0_2 {
+                parent_slot: block.parent_slot,
+                parent_blockhash: block.parent_blockhash.to_string(),
+                slot: block.slot,
+                blockhash: block.blockhash.to_string(),
+                rewards: block.rewards.to_vec(),
+                block_time: block.block_time,
+                block_height: block.block_height,
+                executed_transaction_count: block.executed_transaction_count,
+            },
+            ReplicaBlockInfoVersions::V0_0_3(block) => Self::V0_0_3 {
+                parent_slot: block.parent_slot,
+                parent_blockhash: block.parent_blockhash.to_string(),
+                slot: block.slot,
+                blockhash: block.blockhash.to_string(),
+                rewards: block.rewards.to_vec(),
+                block_time: block.block_time,
+                block_height: block.block_height,
+                executed_transaction_count: block.executed_transaction_count,
+                entry_count: block.entry_count,
+            },
+            ReplicaBlockInfoVersions::V0_0_4(block) => Self::V0_0_4 {
+                parent_slot: block.parent_slot,
+                parent_blockhash: block.parent_blockhash.to_string(),
+                slot: block.slot,
+                blockhash: block.blockhash.to_string(),
+                rewards: block.rewards.clone(),
+                block_time: block.block_time,
+                block_height: block.block_height,
+                executed_transaction_count: block.executed_transaction_count,
+                entry_count: block.entry_count,
+            },
+        }
+    }
+}
+
+impl BlockRecord {
+    pub fn slot(&self) -> Slot {
+        match self {
+            Self::V0_0_1 { slot, .. }
+            | Self::V0_0_2 { slot, .. }
+            | Self::V0_0_3 { slot, .. }
+            | Self::V0_0_4 { slot, .. } => *slot,
+        }
+    }
+
+    fn notify(
+        &self,
+        notify: impl FnOnce(ReplicaBlockInfoVersions) -> PluginResult<()>,
+    ) -> Result<(), ReplayError> {
+        match self {
+            Self::V0_0_1 {
+                slot,
+                blockhash,
+                rewards,
+                block_time,
+                block_height,
+            } => notify(ReplicaBlockInfoVersions::V0_0_1(&ReplicaBlockInfo {
+                slot: *slot,
+                blockhash,
+                rewards,
+                block_time: *block_time,
+                block_height: *block_height,
+            }))?,
+            Self::V0_0_2 {
+                parent_slot,
+                parent_blockhash,
+                slot,
+                blockhash,
+                rewards,
+                block_time,
+                block_height,
+                executed_transaction_count,
+            } => notify(ReplicaBlockInfoVersions::V0_0_2(&ReplicaBlockInfoV2 {
+                parent_slot: *parent_slot,
+                parent_blockhash,
+                slot: *slot,
+                blockhash,
+                rewards,
+                block_time: *block_time,
+                block_height: *block_height,
+                executed_transaction_count: *executed_transaction_count,
+            }))?,
+            Self::V0_0_3 {
+                parent_slot,
+                parent_blockhash,
+                slot,
+                blockhash,
+                rewards,
+                block_time,
+                block_height,
+                executed_transaction_count,
+                entry_count,
+            } => notify(ReplicaBlockInfoVersions::V0_0_3(&ReplicaBlockInfoV3 {
+                parent_slot: *parent_slot,
+                parent_blockhash,
+                slot: *slot,
+                blockhash,
+                rewards,
+                block_time: *block_time,
+                block_height: *block_height,
+                executed_transaction_count: *executed_transaction_count,
+                entry_count: *entry_count,
+            }))?,
+            Self::V0_0_4 {
+                parent_slot,
+                parent_blockhash,
+                slot,
+                blockhash,
+                rewards,
+                block_time,
+                block_height,
+                executed_transaction_count,
+                entry_count,
+            } => notify(ReplicaBlockInfoVersions::V0_0_4(&ReplicaBlockInfoV4 {
+                parent_slot: *parent_slot,
+                parent_blockhash,
+                slot: *slot,
+                blockhash,
+                rewards,
+                block_time: *block_time,
+                block_height: *block_height,
+                executed_transaction_count: *executed_transaction_count,
+                entry_count: *entry_count,
+            }))?,
+        }
+        Ok(())
+    }
+}
+
+/// (De)serializes `TransactionStatusMeta`, which does not implement serde, through its protobuf
+/// representation
+mod transaction_status_meta {
+    use {
+        prost::Message,
+        serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error},
+        solana_storage_proto::convert::generated,
+        solana_transaction_status::TransactionStatusMeta,
+    };
+
+    pub(super) fn serialize<S: Serializer>(
+        meta: &TransactionStatusMeta,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        generated::TransactionStatusMeta::from(meta.clone())
+            .encode_to_vec()
+            .serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<TransactionStatusMeta, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        let meta =
+            generated::TransactionStatusMeta::decode(bytes.as_slice()).map_err(D::Error::custom)?;
+        TransactionStatusMeta::try_from(meta).map_err(D::Error::custom)
+    }
+}
+
+type PluginResult<T> = agave_geyser_plugin_interface::geyser_plugin_interface::Result<T>;
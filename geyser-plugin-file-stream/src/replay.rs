@@ -0,0 +1,109 @@
+//! Reads a recorded stream back and feeds it to another Geyser plugin
+use {
+    crate::{
+        record::Record,
+        segment::{SegmentError, SegmentReader, StreamIndex},
+    },
+    agave_geyser_plugin_interface::geyser_plugin_interface::{GeyserPlugin, GeyserPluginError},
+    solana_clock::Slot,
+    solana_transaction::TransactionError,
+    std::{
+        iter,
+        ops::RangeInclusive,
+        path::{Path, PathBuf},
+    },
+    thiserror::Error,
+};
+
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    #[error("failed to read stream: {0}")]
+    Segment(#[from] SegmentError),
+
+    #[error("recorded transaction is invalid: {0}")]
+    InvalidTransaction(#[from] TransactionError),
+
+    #[error("plugin failed to handle notification: {0}")]
+    Plugin(#[from] GeyserPluginError),
+}
+
+/// A recorded stream directory
+pub struct StreamReader {
+    dir: PathBuf,
+    index: StreamIndex,
+}
+
+impl StreamReader {
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, SegmentError> {
+        let dir = dir.into();
+        let index = StreamIndex::load(&dir)?;
+        Ok(Self { dir, index })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn index(&self) -> &StreamIndex {
+        &self.index
+    }
+
+    /// Returns the records for `slots`, in the order they were recorded
+    ///
+    /// Records that do not belong to a slot, such as the end of startup notification, are always
+    /// returned.
+    pub fn records(
+        &self,
+        slots: RangeInclusive<Slot>,
+    ) -> impl Iterator<Item = Result<Record, SegmentError>> + use<> {
+        let paths: Vec<_> = self
+            .index
+            .segments
+            .iter()
+            .filter(|segment| segment.overlaps(&slots))
+            .map(|segment| self.dir.join(&segment.file_name))
+            .collect();
+
+        paths
+            .into_iter()
+            .flat_map(
+                |path| -> Box<dyn Iterator<Item = Result<Record, SegmentError>>> {
+                    match SegmentReader::open(&path) {
+                        Ok(reader) => Box::new(reader),
+                        Err(err) => Box::new(iter::once(Err(err))),
+                    }
+                },
+            )
+            .filter(move |record| match record {
+                Ok(record) => record.slot().is_none_or(|slot| slots.contains(&slot)),
+                Err(_) => true,
+            })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayStats {
+    /// Number of records read from the stream
+    pub num_records: u64,
+    /// Number of records the plugin was notified of
+    pub num_notified: u64,
+}
+
+/// Replays the records for `slots` recorded in `dir` into `plugin`
+///
+/// Stops at the first record that cannot be read or that the plugin fails to handle.
+pub fn replay(
+    dir: impl Into<PathBuf>,
+    plugin: &dyn GeyserPlugin,
+    slots: RangeInclusive<Slot>,
+) -> Result<ReplayStats, ReplayError> {
+    let reader = StreamReader::open(dir)?;
+    let mut stats = ReplayStats::default();
+    for record in reader.records(slots) {
+        stats.num_records = stats.num_records.saturating_add(1);
+        if record?.notify(plugin)? {
+            stats.num_notified = stats.num_notified.saturating_add(1);
+        }
+    }
+    Ok(stats)
+}
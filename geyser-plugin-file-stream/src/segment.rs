@@ -0,0 +1,461 @@
+//! Rotating, zstd compressed segment files holding a stream of [`Record`]s
+//!
+//! A stream directory contains the segment files plus an `index.json` that lists them in order
+//! along with the range of slots each one covers. A segment is only added to the index once it is
+//! complete, so a segment that was being written when the validator stopped is simply discarded
+//! the next time the stream is opened.
+//!
+//! The decompressed contents of a segment are a header (`MAGIC` followed by the format version as
+//! a little endian u32) and then the records, each one framed by its length as a little endian u32
+//! and serialized with bincode.
+use {
+    crate::record::Record,
+    log::*,
+    serde::{Deserialize, Serialize},
+    solana_clock::Slot,
+    std::{
+        fs::{self, File},
+        io::{self, BufWriter, Read, Write},
+        ops::RangeInclusive,
+        path::{Path, PathBuf},
+    },
+    thiserror::Error,
+};
+
+const MAGIC: &[u8; 8] = b"AGVGSTRM";
+const FORMAT_VERSION: u32 = 1;
+const INDEX_FILE_NAME: &str = "index.json";
+const SEGMENT_FILE_EXTENSION: &str = "seg.zst";
+const TMP_FILE_EXTENSION: &str = "tmp";
+/// Records larger than this are rejected, both when writing and reading
+const MAX_RECORD_SIZE: usize = 64 * 1024 * 1024;
+
+pub const DEFAULT_SEGMENT_SIZE: u64 = 256 * 1024 * 1024;
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+
+#[derive(Error, Debug)]
+pub enum SegmentError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("invalid stream index: {0}")]
+    Index(#[from] serde_json::Error),
+
+    #[error("{0} is not a stream segment")]
+    InvalidHeader(PathBuf),
+
+    #[error("unsupported segment format version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("record of {0} bytes exceeds the maximum record size")]
+    RecordTooLarge(usize),
+
+    #[error("failed to (de)serialize record: {0}")]
+    Serialization(#[from] bincode::Error),
+}
+
+/// Describes a completed segment
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentInfo {
+    pub id: u64,
+    pub file_name: String,
+    /// Lowest slot of the records in the segment, or None if no record has a slot
+    pub min_slot: Option<Slot>,
+    /// Highest slot of the records in the segment, or None if no record has a slot
+    pub max_slot: Option<Slot>,
+    /// Whether the segment holds records that do not belong to a slot
+    pub has_unslotted_records: bool,
+    pub num_records: u64,
+    pub uncompressed_size: u64,
+    pub compressed_size: u64,
+}
+
+impl SegmentInfo {
+    fn new(id: u64) -> Self {
+        Self {
+            id,
+            file_name: format!("segment-{id:010}.{SEGMENT_FILE_EXTENSION}"),
+            min_slot: None,
+            max_slot: None,
+            has_unslotted_records: false,
+            num_records: 0,
+            uncompressed_size: 0,
+            compressed_size: 0,
+        }
+    }
+
+    /// Returns true if the segment may hold records for any of `slots`
+    ///
+    /// Segments with records that do not belong to a slot always overlap, so that records such as
+    /// the end of startup notification are not lost.
+    pub fn overlaps(&self, slots: &RangeInclusive<Slot>) -> bool {
+        self.has_unslotted_records
+            || self
+                .min_slot
+                .zip(self.max_slot)
+                .is_some_and(|(min_slot, max_slot)| {
+                    min_slot <= *slots.end() && max_slot >= *slots.start()
+                })
+    }
+}
+
+/// The list of completed segments in a stream directory, oldest first
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamIndex {
+    pub segments: Vec<SegmentInfo>,
+}
+
+impl StreamIndex {
+    /// Loads the index of the stream in `dir`, which is empty if none has been written yet
+    pub fn load(dir: &Path) -> Result<Self, SegmentError> {
+        match File::open(dir.join(INDEX_FILE_NAME)) {
+            Ok(file) => Ok(serde_json::from_reader(io::BufReader::new(file))?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Atomically replaces the index of the stream in `dir`
+    fn store(&self, dir: &Path) -> Result<(), SegmentError> {
+        let path = dir.join(INDEX_FILE_NAME);
+        let tmp_path = path.with_extension(TMP_FILE_EXTENSION);
+        let mut file = BufWriter::new(File::create(&tmp_path)?);
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.into_inner().map_err(io::Error::from)?.sync_all()?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SegmentWriterConfig {
+    /// Uncompressed size after which the current segment is completed and a new one started
+    pub segment_size: u64,
+    /// Number of completed segments to keep, the oldest ones are removed beyond that
+    pub max_segments: Option<usize>,
+    pub compression_level: i32,
+}
+
+impl Default for SegmentWriterConfig {
+    fn default() -> Self {
+        Self {
+            segment_size: DEFAULT_SEGMENT_SIZE,
+            max_segments: None,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+        }
+    }
+}
+
+struct OpenSegment {
+    info: SegmentInfo,
+    tmp_path: PathBuf,
+    encoder: zstd::Encoder<'static, BufWriter<File>>,
+}
+
+/// Appends records to the stream in a directory, rotating segments as they fill up
+pub struct SegmentWriter {
+    dir: PathBuf,
+    config: SegmentWriterConfig,
+    index: StreamIndex,
+    next_id: u64,
+    current: Option<OpenSegment>,
+    buffer: Vec<u8>,
+}
+
+impl SegmentWriter {
+    /// Opens the stream in `dir`, creating it if needed, to append to it
+    pub fn new(dir: impl Into<PathBuf>, config: SegmentWriterConfig) -> Result<Self, SegmentError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        let index = StreamIndex::load(&dir)?;
+
+        // Remove what is left of segments that were never completed
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|ext| ext == TMP_FILE_EXTENSION)
+            {
+                info!("removing incomplete stream file {}", path.display());
+                fs::remove_file(path)?;
+            }
+        }
+
+        let next_id = index
+            .segments
+            .last()
+            .map_or(0, |segment| segment.id.saturating_add(1));
+        Ok(Self {
+            dir,
+            config,
+            index,
+            next_id,
+            current: None,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Appends `record` to the stream
+    pub fn write(&mut self, record: &Record) -> Result<(), SegmentError> {
+        self.buffer.clear();
+        bincode::serialize_into(&mut self.buffer, record)?;
+        if self.buffer.len() > MAX_RECORD_SIZE {
+            return Err(SegmentError::RecordTooLarge(self.buffer.len()));
+        }
+
+        // A segment that failed to be written to is dropped, and removed when the stream is next
+        // opened
+        let mut segment = match self.current.take() {
+            Some(segment) => segment,
+            None => self.open_segment()?,
+        };
+        segment
+            .encoder
+            .write_all(&(self.buffer.len() as u32).to_le_bytes())?;
+        segment.encoder.write_all(&self.buffer)?;
+
+        let info = &mut segment.info;
+        match record.slot() {
+            Some(slot) => {
+                info.min_slot = Some(info.min_slot.map_or(slot, |min_slot| min_slot.min(slot)));
+                info.max_slot = Some(info.max_slot.map_or(slot, |max_slot| max_slot.max(slot)));
+            }
+            None => info.has_unslotted_records = true,
+        }
+        info.num_records = info.num_records.saturating_add(1);
+        info.uncompressed_size = info
+            .uncompressed_size
+            .saturating_add(size_of::<u32>().saturating_add(self.buffer.len()) as u64);
+
+        if info.uncompressed_size >= self.config.segment_size {
+            self.complete_segment(segment)
+        } else {
+            self.current = Some(segment);
+            Ok(())
+        }
+    }
+
+    /// Completes the current segment, if any
+    pub fn finish(mut self) -> Result<(), SegmentError> {
+        match self.current.take() {
+            Some(segment) => self.complete_segment(segment),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the index of the completed segments
+    pub fn index(&self) -> &StreamIndex {
+        &self.index
+    }
+
+    fn open_segment(&mut self) -> Result<OpenSegment, SegmentError> {
+        let info = SegmentInfo::new(self.next_id);
+        self.next_id = self.next_id.saturating_add(1);
+        let tmp_path = self
+            .dir
+            .join(&info.file_name)
+            .with_extension(TMP_FILE_EXTENSION);
+        let file = BufWriter::new(File::create(&tmp_path)?);
+        let mut encoder = zstd::Encoder::new(file, self.config.compression_level)?;
+        encoder.write_all(MAGIC)?;
+        encoder.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        Ok(OpenSegment {
+            info,
+            tmp_path,
+            encoder,
+        })
+    }
+
+    fn complete_segment(&mut self, segment: OpenSegment) -> Result<(), SegmentError> {
+        let OpenSegment {
+            mut info,
+            tmp_path,
+            encoder,
+        } = segment;
+
+        let file = encoder.finish()?.into_inner().map_err(io::Error::from)?;
+        file.sync_all()?;
+        info.compressed_size = file.metadata()?.len();
+        drop(file);
+        fs::rename(tmp_path, self.dir.join(&info.file_name))?;
+
+        self.index.segments.push(info);
+        let num_removed = self.config.max_segments.map_or(0, |max_segments| {
+            self.index.segments.len().saturating_sub(max_segments)
+        });
+        let removed: Vec<_> = self.index.segments.drain(..num_removed).collect();
+        self.index.store(&self.dir)?;
+
+        // Only remove the files once the index no longer references them
+        for segment in removed {
+            let path = self.dir.join(&segment.file_name);
+            if let Err(err) = fs::remove_file(&path) {
+                warn!("failed to remove stream segment {}: {err}", path.display());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Iterates over the records of a single segment file
+pub struct SegmentReader {
+    decoder: zstd::Decoder<'static, io::BufReader<File>>,
+    buffer: Vec<u8>,
+    done: bool,
+}
+
+impl SegmentReader {
+    pub fn open(path: &Path) -> Result<Self, SegmentError> {
+        let mut decoder = zstd::Decoder::new(File::open(path)?)?;
+        let mut magic = [0u8; MAGIC.len()];
+        let mut version = [0u8; size_of::<u32>()];
+        decoder
+            .read_exact(&mut magic)
+            .and_then(|()| decoder.read_exact(&mut version))
+            .map_err(|_| SegmentError::InvalidHeader(path.to_path_buf()))?;
+        if &magic != MAGIC {
+            return Err(SegmentError::InvalidHeader(path.to_path_buf()));
+        }
+        let version = u32::from_le_bytes(version);
+        if version != FORMAT_VERSION {
+            return Err(SegmentError::UnsupportedVersion(version));
+        }
+        Ok(Self {
+            decoder,
+            buffer: Vec::new(),
+            done: false,
+        })
+    }
+
+    fn read_record(&mut self) -> Result<Option<Record>, SegmentError> {
+        // The end of the segment is only valid on a record boundary
+        let mut len = [0u8; size_of::<u32>()];
+        let mut filled = 0;
+        while filled < len.len() {
+            match self.decoder.read(&mut len[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+                Ok(n) => filled = filled.saturating_add(n),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let len = u32::from_le_bytes(len) as usize;
+        if len > MAX_RECORD_SIZE {
+            return Err(SegmentError::RecordTooLarge(len));
+        }
+        self.buffer.resize(len, 0);
+        self.decoder.read_exact(&mut self.buffer)?;
+        Ok(Some(bincode::deserialize(&self.buffer)?))
+    }
+}
+
+impl Iterator for SegmentReader {
+    type Item = Result<Record, SegmentError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.read_record().transpose();
+        self.done = !matches!(result, Some(Ok(_)));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::record::SlotStatusRecord, tempfile::TempDir};
+
+    fn slot_status(slot: Slot) -> Record {
+        Record::SlotStatus {
+            slot,
+            parent: slot.checked_sub(1),
+            status: SlotStatusRecord::Rooted,
+        }
+    }
+
+    fn read_segments(dir: &Path, index: &StreamIndex) -> Vec<Record> {
+        index
+            .segments
+            .iter()
+            .flat_map(|segment| SegmentReader::open(&dir.join(&segment.file_name)).unwrap())
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_segment_rotation_and_retention() {
+        let dir = TempDir::new().unwrap();
+        let config = SegmentWriterConfig {
+            // Every record completes its segment
+            segment_size: 1,
+            max_segments: Some(3),
+            ..SegmentWriterConfig::default()
+        };
+
+        let mut writer = SegmentWriter::new(dir.path(), config.clone()).unwrap();
+        for slot in 0..5 {
+            writer.write(&slot_status(slot)).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let index = StreamIndex::load(dir.path()).unwrap();
+        assert_eq!(
+            index
+                .segments
+                .iter()
+                .map(|segment| (segment.id, segment.min_slot, segment.max_slot))
+                .collect::<Vec<_>>(),
+            vec![
+                (2, Some(2), Some(2)),
+                (3, Some(3), Some(3)),
+                (4, Some(4), Some(4))
+            ],
+        );
+        assert!(!index.segments[0].overlaps(&(3..=4)));
+        assert!(index.segments[0].overlaps(&(0..=2)));
+        assert_eq!(
+            read_segments(dir.path(), &index),
+            (2..5).map(slot_status).collect::<Vec<_>>()
+        );
+        // Removed segments are gone from the directory, leaving the index and 3 segments
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 4);
+
+        // Reopening resumes after the last segment
+        let mut writer = SegmentWriter::new(dir.path(), config).unwrap();
+        writer.write(&slot_status(5)).unwrap();
+        writer.finish().unwrap();
+        let index = StreamIndex::load(dir.path()).unwrap();
+        assert_eq!(index.segments.last().unwrap().id, 5);
+    }
+
+    #[test]
+    fn test_incomplete_segment_is_discarded() {
+        let dir = TempDir::new().unwrap();
+        let mut writer = SegmentWriter::new(dir.path(), SegmentWriterConfig::default()).unwrap();
+        writer.write(&slot_status(1)).unwrap();
+        writer.write(&Record::EndOfStartup).unwrap();
+        writer.finish().unwrap();
+
+        // Simulate a validator that stopped while writing a segment
+        let mut writer = SegmentWriter::new(dir.path(), SegmentWriterConfig::default()).unwrap();
+        writer.write(&slot_status(2)).unwrap();
+        drop(writer);
+
+        let writer = SegmentWriter::new(dir.path(), SegmentWriterConfig::default()).unwrap();
+        let index = writer.index().clone();
+        assert_eq!(index.segments.len(), 1);
+        assert_eq!(index.segments[0].num_records, 2);
+        assert!(index.segments[0].overlaps(&(1..=1)));
+        // The end of startup record belongs to every range
+        assert!(index.segments[0].overlaps(&(2..=3)));
+        assert_eq!(
+            read_segments(dir.path(), &index),
+            vec![slot_status(1), Record::EndOfStartup]
+        );
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+}
@@ -134,7 +134,7 @@ impl AccountsUpdateNotifierImpl {
             return;
         }
         for plugin in plugin_manager.plugins.iter() {
-            if !plugin.account_data_notifications_enabled() {
+            if !plugin.account_data_notifications_enabled() || !plugin.account_matches(&account) {
                 continue;
             }
             match plugin
@@ -173,7 +173,7 @@ impl AccountsUpdateNotifierImpl {
             return;
         }
         for plugin in plugin_manager.plugins.iter() {
-            if !plugin.account_data_notifications_enabled() {
+            if !plugin.account_data_notifications_enabled() || !plugin.account_matches(&account) {
                 continue;
             }
             match plugin.update_account_for_bank(
@@ -207,7 +207,10 @@ impl AccountsUpdateNotifierImpl {
 mod tests {
     use {
         super::*,
-        crate::geyser_plugin_manager::{GeyserPluginManager, LoadedGeyserPlugin},
+        crate::{
+            geyser_plugin_manager::{GeyserPluginManager, LoadedGeyserPlugin},
+            plugin_filter::PluginFilter,
+        },
         agave_geyser_plugin_interface::geyser_plugin_interface::{
             GeyserPlugin, ReplicaAccountInfoVersions,
         },
@@ -312,6 +315,36 @@ mod tests {
         assert!(disabled_bank_ids.lock().unwrap().is_empty());
     }
 
+    #[test]
+    fn test_notify_account_update_skips_accounts_filtered_out() {
+        let account_update_count = Arc::new(AtomicUsize::new(0));
+        let plugin = loaded_test_plugin(TestAccountPlugin {
+            name: "filtered",
+            account_updates_enabled: true,
+            account_update_count: account_update_count.clone(),
+            account_update_bank_ids: Arc::new(Mutex::new(Vec::new())),
+        });
+        let owner = Pubkey::new_unique();
+        plugin.set_filter(
+            PluginFilter::from_value(serde_json::json!({
+                "accounts": { "owners": [owner.to_string()] },
+            }))
+            .unwrap(),
+        );
+        let plugin_manager = Arc::new(ArcSwap::from(Arc::new(GeyserPluginManager {
+            plugins: vec![plugin],
+        })));
+        let notifier = AccountsUpdateNotifierImpl::new(plugin_manager, false);
+
+        let other_account = AccountSharedData::new(1, 0, &Pubkey::new_unique());
+        notifier.notify_account_update(42, 9, &other_account, &None, &Pubkey::new_unique(), 7);
+        assert_eq!(account_update_count.load(Ordering::Relaxed), 0);
+
+        let owned_account = AccountSharedData::new(1, 0, &owner);
+        notifier.notify_account_update(42, 9, &owned_account, &None, &Pubkey::new_unique(), 8);
+        assert_eq!(account_update_count.load(Ordering::Relaxed), 1);
+    }
+
     #[test]
     fn test_notify_account_restore_from_snapshot_has_no_bank_id() {
         let account_update_count = Arc::new(AtomicUsize::new(0));
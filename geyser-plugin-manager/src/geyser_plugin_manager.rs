@@ -1,6 +1,10 @@
 use {
-    crate::geyser_plugin_service::ARC_TRY_UNWRAP_ATTEMPT_SLEEP_DURATION,
-    agave_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
+    crate::{
+        geyser_plugin_service::ARC_TRY_UNWRAP_ATTEMPT_SLEEP_DURATION, plugin_filter::PluginFilter,
+    },
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, ReplicaAccountInfoV3, ReplicaTransactionInfoV3,
+    },
     arc_swap::ArcSwap,
     jsonrpc_core::{ErrorCode, Result as JsonRpcResult},
     libloading::Library,
@@ -17,6 +21,7 @@ use {
 #[derive(Debug)]
 pub struct LoadedGeyserPlugin {
     name: String,
+    filter: ArcSwap<PluginFilter>,
     plugin: Box<dyn GeyserPlugin>,
     // NOTE: While we do not access the library, the plugin we have loaded most
     // certainly does. To ensure we don't SIGSEGV we must declare the library
@@ -34,6 +39,7 @@ impl LoadedGeyserPlugin {
     pub fn new(library: Library, plugin: Box<dyn GeyserPlugin>, name: Option<String>) -> Self {
         Self {
             name: name.unwrap_or_else(|| plugin.name().to_owned()),
+            filter: ArcSwap::default(),
             plugin,
             library,
         }
@@ -42,6 +48,21 @@ impl LoadedGeyserPlugin {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Replaces the filter applied to the notifications sent to this plugin
+    pub fn set_filter(&self, filter: PluginFilter) {
+        self.filter.store(Arc::new(filter));
+    }
+
+    /// Returns true if the account update passes this plugin's filter
+    pub fn account_matches(&self, account: &ReplicaAccountInfoV3) -> bool {
+        self.filter.load().account_matches(account)
+    }
+
+    /// Returns true if the transaction passes this plugin's filter
+    pub fn transaction_matches(&self, transaction: &ReplicaTransactionInfoV3) -> bool {
+        self.filter.load().transaction_matches(transaction)
+    }
 }
 
 impl Deref for LoadedGeyserPlugin {
@@ -319,6 +340,34 @@ impl GeyserPluginManager {
         Ok(())
     }
 
+    /// Admin RPC request handler
+    ///
+    /// Re-reads the filter from `config_file` and applies it to the plugin named `name`. Unlike
+    /// `reload_plugin`, the plugin itself keeps running and is not notified.
+    pub(crate) fn reload_plugin_filter(&self, name: &str, config_file: &str) -> JsonRpcResult<()> {
+        let Some(plugin) = self.plugins.iter().find(|plugin| plugin.name().eq(name)) else {
+            return Err(jsonrpc_core::error::Error {
+                code: ErrorCode::InvalidRequest,
+                message: String::from(
+                    "The plugin you requested to reload the filter of is not loaded",
+                ),
+                data: None,
+            });
+        };
+
+        let filter = load_plugin_filter_from_config(Path::new(config_file)).map_err(|err| {
+            jsonrpc_core::Error {
+                code: ErrorCode::InvalidRequest,
+                message: err.to_string(),
+                data: None,
+            }
+        })?;
+        plugin.set_filter(filter);
+        info!("Reloaded filter of plugin {name} from {config_file}");
+
+        Ok(())
+    }
+
     /// Blocks the thread and unloads a given plugin.
     /// This synchronously and explicitly waits to hold the last Arc reference
     /// to the plugin before allowing it to be dropped and unloaded. This ensures
@@ -371,6 +420,11 @@ pub enum GeyserPluginManagerRequest {
     ListPlugins {
         response_sender: OneShotSender<JsonRpcResult<Vec<String>>>,
     },
+    ReloadPluginFilter {
+        name: String,
+        config_file: String,
+        response_sender: OneShotSender<JsonRpcResult<()>>,
+    },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -398,6 +452,36 @@ pub enum GeyserPluginManagerError {
 
     #[error("The GeyserPlugin on_load method failed (error: {0})")]
     PluginStartError(String),
+
+    #[error("Invalid plugin filter (error: {0})")]
+    InvalidPluginFilter(String),
+}
+
+/// Reads and parses a plugin's Json5 config file
+fn read_plugin_config(
+    geyser_plugin_config_file: &Path,
+) -> Result<serde_json::Value, GeyserPluginManagerError> {
+    let contents = std::fs::read_to_string(geyser_plugin_config_file).map_err(|err| {
+        GeyserPluginManagerError::CannotReadConfigFile(format!(
+            "Failed to read the plugin config file {geyser_plugin_config_file:?}, error: {err:?}"
+        ))
+    })?;
+
+    json5::from_str(&contents).map_err(|err| {
+        GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+            "The config file {geyser_plugin_config_file:?} is not in a valid Json5 format, error: \
+             {err:?}"
+        ))
+    })
+}
+
+/// Reads the filter of a plugin from the `filter` field of its config file
+fn load_plugin_filter_from_config(
+    geyser_plugin_config_file: &Path,
+) -> Result<PluginFilter, GeyserPluginManagerError> {
+    let config = read_plugin_config(geyser_plugin_config_file)?;
+    PluginFilter::from_plugin_config(&config)
+        .map_err(|err| GeyserPluginManagerError::InvalidPluginFilter(err.to_string()))
 }
 
 /// # Safety
@@ -442,6 +526,9 @@ pub(crate) fn load_plugin_from_config(
         }
     };
 
+    let filter = PluginFilter::from_plugin_config(&result)
+        .map_err(|err| GeyserPluginManagerError::InvalidPluginFilter(err.to_string()))?;
+
     let libpath = result["libpath"]
         .as_str()
         .ok_or(GeyserPluginManagerError::LibPathNotSet)?;
@@ -471,10 +558,9 @@ pub(crate) fn load_plugin_from_config(
         let plugin_raw = constructor();
         (Box::from_raw(plugin_raw), lib)
     };
-    Ok((
-        LoadedGeyserPlugin::new(lib, plugin, plugin_name),
-        config_file,
-    ))
+    let plugin = LoadedGeyserPlugin::new(lib, plugin, plugin_name);
+    plugin.set_filter(filter);
+    Ok((plugin, config_file))
 }
 
 #[cfg(test)]
@@ -519,8 +605,9 @@ mod tests {
             geyser_plugin_service::ARC_TRY_UNWRAP_ATTEMPT_SLEEP_DURATION,
         },
         agave_geyser_plugin_interface::geyser_plugin_interface::{
-            GeyserPlugin, ReplicaDeshredTransactionInfo, ReplicaDeshredTransactionInfoVersions,
-            ReplicaDeshredUpdateParentInfoVersions, Result as PluginResult,
+            GeyserPlugin, ReplicaAccountInfoV3, ReplicaDeshredTransactionInfo,
+            ReplicaDeshredTransactionInfoVersions, ReplicaDeshredUpdateParentInfoVersions,
+            Result as PluginResult,
         },
         arc_swap::ArcSwap,
         libloading::Library,
@@ -784,6 +871,71 @@ mod tests {
         assert_eq!(plugin_manager.load().plugins.len(), 0);
     }
 
+    #[test]
+    fn test_reload_plugin_filter() {
+        let plugin_manager = GeyserPluginManager {
+            plugins: vec![Arc::new(
+                dummy_plugin_and_library(TestPlugin::default(), DUMMY_CONFIG).0,
+            )],
+        };
+        let pubkey = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let account = ReplicaAccountInfoV3 {
+            pubkey: pubkey.as_ref(),
+            lamports: 1,
+            owner: owner.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data: &[],
+            write_version: 0,
+            txn: None,
+        };
+        // Without a filter, everything is sent to the plugin
+        assert!(plugin_manager.plugins[0].account_matches(&account));
+
+        let config_file = tempfile::NamedTempFile::new().unwrap();
+        let config_path = config_file.path().to_str().unwrap();
+        std::fs::write(
+            config_file.path(),
+            format!(
+                r#"{{ libpath: "lib.so", filter: {{ accounts: {{ owners: ["{}"] }} }} }}"#,
+                Pubkey::new_unique()
+            ),
+        )
+        .unwrap();
+
+        let reload_result = plugin_manager.reload_plugin_filter("wrong_name", config_path);
+        assert_eq!(
+            reload_result.unwrap_err().message,
+            "The plugin you requested to reload the filter of is not loaded"
+        );
+
+        plugin_manager
+            .reload_plugin_filter(DUMMY_NAME, config_path)
+            .unwrap();
+        assert!(!plugin_manager.plugins[0].account_matches(&account));
+
+        // An invalid filter is rejected and the current one is kept
+        std::fs::write(
+            config_file.path(),
+            r#"{ filter: { accounts: { owners: ["invalid"] } } }"#,
+        )
+        .unwrap();
+        assert!(
+            plugin_manager
+                .reload_plugin_filter(DUMMY_NAME, config_path)
+                .is_err()
+        );
+        assert!(!plugin_manager.plugins[0].account_matches(&account));
+
+        // Removing the filter from the config lets everything through again
+        std::fs::write(config_file.path(), r#"{ libpath: "lib.so" }"#).unwrap();
+        plugin_manager
+            .reload_plugin_filter(DUMMY_NAME, config_path)
+            .unwrap();
+        assert!(plugin_manager.plugins[0].account_matches(&account));
+    }
+
     #[test]
     fn test_deshred_transaction_notifications_enabled() {
         let empty_manager = GeyserPluginManager::default();
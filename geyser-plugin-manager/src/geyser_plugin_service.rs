@@ -316,6 +316,19 @@ impl GeyserPluginService {
                                     .send(unload_result)
                                     .expect("Admin rpc service will be waiting for response");
                             }
+
+                            GeyserPluginManagerRequest::ReloadPluginFilter {
+                                ref name,
+                                ref config_file,
+                                response_sender,
+                            } => {
+                                let reload_result = plugin_manager
+                                    .load()
+                                    .reload_plugin_filter(name, config_file);
+                                response_sender
+                                    .send(reload_result)
+                                    .expect("Admin rpc service will be waiting for response");
+                            }
                         }
                     }
 
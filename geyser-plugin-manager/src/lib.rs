@@ -7,6 +7,7 @@ pub mod deshred_transaction_notifier;
 pub mod entry_notifier;
 pub mod geyser_plugin_manager;
 pub mod geyser_plugin_service;
+pub mod plugin_filter;
 pub mod slot_status_notifier;
 pub mod slot_status_observer;
 pub mod transaction_notifier;
@@ -0,0 +1,384 @@
+//! Filtering of the notifications sent to a plugin
+//!
+//! A plugin may declare a `filter` object in its config file, which the manager evaluates before
+//! calling into the plugin, so that the plugin only receives the updates it is interested in:
+//!
+//! ```json5
+//! {
+//!   libpath: "libmy_plugin.so",
+//!   filter: {
+//!     accounts: {
+//!       // An account is selected if its pubkey or its owner is listed.
+//!       // Leaving both lists empty selects every account.
+//!       owners: ["TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"],
+//!       pubkeys: [],
+//!       // All of these must match as well, same as the `filters` of `getProgramAccounts`
+//!       filters: [{ dataSize: 165 }, { memcmp: { offset: 0, bytes: "3Mc6vR" } }],
+//!     },
+//!     transactions: {
+//!       vote: false,
+//!       nonVote: true,
+//!       failed: false,
+//!       // A transaction is selected if it references any of these accounts.
+//!       // Leaving it empty selects every transaction.
+//!       accountInclude: [],
+//!     },
+//!   },
+//! }
+//! ```
+//!
+//! A missing `accounts` or `transactions` section lets every account or transaction through.
+
+use {
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        ReplicaAccountInfoV3, ReplicaTransactionInfoV3,
+    },
+    serde::Deserialize,
+    solana_pubkey::{ParsePubkeyError, Pubkey},
+    solana_rpc_client_types::filter::{Memcmp, RpcFilterError, RpcFilterType},
+    std::{collections::HashSet, str::FromStr},
+    thiserror::Error,
+};
+
+#[derive(Error, Debug)]
+pub enum PluginFilterError {
+    #[error("invalid filter: {0}")]
+    InvalidFormat(#[from] serde_json::Error),
+
+    #[error("invalid pubkey {0}: {1}")]
+    InvalidPubkey(String, ParsePubkeyError),
+
+    #[error("invalid account filter: {0}")]
+    InvalidAccountFilter(#[from] RpcFilterError),
+
+    #[error("unsupported account filter: {0:?}")]
+    UnsupportedAccountFilter(RpcFilterType),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct PluginFilterConfig {
+    accounts: Option<AccountFilterConfig>,
+    transactions: Option<TransactionFilterConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+struct AccountFilterConfig {
+    owners: Vec<String>,
+    pubkeys: Vec<String>,
+    filters: Vec<RpcFilterType>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+struct TransactionFilterConfig {
+    vote: bool,
+    non_vote: bool,
+    failed: bool,
+    account_include: Vec<String>,
+}
+
+impl Default for TransactionFilterConfig {
+    fn default() -> Self {
+        Self {
+            vote: true,
+            non_vote: true,
+            failed: true,
+            account_include: Vec::new(),
+        }
+    }
+}
+
+/// The filter applied to the account and transaction notifications of a single plugin
+///
+/// The default filter lets everything through.
+#[derive(Debug, Default)]
+pub struct PluginFilter {
+    accounts: Option<AccountFilter>,
+    transactions: Option<TransactionFilter>,
+}
+
+#[derive(Debug)]
+struct AccountFilter {
+    owners: HashSet<Pubkey>,
+    pubkeys: HashSet<Pubkey>,
+    data_filters: Vec<AccountDataFilter>,
+}
+
+#[derive(Debug)]
+enum AccountDataFilter {
+    DataSize(u64),
+    Memcmp(Memcmp),
+}
+
+#[derive(Debug)]
+struct TransactionFilter {
+    vote: bool,
+    non_vote: bool,
+    failed: bool,
+    account_include: HashSet<Pubkey>,
+}
+
+impl PluginFilter {
+    /// Builds the filter from the `filter` field of a parsed plugin config file
+    ///
+    /// A config without a `filter` field results in the default filter.
+    pub fn from_plugin_config(config: &serde_json::Value) -> Result<Self, PluginFilterError> {
+        match config.get("filter") {
+            None | Some(serde_json::Value::Null) => Ok(Self::default()),
+            Some(filter) => Self::from_value(filter.clone()),
+        }
+    }
+
+    /// Builds the filter from the value of a `filter` field
+    pub fn from_value(value: serde_json::Value) -> Result<Self, PluginFilterError> {
+        let config: PluginFilterConfig = serde_json::from_value(value)?;
+        Ok(Self {
+            accounts: config.accounts.map(AccountFilter::new).transpose()?,
+            transactions: config
+                .transactions
+                .map(TransactionFilter::new)
+                .transpose()?,
+        })
+    }
+
+    /// Returns true if the account update should be sent to the plugin
+    pub fn account_matches(&self, account: &ReplicaAccountInfoV3) -> bool {
+        self.accounts
+            .as_ref()
+            .is_none_or(|filter| filter.matches(account))
+    }
+
+    /// Returns true if the transaction should be sent to the plugin
+    pub fn transaction_matches(&self, transaction: &ReplicaTransactionInfoV3) -> bool {
+        self.transactions
+            .as_ref()
+            .is_none_or(|filter| filter.matches(transaction))
+    }
+}
+
+impl AccountFilter {
+    fn new(config: AccountFilterConfig) -> Result<Self, PluginFilterError> {
+        let data_filters = config
+            .filters
+            .into_iter()
+            .map(|filter| -> Result<_, PluginFilterError> {
+                filter.verify()?;
+                match filter {
+                    RpcFilterType::DataSize(size) => Ok(AccountDataFilter::DataSize(size)),
+                    RpcFilterType::Memcmp(mut memcmp) => {
+                        // Decode once here instead of on every account update
+                        memcmp.convert_to_raw_bytes()?;
+                        Ok(AccountDataFilter::Memcmp(memcmp))
+                    }
+                    filter => Err(PluginFilterError::UnsupportedAccountFilter(filter)),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            owners: parse_pubkeys(config.owners)?,
+            pubkeys: parse_pubkeys(config.pubkeys)?,
+            data_filters,
+        })
+    }
+
+    fn matches(&self, account: &ReplicaAccountInfoV3) -> bool {
+        let selected = (self.owners.is_empty() && self.pubkeys.is_empty())
+            || contains_pubkey(&self.pubkeys, account.pubkey)
+            || contains_pubkey(&self.owners, account.owner);
+        selected
+            && self.data_filters.iter().all(|filter| match filter {
+                AccountDataFilter::DataSize(size) => account.data.len() as u64 == *size,
+                AccountDataFilter::Memcmp(memcmp) => memcmp.bytes_match(account.data),
+            })
+    }
+}
+
+impl TransactionFilter {
+    fn new(config: TransactionFilterConfig) -> Result<Self, PluginFilterError> {
+        Ok(Self {
+            vote: config.vote,
+            non_vote: config.non_vote,
+            failed: config.failed,
+            account_include: parse_pubkeys(config.account_include)?,
+        })
+    }
+
+    fn matches(&self, transaction: &ReplicaTransactionInfoV3) -> bool {
+        let kind_selected = if transaction.is_vote {
+            self.vote
+        } else {
+            self.non_vote
+        };
+        if !kind_selected || (!self.failed && transaction.transaction_status_meta.status.is_err()) {
+            return false;
+        }
+        if self.account_include.is_empty() {
+            return true;
+        }
+        let loaded_addresses = &transaction.transaction_status_meta.loaded_addresses;
+        transaction
+            .transaction
+            .message
+            .static_account_keys()
+            .iter()
+            .chain(&loaded_addresses.writable)
+            .chain(&loaded_addresses.readonly)
+            .any(|key| self.account_include.contains(key))
+    }
+}
+
+fn parse_pubkeys(pubkeys: Vec<String>) -> Result<HashSet<Pubkey>, PluginFilterError> {
+    pubkeys
+        .into_iter()
+        .map(|pubkey| {
+            Pubkey::from_str(&pubkey).map_err(|err| PluginFilterError::InvalidPubkey(pubkey, err))
+        })
+        .collect()
+}
+
+fn contains_pubkey(pubkeys: &HashSet<Pubkey>, pubkey: &[u8]) -> bool {
+    Pubkey::try_from(pubkey).is_ok_and(|pubkey| pubkeys.contains(&pubkey))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        serde_json::json,
+        solana_hash::Hash,
+        solana_message::{Instruction, Message, VersionedMessage},
+        solana_signature::Signature,
+        solana_transaction::{TransactionError, versioned::VersionedTransaction},
+        solana_transaction_status::TransactionStatusMeta,
+    };
+
+    fn account_info<'a>(
+        pubkey: &'a Pubkey,
+        owner: &'a Pubkey,
+        data: &'a [u8],
+    ) -> ReplicaAccountInfoV3<'a> {
+        ReplicaAccountInfoV3 {
+            pubkey: pubkey.as_ref(),
+            lamports: 1,
+            owner: owner.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data,
+            write_version: 0,
+            txn: None,
+        }
+    }
+
+    fn transaction_with_account(account: Pubkey) -> VersionedTransaction {
+        VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::Legacy(Message::new(
+                &[Instruction::new_with_bytes(
+                    Pubkey::new_unique(),
+                    &[],
+                    Vec::new(),
+                )],
+                Some(&account),
+            )),
+        }
+    }
+
+    #[test]
+    fn test_default_filter_matches_everything() {
+        let filter = PluginFilter::from_plugin_config(&json!({ "libpath": "lib.so" })).unwrap();
+        let (pubkey, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(filter.account_matches(&account_info(&pubkey, &owner, &[])));
+
+        let transaction = transaction_with_account(Pubkey::new_unique());
+        let status_meta = TransactionStatusMeta::default();
+        assert!(filter.transaction_matches(&ReplicaTransactionInfoV3 {
+            index: 0,
+            message_hash: &Hash::default(),
+            signature: &transaction.signatures[0],
+            is_vote: true,
+            transaction: &transaction,
+            transaction_status_meta: &status_meta,
+        }));
+    }
+
+    #[test]
+    fn test_account_filter() {
+        let (selected_owner, selected_pubkey) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let filter = PluginFilter::from_value(json!({
+            "accounts": {
+                "owners": [selected_owner.to_string()],
+                "pubkeys": [selected_pubkey.to_string()],
+                "filters": [
+                    { "dataSize": 4 },
+                    { "memcmp": { "offset": 1, "bytes": bs58::encode([2u8, 3]).into_string() } },
+                ],
+            },
+        }))
+        .unwrap();
+
+        let other = Pubkey::new_unique();
+        assert!(filter.account_matches(&account_info(&other, &selected_owner, &[1, 2, 3, 4])));
+        assert!(filter.account_matches(&account_info(&selected_pubkey, &other, &[1, 2, 3, 4])));
+        assert!(!filter.account_matches(&account_info(&other, &other, &[1, 2, 3, 4])));
+        // data size mismatch
+        assert!(!filter.account_matches(&account_info(&other, &selected_owner, &[1, 2, 3])));
+        // memcmp mismatch
+        assert!(!filter.account_matches(&account_info(&other, &selected_owner, &[1, 2, 4, 4])));
+    }
+
+    #[test]
+    fn test_transaction_filter() {
+        let included = Pubkey::new_unique();
+        let filter = PluginFilter::from_value(json!({
+            "transactions": {
+                "vote": false,
+                "failed": false,
+                "accountInclude": [included.to_string()],
+            },
+        }))
+        .unwrap();
+
+        let included_transaction = transaction_with_account(included);
+        let other_transaction = transaction_with_account(Pubkey::new_unique());
+        let succeeded = TransactionStatusMeta::default();
+        let failed = TransactionStatusMeta {
+            status: Err(TransactionError::AccountInUse),
+            ..TransactionStatusMeta::default()
+        };
+        let matches = |transaction: &VersionedTransaction, is_vote, meta| {
+            filter.transaction_matches(&ReplicaTransactionInfoV3 {
+                index: 0,
+                message_hash: &Hash::default(),
+                signature: &transaction.signatures[0],
+                is_vote,
+                transaction,
+                transaction_status_meta: meta,
+            })
+        };
+
+        assert!(matches(&included_transaction, false, &succeeded));
+        assert!(!matches(&included_transaction, true, &succeeded));
+        assert!(!matches(&included_transaction, false, &failed));
+        assert!(!matches(&other_transaction, false, &succeeded));
+    }
+
+    #[test]
+    fn test_invalid_filter() {
+        assert!(matches!(
+            PluginFilter::from_value(json!({ "accounts": { "owners": ["invalid"] } })),
+            Err(PluginFilterError::InvalidPubkey(..))
+        ));
+        assert!(matches!(
+            PluginFilter::from_value(json!({ "accounts": { "filters": ["tokenAccountState"] } })),
+            Err(PluginFilterError::UnsupportedAccountFilter(
+                RpcFilterType::TokenAccountState
+            ))
+        ));
+        assert!(matches!(
+            PluginFilter::from_value(json!({ "blocks": {} })),
+            Err(PluginFilterError::InvalidFormat(_))
+        ));
+    }
+}
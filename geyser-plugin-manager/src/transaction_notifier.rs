@@ -51,7 +51,9 @@ impl TransactionNotifier for TransactionNotifierImpl {
         }
 
         for plugin in plugin_manager.plugins.iter() {
-            if !plugin.transaction_notifications_enabled() {
+            if !plugin.transaction_notifications_enabled()
+                || !plugin.transaction_matches(&transaction_log_info)
+            {
                 continue;
             }
             match plugin.notify_transaction_for_bank(
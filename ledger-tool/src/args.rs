@@ -147,6 +147,18 @@ pub fn accounts_db_args<'a, 'b>() -> Box<[Arg<'a, 'b>]> {
             .takes_value(true)
             .help("The smallest size of ideal ancient storage.")
             .hidden(hidden_unless_forced()),
+        Arg::with_name("accounts_db_ancient_storage_format")
+            .long("accounts-db-ancient-storage-format")
+            .value_name("FORMAT")
+            .takes_value(true)
+            .possible_values(&["append-vec", "compressed"])
+            .help(
+                "Storage format of the storages written when packing ancient slots. \"compressed\" \
+                 stores accounts in read-only, block-compressed files with a pubkey index, trading \
+                 CPU on account loads for disk space. Existing storages of either format are \
+                 always readable.",
+            )
+            .hidden(hidden_unless_forced()),
         Arg::with_name("accounts_db_max_ancient_storages")
             .long("accounts-db-max-ancient-storages")
             .value_name("USIZE")
@@ -361,6 +373,18 @@ pub fn get_accounts_db_config(
         })
         .unwrap_or_default();
 
+    let ancient_accounts_file_provider = arg_matches
+        .value_of("accounts_db_ancient_storage_format")
+        .map(|format| match format {
+            "append-vec" => AccountsFileProvider::AppendVec,
+            "compressed" => AccountsFileProvider::Compressed,
+            _ => {
+                // clap will enforce one of the above values is given
+                unreachable!("invalid value given to accounts_db_ancient_storage_format")
+            }
+        })
+        .unwrap_or_default();
+
     AccountsDbConfig {
         index: Some(accounts_index_config),
         account_indexes: None,
@@ -386,6 +410,8 @@ pub fn get_accounts_db_config(
         num_background_threads: None,
         num_foreground_threads: None,
         accounts_file_provider: AccountsFileProvider::AppendVec,
+        ancient_accounts_file_provider,
+        historical_retention_slots: None,
     }
 }
 
@@ -0,0 +1,751 @@
+//! The `banking-trace` subcommand
+//!
+//! `banking-trace export` converts the banking trace event files of a ledger, which are only
+//! readable by the banking simulator, into two [Arrow IPC files] that standard data tools (pandas,
+//! polars, DuckDB, ...) can load. Rows are written in trace order and `event_index` orders the
+//! rows of both files relative to each other. Keys and hashes are base58 encoded.
+//!
+//! `transactions.arrow` has a row per traced packet. The decoded transaction columns are null
+//! when the packet was discarded or does not hold a valid transaction, and the compute budget
+//! columns are also null when the transaction's compute budget instructions are invalid.
+//!
+//! | Column | Type | Description |
+//! |---|---|---|
+//! | `event_time` | timestamp (ns, UTC) | When the packet batch was sent to banking stage |
+//! | `event_index` | uint64 | Position of the packet batch event in the trace |
+//! | `channel` | utf8 | `non_vote`, `tpu_vote`, `gossip_vote` or `dummy` |
+//! | `packet_index` | uint32 | Position of the packet in its batch |
+//! | `discarded` | bool | Packet was discarded by sigverify |
+//! | `forwarded` | bool | Packet was forwarded by another validator |
+//! | `from_staked_node` | bool | Packet was received from a staked node |
+//! | `simple_vote` | bool | Packet was tagged as a simple vote transaction |
+//! | `signature` | utf8 | First signature of the transaction |
+//! | `fee_payer` | utf8 | |
+//! | `signers` | list\<utf8\> | Accounts required to sign, starting with the fee payer |
+//! | `account_keys` | list\<utf8\> | Static account keys of the message |
+//! | `address_lookup_tables` | list\<utf8\> | Address lookup tables the message loads from |
+//! | `recent_blockhash` | utf8 | |
+//! | `compute_unit_limit` | uint32 | Requested, or default, compute unit limit |
+//! | `compute_unit_price` | uint64 | Effective price in micro-lamports per compute unit |
+//! | `priority_fee` | uint64 | Priority fee in lamports |
+//! | `loaded_accounts_data_size_limit` | uint32 | Requested, or default, limit in bytes |
+//!
+//! `blocks.arrow` has a row per block the traced leader produced, recorded when it froze the bank.
+//!
+//! | Column | Type | Description |
+//! |---|---|---|
+//! | `event_time` | timestamp (ns, UTC) | When the bank was frozen |
+//! | `event_index` | uint64 | Position of the event in the trace |
+//! | `slot` | uint64 | |
+//! | `blockhash` | utf8 | |
+//! | `bank_hash` | utf8 | |
+//!
+//! Compute budget columns are derived as if all features were active, since the trace does not
+//! record the feature set of the banks the transactions were received for.
+//!
+//! [Arrow IPC files]: https://arrow.apache.org/docs/format/Columnar.html#ipc-file-format
+
+use {
+    crate::{
+        error::{LedgerToolError, Result},
+        ledger_path::canonicalize_ledger_path,
+    },
+    agave_feature_set::FeatureSet,
+    arrow_array::{
+        ArrayRef, RecordBatch,
+        builder::{
+            BooleanBuilder, ListBuilder, StringBuilder, TimestampNanosecondBuilder, UInt32Builder,
+            UInt64Builder,
+        },
+    },
+    arrow_ipc::writer::FileWriter,
+    arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit},
+    clap::{App, AppSettings, Arg, ArgMatches, SubCommand, value_t_or_exit},
+    log::*,
+    solana_core::{
+        banking_simulation::{BankingSimulator, BankingTraceEvents},
+        banking_trace::{ChannelLabel, TimedTracedEvent, TracedEvent},
+    },
+    solana_ledger::blockstore::banking_trace_path,
+    solana_runtime_transaction::{
+        runtime_transaction::RuntimeTransaction, transaction_meta::TransactionMeta,
+    },
+    solana_transaction::{
+        sanitized::MessageHash,
+        versioned::{VersionedTransaction, sanitized::SanitizedVersionedTransaction},
+    },
+    std::{
+        collections::HashSet,
+        ffi::OsString,
+        fs::{self, File, read_dir},
+        io::BufWriter,
+        path::{Path, PathBuf},
+        process::exit,
+        sync::Arc,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+pub const TRANSACTIONS_FILE_NAME: &str = "transactions.arrow";
+pub const BLOCKS_FILE_NAME: &str = "blocks.arrow";
+
+/// Number of rows buffered before they are written as a record batch
+const RECORD_BATCH_ROWS: usize = 8192;
+
+pub trait BankingTraceSubCommand {
+    fn banking_trace_subcommand(self) -> Self;
+}
+
+impl BankingTraceSubCommand for App<'_, '_> {
+    fn banking_trace_subcommand(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("banking-trace")
+                .about("Commands to inspect the banking trace of the ledger")
+                .setting(AppSettings::InferSubcommands)
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("export")
+                        .about(
+                            "Export the packet batches and frozen blocks of the banking trace to \
+                             Arrow IPC files",
+                        )
+                        .arg(
+                            Arg::with_name("output_dir")
+                                .long("output-dir")
+                                .value_name("DIR")
+                                .takes_value(true)
+                                .required(true)
+                                .help(
+                                    "Directory to write transactions.arrow and blocks.arrow to, \
+                                     created if missing",
+                                ),
+                        ),
+                ),
+        )
+    }
+}
+
+pub fn banking_trace_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
+    do_banking_trace_process_command(ledger_path, matches).unwrap_or_else(|err| {
+        eprintln!("Failed to complete command: {err:?}");
+        exit(1);
+    });
+}
+
+fn do_banking_trace_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -> Result<()> {
+    let ledger_path = canonicalize_ledger_path(ledger_path);
+
+    match matches.subcommand() {
+        ("export", Some(arg_matches)) => {
+            let output_dir = value_t_or_exit!(arg_matches, "output_dir", PathBuf);
+            let event_file_paths =
+                read_banking_trace_event_file_paths_or_exit(banking_trace_path(&ledger_path));
+            let stats = export(&event_file_paths, &output_dir)?;
+            println!(
+                "Exported {} transactions and {} blocks from {} events to {}",
+                stats.num_transactions,
+                stats.num_blocks,
+                stats.num_events,
+                output_dir.display(),
+            );
+        }
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+pub fn load_banking_trace_events_or_exit(ledger_path: &Path) -> BankingTraceEvents {
+    let file_paths = read_banking_trace_event_file_paths_or_exit(banking_trace_path(ledger_path));
+
+    info!("Using: banking trace event files: {file_paths:?}");
+    match BankingTraceEvents::load(&file_paths) {
+        Ok(banking_trace_events) => banking_trace_events,
+        Err(error) => {
+            eprintln!("Failed to load banking trace events: {error:?}");
+            exit(1)
+        }
+    }
+}
+
+fn read_banking_trace_event_file_paths_or_exit(banking_trace_path: PathBuf) -> Vec<PathBuf> {
+    info!("Using: banking trace events dir: {banking_trace_path:?}");
+
+    let entries = match read_dir(&banking_trace_path) {
+        Ok(entries) => entries,
+        Err(error) => {
+            eprintln!("Error: failed to open banking_trace_path: {error:?}");
+            exit(1);
+        }
+    };
+
+    let mut entry_names = entries
+        .flat_map(|entry| entry.ok().map(|entry| entry.file_name()))
+        .collect::<HashSet<OsString>>();
+
+    let mut event_file_paths = vec![];
+
+    if entry_names.is_empty() {
+        warn!("banking_trace_path dir is empty.");
+        return event_file_paths;
+    }
+
+    for index in 0.. {
+        let event_file_name: OsString = BankingSimulator::event_file_name(index).into();
+        if entry_names.remove(&event_file_name) {
+            event_file_paths.push(banking_trace_path.join(event_file_name));
+        } else {
+            break;
+        }
+    }
+
+    if event_file_paths.is_empty() {
+        warn!("Error: no event files found");
+    }
+
+    if !entry_names.is_empty() {
+        let full_names = entry_names
+            .into_iter()
+            .map(|name| banking_trace_path.join(name))
+            .collect::<Vec<_>>();
+        warn!(
+            "Some files in {banking_trace_path:?} is ignored due to gapped events file rotation \
+             or unrecognized names: {full_names:?}"
+        );
+    }
+
+    // Reverse to load in the chronicle order (note that this isn't strictly needed)
+    event_file_paths.reverse();
+    event_file_paths
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ExportStats {
+    pub num_events: u64,
+    pub num_transactions: u64,
+    pub num_blocks: u64,
+}
+
+/// Exports the events of `event_file_paths` to the Arrow IPC files described in the module
+/// documentation, in `output_dir`
+pub fn export(event_file_paths: &[PathBuf], output_dir: &Path) -> Result<ExportStats> {
+    fs::create_dir_all(output_dir)?;
+    let mut transactions = TableWriter::<TransactionColumns>::create(
+        &output_dir.join(TRANSACTIONS_FILE_NAME),
+        TransactionColumns::schema(),
+    )?;
+    let mut blocks = TableWriter::<BlockColumns>::create(
+        &output_dir.join(BLOCKS_FILE_NAME),
+        BlockColumns::schema(),
+    )?;
+
+    let feature_set = FeatureSet::all_enabled();
+    let mut num_events = 0;
+    let mut result = Ok(());
+    BankingTraceEvents::read_event_files(
+        event_file_paths,
+        |TimedTracedEvent(event_time, event)| {
+            if result.is_err() {
+                return;
+            }
+            let event_index = num_events;
+            num_events += 1;
+            result = match event {
+                TracedEvent::PacketBatch(label, batch) => {
+                    for (packet_index, packet) in batch.iter().enumerate() {
+                        let meta = packet.meta();
+                        transactions.columns.push(
+                            TransactionRow {
+                                event_time,
+                                event_index,
+                                label,
+                                packet_index: packet_index as u32,
+                                discarded: meta.discard(),
+                                forwarded: meta.forwarded(),
+                                from_staked_node: meta.is_from_staked_node(),
+                                simple_vote: meta.is_simple_vote_tx(),
+                            },
+                            packet.data(..),
+                            &feature_set,
+                        );
+                    }
+                    transactions.flush_if_full()
+                }
+                TracedEvent::BlockAndBankHash(slot, blockhash, bank_hash) => {
+                    let columns = &mut blocks.columns;
+                    columns.event_time.append_value(timestamp_nanos(event_time));
+                    columns.event_index.append_value(event_index);
+                    columns.slot.append_value(slot);
+                    columns.blockhash.append_value(blockhash.to_string());
+                    columns.bank_hash.append_value(bank_hash.to_string());
+                    columns.len += 1;
+                    blocks.flush_if_full()
+                }
+            };
+        },
+    )
+    .map_err(|err| LedgerToolError::Generic(format!("failed to read banking trace: {err}")))?;
+    result?;
+
+    Ok(ExportStats {
+        num_events,
+        num_transactions: transactions.finish()?,
+        num_blocks: blocks.finish()?,
+    })
+}
+
+fn timestamp_nanos(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .and_then(|duration| i64::try_from(duration.as_nanos()).ok())
+        .unwrap_or_default()
+}
+
+fn timestamp_field(name: &str) -> Field {
+    Field::new(
+        name,
+        DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into())),
+        false,
+    )
+}
+
+fn utf8_list_field(name: &str) -> Field {
+    Field::new(
+        name,
+        DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true))),
+        true,
+    )
+}
+
+trait Columns: Default {
+    /// Number of buffered rows
+    fn len(&self) -> usize;
+
+    /// Returns the buffered rows, in schema order, and resets the builders
+    fn finish(&mut self) -> Vec<ArrayRef>;
+}
+
+/// Buffers the rows of a table and writes them to an Arrow IPC file in record batches
+struct TableWriter<C> {
+    writer: FileWriter<BufWriter<File>>,
+    schema: SchemaRef,
+    columns: C,
+    num_rows: u64,
+}
+
+impl<C: Columns> TableWriter<C> {
+    fn create(path: &Path, schema: Schema) -> Result<Self> {
+        let writer = FileWriter::try_new_buffered(File::create(path)?, &schema)?;
+        Ok(Self {
+            writer,
+            schema: Arc::new(schema),
+            columns: C::default(),
+            num_rows: 0,
+        })
+    }
+
+    fn flush_if_full(&mut self) -> Result<()> {
+        if self.columns.len() >= RECORD_BATCH_ROWS {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let num_rows = self.columns.len();
+        if num_rows == 0 {
+            return Ok(());
+        }
+        let batch = RecordBatch::try_new(Arc::clone(&self.schema), self.columns.finish())?;
+        self.writer.write(&batch)?;
+        self.num_rows += num_rows as u64;
+        Ok(())
+    }
+
+    /// Writes the remaining rows and the file footer, returning the number of rows written
+    fn finish(mut self) -> Result<u64> {
+        self.flush()?;
+        self.writer.finish()?;
+        Ok(self.num_rows)
+    }
+}
+
+/// The packet level fields of a `transactions.arrow` row
+struct TransactionRow {
+    event_time: SystemTime,
+    event_index: u64,
+    label: ChannelLabel,
+    packet_index: u32,
+    discarded: bool,
+    forwarded: bool,
+    from_staked_node: bool,
+    simple_vote: bool,
+}
+
+struct TransactionColumns {
+    len: usize,
+    event_time: TimestampNanosecondBuilder,
+    event_index: UInt64Builder,
+    channel: StringBuilder,
+    packet_index: UInt32Builder,
+    discarded: BooleanBuilder,
+    forwarded: BooleanBuilder,
+    from_staked_node: BooleanBuilder,
+    simple_vote: BooleanBuilder,
+    signature: StringBuilder,
+    fee_payer: StringBuilder,
+    signers: ListBuilder<StringBuilder>,
+    account_keys: ListBuilder<StringBuilder>,
+    address_lookup_tables: ListBuilder<StringBuilder>,
+    recent_blockhash: StringBuilder,
+    compute_unit_limit: UInt32Builder,
+    compute_unit_price: UInt64Builder,
+    priority_fee: UInt64Builder,
+    loaded_accounts_data_size_limit: UInt32Builder,
+}
+
+impl Default for TransactionColumns {
+    fn default() -> Self {
+        Self {
+            len: 0,
+            event_time: TimestampNanosecondBuilder::new().with_timezone("UTC"),
+            event_index: UInt64Builder::new(),
+            channel: StringBuilder::new(),
+            packet_index: UInt32Builder::new(),
+            discarded: BooleanBuilder::new(),
+            forwarded: BooleanBuilder::new(),
+            from_staked_node: BooleanBuilder::new(),
+            simple_vote: BooleanBuilder::new(),
+            signature: StringBuilder::new(),
+            fee_payer: StringBuilder::new(),
+            signers: ListBuilder::default(),
+            account_keys: ListBuilder::default(),
+            address_lookup_tables: ListBuilder::default(),
+            recent_blockhash: StringBuilder::new(),
+            compute_unit_limit: UInt32Builder::new(),
+            compute_unit_price: UInt64Builder::new(),
+            priority_fee: UInt64Builder::new(),
+            loaded_accounts_data_size_limit: UInt32Builder::new(),
+        }
+    }
+}
+
+impl TransactionColumns {
+    fn schema() -> Schema {
+        Schema::new(vec![
+            timestamp_field("event_time"),
+            Field::new("event_index", DataType::UInt64, false),
+            Field::new("channel", DataType::Utf8, false),
+            Field::new("packet_index", DataType::UInt32, false),
+            Field::new("discarded", DataType::Boolean, false),
+            Field::new("forwarded", DataType::Boolean, false),
+            Field::new("from_staked_node", DataType::Boolean, false),
+            Field::new("simple_vote", DataType::Boolean, false),
+            Field::new("signature", DataType::Utf8, true),
+            Field::new("fee_payer", DataType::Utf8, true),
+            utf8_list_field("signers"),
+            utf8_list_field("account_keys"),
+            utf8_list_field("address_lookup_tables"),
+            Field::new("recent_blockhash", DataType::Utf8, true),
+            Field::new("compute_unit_limit", DataType::UInt32, true),
+            Field::new("compute_unit_price", DataType::UInt64, true),
+            Field::new("priority_fee", DataType::UInt64, true),
+            Field::new("loaded_accounts_data_size_limit", DataType::UInt32, true),
+        ])
+    }
+
+    fn push(&mut self, row: TransactionRow, data: Option<&[u8]>, feature_set: &FeatureSet) {
+        self.event_time
+            .append_value(timestamp_nanos(row.event_time));
+        self.event_index.append_value(row.event_index);
+        self.channel.append_value(match row.label {
+            ChannelLabel::NonVote => "non_vote",
+            ChannelLabel::TpuVote => "tpu_vote",
+            ChannelLabel::GossipVote => "gossip_vote",
+            ChannelLabel::Dummy => "dummy",
+        });
+        self.packet_index.append_value(row.packet_index);
+        self.discarded.append_value(row.discarded);
+        self.forwarded.append_value(row.forwarded);
+        self.from_staked_node.append_value(row.from_staked_node);
+        self.simple_vote.append_value(row.simple_vote);
+        self.len += 1;
+
+        let transaction =
+            data.and_then(|data| bincode::deserialize::<VersionedTransaction>(data).ok());
+        let Some(transaction) = transaction else {
+            self.signature.append_null();
+            self.fee_payer.append_null();
+            self.signers.append_null();
+            self.account_keys.append_null();
+            self.address_lookup_tables.append_null();
+            self.recent_blockhash.append_null();
+            self.append_null_compute_budget();
+            return;
+        };
+
+        let message = &transaction.message;
+        let account_keys = message.static_account_keys();
+        let num_signers = usize::from(message.header().num_required_signatures);
+        self.signature
+            .append_option(transaction.signatures.first().map(ToString::to_string));
+        self.fee_payer
+            .append_option(account_keys.first().map(ToString::to_string));
+        self.signers.append_value(
+            account_keys
+                .iter()
+                .take(num_signers)
+                .map(|key| Some(key.to_string())),
+        );
+        self.account_keys
+            .append_value(account_keys.iter().map(|key| Some(key.to_string())));
+        self.address_lookup_tables.append_value(
+            message
+                .address_table_lookups()
+                .unwrap_or_default()
+                .iter()
+                .map(|lookup| Some(lookup.account_key.to_string())),
+        );
+        self.recent_blockhash
+            .append_value(message.recent_blockhash().to_string());
+
+        let configuration = SanitizedVersionedTransaction::try_from(transaction)
+            .ok()
+            .and_then(|transaction| {
+                RuntimeTransaction::<SanitizedVersionedTransaction>::try_from(
+                    transaction,
+                    MessageHash::Compute,
+                    Some(row.simple_vote),
+                )
+                .ok()
+            })
+            .and_then(|transaction| transaction.transaction_configuration(feature_set).ok());
+        match configuration {
+            Some(configuration) => {
+                self.compute_unit_limit
+                    .append_value(configuration.compute_unit_limit);
+                self.compute_unit_price
+                    .append_value(configuration.compute_unit_price_in_microlamports());
+                self.priority_fee
+                    .append_value(configuration.priority_fee_lamports);
+                self.loaded_accounts_data_size_limit
+                    .append_value(configuration.loaded_accounts_data_size_limit);
+            }
+            None => self.append_null_compute_budget(),
+        }
+    }
+
+    fn append_null_compute_budget(&mut self) {
+        self.compute_unit_limit.append_null();
+        self.compute_unit_price.append_null();
+        self.priority_fee.append_null();
+        self.loaded_accounts_data_size_limit.append_null();
+    }
+}
+
+impl Columns for TransactionColumns {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        self.len = 0;
+        vec![
+            Arc::new(self.event_time.finish()),
+            Arc::new(self.event_index.finish()),
+            Arc::new(self.channel.finish()),
+            Arc::new(self.packet_index.finish()),
+            Arc::new(self.discarded.finish()),
+            Arc::new(self.forwarded.finish()),
+            Arc::new(self.from_staked_node.finish()),
+            Arc::new(self.simple_vote.finish()),
+            Arc::new(self.signature.finish()),
+            Arc::new(self.fee_payer.finish()),
+            Arc::new(self.signers.finish()),
+            Arc::new(self.account_keys.finish()),
+            Arc::new(self.address_lookup_tables.finish()),
+            Arc::new(self.recent_blockhash.finish()),
+            Arc::new(self.compute_unit_limit.finish()),
+            Arc::new(self.compute_unit_price.finish()),
+            Arc::new(self.priority_fee.finish()),
+            Arc::new(self.loaded_accounts_data_size_limit.finish()),
+        ]
+    }
+}
+
+struct BlockColumns {
+    len: usize,
+    event_time: TimestampNanosecondBuilder,
+    event_index: UInt64Builder,
+    slot: UInt64Builder,
+    blockhash: StringBuilder,
+    bank_hash: StringBuilder,
+}
+
+impl Default for BlockColumns {
+    fn default() -> Self {
+        Self {
+            len: 0,
+            event_time: TimestampNanosecondBuilder::new().with_timezone("UTC"),
+            event_index: UInt64Builder::new(),
+            slot: UInt64Builder::new(),
+            blockhash: StringBuilder::new(),
+            bank_hash: StringBuilder::new(),
+        }
+    }
+}
+
+impl BlockColumns {
+    fn schema() -> Schema {
+        Schema::new(vec![
+            timestamp_field("event_time"),
+            Field::new("event_index", DataType::UInt64, false),
+            Field::new("slot", DataType::UInt64, false),
+            Field::new("blockhash", DataType::Utf8, false),
+            Field::new("bank_hash", DataType::Utf8, false),
+        ])
+    }
+}
+
+impl Columns for BlockColumns {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        self.len = 0;
+        vec![
+            Arc::new(self.event_time.finish()),
+            Arc::new(self.event_index.finish()),
+            Arc::new(self.slot.finish()),
+            Arc::new(self.blockhash.finish()),
+            Arc::new(self.bank_hash.finish()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        arrow_array::{
+            Array,
+            cast::AsArray,
+            types::{UInt32Type, UInt64Type},
+        },
+        arrow_ipc::reader::FileReader,
+        solana_core::banking_trace::for_test::sample_packet_batch,
+        solana_hash::Hash,
+        std::{io::Write, time::Duration},
+    };
+
+    fn read_batches(path: &Path) -> Vec<RecordBatch> {
+        FileReader::try_new(File::open(path).unwrap(), None)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    #[test]
+    fn test_export() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let event_file_path = temp_dir.path().join(BankingSimulator::event_file_name(0));
+        let output_dir = temp_dir.path().join("export");
+
+        let batch = sample_packet_batch();
+        let num_packets = batch.len();
+        let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let blockhash = Hash::new_unique();
+        let bank_hash = Hash::new_unique();
+        let events = [
+            TimedTracedEvent(
+                start,
+                TracedEvent::PacketBatch(ChannelLabel::NonVote, batch.clone()),
+            ),
+            TimedTracedEvent(
+                start + Duration::from_millis(1),
+                TracedEvent::BlockAndBankHash(42, blockhash, bank_hash),
+            ),
+            TimedTracedEvent(
+                start + Duration::from_millis(2),
+                TracedEvent::PacketBatch(ChannelLabel::TpuVote, batch.clone()),
+            ),
+        ];
+        let mut file = File::create(&event_file_path).unwrap();
+        for event in &events {
+            bincode::serialize_into(&mut file, event).unwrap();
+        }
+        // A partially written event, as left behind by an unclean shutdown
+        file.write_all(&[0; 3]).unwrap();
+        drop(file);
+
+        let stats = export(&[event_file_path], &output_dir).unwrap();
+        assert_eq!(
+            stats,
+            ExportStats {
+                num_events: 3,
+                num_transactions: 2 * num_packets as u64,
+                num_blocks: 1,
+            }
+        );
+
+        let transactions = read_batches(&output_dir.join(TRANSACTIONS_FILE_NAME));
+        assert_eq!(transactions.len(), 1);
+        let transactions = &transactions[0];
+        assert_eq!(
+            transactions.schema(),
+            Arc::new(TransactionColumns::schema())
+        );
+        assert_eq!(transactions.num_rows(), 2 * num_packets);
+
+        let column = |name| transactions.column_by_name(name).unwrap();
+        let event_index = column("event_index").as_primitive::<UInt64Type>();
+        let channel = column("channel").as_string::<i32>();
+        let signature = column("signature").as_string::<i32>();
+        let fee_payer = column("fee_payer").as_string::<i32>();
+        let signers = column("signers").as_list::<i32>();
+        let account_keys = column("account_keys").as_list::<i32>();
+        let compute_unit_limit = column("compute_unit_limit").as_primitive::<UInt32Type>();
+        let priority_fee = column("priority_fee").as_primitive::<UInt64Type>();
+        for (row, packet) in batch.iter().chain(batch.iter()).enumerate() {
+            let first_batch = row < num_packets;
+            assert_eq!(event_index.value(row), if first_batch { 0 } else { 2 });
+            assert_eq!(
+                channel.value(row),
+                if first_batch { "non_vote" } else { "tpu_vote" }
+            );
+
+            let transaction: VersionedTransaction =
+                bincode::deserialize(packet.data(..).unwrap()).unwrap();
+            let keys = transaction.message.static_account_keys();
+            assert_eq!(signature.value(row), transaction.signatures[0].to_string());
+            assert_eq!(fee_payer.value(row), keys[0].to_string());
+            assert_eq!(
+                signers.value(row).len(),
+                usize::from(transaction.message.header().num_required_signatures)
+            );
+            assert_eq!(account_keys.value(row).len(), keys.len());
+            assert!(compute_unit_limit.is_valid(row));
+            assert_eq!(priority_fee.value(row), 0);
+        }
+
+        let blocks = read_batches(&output_dir.join(BLOCKS_FILE_NAME));
+        assert_eq!(blocks.len(), 1);
+        let blocks = &blocks[0];
+        assert_eq!(blocks.num_rows(), 1);
+        let column = |name| blocks.column_by_name(name).unwrap();
+        assert_eq!(
+            column("event_index").as_primitive::<UInt64Type>().value(0),
+            1
+        );
+        assert_eq!(column("slot").as_primitive::<UInt64Type>().value(0), 42);
+        assert_eq!(
+            column("blockhash").as_string::<i32>().value(0),
+            blockhash.to_string()
+        );
+        assert_eq!(
+            column("bank_hash").as_string::<i32>().value(0),
+            bank_hash.to_string()
+        );
+    }
+}
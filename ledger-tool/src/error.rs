@@ -19,6 +19,9 @@ pub enum LedgerToolError {
     #[error("{0}")]
     Io(#[from] std::io::Error),
 
+    #[error("{0}")]
+    Arrow(#[from] arrow_schema::ArrowError),
+
     #[error("{0}")]
     Generic(String),
 
@@ -2,15 +2,21 @@
 use {
     crate::{
         args::*,
+        banking_trace::{
+            BankingTraceSubCommand, banking_trace_process_command,
+            load_banking_trace_events_or_exit,
+        },
         bigtable::*,
         blockstore::*,
         ledger_path::*,
         ledger_utils::*,
         output::{
             AccountsOutputConfig, AccountsOutputMode, AccountsOutputStreamer, CliAccounts,
-            SlotBankHash,
+            CliSimulationReport, SlotBankHash,
         },
         program::*,
+        snapshot::*,
+        vote_history::*,
     },
     agave_feature_set::{self as feature_set, FeatureSet},
     agave_reserved_account_keys::ReservedAccountKeys,
@@ -37,19 +43,26 @@ use {
     solana_clock::{Epoch, Slot},
     solana_cluster_type::ClusterType,
     solana_core::{
-        banking_simulation::{BankingSimulator, BankingTraceEvents},
+        banking_simulation::{BankingSimulator, SimulationConfig},
+        banking_stage::transaction_scheduler::scheduler_controller::SchedulerConfig,
         resource_limits::adjust_nofile_limit,
         system_monitor_service::{SystemMonitorService, SystemMonitorStatsReportConfig},
-        validator::{BlockProductionMethod, BlockVerificationMethod, TransactionStructure},
+        validator::{
+            BlockProductionMethod, BlockVerificationMethod, SchedulerPacing, TransactionStructure,
+        },
+    },
+    solana_cost_model::{
+        cost_model::CostModel,
+        cost_tracker::{CostTracker, CostTrackerLimits},
     },
-    solana_cost_model::{cost_model::CostModel, cost_tracker::CostTracker},
     solana_entry::entry::create_ticks,
     solana_feature_gate_interface::{self as feature, Feature},
+    solana_fee_structure::FeeStructure,
     solana_inflation::Inflation,
     solana_instruction::TRANSACTION_LEVEL_STACK_HEIGHT,
     solana_keypair::{Keypair, keypair_from_seed},
     solana_ledger::{
-        blockstore::{Blockstore, PurgeType, banking_trace_path, create_new_ledger},
+        blockstore::{Blockstore, PurgeType, create_new_ledger},
         blockstore_options::{AccessType, BLOCKSTORE_DIRECTORY_ROCKS_LEVEL, LedgerColumnOptions},
         blockstore_processor::ProcessSlotCallback,
         shred::{ProcessShredsStats, ReedSolomonCache, Shred, Shredder},
@@ -87,8 +100,8 @@ use {
     std::{
         borrow::Cow,
         collections::{HashMap, HashSet},
-        ffi::{OsStr, OsString},
-        fs::{File, read_dir},
+        ffi::OsStr,
+        fs::File,
         io::{self, Write},
         mem::swap,
         path::{Path, PathBuf},
@@ -103,6 +116,7 @@ use {
 };
 
 mod args;
+mod banking_trace;
 mod bigtable;
 mod blockstore;
 mod error;
@@ -110,6 +124,8 @@ mod ledger_path;
 mod ledger_utils;
 mod output;
 mod program;
+mod snapshot;
+mod vote_history;
 
 fn render_dot(dot: String, output_file: &str, output_format: &str) -> io::Result<()> {
     let mut child = Command::new("dot")
@@ -537,70 +553,6 @@ fn assert_capitalization(bank: &Bank) {
     );
 }
 
-fn load_banking_trace_events_or_exit(ledger_path: &Path) -> BankingTraceEvents {
-    let file_paths = read_banking_trace_event_file_paths_or_exit(banking_trace_path(ledger_path));
-
-    info!("Using: banking trace event files: {file_paths:?}");
-    match BankingTraceEvents::load(&file_paths) {
-        Ok(banking_trace_events) => banking_trace_events,
-        Err(error) => {
-            eprintln!("Failed to load banking trace events: {error:?}");
-            exit(1)
-        }
-    }
-}
-
-fn read_banking_trace_event_file_paths_or_exit(banking_trace_path: PathBuf) -> Vec<PathBuf> {
-    info!("Using: banking trace events dir: {banking_trace_path:?}");
-
-    let entries = match read_dir(&banking_trace_path) {
-        Ok(entries) => entries,
-        Err(error) => {
-            eprintln!("Error: failed to open banking_trace_path: {error:?}");
-            exit(1);
-        }
-    };
-
-    let mut entry_names = entries
-        .flat_map(|entry| entry.ok().map(|entry| entry.file_name()))
-        .collect::<HashSet<OsString>>();
-
-    let mut event_file_paths = vec![];
-
-    if entry_names.is_empty() {
-        warn!("banking_trace_path dir is empty.");
-        return event_file_paths;
-    }
-
-    for index in 0.. {
-        let event_file_name: OsString = BankingSimulator::event_file_name(index).into();
-        if entry_names.remove(&event_file_name) {
-            event_file_paths.push(banking_trace_path.join(event_file_name));
-        } else {
-            break;
-        }
-    }
-
-    if event_file_paths.is_empty() {
-        warn!("Error: no event files found");
-    }
-
-    if !entry_names.is_empty() {
-        let full_names = entry_names
-            .into_iter()
-            .map(|name| banking_trace_path.join(name))
-            .collect::<Vec<_>>();
-        warn!(
-            "Some files in {banking_trace_path:?} is ignored due to gapped events file rotation \
-             or unrecognized names: {full_names:?}"
-        );
-    }
-
-    // Reverse to load in the chronicle order (note that this isn't strictly needed)
-    event_file_paths.reverse();
-    event_file_paths
-}
-
 struct SlotRecorderConfig {
     transaction_recorder: Option<JoinHandle<()>>,
     transaction_status_sender: Option<TransactionStatusSender>,
@@ -1564,6 +1516,42 @@ fn main() {
                         .long("no-block-cost-limits")
                         .takes_value(false)
                         .help("Disable block cost limits effectively by setting them to the max"),
+                )
+                .arg(
+                    Arg::with_name("block_production_pacing_fill_time_millis")
+                        .long("block-production-pacing-fill-time-millis")
+                        .value_name("MILLIS")
+                        .takes_value(true)
+                        .help(
+                            "Pacing fill time in milliseconds for the simulated scheduler, or \
+                             'disabled' [default: the validator's default]",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("block_cost_limit")
+                        .long("block-cost-limit")
+                        .value_name("UNITS")
+                        .takes_value(true)
+                        .validator(is_parsable::<u64>)
+                        .conflicts_with("no_block_cost_limits")
+                        .help("Override the block cost limit of the simulated blocks"),
+                )
+                .arg(
+                    Arg::with_name("account_cost_limit")
+                        .long("account-cost-limit")
+                        .value_name("UNITS")
+                        .takes_value(true)
+                        .validator(is_parsable::<u64>)
+                        .conflicts_with("no_block_cost_limits")
+                        .help("Override the writable account cost limit of the simulated blocks"),
+                )
+                .arg(
+                    Arg::with_name("lamports_per_signature")
+                        .long("lamports-per-signature")
+                        .value_name("LAMPORTS")
+                        .takes_value(true)
+                        .validator(is_parsable::<u64>)
+                        .help("Override the base fee per signature of the simulated blocks"),
                 ),
         )
         .subcommand(
@@ -1694,7 +1682,10 @@ fn main() {
                 )
                 .arg(&allow_dead_slots_arg),
         )
+        .banking_trace_subcommand()
         .program_subcommand()
+        .snapshot_subcommand()
+        .vote_history_subcommand()
         .get_matches();
 
     let logfile = value_t!(matches, "logfile", PathBuf).ok();
@@ -1718,9 +1709,14 @@ fn main() {
         .unwrap();
 
     match matches.subcommand() {
+        ("banking-trace", Some(arg_matches)) => {
+            banking_trace_process_command(&ledger_path, arg_matches)
+        }
         ("bigtable", Some(arg_matches)) => bigtable_process_command(&ledger_path, arg_matches),
         ("blockstore", Some(arg_matches)) => blockstore_process_command(&ledger_path, arg_matches),
         ("program", Some(arg_matches)) => program(&ledger_path, arg_matches),
+        ("snapshot", Some(arg_matches)) => snapshot_process_command(&ledger_path, arg_matches),
+        ("vote-history", Some(arg_matches)) => vote_history_process_command(arg_matches),
         // This match case provides legacy support for commands that were previously top level
         // subcommands of the binary, but have been moved under the blockstore subcommand.
         ("analyze-storage", Some(_))
@@ -2075,7 +2071,9 @@ fn main() {
                             .unwrap_or_else(|| {
                                 panic!("Archive format not recognized: {archive_format_str}")
                             });
-                        if let ArchiveFormat::TarZstd { config } = &mut archive_format {
+                        if let ArchiveFormat::TarZstd { config }
+                        | ArchiveFormat::TarZstdSeekable { config } = &mut archive_format
+                        {
                             config.compression_level = value_t_or_exit!(
                                 arg_matches,
                                 "snapshot_zstd_compression_level",
@@ -2690,13 +2688,59 @@ fn main() {
 
                     info!("Using: block-production-method: {block_production_method}");
 
-                    match simulator.start(
-                        genesis_config,
-                        bank_forks,
-                        blockstore,
+                    let mut scheduler_config = SchedulerConfig::default();
+                    if arg_matches.is_present("block_production_pacing_fill_time_millis") {
+                        scheduler_config.scheduler_pacing = value_t_or_exit!(
+                            arg_matches,
+                            "block_production_pacing_fill_time_millis",
+                            SchedulerPacing
+                        );
+                    }
+                    let cost_limits = if arg_matches.is_present("no_block_cost_limits") {
+                        Some(CostTrackerLimits::new(u64::MAX, u64::MAX, u64::MAX))
+                    } else {
+                        let current_limits = bank_forks
+                            .read()
+                            .unwrap()
+                            .working_bank()
+                            .read_cost_tracker()
+                            .unwrap()
+                            .get_limits();
+                        let block_cost = value_t!(arg_matches, "block_cost_limit", u64).ok();
+                        let account_cost = value_t!(arg_matches, "account_cost_limit", u64).ok();
+                        (block_cost.is_some() || account_cost.is_some()).then(|| {
+                            CostTrackerLimits::new(
+                                account_cost.unwrap_or(current_limits.account_cost),
+                                block_cost.unwrap_or(current_limits.block_cost),
+                                current_limits.allocated_data_size,
+                            )
+                        })
+                    };
+                    let fee_structure = value_t!(arg_matches, "lamports_per_signature", u64)
+                        .ok()
+                        .map(|lamports_per_signature| FeeStructure {
+                            lamports_per_signature,
+                            ..bank_forks
+                                .read()
+                                .unwrap()
+                                .working_bank()
+                                .fee_structure()
+                                .clone()
+                        });
+                    let config = SimulationConfig {
                         block_production_method,
-                    ) {
-                        Ok(()) => println!("Ok"),
+                        scheduler_config,
+                        cost_limits,
+                        fee_structure,
+                    };
+
+                    match simulator.start(genesis_config, bank_forks, blockstore, config) {
+                        Ok(report) => {
+                            let output_format =
+                                OutputFormat::from_matches(arg_matches, "output_format", false);
+                            let report = CliSimulationReport { report };
+                            println!("{}", output_format.formatted_string(&report));
+                        }
                         Err(error) => {
                             eprintln!("{error:?}");
                             exit(1);
@@ -13,9 +13,10 @@ use {
     solana_accounts_db::is_loadable::IsLoadable as _,
     solana_cli_output::{
         CliAccount, CliAccountNewConfig, CliBlock, OutputFormat, QuietDisplay, VerboseDisplay,
-        display::writeln_transaction,
+        display::{build_balance_message, writeln_transaction},
     },
     solana_clock::{Slot, UnixTimestamp},
+    solana_core::banking_simulation::report::{BlockStats, SimulationReport},
     solana_entry::block_component::{
         BlockComponent, BlockFooterV1, BlockMarkerV1, VersionedBlockFooter, VersionedBlockHeader,
         VersionedBlockMarker, VersionedUpdateParent,
@@ -30,7 +31,12 @@ use {
         shred::{Shred, ShredType},
     },
     solana_pubkey::Pubkey,
-    solana_runtime::bank::Bank,
+    solana_runtime::bank::{
+        Bank,
+        accounts_diff::{AccountField, AccountsDiff},
+        bank_hash_details::SlotDetails,
+        lt_hash_bisection::LtHashBisectionRound,
+    },
     solana_signer_store::{Decoded, decode},
     solana_transaction::versioned::VersionedTransaction,
     solana_transaction_status::{
@@ -1301,3 +1307,388 @@ impl fmt::Display for CliAccounts {
 }
 impl QuietDisplay for CliAccounts {}
 impl VerboseDisplay for CliAccounts {}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSnapshotDiffBank {
+    pub slot: Slot,
+    pub bank_hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accounts_lt_hash_checksum: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capitalization: Option<u64>,
+}
+
+impl CliSnapshotDiffBank {
+    pub fn from_bank(bank: &Bank) -> Self {
+        Self {
+            slot: bank.slot(),
+            bank_hash: bank.hash().to_string(),
+            accounts_lt_hash_checksum: Some(bank.accounts_lt_hash_checksum().to_string()),
+            capitalization: Some(bank.capitalization()),
+        }
+    }
+
+    pub fn from_slot_details(slot_details: &SlotDetails) -> Self {
+        Self {
+            slot: slot_details.slot,
+            bank_hash: slot_details.bank_hash.clone(),
+            accounts_lt_hash_checksum: slot_details
+                .bank_hash_components
+                .as_ref()
+                .map(|components| components.accounts_lt_hash_checksum.clone()),
+            capitalization: None,
+        }
+    }
+}
+
+impl fmt::Display for CliSnapshotDiffBank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "slot {}, bank hash {}", self.slot, self.bank_hash)?;
+        if let Some(checksum) = &self.accounts_lt_hash_checksum {
+            write!(f, ", accounts lt hash checksum {checksum}")?;
+        }
+        if let Some(capitalization) = self.capitalization {
+            write!(f, ", capitalization {capitalization}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSnapshotDiffProgram {
+    pub owner: String,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub lamports_delta: i128,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CliAccountDiffKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSnapshotDiffAccount {
+    pub pubkey: String,
+    pub kind: CliAccountDiffKind,
+    /// Owner in the right bank, or in the left bank if the account was removed
+    pub owner: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left_lamports: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right_lamports: Option<u64>,
+    pub lamports_delta: i128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left_data_len: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right_data_len: Option<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changed_fields: Vec<&'static str>,
+}
+
+impl CliSnapshotDiffAccount {
+    fn new(
+        pubkey: &Pubkey,
+        left: Option<&AccountSharedData>,
+        right: Option<&AccountSharedData>,
+        changed_fields: Vec<AccountField>,
+    ) -> Self {
+        let kind = match (left, right) {
+            (None, Some(_)) => CliAccountDiffKind::Added,
+            (Some(_), None) => CliAccountDiffKind::Removed,
+            _ => CliAccountDiffKind::Changed,
+        };
+        let owner = right.or(left).map(|account| account.owner().to_string());
+        let left_lamports = left.map(|account| account.lamports());
+        let right_lamports = right.map(|account| account.lamports());
+        Self {
+            pubkey: pubkey.to_string(),
+            kind,
+            owner: owner.unwrap_or_default(),
+            left_lamports,
+            right_lamports,
+            lamports_delta: i128::from(right_lamports.unwrap_or_default())
+                - i128::from(left_lamports.unwrap_or_default()),
+            left_data_len: left.map(|account| account.data().len()),
+            right_data_len: right.map(|account| account.data().len()),
+            changed_fields: changed_fields.iter().map(AccountField::as_str).collect(),
+        }
+    }
+}
+
+impl fmt::Display for CliSnapshotDiffAccount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (marker, kind) = match self.kind {
+            CliAccountDiffKind::Added => ('+', "added"),
+            CliAccountDiffKind::Removed => ('-', "removed"),
+            CliAccountDiffKind::Changed => ('~', "changed"),
+        };
+        writeln!(
+            f,
+            "{marker} {} ({kind}), owner: {}",
+            self.pubkey, self.owner
+        )?;
+        if !self.changed_fields.is_empty() {
+            writeln!(f, "    changed fields: {}", self.changed_fields.join(", "))?;
+        }
+        let display_opt = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        writeln!(
+            f,
+            "    lamports: {} -> {} ({:+})",
+            display_opt(self.left_lamports.map(|lamports| lamports.to_string())),
+            display_opt(self.right_lamports.map(|lamports| lamports.to_string())),
+            self.lamports_delta,
+        )?;
+        writeln!(
+            f,
+            "    data len: {} -> {}",
+            display_opt(self.left_data_len.map(|len| len.to_string())),
+            display_opt(self.right_data_len.map(|len| len.to_string())),
+        )
+    }
+}
+
+/// The result of comparing the accounts of two banks
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSnapshotDiff {
+    pub left: CliSnapshotDiffBank,
+    pub right: CliSnapshotDiffBank,
+    pub num_added: usize,
+    pub num_removed: usize,
+    pub num_changed: usize,
+    pub lamports_delta: i128,
+    /// Per-program summary, keyed by account owner and sorted by owner
+    pub programs: Vec<CliSnapshotDiffProgram>,
+    pub accounts: Vec<CliSnapshotDiffAccount>,
+}
+
+impl CliSnapshotDiff {
+    pub fn new(left: CliSnapshotDiffBank, right: CliSnapshotDiffBank, diff: &AccountsDiff) -> Self {
+        let accounts: Vec<_> = diff
+            .added
+            .iter()
+            .map(|(pubkey, account)| {
+                CliSnapshotDiffAccount::new(pubkey, None, Some(account), vec![])
+            })
+            .chain(diff.removed.iter().map(|(pubkey, account)| {
+                CliSnapshotDiffAccount::new(pubkey, Some(account), None, vec![])
+            }))
+            .chain(diff.changed.iter().map(|changed| {
+                CliSnapshotDiffAccount::new(
+                    &changed.pubkey,
+                    Some(&changed.left),
+                    Some(&changed.right),
+                    changed.changed_fields(),
+                )
+            }))
+            .collect();
+
+        let mut programs = HashMap::<&str, CliSnapshotDiffProgram>::new();
+        for account in &accounts {
+            let program =
+                programs
+                    .entry(account.owner.as_str())
+                    .or_insert_with(|| CliSnapshotDiffProgram {
+                        owner: account.owner.clone(),
+                        ..CliSnapshotDiffProgram::default()
+                    });
+            match account.kind {
+                CliAccountDiffKind::Added => program.added += 1,
+                CliAccountDiffKind::Removed => program.removed += 1,
+                CliAccountDiffKind::Changed => program.changed += 1,
+            }
+            program.lamports_delta += account.lamports_delta;
+        }
+        let mut programs: Vec<_> = programs.into_values().collect();
+        programs.sort_unstable_by(|a, b| a.owner.cmp(&b.owner));
+
+        Self {
+            left,
+            right,
+            num_added: diff.added.len(),
+            num_removed: diff.removed.len(),
+            num_changed: diff.changed.len(),
+            lamports_delta: accounts.iter().map(|account| account.lamports_delta).sum(),
+            programs,
+            accounts,
+        }
+    }
+
+    fn write_summary(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        writeln!(f, "Left:  {}", self.left)?;
+        writeln!(f, "Right: {}", self.right)?;
+        writeln!(f, "Added accounts: {}", self.num_added)?;
+        writeln!(f, "Removed accounts: {}", self.num_removed)?;
+        writeln!(f, "Changed accounts: {}", self.num_changed)?;
+        writeln!(f, "Lamports delta: {:+}", self.lamports_delta)
+    }
+}
+
+impl QuietDisplay for CliSnapshotDiff {
+    fn write_str(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        self.write_summary(f)
+    }
+}
+impl VerboseDisplay for CliSnapshotDiff {}
+
+impl fmt::Display for CliSnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_summary(f)?;
+        if self.programs.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<44}  {:>9}  {:>9}  {:>9}  {:>20}",
+            "Program", "Added", "Removed", "Changed", "Lamports Delta"
+        )?;
+        for program in &self.programs {
+            writeln!(
+                f,
+                "{:<44}  {:>9}  {:>9}  {:>9}  {:>+20}",
+                program.owner,
+                program.added,
+                program.removed,
+                program.changed,
+                program.lamports_delta,
+            )?;
+        }
+
+        writeln!(f)?;
+        for account in &self.accounts {
+            write!(f, "{account}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of bisecting the accounts of two banks
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliLtHashBisection {
+    #[serde(flatten)]
+    pub diff: CliSnapshotDiff,
+    pub rounds: Vec<LtHashBisectionRound>,
+}
+
+impl CliLtHashBisection {
+    fn write_rounds(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>5}  {:>10}  {:>10}  {:>10}  {:>10}",
+            "Round", "Bins", "Requested", "Divergent", "Accounts"
+        )?;
+        for (i, round) in self.rounds.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>5}  {:>10}  {:>10}  {:>10}  {:>10}",
+                i,
+                round.num_bins,
+                round.num_requested_bins,
+                round.num_divergent_bins,
+                round.num_divergent_bin_accounts,
+            )?;
+        }
+        writeln!(f)
+    }
+}
+
+impl QuietDisplay for CliLtHashBisection {
+    fn write_str(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        QuietDisplay::write_str(&self.diff, f)
+    }
+}
+impl VerboseDisplay for CliLtHashBisection {}
+
+impl fmt::Display for CliLtHashBisection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_rounds(f)?;
+        write!(f, "{}", self.diff)
+    }
+}
+
+/// How the blocks produced by a banking simulation compare to the original ones
+#[derive(Serialize, Debug)]
+#[serde(transparent)]
+pub struct CliSimulationReport {
+    pub report: SimulationReport,
+}
+
+impl CliSimulationReport {
+    fn write_stats(f: &mut dyn fmt::Write, label: &str, stats: Option<&BlockStats>) -> fmt::Result {
+        let Some(stats) = stats else {
+            return writeln!(f, "  {label:<10} no block");
+        };
+        writeln!(
+            f,
+            "  {label:<10} {} txs ({} votes, {} unresolved), fees: {} ({} priority), cost: {} \
+             ({:.1}% of {})",
+            stats.num_transactions,
+            stats.num_vote_transactions,
+            stats.num_unresolved_transactions,
+            build_balance_message(stats.total_fees(), false, true),
+            build_balance_message(stats.priority_fees, false, true),
+            stats.cost,
+            stats.cost_utilization(),
+            stats.block_cost_limit,
+        )
+    }
+
+    fn write_totals(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        writeln!(f, "Total:")?;
+        Self::write_stats(f, "original:", Some(&self.report.original))?;
+        Self::write_stats(f, "simulated:", Some(&self.report.simulated))?;
+        writeln!(
+            f,
+            "  {:<10} {} txs",
+            "common:", self.report.num_common_transactions
+        )
+    }
+}
+
+impl QuietDisplay for CliSimulationReport {
+    fn write_str(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        self.write_totals(f)
+    }
+}
+impl VerboseDisplay for CliSimulationReport {}
+
+impl fmt::Display for CliSimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for slot in &self.report.slots {
+            writeln!(f, "Slot {}:", slot.slot)?;
+            Self::write_stats(f, "original:", slot.original.as_ref())?;
+            Self::write_stats(f, "simulated:", slot.simulated.as_ref())?;
+            writeln!(
+                f,
+                "  {:<10} {} txs",
+                "common:", slot.num_common_transactions
+            )?;
+        }
+        self.write_totals(f)?;
+
+        writeln!(f, "Most contended accounts:")?;
+        for account in &self.report.most_contended_accounts {
+            writeln!(
+                f,
+                "  {:<44}  original: {} txs, cost {}; simulated: {} txs, cost {}",
+                account.pubkey,
+                account.original.num_transactions,
+                account.original.cost,
+                account.simulated.num_transactions,
+                account.simulated.cost,
+            )?;
+        }
+        Ok(())
+    }
+}
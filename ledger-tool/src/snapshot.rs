@@ -0,0 +1,728 @@
+//! The `snapshot` subcommand
+
+use {
+    crate::{
+        args::{accounts_db_args, load_genesis_arg, parse_process_options, snapshot_args},
+        error::{LedgerToolError, Result},
+        ledger_path::{LEDGER_TOOL_DIRECTORY, canonicalize_ledger_path},
+        ledger_utils::{
+            LoadAndProcessLedgerOutput, load_and_process_ledger_or_exit, open_blockstore,
+            open_genesis_config_by,
+        },
+        output::{CliLtHashBisection, CliSnapshotDiff, CliSnapshotDiffBank},
+    },
+    agave_snapshots::{
+        paths::BANK_SNAPSHOTS_DIR,
+        seekable_archive::{SeekableArchive, SeekableArchiveEntry},
+        snapshot_archive_info::{
+            FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter as _,
+        },
+        snapshot_config::SnapshotConfig,
+    },
+    clap::{
+        App, AppSettings, Arg, ArgMatches, SubCommand, value_t, value_t_or_exit, values_t_or_exit,
+    },
+    log::*,
+    serde::{Serialize, de::DeserializeOwned},
+    solana_accounts_db::utils::create_all_accounts_run_and_snapshot_dirs,
+    solana_clap_utils::input_validators::{is_parsable, is_pow2, is_slot, is_within_range},
+    solana_cli_output::OutputFormat,
+    solana_clock::Slot,
+    solana_genesis_config::GenesisConfig,
+    solana_ledger::blockstore_options::AccessType,
+    solana_runtime::{
+        bank::{
+            Bank,
+            bank_hash_details::{BankHashDetails, SlotDetails},
+            lt_hash_bisection::{
+                self, LtHashBisectionConfig, LtHashBisectionError, LtHashBisectionSource,
+                MAX_BISECTION_OFFSET, RemoteBisectionPeer, RemoteBisectionRequest,
+                RemoteBisectionResponse,
+            },
+        },
+        snapshot_bank_utils,
+    },
+    std::{
+        fs::{self, File},
+        io::{self, BufReader, BufWriter, Write},
+        num::NonZeroUsize,
+        path::{Path, PathBuf},
+        sync::{Arc, atomic::AtomicBool},
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+// Name of the file in which `snapshot bisect-serve` describes its side of a remote bisection
+const REMOTE_BANK_FILE: &str = "bank.json";
+// Name of the file in which either side of a remote bisection reports that it failed
+const REMOTE_ERROR_FILE: &str = "error.json";
+const EXCHANGE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub trait SnapshotSubCommand {
+    fn snapshot_subcommand(self) -> Self;
+}
+
+impl SnapshotSubCommand for App<'_, '_> {
+    fn snapshot_subcommand(self) -> Self {
+        let comparison_args = comparison_args();
+
+        self.subcommand(
+            SubCommand::with_name("snapshot")
+                .about("Commands to inspect snapshot archives")
+                .setting(AppSettings::InferSubcommands)
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("diff")
+                        .about(
+                            "Compare the accounts of two snapshots, or of a snapshot and the bank \
+                             replayed from the ledger",
+                        )
+                        .args(&comparison_args),
+                )
+                .subcommand(
+                    SubCommand::with_name("bisect")
+                        .about(
+                            "Find the accounts that diverged between two snapshots or bank hash \
+                             details files by bisecting over lattice hashes of pubkey ranges",
+                        )
+                        .args(&comparison_args)
+                        .arg(slot_arg())
+                        .arg(
+                            Arg::with_name("remote_exchange_dir")
+                                .long("remote-exchange-dir")
+                                .value_name("DIR")
+                                .takes_value(true)
+                                .conflicts_with_all(&["right_snapshot_archive", "halt_at_slot"])
+                                .help(
+                                    "Bisect LEFT against the side served by a remote `snapshot \
+                                     bisect-serve`, exchanging requests and responses with it \
+                                     through files in DIR. DIR must be empty and is typically \
+                                     synced between both nodes",
+                                ),
+                        )
+                        .arg(exchange_timeout_arg())
+                        .arg(
+                            Arg::with_name("bisection_offset")
+                                .long("bisection-offset")
+                                .value_name("BITS")
+                                .takes_value(true)
+                                .default_value("128")
+                                .validator(|value| is_within_range(value, 0..=MAX_BISECTION_OFFSET))
+                                .help("Bit offset into the pubkey used to partition accounts"),
+                        )
+                        .arg(
+                            Arg::with_name("bisection_fanout")
+                                .long("bisection-fanout")
+                                .value_name("NUMBER")
+                                .takes_value(true)
+                                .default_value("16")
+                                .validator(|value| {
+                                    is_pow2(&value)?;
+                                    is_within_range(value, 2..)
+                                })
+                                .help(
+                                    "Number of partitions each divergent partition is split into",
+                                ),
+                        )
+                        .arg(
+                            Arg::with_name("max_leaf_accounts")
+                                .long("max-leaf-accounts")
+                                .value_name("NUMBER")
+                                .takes_value(true)
+                                .default_value("256")
+                                .validator(is_parsable::<usize>)
+                                .help(
+                                    "Compare individual accounts once the divergent partitions \
+                                     contain at most this many accounts",
+                                ),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("bisect-serve")
+                        .about(
+                            "Answer the requests of a remote `snapshot bisect \
+                             --remote-exchange-dir` about a snapshot or bank hash details file",
+                        )
+                        .arg(load_genesis_arg())
+                        .arg(
+                            Arg::with_name("left_snapshot_archive")
+                                .index(1)
+                                .value_name("SNAPSHOT")
+                                .takes_value(true)
+                                .required(true)
+                                .help(
+                                    "Full snapshot archive or bank hash details file of the side \
+                                     to serve",
+                                ),
+                        )
+                        .arg(
+                            Arg::with_name("left_incremental_snapshot_archive")
+                                .long("incremental-snapshot-archive")
+                                .value_name("PATH")
+                                .takes_value(true)
+                                .help("Incremental snapshot archive to apply on top of SNAPSHOT"),
+                        )
+                        .args(&accounts_db_args())
+                        .args(&snapshot_args())
+                        .arg(slot_arg())
+                        .arg(
+                            Arg::with_name("exchange_dir")
+                                .long("exchange-dir")
+                                .value_name("DIR")
+                                .takes_value(true)
+                                .required(true)
+                                .help(
+                                    "Directory through which requests and responses are \
+                                     exchanged with the remote `snapshot bisect`",
+                                ),
+                        )
+                        .arg(exchange_timeout_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("extract-storages")
+                        .about(
+                            "Extract the account storage files of some slots from a seekable \
+                             snapshot archive, without unpacking the rest of the archive",
+                        )
+                        .arg(
+                            Arg::with_name("snapshot_archive")
+                                .index(1)
+                                .value_name("SNAPSHOT")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Seekable snapshot archive (.tar.seekable.zst)"),
+                        )
+                        .arg(
+                            Arg::with_name("output_directory")
+                                .index(2)
+                                .value_name("DIR")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Directory to write the account storage files to"),
+                        )
+                        .arg(
+                            Arg::with_name("storage_slots")
+                                .long("slot")
+                                .value_name("SLOT")
+                                .takes_value(true)
+                                .multiple(true)
+                                .number_of_values(1)
+                                .required(true)
+                                .validator(is_slot)
+                                .help("Slot whose account storages to extract, may be repeated"),
+                        ),
+                ),
+        )
+    }
+}
+
+fn slot_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("slot")
+        .long("slot")
+        .value_name("SLOT")
+        .validator(is_slot)
+        .takes_value(true)
+        .help("Slot to use from bank hash details files that contain more than one slot")
+}
+
+fn exchange_timeout_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("exchange_timeout")
+        .long("exchange-timeout")
+        .value_name("SECONDS")
+        .takes_value(true)
+        .default_value("600")
+        .validator(is_parsable::<u64>)
+        .help("How long to wait for the other side of a remote bisection")
+}
+
+/// Returns the arguments that select the two sides of a comparison
+fn comparison_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    let mut args = vec![
+        load_genesis_arg(),
+        Arg::with_name("left_snapshot_archive")
+            .index(1)
+            .value_name("LEFT")
+            .takes_value(true)
+            .required(true)
+            .help(
+                "Full snapshot archive, or bank hash details file where supported, of the left \
+                 side of the comparison",
+            ),
+        Arg::with_name("right_snapshot_archive")
+            .index(2)
+            .value_name("RIGHT")
+            .takes_value(true)
+            .help(
+                "Full snapshot archive, or bank hash details file where supported, of the right \
+                 side of the comparison [default: the bank replayed from the ledger]",
+            ),
+        Arg::with_name("left_incremental_snapshot_archive")
+            .long("left-incremental-snapshot-archive")
+            .value_name("PATH")
+            .takes_value(true)
+            .help("Incremental snapshot archive to apply on top of LEFT"),
+        Arg::with_name("right_incremental_snapshot_archive")
+            .long("right-incremental-snapshot-archive")
+            .value_name("PATH")
+            .takes_value(true)
+            .requires("right_snapshot_archive")
+            .help("Incremental snapshot archive to apply on top of RIGHT"),
+        Arg::with_name("halt_at_slot")
+            .long("halt-at-slot")
+            .value_name("SLOT")
+            .validator(is_slot)
+            .takes_value(true)
+            .conflicts_with("right_snapshot_archive")
+            .help(
+                "Halt processing at the given slot when replaying the right side from the ledger",
+            ),
+    ];
+    args.extend(accounts_db_args().into_vec());
+    args.extend(snapshot_args().into_vec());
+    args
+}
+
+pub fn snapshot_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
+    do_snapshot_process_command(ledger_path, matches).unwrap_or_else(|err| {
+        eprintln!("Failed to complete command: {err:?}");
+        std::process::exit(1);
+    });
+}
+
+fn do_snapshot_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -> Result<()> {
+    let ledger_path = canonicalize_ledger_path(ledger_path);
+
+    match matches.subcommand() {
+        ("diff", Some(arg_matches)) => {
+            let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+            let load_bank = |side: &str| -> Result<Arc<Bank>> {
+                match load_comparison_side(&ledger_path, arg_matches, side)? {
+                    ComparisonSide::Bank(bank) => Ok(bank),
+                    ComparisonSide::BankHashDetails(_) => Err(LedgerToolError::BadArgument(
+                        "bank hash details files are only supported by `snapshot bisect`"
+                            .to_string(),
+                    )),
+                }
+            };
+            let left = load_bank("left")?;
+            let right = load_bank("right")?;
+
+            info!(
+                "Comparing accounts of slot {} (left) and slot {} (right)",
+                left.slot(),
+                right.slot(),
+            );
+            let diff = left.diff_accounts(&right).map_err(|err| {
+                LedgerToolError::Generic(format!("failed to scan accounts: {err}"))
+            })?;
+            let cli_diff = CliSnapshotDiff::new(
+                CliSnapshotDiffBank::from_bank(&left),
+                CliSnapshotDiffBank::from_bank(&right),
+                &diff,
+            );
+            println!("{}", output_format.formatted_string(&cli_diff));
+        }
+        ("bisect", Some(arg_matches)) => {
+            let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+            let config = LtHashBisectionConfig {
+                offset: value_t_or_exit!(arg_matches, "bisection_offset", usize),
+                fanout: value_t_or_exit!(arg_matches, "bisection_fanout", NonZeroUsize),
+                max_leaf_accounts: value_t_or_exit!(arg_matches, "max_leaf_accounts", usize),
+            };
+            let left = load_comparison_side(&ledger_path, arg_matches, "left")?;
+
+            let (right_bank, bisection) =
+                if let Some(exchange_dir) = arg_matches.value_of("remote_exchange_dir") {
+                    let mut exchange =
+                        BisectionExchangeDir::new(exchange_dir, exchange_timeout(arg_matches))?;
+                    if exchange.request_path(0).exists() {
+                        return Err(LedgerToolError::BadArgument(format!(
+                            "{exchange_dir} already contains bisection requests"
+                        )));
+                    }
+                    info!("Waiting for the remote side in {exchange_dir}");
+                    let right_bank: CliSnapshotDiffBank =
+                        exchange.read(&exchange.dir.join(REMOTE_BANK_FILE))?;
+                    let bisection = lt_hash_bisection::bisect_accounts_with_remote(
+                        left.bisection_source(),
+                        &mut exchange,
+                        config,
+                    );
+                    if let Err(err) = &bisection {
+                        exchange.write_error(&err.to_string());
+                    }
+                    (right_bank, bisection)
+                } else {
+                    let right = load_comparison_side(&ledger_path, arg_matches, "right")?;
+                    let bisection = lt_hash_bisection::bisect_accounts(
+                        left.bisection_source(),
+                        right.bisection_source(),
+                        config,
+                    );
+                    (right.cli_bank(), bisection)
+                };
+            let (diff, rounds) = bisection
+                .map_err(|err| LedgerToolError::Generic(format!("failed to bisect: {err}")))?;
+            let cli_bisection = CliLtHashBisection {
+                diff: CliSnapshotDiff::new(left.cli_bank(), right_bank, &diff),
+                rounds,
+            };
+            println!("{}", output_format.formatted_string(&cli_bisection));
+        }
+        ("bisect-serve", Some(arg_matches)) => {
+            let exchange_dir = arg_matches.value_of("exchange_dir").unwrap();
+            let exchange = BisectionExchangeDir::new(exchange_dir, exchange_timeout(arg_matches))?;
+            if exchange.response_path(0).exists() {
+                return Err(LedgerToolError::BadArgument(format!(
+                    "{exchange_dir} already contains bisection responses"
+                )));
+            }
+            let serve = || -> Result<()> {
+                let side = load_comparison_side(&ledger_path, arg_matches, "left")?;
+                exchange.write(&exchange.dir.join(REMOTE_BANK_FILE), &side.cli_bank())?;
+                info!("Serving bisection requests in {exchange_dir}");
+                exchange.serve(side.bisection_source())
+            };
+            // let the remote side fail right away rather than wait for its timeout
+            serve().inspect_err(|err| exchange.write_error(&err.to_string()))?;
+        }
+        ("extract-storages", Some(arg_matches)) => {
+            let archive_path = value_t_or_exit!(arg_matches, "snapshot_archive", PathBuf);
+            let output_dir = value_t_or_exit!(arg_matches, "output_directory", PathBuf);
+            let slots = values_t_or_exit!(arg_matches, "storage_slots", Slot);
+
+            let archive = SeekableArchive::open(&archive_path).map_err(|err| {
+                LedgerToolError::Generic(format!(
+                    "failed to open seekable snapshot archive {}: {err}",
+                    archive_path.display()
+                ))
+            })?;
+            fs::create_dir_all(&output_dir)?;
+            for slot in slots {
+                let entries: Vec<_> = archive
+                    .entries()
+                    .iter()
+                    .filter(|entry| storage_entry_slot(entry) == Some(slot))
+                    .collect();
+                if entries.is_empty() {
+                    return Err(LedgerToolError::BadArgument(format!(
+                        "{} has no account storage for slot {slot}",
+                        archive_path.display()
+                    )));
+                }
+                for entry in entries {
+                    let file_name = Path::new(&entry.path)
+                        .file_name()
+                        .expect("storage entries are files");
+                    let path = output_dir.join(file_name);
+                    let mut writer = BufWriter::new(File::create(&path)?);
+                    io::copy(&mut archive.entry_reader(entry)?, &mut writer)?;
+                    writer.flush()?;
+                    println!("{}", path.display());
+                }
+            }
+        }
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+/// Returns the slot of an account storage entry, whose file name is `<slot>.<id>`
+fn storage_entry_slot(entry: &SeekableArchiveEntry) -> Option<Slot> {
+    let file_name = Path::new(&entry.path).file_name()?.to_str()?;
+    let (slot, _id) = file_name.split_once('.')?;
+    slot.parse().ok()
+}
+
+fn exchange_timeout(arg_matches: &ArgMatches<'_>) -> Duration {
+    Duration::from_secs(value_t_or_exit!(arg_matches, "exchange_timeout", u64))
+}
+
+/// Exchanges the requests and responses of a remote bisection as JSON files in a directory that
+/// both sides can access
+///
+/// `snapshot bisect` writes `request-<N>.json` and `snapshot bisect-serve` answers each of them
+/// with `response-<N>.json`. Files are written under a temporary name and then renamed, so that
+/// the other side never reads a partially written file. A side that fails writes its error to
+/// `error.json`, which makes the other side fail too instead of waiting for its timeout.
+struct BisectionExchangeDir {
+    dir: PathBuf,
+    timeout: Duration,
+    next_request_id: usize,
+}
+
+impl BisectionExchangeDir {
+    fn new(dir: impl Into<PathBuf>, timeout: Duration) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            timeout,
+            next_request_id: 0,
+        })
+    }
+
+    fn request_path(&self, id: usize) -> PathBuf {
+        self.dir.join(format!("request-{id}.json"))
+    }
+
+    fn response_path(&self, id: usize) -> PathBuf {
+        self.dir.join(format!("response-{id}.json"))
+    }
+
+    fn write(&self, path: &Path, value: &impl Serialize) -> Result<()> {
+        let temp_path = path.with_extension("json.tmp");
+        let mut writer = BufWriter::new(File::create(&temp_path)?);
+        serde_json::to_writer(&mut writer, value)?;
+        writer.flush()?;
+        drop(writer);
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Waits for the file at `path` to show up and reads it
+    fn read<T: DeserializeOwned>(&self, path: &Path) -> Result<T> {
+        let start = Instant::now();
+        let error_path = self.dir.join(REMOTE_ERROR_FILE);
+        while !path.exists() {
+            if error_path.exists() {
+                let err: String =
+                    serde_json::from_reader(BufReader::new(File::open(&error_path)?))?;
+                return Err(LedgerToolError::Generic(format!(
+                    "remote side of the bisection failed: {err}"
+                )));
+            }
+            if start.elapsed() > self.timeout {
+                return Err(LedgerToolError::Generic(format!(
+                    "timed out waiting for {}",
+                    path.display()
+                )));
+            }
+            thread::sleep(EXCHANGE_POLL_INTERVAL);
+        }
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+
+    /// Reports `err` to the remote side
+    fn write_error(&self, err: &str) {
+        if let Err(write_err) = self.write(&self.dir.join(REMOTE_ERROR_FILE), &err) {
+            warn!("Failed to report error to the remote side: {write_err}");
+        }
+    }
+
+    /// Answers requests about `source` until the remote side is done
+    fn serve(&self, source: &dyn LtHashBisectionSource) -> Result<()> {
+        let mut id = 0;
+        loop {
+            let request: RemoteBisectionRequest = self.read(&self.request_path(id))?;
+            debug!("Answering bisection request {id}: {request:?}");
+            let response = lt_hash_bisection::respond_to_remote(source, &request)
+                .map_err(|err| LedgerToolError::Generic(format!("failed to bisect: {err}")))?;
+            let Some(response) = response else {
+                return Ok(());
+            };
+            self.write(&self.response_path(id), &response)?;
+            id = id.saturating_add(1);
+        }
+    }
+}
+
+impl RemoteBisectionPeer for BisectionExchangeDir {
+    fn exchange(
+        &mut self,
+        request: &RemoteBisectionRequest,
+    ) -> std::result::Result<Option<RemoteBisectionResponse>, LtHashBisectionError> {
+        let id = self.next_request_id;
+        self.next_request_id = id.saturating_add(1);
+        let remote_error = |err: LedgerToolError| LtHashBisectionError::Remote(err.to_string());
+        self.write(&self.request_path(id), request)
+            .map_err(remote_error)?;
+        if *request == RemoteBisectionRequest::Done {
+            return Ok(None);
+        }
+        self.read(&self.response_path(id))
+            .map(Some)
+            .map_err(remote_error)
+    }
+}
+
+/// One side of a comparison
+enum ComparisonSide {
+    Bank(Arc<Bank>),
+    BankHashDetails(SlotDetails),
+}
+
+impl ComparisonSide {
+    fn bisection_source(&self) -> &dyn LtHashBisectionSource {
+        match self {
+            Self::Bank(bank) => bank.as_ref(),
+            Self::BankHashDetails(slot_details) => slot_details
+                .bank_hash_components
+                .as_ref()
+                .map(|components| &components.accounts)
+                .expect("checked when loading"),
+        }
+    }
+
+    fn cli_bank(&self) -> CliSnapshotDiffBank {
+        match self {
+            Self::Bank(bank) => CliSnapshotDiffBank::from_bank(bank),
+            Self::BankHashDetails(slot_details) => {
+                CliSnapshotDiffBank::from_slot_details(slot_details)
+            }
+        }
+    }
+}
+
+/// Loads the `<side>` of a comparison
+///
+/// A side is either a snapshot archive, a bank hash details file (ending in
+/// `.json`), or, for the right side only, the bank replayed from the ledger.
+fn load_comparison_side(
+    ledger_path: &Path,
+    arg_matches: &ArgMatches<'_>,
+    side: &str,
+) -> Result<ComparisonSide> {
+    let Some(path) = arg_matches.value_of(format!("{side}_snapshot_archive")) else {
+        let genesis_config = open_genesis_config_by(ledger_path, arg_matches);
+        let process_options = parse_process_options(ledger_path, arg_matches);
+        let blockstore = open_blockstore(ledger_path, arg_matches, AccessType::ReadOnly);
+        let LoadAndProcessLedgerOutput { bank_forks, .. } = load_and_process_ledger_or_exit(
+            arg_matches,
+            &genesis_config,
+            Arc::new(blockstore),
+            process_options,
+            None,
+        );
+        return Ok(ComparisonSide::Bank(
+            bank_forks.read().unwrap().working_bank(),
+        ));
+    };
+
+    if Path::new(path).extension().is_some_and(|ext| ext == "json") {
+        return load_slot_details(Path::new(path), arg_matches)
+            .map(ComparisonSide::BankHashDetails);
+    }
+
+    let genesis_config = open_genesis_config_by(ledger_path, arg_matches);
+    load_bank_from_snapshot_archives(ledger_path, arg_matches, &genesis_config, side)
+        .map(|bank| ComparisonSide::Bank(Arc::new(bank)))
+}
+
+/// Loads the details of one slot, including its accounts, from a bank hash details file
+fn load_slot_details(path: &Path, arg_matches: &ArgMatches<'_>) -> Result<SlotDetails> {
+    let bank_hash_details: BankHashDetails =
+        serde_json::from_reader(BufReader::new(File::open(path)?))?;
+    let slot = value_t!(arg_matches, "slot", Slot).ok();
+    let mut slot_details: Vec<_> = bank_hash_details
+        .bank_hash_details
+        .into_iter()
+        .filter(|details| slot.is_none_or(|slot| slot == details.slot))
+        .collect();
+    let slot_details = match slot_details.len() {
+        1 => slot_details.pop().unwrap(),
+        0 => {
+            return Err(LedgerToolError::BadArgument(format!(
+                "{} does not contain the requested slot",
+                path.display()
+            )));
+        }
+        _ => {
+            return Err(LedgerToolError::BadArgument(format!(
+                "{} contains more than one slot, use --slot to select one",
+                path.display()
+            )));
+        }
+    };
+    if slot_details.bank_hash_components.is_none() {
+        return Err(LedgerToolError::BadArgument(format!(
+            "{} does not contain the accounts of slot {}",
+            path.display(),
+            slot_details.slot
+        )));
+    }
+    Ok(slot_details)
+}
+
+/// Loads a bank from the `<side>_snapshot_archive` and optional
+/// `<side>_incremental_snapshot_archive` arguments
+///
+/// Each side unpacks into its own directory under the ledger tool directory so
+/// that both banks can be alive at the same time.
+fn load_bank_from_snapshot_archives(
+    ledger_path: &Path,
+    arg_matches: &ArgMatches<'_>,
+    genesis_config: &GenesisConfig,
+    side: &str,
+) -> Result<Bank> {
+    let full_snapshot_archive_info = FullSnapshotArchiveInfo::new_from_path(PathBuf::from(
+        arg_matches
+            .value_of(format!("{side}_snapshot_archive"))
+            .unwrap(),
+    ))
+    .map_err(|err| LedgerToolError::BadArgument(err.to_string()))?;
+    let incremental_snapshot_archive_info = arg_matches
+        .value_of(format!("{side}_incremental_snapshot_archive"))
+        .map(|path| IncrementalSnapshotArchiveInfo::new_from_path(PathBuf::from(path)))
+        .transpose()
+        .map_err(|err| LedgerToolError::BadArgument(err.to_string()))?;
+
+    let working_dir = ledger_path
+        .join(LEDGER_TOOL_DIRECTORY)
+        .join("snapshot_diff")
+        .join(side);
+    if working_dir.exists() {
+        fs::remove_dir_all(&working_dir)?;
+    }
+    let bank_snapshots_dir = working_dir.join(BANK_SNAPSHOTS_DIR);
+    fs::create_dir_all(&bank_snapshots_dir)?;
+    let (account_paths, _account_snapshot_paths) =
+        create_all_accounts_run_and_snapshot_dirs(&[working_dir.join("accounts")])?;
+
+    let process_options = parse_process_options(ledger_path, arg_matches);
+    let mut accounts_db_config = process_options.accounts_db_config;
+    // The disk index wipes its drives on startup, so each side needs its own
+    if let Some(index) = accounts_db_config.index.as_mut() {
+        index.drives = Some(vec![working_dir.join("accounts_index")]);
+    }
+    let snapshot_config = SnapshotConfig {
+        full_snapshot_archives_dir: full_snapshot_archive_info
+            .path()
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+        incremental_snapshot_archives_dir: incremental_snapshot_archive_info
+            .as_ref()
+            .and_then(|info| info.path().parent())
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+        bank_snapshots_dir,
+        ..SnapshotConfig::new_load_only()
+    };
+
+    info!(
+        "Loading {side} bank from {}",
+        full_snapshot_archive_info.path().display()
+    );
+    let bank = snapshot_bank_utils::bank_from_snapshot_archives(
+        &account_paths,
+        &full_snapshot_archive_info,
+        incremental_snapshot_archive_info.as_ref(),
+        &snapshot_config,
+        genesis_config,
+        &process_options.runtime_config,
+        process_options.debug_keys,
+        None,
+        None,
+        process_options.accounts_db_skip_shrink,
+        false,
+        process_options.verify_index,
+        accounts_db_config,
+        None,
+        Arc::new(AtomicBool::new(false)),
+    )
+    .map_err(|err| LedgerToolError::Generic(format!("failed to load {side} bank: {err}")))?;
+    Ok(bank)
+}
@@ -0,0 +1,686 @@
+//! The `vote-history` subcommand
+//!
+//! Offline tools for the files a validator persists its own votes in, so they can be inspected and
+//! carried over when the validator moves to a new identity:
+//!
+//! | File | Format |
+//! |---|---|
+//! | `tower-<IDENTITY>.bin` | `tower1_7_14`, towers saved before 1.9 |
+//! | `tower-1_9-<IDENTITY>.bin` | `tower1_14_11`, a signed `SavedTower` |
+//! | `vote_history-<IDENTITY>.bin` | `vote-history`, a signed Alpenglow `SavedVoteHistory` |
+//!
+//! The format and identity of a file are taken from its name, `--format` and `--identity`
+//! override them for renamed files. Files written by this command are named as the validator
+//! expects them and are never overwritten without `--force`.
+
+use {
+    crate::error::{LedgerToolError, Result},
+    agave_votor::{
+        vote_history::VoteHistory,
+        vote_history_storage::{
+            FileVoteHistoryStorage, SavedVoteHistory, SavedVoteHistoryVersions, VoteHistoryStorage,
+        },
+    },
+    clap::{App, AppSettings, Arg, ArgMatches, SubCommand, value_t_or_exit},
+    solana_clap_utils::{
+        input_parsers::{keypair_of, pubkey_of},
+        input_validators::{is_keypair, is_pubkey},
+    },
+    solana_clock::Slot,
+    solana_core::consensus::{
+        Tower,
+        tower_storage::{FileTowerStorage, SavedTower, SavedTowerVersions, TowerStorage},
+    },
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    std::{
+        fmt, fs,
+        path::{Path, PathBuf},
+        process::exit,
+        str::FromStr,
+    },
+};
+
+const FILE_FORMATS: &[&str] = &["tower1_7_14", "tower1_14_11", "vote-history"];
+
+pub trait VoteHistorySubCommand {
+    fn vote_history_subcommand(self) -> Self;
+}
+
+impl VoteHistorySubCommand for App<'_, '_> {
+    fn vote_history_subcommand(self) -> Self {
+        let file_args = [
+            Arg::with_name("file")
+                .index(1)
+                .value_name("FILE")
+                .takes_value(true)
+                .required(true)
+                .help("Tower or vote history file"),
+            Arg::with_name("file_format")
+                .long("format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(FILE_FORMATS)
+                .help("Format of FILE [default: derived from the file name]"),
+            Arg::with_name("identity")
+                .long("identity")
+                .value_name("PUBKEY")
+                .takes_value(true)
+                .validator(is_pubkey)
+                .help(
+                    "Identity FILE is expected to be signed by [default: derived from the file \
+                     name]",
+                ),
+        ];
+        let output_args = [
+            Arg::with_name("output_dir")
+                .long("output-dir")
+                .value_name("DIR")
+                .takes_value(true)
+                .required(true)
+                .help("Directory to write the new file to, created if missing"),
+            Arg::with_name("force")
+                .long("force")
+                .takes_value(false)
+                .help("Overwrite existing files in the output directory"),
+        ];
+
+        self.subcommand(
+            SubCommand::with_name("vote-history")
+                .about("Commands to inspect and convert tower and vote history files")
+                .setting(AppSettings::InferSubcommands)
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("print")
+                        .about("Print the contents of a tower or vote history file")
+                        .args(&file_args),
+                )
+                .subcommand(
+                    SubCommand::with_name("verify")
+                        .about(
+                            "Verify that a tower or vote history file is signed by, and belongs \
+                             to, its identity",
+                        )
+                        .args(&file_args),
+                )
+                .subcommand(
+                    SubCommand::with_name("convert")
+                        .about(
+                            "Convert a tower to the current tower format or to a vote history \
+                             starting at the tower's root",
+                        )
+                        .args(&file_args)
+                        .args(&output_args)
+                        .arg(
+                            Arg::with_name("to")
+                                .long("to")
+                                .value_name("FORMAT")
+                                .takes_value(true)
+                                .required(true)
+                                .possible_values(&["tower1_14_11", "vote-history"])
+                                .help("Format to convert to"),
+                        )
+                        .arg(
+                            Arg::with_name("identity_keypair")
+                                .long("identity-keypair")
+                                .value_name("KEYPAIR")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_keypair)
+                                .help("Identity keypair FILE belongs to, signs the new file"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("migrate")
+                        .about(
+                            "Carry the votes of a tower or vote history file over to a new \
+                             identity",
+                        )
+                        .long_about(
+                            "Carry the votes of a tower or vote history file over to a new \
+                             identity. A vote history is re-signed by the new identity with all \
+                             of its votes, so the validator does not vote again in slots it \
+                             already voted in. A tower is re-signed by the new identity and a \
+                             fresh vote history starting at the tower's root is written next to \
+                             it.",
+                        )
+                        .args(&file_args)
+                        .args(&output_args)
+                        .arg(
+                            Arg::with_name("new_identity_keypair")
+                                .long("new-identity-keypair")
+                                .value_name("KEYPAIR")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_keypair)
+                                .help("Identity keypair to migrate to"),
+                        ),
+                ),
+        )
+    }
+}
+
+pub fn vote_history_process_command(matches: &ArgMatches<'_>) {
+    do_vote_history_process_command(matches).unwrap_or_else(|err| {
+        eprintln!("Failed to complete command: {err}");
+        exit(1);
+    });
+}
+
+fn do_vote_history_process_command(matches: &ArgMatches<'_>) -> Result<()> {
+    match matches.subcommand() {
+        ("print", Some(arg_matches)) => {
+            let (path, file) = vote_file_of(arg_matches)?;
+            let identity = identity_of(arg_matches, &path, &file)?;
+            println!("File: {}", path.display());
+            print!("{}", file.display(&identity)?);
+            Ok(())
+        }
+        ("verify", Some(arg_matches)) => {
+            let (path, file) = vote_file_of(arg_matches)?;
+            let identity = identity_of(arg_matches, &path, &file)?;
+            file.verify(&identity)?;
+            println!(
+                "{} is a {} signed by {identity}",
+                path.display(),
+                file.format()
+            );
+            Ok(())
+        }
+        ("convert", Some(arg_matches)) => {
+            let (_path, file) = vote_file_of(arg_matches)?;
+            let to = value_t_or_exit!(arg_matches, "to", FileFormat);
+            let identity_keypair = keypair_of(arg_matches, "identity_keypair").unwrap();
+            let output_dir = value_t_or_exit!(arg_matches, "output_dir", PathBuf);
+            let written = convert(
+                &file,
+                to,
+                &identity_keypair,
+                &output_dir,
+                arg_matches.is_present("force"),
+            )?;
+            println!("Wrote {}", written.display());
+            Ok(())
+        }
+        ("migrate", Some(arg_matches)) => {
+            let (path, file) = vote_file_of(arg_matches)?;
+            let identity = identity_of(arg_matches, &path, &file)?;
+            let new_identity_keypair = keypair_of(arg_matches, "new_identity_keypair").unwrap();
+            let output_dir = value_t_or_exit!(arg_matches, "output_dir", PathBuf);
+            for written in migrate(
+                &file,
+                &identity,
+                &new_identity_keypair,
+                &output_dir,
+                arg_matches.is_present("force"),
+            )? {
+                println!("Wrote {}", written.display());
+            }
+            Ok(())
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FileFormat {
+    Tower1_7_14,
+    Tower1_14_11,
+    VoteHistory,
+}
+
+impl FileFormat {
+    /// File name prefixes, most specific first since the tower ones overlap
+    const FILE_NAME_PREFIXES: [(&'static str, Self); 3] = [
+        ("vote_history-", Self::VoteHistory),
+        ("tower-1_9-", Self::Tower1_14_11),
+        ("tower-", Self::Tower1_7_14),
+    ];
+
+    /// Derives the format and identity of a file from its name
+    fn from_file_name(path: &Path) -> Option<(Self, Option<Pubkey>)> {
+        let file_name = path.file_name()?.to_str()?;
+        Self::FILE_NAME_PREFIXES
+            .into_iter()
+            .find_map(|(prefix, format)| {
+                let identity = file_name.strip_prefix(prefix)?;
+                let identity = identity.strip_suffix(".bin").unwrap_or(identity);
+                Some((format, Pubkey::from_str(identity).ok()))
+            })
+    }
+}
+
+impl FromStr for FileFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "tower1_7_14" => Ok(Self::Tower1_7_14),
+            "tower1_14_11" => Ok(Self::Tower1_14_11),
+            "vote-history" => Ok(Self::VoteHistory),
+            _ => Err(format!("unknown file format: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for FileFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Tower1_7_14 => "tower1_7_14",
+            Self::Tower1_14_11 => "tower1_14_11",
+            Self::VoteHistory => "vote-history",
+        })
+    }
+}
+
+/// A tower or vote history as read from disk, before its signature is checked
+enum VoteFile {
+    Tower(SavedTowerVersions),
+    VoteHistory(SavedVoteHistoryVersions),
+}
+
+impl VoteFile {
+    fn read(path: &Path, format: FileFormat) -> Result<Self> {
+        let file = match format {
+            FileFormat::Tower1_7_14 => FileTowerStorage::read_old_saved_tower(path)
+                .map(Self::Tower)
+                .map_err(|err| err.to_string()),
+            FileFormat::Tower1_14_11 => FileTowerStorage::read_saved_tower(path)
+                .map(Self::Tower)
+                .map_err(|err| err.to_string()),
+            FileFormat::VoteHistory => FileVoteHistoryStorage::read_saved_vote_history(path)
+                .map(Self::VoteHistory)
+                .map_err(|err| err.to_string()),
+        };
+        file.map_err(|err| {
+            LedgerToolError::Generic(format!("failed to read {}: {err}", path.display()))
+        })
+    }
+
+    fn format(&self) -> FileFormat {
+        match self {
+            Self::Tower(SavedTowerVersions::V1_17_14(_)) => FileFormat::Tower1_7_14,
+            Self::Tower(SavedTowerVersions::Current(_)) => FileFormat::Tower1_14_11,
+            Self::VoteHistory(_) => FileFormat::VoteHistory,
+        }
+    }
+
+    /// The identity recorded in the file, which nothing vouches for until the
+    /// file is verified
+    fn recorded_identity(&self) -> Result<Pubkey> {
+        match self {
+            Self::Tower(saved_tower) => saved_tower
+                .decode_unverified()
+                .map(|tower| tower.node_pubkey)
+                .map_err(|err| LedgerToolError::Generic(err.to_string())),
+            Self::VoteHistory(saved_vote_history) => saved_vote_history
+                .decode_unverified()
+                .map(|vote_history| vote_history.node_pubkey)
+                .map_err(|err| LedgerToolError::Generic(err.to_string())),
+        }
+    }
+
+    /// Checks that the file is signed by and belongs to `identity`
+    fn verify(&self, identity: &Pubkey) -> Result<()> {
+        let verified = match self {
+            Self::Tower(saved_tower) => saved_tower
+                .try_into_tower(identity)
+                .map(|_| ())
+                .map_err(|err| err.to_string()),
+            Self::VoteHistory(saved_vote_history) => saved_vote_history
+                .try_into_vote_history(identity)
+                .map(|_| ())
+                .map_err(|err| err.to_string()),
+        };
+        verified.map_err(LedgerToolError::Generic)
+    }
+
+    /// Renders the contents of the file, noting whether `identity` signed it
+    fn display(&self, identity: &Pubkey) -> Result<String> {
+        let signature_status = match self.verify(identity) {
+            Ok(()) => format!("valid, signed by {identity}"),
+            Err(err) => format!("INVALID: {err}"),
+        };
+        let mut output = format!("Format: {}\nSignature: {signature_status}\n", self.format());
+        match self {
+            Self::Tower(saved_tower) => {
+                let tower = saved_tower
+                    .decode_unverified()
+                    .map_err(|err| LedgerToolError::Generic(err.to_string()))?;
+                output.push_str(&format!(
+                    "Identity: {}\nRoot: {}\nLast voted slot: {}\nTower slots: {:?}\nStray \
+                     restored slot: {}\nLast vote: {:?}\n",
+                    tower.node_pubkey,
+                    tower.root(),
+                    display_slot(tower.last_voted_slot()),
+                    tower.tower_slots(),
+                    display_slot(tower.stray_restored_slot()),
+                    tower.last_vote(),
+                ));
+            }
+            Self::VoteHistory(saved_vote_history) => {
+                let vote_history = saved_vote_history
+                    .decode_unverified()
+                    .map_err(|err| LedgerToolError::Generic(err.to_string()))?;
+                let highest_parent_ready = vote_history
+                    .highest_parent_ready()
+                    .map(|(slot, parent)| format!("{slot} (parent {parent:?})"))
+                    .unwrap_or_else(|| "none".to_string());
+                let mut votes =
+                    vote_history.votes_cast_since(vote_history.root().saturating_sub(1));
+                votes.sort_by_key(|vote| vote.slot());
+                output.push_str(&format!(
+                    "Identity: {}\nRoot: {}\nHighest parent ready: {highest_parent_ready}\nVotes \
+                     cast: {}\n",
+                    vote_history.node_pubkey,
+                    vote_history.root(),
+                    votes.len(),
+                ));
+                for vote in votes {
+                    output.push_str(&format!("  {}: {vote:?}\n", vote.slot()));
+                }
+            }
+        }
+        Ok(output)
+    }
+}
+
+fn display_slot(slot: Option<Slot>) -> String {
+    slot.map(|slot| slot.to_string())
+        .unwrap_or_else(|| "none".to_string())
+}
+
+/// Reads the file named by the `file` and `file_format` arguments
+fn vote_file_of(matches: &ArgMatches<'_>) -> Result<(PathBuf, VoteFile)> {
+    let path = value_t_or_exit!(matches, "file", PathBuf);
+    let format = match matches.value_of("file_format") {
+        Some(format) => format.parse().map_err(LedgerToolError::BadArgument)?,
+        None => FileFormat::from_file_name(&path)
+            .map(|(format, _)| format)
+            .ok_or_else(|| {
+                LedgerToolError::BadArgument(format!(
+                    "can not tell the format of {} from its name, use --format",
+                    path.display()
+                ))
+            })?,
+    };
+    let file = VoteFile::read(&path, format)?;
+    Ok((path, file))
+}
+
+/// Returns the identity `file` is expected to be signed by: the `identity`
+/// argument, else the identity in the file name, else the one recorded in the
+/// file itself
+fn identity_of(matches: &ArgMatches<'_>, path: &Path, file: &VoteFile) -> Result<Pubkey> {
+    if let Some(identity) = pubkey_of(matches, "identity") {
+        return Ok(identity);
+    }
+    match FileFormat::from_file_name(path).and_then(|(_, identity)| identity) {
+        Some(identity) => Ok(identity),
+        None => file.recorded_identity(),
+    }
+}
+
+/// Fails unless `path` is free to be written or `force` is set
+fn check_overwrite(path: &Path, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        return Err(LedgerToolError::BadArgument(format!(
+            "{} already exists, use --force to overwrite it",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+fn store_tower(
+    tower: &Tower,
+    keypair: &Keypair,
+    output_dir: &Path,
+    force: bool,
+) -> Result<PathBuf> {
+    let tower_storage = FileTowerStorage::new(output_dir.to_path_buf());
+    let path = tower_storage.filename(&keypair.pubkey());
+    check_overwrite(&path, force)?;
+    SavedTower::new(tower, keypair)
+        .and_then(|saved_tower| tower_storage.store(&SavedTowerVersions::from(saved_tower)))
+        .map_err(|err| LedgerToolError::Generic(err.to_string()))?;
+    Ok(path)
+}
+
+fn store_vote_history(
+    vote_history: &VoteHistory,
+    keypair: &Keypair,
+    output_dir: &Path,
+    force: bool,
+) -> Result<PathBuf> {
+    let vote_history_storage = FileVoteHistoryStorage::new(output_dir.to_path_buf());
+    let path = vote_history_storage.filename(&keypair.pubkey());
+    check_overwrite(&path, force)?;
+    SavedVoteHistory::new(vote_history, keypair)
+        .and_then(|saved_vote_history| {
+            vote_history_storage.store(&SavedVoteHistoryVersions::from(saved_vote_history))
+        })
+        .map_err(|err| LedgerToolError::Generic(err.to_string()))?;
+    Ok(path)
+}
+
+/// Converts the tower in `file`, which must belong to `identity_keypair`, to
+/// `to` and writes it to `output_dir`
+fn convert(
+    file: &VoteFile,
+    to: FileFormat,
+    identity_keypair: &Keypair,
+    output_dir: &Path,
+    force: bool,
+) -> Result<PathBuf> {
+    let VoteFile::Tower(saved_tower) = file else {
+        return Err(LedgerToolError::BadArgument(
+            "a vote history can not be converted to a tower".to_string(),
+        ));
+    };
+    let tower = saved_tower
+        .try_into_tower(&identity_keypair.pubkey())
+        .map_err(|err| LedgerToolError::Generic(err.to_string()))?;
+    fs::create_dir_all(output_dir)?;
+    match to {
+        FileFormat::Tower1_14_11 => store_tower(&tower, identity_keypair, output_dir, force),
+        FileFormat::VoteHistory => store_vote_history(
+            &VoteHistory::new(tower.node_pubkey, tower.root()),
+            identity_keypair,
+            output_dir,
+            force,
+        ),
+        FileFormat::Tower1_7_14 => Err(LedgerToolError::BadArgument(
+            "towers can not be converted to the pre 1.9 format".to_string(),
+        )),
+    }
+}
+
+/// Re-signs the votes in `file`, which must belong to `identity`, for
+/// `new_identity_keypair` and writes them to `output_dir`
+fn migrate(
+    file: &VoteFile,
+    identity: &Pubkey,
+    new_identity_keypair: &Keypair,
+    output_dir: &Path,
+    force: bool,
+) -> Result<Vec<PathBuf>> {
+    file.verify(identity)?;
+    let new_identity = new_identity_keypair.pubkey();
+    fs::create_dir_all(output_dir)?;
+    // Check every target up front so that nothing is written on failure
+    let tower_path = FileTowerStorage::new(output_dir.to_path_buf()).filename(&new_identity);
+    let vote_history_path =
+        FileVoteHistoryStorage::new(output_dir.to_path_buf()).filename(&new_identity);
+    check_overwrite(&vote_history_path, force)?;
+    match file {
+        VoteFile::Tower(saved_tower) => {
+            check_overwrite(&tower_path, force)?;
+            let mut tower = saved_tower
+                .try_into_tower(identity)
+                .map_err(|err| LedgerToolError::Generic(err.to_string()))?;
+            tower.node_pubkey = new_identity;
+            let vote_history = VoteHistory::new(new_identity, tower.root());
+            Ok(vec![
+                store_tower(&tower, new_identity_keypair, output_dir, force)?,
+                store_vote_history(&vote_history, new_identity_keypair, output_dir, force)?,
+            ])
+        }
+        VoteFile::VoteHistory(saved_vote_history) => {
+            let mut vote_history = saved_vote_history
+                .try_into_vote_history(identity)
+                .map_err(|err| LedgerToolError::Generic(err.to_string()))?;
+            vote_history.node_pubkey = new_identity;
+            Ok(vec![store_vote_history(
+                &vote_history,
+                new_identity_keypair,
+                output_dir,
+                force,
+            )?])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, agave_votor_messages::vote::Vote};
+
+    fn read(path: &Path) -> VoteFile {
+        let (format, _) = FileFormat::from_file_name(path).unwrap();
+        VoteFile::read(path, format).unwrap()
+    }
+
+    #[test]
+    fn test_file_format_from_file_name() {
+        let identity = Pubkey::new_unique();
+        for (file_name, format) in [
+            (format!("tower-{identity}.bin"), FileFormat::Tower1_7_14),
+            (
+                format!("tower-1_9-{identity}.bin"),
+                FileFormat::Tower1_14_11,
+            ),
+            (
+                format!("vote_history-{identity}.bin"),
+                FileFormat::VoteHistory,
+            ),
+        ] {
+            assert_eq!(
+                FileFormat::from_file_name(&Path::new("/ledger").join(file_name)),
+                Some((format, Some(identity)))
+            );
+        }
+        assert_eq!(
+            FileFormat::from_file_name(Path::new("tower-1_9-copy.bin")),
+            Some((FileFormat::Tower1_14_11, None))
+        );
+        assert_eq!(FileFormat::from_file_name(Path::new("backup.bin")), None);
+    }
+
+    #[test]
+    fn test_convert_tower() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let keypair = Keypair::new();
+        let mut tower = Tower::default();
+        tower.node_pubkey = keypair.pubkey();
+        let tower_path = store_tower(&tower, &keypair, temp_dir.path(), false).unwrap();
+        let file = read(&tower_path);
+        assert_eq!(file.format(), FileFormat::Tower1_14_11);
+        file.verify(&keypair.pubkey()).unwrap();
+        assert!(file.verify(&Pubkey::new_unique()).is_err());
+
+        let output_dir = temp_dir.path().join("converted");
+        let path = convert(&file, FileFormat::VoteHistory, &keypair, &output_dir, false).unwrap();
+        let VoteFile::VoteHistory(saved_vote_history) = read(&path) else {
+            panic!("expected a vote history");
+        };
+        let vote_history = saved_vote_history
+            .try_into_vote_history(&keypair.pubkey())
+            .unwrap();
+        assert_eq!(vote_history.root(), tower.root());
+
+        // The output is not overwritten unless forced
+        assert!(convert(&file, FileFormat::VoteHistory, &keypair, &output_dir, false).is_err());
+        convert(&file, FileFormat::VoteHistory, &keypair, &output_dir, true).unwrap();
+        // Converting with somebody else's keypair fails
+        assert!(
+            convert(
+                &file,
+                FileFormat::Tower1_14_11,
+                &Keypair::new(),
+                &output_dir,
+                true
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_migrate_vote_history() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let old_keypair = Keypair::new();
+        let new_keypair = Keypair::new();
+        let mut vote_history = VoteHistory::new(old_keypair.pubkey(), 10);
+        vote_history.add_vote(Vote::new_skip_vote(11));
+        let path = store_vote_history(&vote_history, &old_keypair, temp_dir.path(), false).unwrap();
+        let file = read(&path);
+        assert!(
+            file.display(&old_keypair.pubkey())
+                .unwrap()
+                .contains("valid, signed by")
+        );
+
+        let output_dir = temp_dir.path().join("migrated");
+        // The old identity must match
+        assert!(
+            migrate(
+                &file,
+                &new_keypair.pubkey(),
+                &new_keypair,
+                &output_dir,
+                false
+            )
+            .is_err()
+        );
+        let written = migrate(
+            &file,
+            &old_keypair.pubkey(),
+            &new_keypair,
+            &output_dir,
+            false,
+        )
+        .unwrap();
+        assert_eq!(written.len(), 1);
+        let VoteFile::VoteHistory(saved_vote_history) = read(&written[0]) else {
+            panic!("expected a vote history");
+        };
+        let migrated = saved_vote_history
+            .try_into_vote_history(&new_keypair.pubkey())
+            .unwrap();
+        assert_eq!(migrated.root(), 10);
+        assert_eq!(
+            migrated.votes_cast_since(10),
+            vote_history.votes_cast_since(10)
+        );
+
+        // An existing history of the new identity is not overwritten unless forced
+        assert!(
+            migrate(
+                &file,
+                &old_keypair.pubkey(),
+                &new_keypair,
+                &output_dir,
+                false
+            )
+            .is_err()
+        );
+        migrate(
+            &file,
+            &old_keypair.pubkey(),
+            &new_keypair,
+            &output_dir,
+            true,
+        )
+        .unwrap();
+    }
+}
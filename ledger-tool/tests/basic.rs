@@ -1,19 +1,31 @@
 use {
+    solana_account::{AccountSharedData, WritableAccount},
+    solana_hash::Hash,
     solana_ledger::{
         blockstore, blockstore::Blockstore, create_new_tmp_ledger_auto_delete,
         genesis_utils::create_genesis_config, get_tmp_ledger_path_auto_delete,
     },
+    solana_pubkey::Pubkey,
+    solana_runtime::bank::bank_hash_details::{
+        AccountsDetails, BankHashComponents, BankHashDetails, SlotDetails,
+    },
     std::{
-        path::Path,
+        fs::{self, File},
+        io::Read,
+        path::{Path, PathBuf},
         process::{Command, Output},
+        time::{Duration, Instant},
     },
 };
 
+fn ledger_tool_command(args: &[&str]) -> Command {
+    let mut command = Command::new(assert_cmd::cargo::cargo_bin!(env!("CARGO_PKG_NAME")));
+    command.args(args);
+    command
+}
+
 fn run_ledger_tool(args: &[&str]) -> Output {
-    Command::new(assert_cmd::cargo::cargo_bin!(env!("CARGO_PKG_NAME")))
-        .args(args)
-        .output()
-        .unwrap()
+    ledger_tool_command(args).output().unwrap()
 }
 
 #[test]
@@ -87,3 +99,228 @@ fn ledger_tool_copy_test() {
         assert!(!src_slot_output.stdout.is_empty());
     }
 }
+
+fn write_bank_hash_details(path: &Path, accounts: Vec<(Pubkey, AccountSharedData)>) {
+    let details = BankHashDetails::new(vec![SlotDetails {
+        slot: 42,
+        bank_hash: Hash::new_unique().to_string(),
+        bank_hash_components: Some(BankHashComponents {
+            accounts: AccountsDetails { accounts },
+            ..BankHashComponents::default()
+        }),
+        transactions: Vec::new(),
+    }]);
+    serde_json::to_writer(File::create(path).unwrap(), &details).unwrap();
+}
+
+#[test]
+fn snapshot_bisect_with_remote() {
+    let genesis_config = create_genesis_config(100).genesis_config;
+    let (ledger_path, _blockhash) = create_new_tmp_ledger_auto_delete!(&genesis_config);
+    let ledger_path = ledger_path.path().to_str().unwrap();
+    let temp_dir = tempfile::TempDir::new().unwrap();
+
+    let owner = Pubkey::new_unique();
+    let local_accounts: Vec<_> = (1..=1_000)
+        .map(|lamports| {
+            (
+                Pubkey::new_unique(),
+                AccountSharedData::new(lamports, 0, &owner),
+            )
+        })
+        .collect();
+    let mut remote_accounts = local_accounts.clone();
+    let changed = remote_accounts[123].0;
+    remote_accounts[123].1.set_lamports(1_000_000);
+    let added = Pubkey::new_unique();
+    remote_accounts.push((added, AccountSharedData::new(7, 0, &owner)));
+
+    let local_path = temp_dir.path().join("local.json");
+    let remote_path = temp_dir.path().join("remote.json");
+    let exchange_dir = temp_dir.path().join("exchange");
+    write_bank_hash_details(&local_path, local_accounts);
+    write_bank_hash_details(&remote_path, remote_accounts);
+
+    // Both sides only share the exchange directory, like two nodes syncing it would
+    let remote = ledger_tool_command(&[
+        "-l",
+        ledger_path,
+        "snapshot",
+        "bisect-serve",
+        remote_path.to_str().unwrap(),
+        "--exchange-dir",
+        exchange_dir.to_str().unwrap(),
+        "--exchange-timeout",
+        "60",
+    ])
+    .spawn()
+    .unwrap();
+    let output = run_ledger_tool(&[
+        "-l",
+        ledger_path,
+        "--output",
+        "json",
+        "snapshot",
+        "bisect",
+        local_path.to_str().unwrap(),
+        "--remote-exchange-dir",
+        exchange_dir.to_str().unwrap(),
+        "--exchange-timeout",
+        "60",
+        "--max-leaf-accounts",
+        "16",
+    ]);
+    assert!(remote.wait_with_output().unwrap().status.success());
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let bisection: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(bisection["numAdded"], 1);
+    assert_eq!(bisection["numRemoved"], 0);
+    assert_eq!(bisection["numChanged"], 1);
+    let mut pubkeys: Vec<_> = bisection["accounts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|account| account["pubkey"].as_str().unwrap().to_string())
+        .collect();
+    pubkeys.sort_unstable();
+    let mut expected = vec![added.to_string(), changed.to_string()];
+    expected.sort_unstable();
+    assert_eq!(pubkeys, expected);
+    assert!(bisection["rounds"].as_array().unwrap().len() > 1);
+}
+
+#[test]
+fn snapshot_bisect_with_failing_remote() {
+    let genesis_config = create_genesis_config(100).genesis_config;
+    let (ledger_path, _blockhash) = create_new_tmp_ledger_auto_delete!(&genesis_config);
+    let ledger_path = ledger_path.path().to_str().unwrap();
+    let temp_dir = tempfile::TempDir::new().unwrap();
+
+    let local_path = temp_dir.path().join("local.json");
+    let missing_path = temp_dir.path().join("missing.json");
+    let exchange_dir = temp_dir.path().join("exchange");
+    write_bank_hash_details(&local_path, Vec::new());
+
+    let remote = run_ledger_tool(&[
+        "-l",
+        ledger_path,
+        "snapshot",
+        "bisect-serve",
+        missing_path.to_str().unwrap(),
+        "--exchange-dir",
+        exchange_dir.to_str().unwrap(),
+    ]);
+    assert!(!remote.status.success());
+
+    // The failure of the remote side is reported instead of waiting out the default timeout
+    let start = Instant::now();
+    let output = run_ledger_tool(&[
+        "-l",
+        ledger_path,
+        "snapshot",
+        "bisect",
+        local_path.to_str().unwrap(),
+        "--remote-exchange-dir",
+        exchange_dir.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+    assert!(start.elapsed() < Duration::from_secs(60));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("remote side of the bisection failed"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn snapshot_extract_storages() {
+    let genesis_config = create_genesis_config(100).genesis_config;
+    let (ledger_path, _blockhash) = create_new_tmp_ledger_auto_delete!(&genesis_config);
+    let ledger_path = ledger_path.path().to_str().unwrap();
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let archive_dir = temp_dir.path().join("archives");
+    let extract_dir = temp_dir.path().join("extracted");
+
+    let output = run_ledger_tool(&[
+        "-l",
+        ledger_path,
+        "create-snapshot",
+        "0",
+        archive_dir.to_str().unwrap(),
+        "--snapshot-archive-format",
+        "zstd-seekable",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let archive_path = fs::read_dir(&archive_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.to_str().unwrap().ends_with(".tar.seekable.zst"))
+        .unwrap();
+
+    let output = run_ledger_tool(&[
+        "-l",
+        ledger_path,
+        "snapshot",
+        "extract-storages",
+        archive_path.to_str().unwrap(),
+        extract_dir.to_str().unwrap(),
+        "--slot",
+        "0",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let extracted: Vec<_> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(PathBuf::from)
+        .collect();
+    assert!(!extracted.is_empty());
+
+    // The extracted storages must match the ones found by unpacking the whole archive
+    let decoder = zstd::stream::read::Decoder::new(File::open(&archive_path).unwrap()).unwrap();
+    let mut archive = tar::Archive::new(decoder);
+    let mut num_matched = 0;
+    for entry in archive.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let path = entry.path().unwrap().into_owned();
+        if !path.starts_with("accounts") {
+            continue;
+        }
+        let Some(extracted_path) = extracted
+            .iter()
+            .find(|extracted_path| extracted_path.file_name() == path.file_name())
+        else {
+            continue;
+        };
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).unwrap();
+        assert_eq!(fs::read(extracted_path).unwrap(), data);
+        num_matched += 1;
+    }
+    assert_eq!(num_matched, extracted.len());
+
+    // there are no storages for slots past the snapshot
+    let output = run_ledger_tool(&[
+        "-l",
+        ledger_path,
+        "snapshot",
+        "extract-storages",
+        archive_path.to_str().unwrap(),
+        extract_dir.to_str().unwrap(),
+        "--slot",
+        "1",
+    ]);
+    assert!(!output.status.success());
+}
@@ -27,7 +27,8 @@ use {
     },
     agave_snapshots::unpack_genesis_archive,
     agave_votor_messages::{
-        migration::MigrationStatus, unverified_vote_message::UnverifiedCertificate,
+        equivocation::EquivocationProof, migration::MigrationStatus,
+        unverified_vote_message::UnverifiedCertificate,
     },
     assert_matches::{assert_matches, debug_assert_matches},
     crossbeam_channel::{Receiver, Sender, TrySendError, bounded},
@@ -372,6 +373,9 @@ pub struct Blockstore {
     roots_cf: LedgerColumn<cf::Root>,
     dead_slots_cf: LedgerColumn<cf::DeadSlots>,
 
+    // Alpenglow slashing evidence column families
+    equivocation_proofs_cf: LedgerColumn<cf::EquivocationProofs>,
+
     // Block and transaction metadata column families (for RPC)
     block_height_cf: LedgerColumn<cf::BlockHeight>,
     blocktime_cf: LedgerColumn<cf::Blocktime>,
@@ -694,6 +698,8 @@ impl Blockstore {
         let roots_cf = db.column();
         let dead_slots_cf = db.column();
 
+        let equivocation_proofs_cf = db.column();
+
         let block_height_cf = db.column();
         let blocktime_cf = db.column();
         let rewards_cf = db.column();
@@ -722,6 +728,7 @@ impl Blockstore {
             code_shred_cf,
             data_shred_cf,
             dead_slots_cf,
+            equivocation_proofs_cf,
             orphans_cf,
             duplicate_slots_cf,
             erasure_meta_cf,
@@ -1574,6 +1581,8 @@ impl Blockstore {
         self.roots_cf.submit_rocksdb_cf_metrics();
         self.dead_slots_cf.submit_rocksdb_cf_metrics();
 
+        self.equivocation_proofs_cf.submit_rocksdb_cf_metrics();
+
         self.block_height_cf.submit_rocksdb_cf_metrics();
         self.blocktime_cf.submit_rocksdb_cf_metrics();
         self.rewards_cf.submit_rocksdb_cf_metrics();
@@ -5304,6 +5313,50 @@ impl Blockstore {
             .is_some()
     }
 
+    /// Stores `proof` unless a proof for the same validator and slot is
+    /// already stored. Returns whether `proof` was stored.
+    pub fn store_equivocation_proof(&self, proof: &EquivocationProof) -> Result<bool> {
+        let index = (proof.slot(), proof.vote_account);
+        if self.equivocation_proofs_cf.get_bytes(index)?.is_some() {
+            return Ok(false);
+        }
+        self.equivocation_proofs_cf.put(index, proof)?;
+        Ok(true)
+    }
+
+    pub fn get_equivocation_proof(
+        &self,
+        slot: Slot,
+        vote_account: Pubkey,
+    ) -> Result<Option<EquivocationProof>> {
+        self.equivocation_proofs_cf.get((slot, vote_account))
+    }
+
+    /// Returns the stored equivocation proofs for slots at or after `slot`,
+    /// in slot order. Proofs that cannot be deserialized are logged and
+    /// skipped.
+    pub fn equivocation_proofs_iterator(
+        &self,
+        slot: Slot,
+    ) -> Result<impl Iterator<Item = EquivocationProof> + '_> {
+        let proofs_iterator = self.equivocation_proofs_cf.iter(IteratorMode::From(
+            (slot, Pubkey::default()),
+            IteratorDirection::Forward,
+        ))?;
+        Ok(
+            proofs_iterator.filter_map(|((slot, vote_account), proof_bytes)| {
+                cf::EquivocationProofs::deserialize(&proof_bytes)
+                    .inspect_err(|err| {
+                        warn!(
+                            "Could not deserialize equivocation proof against {vote_account} \
+                             in slot {slot}: {err:?}"
+                        )
+                    })
+                    .ok()
+            }),
+        )
+    }
+
     pub fn orphans_iterator(&self, slot: Slot) -> Result<impl Iterator<Item = u64> + '_> {
         let orphans_iter = self
             .orphans_cf
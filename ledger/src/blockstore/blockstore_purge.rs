@@ -263,6 +263,8 @@ impl Blockstore {
             .delete_range_in_batch(write_batch, from_slot, to_slot);
         self.duplicate_slots_cf
             .delete_range_in_batch(write_batch, from_slot, to_slot);
+        self.equivocation_proofs_cf
+            .delete_range_in_batch(write_batch, from_slot, to_slot);
         self.erasure_meta_cf
             .delete_range_in_batch(write_batch, from_slot, to_slot);
         self.orphans_cf
@@ -331,6 +333,8 @@ impl Blockstore {
             .delete_file_in_range(from_slot, to_slot)?;
         self.duplicate_slots_cf
             .delete_file_in_range(from_slot, to_slot)?;
+        self.equivocation_proofs_cf
+            .delete_file_in_range(from_slot, to_slot)?;
         self.erasure_meta_cf
             .delete_file_in_range(from_slot, to_slot)?;
         self.orphans_cf.delete_file_in_range(from_slot, to_slot)?;
@@ -626,6 +630,15 @@ pub mod tests {
                 .map(|(slot, _)| slot >= min_slot)
                 .unwrap_or(true)
         );
+        assert!(
+            blockstore
+                .equivocation_proofs_cf
+                .iter(IteratorMode::Start)
+                .unwrap()
+                .next()
+                .map(|((slot, _), _)| slot >= min_slot)
+                .unwrap_or(true)
+        );
 
         assert!(
             blockstore
@@ -254,6 +254,17 @@ pub mod columns {
     /// * index type: `(Slot, BlockLocation)`
     /// * value type: [`blockstore_meta::DoubleMerkleMeta`]
     pub struct DoubleMerkleMeta;
+
+    #[derive(Debug)]
+    /// The equivocation proofs column
+    ///
+    /// This column stores proofs that a validator signed conflicting Alpenglow
+    /// votes for a slot. Only the first proof observed for a validator in a
+    /// slot is stored.
+    ///
+    /// * index type: `(Slot, vote_account: Pubkey)`
+    /// * value type: [`agave_votor_messages::equivocation::EquivocationProof`]
+    pub struct EquivocationProofs;
 }
 
 macro_rules! convert_column_index_to_key_bytes {
@@ -876,6 +887,40 @@ impl TypedColumn for columns::DoubleMerkleMeta {
     type Type = blockstore_meta::DoubleMerkleMeta;
 }
 
+impl Column for columns::EquivocationProofs {
+    type Index = (Slot, /* vote_account */ Pubkey);
+    type Key = [u8; std::mem::size_of::<Slot>() + PUBKEY_BYTES];
+
+    #[inline]
+    fn key((slot, vote_account): &Self::Index) -> Self::Key {
+        convert_column_index_to_key_bytes!(Key,
+            ..8 => &slot.to_be_bytes(),
+            8.. => vote_account.as_ref(),
+        )
+    }
+
+    fn index(key: &[u8]) -> Self::Index {
+        convert_column_key_bytes_to_index!(key,
+            0..8  => Slot::from_be_bytes,
+            8..40 => Pubkey::from,
+        )
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.0
+    }
+
+    fn as_index(slot: Slot) -> Self::Index {
+        (slot, Pubkey::default())
+    }
+}
+impl ColumnName for columns::EquivocationProofs {
+    const NAME: &'static str = "equivocation_proofs";
+}
+impl TypedColumn for columns::EquivocationProofs {
+    type Type = agave_votor_messages::equivocation::EquivocationProof;
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -7370,3 +7370,78 @@ fn test_connectivity_does_not_propagate_through_incomplete_slot() {
     assert!(!blockstore.meta(60).unwrap().unwrap().is_connected());
     assert!(!blockstore.meta(70).unwrap().unwrap().is_connected());
 }
+
+#[test]
+fn test_equivocation_proofs() {
+    use {
+        agave_votor_messages::{
+            consensus_message::Block, equivocation::SignedVote, wire::VotePayloadToSign,
+        },
+        solana_bls_signatures::{BLS_SIGNATURE_AFFINE_SIZE, Signature as BLSSignature},
+    };
+
+    let ledger_path = get_tmp_ledger_path_auto_delete!();
+    let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+    let make_proof = |slot: Slot, vote_account: Pubkey| EquivocationProof {
+        vote_account,
+        first: SignedVote {
+            payload: VotePayloadToSign::Notar {
+                block: Block {
+                    slot,
+                    block_id: Hash::new_unique(),
+                },
+                shred_version: 1,
+            },
+            signature: BLSSignature([1; BLS_SIGNATURE_AFFINE_SIZE]),
+        },
+        second: SignedVote {
+            payload: VotePayloadToSign::Skip {
+                slot,
+                shred_version: 1,
+            },
+            signature: BLSSignature([2; BLS_SIGNATURE_AFFINE_SIZE]),
+        },
+    };
+    let vote_account = Pubkey::new_unique();
+    let proof = make_proof(5, vote_account);
+    assert!(blockstore.store_equivocation_proof(&proof).unwrap());
+    // Only the first proof for a validator in a slot is kept
+    assert!(
+        !blockstore
+            .store_equivocation_proof(&make_proof(5, vote_account))
+            .unwrap()
+    );
+    assert_eq!(
+        blockstore.get_equivocation_proof(5, vote_account).unwrap(),
+        Some(proof.clone())
+    );
+
+    let later_proof = make_proof(9, vote_account);
+    assert!(blockstore.store_equivocation_proof(&later_proof).unwrap());
+    // Corrupt proofs are skipped by the iterator
+    blockstore
+        .equivocation_proofs_cf
+        .put_bytes((7, Pubkey::new_unique()), &[0xff; 3])
+        .unwrap();
+    assert_eq!(
+        blockstore
+            .equivocation_proofs_iterator(0)
+            .unwrap()
+            .collect::<Vec<_>>(),
+        vec![proof, later_proof.clone()]
+    );
+    assert_eq!(
+        blockstore
+            .equivocation_proofs_iterator(6)
+            .unwrap()
+            .collect::<Vec<_>>(),
+        vec![later_proof]
+    );
+
+    blockstore.purge_slots(0, 5, PurgeType::Exact).unwrap();
+    assert_eq!(
+        blockstore.get_equivocation_proof(5, vote_account).unwrap(),
+        None
+    );
+}
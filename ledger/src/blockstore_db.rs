@@ -195,6 +195,7 @@ impl Rocks {
             new_cf_descriptor::<columns::AlternateShredData>(options, oldest_slot),
             new_cf_descriptor::<columns::AlternateMerkleRootMeta>(options, oldest_slot),
             new_cf_descriptor::<columns::DoubleMerkleMeta>(options, oldest_slot),
+            new_cf_descriptor::<columns::EquivocationProofs>(options, oldest_slot),
         ];
 
         // When remaining columns are optional we can just return immediately here.
@@ -239,7 +240,7 @@ impl Rocks {
         cf_descriptors
     }
 
-    const fn columns() -> [&'static str; 24] {
+    const fn columns() -> [&'static str; 25] {
         [
             columns::ErasureMeta::NAME,
             columns::DeadSlots::NAME,
@@ -265,6 +266,7 @@ impl Rocks {
             columns::AlternateShredData::NAME,
             columns::AlternateMerkleRootMeta::NAME,
             columns::DoubleMerkleMeta::NAME,
+            columns::EquivocationProofs::NAME,
         ]
     }
 
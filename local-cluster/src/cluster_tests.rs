@@ -651,6 +651,8 @@ pub fn start_datagram_listener_for_alpenglow_votor(
         client_socket,
         sender,
         peer_list_receiver,
+        // Every peer gets the base rate.
+        tokio::sync::watch::channel(Arc::default()).1,
         VOTOR_RATE_LIMIT_PPS,
         CancellationToken::new(),
     )
@@ -0,0 +1,272 @@
+//! Per-invocation records of how a transaction executed, used to diagnose simulated transactions.
+
+use {
+    solana_account::ReadableAccount,
+    solana_instruction::error::InstructionError,
+    solana_pubkey::Pubkey,
+    solana_transaction_context::{IndexOfAccount, transaction::TransactionContext},
+    std::hash::{DefaultHasher, Hash, Hasher},
+};
+
+/// Maximum number of program counters recorded per transaction, across all its invocations
+///
+/// Register tracing keeps every register of every executed sBPF instruction until the invocation
+/// returns, so the traces are cut down to this many program counters as soon as possible.
+pub const MAX_TRACED_PROGRAM_COUNTERS: usize = 100_000;
+
+/// How one program invocation, a top-level instruction or a CPI, executed
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvocationTrace {
+    /// Position of the instruction in the transaction's instruction trace
+    pub index_in_trace: usize,
+    /// Position of the invoking instruction in the instruction trace, `None` for top-level
+    /// instructions
+    pub caller_index_in_trace: Option<usize>,
+    pub program_id: Pubkey,
+    /// `TRANSACTION_LEVEL_STACK_HEIGHT` for top-level instructions
+    pub stack_height: usize,
+    /// Compute units consumed, including those of nested invocations
+    pub compute_units_consumed: u64,
+    pub result: Result<(), InstructionError>,
+    /// Writable accounts whose data differs after the invocation, including changes made by
+    /// nested invocations
+    pub written_accounts: Vec<Pubkey>,
+    /// Program counter of every executed sBPF instruction
+    ///
+    /// Empty for builtins, and unless the program was loaded into a runtime environment with
+    /// register tracing enabled.
+    pub program_counters: Vec<u64>,
+    /// Whether `program_counters` stops short because the transaction executed more than
+    /// `MAX_TRACED_PROGRAM_COUNTERS` instructions
+    pub program_counters_truncated: bool,
+}
+
+/// Invocations of a transaction, in the order they started
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExecutionTrace {
+    pub invocations: Vec<InvocationTrace>,
+}
+
+#[derive(Debug)]
+pub(crate) struct ExecutionTraceRecorder {
+    trace: ExecutionTrace,
+    /// Invocations still executing, as positions in `trace.invocations` along with
+    /// fingerprints of their writable accounts' data at the start
+    open_invocations: Vec<(usize, Vec<(IndexOfAccount, u64)>)>,
+    /// How many more program counters may be recorded
+    remaining_program_counters: usize,
+    /// Positions in the instruction trace of the invocations whose register trace was truncated
+    truncated_invocations: Vec<usize>,
+}
+
+impl Default for ExecutionTraceRecorder {
+    fn default() -> Self {
+        Self::new(MAX_TRACED_PROGRAM_COUNTERS)
+    }
+}
+
+impl ExecutionTraceRecorder {
+    fn new(max_program_counters: usize) -> Self {
+        Self {
+            trace: ExecutionTrace::default(),
+            open_invocations: Vec::new(),
+            remaining_program_counters: max_program_counters,
+            truncated_invocations: Vec::new(),
+        }
+    }
+
+    /// Starts recording the invocation of the current instruction
+    pub(crate) fn begin_invocation(&mut self, transaction_context: &TransactionContext) {
+        let Ok(instruction_context) = transaction_context.get_current_instruction_context() else {
+            return;
+        };
+        let caller_index_in_trace = self
+            .open_invocations
+            .last()
+            .and_then(|(position, _)| self.trace.invocations.get(*position))
+            .map(|caller| caller.index_in_trace);
+
+        let mut writable_accounts = Vec::new();
+        for instruction_account_index in 0..instruction_context.get_number_of_instruction_accounts()
+        {
+            if !instruction_context
+                .is_instruction_account_writable(instruction_account_index)
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            let Ok(index_in_transaction) = instruction_context
+                .get_index_of_instruction_account_in_transaction(instruction_account_index)
+            else {
+                continue;
+            };
+            if writable_accounts
+                .iter()
+                .all(|(index, _)| *index != index_in_transaction)
+                && let Some(fingerprint) =
+                    data_fingerprint(transaction_context, index_in_transaction)
+            {
+                writable_accounts.push((index_in_transaction, fingerprint));
+            }
+        }
+
+        self.open_invocations
+            .push((self.trace.invocations.len(), writable_accounts));
+        self.trace.invocations.push(InvocationTrace {
+            index_in_trace: instruction_context.get_index_in_trace(),
+            caller_index_in_trace,
+            program_id: instruction_context
+                .get_program_key()
+                .copied()
+                .unwrap_or_default(),
+            stack_height: instruction_context.get_stack_height(),
+            compute_units_consumed: 0,
+            result: Ok(()),
+            written_accounts: Vec::new(),
+            program_counters: Vec::new(),
+            program_counters_truncated: false,
+        });
+    }
+
+    /// Finishes recording the innermost invocation still executing
+    pub(crate) fn end_invocation(
+        &mut self,
+        transaction_context: &TransactionContext,
+        compute_units_consumed: u64,
+        result: &Result<(), InstructionError>,
+    ) {
+        let Some((position, writable_accounts)) = self.open_invocations.pop() else {
+            return;
+        };
+        let Some(invocation) = self.trace.invocations.get_mut(position) else {
+            return;
+        };
+        invocation.compute_units_consumed = compute_units_consumed;
+        invocation.result = result.clone();
+        invocation.written_accounts = writable_accounts
+            .into_iter()
+            .filter(|(index_in_transaction, fingerprint)| {
+                data_fingerprint(transaction_context, *index_in_transaction) != Some(*fingerprint)
+            })
+            .filter_map(|(index_in_transaction, _)| {
+                transaction_context
+                    .get_key_of_account_at_index(index_in_transaction)
+                    .ok()
+                    .copied()
+            })
+            .collect();
+    }
+
+    /// Truncates the VM register trace of the invocation at `index_in_trace` to the number of
+    /// program counters that may still be recorded
+    pub(crate) fn limit_register_trace(
+        &mut self,
+        index_in_trace: usize,
+        register_trace: &mut Vec<[u64; 12]>,
+    ) {
+        if register_trace.len() > self.remaining_program_counters {
+            register_trace.truncate(self.remaining_program_counters);
+            register_trace.shrink_to_fit();
+            self.truncated_invocations.push(index_in_trace);
+        }
+        self.remaining_program_counters = self
+            .remaining_program_counters
+            .saturating_sub(register_trace.len());
+    }
+
+    /// Returns the recorded trace, attaching the given VM register traces to their invocations
+    pub(crate) fn finish(self, register_traces: &[(usize, Vec<[u64; 12]>)]) -> ExecutionTrace {
+        let mut trace = self.trace;
+        for invocation in &mut trace.invocations {
+            invocation.program_counters_truncated = self
+                .truncated_invocations
+                .contains(&invocation.index_in_trace);
+        }
+        for (index_in_trace, register_trace) in register_traces {
+            if let Some(invocation) = trace
+                .invocations
+                .iter_mut()
+                .find(|invocation| invocation.index_in_trace == *index_in_trace)
+            {
+                // The program counter follows the 11 general purpose registers
+                invocation.program_counters = register_trace
+                    .iter()
+                    .map(|[.., program_counter]| *program_counter)
+                    .collect();
+            }
+        }
+        trace
+    }
+}
+
+/// Fingerprint of an account's data, to detect writes without copying the data
+fn data_fingerprint(
+    transaction_context: &TransactionContext,
+    index_in_transaction: IndexOfAccount,
+) -> Option<u64> {
+    let account = transaction_context
+        .accounts()
+        .try_borrow(index_in_transaction)
+        .ok()?;
+    let mut hasher = DefaultHasher::new();
+    account.data().hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invocation(index_in_trace: usize) -> InvocationTrace {
+        InvocationTrace {
+            index_in_trace,
+            caller_index_in_trace: None,
+            program_id: Pubkey::new_unique(),
+            stack_height: 1,
+            compute_units_consumed: 0,
+            result: Ok(()),
+            written_accounts: Vec::new(),
+            program_counters: Vec::new(),
+            program_counters_truncated: false,
+        }
+    }
+
+    fn register_trace(program_counters: std::ops::Range<u64>) -> Vec<[u64; 12]> {
+        program_counters
+            .map(|program_counter| {
+                let mut registers = [0; 12];
+                registers[11] = program_counter;
+                registers
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_limit_register_trace() {
+        let mut recorder = ExecutionTraceRecorder::new(5);
+        recorder.trace.invocations = (0..3).map(invocation).collect();
+
+        let mut register_traces = Vec::new();
+        for (index_in_trace, program_counters) in [(0, 0..3), (1, 10..14), (2, 20..22)] {
+            let mut register_trace = register_trace(program_counters);
+            recorder.limit_register_trace(index_in_trace, &mut register_trace);
+            register_traces.push((index_in_trace, register_trace));
+        }
+
+        let trace = recorder.finish(&register_traces);
+        let program_counters: Vec<_> = trace
+            .invocations
+            .iter()
+            .map(|invocation| {
+                (
+                    invocation.program_counters.clone(),
+                    invocation.program_counters_truncated,
+                )
+            })
+            .collect();
+        assert_eq!(
+            program_counters,
+            vec![(vec![0, 1, 2], false), (vec![10, 11], true), (vec![], true),]
+        );
+    }
+}
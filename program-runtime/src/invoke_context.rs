@@ -13,6 +13,7 @@ use {
 use {
     crate::{
         execution_budget::{SVMTransactionExecutionBudget, SVMTransactionExecutionCost},
+        execution_trace::{ExecutionTrace, ExecutionTraceRecorder},
         loaded_programs::{
             ProgramCacheForTxBatch, ProgramRuntimeEnvironment, ProgramRuntimeEnvironments,
         },
@@ -239,6 +240,8 @@ pub struct InvokeContext<'a, 'ix_data> {
     pub memory_contexts: MemoryContexts,
     /// Pairs of index in TX instruction trace and VM register trace
     register_traces: Vec<(usize, Vec<[u64; 12]>)>,
+    /// Execution trace being recorded, if enabled
+    execution_trace: Option<ExecutionTraceRecorder>,
     /// Debug port to use for this executing transaction.
     #[cfg(feature = "sbpf-debugger")]
     pub debug_port: Option<u16>,
@@ -265,6 +268,7 @@ impl<'a, 'ix_data> InvokeContext<'a, 'ix_data> {
             timings: ExecuteDetailsTimings::default(),
             memory_contexts: MemoryContexts::new(),
             register_traces: Vec::new(),
+            execution_trace: None,
             #[cfg(feature = "sbpf-debugger")]
             debug_port: None,
         }
@@ -607,10 +611,20 @@ impl<'a, 'ix_data> InvokeContext<'a, 'ix_data> {
     ) -> Result<(), InstructionError> {
         *compute_units_consumed = 0;
         self.push()?;
-        self.process_executable_chain(compute_units_consumed, timings)
-            // MUST pop if and only if `push` succeeded, independent of `result`.
-            // Thus, the `.and()` instead of an `.and_then()`.
-            .and(self.pop())
+        if let Some(execution_trace) = &mut self.execution_trace {
+            execution_trace.begin_invocation(self.transaction_context);
+        }
+        let result = self.process_executable_chain(compute_units_consumed, timings);
+        if let Some(execution_trace) = &mut self.execution_trace {
+            execution_trace.end_invocation(
+                self.transaction_context,
+                *compute_units_consumed,
+                &result,
+            );
+        }
+        // MUST pop if and only if `push` succeeded, independent of `result`.
+        // Thus, the `.and()` instead of an `.and_then()`.
+        result.and(self.pop())
     }
 
     /// Processes a precompile instruction
@@ -810,7 +824,7 @@ impl<'a, 'ix_data> InvokeContext<'a, 'ix_data> {
     }
 
     /// Insert a VM register trace
-    pub(crate) fn insert_register_trace(&mut self, register_trace: Vec<[u64; 12]>) {
+    pub(crate) fn insert_register_trace(&mut self, mut register_trace: Vec<[u64; 12]>) {
         if register_trace.is_empty() {
             return;
         }
@@ -818,8 +832,28 @@ impl<'a, 'ix_data> InvokeContext<'a, 'ix_data> {
         else {
             return;
         };
-        self.register_traces
-            .push((instruction_context.get_index_in_trace(), register_trace));
+        let index_in_trace = instruction_context.get_index_in_trace();
+        if let Some(execution_trace) = &mut self.execution_trace {
+            execution_trace.limit_register_trace(index_in_trace, &mut register_trace);
+            if register_trace.is_empty() {
+                return;
+            }
+        }
+        self.register_traces.push((index_in_trace, register_trace));
+    }
+
+    /// Start recording an execution trace of the invocations processed from now on
+    pub fn enable_execution_trace(&mut self) {
+        self.execution_trace = Some(ExecutionTraceRecorder::default());
+    }
+
+    /// Returns the execution trace recorded since `enable_execution_trace`, if it was called
+    ///
+    /// Program counters are only recorded for programs executing with register tracing enabled.
+    pub fn take_execution_trace(&mut self) -> Option<ExecutionTrace> {
+        self.execution_trace
+            .take()
+            .map(|execution_trace| execution_trace.finish(&self.register_traces))
     }
 
     /// Iterates over all VM register traces (including CPI)
@@ -1135,9 +1169,12 @@ pub fn mock_process_instruction<F: FnMut(&mut InvokeContext), G: FnMut(&mut Invo
 mod tests {
     use {
         super::*,
-        crate::execution_budget::{
-            DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT, MAX_INSTRUCTION_STACK_DEPTH,
-            MAX_INSTRUCTION_STACK_DEPTH_SIMD_0268,
+        crate::{
+            execution_budget::{
+                DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT, MAX_INSTRUCTION_STACK_DEPTH,
+                MAX_INSTRUCTION_STACK_DEPTH_SIMD_0268,
+            },
+            execution_trace::InvocationTrace,
         },
         openssl::{
             ec::{EcGroup, EcKey},
@@ -1595,6 +1632,92 @@ mod tests {
         invoke_context.pop().unwrap();
     }
 
+    #[test]
+    fn test_execution_trace() {
+        let callee_program_id = solana_pubkey::new_rand();
+        let owned_account = AccountSharedData::new(42, 1, &callee_program_id);
+        let not_owned_account = AccountSharedData::new(84, 1, &solana_pubkey::new_rand());
+        let readonly_account = AccountSharedData::new(168, 1, &solana_pubkey::new_rand());
+        let loader_account = AccountSharedData::new(0, 1, &native_loader::id());
+        let mut program_account = AccountSharedData::new(1, 1, &native_loader::id());
+        program_account.set_executable(true);
+        let transaction_accounts = vec![
+            (solana_pubkey::new_rand(), owned_account),
+            (solana_pubkey::new_rand(), not_owned_account),
+            (solana_pubkey::new_rand(), readonly_account),
+            (callee_program_id, program_account),
+            (solana_pubkey::new_rand(), loader_account),
+        ];
+        let owned_account_key = transaction_accounts.first().unwrap().0;
+        let instruction_accounts = (0..4)
+            .map(|instruction_account_index| {
+                InstructionAccount::new(
+                    instruction_account_index,
+                    false,
+                    instruction_account_index < 2,
+                )
+            })
+            .collect::<Vec<_>>();
+        with_mock_invoke_context!(invoke_context, transaction_context, 2, transaction_accounts);
+        let mut program_cache_for_tx_batch = ProgramCacheForTxBatch::default();
+        program_cache_for_tx_batch.replenish(
+            callee_program_id,
+            Arc::new(ProgramCacheEntry::new_builtin(0, MockBuiltin::register)),
+        );
+        invoke_context.program_cache_for_tx_batch = &mut program_cache_for_tx_batch;
+        invoke_context.enable_execution_trace();
+
+        let compute_units_to_consume = 10;
+        for instruction in [
+            MockInstruction::ModifyOwned,
+            MockInstruction::ConsumeComputeUnits {
+                compute_units_to_consume,
+                desired_result: Err(InstructionError::GenericError),
+            },
+        ] {
+            invoke_context
+                .transaction_context
+                .configure_top_level_instruction_for_tests(
+                    3,
+                    instruction_accounts.clone(),
+                    bincode::serialize(&instruction).unwrap(),
+                )
+                .unwrap();
+            let _ = invoke_context.process_instruction(&mut 0, &mut ExecuteTimings::default());
+        }
+
+        let execution_trace = invoke_context.take_execution_trace().unwrap();
+        assert_eq!(
+            execution_trace.invocations,
+            vec![
+                InvocationTrace {
+                    index_in_trace: 0,
+                    caller_index_in_trace: None,
+                    program_id: callee_program_id,
+                    stack_height: 1,
+                    compute_units_consumed: MOCK_BUILTIN_COMPUTE_UNIT_COST,
+                    result: Ok(()),
+                    written_accounts: vec![owned_account_key],
+                    program_counters: vec![],
+                    program_counters_truncated: false,
+                },
+                InvocationTrace {
+                    index_in_trace: 1,
+                    caller_index_in_trace: None,
+                    program_id: callee_program_id,
+                    stack_height: 1,
+                    compute_units_consumed: compute_units_to_consume
+                        .saturating_add(MOCK_BUILTIN_COMPUTE_UNIT_COST),
+                    result: Err(InstructionError::GenericError),
+                    written_accounts: vec![],
+                    program_counters: vec![],
+                    program_counters_truncated: false,
+                },
+            ]
+        );
+        assert_eq!(invoke_context.take_execution_trace(), None);
+    }
+
     #[test]
     fn test_invoke_context_compute_budget() {
         let transaction_accounts = vec![(solana_pubkey::new_rand(), AccountSharedData::default())];
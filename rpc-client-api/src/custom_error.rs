@@ -30,6 +30,7 @@ pub const JSON_RPC_SERVER_ERROR_SLOT_NOT_EPOCH_BOUNDARY: i64 = -32018;
 pub const JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_UNREACHABLE: i64 = -32019;
 pub const JSON_RPC_SERVER_ERROR_FILTER_TRANSACTION_NOT_FOUND: i64 = -32020;
 pub const JSON_RPC_SERVER_ERROR_NO_SLOT_HISTORY: i64 = -32021;
+pub const JSON_RPC_SERVER_ERROR_ACCOUNT_STATE_NOT_AVAILABLE: i64 = -32022;
 
 #[derive(Error, Debug)]
 #[allow(clippy::large_enum_variant)]
@@ -86,6 +87,11 @@ pub enum RpcCustomError {
     FilterTransactionNotFound { signature: String },
     #[error("NoSlotHistory")]
     NoSlotHistory,
+    #[error("AccountStateNotAvailable")]
+    AccountStateNotAvailable {
+        slot: Slot,
+        first_available_slot: Option<Slot>,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -275,6 +281,20 @@ impl From<RpcCustomError> for Error {
                 message: "No slot history".to_string(),
                 data: None,
             },
+            RpcCustomError::AccountStateNotAvailable {
+                slot,
+                first_available_slot,
+            } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNT_STATE_NOT_AVAILABLE),
+                message: match first_available_slot {
+                    Some(first_available_slot) => format!(
+                        "Account state for slot {slot} is not available. First available slot: \
+                         {first_available_slot}"
+                    ),
+                    None => format!("Account state for slot {slot} is not available"),
+                },
+                data: None,
+            },
         }
     }
 }
@@ -1,7 +1,8 @@
 use {
     crate::filter::RpcFilterType,
     serde::{Deserialize, Serialize},
-    solana_clock::{Epoch, Slot},
+    solana_clock::{Epoch, Slot, UnixTimestamp},
+    std::collections::HashMap,
 };
 pub use {
     solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig},
@@ -47,6 +48,56 @@ pub struct RpcSimulateTransactionConfig {
     pub min_context_slot: Option<Slot>,
     #[serde(default)]
     pub inner_instructions: bool,
+    /// Account state to simulate against instead of the bank's, keyed by base-58 address
+    pub account_overrides: Option<HashMap<String, RpcAccountOverride>>,
+    /// Sysvar values to simulate against instead of the bank's
+    pub sysvar_overrides: Option<RpcSysvarOverrides>,
+    /// Record how each program invocation executed
+    #[serde(default)]
+    pub execution_trace: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountOverride {
+    pub lamports: u64,
+    /// Base-58 encoded program id
+    pub owner: String,
+    /// Account data as `[data, encoding]`; only base64 and base64+zstd are accepted
+    pub data: (String, UiAccountEncoding),
+    #[serde(default)]
+    pub executable: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSysvarOverrides {
+    pub clock: Option<RpcClockOverride>,
+}
+
+/// Clock fields to override; fields left unset keep the bank's value
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcClockOverride {
+    pub slot: Option<Slot>,
+    pub unix_timestamp: Option<UnixTimestamp>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundleConfig {
+    #[serde(default)]
+    pub sig_verify: bool,
+    #[serde(default)]
+    pub replace_recent_blockhash: bool,
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+    pub encoding: Option<UiTransactionEncoding>,
+    /// Accounts to return the state of after each transaction
+    pub accounts: Option<RpcSimulateTransactionAccountsConfig>,
+    pub min_context_slot: Option<Slot>,
+    #[serde(default)]
+    pub inner_instructions: bool,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -159,6 +210,17 @@ pub struct RpcAccountInfoConfig {
     pub min_context_slot: Option<Slot>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountInfoAtSlotConfig {
+    #[serde(flatten)]
+    pub account_config: RpcAccountInfoConfig,
+    /// Read accounts as of this slot instead of the latest slot at the requested
+    /// commitment. Slots older than the node's root are served from its retained
+    /// account history.
+    pub at_slot: Option<Slot>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcProgramAccountsConfig {
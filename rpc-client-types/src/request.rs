@@ -15,6 +15,7 @@ pub enum RpcRequest {
     GetAccountInfo,
     GetBalance,
     GetBlock,
+    GetBlockCertificates,
     GetBlockHeight,
     GetBlockCommitment,
     GetBlockProduction,
@@ -24,6 +25,7 @@ pub enum RpcRequest {
     GetClusterNodes,
     GetEpochInfo,
     GetEpochSchedule,
+    GetEquivocationProofs,
     GetFeeForMessage,
     GetFirstAvailableBlock,
     GetGenesisHash,
@@ -69,6 +71,7 @@ pub enum RpcRequest {
     RegisterNode,
     RequestAirdrop,
     SendTransaction,
+    SimulateBundle,
     SimulateTransaction,
     SignVote,
 }
@@ -82,6 +85,7 @@ impl RpcRequest {
             RpcRequest::GetAccountInfo => "getAccountInfo",
             RpcRequest::GetBalance => "getBalance",
             RpcRequest::GetBlock => "getBlock",
+            RpcRequest::GetBlockCertificates => "getBlockCertificates",
             RpcRequest::GetBlockHeight => "getBlockHeight",
             RpcRequest::GetBlockCommitment => "getBlockCommitment",
             RpcRequest::GetBlockProduction => "getBlockProduction",
@@ -91,6 +95,7 @@ impl RpcRequest {
             RpcRequest::GetClusterNodes => "getClusterNodes",
             RpcRequest::GetEpochInfo => "getEpochInfo",
             RpcRequest::GetEpochSchedule => "getEpochSchedule",
+            RpcRequest::GetEquivocationProofs => "getEquivocationProofs",
             RpcRequest::GetFeeForMessage => "getFeeForMessage",
             RpcRequest::GetFirstAvailableBlock => "getFirstAvailableBlock",
             RpcRequest::GetGenesisHash => "getGenesisHash",
@@ -136,6 +141,7 @@ impl RpcRequest {
             RpcRequest::RegisterNode => "registerNode",
             RpcRequest::RequestAirdrop => "requestAirdrop",
             RpcRequest::SendTransaction => "sendTransaction",
+            RpcRequest::SimulateBundle => "simulateBundle",
             RpcRequest::SimulateTransaction => "simulateTransaction",
             RpcRequest::SignVote => "signVote",
         }
@@ -158,6 +164,10 @@ pub const MAX_GET_INFLATION_REWARD_ADDRESSES: usize = 32;
 pub const NUM_LARGEST_ACCOUNTS: usize = 20;
 pub const MAX_GET_PROGRAM_ACCOUNT_FILTERS: usize = 4;
 pub const MAX_GET_SLOT_LEADERS: usize = 5000;
+pub const MAX_SIMULATE_BUNDLE_TRANSACTIONS: usize = 16;
+pub const MAX_SIMULATE_ACCOUNT_OVERRIDES: usize = 64;
+// Limit the total size of the (decompressed) data of the account overrides of a simulation
+pub const MAX_SIMULATE_ACCOUNT_OVERRIDES_DATA_LEN: usize = 4 * 1024 * 1024;
 
 // Limit the length of the `epoch_credits` array for each validator in a `get_vote_accounts`
 // response
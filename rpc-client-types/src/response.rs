@@ -1,4 +1,5 @@
 use {
+    base64::{Engine, prelude::BASE64_STANDARD},
     serde::{Deserialize, Deserializer, Serialize, Serializer},
     solana_clock::{Epoch, Slot, UnixTimestamp},
     solana_inflation::Inflation,
@@ -387,6 +388,72 @@ pub struct RpcVote {
     pub signature: String,
 }
 
+/// Kind of an Alpenglow consensus certificate
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcCertificateType {
+    Finalize,
+    FinalizeFast,
+    Notarize,
+    NotarizeFallback,
+    Skip,
+    Genesis,
+}
+
+/// An Alpenglow consensus certificate, aggregating the BLS signatures of the validators that
+/// voted for a block or slot
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcCertificate {
+    pub slot: Slot,
+    pub certificate_type: RpcCertificateType,
+    /// Id of the certified block, as base-58 encoded string; `None` for finalize and skip
+    /// certificates, which certify a slot
+    pub block_id: Option<String>,
+    /// Aggregate BLS signature, as base64 encoded string
+    pub signature: String,
+    /// Bitmap of the ranks of the validators whose signatures are aggregated, as base64 encoded
+    /// string; see solana-signer-store for the bitmap encoding
+    pub bitmap: String,
+}
+
+/// Kind of an Alpenglow vote
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcAlpenglowVoteType {
+    Notarize,
+    NotarizeFallback,
+    Skip,
+    SkipFallback,
+    Finalize,
+    Genesis,
+}
+
+/// An Alpenglow vote along with its sender's BLS signature
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSignedAlpenglowVote {
+    pub vote_type: RpcAlpenglowVoteType,
+    /// Id of the voted block, as base-58 encoded string; `None` for votes on a slot
+    pub block_id: Option<String>,
+    pub shred_version: u16,
+    /// BLS signature, as base64 encoded string
+    pub signature: String,
+}
+
+/// Proof that a validator signed two conflicting Alpenglow votes for the same slot
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcEquivocationProof {
+    pub slot: Slot,
+    /// Vote account of the offending validator, as base-58 encoded string
+    pub vote_account: String,
+    pub first: RpcSignedAlpenglowVote,
+    pub second: RpcSignedAlpenglowVote,
+    /// The wincode serialized proof, as base64 encoded string, for submission on-chain
+    pub proof: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcVoteAccountStatus {
@@ -459,6 +526,91 @@ pub struct RpcSimulateTransactionResult {
     pub pre_token_balances: Option<Vec<UiTransactionTokenBalance>>,
     pub post_token_balances: Option<Vec<UiTransactionTokenBalance>>,
     pub loaded_addresses: Option<UiLoadedAddresses>,
+    /// Program invocations, in the order they started; set when execution tracing was requested
+    pub execution_trace: Option<Vec<RpcInvocationTrace>>,
+}
+
+/// How one program invocation, a top-level instruction or a CPI, executed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInvocationTrace {
+    /// Position of the instruction in the transaction's instruction trace
+    pub index_in_trace: usize,
+    /// Position of the invoking instruction in the instruction trace, `None` for top-level
+    /// instructions
+    pub caller_index_in_trace: Option<usize>,
+    /// Base-58 encoded program id
+    pub program_id: String,
+    pub stack_height: usize,
+    /// Compute units consumed, including those of nested invocations
+    pub compute_units_consumed: u64,
+    /// Error the invocation failed with, attributed to its top-level instruction
+    pub err: Option<UiTransactionError>,
+    /// Base-58 encoded writable accounts whose data changed during the invocation
+    pub written_accounts: Vec<String>,
+    /// Program counters of the executed sBPF instructions, see
+    /// [`RpcInvocationTrace::decode_program_counters`]
+    pub program_counters: String,
+    /// Whether `program_counters` stops short because the transaction executed more sBPF
+    /// instructions than the node records
+    #[serde(default)]
+    pub program_counters_truncated: bool,
+}
+
+impl RpcInvocationTrace {
+    /// Encodes program counters compactly as base64 of zigzag LEB128 varints of the deltas
+    /// between consecutive program counters
+    pub fn encode_program_counters(program_counters: &[u64]) -> String {
+        let mut bytes = Vec::with_capacity(program_counters.len());
+        let mut previous = 0u64;
+        for program_counter in program_counters {
+            let delta = program_counter.wrapping_sub(previous) as i64;
+            previous = *program_counter;
+            let mut zigzag = ((delta << 1) ^ (delta >> 63)) as u64;
+            while zigzag >= 0x80 {
+                bytes.push((zigzag as u8) | 0x80);
+                zigzag >>= 7;
+            }
+            bytes.push(zigzag as u8);
+        }
+        BASE64_STANDARD.encode(bytes)
+    }
+
+    /// Decodes program counters encoded by [`RpcInvocationTrace::encode_program_counters`],
+    /// returning `None` if the encoding is malformed
+    pub fn decode_program_counters(encoded: &str) -> Option<Vec<u64>> {
+        let bytes = BASE64_STANDARD.decode(encoded).ok()?;
+        let mut program_counters = Vec::new();
+        let mut previous = 0u64;
+        let mut zigzag = 0u64;
+        let mut shift = 0u32;
+        for byte in bytes {
+            if shift >= u64::BITS {
+                return None;
+            }
+            zigzag |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 != 0 {
+                shift += 7;
+                continue;
+            }
+            let delta = ((zigzag >> 1) as i64) ^ -((zigzag & 1) as i64);
+            previous = previous.wrapping_add(delta as u64);
+            program_counters.push(previous);
+            zigzag = 0;
+            shift = 0;
+        }
+        (shift == 0).then_some(program_counters)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundleResult {
+    /// Error of the first transaction that failed, after which no transactions were simulated
+    pub err: Option<UiTransactionError>,
+    pub failed_transaction_index: Option<usize>,
+    /// Results of the simulated transactions, in order
+    pub transaction_results: Vec<RpcSimulateTransactionResult>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -674,4 +826,32 @@ pub mod tests {
         assert_eq!(result.confirmation_status, None);
         assert_eq!(result.transaction_index, Some(42));
     }
+
+    #[test]
+    fn test_rpc_invocation_trace_program_counters_round_trip() {
+        for program_counters in [
+            vec![],
+            vec![0],
+            vec![0, 1, 2, 3, 10, 11, 4, 5],
+            vec![u64::MAX, 0, 1 << 40, 63, 64, 65],
+        ] {
+            let encoded = RpcInvocationTrace::encode_program_counters(&program_counters);
+            assert_eq!(
+                RpcInvocationTrace::decode_program_counters(&encoded),
+                Some(program_counters)
+            );
+        }
+
+        // Sequential execution encodes to a byte per instruction
+        let encoded = RpcInvocationTrace::encode_program_counters(&[0, 1, 2, 3]);
+        assert_eq!(BASE64_STANDARD.decode(encoded).unwrap(), vec![0, 2, 2, 2]);
+
+        // Truncated varint
+        let truncated = BASE64_STANDARD.encode([0x80]);
+        assert_eq!(
+            RpcInvocationTrace::decode_program_counters(&truncated),
+            None
+        );
+        assert_eq!(RpcInvocationTrace::decode_program_counters("!"), None);
+    }
 }
@@ -388,6 +388,7 @@ impl RpcSender for MockSender {
                     pre_token_balances: None,
                     post_token_balances: None,
                     loaded_addresses: None,
+                    execution_trace: None,
                 }
             })?,
             "getMinimumBalanceForRentExemption" => json![20],
@@ -0,0 +1,153 @@
+//! Recent Alpenglow certificates observed by the consensus pool, kept so that clients can verify
+//! finality themselves.
+
+use {
+    agave_votor_messages::certificate::{Certificate, CertificateType},
+    base64::{Engine, prelude::BASE64_STANDARD},
+    solana_clock::Slot,
+    solana_rpc_client_api::response::{RpcCertificate, RpcCertificateType},
+    std::collections::BTreeMap,
+};
+
+/// Number of most recent slots to keep certificates for
+pub const MAX_BLOCK_CERTIFICATE_SLOTS: u64 = 4096;
+
+#[derive(Debug, Default)]
+pub struct BlockCertificateCache {
+    certificates: BTreeMap<Slot, Vec<RpcCertificate>>,
+}
+
+impl BlockCertificateCache {
+    /// Adds a certificate, returning it in RPC form if it was not already cached and is recent
+    /// enough to be kept
+    pub fn insert(&mut self, certificate: &Certificate) -> Option<RpcCertificate> {
+        let slot = certificate.cert_type.slot();
+        if let Some(highest_slot) = self.highest_slot()
+            && slot.saturating_add(MAX_BLOCK_CERTIFICATE_SLOTS) <= highest_slot
+        {
+            return None;
+        }
+        let rpc_certificate = rpc_certificate(certificate);
+        let certificates = self.certificates.entry(slot).or_default();
+        if certificates.iter().any(|cached| {
+            cached.certificate_type == rpc_certificate.certificate_type
+                && cached.block_id == rpc_certificate.block_id
+        }) {
+            return None;
+        }
+        certificates.push(rpc_certificate.clone());
+
+        let lowest_kept_slot = self
+            .highest_slot()
+            .unwrap_or_default()
+            .saturating_sub(MAX_BLOCK_CERTIFICATE_SLOTS.saturating_sub(1));
+        self.certificates = self.certificates.split_off(&lowest_kept_slot);
+        Some(rpc_certificate)
+    }
+
+    /// Returns the cached certificates for `slot`, in the order they were observed
+    pub fn get(&self, slot: Slot) -> &[RpcCertificate] {
+        self.certificates.get(&slot).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the lowest slot certificates are kept for, if any are cached
+    pub fn lowest_slot(&self) -> Option<Slot> {
+        self.certificates.keys().next().copied()
+    }
+
+    fn highest_slot(&self) -> Option<Slot> {
+        self.certificates.keys().next_back().copied()
+    }
+}
+
+pub fn rpc_certificate(certificate: &Certificate) -> RpcCertificate {
+    let certificate_type = match certificate.cert_type {
+        CertificateType::Finalize(_) => RpcCertificateType::Finalize,
+        CertificateType::FinalizeFast(_) => RpcCertificateType::FinalizeFast,
+        CertificateType::Notarize(_) => RpcCertificateType::Notarize,
+        CertificateType::NotarizeFallback(_) => RpcCertificateType::NotarizeFallback,
+        CertificateType::Skip(_) => RpcCertificateType::Skip,
+        CertificateType::Genesis(_) => RpcCertificateType::Genesis,
+    };
+    RpcCertificate {
+        slot: certificate.cert_type.slot(),
+        certificate_type,
+        block_id: certificate
+            .cert_type
+            .to_block()
+            .map(|block| block.block_id.to_string()),
+        signature: BASE64_STANDARD.encode(certificate.signature.0),
+        bitmap: BASE64_STANDARD.encode(&certificate.bitmap),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        agave_votor_messages::consensus_message::Block,
+        solana_bls_signatures::{BLS_SIGNATURE_AFFINE_SIZE, Signature as BLSSignature},
+        solana_hash::Hash,
+    };
+
+    fn new_certificate(cert_type: CertificateType) -> Certificate {
+        Certificate {
+            cert_type,
+            signature: BLSSignature([7; BLS_SIGNATURE_AFFINE_SIZE]),
+            bitmap: vec![0b101],
+        }
+    }
+
+    #[test]
+    fn test_block_certificate_cache() {
+        let mut cache = BlockCertificateCache::default();
+        let block = Block {
+            slot: 10,
+            block_id: Hash::new_unique(),
+        };
+
+        let notarize = cache
+            .insert(&new_certificate(CertificateType::Notarize(block)))
+            .unwrap();
+        assert_eq!(notarize.slot, 10);
+        assert_eq!(notarize.certificate_type, RpcCertificateType::Notarize);
+        assert_eq!(notarize.block_id, Some(block.block_id.to_string()));
+        assert_eq!(
+            notarize.signature,
+            BASE64_STANDARD.encode([7; BLS_SIGNATURE_AFFINE_SIZE])
+        );
+        assert_eq!(notarize.bitmap, BASE64_STANDARD.encode([0b101]));
+        let finalize = cache
+            .insert(&new_certificate(CertificateType::Finalize(10)))
+            .unwrap();
+        assert_eq!(finalize.block_id, None);
+
+        // Duplicates are not cached again
+        assert_eq!(
+            cache.insert(&new_certificate(CertificateType::Notarize(block))),
+            None
+        );
+        assert_eq!(cache.get(10), [notarize, finalize]);
+        assert!(cache.get(11).is_empty());
+
+        // Old slots are pruned once certificates for newer slots arrive
+        let newest_slot = 10 + MAX_BLOCK_CERTIFICATE_SLOTS;
+        assert!(
+            cache
+                .insert(&new_certificate(CertificateType::Skip(newest_slot)))
+                .is_some()
+        );
+        assert!(cache.get(10).is_empty());
+        assert_eq!(cache.lowest_slot(), Some(newest_slot));
+        assert_eq!(
+            cache.insert(&new_certificate(CertificateType::Skip(10))),
+            None
+        );
+        assert!(
+            cache
+                .insert(&new_certificate(CertificateType::Skip(11)))
+                .is_some()
+        );
+        assert_eq!(cache.lowest_slot(), Some(11));
+    }
+}
@@ -0,0 +1,58 @@
+//! Alpenglow equivocation proofs persisted to blockstore, in RPC form.
+
+use {
+    agave_votor_messages::{
+        equivocation::{EquivocationProof, SignedVote},
+        vote::Vote,
+    },
+    base64::{Engine, prelude::BASE64_STANDARD},
+    solana_clock::Slot,
+    solana_ledger::blockstore::{Blockstore, BlockstoreError},
+    solana_rpc_client_api::response::{
+        RpcAlpenglowVoteType, RpcEquivocationProof, RpcSignedAlpenglowVote,
+    },
+};
+
+/// Maximum number of proofs returned by a single request
+pub const MAX_GET_EQUIVOCATION_PROOFS: usize = 1000;
+
+/// Returns up to [`MAX_GET_EQUIVOCATION_PROOFS`] stored proofs for slots at or after
+/// `start_slot`, in slot order
+pub fn get_equivocation_proofs(
+    blockstore: &Blockstore,
+    start_slot: Slot,
+) -> Result<Vec<RpcEquivocationProof>, BlockstoreError> {
+    Ok(blockstore
+        .equivocation_proofs_iterator(start_slot)?
+        .take(MAX_GET_EQUIVOCATION_PROOFS)
+        .map(|proof| rpc_equivocation_proof(&proof))
+        .collect())
+}
+
+pub fn rpc_equivocation_proof(proof: &EquivocationProof) -> RpcEquivocationProof {
+    RpcEquivocationProof {
+        slot: proof.slot(),
+        vote_account: proof.vote_account.to_string(),
+        first: rpc_signed_vote(&proof.first),
+        second: rpc_signed_vote(&proof.second),
+        proof: BASE64_STANDARD.encode(wincode::serialize(proof).unwrap()),
+    }
+}
+
+fn rpc_signed_vote(signed_vote: &SignedVote) -> RpcSignedAlpenglowVote {
+    let vote = signed_vote.vote();
+    let vote_type = match vote {
+        Vote::Notarize(_) => RpcAlpenglowVoteType::Notarize,
+        Vote::NotarizeFallback(_) => RpcAlpenglowVoteType::NotarizeFallback,
+        Vote::Skip(_) => RpcAlpenglowVoteType::Skip,
+        Vote::SkipFallback(_) => RpcAlpenglowVoteType::SkipFallback,
+        Vote::Finalize(_) => RpcAlpenglowVoteType::Finalize,
+        Vote::Genesis(_) => RpcAlpenglowVoteType::Genesis,
+    };
+    RpcSignedAlpenglowVote {
+        vote_type,
+        block_id: vote.block_id().map(ToString::to_string),
+        shred_version: signed_vote.shred_version(),
+        signature: BASE64_STANDARD.encode(signed_vote.signature.0),
+    }
+}
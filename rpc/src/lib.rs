@@ -1,6 +1,8 @@
 #![cfg(feature = "agave-unstable-api")]
 #![allow(clippy::arithmetic_side_effects)]
+pub mod block_certificate_cache;
 mod cluster_tpu_info;
+pub mod equivocation_proofs;
 pub mod filter;
 pub mod max_slots;
 pub mod optimistically_confirmed_bank_tracker;
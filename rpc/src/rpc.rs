@@ -5,8 +5,8 @@ use solana_runtime::installed_scheduler_pool::{
 };
 use {
     crate::{
-        filter::filter_allows, max_slots::MaxSlots,
-        optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
+        block_certificate_cache::BlockCertificateCache, equivocation_proofs, filter::filter_allows,
+        max_slots::MaxSlots, optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
         parsed_token_accounts::*, rpc_cache::LargestAccountsCache, rpc_health::*,
     },
     agave_snapshots::{paths as snapshot_paths, snapshot_config::SnapshotConfig},
@@ -23,12 +23,16 @@ use {
     solana_account_decoder::{
         MAX_BASE58_BYTES, UiAccount, UiAccountEncoding, UiDataSliceConfig, encode_ui_account,
         parse_account_data::SplTokenAdditionalDataV2,
-        parse_token::{UiTokenAmount, is_known_spl_token_id, token_amount_to_ui_amount_v3},
+        parse_token::{
+            UiTokenAmount, get_token_account_mint, is_known_spl_token_id,
+            token_amount_to_ui_amount_v3,
+        },
     },
     solana_accounts_db::{
         accounts::AccountAddressFilter,
         accounts_index::{AccountIndex, AccountSecondaryIndexes, IndexKey},
         accounts_scan::ScanResult,
+        historical_view::{HistoricalView, HistoricalViewError},
     },
     solana_clock::{Slot, UnixTimestamp},
     solana_commitment_config::{CommitmentConfig, CommitmentLevel},
@@ -60,13 +64,14 @@ use {
             MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT, MAX_GET_INFLATION_REWARD_ADDRESSES,
             MAX_GET_PROGRAM_ACCOUNT_FILTERS, MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS,
             MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
-            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, NUM_LARGEST_ACCOUNTS,
-            TokenAccountsFilter,
+            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, MAX_SIMULATE_ACCOUNT_OVERRIDES,
+            MAX_SIMULATE_ACCOUNT_OVERRIDES_DATA_LEN, MAX_SIMULATE_BUNDLE_TRANSACTIONS,
+            NUM_LARGEST_ACCOUNTS, TokenAccountsFilter,
         },
         response::{Response as RpcResponse, *},
     },
     solana_runtime::{
-        bank::{Bank, TransactionSimulationResult},
+        bank::{Bank, SimulationConfig, SimulationStateOverrides, TransactionSimulationResult},
         bank_forks::BankForks,
         commitment::{BlockCommitmentArray, BlockCommitmentCache},
         non_circulating_supply::{NonCirculatingSupply, calculate_non_circulating_supply},
@@ -108,6 +113,7 @@ use {
         cmp::{Reverse, max, min},
         collections::{BinaryHeap, HashMap, HashSet},
         convert::TryFrom,
+        mem,
         net::SocketAddr,
         str::FromStr,
         sync::{
@@ -149,6 +155,16 @@ fn new_response<T>(bank: &Bank, value: T) -> RpcResponse<T> {
     }
 }
 
+fn new_response_at_slot<T>(bank: &Bank, view: Option<&HistoricalView>, value: T) -> RpcResponse<T> {
+    match view {
+        Some(view) => RpcResponse {
+            context: RpcResponseContext::new(view.slot()),
+            value,
+        },
+        None => new_response(bank, value),
+    }
+}
+
 fn is_finalized(
     block_commitment_cache: &BlockCommitmentCache,
     bank: &Bank,
@@ -179,6 +195,9 @@ pub struct JsonRpcConfig {
     pub scan_results_limit_bytes: Option<usize>,
     /// Disable the health check, used for tests and TestValidator
     pub disable_health_check: bool,
+    /// Allow `simulateTransaction` requests to record an execution trace, which re-executes
+    /// programs with register tracing enabled
+    pub enable_simulation_tracing: bool,
 }
 
 impl Default for JsonRpcConfig {
@@ -200,6 +219,7 @@ impl Default for JsonRpcConfig {
             max_request_body_size: Option::default(),
             scan_results_limit_bytes: Option::default(),
             disable_health_check: Default::default(),
+            enable_simulation_tracing: Default::default(),
         }
     }
 }
@@ -257,6 +277,7 @@ pub struct JsonRpcRequestProcessor {
     leader_schedule_cache: Arc<LeaderScheduleCache>,
     max_complete_transaction_status_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Option<Arc<PrioritizationFeeCache>>,
+    block_certificate_cache: Arc<RwLock<BlockCertificateCache>>,
     runtime: Arc<Runtime>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
@@ -421,6 +442,7 @@ impl JsonRpcRequestProcessor {
         leader_schedule_cache: Arc<LeaderScheduleCache>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Option<Arc<PrioritizationFeeCache>>,
+        block_certificate_cache: Arc<RwLock<BlockCertificateCache>>,
         runtime: Arc<Runtime>,
     ) -> (Self, Receiver<TransactionInfo>) {
         let (transaction_sender, transaction_receiver) = unbounded();
@@ -443,6 +465,7 @@ impl JsonRpcRequestProcessor {
                 leader_schedule_cache,
                 max_complete_transaction_status_slot,
                 prioritization_fee_cache,
+                block_certificate_cache,
                 runtime,
             },
             transaction_receiver,
@@ -527,68 +550,137 @@ impl JsonRpcRequestProcessor {
             leader_schedule_cache,
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Some(Arc::new(PrioritizationFeeCache::default())),
+            block_certificate_cache: Arc::default(),
             runtime,
         }
     }
 
+    /// Resolves `at_slot` against `bank`, the latest bank at the requested commitment.
+    ///
+    /// Slots still in `BankForks` are served by their bank. Older roots are served by a
+    /// historical view of accounts-db, alongside `bank` which supplies the sysvars needed
+    /// to parse accounts.
+    fn get_bank_at_slot(
+        &self,
+        bank: Arc<Bank>,
+        at_slot: Option<Slot>,
+    ) -> Result<(Arc<Bank>, Option<Arc<HistoricalView>>)> {
+        let Some(at_slot) = at_slot else {
+            return Ok((bank, None));
+        };
+        if at_slot > bank.slot() {
+            return Err(RpcCustomError::MinContextSlotNotReached {
+                context_slot: bank.slot(),
+            }
+            .into());
+        }
+        if bank.ancestors.contains_key(&at_slot)
+            && let Some(at_bank) = self.bank_forks.read().unwrap().get(at_slot)
+        {
+            return Ok((at_bank, None));
+        }
+        let view = bank
+            .accounts()
+            .historical_view(at_slot)
+            .map_err(|err| match err {
+                HistoricalViewError::NotRooted { slot, .. } => RpcCustomError::SlotSkipped { slot },
+                HistoricalViewError::NotRetained {
+                    slot,
+                    oldest_retained_root,
+                } => RpcCustomError::AccountStateNotAvailable {
+                    slot,
+                    first_available_slot: oldest_retained_root,
+                },
+            })?;
+        Ok((bank, Some(Arc::new(view))))
+    }
+
     pub async fn get_account_info(
         &self,
         pubkey: Pubkey,
-        config: Option<RpcAccountInfoConfig>,
+        config: Option<RpcAccountInfoAtSlotConfig>,
     ) -> Result<RpcResponse<Option<UiAccount>>> {
-        let RpcAccountInfoConfig {
-            encoding,
-            data_slice,
-            commitment,
-            min_context_slot,
+        let RpcAccountInfoAtSlotConfig {
+            account_config:
+                RpcAccountInfoConfig {
+                    encoding,
+                    data_slice,
+                    commitment,
+                    min_context_slot,
+                },
+            at_slot,
         } = config.unwrap_or_default();
         let bank = self.get_bank_with_config(RpcContextConfig {
             commitment,
             min_context_slot,
         })?;
+        let (bank, view) = self.get_bank_at_slot(bank, at_slot)?;
         let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
 
         let response = self
             .runtime
             .spawn_blocking({
                 let bank = Arc::clone(&bank);
-                move || get_encoded_account(&bank, &pubkey, encoding, data_slice, None)
+                let view = view.clone();
+                move || {
+                    get_encoded_account_at_slot(
+                        &bank,
+                        view.as_deref(),
+                        &pubkey,
+                        encoding,
+                        data_slice,
+                    )
+                }
             })
             .await
             .expect("rpc: get_encoded_account panicked")?;
-        Ok(new_response(&bank, response))
+        Ok(new_response_at_slot(&bank, view.as_deref(), response))
     }
 
     pub async fn get_multiple_accounts(
         &self,
         pubkeys: Vec<Pubkey>,
-        config: Option<RpcAccountInfoConfig>,
+        config: Option<RpcAccountInfoAtSlotConfig>,
     ) -> Result<RpcResponse<Vec<Option<UiAccount>>>> {
-        let RpcAccountInfoConfig {
-            encoding,
-            data_slice,
-            commitment,
-            min_context_slot,
+        let RpcAccountInfoAtSlotConfig {
+            account_config:
+                RpcAccountInfoConfig {
+                    encoding,
+                    data_slice,
+                    commitment,
+                    min_context_slot,
+                },
+            at_slot,
         } = config.unwrap_or_default();
         let bank = self.get_bank_with_config(RpcContextConfig {
             commitment,
             min_context_slot,
         })?;
+        // Every account is read through the same bank or view, so all of them
+        // reflect the same slot
+        let (bank, view) = self.get_bank_at_slot(bank, at_slot)?;
         let encoding = encoding.unwrap_or(UiAccountEncoding::Base64);
 
         let mut accounts = Vec::with_capacity(pubkeys.len());
         for pubkey in pubkeys {
             let bank = Arc::clone(&bank);
+            let view = view.clone();
             accounts.push(
                 self.runtime
                     .spawn_blocking(move || {
-                        get_encoded_account(&bank, &pubkey, encoding, data_slice, None)
+                        get_encoded_account_at_slot(
+                            &bank,
+                            view.as_deref(),
+                            &pubkey,
+                            encoding,
+                            data_slice,
+                        )
                     })
                     .await
                     .expect("rpc: get_encoded_account panicked")?,
             );
         }
-        Ok(new_response(&bank, accounts))
+        Ok(new_response_at_slot(&bank, view.as_deref(), accounts))
     }
 
     pub fn get_minimum_balance_for_rent_exemption(
@@ -1639,6 +1731,27 @@ impl JsonRpcRequestProcessor {
         }
     }
 
+    pub fn get_block_certificates(&self, slot: Slot) -> Result<Vec<RpcCertificate>> {
+        let block_certificate_cache = self.block_certificate_cache.read().unwrap();
+        if let Some(lowest_slot) = block_certificate_cache.lowest_slot()
+            && slot < lowest_slot
+        {
+            return Err(Error::invalid_params(format!(
+                "Certificates for slot {slot} are no longer available; lowest available slot is \
+                 {lowest_slot}"
+            )));
+        }
+        Ok(block_certificate_cache.get(slot).to_vec())
+    }
+
+    pub fn get_equivocation_proofs(
+        &self,
+        start_slot: Option<Slot>,
+    ) -> Result<Vec<RpcEquivocationProof>> {
+        equivocation_proofs::get_equivocation_proofs(&self.blockstore, start_slot.unwrap_or(0))
+            .map_err(|_| Error::internal_error())
+    }
+
     pub fn get_signature_confirmation_status(
         &self,
         signature: Signature,
@@ -2259,7 +2372,21 @@ impl JsonRpcRequestProcessor {
         sort_results: bool,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         optimize_filters(&mut filters);
-        if self
+        if let Some(index_key) =
+            data_slice_index_key(&self.config.account_indexes, &program_id, &filters)
+        {
+            self.get_filtered_indexed_accounts(
+                &bank,
+                &index_key,
+                &program_id,
+                filters,
+                sort_results,
+            )
+            .await
+            .map_err(|e| RpcCustomError::ScanError {
+                message: e.to_string(),
+            })
+        } else if self
             .config
             .account_indexes
             .contains(&AccountIndex::ProgramId)
@@ -2468,6 +2595,21 @@ pub(crate) fn optimize_filters(filters: &mut [RpcFilterType]) {
     })
 }
 
+/// Returns the key of a data slice index that can answer a query for accounts of `program_id`
+/// passing `filters`, if a `memcmp` filter covers exactly the range of an enabled index.
+fn data_slice_index_key(
+    account_indexes: &AccountSecondaryIndexes,
+    program_id: &Pubkey,
+    filters: &[RpcFilterType],
+) -> Option<IndexKey> {
+    filters.iter().find_map(|filter_type| match filter_type {
+        RpcFilterType::Memcmp(compare) => compare.bytes().and_then(|bytes| {
+            account_indexes.data_slice_index_key(program_id, compare.offset(), &bytes)
+        }),
+        _ => None,
+    })
+}
+
 pub(crate) fn verify_filters(filters: &[RpcFilterType]) -> Result<()> {
     if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
         return Err(Error::invalid_params(format!(
@@ -2572,6 +2714,35 @@ fn get_encoded_account(
     }
 }
 
+/// Like `get_encoded_account()`, but reads from `view` when given
+fn get_encoded_account_at_slot(
+    bank: &Bank,
+    view: Option<&HistoricalView>,
+    pubkey: &Pubkey,
+    encoding: UiAccountEncoding,
+    data_slice: Option<UiDataSliceConfig>,
+) -> Result<Option<UiAccount>> {
+    let Some(view) = view else {
+        return get_encoded_account(bank, pubkey, encoding, data_slice, None);
+    };
+    let Some((account, _slot)) = view.load(pubkey) else {
+        return Ok(None);
+    };
+    let response =
+        if is_known_spl_token_id(account.owner()) && encoding == UiAccountEncoding::JsonParsed {
+            // Parse against the mint as of the same slot
+            let mint_account = get_token_account_mint(account.data()).and_then(|mint_pubkey| {
+                view.load(&mint_pubkey)
+                    .map(|(mint_account, _slot)| (mint_pubkey, mint_account))
+            });
+            let overwrite_accounts: HashMap<_, _> = mint_account.into_iter().collect();
+            get_parsed_token_account(bank, pubkey, account, Some(&overwrite_accounts))
+        } else {
+            encode_account(&account, pubkey, encoding, data_slice)?
+        };
+    Ok(Some(response))
+}
+
 fn encode_account<T: ReadableAccount>(
     account: &T,
     pubkey: &Pubkey,
@@ -3234,7 +3405,7 @@ pub mod rpc_accounts {
             &self,
             meta: Self::Metadata,
             pubkey_str: String,
-            config: Option<RpcAccountInfoConfig>,
+            config: Option<RpcAccountInfoAtSlotConfig>,
         ) -> BoxFuture<Result<RpcResponse<Option<UiAccount>>>>;
 
         #[rpc(meta, name = "getMultipleAccounts")]
@@ -3242,7 +3413,7 @@ pub mod rpc_accounts {
             &self,
             meta: Self::Metadata,
             pubkey_strs: Vec<String>,
-            config: Option<RpcAccountInfoConfig>,
+            config: Option<RpcAccountInfoAtSlotConfig>,
         ) -> BoxFuture<Result<RpcResponse<Vec<Option<UiAccount>>>>>;
 
         #[rpc(meta, name = "getBlockCommitment")]
@@ -3281,7 +3452,7 @@ pub mod rpc_accounts {
             &self,
             meta: Self::Metadata,
             pubkey_str: String,
-            config: Option<RpcAccountInfoConfig>,
+            config: Option<RpcAccountInfoAtSlotConfig>,
         ) -> BoxFuture<Result<RpcResponse<Option<UiAccount>>>> {
             debug!("get_account_info rpc request received: {pubkey_str:?}");
             async move {
@@ -3295,7 +3466,7 @@ pub mod rpc_accounts {
             &self,
             meta: Self::Metadata,
             pubkey_strs: Vec<String>,
-            config: Option<RpcAccountInfoConfig>,
+            config: Option<RpcAccountInfoAtSlotConfig>,
         ) -> BoxFuture<Result<RpcResponse<Vec<Option<UiAccount>>>>> {
             debug!(
                 "get_multiple_accounts rpc request received: {:?}",
@@ -3518,8 +3689,11 @@ pub mod rpc_accounts_scan {
 pub mod rpc_full {
     use {
         super::*,
+        solana_account::Account,
         solana_message::{SanitizedVersionedMessage, VersionedMessage},
+        solana_program_runtime::execution_trace::{ExecutionTrace, InvocationTrace},
         solana_transaction_status::{UiLoadedAddresses, parse_ui_inner_instructions},
+        std::io::Read,
     };
     #[rpc]
     pub trait Full {
@@ -3582,6 +3756,14 @@ pub mod rpc_full {
             config: Option<RpcSimulateTransactionConfig>,
         ) -> Result<RpcResponse<RpcSimulateTransactionResult>>;
 
+        #[rpc(meta, name = "simulateBundle")]
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<RpcSimulateBundleResult>>;
+
         #[rpc(meta, name = "minimumLedgerSlot")]
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot>;
 
@@ -3600,6 +3782,20 @@ pub mod rpc_full {
             slot: Slot,
         ) -> BoxFuture<Result<Option<UnixTimestamp>>>;
 
+        #[rpc(meta, name = "getBlockCertificates")]
+        fn get_block_certificates(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+        ) -> Result<Vec<RpcCertificate>>;
+
+        #[rpc(meta, name = "getEquivocationProofs")]
+        fn get_equivocation_proofs(
+            &self,
+            meta: Self::Metadata,
+            start_slot: Option<Slot>,
+        ) -> Result<Vec<RpcEquivocationProof>>;
+
         #[rpc(meta, name = "getBlocks")]
         fn get_blocks(
             &self,
@@ -3962,6 +4158,7 @@ pub mod rpc_full {
                     post_balances: _,
                     pre_token_balances: _,
                     post_token_balances: _,
+                    execution_trace: _,
                 } = simulation_result
                 {
                     match err {
@@ -3989,6 +4186,7 @@ pub mod rpc_full {
                             pre_token_balances: None,
                             post_token_balances: None,
                             loaded_addresses: None,
+                            execution_trace: None,
                         },
                     }
                     .into());
@@ -4022,7 +4220,16 @@ pub mod rpc_full {
                 accounts: config_accounts,
                 min_context_slot,
                 inner_instructions: enable_cpi_recording,
+                account_overrides,
+                sysvar_overrides,
+                execution_trace: enable_execution_tracing,
             } = config.unwrap_or_default();
+            if enable_execution_tracing && !meta.config.enable_simulation_tracing {
+                return Err(Error::invalid_params(
+                    "executionTrace is not enabled on this node, see \
+                     --rpc-enable-simulation-tracing",
+                ));
+            }
             let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
                 Error::invalid_params(format!(
@@ -4036,6 +4243,10 @@ pub mod rpc_full {
                 commitment,
                 min_context_slot,
             })?;
+            let state_overrides =
+                simulation_state_overrides(bank, account_overrides, sysvar_overrides)?;
+            // Overridden accounts the transaction did not load are reported as overridden
+            let mut post_simulation_accounts_map = state_overrides.accounts.clone();
             let mut blockhash: Option<RpcBlockhash> = None;
             if replace_recent_blockhash {
                 if sig_verify {
@@ -4065,40 +4276,22 @@ pub mod rpc_full {
                 None
             };
 
-            let simulation_result = if let Some(err) = verification_error {
+            let mut simulation_result = if let Some(err) = verification_error {
                 TransactionSimulationResult::new_error(err)
             } else {
-                bank.simulate_transaction(&transaction, enable_cpi_recording)
+                bank.simulate_transaction_with_config(
+                    &transaction,
+                    &SimulationConfig {
+                        enable_cpi_recording,
+                        enable_execution_tracing,
+                        state_overrides,
+                    },
+                )
             };
 
-            let TransactionSimulationResult {
-                result,
-                logs,
-                post_simulation_accounts,
-                units_consumed,
-                loaded_accounts_data_size,
-                return_data,
-                inner_instructions,
-                fee,
-                pre_balances,
-                post_balances,
-                pre_token_balances,
-                post_token_balances,
-            } = simulation_result;
-
-            let account_keys = transaction.message().account_keys();
-            let number_of_accounts = account_keys.len();
-
+            let number_of_accounts = transaction.message().account_keys().len();
             let accounts = if let Some(config_accounts) = config_accounts {
-                let accounts_encoding = config_accounts
-                    .encoding
-                    .unwrap_or(UiAccountEncoding::Base64);
-
-                if accounts_encoding == UiAccountEncoding::Binary
-                    || accounts_encoding == UiAccountEncoding::Base58
-                {
-                    return Err(Error::invalid_params("base58 encoding not supported"));
-                }
+                let accounts_encoding = simulation_accounts_encoding(&config_accounts)?;
 
                 if config_accounts.addresses.len() > number_of_accounts {
                     return Err(Error::invalid_params(format!(
@@ -4106,62 +4299,175 @@ pub mod rpc_full {
                     )));
                 }
 
-                if result.is_err() {
+                if simulation_result.result.is_err() {
                     Some(vec![None; config_accounts.addresses.len()])
                 } else {
-                    let mut post_simulation_accounts_map = HashMap::new();
-                    for (pubkey, data) in post_simulation_accounts {
-                        post_simulation_accounts_map.insert(pubkey, data);
-                    }
-
-                    Some(
-                        config_accounts
-                            .addresses
-                            .iter()
-                            .map(|address_str| {
-                                let pubkey = verify_pubkey(address_str)?;
-                                get_encoded_account(
-                                    bank,
-                                    &pubkey,
-                                    accounts_encoding,
-                                    None,
-                                    Some(&post_simulation_accounts_map),
-                                )
-                            })
-                            .collect::<Result<Vec<_>>>()?,
-                    )
+                    post_simulation_accounts_map
+                        .extend(mem::take(&mut simulation_result.post_simulation_accounts));
+                    Some(encode_simulated_accounts(
+                        bank,
+                        &config_accounts.addresses,
+                        accounts_encoding,
+                        &post_simulation_accounts_map,
+                    )?)
                 }
             } else {
                 None
             };
 
-            let inner_instructions = inner_instructions.map(|info| {
-                map_inner_instructions(info)
-                    .map(|converted| parse_ui_inner_instructions(converted, &account_keys))
-                    .collect()
+            Ok(new_response(
+                bank,
+                new_rpc_simulate_transaction_result(
+                    &transaction,
+                    simulation_result,
+                    accounts,
+                    blockhash,
+                ),
+            ))
+        }
+
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<RpcSimulateBundleResult>> {
+            debug!("simulate_bundle rpc request received");
+            let RpcSimulateBundleConfig {
+                sig_verify,
+                replace_recent_blockhash,
+                commitment,
+                encoding,
+                accounts: config_accounts,
+                min_context_slot,
+                inner_instructions: enable_cpi_recording,
+            } = config.unwrap_or_default();
+            if data.is_empty() {
+                return Err(Error::invalid_params("No transactions provided"));
+            }
+            if data.len() > MAX_SIMULATE_BUNDLE_TRANSACTIONS {
+                return Err(Error::invalid_params(format!(
+                    "Too many transactions provided; max {MAX_SIMULATE_BUNDLE_TRANSACTIONS}"
+                )));
+            }
+            if replace_recent_blockhash && sig_verify {
+                return Err(Error::invalid_params(
+                    "sigVerify may not be used with replaceRecentBlockhash",
+                ));
+            }
+            let accounts_encoding = config_accounts
+                .as_ref()
+                .map(simulation_accounts_encoding)
+                .transpose()?;
+            if let Some(config_accounts) = &config_accounts
+                && config_accounts.addresses.len() > MAX_MULTIPLE_ACCOUNTS
+            {
+                return Err(Error::invalid_params(format!(
+                    "Too many accounts provided; max {MAX_MULTIPLE_ACCOUNTS}"
+                )));
+            }
+            let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
+            let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+                Error::invalid_params(format!(
+                    "unsupported encoding: {tx_encoding}. Supported encodings: base58, base64"
+                ))
+            })?;
+
+            let bank = &*meta.get_bank_with_config(RpcContextConfig {
+                commitment,
+                min_context_slot,
+            })?;
+            let blockhash = replace_recent_blockhash.then(|| {
+                let recent_blockhash = bank.last_blockhash();
+                let last_valid_block_height = bank
+                    .get_blockhash_last_valid_block_height(&recent_blockhash)
+                    .expect("bank blockhash queue should contain blockhash");
+                (
+                    recent_blockhash,
+                    RpcBlockhash {
+                        blockhash: recent_blockhash.to_string(),
+                        last_valid_block_height,
+                    },
+                )
             });
 
+            // Address lookup tables are resolved against the bank, so transactions can't use
+            // tables created or extended earlier in the bundle
+            let transactions = data
+                .into_iter()
+                .map(|data| {
+                    let (_, mut unsanitized_tx) =
+                        decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
+                    if let Some((recent_blockhash, _)) = &blockhash {
+                        unsanitized_tx
+                            .message
+                            .set_recent_blockhash(*recent_blockhash);
+                    }
+                    sanitize_transaction(unsanitized_tx, bank, bank.get_reserved_account_keys())
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let verification_error = if sig_verify {
+                transactions
+                    .iter()
+                    .enumerate()
+                    .find_map(|(index, transaction)| {
+                        transaction.verify().err().map(|err| (index, err))
+                    })
+            } else {
+                None
+            };
+            let num_verified = verification_error
+                .as_ref()
+                .map_or(transactions.len(), |(index, _)| *index);
+            let mut simulation_results = bank
+                .simulate_transaction_sequence(&transactions[..num_verified], enable_cpi_recording);
+            if let Some((_, err)) = verification_error
+                && simulation_results
+                    .iter()
+                    .all(|simulation_result| simulation_result.result.is_ok())
+            {
+                simulation_results.push(TransactionSimulationResult::new_error(err));
+            }
+
+            let mut post_simulation_accounts = HashMap::new();
+            let mut transaction_results = Vec::with_capacity(simulation_results.len());
+            for (transaction, mut simulation_result) in transactions.iter().zip(simulation_results)
+            {
+                let accounts = match (&config_accounts, accounts_encoding) {
+                    (Some(config_accounts), Some(accounts_encoding))
+                        if simulation_result.result.is_ok() =>
+                    {
+                        post_simulation_accounts
+                            .extend(mem::take(&mut simulation_result.post_simulation_accounts));
+                        Some(encode_simulated_accounts(
+                            bank,
+                            &config_accounts.addresses,
+                            accounts_encoding,
+                            &post_simulation_accounts,
+                        )?)
+                    }
+                    (Some(config_accounts), _) => Some(vec![None; config_accounts.addresses.len()]),
+                    (None, _) => None,
+                };
+                transaction_results.push(new_rpc_simulate_transaction_result(
+                    transaction,
+                    simulation_result,
+                    accounts,
+                    blockhash.as_ref().map(|(_, blockhash)| blockhash.clone()),
+                ));
+            }
+
+            let failed_transaction_index = transaction_results
+                .iter()
+                .position(|transaction_result| transaction_result.err.is_some());
             Ok(new_response(
                 bank,
-                RpcSimulateTransactionResult {
-                    err: result.err().map(Into::into),
-                    logs: Some(logs),
-                    accounts,
-                    units_consumed: Some(units_consumed),
-                    loaded_accounts_data_size: Some(loaded_accounts_data_size),
-                    return_data: return_data.map(|return_data| return_data.into()),
-                    inner_instructions,
-                    replacement_blockhash: blockhash,
-                    fee,
-                    pre_balances,
-                    post_balances,
-                    pre_token_balances: pre_token_balances.map(|balances| {
-                        balances.into_iter().map(|balance| solana_runtime::transaction_balances::svm_token_info_to_token_balance(balance).into()).collect()
-                    }),
-                    post_token_balances: post_token_balances.map(|balances| {
-                        balances.into_iter().map(|balance| solana_runtime::transaction_balances::svm_token_info_to_token_balance(balance).into()).collect()
-                    }),
-                    loaded_addresses: Some(UiLoadedAddresses::from(&transaction.get_loaded_addresses())),
+                RpcSimulateBundleResult {
+                    err: failed_transaction_index
+                        .and_then(|index| transaction_results[index].err.clone()),
+                    failed_transaction_index,
+                    transaction_results,
                 },
             ))
         }
@@ -4216,6 +4522,24 @@ pub mod rpc_full {
             Box::pin(async move { meta.get_block_time(slot).await })
         }
 
+        fn get_block_certificates(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+        ) -> Result<Vec<RpcCertificate>> {
+            debug!("get_block_certificates rpc request received: {slot:?}");
+            meta.get_block_certificates(slot)
+        }
+
+        fn get_equivocation_proofs(
+            &self,
+            meta: Self::Metadata,
+            start_slot: Option<Slot>,
+        ) -> Result<Vec<RpcEquivocationProof>> {
+            debug!("get_equivocation_proofs rpc request received: {start_slot:?}");
+            meta.get_equivocation_proofs(start_slot)
+        }
+
         fn get_transaction(
             &self,
             meta: Self::Metadata,
@@ -4375,6 +4699,245 @@ pub mod rpc_full {
             meta.get_recent_prioritization_fees(pubkeys)
         }
     }
+
+    fn simulation_accounts_encoding(
+        config_accounts: &RpcSimulateTransactionAccountsConfig,
+    ) -> Result<UiAccountEncoding> {
+        let accounts_encoding = config_accounts
+            .encoding
+            .unwrap_or(UiAccountEncoding::Base64);
+        if accounts_encoding == UiAccountEncoding::Binary
+            || accounts_encoding == UiAccountEncoding::Base58
+        {
+            return Err(Error::invalid_params("base58 encoding not supported"));
+        }
+        Ok(accounts_encoding)
+    }
+
+    /// Decodes the state overrides of a `simulateTransaction` request
+    fn simulation_state_overrides(
+        bank: &Bank,
+        account_overrides: Option<HashMap<String, RpcAccountOverride>>,
+        sysvar_overrides: Option<RpcSysvarOverrides>,
+    ) -> Result<SimulationStateOverrides> {
+        let account_overrides = account_overrides.unwrap_or_default();
+        if account_overrides.len() > MAX_SIMULATE_ACCOUNT_OVERRIDES {
+            return Err(Error::invalid_params(format!(
+                "Too many account overrides provided; max {MAX_SIMULATE_ACCOUNT_OVERRIDES}"
+            )));
+        }
+
+        let mut state_overrides = SimulationStateOverrides::default();
+        // The data of all overrides is held in memory several times over while simulating
+        let mut remaining_data_len = MAX_SIMULATE_ACCOUNT_OVERRIDES_DATA_LEN;
+        for (address, account_override) in account_overrides {
+            let pubkey = verify_pubkey(&address)?;
+            if bank
+                .get_account(&pubkey)
+                .is_some_and(|account| solana_sdk_ids::sysvar::check_id(account.owner()))
+            {
+                return Err(Error::invalid_params(format!(
+                    "Sysvar account {pubkey} may not be overridden; use sysvarOverrides"
+                )));
+            }
+            let RpcAccountOverride {
+                lamports,
+                owner,
+                data: (data, data_encoding),
+                executable,
+            } = account_override;
+            let owner = verify_pubkey(&owner)?;
+            let data = decode_account_override_data(&data, data_encoding, remaining_data_len)?;
+            remaining_data_len = remaining_data_len.saturating_sub(data.len());
+            let account = Account {
+                lamports,
+                data,
+                owner,
+                executable,
+                ..Account::default()
+            };
+            state_overrides.accounts.insert(pubkey, account.into());
+        }
+
+        if let Some(RpcSysvarOverrides {
+            clock: Some(clock_override),
+        }) = sysvar_overrides
+        {
+            let mut clock = bank.clock();
+            if let Some(slot) = clock_override.slot {
+                clock.slot = slot;
+            }
+            if let Some(unix_timestamp) = clock_override.unix_timestamp {
+                clock.unix_timestamp = unix_timestamp;
+            }
+            state_overrides.clock = Some(clock);
+        }
+        Ok(state_overrides)
+    }
+
+    /// Decodes the data of an account override, failing if it exceeds `max_len` bytes
+    fn decode_account_override_data(
+        data: &str,
+        encoding: UiAccountEncoding,
+        max_len: usize,
+    ) -> Result<Vec<u8>> {
+        let invalid_data = |err: &dyn std::fmt::Display| {
+            Error::invalid_params(format!("invalid account override data: {err}"))
+        };
+        let decoded = BASE64_STANDARD
+            .decode(data)
+            .map_err(|err| invalid_data(&err));
+        let data = match encoding {
+            UiAccountEncoding::Base64 => decoded?,
+            UiAccountEncoding::Base64Zstd => {
+                let mut data = vec![];
+                // Bound the decompressed size so a small request can't exhaust memory
+                zstd::stream::read::Decoder::new(decoded?.as_slice())
+                    .and_then(|decoder| {
+                        decoder
+                            .take((max_len as u64).saturating_add(1))
+                            .read_to_end(&mut data)
+                    })
+                    .map_err(|err| invalid_data(&err))?;
+                data
+            }
+            _ => {
+                return Err(Error::invalid_params(
+                    "unsupported account override data encoding. Supported encodings: base64, \
+                     base64+zstd",
+                ));
+            }
+        };
+        if data.len() > max_len {
+            return Err(invalid_data(&format!(
+                "exceeds {MAX_SIMULATE_ACCOUNT_OVERRIDES_DATA_LEN} bytes across all overrides"
+            )));
+        }
+        Ok(data)
+    }
+
+    /// Encodes the requested accounts as left by the simulated transactions, falling back to the
+    /// bank for accounts they did not load
+    fn encode_simulated_accounts(
+        bank: &Bank,
+        addresses: &[String],
+        accounts_encoding: UiAccountEncoding,
+        post_simulation_accounts: &HashMap<Pubkey, AccountSharedData>,
+    ) -> Result<Vec<Option<UiAccount>>> {
+        addresses
+            .iter()
+            .map(|address_str| {
+                let pubkey = verify_pubkey(address_str)?;
+                get_encoded_account(
+                    bank,
+                    &pubkey,
+                    accounts_encoding,
+                    None,
+                    Some(post_simulation_accounts),
+                )
+            })
+            .collect()
+    }
+
+    fn new_rpc_simulate_transaction_result(
+        transaction: &RuntimeTransaction<SanitizedTransaction>,
+        simulation_result: TransactionSimulationResult,
+        accounts: Option<Vec<Option<UiAccount>>>,
+        replacement_blockhash: Option<RpcBlockhash>,
+    ) -> RpcSimulateTransactionResult {
+        let TransactionSimulationResult {
+            result,
+            logs,
+            post_simulation_accounts: _,
+            units_consumed,
+            loaded_accounts_data_size,
+            return_data,
+            inner_instructions,
+            fee,
+            pre_balances,
+            post_balances,
+            pre_token_balances,
+            post_token_balances,
+            execution_trace,
+        } = simulation_result;
+
+        let account_keys = transaction.message().account_keys();
+        let inner_instructions = inner_instructions.map(|info| {
+            map_inner_instructions(info)
+                .map(|converted| parse_ui_inner_instructions(converted, &account_keys))
+                .collect()
+        });
+        let to_ui_token_balances = |balances: Vec<_>| {
+            balances
+                .into_iter()
+                .map(|balance| {
+                    solana_runtime::transaction_balances::svm_token_info_to_token_balance(balance)
+                        .into()
+                })
+                .collect()
+        };
+
+        RpcSimulateTransactionResult {
+            err: result.err().map(Into::into),
+            logs: Some(logs),
+            accounts,
+            units_consumed: Some(units_consumed),
+            loaded_accounts_data_size: Some(loaded_accounts_data_size),
+            return_data: return_data.map(|return_data| return_data.into()),
+            inner_instructions,
+            replacement_blockhash,
+            fee,
+            pre_balances,
+            post_balances,
+            pre_token_balances: pre_token_balances.map(to_ui_token_balances),
+            post_token_balances: post_token_balances.map(to_ui_token_balances),
+            loaded_addresses: Some(UiLoadedAddresses::from(&transaction.get_loaded_addresses())),
+            execution_trace: execution_trace.map(rpc_execution_trace),
+        }
+    }
+
+    fn rpc_execution_trace(execution_trace: ExecutionTrace) -> Vec<RpcInvocationTrace> {
+        let mut top_level_index = 0;
+        execution_trace
+            .invocations
+            .into_iter()
+            .map(|invocation| {
+                let InvocationTrace {
+                    index_in_trace,
+                    caller_index_in_trace,
+                    program_id,
+                    stack_height,
+                    compute_units_consumed,
+                    result,
+                    written_accounts,
+                    program_counters,
+                    program_counters_truncated,
+                } = invocation;
+                // Top-level instructions occupy the start of the instruction trace, CPIs follow
+                if caller_index_in_trace.is_none() {
+                    top_level_index = index_in_trace;
+                }
+                RpcInvocationTrace {
+                    index_in_trace,
+                    caller_index_in_trace,
+                    program_id: program_id.to_string(),
+                    stack_height,
+                    compute_units_consumed,
+                    err: result.err().map(|err| {
+                        TransactionError::InstructionError(top_level_index as u8, err).into()
+                    }),
+                    written_accounts: written_accounts
+                        .iter()
+                        .map(|pubkey| pubkey.to_string())
+                        .collect(),
+                    program_counters: RpcInvocationTrace::encode_program_counters(
+                        &program_counters,
+                    ),
+                    program_counters_truncated,
+                }
+            })
+            .collect()
+    }
 }
 
 fn rpc_perf_sample_from_perf_sample(slot: u64, sample: PerfSample) -> RpcPerfSample {
@@ -4597,15 +5160,25 @@ pub mod tests {
             rpc_subscriptions::RpcSubscriptions,
         },
         agave_reserved_account_keys::ReservedAccountKeys,
+        agave_votor_messages::{
+            certificate::{Certificate, CertificateType},
+            consensus_message::Block,
+            equivocation::{EquivocationProof, SignedVote},
+            wire::VotePayloadToSign,
+        },
         jsonrpc_core::{ErrorCode, MetaIoHandler, Output, Response, Value, futures},
         jsonrpc_core_client::transports::local,
         serde::de::DeserializeOwned,
         solana_account::{Account, state_traits::StateMut},
-        solana_accounts_db::accounts_db::{ACCOUNTS_DB_CONFIG_FOR_TESTING, AccountsDbConfig},
+        solana_accounts_db::{
+            accounts_db::{ACCOUNTS_DB_CONFIG_FOR_TESTING, AccountsDbConfig},
+            accounts_index::DataSliceIndex,
+        },
         solana_address_lookup_table_interface::{
             self as address_lookup_table,
             state::{AddressLookupTable, LookupTableMeta},
         },
+        solana_bls_signatures::{BLS_SIGNATURE_AFFINE_SIZE, Signature as BLSSignature},
         solana_compute_budget_interface::ComputeBudgetInstruction,
         solana_entry::entry::next_versioned_entry,
         solana_fee_calculator::FeeRateGovernor,
@@ -4631,7 +5204,9 @@ pub mod tests {
         },
         solana_rpc_client_api::{
             custom_error::{
+                JSON_RPC_SERVER_ERROR_ACCOUNT_STATE_NOT_AVAILABLE,
                 JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED,
                 JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
                 JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION,
             },
@@ -4642,7 +5217,7 @@ pub mod tests {
             commitment::{BlockCommitment, CommitmentSlots},
             non_circulating_supply::non_circulating_accounts,
         },
-        solana_sdk_ids::bpf_loader_upgradeable,
+        solana_sdk_ids::{bpf_loader, bpf_loader_upgradeable, sysvar},
         solana_send_transaction_service::test_utils::create_client_for_tests,
         solana_sha256_hasher::hash,
         solana_signer::Signer,
@@ -4908,6 +5483,7 @@ pub mod tests {
                 Arc::new(LeaderScheduleCache::new_from_bank(&bank)),
                 max_complete_transaction_status_slot.clone(),
                 prioritization_fee_cache,
+                Arc::default(),
                 service_runtime(rpc_threads, rpc_blocking_threads, rpc_niceness_adj),
             )
             .0;
@@ -5932,6 +6508,57 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_rpc_get_account_info_at_slot() {
+        let rpc = RpcHandler::start();
+        let pubkey = Pubkey::new_unique();
+        let address = pubkey.to_string();
+        let bank0 = rpc.working_bank();
+        bank0.store_account(&pubkey, &AccountSharedData::new(42, 0, &Pubkey::default()));
+        let bank1 = rpc.advance_bank_to_confirmed_slot(1);
+        bank1.store_account(&pubkey, &AccountSharedData::new(43, 0, &Pubkey::default()));
+
+        let get_account_info_at_slot = |at_slot: Slot| {
+            rpc.handle_request_sync(create_test_request(
+                "getAccountInfo",
+                Some(json!([address, {"atSlot": at_slot}])),
+            ))
+        };
+
+        // Slots still in BankForks are served by their bank
+        let result: Value = parse_success_result(get_account_info_at_slot(0));
+        assert_eq!(result["context"]["slot"], 0);
+        assert_eq!(result["value"]["lamports"], 42);
+        let result: Value = parse_success_result(get_account_info_at_slot(1));
+        assert_eq!(result["context"]["slot"], 1);
+        assert_eq!(result["value"]["lamports"], 43);
+        let (code, _) = parse_failure_response(get_account_info_at_slot(2));
+        assert_eq!(code, JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED);
+
+        // Once pruned from BankForks, roots are served from accounts-db until cleaned
+        rpc.bank_forks.write().unwrap().set_root(1, None, Some(0));
+        assert!(rpc.bank_forks.read().unwrap().get(0).is_none());
+        let result: Value = parse_success_result(get_account_info_at_slot(0));
+        assert_eq!(result["context"]["slot"], 0);
+        assert_eq!(result["value"]["lamports"], 42);
+        let request = create_test_request(
+            "getMultipleAccounts",
+            Some(json!([[address, Pubkey::new_unique().to_string()], {"atSlot": 0}])),
+        );
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result["context"]["slot"], 0);
+        assert_eq!(result["value"][0]["lamports"], 42);
+        assert_eq!(result["value"][1], Value::Null);
+
+        bank1.force_flush_accounts_cache();
+        let response = parse_failure_response(get_account_info_at_slot(0));
+        let expected = (
+            JSON_RPC_SERVER_ERROR_ACCOUNT_STATE_NOT_AVAILABLE,
+            String::from("Account state for slot 0 is not available. First available slot: 1"),
+        );
+        assert_eq!(response, expected);
+    }
+
     #[test]
     fn test_rpc_get_program_accounts() {
         let rpc = RpcHandler::start();
@@ -6430,6 +7057,380 @@ pub mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_rpc_simulate_bundle() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        let alice = Keypair::new();
+        let bob_pubkey = solana_pubkey::new_rand();
+        let funding = 2 * rent_exempt_amount;
+        let encode = |tx: &Transaction| BASE64_STANDARD.encode(wincode::serialize(tx).unwrap());
+        // `alice` can only pay `bob` after being funded by the first transaction
+        let fund_alice = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &alice.pubkey(),
+            funding,
+            recent_blockhash,
+        );
+        let pay_bob =
+            system_transaction::transfer(&alice, &bob_pubkey, rent_exempt_amount, recent_blockhash);
+        let mut pay_bob_badsig = pay_bob.clone();
+        pay_bob_badsig.signatures[0] = Signature::default();
+        bank.freeze();
+
+        let simulate_bundle = |transactions: &[&Transaction]| {
+            let request = create_test_request(
+                "simulateBundle",
+                Some(json!([
+                    transactions.iter().map(|tx| encode(tx)).collect::<Vec<_>>(),
+                    {
+                        "encoding": "base64",
+                        "sigVerify": true,
+                        "accounts": {
+                            "encoding": "base64",
+                            "addresses": [alice.pubkey().to_string(), bob_pubkey.to_string()],
+                        },
+                    },
+                ])),
+            );
+            parse_success_result::<RpcResponse<RpcSimulateBundleResult>>(
+                rpc.handle_request_sync(request),
+            )
+            .value
+        };
+        let lamports = |transaction_result: &RpcSimulateTransactionResult| {
+            transaction_result
+                .accounts
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|account| account.as_ref().map(|account| account.lamports))
+                .collect::<Vec<_>>()
+        };
+
+        let result = simulate_bundle(&[&fund_alice, &pay_bob]);
+        assert_eq!(result.err, None);
+        assert_eq!(result.failed_transaction_index, None);
+        assert_eq!(result.transaction_results.len(), 2);
+        assert_eq!(
+            lamports(&result.transaction_results[0]),
+            [Some(funding), None]
+        );
+        assert_eq!(
+            lamports(&result.transaction_results[1]),
+            [
+                Some(funding - rent_exempt_amount - TEST_SIGNATURE_FEE),
+                Some(rent_exempt_amount)
+            ]
+        );
+
+        // The second transaction alone can't pay for its fee
+        let result = simulate_bundle(&[&pay_bob]);
+        assert_eq!(result.err, Some(TransactionError::AccountNotFound.into()));
+        assert_eq!(result.failed_transaction_index, Some(0));
+        assert_eq!(lamports(&result.transaction_results[0]), [None, None]);
+
+        // Transactions after a failed one are not simulated
+        let result = simulate_bundle(&[&fund_alice, &pay_bob_badsig, &pay_bob]);
+        assert_eq!(result.err, Some(TransactionError::SignatureFailure.into()));
+        assert_eq!(result.failed_transaction_index, Some(1));
+        assert_eq!(result.transaction_results.len(), 2);
+
+        // Empty bundles are rejected
+        let request = create_test_request("simulateBundle", Some(json!([[]])));
+        assert_eq!(
+            parse_failure_response(rpc.handle_request_sync(request)),
+            (
+                ErrorCode::InvalidParams.code(),
+                "No transactions provided".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_rpc_simulate_transaction_with_state_overrides() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        let alice = Keypair::new();
+        let bob_pubkey = solana_pubkey::new_rand();
+        let carol_pubkey = solana_pubkey::new_rand();
+        let tx =
+            system_transaction::transfer(&alice, &bob_pubkey, rent_exempt_amount, recent_blockhash);
+        let tx_serialized_encoded = BASE64_STANDARD.encode(wincode::serialize(&tx).unwrap());
+        let carol_data = [1u8, 2, 3];
+        let carol_data_encoded =
+            BASE64_STANDARD.encode(zstd::encode_all(&carol_data[..], 0).unwrap());
+        bank.freeze();
+
+        let simulate = |config: Value| {
+            let request = create_test_request(
+                "simulateTransaction",
+                Some(json!([tx_serialized_encoded, config])),
+            );
+            rpc.handle_request_sync(request)
+        };
+
+        // `alice` doesn't exist without overrides
+        let result = parse_success_result::<RpcResponse<RpcSimulateTransactionResult>>(simulate(
+            json!({"encoding": "base64"}),
+        ))
+        .value;
+        assert_eq!(result.err, Some(TransactionError::AccountNotFound.into()));
+
+        let result =
+            parse_success_result::<RpcResponse<RpcSimulateTransactionResult>>(simulate(json!({
+                "encoding": "base64",
+                "sigVerify": true,
+                "accountOverrides": {
+                    alice.pubkey().to_string(): {
+                        "lamports": 2 * rent_exempt_amount,
+                        "owner": system_program::id().to_string(),
+                        "data": ["", "base64"],
+                    },
+                    carol_pubkey.to_string(): {
+                        "lamports": rent_exempt_amount,
+                        "owner": system_program::id().to_string(),
+                        "data": [carol_data_encoded, "base64+zstd"],
+                    },
+                },
+                "sysvarOverrides": {
+                    "clock": {"unixTimestamp": 1_700_000_000},
+                },
+                "accounts": {
+                    "encoding": "base64",
+                    "addresses": [
+                        alice.pubkey().to_string(),
+                        bob_pubkey.to_string(),
+                        carol_pubkey.to_string(),
+                    ],
+                },
+            })))
+            .value;
+        assert_eq!(result.err, None);
+        let accounts = result.accounts.unwrap();
+        assert_eq!(
+            accounts[0].as_ref().unwrap().lamports,
+            rent_exempt_amount - TEST_SIGNATURE_FEE
+        );
+        assert_eq!(accounts[1].as_ref().unwrap().lamports, rent_exempt_amount);
+        // Overridden accounts the transaction didn't load are reported as overridden
+        assert_eq!(
+            accounts[2].as_ref().unwrap().data.decode().unwrap(),
+            carol_data
+        );
+        // Nothing was committed
+        assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+
+        // Sysvars can only be overridden through `sysvarOverrides`
+        let clock_override = json!({
+            "encoding": "base64",
+            "accountOverrides": {
+                sysvar::clock::id().to_string(): {
+                    "lamports": 1,
+                    "owner": sysvar::id().to_string(),
+                    "data": ["", "base64"],
+                },
+            },
+        });
+        assert_eq!(
+            parse_failure_response(simulate(clock_override)),
+            (
+                ErrorCode::InvalidParams.code(),
+                format!(
+                    "Sysvar account {} may not be overridden; use sysvarOverrides",
+                    sysvar::clock::id()
+                )
+            )
+        );
+
+        let base58_data = json!({
+            "encoding": "base64",
+            "accountOverrides": {
+                alice.pubkey().to_string(): {
+                    "lamports": 1,
+                    "owner": system_program::id().to_string(),
+                    "data": ["", "base58"],
+                },
+            },
+        });
+        assert_eq!(
+            parse_failure_response(simulate(base58_data)),
+            (
+                ErrorCode::InvalidParams.code(),
+                "unsupported account override data encoding. Supported encodings: base64, \
+                 base64+zstd"
+                    .to_string()
+            )
+        );
+
+        // Small requests can't inflate into more override data than the total budget
+        let large_data_encoded = BASE64_STANDARD.encode(
+            zstd::encode_all(
+                &vec![0u8; MAX_SIMULATE_ACCOUNT_OVERRIDES_DATA_LEN / 2 + 1][..],
+                0,
+            )
+            .unwrap(),
+        );
+        let large_override = json!({
+            "lamports": 1,
+            "owner": system_program::id().to_string(),
+            "data": [large_data_encoded, "base64+zstd"],
+        });
+        let within_budget = json!({
+            "encoding": "base64",
+            "accountOverrides": {
+                alice.pubkey().to_string(): large_override,
+            },
+        });
+        parse_success_result::<RpcResponse<RpcSimulateTransactionResult>>(simulate(within_budget));
+        let over_budget = json!({
+            "encoding": "base64",
+            "accountOverrides": {
+                alice.pubkey().to_string(): large_override,
+                carol_pubkey.to_string(): large_override,
+            },
+        });
+        assert_eq!(
+            parse_failure_response(simulate(over_budget)),
+            (
+                ErrorCode::InvalidParams.code(),
+                format!(
+                    "invalid account override data: exceeds \
+                     {MAX_SIMULATE_ACCOUNT_OVERRIDES_DATA_LEN} bytes across all overrides"
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn test_rpc_simulate_transaction_with_execution_trace() {
+        let rpc = RpcHandler::start_with_config(JsonRpcConfig {
+            enable_rpc_transaction_history: true,
+            enable_simulation_tracing: true,
+            ..JsonRpcConfig::default()
+        });
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+
+        // The program transfers lamports from its first account to its second one with a CPI to
+        // the system program
+        let program_id = solana_pubkey::new_rand();
+        let program_data = include_bytes!(
+            "../../svm/tests/example-programs/simple-transfer/simple_transfer_program.so"
+        );
+        let program_account = Account {
+            lamports: bank.get_minimum_balance_for_rent_exemption(program_data.len()),
+            data: program_data.to_vec(),
+            owner: bpf_loader::id(),
+            executable: true,
+            ..Account::default()
+        };
+        bank.store_account(&program_id, &program_account.into());
+
+        // Advance the bank so that the program becomes effective
+        let bank = rpc.advance_bank_to_confirmed_slot(1);
+        let recent_blockhash = bank.last_blockhash();
+        let alice = Keypair::new();
+        let bob_pubkey = solana_pubkey::new_rand();
+        bank.freeze();
+
+        let request = |lamports: u64, execution_trace: bool| {
+            let instruction = Instruction::new_with_bytes(
+                program_id,
+                &lamports.to_be_bytes(),
+                vec![
+                    AccountMeta::new(alice.pubkey(), true),
+                    AccountMeta::new(bob_pubkey, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            );
+            let tx = Transaction::new_signed_with_payer(
+                &[instruction],
+                Some(&alice.pubkey()),
+                &[&alice],
+                recent_blockhash,
+            );
+            let request = create_test_request(
+                "simulateTransaction",
+                Some(json!([
+                    BASE64_STANDARD.encode(wincode::serialize(&tx).unwrap()),
+                    {
+                        "encoding": "base64",
+                        "executionTrace": execution_trace,
+                        "accountOverrides": {
+                            alice.pubkey().to_string(): {
+                                "lamports": 10 * rent_exempt_amount,
+                                "owner": system_program::id().to_string(),
+                                "data": ["", "base64"],
+                            },
+                        },
+                    },
+                ])),
+            )
+        };
+        let simulate = |lamports: u64, execution_trace: bool| {
+            parse_success_result::<RpcResponse<RpcSimulateTransactionResult>>(
+                rpc.handle_request_sync(request(lamports, execution_trace)),
+            )
+            .value
+        };
+
+        // Nodes only record execution traces when configured to
+        assert_eq!(
+            parse_failure_response(
+                RpcHandler::start().handle_request_sync(request(rent_exempt_amount, true))
+            ),
+            (
+                ErrorCode::InvalidParams.code(),
+                "executionTrace is not enabled on this node, see --rpc-enable-simulation-tracing"
+                    .to_string(),
+            )
+        );
+
+        let result = simulate(rent_exempt_amount, false);
+        assert_eq!(result.err, None);
+        assert_eq!(result.execution_trace, None);
+
+        let result = simulate(rent_exempt_amount, true);
+        assert_eq!(result.err, None);
+        let execution_trace = result.execution_trace.unwrap();
+        assert_eq!(execution_trace.len(), 2);
+
+        let program_invocation = &execution_trace[0];
+        assert_eq!(program_invocation.index_in_trace, 0);
+        assert_eq!(program_invocation.caller_index_in_trace, None);
+        assert_eq!(program_invocation.program_id, program_id.to_string());
+        assert_eq!(program_invocation.stack_height, 1);
+        assert_eq!(program_invocation.err, None);
+        // The executed sBPF instructions are recorded
+        assert!(!program_invocation.program_counters.is_empty());
+
+        let cpi_invocation = &execution_trace[1];
+        assert_eq!(cpi_invocation.index_in_trace, 1);
+        assert_eq!(cpi_invocation.caller_index_in_trace, Some(0));
+        assert_eq!(cpi_invocation.program_id, system_program::id().to_string());
+        assert_eq!(cpi_invocation.stack_height, 2);
+        assert_eq!(cpi_invocation.err, None);
+        // Only lamports changed, no account data
+        assert!(cpi_invocation.written_accounts.is_empty());
+        // Builtins don't execute sBPF instructions
+        assert_eq!(cpi_invocation.program_counters, String::new());
+
+        // The failing CPI reports its error against the top-level instruction
+        let result = simulate(20 * rent_exempt_amount, true);
+        let expected_err: UiTransactionError =
+            TransactionError::InstructionError(0, InstructionError::Custom(1)).into();
+        assert_eq!(result.err, Some(expected_err.clone()));
+        let execution_trace = result.execution_trace.unwrap();
+        assert_eq!(execution_trace.len(), 2);
+        assert!(!execution_trace[0].program_counters.is_empty());
+        assert_eq!(execution_trace[1].err, Some(expected_err));
+    }
+
     #[test]
     fn test_rpc_simulate_transaction_with_parsing_token_accounts() {
         let rpc = RpcHandler::start();
@@ -6984,6 +7985,7 @@ pub mod tests {
             Arc::new(LeaderScheduleCache::default()),
             Arc::new(AtomicU64::default()),
             Some(Arc::new(PrioritizationFeeCache::default())),
+            Arc::default(),
             runtime.clone(),
         );
 
@@ -7141,6 +8143,37 @@ pub mod tests {
         assert!(verify_filter(&filter).is_err());
     }
 
+    #[test]
+    fn test_data_slice_index_key() {
+        let program_id = Pubkey::new_unique();
+        let data_slice_index = DataSliceIndex::new(program_id, 8, 32).unwrap();
+        let account_indexes = AccountSecondaryIndexes {
+            keys: None,
+            indexes: HashSet::from([AccountIndex::DataSlice(data_slice_index)]),
+        };
+        let key = Pubkey::new_unique();
+        let mut filters = vec![
+            RpcFilterType::DataSize(165),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &[1, 2, 3])),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8, key.as_ref())),
+        ];
+        optimize_filters(&mut filters);
+        assert!(matches!(
+            data_slice_index_key(&account_indexes, &program_id, &filters),
+            Some(IndexKey::DataSlice(index, index_key)) if index == data_slice_index && index_key == key
+        ));
+
+        // Another program
+        assert!(data_slice_index_key(&account_indexes, &Pubkey::new_unique(), &filters).is_none());
+        // No filter covering the indexed range
+        assert!(data_slice_index_key(&account_indexes, &program_id, &filters[..2]).is_none());
+        // Index not enabled
+        assert!(
+            data_slice_index_key(&AccountSecondaryIndexes::default(), &program_id, &filters)
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_rpc_verify_pubkey() {
         let pubkey = solana_pubkey::new_rand();
@@ -7299,6 +8332,7 @@ pub mod tests {
             Arc::new(LeaderScheduleCache::default()),
             Arc::new(AtomicU64::default()),
             Some(Arc::new(PrioritizationFeeCache::default())),
+            Arc::default(),
             runtime,
         );
 
@@ -7757,6 +8791,98 @@ pub mod tests {
         assert_eq!(response, expected);
     }
 
+    #[test]
+    fn test_get_block_certificates() {
+        let rpc = RpcHandler::start();
+        let new_certificate = |cert_type| Certificate {
+            cert_type,
+            signature: BLSSignature([0; BLS_SIGNATURE_AFFINE_SIZE]),
+            bitmap: vec![1],
+        };
+
+        let request = create_test_request("getBlockCertificates", Some(json!([5u64])));
+        let result: Vec<RpcCertificate> = parse_success_result(rpc.handle_request_sync(request));
+        assert!(result.is_empty());
+
+        let finalize = {
+            let mut block_certificate_cache = rpc.meta.block_certificate_cache.write().unwrap();
+            block_certificate_cache.insert(&new_certificate(CertificateType::Skip(6)));
+            block_certificate_cache
+                .insert(&new_certificate(CertificateType::Finalize(5)))
+                .unwrap()
+        };
+        let request = create_test_request("getBlockCertificates", Some(json!([5u64])));
+        let result: Vec<RpcCertificate> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result, vec![finalize]);
+
+        let request = create_test_request("getBlockCertificates", Some(json!([4u64])));
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            String::from(
+                "Certificates for slot 4 are no longer available; lowest available slot is 5",
+            ),
+        );
+        assert_eq!(response, expected);
+    }
+
+    #[test]
+    fn test_get_equivocation_proofs() {
+        let rpc = RpcHandler::start();
+        let request = create_test_request("getEquivocationProofs", None);
+        let result: Vec<RpcEquivocationProof> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert!(result.is_empty());
+
+        let block = Block {
+            slot: 7,
+            block_id: Hash::new_unique(),
+        };
+        let proof = EquivocationProof {
+            vote_account: rpc.leader_vote_keypair.pubkey(),
+            first: SignedVote {
+                payload: VotePayloadToSign::Notar {
+                    block,
+                    shred_version: 1,
+                },
+                signature: BLSSignature([1; BLS_SIGNATURE_AFFINE_SIZE]),
+            },
+            second: SignedVote {
+                payload: VotePayloadToSign::Skip {
+                    slot: 7,
+                    shred_version: 1,
+                },
+                signature: BLSSignature([2; BLS_SIGNATURE_AFFINE_SIZE]),
+            },
+        };
+        assert!(rpc.blockstore.store_equivocation_proof(&proof).unwrap());
+
+        let request = create_test_request("getEquivocationProofs", Some(json!([5u64])));
+        let result: Vec<RpcEquivocationProof> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 1);
+        let rpc_proof = &result[0];
+        assert_eq!(rpc_proof.slot, 7);
+        assert_eq!(
+            rpc_proof.vote_account,
+            rpc.leader_vote_keypair.pubkey().to_string()
+        );
+        assert_eq!(rpc_proof.first.vote_type, RpcAlpenglowVoteType::Notarize);
+        assert_eq!(rpc_proof.first.block_id, Some(block.block_id.to_string()));
+        assert_eq!(rpc_proof.second.vote_type, RpcAlpenglowVoteType::Skip);
+        assert_eq!(rpc_proof.second.block_id, None);
+        let encoded = BASE64_STANDARD.decode(&rpc_proof.proof).unwrap();
+        assert_eq!(
+            wincode::deserialize::<EquivocationProof>(&encoded).unwrap(),
+            proof
+        );
+
+        let request = create_test_request("getEquivocationProofs", Some(json!([8u64])));
+        let result: Vec<RpcEquivocationProof> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert!(result.is_empty());
+    }
+
     #[test]
     fn test_get_vote_accounts() {
         let rpc = RpcHandler::start();
@@ -9069,6 +10195,7 @@ pub mod tests {
             Arc::new(LeaderScheduleCache::default()),
             max_complete_transaction_status_slot,
             prioritization_fee_cache_inner.clone(),
+            Arc::default(),
             service_runtime(rpc_threads, rpc_blocking_threads, rpc_niceness_adj),
         );
 
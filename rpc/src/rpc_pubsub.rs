@@ -26,8 +26,8 @@ use {
             RpcTransactionLogsFilter,
         },
         response::{
-            Response as RpcResponse, RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse,
-            RpcSignatureResult, RpcVersionInfo, RpcVote, SlotInfo, SlotUpdate,
+            Response as RpcResponse, RpcBlockUpdate, RpcCertificate, RpcKeyedAccount,
+            RpcLogsResponse, RpcSignatureResult, RpcVersionInfo, RpcVote, SlotInfo, SlotUpdate,
         },
     },
     solana_signature::Signature,
@@ -232,6 +232,26 @@ pub trait RpcSolPubSub {
         id: PubSubSubscriptionId,
     ) -> Result<bool>;
 
+    // Get notification when a new Alpenglow certificate is observed
+    #[pubsub(
+        subscription = "certificateNotification",
+        subscribe,
+        name = "certificateSubscribe"
+    )]
+    fn certificate_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<RpcCertificate>);
+
+    // Unsubscribe from certificate notification subscription.
+    #[pubsub(
+        subscription = "certificateNotification",
+        unsubscribe,
+        name = "certificateUnsubscribe"
+    )]
+    fn certificate_unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        id: PubSubSubscriptionId,
+    ) -> Result<bool>;
+
     // Get notification when a new root is set
     #[pubsub(subscription = "rootNotification", subscribe, name = "rootSubscribe")]
     fn root_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<Slot>);
@@ -344,6 +364,14 @@ mod internal {
         #[rpc(name = "voteUnsubscribe")]
         fn vote_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
 
+        // Get notification when a new Alpenglow certificate is observed
+        #[rpc(name = "certificateSubscribe")]
+        fn certificate_subscribe(&self) -> Result<SubscriptionId>;
+
+        // Unsubscribe from certificate notification subscription.
+        #[rpc(name = "certificateUnsubscribe")]
+        fn certificate_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
+
         // Get notification when a new root is set
         #[rpc(name = "rootSubscribe")]
         fn root_subscribe(&self) -> Result<SubscriptionId>;
@@ -593,6 +621,14 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
         self.unsubscribe(id)
     }
 
+    fn certificate_subscribe(&self) -> Result<SubscriptionId> {
+        self.subscribe(SubscriptionParams::Certificate)
+    }
+
+    fn certificate_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
+        self.unsubscribe(id)
+    }
+
     fn root_subscribe(&self) -> Result<SubscriptionId> {
         self.subscribe(SubscriptionParams::Root)
     }
@@ -618,11 +654,13 @@ mod tests {
             optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank, rpc_pubsub_service,
             rpc_subscriptions::RpcSubscriptions,
         },
+        agave_votor_messages::certificate::{Certificate, CertificateType},
         base64::{Engine, prelude::BASE64_STANDARD},
         jsonrpc_core::{IoHandler, Response},
         serial_test::serial,
         solana_account::ReadableAccount,
         solana_account_decoder::{UiAccountEncoding, parse_account_data::parse_account_data_v3},
+        solana_bls_signatures::{BLS_SIGNATURE_AFFINE_SIZE, Signature as BLSSignature},
         solana_clock::Slot,
         solana_commitment_config::CommitmentConfig,
         solana_hash::Hash,
@@ -1383,6 +1421,50 @@ mod tests {
         assert!(rpc.vote_unsubscribe(sub_id).is_ok());
     }
 
+    #[test]
+    #[serial]
+    fn test_certificate_subscribe() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let bank_forks = BankForks::new_rw_arc(bank);
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::default());
+        let rpc_subscriptions = Arc::new(RpcSubscriptions::default_with_bank_forks(
+            max_complete_transaction_status_slot,
+            bank_forks,
+        ));
+        let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&rpc_subscriptions);
+        let sub_id = rpc.certificate_subscribe().unwrap();
+
+        let certificate = Certificate {
+            cert_type: CertificateType::Finalize(3),
+            signature: BLSSignature([0; BLS_SIGNATURE_AFFINE_SIZE]),
+            bitmap: vec![1],
+        };
+        // Certificates already seen are not notified again
+        rpc_subscriptions.notify_certificates([&certificate, &certificate]);
+
+        let response = receiver.recv();
+        let expected_signature = BASE64_STANDARD.encode([0; BLS_SIGNATURE_AFFINE_SIZE]);
+        assert_eq!(
+            response,
+            format!(
+                r#"{{"jsonrpc":"2.0","method":"certificateNotification","params":{{"result":{{"slot":3,"certificateType":"finalize","blockId":null,"signature":"{expected_signature}","bitmap":"AQ=="}},"subscription":0}}}}"#
+            )
+        );
+        assert_eq!(
+            rpc_subscriptions
+                .block_certificate_cache()
+                .read()
+                .unwrap()
+                .get(3)
+                .len(),
+            1
+        );
+
+        assert!(rpc.certificate_unsubscribe(42.into()).is_err());
+        assert!(rpc.certificate_unsubscribe(sub_id).is_ok());
+    }
+
     #[test]
     fn test_get_version() {
         let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
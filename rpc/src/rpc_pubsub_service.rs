@@ -121,6 +121,7 @@ struct SentNotificationStats {
     num_slots_updates: AtomicUsize,
     num_root: AtomicUsize,
     num_vote: AtomicUsize,
+    num_certificate: AtomicUsize,
     num_block: AtomicUsize,
     total_creation_to_queue_time_us: AtomicU64,
     last_report: AtomicInterval,
@@ -171,6 +172,11 @@ impl SentNotificationStats {
                     self.num_vote.swap(0, Ordering::Relaxed) as i64,
                     i64
                 ),
+                (
+                    "num_certificate",
+                    self.num_certificate.swap(0, Ordering::Relaxed) as i64,
+                    i64
+                ),
                 (
                     "num_block",
                     self.num_block.swap(0, Ordering::Relaxed) as i64,
@@ -222,6 +228,9 @@ fn increment_sent_notification_stats(
         SubscriptionParams::Vote => {
             stats.num_vote.fetch_add(1, Ordering::Relaxed);
         }
+        SubscriptionParams::Certificate => {
+            stats.num_certificate.fetch_add(1, Ordering::Relaxed);
+        }
         SubscriptionParams::Block(_) => {
             stats.num_block.fetch_add(1, Ordering::Relaxed);
         }
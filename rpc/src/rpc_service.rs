@@ -491,6 +491,7 @@ pub struct JsonRpcServiceConfig<'a> {
     pub leader_schedule_cache: Arc<LeaderScheduleCache>,
     pub max_complete_transaction_status_slot: Arc<AtomicU64>,
     pub prioritization_fee_cache: Option<Arc<PrioritizationFeeCache>>,
+    pub block_certificate_cache: Arc<RwLock<BlockCertificateCache>>,
     pub rpc_tpu_client_args: RpcTpuClientArgs<'a>,
 }
 
@@ -565,6 +566,7 @@ impl JsonRpcService {
             client,
             config.max_complete_transaction_status_slot,
             config.prioritization_fee_cache,
+            config.block_certificate_cache,
             runtime,
         )?;
         Ok(json_rpc_service)
@@ -592,6 +594,7 @@ impl JsonRpcService {
         client: TpuClient,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Option<Arc<PrioritizationFeeCache>>,
+        block_certificate_cache: Arc<RwLock<BlockCertificateCache>>,
         runtime: Arc<TokioRuntime>,
     ) -> Result<Self, String> {
         info!("rpc bound to {rpc_addr:?}");
@@ -683,6 +686,7 @@ impl JsonRpcService {
             leader_schedule_cache,
             max_complete_transaction_status_slot,
             prioritization_fee_cache,
+            block_certificate_cache,
             Arc::clone(&runtime),
         );
 
@@ -912,6 +916,7 @@ mod tests {
             client,
             Arc::new(AtomicU64::default()),
             Some(Arc::new(PrioritizationFeeCache::default())),
+            Arc::default(),
             runtime,
         )
         .expect("assume successful JsonRpcService start");
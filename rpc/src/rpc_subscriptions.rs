@@ -2,6 +2,7 @@
 
 use {
     crate::{
+        block_certificate_cache::BlockCertificateCache,
         filter::filter_allows,
         optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
         parsed_token_accounts::{get_parsed_token_account, get_parsed_token_accounts},
@@ -13,6 +14,7 @@ use {
             SubscriptionParams, SubscriptionsTracker,
         },
     },
+    agave_votor_messages::certificate::Certificate,
     crossbeam_channel::{Receiver, RecvTimeoutError, SendError, Sender},
     itertools::Either,
     rayon::prelude::*,
@@ -27,7 +29,7 @@ use {
     solana_pubkey::Pubkey,
     solana_rpc_client_api::response::{
         ProcessedSignatureResult, ReceivedSignatureResult, Response as RpcResponse, RpcBlockUpdate,
-        RpcBlockUpdateError, RpcKeyedAccount, RpcLogsResponse, RpcResponseContext,
+        RpcBlockUpdateError, RpcCertificate, RpcKeyedAccount, RpcLogsResponse, RpcResponseContext,
         RpcSignatureResult, RpcVote, SlotInfo, SlotUpdate,
     },
     solana_runtime::{
@@ -98,6 +100,7 @@ pub enum NotificationEntry {
     Slot(SlotInfo),
     SlotUpdate(SlotUpdate),
     Vote((Pubkey, VoteTransaction, Signature)),
+    Certificate(RpcCertificate),
     Root(Slot),
     Bank(CommitmentSlots),
     Gossip(Slot),
@@ -111,6 +114,9 @@ impl std::fmt::Debug for NotificationEntry {
         match self {
             NotificationEntry::Root(root) => write!(f, "Root({root})"),
             NotificationEntry::Vote(vote) => write!(f, "Vote({vote:?})"),
+            NotificationEntry::Certificate(certificate) => {
+                write!(f, "Certificate({certificate:?})")
+            }
             NotificationEntry::Slot(slot_info) => write!(f, "Slot({slot_info:?})"),
             NotificationEntry::SlotUpdate(slot_update) => {
                 write!(f, "SlotUpdate({slot_update:?})")
@@ -483,6 +489,7 @@ struct PubsubNotificationStats {
     notify_slot_count: u64,
     notify_slot_update_count: u64,
     notify_vote_count: u64,
+    notify_certificate_count: u64,
     notify_root_count: u64,
     notify_signature_count: u64,
     notification_entry_processing_count: u64,
@@ -505,6 +512,11 @@ impl PubsubNotificationStats {
                 i64
             ),
             ("notify_vote_count", self.notify_vote_count, i64),
+            (
+                "notify_certificate_count",
+                self.notify_certificate_count,
+                i64
+            ),
             ("notify_root_count", self.notify_root_count, i64),
             ("notify_signature_count", self.notify_signature_count, i64),
             (
@@ -531,6 +543,7 @@ pub struct RpcSubscriptions {
 
     exit: Arc<AtomicBool>,
     control: SubscriptionControl,
+    block_certificate_cache: Arc<RwLock<BlockCertificateCache>>,
 }
 
 impl Drop for RpcSubscriptions {
@@ -662,6 +675,7 @@ impl RpcSubscriptions {
             t_cleanup,
             exit,
             control,
+            block_certificate_cache: Arc::default(),
         }
     }
 
@@ -691,6 +705,11 @@ impl RpcSubscriptions {
         &self.control
     }
 
+    /// Recent certificates passed to `notify_certificates`
+    pub fn block_certificate_cache(&self) -> &Arc<RwLock<BlockCertificateCache>> {
+        &self.block_certificate_cache
+    }
+
     /// Notify subscribers of changes to any accounts or new signatures since
     /// the bank's last checkpoint.
     pub fn notify_subscribers(&self, commitment_slots: CommitmentSlots) {
@@ -724,6 +743,21 @@ impl RpcSubscriptions {
         self.enqueue_notification(NotificationEntry::Vote((vote_pubkey, vote, signature)));
     }
 
+    /// Caches certificates observed by the consensus pool and notifies subscribers of the ones
+    /// not seen before
+    pub fn notify_certificates<'a>(&self, certificates: impl IntoIterator<Item = &'a Certificate>) {
+        let new_certificates: Vec<_> = {
+            let mut block_certificate_cache = self.block_certificate_cache.write().unwrap();
+            certificates
+                .into_iter()
+                .filter_map(|certificate| block_certificate_cache.insert(certificate))
+                .collect()
+        };
+        for certificate in new_certificates {
+            self.enqueue_notification(NotificationEntry::Certificate(certificate));
+        }
+    }
+
     pub fn notify_roots(&self, mut rooted_slots: Vec<Slot>) {
         rooted_slots.sort_unstable();
         rooted_slots.into_iter().for_each(|root| {
@@ -822,6 +856,16 @@ impl RpcSubscriptions {
                                 notifier.notify(&rpc_vote, sub, false);
                             }
                         }
+                        NotificationEntry::Certificate(ref certificate) => {
+                            if let Some(sub) = subscriptions
+                                .node_progress_watchers()
+                                .get(&SubscriptionParams::Certificate)
+                            {
+                                debug!("certificate notify: {certificate:?}");
+                                stats.notify_certificate_count += 1;
+                                notifier.notify(certificate, sub, false);
+                            }
+                        }
                         NotificationEntry::Root(root) => {
                             if let Some(sub) = subscriptions
                                 .node_progress_watchers()
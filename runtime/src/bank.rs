@@ -138,9 +138,11 @@ use {
     solana_packet::PACKET_DATA_SIZE,
     solana_precompile_error::PrecompileError,
     solana_program_runtime::{
+        execution_trace::ExecutionTrace,
         invoke_context::BuiltinFunctionRegisterer,
         loaded_programs::{ProgramRuntimeEnvironment, ProgramRuntimeEnvironments},
         program_cache_entry::ProgramCacheEntry,
+        sysvar_cache::SysvarCache,
     },
     solana_pubkey::Pubkey,
     solana_rent::Rent,
@@ -176,7 +178,9 @@ use {
     solana_svm_callback::{AccountState, InvokeContextCallback, TransactionProcessingCallback},
     solana_svm_timings::{ExecuteTimingType, ExecuteTimings},
     solana_svm_transaction::svm_message::SVMMessage,
-    solana_syscalls::create_program_runtime_environment,
+    solana_syscalls::{
+        create_program_runtime_environment, create_program_runtime_environment_for_tracing,
+    },
     solana_system_transaction as system_transaction,
     solana_sysvar::{self as sysvar, last_restart_slot::LastRestartSlot},
     solana_sysvar_id::SysvarId,
@@ -223,10 +227,10 @@ use {
     },
     solana_nonce as nonce,
     solana_nonce_account::{SystemAccountKind, get_system_account_kind},
-    solana_program_runtime::sysvar_cache::SysvarCache,
     solana_svm::program_loader::load_program_with_pubkey,
 };
 
+pub mod accounts_diff;
 mod accounts_lt_hash;
 mod address_lookup_table;
 pub mod bank_hash_details;
@@ -234,6 +238,7 @@ pub mod builtins;
 mod check_transactions;
 pub mod entry_bytes_budget;
 mod fee_distribution;
+pub mod lt_hash_bisection;
 mod metrics;
 pub(crate) mod partitioned_epoch_rewards;
 mod recent_blockhashes_account;
@@ -368,6 +373,26 @@ pub struct TransactionSimulationResult {
     pub post_balances: Option<Vec<u64>>,
     pub pre_token_balances: Option<Vec<SvmTokenInfo>>,
     pub post_token_balances: Option<Vec<SvmTokenInfo>>,
+    pub execution_trace: Option<ExecutionTrace>,
+}
+
+/// Options for `Bank::simulate_transaction_with_config`
+#[derive(Debug, Default, Clone)]
+pub struct SimulationConfig {
+    pub enable_cpi_recording: bool,
+    /// Record an execution trace of every program invocation, including the executed sBPF
+    /// instructions
+    pub enable_execution_tracing: bool,
+    pub state_overrides: SimulationStateOverrides,
+}
+
+/// State to substitute for the bank's own when simulating a transaction
+#[derive(Debug, Default, Clone)]
+pub struct SimulationStateOverrides {
+    /// Accounts to load in place of the ones stored in the bank
+    pub accounts: HashMap<Pubkey, AccountSharedData>,
+    /// Clock to expose to programs, both as the sysvar account and through the sysvar cache
+    pub clock: Option<sysvar::clock::Clock>,
 }
 
 impl TransactionSimulationResult {
@@ -385,6 +410,7 @@ impl TransactionSimulationResult {
             result: Err(err),
             return_data: None,
             units_consumed: 0,
+            execution_trace: None,
         }
     }
 }
@@ -1648,7 +1674,8 @@ impl Bank {
         } else if slot_index.saturating_add(slots_in_recompilation_phase) >= slots_in_epoch {
             // Anticipate the upcoming program runtime environment for the next epoch,
             // so we can try to recompile loaded programs before the feature transition hits.
-            let new_environment = self.create_program_runtime_environment(&upcoming_feature_set);
+            let new_environment =
+                self.create_program_runtime_environment(&upcoming_feature_set, false);
             let mut upcoming_environment = self
                 .transaction_processor
                 .program_runtime_environment
@@ -1897,7 +1924,7 @@ impl Bank {
         );
 
         let program_runtime_environment =
-            self.create_program_runtime_environment(&self.feature_set);
+            self.create_program_runtime_environment(&self.feature_set, false);
         self.transaction_processor
             .set_program_runtime_environment(program_runtime_environment);
     }
@@ -3824,9 +3851,118 @@ impl Bank {
         transaction: &impl TransactionWithMeta,
         enable_cpi_recording: bool,
     ) -> TransactionSimulationResult {
-        let account_keys = transaction.account_keys();
-        let number_of_accounts = account_keys.len();
-        let account_overrides = self.get_account_overrides_for_simulation(&account_keys);
+        let account_overrides =
+            self.get_account_overrides_for_simulation(&transaction.account_keys());
+        self.simulate_transaction_with_overrides(
+            transaction,
+            enable_cpi_recording,
+            &account_overrides,
+            None,
+            false,
+        )
+    }
+
+    /// Run a transaction against a frozen bank without committing the results, with the options
+    /// `simulate_transaction` doesn't offer
+    pub fn simulate_transaction_with_config(
+        &self,
+        transaction: &impl TransactionWithMeta,
+        config: &SimulationConfig,
+    ) -> TransactionSimulationResult {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+        let SimulationConfig {
+            enable_cpi_recording,
+            enable_execution_tracing,
+            state_overrides,
+        } = config;
+
+        let mut account_overrides =
+            self.get_account_overrides_for_simulation(&transaction.account_keys());
+        for (pubkey, account) in &state_overrides.accounts {
+            account_overrides.set_account(pubkey, Some(account.clone()));
+        }
+
+        let sysvar_cache = state_overrides.clock.as_ref().map(|clock| {
+            let clock_id = sysvar::clock::id();
+            let clock_account = create_account(
+                clock,
+                self.inherit_specially_retained_account_fields(&self.get_account(&clock_id)),
+            );
+            account_overrides.set_account(&clock_id, Some(clock_account));
+
+            let mut sysvar_cache = self.transaction_processor.sysvar_cache().clone();
+            sysvar_cache.set_clock(clock);
+            sysvar_cache
+        });
+
+        self.simulate_transaction_with_overrides(
+            transaction,
+            *enable_cpi_recording,
+            &account_overrides,
+            sysvar_cache.as_ref(),
+            *enable_execution_tracing,
+        )
+    }
+
+    /// Run transactions in order against a frozen bank without committing the results, each one
+    /// seeing the accounts written by the ones before it
+    ///
+    /// Stops at the first transaction that fails, whose result is the last one returned. Only
+    /// account state is carried over: programs deployed and address lookup tables extended by
+    /// earlier transactions are not visible to later ones.
+    pub fn simulate_transaction_sequence(
+        &self,
+        transactions: &[impl TransactionWithMeta],
+        enable_cpi_recording: bool,
+    ) -> Vec<TransactionSimulationResult> {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+
+        let mut written_accounts = HashMap::<Pubkey, AccountSharedData>::new();
+        let mut results = Vec::with_capacity(transactions.len());
+        for transaction in transactions {
+            let account_keys = transaction.account_keys();
+            let mut account_overrides = self.get_account_overrides_for_simulation(&account_keys);
+            for pubkey in account_keys.iter() {
+                if let Some(account) = written_accounts.get(pubkey) {
+                    account_overrides.set_account(pubkey, Some(account.clone()));
+                }
+            }
+
+            let result = self.simulate_transaction_with_overrides(
+                transaction,
+                enable_cpi_recording,
+                &account_overrides,
+                None,
+                false,
+            );
+            let failed = result.result.is_err();
+            if !failed {
+                written_accounts.extend(
+                    result
+                        .post_simulation_accounts
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, _)| transaction.is_writable(*index))
+                        .map(|(_, (pubkey, account))| (*pubkey, account.clone())),
+                );
+            }
+            results.push(result);
+            if failed {
+                break;
+            }
+        }
+        results
+    }
+
+    fn simulate_transaction_with_overrides(
+        &self,
+        transaction: &impl TransactionWithMeta,
+        enable_cpi_recording: bool,
+        account_overrides: &AccountOverrides,
+        sysvar_cache_override: Option<&SysvarCache>,
+        enable_execution_tracing: bool,
+    ) -> TransactionSimulationResult {
+        let number_of_accounts = transaction.account_keys().len();
         let batch = self.prepare_unlocked_batch_from_single_tx(transaction);
         let mut timings = ExecuteTimings::default();
 
@@ -3844,7 +3980,8 @@ impl Bank {
             &mut timings,
             &mut TransactionErrorMetrics::default(),
             TransactionProcessingConfig {
-                account_overrides: Some(&account_overrides),
+                account_overrides: Some(account_overrides),
+                sysvar_cache_override,
                 check_program_deployment_slot: self.check_program_deployment_slot,
                 log_messages_bytes_limit: None,
                 limit_to_load_programs: true,
@@ -3858,6 +3995,7 @@ impl Bank {
                 all_or_nothing: false,
                 strict_nonce_size_check: true,
                 drop_noop_transactions: true,
+                enable_execution_tracing,
             },
         );
 
@@ -3875,6 +4013,7 @@ impl Bank {
             inner_instructions,
             units_consumed,
             loaded_accounts_data_size,
+            execution_trace,
         ) = match processing_result {
             Ok(processed_tx) => {
                 let executed_units = processed_tx.executed_units();
@@ -3898,6 +4037,7 @@ impl Bank {
                             details.inner_instructions,
                             executed_units,
                             loaded_accounts_data_size,
+                            details.execution_trace,
                         )
                     }
                     ProcessedTransaction::FeesOnly(fees_only_tx) => (
@@ -3909,6 +4049,7 @@ impl Bank {
                         None,
                         executed_units,
                         loaded_accounts_data_size,
+                        None,
                     ),
                     ProcessedTransaction::NoOp(no_op_tx) => (
                         vec![],
@@ -3919,10 +4060,11 @@ impl Bank {
                         None,
                         executed_units,
                         loaded_accounts_data_size,
+                        None,
                     ),
                 }
             }
-            Err(error) => (vec![], Err(error), None, None, None, None, 0, 0),
+            Err(error) => (vec![], Err(error), None, None, None, None, 0, 0, None),
         };
         let logs = logs.unwrap_or_default();
 
@@ -3955,6 +4097,7 @@ impl Bank {
             post_balances,
             pre_token_balances,
             post_token_balances,
+            execution_trace,
         }
     }
 
@@ -4048,9 +4191,15 @@ impl Bank {
             epoch_total_stake: self.get_current_epoch_total_stake(),
             feature_set: self.feature_set.runtime_features(),
             program_runtime_environments: ProgramRuntimeEnvironments::new(
-                self.transaction_processor
-                    .program_runtime_environment
-                    .clone(),
+                if processing_config.enable_execution_tracing {
+                    // Programs are then loaded outside of the global program cache, so they can
+                    // be compiled for an environment with register tracing enabled
+                    self.create_program_runtime_environment(&self.feature_set, true)
+                } else {
+                    self.transaction_processor
+                        .program_runtime_environment
+                        .clone()
+                },
                 self.transaction_processor
                     .program_runtime_environment_for_epoch(effective_epoch_of_deployments),
             ),
@@ -4608,6 +4757,7 @@ impl Bank {
             &mut TransactionErrorMetrics::default(),
             TransactionProcessingConfig {
                 account_overrides: None,
+                sysvar_cache_override: None,
                 check_program_deployment_slot: self.check_program_deployment_slot,
                 log_messages_bytes_limit,
                 limit_to_load_programs: false,
@@ -4616,6 +4766,7 @@ impl Bank {
                 all_or_nothing: false,
                 strict_nonce_size_check: false,
                 drop_noop_transactions: false,
+                enable_execution_tracing: false,
             },
         );
 
@@ -4968,7 +5119,7 @@ impl Bank {
         self.apply_simd_0339_invoke_cost_changes();
 
         let program_runtime_environment =
-            self.create_program_runtime_environment(&self.feature_set);
+            self.create_program_runtime_environment(&self.feature_set, false);
         self.transaction_processor
             .global_program_cache
             .write()
@@ -4988,6 +5139,7 @@ impl Bank {
     fn create_program_runtime_environment(
         &self,
         feature_set: &FeatureSet,
+        execution_tracing: bool,
     ) -> ProgramRuntimeEnvironment {
         let simd_0268_active = feature_set.snapshot().raise_cpi_nesting_limit_to_8;
         let compute_budget = self
@@ -4995,12 +5147,19 @@ impl Bank {
             .as_ref()
             .unwrap_or(&ComputeBudget::new_with_defaults(simd_0268_active))
             .to_budget();
-        create_program_runtime_environment(
-            &feature_set.runtime_features(),
-            &compute_budget,
-            false, /* deployment */
-            false, /* debugging_features */
-        )
+        if execution_tracing {
+            create_program_runtime_environment_for_tracing(
+                &feature_set.runtime_features(),
+                &compute_budget,
+            )
+        } else {
+            create_program_runtime_environment(
+                &feature_set.runtime_features(),
+                &compute_budget,
+                false, /* deployment */
+                false, /* debugging_features */
+            )
+        }
         .unwrap()
     }
 
@@ -0,0 +1,241 @@
+//! Compares the accounts of two banks, e.g. to narrow down a bank hash mismatch
+
+use {
+    super::Bank,
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_accounts_db::{
+        accounts_db::AccountsDb, accounts_scan::ScanResult, is_loadable::IsLoadable as _,
+    },
+    solana_lattice_hash::lt_hash::Checksum,
+    solana_pubkey::Pubkey,
+    std::collections::HashMap,
+};
+
+/// The accounts that differ between a "left" and a "right" bank
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AccountsDiff {
+    /// Accounts that only exist in the right bank, sorted by pubkey
+    pub added: Vec<(Pubkey, AccountSharedData)>,
+    /// Accounts that only exist in the left bank, sorted by pubkey
+    pub removed: Vec<(Pubkey, AccountSharedData)>,
+    /// Accounts that exist in both banks with different state, sorted by pubkey
+    pub changed: Vec<ChangedAccount>,
+}
+
+impl AccountsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// An account that exists in both banks with different state
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChangedAccount {
+    pub pubkey: Pubkey,
+    pub left: AccountSharedData,
+    pub right: AccountSharedData,
+}
+
+/// The account fields that go into the account's lattice hash
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AccountField {
+    Lamports,
+    Owner,
+    Executable,
+    Data,
+}
+
+impl AccountField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lamports => "lamports",
+            Self::Owner => "owner",
+            Self::Executable => "executable",
+            Self::Data => "data",
+        }
+    }
+}
+
+impl ChangedAccount {
+    /// Returns the fields that differ between the left and right versions of the account
+    pub fn changed_fields(&self) -> Vec<AccountField> {
+        let mut fields = Vec::new();
+        if self.left.lamports() != self.right.lamports() {
+            fields.push(AccountField::Lamports);
+        }
+        if self.left.owner() != self.right.owner() {
+            fields.push(AccountField::Owner);
+        }
+        if self.left.executable() != self.right.executable() {
+            fields.push(AccountField::Executable);
+        }
+        if self.left.data() != self.right.data() {
+            fields.push(AccountField::Data);
+        }
+        fields
+    }
+}
+
+impl Bank {
+    /// Returns the checksum of the accounts lt hash
+    ///
+    /// The value is only meaningful after freezing.
+    pub fn accounts_lt_hash_checksum(&self) -> Checksum {
+        self.accounts_lt_hash.lock().unwrap().0.checksum()
+    }
+
+    /// Compares the accounts of `self` (left) with the accounts of `other` (right)
+    ///
+    /// If both banks are frozen and their accounts lt hashes match, the accounts are known to be
+    /// identical and no scan is done. Otherwise, both banks are scanned once, comparing the
+    /// lattice hash of each account, and only the accounts that differ are loaded again.
+    /// Zero-lamport accounts are treated as non-existent.
+    pub fn diff_accounts(&self, other: &Bank) -> ScanResult<AccountsDiff> {
+        if self.is_frozen()
+            && other.is_frozen()
+            && self.accounts_lt_hash_checksum() == other.accounts_lt_hash_checksum()
+        {
+            return Ok(AccountsDiff::default());
+        }
+
+        let mut left_checksums = HashMap::new();
+        self.scan_all_accounts(|item| {
+            if let Some((pubkey, account, _slot)) =
+                item.filter(|(_, account, _)| account.is_loadable())
+            {
+                left_checksums.insert(
+                    *pubkey,
+                    AccountsDb::lt_hash_account(&account, pubkey).0.checksum(),
+                );
+            }
+        })?;
+
+        let mut diff = AccountsDiff::default();
+        let mut changed = Vec::new();
+        other.scan_all_accounts(|item| {
+            let Some((pubkey, account, _slot)) =
+                item.filter(|(_, account, _)| account.is_loadable())
+            else {
+                return;
+            };
+            match left_checksums.remove(pubkey) {
+                None => diff.added.push((*pubkey, account)),
+                Some(checksum) => {
+                    if checksum != AccountsDb::lt_hash_account(&account, pubkey).0.checksum() {
+                        changed.push((*pubkey, account));
+                    }
+                }
+            }
+        })?;
+
+        diff.changed = changed
+            .into_iter()
+            .filter_map(|(pubkey, right)| {
+                self.get_account_with_fixed_root(&pubkey)
+                    .map(|left| ChangedAccount {
+                        pubkey,
+                        left,
+                        right,
+                    })
+            })
+            .collect();
+        diff.removed = left_checksums
+            .into_keys()
+            .filter_map(|pubkey| {
+                self.get_account_with_fixed_root(&pubkey)
+                    .map(|account| (pubkey, account))
+            })
+            .collect();
+
+        diff.added.sort_unstable_by_key(|(pubkey, _)| *pubkey);
+        diff.removed.sort_unstable_by_key(|(pubkey, _)| *pubkey);
+        diff.changed.sort_unstable_by_key(|account| account.pubkey);
+        Ok(diff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::genesis_utils::create_genesis_config,
+        solana_account::{Account, WritableAccount},
+    };
+
+    fn new_account(lamports: u64, data: &[u8], owner: Pubkey) -> AccountSharedData {
+        AccountSharedData::from(Account {
+            lamports,
+            data: data.to_vec(),
+            owner,
+            executable: false,
+            rent_epoch: u64::MAX,
+        })
+    }
+
+    #[test]
+    fn test_diff_accounts() {
+        let genesis_config = create_genesis_config(1_000_000).genesis_config;
+        let left = Bank::new_for_tests(&genesis_config);
+        let right = Bank::new_for_tests(&genesis_config);
+        let owner = Pubkey::new_unique();
+
+        let unchanged = Pubkey::new_unique();
+        let account = new_account(10, &[1, 2, 3], owner);
+        left.store_account(&unchanged, &account);
+        right.store_account(&unchanged, &account);
+
+        let removed = Pubkey::new_unique();
+        left.store_account(&removed, &new_account(20, &[], owner));
+
+        let added = Pubkey::new_unique();
+        right.store_account(&added, &new_account(30, &[4], owner));
+
+        let changed = Pubkey::new_unique();
+        let left_account = new_account(40, &[5, 6], owner);
+        let mut right_account = left_account.clone();
+        right_account.set_lamports(41);
+        right_account.data_as_mut_slice()[0] = 7;
+        left.store_account(&changed, &left_account);
+        right.store_account(&changed, &right_account);
+
+        // Zero-lamport accounts don't exist as far as the diff is concerned
+        left.store_account(&Pubkey::new_unique(), &new_account(0, &[], owner));
+
+        let diff = left.diff_accounts(&right).unwrap();
+        assert_eq!(diff.added, vec![(added, new_account(30, &[4], owner))]);
+        assert_eq!(diff.removed, vec![(removed, new_account(20, &[], owner))]);
+        assert_eq!(
+            diff.changed,
+            vec![ChangedAccount {
+                pubkey: changed,
+                left: left_account,
+                right: right_account,
+            }]
+        );
+        assert_eq!(
+            diff.changed[0].changed_fields(),
+            vec![AccountField::Lamports, AccountField::Data]
+        );
+
+        let diff = right.diff_accounts(&left).unwrap();
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].0, removed);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].0, added);
+    }
+
+    #[test]
+    fn test_diff_accounts_identical() {
+        let genesis_config = create_genesis_config(1_000_000).genesis_config;
+        let left = Bank::new_for_tests(&genesis_config);
+        let right = Bank::new_for_tests(&genesis_config);
+        left.freeze();
+        right.freeze();
+
+        assert_eq!(
+            left.accounts_lt_hash_checksum(),
+            right.accounts_lt_hash_checksum()
+        );
+        assert!(left.diff_accounts(&right).unwrap().is_empty());
+    }
+}
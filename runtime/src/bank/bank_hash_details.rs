@@ -134,13 +134,7 @@ impl SlotDetails {
                 parent_bank_hash: bank.parent_hash().to_string(),
                 signature_count: bank.signature_count(),
                 last_blockhash: bank.last_blockhash().to_string(),
-                accounts_lt_hash_checksum: bank
-                    .accounts_lt_hash
-                    .lock()
-                    .unwrap()
-                    .0
-                    .checksum()
-                    .to_string(),
+                accounts_lt_hash_checksum: bank.accounts_lt_hash_checksum().to_string(),
                 accounts: AccountsDetails { accounts },
             })
         } else {
@@ -0,0 +1,771 @@
+//! Narrows down which accounts diverged between two account sets by bisecting over lattice hashes
+//!
+//! Both sides split the pubkey space into bins with `PubkeyBinCalculator` and report the
+//! checksum of the accounts lt hash of each requested bin. Bins whose checksums differ are split
+//! further, until few enough accounts remain that per-account checksums can be compared.
+//!
+//! Requests and responses are serializable, so the two sides do not need to live in the same
+//! process; e.g. two nodes that disagree on a bank hash can exchange them as files. See
+//! `bisect_accounts_with_remote()` for the side driving such a bisection, and
+//! `respond_to_remote()` for the remote side.
+
+use {
+    super::{
+        Bank,
+        accounts_diff::{AccountsDiff, ChangedAccount},
+        bank_hash_details::AccountsDetails,
+    },
+    serde::{Deserialize, Serialize},
+    solana_account::AccountSharedData,
+    solana_accounts_db::{
+        accounts_db::AccountsDb,
+        accounts_scan::{ScanError, ScanResult},
+        is_loadable::IsLoadable as _,
+        pubkey_bins::{PubkeyBinCalculator, PubkeyBinCalculatorBuilder},
+    },
+    solana_lattice_hash::lt_hash::LtHash,
+    solana_pubkey::Pubkey,
+    std::{
+        collections::{HashMap, HashSet},
+        num::NonZeroUsize,
+    },
+    thiserror::Error,
+};
+
+/// The maximum number of bins supported by `PubkeyBinCalculator`
+pub const MAX_BISECTION_BINS: usize = 1 << 25;
+/// The maximum bit offset supported by `PubkeyBinCalculator`
+pub const MAX_BISECTION_OFFSET: usize = 231;
+
+#[derive(Debug, Error)]
+pub enum LtHashBisectionError {
+    #[error("failed to scan accounts: {0}")]
+    Scan(#[from] ScanError),
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("failed to exchange messages with the remote side: {0}")]
+    Remote(String),
+    #[error("unexpected response from the remote side: {0}")]
+    UnexpectedResponse(String),
+}
+
+/// A set of accounts that can be bisected
+pub trait LtHashBisectionSource {
+    /// Calls `func` for every loadable account
+    fn scan_accounts(&self, func: &mut dyn FnMut(&Pubkey, &AccountSharedData)) -> ScanResult<()>;
+
+    /// Returns the account for `pubkey`, if it is loadable
+    fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData>;
+}
+
+impl LtHashBisectionSource for Bank {
+    fn scan_accounts(&self, func: &mut dyn FnMut(&Pubkey, &AccountSharedData)) -> ScanResult<()> {
+        self.scan_all_accounts(|item| {
+            if let Some((pubkey, account, _slot)) =
+                item.filter(|(_, account, _)| account.is_loadable())
+            {
+                func(pubkey, &account);
+            }
+        })
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.get_account_with_fixed_root(pubkey)
+    }
+}
+
+/// Bisects the accounts recorded in a bank hash details file
+impl LtHashBisectionSource for AccountsDetails {
+    fn scan_accounts(&self, func: &mut dyn FnMut(&Pubkey, &AccountSharedData)) -> ScanResult<()> {
+        self.accounts
+            .iter()
+            .filter(|(_, account)| account.is_loadable())
+            .for_each(|(pubkey, account)| func(pubkey, account));
+        Ok(())
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.accounts
+            .iter()
+            .find(|(key, account)| key == pubkey && account.is_loadable())
+            .map(|(_, account)| account.clone())
+    }
+}
+
+/// Asks one side for the checksums of some bins
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartitionRequest {
+    /// Bit offset into the pubkey used to compute bins
+    pub offset: usize,
+    /// Total number of bins the pubkey space is split into
+    pub num_bins: usize,
+    /// The bins to report
+    pub bins: Vec<usize>,
+    /// Also report the checksum of every account in the requested bins
+    pub include_accounts: bool,
+}
+
+impl PartitionRequest {
+    /// Returns the bin calculator for the request, if its `num_bins` and `offset` are supported
+    fn bin_calculator(&self) -> Result<PubkeyBinCalculator, LtHashBisectionError> {
+        let num_bins = NonZeroUsize::new(self.num_bins)
+            .filter(|num_bins| num_bins.is_power_of_two() && num_bins.get() <= MAX_BISECTION_BINS)
+            .ok_or_else(|| {
+                LtHashBisectionError::InvalidRequest(format!(
+                    "num_bins must be a power of two <= {MAX_BISECTION_BINS} (actual: {})",
+                    self.num_bins,
+                ))
+            })?;
+        if self.offset > MAX_BISECTION_OFFSET {
+            return Err(LtHashBisectionError::InvalidRequest(format!(
+                "offset must be <= {MAX_BISECTION_OFFSET} (actual: {})",
+                self.offset,
+            )));
+        }
+        Ok(PubkeyBinCalculatorBuilder::with_bins_and_offset(
+            num_bins,
+            self.offset,
+        ))
+    }
+}
+
+/// One side's answer to a `PartitionRequest`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartitionResponse {
+    pub partitions: Vec<PartitionChecksum>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartitionChecksum {
+    pub bin: usize,
+    pub num_accounts: usize,
+    /// Checksum of the lt hash of all accounts in the bin
+    pub checksum: String,
+    /// Pubkey and lt hash checksum of each account in the bin, sorted by pubkey
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<(String, String)>,
+}
+
+/// Computes the answer to `request` from `source`
+///
+/// Requests may come from another process, so their `num_bins` and `offset` are checked to be
+/// supported by `PubkeyBinCalculator`.
+pub fn compute_partitions(
+    source: &(impl LtHashBisectionSource + ?Sized),
+    request: &PartitionRequest,
+) -> Result<PartitionResponse, LtHashBisectionError> {
+    let bin_calculator = request.bin_calculator()?;
+    let requested: HashSet<_> = request.bins.iter().copied().collect();
+
+    struct Partition {
+        lt_hash: LtHash,
+        num_accounts: usize,
+        accounts: Vec<(Pubkey, LtHash)>,
+    }
+    let mut partitions = HashMap::<usize, Partition>::new();
+    source.scan_accounts(&mut |pubkey, account| {
+        let bin = bin_calculator.bin_from_pubkey(pubkey);
+        if !requested.contains(&bin) {
+            return;
+        }
+        let account_lt_hash = AccountsDb::lt_hash_account(account, pubkey).0;
+        let partition = partitions.entry(bin).or_insert_with(|| Partition {
+            lt_hash: LtHash::identity(),
+            num_accounts: 0,
+            accounts: Vec::new(),
+        });
+        partition.lt_hash.mix_in(&account_lt_hash);
+        partition.num_accounts += 1;
+        if request.include_accounts {
+            partition.accounts.push((*pubkey, account_lt_hash));
+        }
+    })?;
+
+    let partitions = request
+        .bins
+        .iter()
+        .map(|bin| match partitions.remove(bin) {
+            Some(mut partition) => {
+                partition
+                    .accounts
+                    .sort_unstable_by_key(|(pubkey, _)| *pubkey);
+                PartitionChecksum {
+                    bin: *bin,
+                    num_accounts: partition.num_accounts,
+                    checksum: partition.lt_hash.checksum().to_string(),
+                    accounts: partition
+                        .accounts
+                        .iter()
+                        .map(|(pubkey, lt_hash)| {
+                            (pubkey.to_string(), lt_hash.checksum().to_string())
+                        })
+                        .collect(),
+                }
+            }
+            None => PartitionChecksum {
+                bin: *bin,
+                num_accounts: 0,
+                checksum: LtHash::identity().checksum().to_string(),
+                accounts: Vec::new(),
+            },
+        })
+        .collect();
+    Ok(PartitionResponse { partitions })
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LtHashBisectionConfig {
+    /// Bit offset into the pubkey used to compute bins; both sides must use the same value
+    pub offset: usize,
+    /// Number of child bins each divergent bin is split into per round; must be a power of two
+    /// greater than one
+    pub fanout: NonZeroUsize,
+    /// Compare per-account checksums once the divergent bins hold at most this many accounts
+    pub max_leaf_accounts: usize,
+}
+
+impl Default for LtHashBisectionConfig {
+    fn default() -> Self {
+        Self {
+            // Skip the beginning and end of the pubkey, which are the most common to grind
+            offset: 128,
+            fanout: NonZeroUsize::new(16).unwrap(),
+            max_leaf_accounts: 256,
+        }
+    }
+}
+
+/// Drives a bisection between a "left" and a "right" side
+///
+/// Use `next_request()` to get the request both sides must answer, and pass the answers to
+/// `process()`, until `next_request()` returns None.
+#[derive(Debug)]
+pub struct LtHashBisector {
+    config: LtHashBisectionConfig,
+    num_bins: usize,
+    divergent_bins: Vec<usize>,
+    include_accounts: bool,
+    rounds: Vec<LtHashBisectionRound>,
+    divergent_accounts: Option<Vec<Pubkey>>,
+}
+
+/// Summary of one round of bisection
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LtHashBisectionRound {
+    pub num_bins: usize,
+    pub num_requested_bins: usize,
+    pub num_divergent_bins: usize,
+    /// Number of accounts in the divergent bins, taking the larger of both sides
+    pub num_divergent_bin_accounts: usize,
+}
+
+impl LtHashBisector {
+    /// # Panics
+    ///
+    /// Panics if `config.fanout` is not a power of two greater than one, or `config.offset` is
+    /// too large.
+    pub fn new(config: LtHashBisectionConfig) -> Self {
+        assert!(
+            config.fanout.is_power_of_two() && config.fanout.get() > 1,
+            "fanout must be a power of two greater than one (actual: {})",
+            config.fanout,
+        );
+        assert!(
+            config.offset <= MAX_BISECTION_OFFSET,
+            "offset must be <= {MAX_BISECTION_OFFSET} (actual: {})",
+            config.offset,
+        );
+        Self {
+            config,
+            num_bins: 1,
+            divergent_bins: vec![0],
+            include_accounts: false,
+            rounds: Vec::new(),
+            divergent_accounts: None,
+        }
+    }
+
+    /// Returns the request both sides must answer next, or None once bisection is done
+    pub fn next_request(&self) -> Option<PartitionRequest> {
+        self.divergent_accounts.is_none().then(|| PartitionRequest {
+            offset: self.config.offset,
+            num_bins: self.num_bins,
+            bins: self.divergent_bins.clone(),
+            include_accounts: self.include_accounts,
+        })
+    }
+
+    /// Compares the answers of both sides to the last request and advances the bisection
+    pub fn process(&mut self, left: &PartitionResponse, right: &PartitionResponse) {
+        assert!(
+            self.divergent_accounts.is_none(),
+            "bisection is already done"
+        );
+        let right_partitions: HashMap<_, _> = right
+            .partitions
+            .iter()
+            .map(|partition| (partition.bin, partition))
+            .collect();
+
+        let mut divergent = Vec::new();
+        let mut num_divergent_bin_accounts = 0;
+        for left_partition in &left.partitions {
+            let right_partition = right_partitions.get(&left_partition.bin);
+            if right_partition.map(|partition| &partition.checksum)
+                != Some(&left_partition.checksum)
+            {
+                num_divergent_bin_accounts += left_partition.num_accounts.max(
+                    right_partition
+                        .map(|partition| partition.num_accounts)
+                        .unwrap_or_default(),
+                );
+                divergent.push((left_partition, right_partition.copied()));
+            }
+        }
+        self.rounds.push(LtHashBisectionRound {
+            num_bins: self.num_bins,
+            num_requested_bins: self.divergent_bins.len(),
+            num_divergent_bins: divergent.len(),
+            num_divergent_bin_accounts,
+        });
+
+        if divergent.is_empty() {
+            self.divergent_accounts = Some(Vec::new());
+        } else if self.include_accounts {
+            self.divergent_accounts = Some(Self::find_divergent_accounts(&divergent));
+        } else {
+            let next_num_bins = self.num_bins.saturating_mul(self.config.fanout.get());
+            if num_divergent_bin_accounts <= self.config.max_leaf_accounts
+                || next_num_bins > MAX_BISECTION_BINS
+            {
+                // Ask for the same bins again, this time with per-account checksums
+                self.include_accounts = true;
+            } else {
+                // Child bins share the low bits of their parent bin
+                let (num_bins, fanout) = (self.num_bins, self.config.fanout.get());
+                self.divergent_bins = divergent
+                    .iter()
+                    .flat_map(|(partition, _)| {
+                        (0..fanout).map(move |i| partition.bin + i * num_bins)
+                    })
+                    .collect();
+                self.num_bins = next_num_bins;
+                return;
+            }
+        }
+        self.divergent_bins = divergent
+            .iter()
+            .map(|(partition, _)| partition.bin)
+            .collect();
+    }
+
+    fn find_divergent_accounts(
+        divergent: &[(&PartitionChecksum, Option<&PartitionChecksum>)],
+    ) -> Vec<Pubkey> {
+        let mut pubkeys = HashSet::new();
+        for (left, right) in divergent {
+            let left_accounts: HashMap<_, _> = left.accounts.iter().cloned().collect();
+            let right_accounts: HashMap<_, _> = right
+                .map(|right| right.accounts.iter().cloned().collect())
+                .unwrap_or_default();
+            for (pubkey, checksum) in &left_accounts {
+                if right_accounts.get(pubkey) != Some(checksum) {
+                    pubkeys.insert(pubkey.clone());
+                }
+            }
+            for pubkey in right_accounts.keys() {
+                if !left_accounts.contains_key(pubkey) {
+                    pubkeys.insert(pubkey.clone());
+                }
+            }
+        }
+        let mut pubkeys: Vec<_> = pubkeys
+            .into_iter()
+            .filter_map(|pubkey| pubkey.parse().ok())
+            .collect();
+        pubkeys.sort_unstable();
+        pubkeys
+    }
+
+    pub fn rounds(&self) -> &[LtHashBisectionRound] {
+        &self.rounds
+    }
+
+    /// Returns the pubkeys of the divergent accounts, sorted, once bisection is done
+    pub fn divergent_accounts(&self) -> Option<&[Pubkey]> {
+        self.divergent_accounts.as_deref()
+    }
+}
+
+/// Bisects `left` and `right` in-process and loads the divergent accounts from both sides
+pub fn bisect_accounts(
+    left: &(impl LtHashBisectionSource + ?Sized),
+    right: &(impl LtHashBisectionSource + ?Sized),
+    config: LtHashBisectionConfig,
+) -> Result<(AccountsDiff, Vec<LtHashBisectionRound>), LtHashBisectionError> {
+    let mut bisector = LtHashBisector::new(config);
+    while let Some(request) = bisector.next_request() {
+        let left_response = compute_partitions(left, &request)?;
+        let right_response = compute_partitions(right, &request)?;
+        bisector.process(&left_response, &right_response);
+    }
+
+    let diff = diff_divergent_accounts(
+        bisector.divergent_accounts().unwrap_or_default(),
+        |pubkey| left.get_account(pubkey),
+        |pubkey| right.get_account(pubkey),
+    );
+    Ok((diff, bisector.rounds))
+}
+
+/// A request sent to the remote side of a bisection
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum RemoteBisectionRequest {
+    /// Report the checksums of some bins
+    Partitions(PartitionRequest),
+    /// Report the loadable accounts among `pubkeys`, once bisection is done
+    Accounts { pubkeys: Vec<String> },
+    /// Bisection is done, no more requests follow
+    Done,
+}
+
+/// The remote side's answer to a `RemoteBisectionRequest`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum RemoteBisectionResponse {
+    Partitions(PartitionResponse),
+    Accounts { accounts: AccountsDetails },
+}
+
+/// The remote side of a bisection, e.g. another process that requests and responses are
+/// exchanged with as files
+pub trait RemoteBisectionPeer {
+    /// Sends `request` to the remote side and returns its response
+    ///
+    /// Returns None for `RemoteBisectionRequest::Done`, which is not answered.
+    fn exchange(
+        &mut self,
+        request: &RemoteBisectionRequest,
+    ) -> Result<Option<RemoteBisectionResponse>, LtHashBisectionError>;
+}
+
+/// Bisects `local` against a `remote` side and loads the divergent accounts from both sides
+///
+/// The local side is the left side of the returned diff.
+pub fn bisect_accounts_with_remote(
+    local: &(impl LtHashBisectionSource + ?Sized),
+    remote: &mut impl RemoteBisectionPeer,
+    config: LtHashBisectionConfig,
+) -> Result<(AccountsDiff, Vec<LtHashBisectionRound>), LtHashBisectionError> {
+    let mut bisector = LtHashBisector::new(config);
+    while let Some(request) = bisector.next_request() {
+        let remote_request = RemoteBisectionRequest::Partitions(request.clone());
+        let remote_response = match remote.exchange(&remote_request)? {
+            Some(RemoteBisectionResponse::Partitions(response)) => response,
+            response => return Err(unexpected_response(&remote_request, response)),
+        };
+        let local_response = compute_partitions(local, &request)?;
+        bisector.process(&local_response, &remote_response);
+    }
+
+    let pubkeys = bisector.divergent_accounts().unwrap_or_default();
+    let remote_request = RemoteBisectionRequest::Accounts {
+        pubkeys: pubkeys.iter().map(Pubkey::to_string).collect(),
+    };
+    let mut remote_accounts: HashMap<_, _> = match remote.exchange(&remote_request)? {
+        Some(RemoteBisectionResponse::Accounts { accounts }) => {
+            accounts.accounts.into_iter().collect()
+        }
+        response => return Err(unexpected_response(&remote_request, response)),
+    };
+    remote.exchange(&RemoteBisectionRequest::Done)?;
+
+    let diff = diff_divergent_accounts(
+        pubkeys,
+        |pubkey| local.get_account(pubkey),
+        |pubkey| remote_accounts.remove(pubkey),
+    );
+    Ok((diff, bisector.rounds))
+}
+
+/// Answers a request from the side driving a bisection, returns None once bisection is done
+pub fn respond_to_remote(
+    source: &(impl LtHashBisectionSource + ?Sized),
+    request: &RemoteBisectionRequest,
+) -> Result<Option<RemoteBisectionResponse>, LtHashBisectionError> {
+    match request {
+        RemoteBisectionRequest::Partitions(request) => compute_partitions(source, request)
+            .map(|response| Some(RemoteBisectionResponse::Partitions(response))),
+        RemoteBisectionRequest::Accounts { pubkeys } => {
+            let mut accounts = Vec::new();
+            for pubkey in pubkeys {
+                let pubkey: Pubkey = pubkey.parse().map_err(|err| {
+                    LtHashBisectionError::InvalidRequest(format!("invalid pubkey {pubkey}: {err}"))
+                })?;
+                if let Some(account) = source.get_account(&pubkey) {
+                    accounts.push((pubkey, account));
+                }
+            }
+            Ok(Some(RemoteBisectionResponse::Accounts {
+                accounts: AccountsDetails { accounts },
+            }))
+        }
+        RemoteBisectionRequest::Done => Ok(None),
+    }
+}
+
+fn unexpected_response(
+    request: &RemoteBisectionRequest,
+    response: Option<RemoteBisectionResponse>,
+) -> LtHashBisectionError {
+    let response = match response {
+        Some(RemoteBisectionResponse::Partitions(_)) => "partitions",
+        Some(RemoteBisectionResponse::Accounts { .. }) => "accounts",
+        None => "none",
+    };
+    let request = match request {
+        RemoteBisectionRequest::Partitions(_) => "partitions",
+        RemoteBisectionRequest::Accounts { .. } => "accounts",
+        RemoteBisectionRequest::Done => "done",
+    };
+    LtHashBisectionError::UnexpectedResponse(format!(
+        "got {response} response to {request} request"
+    ))
+}
+
+/// Sorts the divergent accounts into the added, removed and changed accounts of a diff
+fn diff_divergent_accounts(
+    pubkeys: &[Pubkey],
+    mut get_left_account: impl FnMut(&Pubkey) -> Option<AccountSharedData>,
+    mut get_right_account: impl FnMut(&Pubkey) -> Option<AccountSharedData>,
+) -> AccountsDiff {
+    let mut diff = AccountsDiff::default();
+    for pubkey in pubkeys {
+        match (get_left_account(pubkey), get_right_account(pubkey)) {
+            (Some(left), Some(right)) => diff.changed.push(ChangedAccount {
+                pubkey: *pubkey,
+                left,
+                right,
+            }),
+            (Some(left), None) => diff.removed.push((*pubkey, left)),
+            (None, Some(right)) => diff.added.push((*pubkey, right)),
+            (None, None) => {}
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::genesis_utils::create_genesis_config,
+        solana_account::{ReadableAccount, WritableAccount},
+    };
+
+    fn new_accounts(num_accounts: usize) -> Vec<(Pubkey, AccountSharedData)> {
+        let owner = Pubkey::new_unique();
+        let mut accounts: Vec<_> = (0..num_accounts)
+            .map(|i| {
+                (
+                    Pubkey::new_unique(),
+                    AccountSharedData::new(i as u64 + 1, i % 8, &owner),
+                )
+            })
+            .collect();
+        accounts.sort_unstable_by_key(|(pubkey, _)| *pubkey);
+        accounts
+    }
+
+    #[test]
+    fn test_compute_partitions_mixes_in_children() {
+        let accounts = AccountsDetails {
+            accounts: new_accounts(100),
+        };
+        let request = |num_bins: usize, bins: Vec<usize>| PartitionRequest {
+            offset: 100,
+            num_bins,
+            bins,
+            include_accounts: false,
+        };
+        let parent = compute_partitions(&accounts, &request(4, vec![1])).unwrap();
+        let children = compute_partitions(&accounts, &request(16, vec![1, 5, 9, 13])).unwrap();
+        assert_eq!(
+            parent.partitions[0].num_accounts,
+            children
+                .partitions
+                .iter()
+                .map(|partition| partition.num_accounts)
+                .sum::<usize>(),
+        );
+
+        let all = compute_partitions(&accounts, &request(1, vec![0])).unwrap();
+        assert_eq!(all.partitions[0].num_accounts, 100);
+        let mut lt_hash = LtHash::identity();
+        for (pubkey, account) in &accounts.accounts {
+            lt_hash.mix_in(&AccountsDb::lt_hash_account(account, pubkey).0);
+        }
+        assert_eq!(all.partitions[0].checksum, lt_hash.checksum().to_string());
+    }
+
+    #[test]
+    fn test_compute_partitions_rejects_unsupported_requests() {
+        let accounts = AccountsDetails {
+            accounts: new_accounts(10),
+        };
+        let request = |offset: usize, num_bins: usize| PartitionRequest {
+            offset,
+            num_bins,
+            bins: vec![0],
+            include_accounts: false,
+        };
+        for request in [
+            request(0, 0),
+            request(0, 3),
+            request(0, MAX_BISECTION_BINS * 2),
+            request(MAX_BISECTION_OFFSET + 1, 1),
+        ] {
+            assert!(matches!(
+                compute_partitions(&accounts, &request),
+                Err(LtHashBisectionError::InvalidRequest(_)),
+            ));
+        }
+        assert!(compute_partitions(&accounts, &request(MAX_BISECTION_OFFSET, 1)).is_ok());
+    }
+
+    #[test]
+    fn test_bisect_accounts_details() {
+        let left = AccountsDetails {
+            accounts: new_accounts(10_000),
+        };
+        let mut right = left.clone();
+        let changed = right.accounts[1234].0;
+        right.accounts[1234].1.set_lamports(1_000_000);
+        let removed = right.accounts.remove(5678).0;
+        let added = Pubkey::new_unique();
+        right
+            .accounts
+            .push((added, AccountSharedData::new(1, 0, &Pubkey::new_unique())));
+
+        let config = LtHashBisectionConfig {
+            max_leaf_accounts: 16,
+            ..LtHashBisectionConfig::default()
+        };
+        let (diff, rounds) = bisect_accounts(&left, &right, config).unwrap();
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].0, added);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].0, removed);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].pubkey, changed);
+        assert_eq!(diff.changed[0].right.lamports(), 1_000_000);
+        // Narrowing down must take more than the initial round and the per-account round
+        assert!(rounds.len() > 2);
+        assert!(rounds.last().unwrap().num_divergent_bin_accounts <= 16);
+    }
+
+    /// Answers requests from a remote side, passing everything through JSON like a remote process
+    struct JsonPeer<'a> {
+        source: &'a AccountsDetails,
+        requests: Vec<RemoteBisectionRequest>,
+    }
+
+    impl RemoteBisectionPeer for JsonPeer<'_> {
+        fn exchange(
+            &mut self,
+            request: &RemoteBisectionRequest,
+        ) -> Result<Option<RemoteBisectionResponse>, LtHashBisectionError> {
+            let request: RemoteBisectionRequest =
+                serde_json::from_str(&serde_json::to_string(request).unwrap()).unwrap();
+            let response = respond_to_remote(self.source, &request)?;
+            self.requests.push(request);
+            Ok(response
+                .map(|response| serde_json::to_string(&response).unwrap())
+                .map(|response| serde_json::from_str(&response).unwrap()))
+        }
+    }
+
+    #[test]
+    fn test_bisect_accounts_with_remote() {
+        let local = AccountsDetails {
+            accounts: new_accounts(10_000),
+        };
+        let mut remote_accounts = local.clone();
+        let changed = remote_accounts.accounts[4321].0;
+        remote_accounts.accounts[4321].1.set_data(vec![1, 2, 3]);
+        let removed = remote_accounts.accounts.remove(8765).0;
+        let config = LtHashBisectionConfig {
+            max_leaf_accounts: 16,
+            ..LtHashBisectionConfig::default()
+        };
+
+        let mut remote = JsonPeer {
+            source: &remote_accounts,
+            requests: Vec::new(),
+        };
+        let (diff, rounds) =
+            bisect_accounts_with_remote(&local, &mut remote, config.clone()).unwrap();
+        assert_eq!(
+            (diff.clone(), rounds),
+            bisect_accounts(&local, &remote_accounts, config).unwrap(),
+        );
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].0, removed);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].pubkey, changed);
+        assert_eq!(diff.changed[0].right.data(), &[1, 2, 3]);
+
+        // The last partitions request is followed by a request for the divergent accounts
+        let mut divergent = vec![changed, removed];
+        divergent.sort_unstable();
+        let num_requests = remote.requests.len();
+        assert_eq!(
+            remote.requests[num_requests - 2],
+            RemoteBisectionRequest::Accounts {
+                pubkeys: divergent.iter().map(Pubkey::to_string).collect(),
+            },
+        );
+        assert_eq!(
+            remote.requests[num_requests - 1],
+            RemoteBisectionRequest::Done
+        );
+    }
+
+    #[test]
+    fn test_bisect_identical() {
+        let left = AccountsDetails {
+            accounts: new_accounts(100),
+        };
+        let (diff, rounds) =
+            bisect_accounts(&left, &left.clone(), LtHashBisectionConfig::default()).unwrap();
+        assert!(diff.is_empty());
+        assert_eq!(rounds.len(), 1);
+        assert_eq!(rounds[0].num_divergent_bins, 0);
+    }
+
+    #[test]
+    fn test_bisect_banks_matches_diff() {
+        let genesis_config = create_genesis_config(1_000_000).genesis_config;
+        let left = Bank::new_for_tests(&genesis_config);
+        let right = Bank::new_for_tests(&genesis_config);
+        let owner = Pubkey::new_unique();
+        for i in 0..100 {
+            let account = AccountSharedData::new(i + 1, 0, &owner);
+            let pubkey = Pubkey::new_unique();
+            left.store_account(&pubkey, &account);
+            right.store_account(&pubkey, &account);
+        }
+        right.store_account(&Pubkey::new_unique(), &AccountSharedData::new(7, 3, &owner));
+
+        let (diff, _rounds) =
+            bisect_accounts(&left, &right, LtHashBisectionConfig::default()).unwrap();
+        assert_eq!(diff, left.diff_accounts(&right).unwrap());
+        assert_eq!(diff.added.len(), 1);
+    }
+}
@@ -104,6 +104,7 @@ use {
     solana_program_runtime::{
         declare_process_instruction,
         execution_budget::{self, MAX_COMPUTE_UNIT_LIMIT},
+        execution_trace::InvocationTrace,
         program_cache_entry::{ProgramCacheEntry, ProgramCacheEntryType},
         solana_sbpf::program::BuiltinFunctionDefinition,
     },
@@ -268,6 +269,7 @@ fn new_executed_processing_result(
                 return_data: None,
                 executed_units: 0,
                 accounts_deltas,
+                execution_trace: None,
             },
             programs_modified_by_tx: HashMap::new(),
         },
@@ -11343,6 +11345,18 @@ fn test_is_in_slot_hashes_history() {
     assert!(!new_bank.is_in_slot_hashes_history(&0));
 }
 
+/// Returns a rent-exempt loader v2 program account holding the noop program
+fn noop_program_account() -> AccountSharedData {
+    let program_data = include_bytes!("../../../programs/bpf_loader/test_elfs/out/noop_aligned.so");
+    AccountSharedData::from(Account {
+        lamports: Rent::default().minimum_balance(program_data.len()),
+        data: program_data.to_vec(),
+        owner: bpf_loader::id(),
+        executable: true,
+        rent_epoch: 0,
+    })
+}
+
 #[test]
 fn test_feature_activation_loaded_programs_cache_preparation_phase() {
     agave_logger::setup();
@@ -11358,14 +11372,7 @@ fn test_feature_activation_loaded_programs_cache_preparation_phase() {
 
     // Program Setup
     let program_keypair = Keypair::new();
-    let program_data = include_bytes!("../../../programs/bpf_loader/test_elfs/out/noop_aligned.so");
-    let program_account = AccountSharedData::from(Account {
-        lamports: Rent::default().minimum_balance(program_data.len()).min(1),
-        data: program_data.to_vec(),
-        owner: bpf_loader::id(),
-        executable: true,
-        rent_epoch: 0,
-    });
+    let program_account = noop_program_account();
     root_bank.store_account(&program_keypair.pubkey(), &program_account);
 
     // Compose message using the desired program.
@@ -11467,14 +11474,7 @@ fn test_feature_activation_loaded_programs_epoch_transition() {
 
     // Program Setup
     let program_keypair = Keypair::new();
-    let program_data = include_bytes!("../../../programs/bpf_loader/test_elfs/out/noop_aligned.so");
-    let program_account = AccountSharedData::from(Account {
-        lamports: Rent::default().minimum_balance(program_data.len()).min(1),
-        data: program_data.to_vec(),
-        owner: bpf_loader::id(),
-        executable: true,
-        rent_epoch: 0,
-    });
+    let program_account = noop_program_account();
     root_bank.store_account(&program_keypair.pubkey(), &program_account);
 
     // Compose message using the desired program.
@@ -12007,10 +12007,243 @@ fn test_failed_simulation_load_error() {
             post_balances: Some(vec![mint_balance, 0]),
             pre_token_balances: Some(vec![]),
             post_token_balances: Some(vec![]),
+            execution_trace: None,
         }
     );
 }
 
+/// Test that simulated transaction sequences see the accounts written by earlier transactions
+#[test]
+fn test_simulate_transaction_sequence() {
+    let (genesis_config, mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let (bank, _bank_forks) = bank.wrap_with_bank_forks_for_tests();
+    let blockhash = bank.last_blockhash();
+    let keypair = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    let amount = LAMPORTS_PER_SOL / 2;
+    // `keypair` only has funds to pay for the second transfer after the first one
+    let funding = RuntimeTransaction::from_transaction_for_tests(system_transaction::transfer(
+        &mint_keypair,
+        &keypair.pubkey(),
+        amount,
+        blockhash,
+    ));
+    let spending = RuntimeTransaction::from_transaction_for_tests(system_transaction::transfer(
+        &keypair,
+        &recipient,
+        amount / 2,
+        blockhash,
+    ));
+    let overspending = RuntimeTransaction::from_transaction_for_tests(
+        system_transaction::transfer(&keypair, &recipient, amount, blockhash),
+    );
+    bank.freeze();
+
+    assert_eq!(
+        bank.simulate_transaction(&spending, false).result,
+        Err(TransactionError::AccountNotFound)
+    );
+
+    let results = bank
+        .simulate_transaction_sequence(&[funding.clone(), spending, overspending, funding], false);
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].result, Ok(()));
+    assert_eq!(results[1].result, Ok(()));
+    let fee = results[1].fee.unwrap();
+    assert_eq!(results[1].pre_balances.as_ref().unwrap()[..2], [amount, 0]);
+    assert_eq!(
+        results[1].post_balances.as_ref().unwrap()[..2],
+        [amount - amount / 2 - fee, amount / 2]
+    );
+    assert_eq!(
+        results[2].result,
+        Err(TransactionError::InstructionError(
+            0,
+            SystemError::ResultWithNegativeLamports.into()
+        ))
+    );
+
+    // Nothing was committed
+    assert_eq!(bank.get_balance(&keypair.pubkey()), 0);
+    assert_eq!(bank.get_balance(&recipient), 0);
+}
+
+#[test]
+fn test_simulate_transaction_with_state_overrides() {
+    let (genesis_config, _mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let (bank, _bank_forks) = bank.wrap_with_bank_forks_for_tests();
+    let keypair = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    let amount = LAMPORTS_PER_SOL / 2;
+    let mut instruction = system_instruction::transfer(&keypair.pubkey(), &recipient, amount);
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    let transaction =
+        RuntimeTransaction::from_transaction_for_tests(Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&keypair.pubkey()),
+            &[&keypair],
+            bank.last_blockhash(),
+        ));
+    bank.freeze();
+
+    assert_eq!(
+        bank.simulate_transaction(&transaction, false).result,
+        Err(TransactionError::AccountNotFound)
+    );
+
+    let clock = Clock {
+        slot: bank.slot() + 1_000,
+        unix_timestamp: 1_700_000_000,
+        ..bank.clock()
+    };
+    let config = SimulationConfig {
+        state_overrides: SimulationStateOverrides {
+            accounts: HashMap::from([(
+                keypair.pubkey(),
+                AccountSharedData::new(LAMPORTS_PER_SOL, 0, &system_program::id()),
+            )]),
+            clock: Some(clock.clone()),
+        },
+        ..SimulationConfig::default()
+    };
+    let result = bank.simulate_transaction_with_config(&transaction, &config);
+    assert_eq!(result.result, Ok(()));
+    let fee = result.fee.unwrap();
+    assert_eq!(
+        result.post_balances.as_ref().unwrap()[..2],
+        [LAMPORTS_PER_SOL - amount - fee, amount]
+    );
+    let (_, clock_account) = result
+        .post_simulation_accounts
+        .iter()
+        .find(|(pubkey, _)| *pubkey == sysvar::clock::id())
+        .unwrap();
+    assert_eq!(from_account::<Clock>(clock_account).unwrap(), clock);
+
+    // Nothing was committed
+    assert_eq!(bank.get_balance(&keypair.pubkey()), 0);
+    assert_ne!(bank.clock(), clock);
+}
+
+#[test]
+fn test_simulate_transaction_with_execution_tracing() {
+    let (genesis_config, mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let (bank, _bank_forks) = bank.wrap_with_bank_forks_for_tests();
+    let new_account = Keypair::new();
+    let transaction =
+        RuntimeTransaction::from_transaction_for_tests(Transaction::new_signed_with_payer(
+            &[system_instruction::create_account(
+                &mint_keypair.pubkey(),
+                &new_account.pubkey(),
+                LAMPORTS_PER_SOL / 2,
+                8,
+                &system_program::id(),
+            )],
+            Some(&mint_keypair.pubkey()),
+            &[&mint_keypair, &new_account],
+            bank.last_blockhash(),
+        ));
+    bank.freeze();
+
+    assert_eq!(
+        bank.simulate_transaction(&transaction, false)
+            .execution_trace,
+        None
+    );
+
+    let config = SimulationConfig {
+        enable_execution_tracing: true,
+        ..SimulationConfig::default()
+    };
+    let result = bank.simulate_transaction_with_config(&transaction, &config);
+    assert_eq!(result.result, Ok(()));
+    assert_eq!(
+        result.execution_trace.unwrap().invocations,
+        vec![InvocationTrace {
+            index_in_trace: 0,
+            caller_index_in_trace: None,
+            program_id: system_program::id(),
+            stack_height: 1,
+            compute_units_consumed: result.units_consumed,
+            result: Ok(()),
+            // Only the allocated account's data changed
+            written_accounts: vec![new_account.pubkey()],
+            program_counters: vec![],
+            program_counters_truncated: false,
+        }]
+    );
+}
+
+#[test]
+fn test_simulate_transaction_with_program_account_overrides() {
+    let (genesis_config, mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+    let mut bank = Bank::new_for_tests(&genesis_config);
+    let mut feature_set = FeatureSet::all_enabled();
+    feature_set.deactivate(&feature_set::disable_sbpf_v0_execution::id());
+    bank.feature_set = Arc::new(feature_set);
+    let (root_bank, bank_forks) = bank.wrap_with_bank_forks_for_tests();
+
+    let program_id = Pubkey::new_unique();
+    let program_account = noop_program_account();
+    root_bank.store_account(&program_id, &program_account);
+
+    // Advance the bank so that the program becomes effective.
+    goto_end_of_slot(root_bank.clone());
+    let bank = new_from_parent_with_fork_next_slot(root_bank, bank_forks.as_ref());
+
+    let transaction_with_data = |data: &[u8]| {
+        Transaction::new_signed_with_payer(
+            &[Instruction::new_with_bytes(program_id, data, Vec::new())],
+            Some(&mint_keypair.pubkey()),
+            &[&mint_keypair],
+            bank.last_blockhash(),
+        )
+    };
+
+    // Executing the program puts it into the global program cache.
+    assert_eq!(
+        bank.process_transaction(&transaction_with_data(&[0])),
+        Ok(())
+    );
+    bank.freeze();
+
+    let transaction = RuntimeTransaction::from_transaction_for_tests(transaction_with_data(&[1]));
+    assert_eq!(
+        bank.simulate_transaction(&transaction, false).result,
+        Ok(())
+    );
+
+    // The overridden program, which doesn't verify, is executed instead of the cached one.
+    let mut overridden_program_account = program_account.clone();
+    overridden_program_account.set_data_from_slice(&[0; 64]);
+    let config = SimulationConfig {
+        state_overrides: SimulationStateOverrides {
+            accounts: HashMap::from([(program_id, overridden_program_account)]),
+            clock: None,
+        },
+        ..SimulationConfig::default()
+    };
+    assert_eq!(
+        bank.simulate_transaction_with_config(&transaction, &config)
+            .result,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::UnsupportedProgramId
+        ))
+    );
+
+    // The cached program is left untouched.
+    assert_eq!(
+        bank.simulate_transaction(&transaction, false).result,
+        Ok(())
+    );
+}
+
 #[test]
 fn test_filter_program_errors_and_collect_fee_details() {
     // TX  | PROCESSING RESULT           | COLLECT            | COLLECT
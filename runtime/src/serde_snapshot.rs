@@ -1,10 +1,7 @@
 #[cfg(feature = "frozen-abi")]
 use solana_frozen_abi::stable_abi;
 #[cfg(all(target_os = "linux", target_env = "gnu"))]
-use std::{
-    ffi::{CStr, CString},
-    path::Path,
-};
+use std::ffi::{CStr, CString};
 use {
     crate::{
         bank::{Bank, BankFieldsToDeserialize, BankFieldsToSerialize, BankHashStats, BankRc},
@@ -50,7 +47,7 @@ use {
         borrow::Borrow,
         collections::{HashMap, HashSet},
         io::{self, BufReader, Read, Write},
-        path::PathBuf,
+        path::{Path, PathBuf},
         result::Result,
         sync::{
             Arc,
@@ -435,6 +432,14 @@ impl SnapshotAccountsDbFields {
         }
     }
 
+    /// The slot of the snapshot, the incremental one if there is one
+    fn slot(&self) -> Slot {
+        self.incremental_snapshot_accounts_db_fields
+            .as_ref()
+            .unwrap_or(&self.full_snapshot_accounts_db_fields)
+            .2
+    }
+
     /// Extract final bank hash info from full and incremental accounts db fields.
     ///
     /// If there is no incremental snapshot, this returns the field from the full snapshot.
@@ -662,6 +667,7 @@ where
         runtime_config,
         account_paths,
         storage_and_next_append_vec_id,
+        None, // secondary_indexes_dir
         debug_keys,
         None, // leader_for_tests
         limit_load_slot_count_from_snapshot,
@@ -939,6 +945,7 @@ pub(crate) fn reconstruct_bank_from_fields(
     runtime_config: &RuntimeConfig,
     account_paths: &[PathBuf],
     storage_and_next_append_vec_id: StorageAndNextAccountsFileId,
+    secondary_indexes_dir: Option<&Path>,
     debug_keys: Option<Arc<HashSet<Pubkey>>>,
     leader_for_tests: Option<SlotLeader>,
     limit_load_slot_count_from_snapshot: Option<usize>,
@@ -962,6 +969,7 @@ pub(crate) fn reconstruct_bank_from_fields(
         snapshot_accounts_db_fields,
         account_paths,
         storage_and_next_append_vec_id,
+        secondary_indexes_dir,
         limit_load_slot_count_from_snapshot,
         verify_index,
         accounts_db_config,
@@ -1151,6 +1159,7 @@ fn reconstruct_accountsdb_from_fields(
     snapshot_accounts_db_fields: SnapshotAccountsDbFields,
     account_paths: &[PathBuf],
     storage_and_next_append_vec_id: StorageAndNextAccountsFileId,
+    secondary_indexes_dir: Option<&Path>,
     limit_load_slot_count_from_snapshot: Option<usize>,
     verify_index: bool,
     accounts_db_config: AccountsDbConfig,
@@ -1164,6 +1173,7 @@ fn reconstruct_accountsdb_from_fields(
         exit,
     );
 
+    let snapshot_slot = snapshot_accounts_db_fields.slot();
     let snapshot_bank_hash_info = snapshot_accounts_db_fields.into_bank_hash_info();
 
     // Ensure all account paths exist
@@ -1195,6 +1205,13 @@ fn reconstruct_accountsdb_from_fields(
         .next_id
         .store(next_append_vec_id, Ordering::Release);
 
+    if let Some(secondary_indexes_dir) = secondary_indexes_dir {
+        info!("Loading secondary indexes...");
+        let start = Instant::now();
+        accounts_db.load_secondary_indexes(secondary_indexes_dir, snapshot_slot);
+        info!("Loading secondary indexes... Done in {:?}", start.elapsed());
+    }
+
     info!("Building accounts index...");
     let start = Instant::now();
     let IndexGenerationInfo {
@@ -70,6 +70,7 @@ mod serde_snapshot_tests {
             account_paths,
             storage_and_next_append_vec_id,
             None,
+            None,
             false,
             accounts_db_config,
             None,
@@ -210,6 +210,7 @@ pub fn bank_from_snapshot_archives(
         runtime_config,
         account_paths,
         storage_and_next_append_vec_id,
+        None, // secondary indexes are not archived
         debug_keys,
         leader_for_tests,
         limit_load_slot_count_from_snapshot,
@@ -400,6 +401,9 @@ pub fn bank_from_snapshot_dir(
     };
     let snapshot_bank_fields = SnapshotBankFields::new(bank_fields, None);
     let snapshot_accounts_db_fields = SnapshotAccountsDbFields::new(accounts_db_fields, None);
+    let secondary_indexes_dir = bank_snapshot
+        .snapshot_dir
+        .join(snapshot_paths::SNAPSHOT_SECONDARY_INDEXES_DIR);
     let ((bank, info), measure_rebuild_bank) = measure_time!(
         reconstruct_bank_from_fields(
             snapshot_bank_fields,
@@ -408,6 +412,7 @@ pub fn bank_from_snapshot_dir(
             runtime_config,
             account_paths,
             storage_and_next_append_vec_id,
+            Some(&secondary_indexes_dir),
             debug_keys,
             leader_for_tests,
             limit_load_slot_count_from_snapshot,
@@ -939,6 +944,7 @@ mod tests {
             bank_fields: bank.get_fields_to_serialize(),
             bank_hash_stats: bank.get_bank_hash_stats(),
             status_cache_slot_deltas: bank.status_cache.read().unwrap().root_slot_deltas(),
+            accounts_db: None,
         };
 
         let snapshot_storages = bank.get_snapshot_storages(None);
@@ -3,7 +3,7 @@ use solana_hash::Hash;
 use {
     crate::bank::{Bank, BankFieldsToSerialize, BankHashStats, BankSlotDelta},
     agave_snapshots::{SnapshotArchiveKind, SnapshotKind, snapshot_hash::SnapshotHash},
-    solana_accounts_db::account_storage_entry::AccountStorageEntry,
+    solana_accounts_db::{account_storage_entry::AccountStorageEntry, accounts_db::AccountsDb},
     solana_clock::Slot,
     std::{sync::Arc, time::Instant},
 };
@@ -49,6 +49,9 @@ impl SnapshotPackage {
             bank_fields: bank_fields_to_serialize,
             bank_hash_stats: bank.get_bank_hash_stats(),
             status_cache_slot_deltas,
+            accounts_db: (snapshot_kind.is_full_snapshot()
+                && !bank.rc.accounts.accounts_db.account_indexes.is_empty())
+            .then(|| Arc::clone(&bank.rc.accounts.accounts_db)),
         };
 
         Self {
@@ -71,6 +74,7 @@ impl SnapshotPackage {
             bank_fields: BankFieldsToSerialize::default_for_tests(),
             bank_hash_stats: BankHashStats::default(),
             status_cache_slot_deltas: Vec::default(),
+            accounts_db: None,
         };
 
         Self {
@@ -99,4 +103,7 @@ pub struct BankSnapshotPackage {
     pub bank_fields: BankFieldsToSerialize,
     pub bank_hash_stats: BankHashStats,
     pub status_cache_slot_deltas: Vec<BankSlotDelta>,
+    /// Set for full snapshots if secondary indexes are enabled, so they are saved with the bank
+    /// snapshot
+    pub accounts_db: Option<Arc<AccountsDb>>,
 }
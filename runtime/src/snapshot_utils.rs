@@ -493,6 +493,7 @@ pub fn serialize_snapshot(
         mut bank_fields,
         bank_hash_stats,
         status_cache_slot_deltas,
+        accounts_db,
     } = bank_snapshot_package;
     let status_cache_slot_deltas = status_cache_slot_deltas.as_slice();
     let slot = bank_fields.slot;
@@ -561,6 +562,22 @@ pub fn serialize_snapshot(
                 .map_err(|err| AddBankSnapshotError::WriteSnapshotVersionFile(err, version_path))?
         );
 
+        // Saving the secondary indexes is best effort, without them a restart generates them
+        let (save_secondary_indexes_us, num_saved_secondary_index_keys) = accounts_db
+            .map(|accounts_db| {
+                let secondary_indexes_dir =
+                    bank_snapshot_dir.join(snapshot_paths::SNAPSHOT_SECONDARY_INDEXES_DIR);
+                let (result, save_secondary_indexes_us) =
+                    measure_us!(accounts_db.save_secondary_indexes(&secondary_indexes_dir, slot));
+                let num_saved_keys = result
+                    .inspect_err(|err| {
+                        warn!("Failed to save secondary indexes for slot {slot}: {err}");
+                    })
+                    .ok();
+                (save_secondary_indexes_us, num_saved_keys)
+            })
+            .unzip();
+
         let (flush_storages_us, serialize_obsolete_accounts_us, write_storages_list_us) =
             if should_finalize {
                 let flush_measure = Measure::start("");
@@ -620,6 +637,17 @@ pub fn serialize_snapshot(
             ("bank_serialize_us", bank_serialize.as_us(), i64),
             ("status_cache_serialize_us", status_cache_serialize_us, i64),
             ("write_version_file_us", write_version_file_us, i64),
+            ("save_secondary_indexes_us", save_secondary_indexes_us, Option<i64>),
+            (
+                "save_secondary_indexes_num_keys",
+                num_saved_secondary_index_keys.flatten(),
+                Option<i64>
+            ),
+            (
+                "save_secondary_indexes_failed",
+                num_saved_secondary_index_keys.is_some_and(|num_keys| num_keys.is_none()),
+                bool
+            ),
             ("total_us", measure_everything.as_us(), i64),
         );
 
@@ -1,7 +1,11 @@
 use {
     crate::{
-        ArchiveFormat, Result, SnapshotArchiveKind, error::ArchiveSnapshotPackageError,
-        multiframe::MultiFrameZstdWriter, paths, snapshot_archive_info::SnapshotArchiveInfo,
+        ArchiveFormat, Result, SnapshotArchiveKind,
+        error::ArchiveSnapshotPackageError,
+        multiframe::MultiFrameZstdWriter,
+        paths,
+        seekable_archive::{self, PositionTrackingWriter, SeekableArchiveToc},
+        snapshot_archive_info::SnapshotArchiveInfo,
         snapshot_hash::SnapshotHash,
     },
     agave_fs::{
@@ -40,6 +44,10 @@ const STORAGE_FILE_OPEN_CHUNK_SIZE: usize = 25
 // while giving parallel decompressors enough chunk size.
 const ZSTD_FRAME_SIZE: u32 = 32 * 1024 * 1024;
 
+// Uncompressed bytes per zstd frame of seekable archives. Smaller frames bound how much needs to
+// be decompressed to extract a single storage file, at a small cost in compression ratio.
+const SEEKABLE_ZSTD_FRAME_SIZE: u32 = 8 * 1024 * 1024;
+
 /// Archives a snapshot into `archive_path`
 pub fn archive_snapshot(
     snapshot_archive_kind: SnapshotArchiveKind,
@@ -112,8 +120,11 @@ pub fn archive_snapshot(
         let archive_writer = large_file_buf_writer(&staging_archive_path, io_setup)
             .map_err(|err| E::CreateArchiveFile(err, staging_archive_path.clone()))?;
 
-        let do_archive_files = |encoder: &mut dyn Write| -> std::result::Result<(), E> {
-            let mut archive = tar::Builder::new(encoder);
+        // Returns the table of contents of the archived files, which is only persisted for
+        // seekable archives
+        let do_archive_files = |encoder: &mut dyn Write| -> std::result::Result<_, E> {
+            let mut archive = tar::Builder::new(PositionTrackingWriter::new(encoder));
+            let mut toc = SeekableArchiveToc::default();
             // Disable sparse file handling.  This seems to be the root cause of an issue when
             // upgrading v2.0 to v2.1, and the tar crate from 0.4.41 to 0.4.42.
             // Since the tarball will still go through compression (zstd/etc) afterwards, disabling
@@ -131,6 +142,7 @@ pub fn archive_snapshot(
             archive
                 .append_dir_all(paths::BANK_SNAPSHOTS_DIR, &staging_snapshots_dir)
                 .map_err(E::ArchiveSnapshotsDir)?;
+            toc.set_meta_len(archive.get_ref().position());
 
             let storages_orderer = AccountStoragesOrderer::with_small_to_large_ratio(
                 snapshot_storages,
@@ -174,7 +186,12 @@ pub fn archive_snapshot(
 
                 // Queue the whole chunk for read-ahead before consuming any of
                 // it, so the io_uring pipeline can saturate across files.
-                for (storage, file) in &chunk {
+                // Compressed storages are archived from their AppendVec
+                // representation instead of read from their file.
+                for (storage, file) in chunk
+                    .iter()
+                    .filter(|(storage, _)| !storage.accounts.is_compressed())
+                {
                     chunk_reader
                         .add_file_to_prefetch(file.as_ref(), storage.accounts.len() as FileSize)
                         .map_err(E::StorageFileBufReaderError)?;
@@ -184,11 +201,13 @@ pub fn archive_snapshot(
                     let path_in_archive = Path::new(ACCOUNTS_DIR)
                         .join(AccountsFile::file_name(storage.slot(), storage.id()));
 
-                    chunk_reader
-                        .set_file(file.as_ref(), storage.accounts.len() as FileSize)
-                        .map_err(|err| {
-                            E::AccountStorageReaderError(err, storage.path().to_path_buf())
-                        })?;
+                    if !storage.accounts.is_compressed() {
+                        chunk_reader
+                            .set_file(file.as_ref(), storage.accounts.len() as FileSize)
+                            .map_err(|err| {
+                                E::AccountStorageReaderError(err, storage.path().to_path_buf())
+                            })?;
+                    }
                     let reader = AccountStorageReader::new(
                         storage,
                         Some(snapshot_slot),
@@ -199,14 +218,22 @@ pub fn archive_snapshot(
                         E::AccountStorageReaderError(err, storage.path().to_path_buf())
                     })?;
                     let mut header = tar::Header::new_gnu();
-                    header.set_path(path_in_archive).map_err(|err| {
+                    header.set_path(&path_in_archive).map_err(|err| {
                         E::ArchiveAccountStorageFile(err, storage.path().to_path_buf())
                     })?;
-                    header.set_size(reader.len() as u64);
+                    let data_size = reader.len() as u64;
+                    header.set_size(data_size);
                     header.set_cksum();
+                    let header_offset = archive.get_ref().position();
                     archive.append(&header, reader).map_err(|err| {
                         E::ArchiveAccountStorageFile(err, storage.path().to_path_buf())
                     })?;
+                    toc.push_entry(
+                        path_in_archive.to_string_lossy(),
+                        header_offset,
+                        data_size,
+                        archive.get_ref().position(),
+                    );
                 }
 
                 buf_reader = chunk_reader
@@ -215,7 +242,7 @@ pub fn archive_snapshot(
             }
 
             archive.into_inner().map_err(E::FinishArchive)?;
-            Ok(())
+            Ok(toc)
         };
 
         match archive_format {
@@ -230,6 +257,20 @@ pub fn archive_snapshot(
                 let mut writer = encoder.finish().map_err(E::FinishEncoder)?;
                 writer.flush().map_err(E::FinishEncoder)?;
             }
+            ArchiveFormat::TarZstdSeekable { config } => {
+                let mut encoder = MultiFrameZstdWriter::new(
+                    archive_writer,
+                    config.compression_level,
+                    SEEKABLE_ZSTD_FRAME_SIZE,
+                )
+                .map_err(E::CreateEncoder)?;
+                let toc = do_archive_files(&mut encoder)?;
+                let (mut writer, frames) =
+                    encoder.finish_with_frames().map_err(E::FinishEncoder)?;
+                seekable_archive::write_toc(&mut writer, &frames, &toc)
+                    .map_err(E::WriteSeekableToc)?;
+                writer.flush().map_err(E::FinishEncoder)?;
+            }
             ArchiveFormat::TarLz4 => {
                 let mut encoder = lz4::EncoderBuilder::new()
                     .level(1)
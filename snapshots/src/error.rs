@@ -263,6 +263,9 @@ pub enum ArchiveSnapshotPackageError {
     #[error("failed to encode archive: {0}")]
     FinishEncoder(#[source] io::Error),
 
+    #[error("failed to write seekable archive table of contents: {0}")]
+    WriteSeekableToc(#[source] io::Error),
+
     #[error("failed to query archive metadata '{1}': {0}")]
     QueryArchiveMetadata(#[source] io::Error, PathBuf),
 
@@ -1,4 +1,5 @@
 use {
+    crate::seekable_archive::SeekableArchive,
     agave_fs::file_io::{self, FileCreator},
     log::*,
     rand::{Rng, rng},
@@ -10,7 +11,10 @@ use {
             Component::{self, CurDir, Normal},
             Path, PathBuf,
         },
-        sync::Arc,
+        sync::{
+            Arc,
+            atomic::{AtomicU64, Ordering},
+        },
     },
     tar::{
         Archive,
@@ -68,6 +72,68 @@ fn checked_total_count_increment(total_count: u64, limit_count: u64) -> Result<u
     Ok(total_count)
 }
 
+/// Running totals of the entries unpacked from an archive, checked against the archive's limits
+///
+/// Partitions of a seekable archive are unpacked in parallel, so they share one instance and the
+/// limits apply to the archive as a whole rather than to each partition.
+#[derive(Debug)]
+pub(super) struct UnpackTotals {
+    apparent_limit_size: u64,
+    actual_limit_size: u64,
+    limit_count: u64,
+    apparent_size: AtomicU64,
+    actual_size: AtomicU64,
+    count: AtomicU64,
+}
+
+impl UnpackTotals {
+    fn new(apparent_limit_size: u64, actual_limit_size: u64, limit_count: u64) -> Self {
+        Self {
+            apparent_limit_size,
+            actual_limit_size,
+            limit_count,
+            apparent_size: AtomicU64::new(0),
+            actual_size: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns totals checked against the limits of snapshot archives
+    pub(super) fn new_for_snapshot() -> Self {
+        Self::new(
+            MAX_SNAPSHOT_ARCHIVE_UNPACKED_APPARENT_SIZE,
+            MAX_SNAPSHOT_ARCHIVE_UNPACKED_ACTUAL_SIZE,
+            MAX_SNAPSHOT_ARCHIVE_UNPACKED_COUNT,
+        )
+    }
+
+    /// Adds an entry of the given sizes, failing if that exceeds any of the limits
+    fn add_entry(&self, apparent_size: u64, actual_size: u64) -> Result<()> {
+        checked_total_size_sum(
+            fetch_saturating_add(&self.apparent_size, apparent_size),
+            apparent_size,
+            self.apparent_limit_size,
+        )?;
+        checked_total_size_sum(
+            fetch_saturating_add(&self.actual_size, actual_size),
+            actual_size,
+            self.actual_limit_size,
+        )?;
+        checked_total_count_increment(fetch_saturating_add(&self.count, 1), self.limit_count)?;
+        Ok(())
+    }
+}
+
+/// Adds `value` to `total` without wrapping around, returning the previous total
+fn fetch_saturating_add(total: &AtomicU64, value: u64) -> u64 {
+    // The update closure never fails, so both variants hold the previous total
+    total
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |total| {
+            Some(total.saturating_add(value))
+        })
+        .unwrap_or_else(|total| total)
+}
+
 fn check_unpack_result(unpack_result: Result<()>, path: String) -> Result<()> {
     if let Err(err) = unpack_result {
         return Err(UnpackError::Archive(format!(
@@ -88,18 +154,12 @@ enum UnpackPath<'a> {
 fn unpack_archive<'a, C>(
     input: impl Read,
     mut file_creator: Box<dyn FileCreator + '_>,
-    apparent_limit_size: u64,
-    actual_limit_size: u64,
-    limit_count: u64,
+    totals: &UnpackTotals,
     mut entry_checker: C, // checks if entry is valid
 ) -> Result<()>
 where
     C: FnMut(&[&str], tar::EntryType) -> UnpackPath<'a>,
 {
-    let mut apparent_total_size: u64 = 0;
-    let mut actual_total_size: u64 = 0;
-    let mut total_count: u64 = 0;
-
     let mut total_entries = 0;
     let mut open_dirs = Vec::new();
 
@@ -147,17 +207,7 @@ where
             UnpackPath::Valid(unpack_dir) => unpack_dir,
         };
 
-        apparent_total_size = checked_total_size_sum(
-            apparent_total_size,
-            entry.header().size()?,
-            apparent_limit_size,
-        )?;
-        actual_total_size = checked_total_size_sum(
-            actual_total_size,
-            entry.header().entry_size()?,
-            actual_limit_size,
-        )?;
-        total_count = checked_total_count_increment(total_count, limit_count)?;
+        totals.add_entry(entry.header().size()?, entry.header().entry_size()?)?;
 
         let account_filename = match parts.as_slice() {
             ["accounts", account_filename] => Some(PathBuf::from(account_filename)),
@@ -313,13 +363,23 @@ fn validate_inside_dst(dst: &Path, file_dst: &Path) -> Result<PathBuf> {
 
 /// Unpacks snapshot from (potentially partial) `archive` and
 /// sends entry file paths through the `sender` channel
+///
+/// The unpacked entries are added to `totals`, which is shared by all partitions of an archive.
 pub(super) fn streaming_unpack_snapshot(
     input: impl Read,
     file_creator: Box<dyn FileCreator + '_>,
     ledger_dir: &Path,
     account_paths: &[PathBuf],
+    totals: &UnpackTotals,
 ) -> Result<()> {
-    unpack_snapshot_with_processors(input, file_creator, ledger_dir, account_paths, |_, _| {})
+    unpack_snapshot_with_processors(
+        input,
+        file_creator,
+        ledger_dir,
+        account_paths,
+        totals,
+        |_, _| {},
+    )
 }
 
 fn unpack_snapshot_with_processors<F>(
@@ -327,6 +387,7 @@ fn unpack_snapshot_with_processors<F>(
     file_creator: Box<dyn FileCreator + '_>,
     ledger_dir: &Path,
     account_paths: &[PathBuf],
+    totals: &UnpackTotals,
     mut accounts_path_processor: F,
 ) -> Result<()>
 where
@@ -334,35 +395,55 @@ where
 {
     assert!(!account_paths.is_empty());
 
-    unpack_archive(
-        input,
-        file_creator,
-        MAX_SNAPSHOT_ARCHIVE_UNPACKED_APPARENT_SIZE,
-        MAX_SNAPSHOT_ARCHIVE_UNPACKED_ACTUAL_SIZE,
-        MAX_SNAPSHOT_ARCHIVE_UNPACKED_COUNT,
-        |parts, kind| {
-            if is_valid_snapshot_archive_entry(parts, kind) {
-                if let ["accounts", file] = parts {
-                    // Randomly distribute the accounts files about the available `account_paths`,
-                    let path_index = rng().random_range(0..account_paths.len());
-                    match account_paths
-                        .get(path_index)
-                        .map(|path_buf| path_buf.as_path())
-                    {
-                        Some(path) => {
-                            accounts_path_processor(file, path);
-                            UnpackPath::Valid(path)
-                        }
-                        None => UnpackPath::Invalid,
+    unpack_archive(input, file_creator, totals, |parts, kind| {
+        if is_valid_snapshot_archive_entry(parts, kind) {
+            if let ["accounts", file] = parts {
+                // Randomly distribute the accounts files about the available `account_paths`,
+                let path_index = rng().random_range(0..account_paths.len());
+                match account_paths
+                    .get(path_index)
+                    .map(|path_buf| path_buf.as_path())
+                {
+                    Some(path) => {
+                        accounts_path_processor(file, path);
+                        UnpackPath::Valid(path)
                     }
-                } else {
-                    UnpackPath::Valid(ledger_dir)
+                    None => UnpackPath::Invalid,
                 }
             } else {
-                UnpackPath::Invalid
+                UnpackPath::Valid(ledger_dir)
             }
-        },
-    )
+        } else {
+            UnpackPath::Invalid
+        }
+    })
+}
+
+/// Checks the table of contents of a seekable snapshot archive against the limits enforced when
+/// unpacking, before it is split up and unpacked in parallel
+///
+/// This only rejects archives early, the limits are still enforced on the actual tar entries
+/// while unpacking.
+pub(super) fn check_seekable_snapshot_archive(archive: &SeekableArchive) -> Result<()> {
+    let mut total_size: u64 = 0;
+    let mut total_count: u64 = 0;
+    for entry in archive.entries() {
+        let parts: Vec<_> = entry.path.split('/').collect();
+        if !matches!(parts.as_slice(), ["accounts", file] if like_storage(file)) {
+            return Err(UnpackError::Archive(format!(
+                "invalid seekable archive entry: {:?}",
+                entry.path
+            )));
+        }
+        total_size = checked_total_size_sum(
+            total_size,
+            entry.data_size,
+            MAX_SNAPSHOT_ARCHIVE_UNPACKED_ACTUAL_SIZE,
+        )?;
+        total_count =
+            checked_total_count_increment(total_count, MAX_SNAPSHOT_ARCHIVE_UNPACKED_COUNT)?;
+    }
+    Ok(())
 }
 
 fn all_digits(v: &str) -> bool {
@@ -424,9 +505,11 @@ pub(super) fn unpack_genesis(
     unpack_archive(
         input,
         file_creator,
-        max_genesis_archive_unpacked_size,
-        max_genesis_archive_unpacked_size,
-        MAX_GENESIS_ARCHIVE_UNPACKED_COUNT,
+        &UnpackTotals::new(
+            max_genesis_archive_unpacked_size,
+            max_genesis_archive_unpacked_size,
+            MAX_GENESIS_ARCHIVE_UNPACKED_COUNT,
+        ),
         |p, k| is_valid_genesis_archive_entry(unpack_dir, p, k),
     )
 }
@@ -706,8 +789,15 @@ mod tests {
             Some(file_info.file)
         })?;
         with_finalize_and_unpack(archive, move |a, b| {
-            unpack_snapshot_with_processors(a, file_creator, b, &[PathBuf::new()], |_, _| {})
-                .map(|_| ())
+            unpack_snapshot_with_processors(
+                a,
+                file_creator,
+                b,
+                &[PathBuf::new()],
+                &UnpackTotals::new_for_snapshot(),
+                |_, _| {},
+            )
+            .map(|_| ())
         })
     }
 
@@ -1008,9 +1098,56 @@ mod tests {
                 file_creator,
                 tmp,
                 &[tmp.join("accounts_dest")],
+                &UnpackTotals::new_for_snapshot(),
                 |_, _| {},
             )
         });
         assert_matches!(result, Ok(()));
     }
+
+    #[test]
+    fn test_archive_unpack_totals_shared_between_partitions() {
+        let partition = |path: &str| {
+            let mut header = Header::new_gnu();
+            header.set_path(path).unwrap();
+            header.set_size(4);
+            header.set_cksum();
+            let data: &[u8] = &[1, 2, 3, 4];
+            let mut archive = Builder::new(Vec::new());
+            archive.append(&header, data).unwrap();
+            archive.into_inner().unwrap()
+        };
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let unpack = |data: &[u8], totals: &UnpackTotals| -> Result<()> {
+            let file_creator = file_creator(256, &IoSetupState::default(), |file_info| {
+                Some(file_info.file)
+            })?;
+            streaming_unpack_snapshot(
+                data,
+                file_creator,
+                temp_dir.path(),
+                &[temp_dir.path().to_path_buf()],
+                totals,
+            )
+        };
+
+        // Each partition is within the limits on its own, but not together with the other one
+        let totals = UnpackTotals::new(
+            MAX_SNAPSHOT_ARCHIVE_UNPACKED_APPARENT_SIZE,
+            MAX_SNAPSHOT_ARCHIVE_UNPACKED_ACTUAL_SIZE,
+            1,
+        );
+        assert_matches!(unpack(&partition("accounts/1.0"), &totals), Ok(()));
+        assert_matches!(
+            unpack(&partition("accounts/2.1"), &totals),
+            Err(UnpackError::Archive(ref message)) if message == "too many files in snapshot: 2"
+        );
+
+        let totals = UnpackTotals::new(6, 6, MAX_SNAPSHOT_ARCHIVE_UNPACKED_COUNT);
+        assert_matches!(unpack(&partition("accounts/3.2"), &totals), Ok(()));
+        assert_matches!(
+            unpack(&partition("accounts/4.3"), &totals),
+            Err(UnpackError::Archive(ref message)) if message == "too large archive: 8 than limit: 6"
+        );
+    }
 }
@@ -7,6 +7,7 @@ pub mod hardened_unpack;
 mod kind;
 mod multiframe;
 pub mod paths;
+pub mod seekable_archive;
 pub mod snapshot_archive_info;
 pub mod snapshot_config;
 pub mod snapshot_hash;
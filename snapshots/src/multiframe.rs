@@ -4,7 +4,14 @@
 //! back to back, so that readers can decompress frames in parallel.
 use std::io::{self, Write};
 
-const MAX_ZSTD_FRAME_SIZE: u32 = 1 << 30;
+pub(crate) const MAX_ZSTD_FRAME_SIZE: u32 = 1 << 30;
+
+/// Sizes of a single frame emitted by [`MultiFrameZstdWriter`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ZstdFrame {
+    pub compressed_size: u64,
+    pub decompressed_size: u64,
+}
 
 /// Compresses a byte stream into independent zstd frames of `frame_size`
 /// uncompressed bytes each, written back to back.
@@ -14,6 +21,7 @@ pub struct MultiFrameZstdWriter<W: Write> {
     frame_size: usize,
     buf: Vec<u8>,
     compressed: Vec<u8>,
+    frames: Vec<ZstdFrame>,
 }
 
 impl<W: Write> MultiFrameZstdWriter<W> {
@@ -31,6 +39,7 @@ impl<W: Write> MultiFrameZstdWriter<W> {
             frame_size,
             buf: Vec::with_capacity(frame_size),
             compressed: Vec::new(),
+            frames: Vec::new(),
         })
     }
 
@@ -45,13 +54,22 @@ impl<W: Write> MultiFrameZstdWriter<W> {
             .compressor
             .compress_to_buffer(&self.buf, &mut self.compressed)?;
         self.inner.write_all(&self.compressed[..compressed_size])?;
+        self.frames.push(ZstdFrame {
+            compressed_size: compressed_size as u64,
+            decompressed_size: self.buf.len() as u64,
+        });
         self.buf.clear();
         Ok(())
     }
 
-    pub fn finish(mut self) -> io::Result<W> {
+    pub fn finish(self) -> io::Result<W> {
+        self.finish_with_frames().map(|(inner, _frames)| inner)
+    }
+
+    /// Like [`Self::finish`], but also returns the sizes of all emitted frames, in order
+    pub fn finish_with_frames(mut self) -> io::Result<(W, Vec<ZstdFrame>)> {
         self.emit_frame()?;
-        Ok(self.inner)
+        Ok((self.inner, self.frames))
     }
 }
 
@@ -215,6 +233,22 @@ mod tests {
         assert_eq!(zstd::decode_all(&archive[..]).unwrap(), data);
     }
 
+    #[test]
+    fn test_finish_with_frames_matches_archive() {
+        let data = test_data(3 * TEST_FRAME_SIZE as usize + 4321);
+        let mut writer = MultiFrameZstdWriter::new(Vec::new(), 1, TEST_FRAME_SIZE).unwrap();
+        writer.write_all(&data).unwrap();
+        let (archive, frames) = writer.finish_with_frames().unwrap();
+        let expected: Vec<_> = walk_frames(&archive)
+            .into_iter()
+            .map(|(compressed_size, decompressed_size)| ZstdFrame {
+                compressed_size: compressed_size as u64,
+                decompressed_size: decompressed_size as u64,
+            })
+            .collect();
+        assert_eq!(frames, expected);
+    }
+
     #[test]
     fn test_invalid_frame_size_rejected() {
         assert!(MultiFrameZstdWriter::new(Vec::new(), 1, 0).is_err());
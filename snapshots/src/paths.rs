@@ -25,14 +25,14 @@ pub const SNAPSHOT_ACCOUNTS_HARDLINKS: &str = "accounts_hardlinks";
 pub const SNAPSHOT_STORAGES_LIST_FILENAME: &str = "storages_list";
 pub const SNAPSHOT_ARCHIVE_DOWNLOAD_DIR: &str = "remote";
 pub const SNAPSHOT_OBSOLETE_ACCOUNTS_FILENAME: &str = "obsolete_accounts";
+pub const SNAPSHOT_SECONDARY_INDEXES_DIR: &str = "secondary_indexes";
 /// When a snapshot is taken of a bank, the state is serialized under this directory.
 /// Specifically in `BANK_SNAPSHOTS_DIR/SLOT/`.
 /// This is also where the bank state is located in the snapshot archive.
 pub const BANK_SNAPSHOTS_DIR: &str = "snapshots";
 pub const TMP_SNAPSHOT_ARCHIVE_PREFIX: &str = "tmp-snapshot-archive-";
-pub const FULL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str =
-    r"^snapshot-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar\.zst|tar\.lz4)$";
-pub const INCREMENTAL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^incremental-snapshot-(?P<base>[[:digit:]]+)-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar\.zst|tar\.lz4)$";
+pub const FULL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^snapshot-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar\.zst|tar\.lz4|tar\.seekable\.zst)$";
+pub const INCREMENTAL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^incremental-snapshot-(?P<base>[[:digit:]]+)-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar\.zst|tar\.lz4|tar\.seekable\.zst)$";
 
 /// Get the `&str` from a `&Path`
 pub fn path_to_file_name_str(path: &Path) -> Result<&str> {
@@ -286,6 +286,20 @@ mod tests {
             .unwrap(),
             (45, SnapshotHash(Hash::default()), ArchiveFormat::TarLz4)
         );
+        assert_eq!(
+            parse_full_snapshot_archive_filename(&format!(
+                "snapshot-47-{}.tar.seekable.zst",
+                Hash::default()
+            ))
+            .unwrap(),
+            (
+                47,
+                SnapshotHash(Hash::default()),
+                ArchiveFormat::TarZstdSeekable {
+                    config: ZstdConfig::default(),
+                }
+            )
+        );
 
         assert!(parse_full_snapshot_archive_filename("invalid").is_err());
         assert!(
@@ -0,0 +1,772 @@
+//! Seekable snapshot archives.
+//!
+//! A seekable archive is a regular multi-frame zstd tar stream (see
+//! [`MultiFrameZstdWriter`](crate::multiframe::MultiFrameZstdWriter)) followed by a zstd
+//! skippable frame that holds a table of contents (TOC). Since zstd decoders skip over skippable
+//! frames, the archive can still be unpacked as a plain `.tar.zst` stream, while readers that
+//! understand the TOC can locate individual entries and only decompress the frames covering them.
+//!
+//! The skippable frame payload is laid out as follows, all integers are little-endian:
+//!
+//! ```text
+//! toc:
+//!     version:     u32
+//!     num_frames:  u64
+//!     frames:      [compressed_size: u64, decompressed_size: u64; num_frames]
+//!     meta_len:    u64
+//!     num_entries: u64
+//!     entries:     [path_len: u16, path: [u8; path_len],
+//!                   header_offset: u64, data_size: u64, end_offset: u64; num_entries]
+//! footer:
+//!     toc_len:     u64
+//!     magic:       [u8; 8]
+//! ```
+//!
+//! All offsets refer to the decompressed tar stream. The first `meta_len` bytes of the stream hold
+//! the version file, the bank snapshot and the status cache. They are followed by one contiguous
+//! entry per account storage file.
+use {
+    crate::multiframe::{MAX_ZSTD_FRAME_SIZE, ZstdFrame},
+    std::{
+        fs::File,
+        io::{self, Read, Seek, SeekFrom, Write},
+        ops::Range,
+        path::{Path, PathBuf},
+    },
+};
+
+const TOC_VERSION: u32 = 1;
+const TOC_MAGIC: [u8; 8] = *b"AGVSKTOC";
+const TOC_FOOTER_LEN: u64 = 16;
+// Limits the allocation made for the TOC when opening untrusted archives
+const MAX_TOC_LEN: u64 = 1 << 30;
+
+// Any magic in 0x184D2A50..=0x184D2A5F marks a zstd skippable frame
+const TOC_SKIPPABLE_FRAME_MAGIC: u32 = 0x184D2A5E;
+const SKIPPABLE_FRAME_HEADER_LEN: u64 = 8;
+
+const TAR_BLOCK_SIZE: u64 = 512;
+
+// Sizes of the fixed-width TOC records, used to bound counts before allocating
+const FRAME_RECORD_LEN: usize = 16;
+const MIN_ENTRY_RECORD_LEN: usize = 2 + 24;
+
+/// A single file stored in a seekable archive
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SeekableArchiveEntry {
+    /// Path of the entry inside the archive, e.g. `accounts/123.4`
+    pub path: String,
+    /// Offset of the entry's tar header in the decompressed stream
+    pub header_offset: u64,
+    /// Size of the entry's file contents
+    pub data_size: u64,
+    /// Offset just past the entry's padded file contents in the decompressed stream
+    pub end_offset: u64,
+}
+
+impl SeekableArchiveEntry {
+    /// Offset of the entry's file contents in the decompressed stream
+    pub fn data_offset(&self) -> u64 {
+        self.end_offset
+            .saturating_sub(self.data_size.next_multiple_of(TAR_BLOCK_SIZE))
+    }
+}
+
+/// Table of contents accumulated while writing a seekable archive
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub(crate) struct SeekableArchiveToc {
+    meta_len: u64,
+    entries: Vec<SeekableArchiveEntry>,
+}
+
+impl SeekableArchiveToc {
+    /// Records the end of the leading, non-storage part of the stream
+    pub(crate) fn set_meta_len(&mut self, meta_len: u64) {
+        self.meta_len = meta_len;
+    }
+
+    /// Records an entry that spans `header_offset..end_offset` in the decompressed stream
+    pub(crate) fn push_entry(
+        &mut self,
+        path: impl Into<String>,
+        header_offset: u64,
+        data_size: u64,
+        end_offset: u64,
+    ) {
+        self.entries.push(SeekableArchiveEntry {
+            path: path.into(),
+            header_offset,
+            data_size,
+            end_offset,
+        });
+    }
+
+    fn encode(&self, frames: &[ZstdFrame]) -> io::Result<Vec<u8>> {
+        let mut toc = Vec::new();
+        toc.extend_from_slice(&TOC_VERSION.to_le_bytes());
+        toc.extend_from_slice(&(frames.len() as u64).to_le_bytes());
+        for frame in frames {
+            toc.extend_from_slice(&frame.compressed_size.to_le_bytes());
+            toc.extend_from_slice(&frame.decompressed_size.to_le_bytes());
+        }
+        toc.extend_from_slice(&self.meta_len.to_le_bytes());
+        toc.extend_from_slice(&(self.entries.len() as u64).to_le_bytes());
+        for entry in &self.entries {
+            let path_len = u16::try_from(entry.path.len()).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("archive entry path too long: {}", entry.path),
+                )
+            })?;
+            toc.extend_from_slice(&path_len.to_le_bytes());
+            toc.extend_from_slice(entry.path.as_bytes());
+            toc.extend_from_slice(&entry.header_offset.to_le_bytes());
+            toc.extend_from_slice(&entry.data_size.to_le_bytes());
+            toc.extend_from_slice(&entry.end_offset.to_le_bytes());
+        }
+        Ok(toc)
+    }
+
+    fn decode(toc: &[u8]) -> io::Result<(Vec<ZstdFrame>, Self)> {
+        let mut decoder = TocDecoder { bytes: toc };
+        let version = decoder.u32()?;
+        if version != TOC_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported table of contents version: {version}"
+            )));
+        }
+
+        let num_frames = decoder.count(FRAME_RECORD_LEN)?;
+        let mut frames = Vec::with_capacity(num_frames);
+        for _ in 0..num_frames {
+            frames.push(ZstdFrame {
+                compressed_size: decoder.u64()?,
+                decompressed_size: decoder.u64()?,
+            });
+        }
+
+        let meta_len = decoder.u64()?;
+        let num_entries = decoder.count(MIN_ENTRY_RECORD_LEN)?;
+        let mut entries = Vec::with_capacity(num_entries);
+        for _ in 0..num_entries {
+            let path_len = decoder.u16()?;
+            let path = std::str::from_utf8(decoder.take(path_len.into())?)
+                .map_err(|err| invalid_data(format!("invalid entry path: {err}")))?
+                .to_string();
+            entries.push(SeekableArchiveEntry {
+                path,
+                header_offset: decoder.u64()?,
+                data_size: decoder.u64()?,
+                end_offset: decoder.u64()?,
+            });
+        }
+
+        if !decoder.bytes.is_empty() {
+            return Err(invalid_data("trailing bytes in table of contents"));
+        }
+        Ok((frames, Self { meta_len, entries }))
+    }
+}
+
+/// Appends the table of contents for an archive made of `frames` to `writer`
+///
+/// `writer` must be positioned right after the last frame.
+pub(crate) fn write_toc(
+    writer: &mut impl Write,
+    frames: &[ZstdFrame],
+    toc: &SeekableArchiveToc,
+) -> io::Result<()> {
+    let encoded = toc.encode(frames)?;
+    let toc_len = encoded.len() as u64;
+    let frame_len = u32::try_from(toc_len.saturating_add(TOC_FOOTER_LEN))
+        .ok()
+        .filter(|_| toc_len <= MAX_TOC_LEN)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("table of contents too large: {toc_len} bytes"),
+            )
+        })?;
+    writer.write_all(&TOC_SKIPPABLE_FRAME_MAGIC.to_le_bytes())?;
+    writer.write_all(&frame_len.to_le_bytes())?;
+    writer.write_all(&encoded)?;
+    writer.write_all(&toc_len.to_le_bytes())?;
+    writer.write_all(&TOC_MAGIC)
+}
+
+/// Writer that keeps track of the number of bytes written through it
+///
+/// Used to learn the decompressed stream offsets of tar entries as they are appended.
+pub(crate) struct PositionTrackingWriter<W> {
+    inner: W,
+    position: u64,
+}
+
+impl<W: Write> PositionTrackingWriter<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        Self { inner, position: 0 }
+    }
+
+    pub(crate) fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl<W: Write> Write for PositionTrackingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.position = self.position.saturating_add(written as u64);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct FrameLocation {
+    compressed_offset: u64,
+    decompressed_offset: u64,
+    frame: ZstdFrame,
+}
+
+impl FrameLocation {
+    fn decompressed_end(&self) -> u64 {
+        self.decompressed_offset
+            .saturating_add(self.frame.decompressed_size)
+    }
+}
+
+/// A seekable snapshot archive opened for random access
+#[derive(Debug)]
+pub struct SeekableArchive {
+    path: PathBuf,
+    frames: Vec<FrameLocation>,
+    decompressed_len: u64,
+    meta_len: u64,
+    entries: Vec<SeekableArchiveEntry>,
+}
+
+impl SeekableArchive {
+    /// Opens the archive at `path` and reads and validates its table of contents
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let mut file = File::open(&path)?;
+        let file_len = file.metadata()?.len();
+        if file_len < SKIPPABLE_FRAME_HEADER_LEN.saturating_add(TOC_FOOTER_LEN) {
+            return Err(invalid_data(
+                "archive too small to hold a table of contents",
+            ));
+        }
+
+        let mut footer = [0u8; TOC_FOOTER_LEN as usize];
+        file.seek(SeekFrom::Start(file_len.saturating_sub(TOC_FOOTER_LEN)))?;
+        file.read_exact(&mut footer)?;
+        let (toc_len, magic) = footer.split_at(8);
+        if magic != TOC_MAGIC {
+            return Err(invalid_data("archive has no table of contents"));
+        }
+        let toc_len = u64::from_le_bytes(toc_len.try_into().unwrap());
+        if toc_len > MAX_TOC_LEN {
+            return Err(invalid_data(format!(
+                "table of contents too large: {toc_len} bytes"
+            )));
+        }
+
+        let toc_frame_len = toc_len
+            .saturating_add(TOC_FOOTER_LEN)
+            .saturating_add(SKIPPABLE_FRAME_HEADER_LEN);
+        let toc_frame_offset = file_len
+            .checked_sub(toc_frame_len)
+            .ok_or_else(|| invalid_data("truncated table of contents"))?;
+        file.seek(SeekFrom::Start(toc_frame_offset))?;
+        let mut frame_header = [0u8; SKIPPABLE_FRAME_HEADER_LEN as usize];
+        file.read_exact(&mut frame_header)?;
+        let (magic, frame_len) = frame_header.split_at(4);
+        if u32::from_le_bytes(magic.try_into().unwrap()) != TOC_SKIPPABLE_FRAME_MAGIC
+            || u64::from(u32::from_le_bytes(frame_len.try_into().unwrap()))
+                != toc_len.saturating_add(TOC_FOOTER_LEN)
+        {
+            return Err(invalid_data("corrupt table of contents frame header"));
+        }
+
+        let mut toc = vec![0; toc_len as usize];
+        file.read_exact(&mut toc)?;
+        let (frames, toc) = SeekableArchiveToc::decode(&toc)?;
+        Self::new(path, &frames, toc, toc_frame_offset)
+    }
+
+    /// Validates the decoded table of contents against the archive layout
+    fn new(
+        path: PathBuf,
+        frames: &[ZstdFrame],
+        toc: SeekableArchiveToc,
+        compressed_len: u64,
+    ) -> io::Result<Self> {
+        let overflow = || invalid_data("table of contents offsets overflow");
+
+        let mut locations = Vec::with_capacity(frames.len());
+        let mut compressed_offset = 0u64;
+        let mut decompressed_offset = 0u64;
+        for frame in frames {
+            if frame.compressed_size == 0
+                || frame.decompressed_size == 0
+                || frame.decompressed_size > u64::from(MAX_ZSTD_FRAME_SIZE)
+            {
+                return Err(invalid_data(format!("invalid frame: {frame:?}")));
+            }
+            locations.push(FrameLocation {
+                compressed_offset,
+                decompressed_offset,
+                frame: *frame,
+            });
+            compressed_offset = compressed_offset
+                .checked_add(frame.compressed_size)
+                .ok_or_else(overflow)?;
+            decompressed_offset = decompressed_offset
+                .checked_add(frame.decompressed_size)
+                .ok_or_else(overflow)?;
+        }
+        if compressed_offset != compressed_len {
+            return Err(invalid_data(format!(
+                "frames cover {compressed_offset} bytes, but archive has {compressed_len} bytes \
+                 of frames"
+            )));
+        }
+        let decompressed_len = decompressed_offset;
+
+        if toc.meta_len > decompressed_len {
+            return Err(invalid_data("metadata range exceeds archive"));
+        }
+        // Entries must follow the metadata and each other without gaps, so that any contiguous
+        // run of them forms a valid tar stream.
+        let mut expected_header_offset = toc.meta_len;
+        for entry in &toc.entries {
+            let padded_end = entry
+                .header_offset
+                .checked_add(TAR_BLOCK_SIZE)
+                .and_then(|offset| {
+                    offset.checked_add(entry.data_size.next_multiple_of(TAR_BLOCK_SIZE))
+                })
+                .ok_or_else(overflow)?;
+            if entry.path.is_empty()
+                || entry.header_offset != expected_header_offset
+                || entry.end_offset < padded_end
+                || entry.end_offset > decompressed_len
+            {
+                return Err(invalid_data(format!("invalid entry: {entry:?}")));
+            }
+            expected_header_offset = entry.end_offset;
+        }
+
+        Ok(Self {
+            path,
+            frames: locations,
+            decompressed_len,
+            meta_len: toc.meta_len,
+            entries: toc.entries,
+        })
+    }
+
+    /// Path of the archive file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Total length of the decompressed tar stream
+    pub fn decompressed_len(&self) -> u64 {
+        self.decompressed_len
+    }
+
+    /// All account storage entries, ordered by their offset in the archive
+    pub fn entries(&self) -> &[SeekableArchiveEntry] {
+        &self.entries
+    }
+
+    /// Looks up an entry by its path inside the archive
+    pub fn entry(&self, path: &str) -> Option<&SeekableArchiveEntry> {
+        self.entries.iter().find(|entry| entry.path == path)
+    }
+
+    /// Returns a reader over the file contents of `entry`
+    pub fn entry_reader(&self, entry: &SeekableArchiveEntry) -> io::Result<RangeReader<'_>> {
+        self.range_reader(entry.data_offset()..entry.data_offset().saturating_add(entry.data_size))
+    }
+
+    /// Returns a tar stream of the entries that precede the account storages, i.e. the version
+    /// file, the bank snapshot and the status cache
+    pub fn meta_reader(&self) -> io::Result<RangeReader<'_>> {
+        self.range_reader(0..self.meta_len)
+    }
+
+    /// Splits the archive into at most `num_partitions` contiguous ranges of similar size, each of
+    /// which is a valid tar stream on its own
+    ///
+    /// The first partition always starts with the metadata entries.
+    pub fn partitions(&self, num_partitions: usize) -> Vec<Range<u64>> {
+        let end = self
+            .entries
+            .last()
+            .map_or(self.meta_len, |entry| entry.end_offset);
+        let target_len = end.div_ceil(num_partitions.max(1) as u64).max(1);
+
+        let mut partitions = Vec::with_capacity(num_partitions);
+        let mut start = 0;
+        for entry in &self.entries {
+            if partitions.len().saturating_add(1) >= num_partitions {
+                break;
+            }
+            if entry.header_offset.saturating_sub(start) >= target_len {
+                partitions.push(start..entry.header_offset);
+                start = entry.header_offset;
+            }
+        }
+        partitions.push(start..end);
+        partitions
+    }
+
+    /// Returns a reader over `range` of the decompressed stream
+    ///
+    /// Only the frames overlapping `range` are read and decompressed.
+    pub fn range_reader(&self, range: Range<u64>) -> io::Result<RangeReader<'_>> {
+        if range.start > range.end || range.end > self.decompressed_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "range {range:?} out of bounds of decompressed stream of length {}",
+                    self.decompressed_len
+                ),
+            ));
+        }
+        let first_frame = self
+            .frames
+            .partition_point(|location| location.decompressed_end() <= range.start);
+        let frames = &self.frames[first_frame..];
+
+        let mut file = File::open(&self.path)?;
+        let skip = match frames.first() {
+            Some(location) => {
+                file.seek(SeekFrom::Start(location.compressed_offset))?;
+                range.start.saturating_sub(location.decompressed_offset)
+            }
+            None => 0,
+        };
+        Ok(RangeReader {
+            file,
+            frames,
+            decompressor: zstd::bulk::Decompressor::new()?,
+            compressed: Vec::new(),
+            decompressed: Vec::new(),
+            pos: 0,
+            skip,
+            remaining: range.end.saturating_sub(range.start),
+        })
+    }
+}
+
+/// Reader over a range of a seekable archive's decompressed stream
+pub struct RangeReader<'a> {
+    file: File,
+    frames: &'a [FrameLocation],
+    decompressor: zstd::bulk::Decompressor<'static>,
+    compressed: Vec<u8>,
+    decompressed: Vec<u8>,
+    pos: usize,
+    skip: u64,
+    remaining: u64,
+}
+
+impl RangeReader<'_> {
+    fn load_next_frame(&mut self) -> io::Result<()> {
+        let Some((location, rest)) = self.frames.split_first() else {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "range extends past the last frame",
+            ));
+        };
+        self.frames = rest;
+
+        let frame = location.frame;
+        self.compressed.resize(frame.compressed_size as usize, 0);
+        self.file.read_exact(&mut self.compressed)?;
+        self.decompressed.clear();
+        self.decompressed.reserve(frame.decompressed_size as usize);
+        let decompressed_size = self
+            .decompressor
+            .decompress_to_buffer(&self.compressed, &mut self.decompressed)?;
+        if decompressed_size as u64 != frame.decompressed_size {
+            return Err(invalid_data(format!(
+                "frame decompressed to {decompressed_size} bytes, expected {}",
+                frame.decompressed_size
+            )));
+        }
+        // `skip` is less than the first frame's size by construction
+        self.pos = std::mem::take(&mut self.skip) as usize;
+        Ok(())
+    }
+}
+
+impl Read for RangeReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+        if self.pos >= self.decompressed.len() {
+            self.load_next_frame()?;
+        }
+        let available = &self.decompressed[self.pos..];
+        let len = available
+            .len()
+            .min(buf.len())
+            .min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+        buf[..len].copy_from_slice(&available[..len]);
+        self.pos = self.pos.saturating_add(len);
+        self.remaining = self.remaining.saturating_sub(len as u64);
+        Ok(len)
+    }
+}
+
+struct TocDecoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> TocDecoder<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if len > self.bytes.len() {
+            return Err(invalid_data("truncated table of contents"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Reads a record count, rejecting counts that can't fit in the remaining bytes
+    fn count(&mut self, min_record_len: usize) -> io::Result<usize> {
+        let count = self.u64()?;
+        usize::try_from(count)
+            .ok()
+            .filter(|count| count.saturating_mul(min_record_len) <= self.bytes.len())
+            .ok_or_else(|| invalid_data(format!("invalid record count: {count}")))
+    }
+}
+
+fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+#[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
+pub(crate) mod tests {
+    use {super::*, crate::multiframe::MultiFrameZstdWriter, tempfile::NamedTempFile};
+
+    const TEST_FRAME_SIZE: u32 = 4 * 1024;
+
+    fn append_file(
+        archive: &mut tar::Builder<PositionTrackingWriter<&mut MultiFrameZstdWriter<File>>>,
+        path: &str,
+        data: &[u8],
+    ) {
+        let mut header = tar::Header::new_gnu();
+        header.set_path(path).unwrap();
+        header.set_size(data.len() as u64);
+        header.set_cksum();
+        archive.append(&header, data).unwrap();
+    }
+
+    /// Writes a seekable archive with a version file followed by the given storages
+    pub(crate) fn write_archive(storages: &[(&str, Vec<u8>)]) -> NamedTempFile {
+        let file = NamedTempFile::new().unwrap();
+        let mut encoder =
+            MultiFrameZstdWriter::new(file.reopen().unwrap(), 1, TEST_FRAME_SIZE).unwrap();
+        let mut toc = SeekableArchiveToc::default();
+        {
+            let mut archive = tar::Builder::new(PositionTrackingWriter::new(&mut encoder));
+            append_file(&mut archive, "version", b"1.2.0");
+            toc.set_meta_len(archive.get_ref().position());
+            for (path, data) in storages {
+                let header_offset = archive.get_ref().position();
+                append_file(&mut archive, path, data);
+                toc.push_entry(
+                    *path,
+                    header_offset,
+                    data.len() as u64,
+                    archive.get_ref().position(),
+                );
+            }
+            archive.finish().unwrap();
+        }
+        let (mut writer, frames) = encoder.finish_with_frames().unwrap();
+        write_toc(&mut writer, &frames, &toc).unwrap();
+        writer.flush().unwrap();
+        file
+    }
+
+    pub(crate) fn test_storages() -> Vec<(&'static str, Vec<u8>)> {
+        vec![
+            ("accounts/1.0", (0..10_000u32).map(|i| i as u8).collect()),
+            ("accounts/2.1", vec![7; 1]),
+            ("accounts/3.2", vec![]),
+            (
+                "accounts/4.3",
+                (0..30_000u32).map(|i| (i / 7) as u8).collect(),
+            ),
+        ]
+    }
+
+    fn read_all(mut reader: impl Read) -> Vec<u8> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_entry_reader() {
+        let storages = test_storages();
+        let file = write_archive(&storages);
+        let archive = SeekableArchive::open(file.path()).unwrap();
+
+        assert_eq!(archive.entries().len(), storages.len());
+        for (path, data) in &storages {
+            let entry = archive.entry(path).unwrap();
+            assert_eq!(entry.data_size, data.len() as u64);
+            assert_eq!(&read_all(archive.entry_reader(entry).unwrap()), data);
+        }
+        assert!(archive.entry("accounts/5.4").is_none());
+    }
+
+    #[test]
+    fn test_meta_reader() {
+        let file = write_archive(&test_storages());
+        let archive = SeekableArchive::open(file.path()).unwrap();
+
+        let mut meta = tar::Archive::new(archive.meta_reader().unwrap());
+        let mut entries = meta.entries().unwrap();
+        let mut version = entries.next().unwrap().unwrap();
+        assert_eq!(version.path().unwrap().to_str(), Some("version"));
+        let mut contents = String::new();
+        version.read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "1.2.0");
+        drop(version);
+        assert!(entries.next().is_none());
+    }
+
+    #[test]
+    fn test_plain_zstd_decoder_compatible() {
+        let storages = test_storages();
+        let file = write_archive(&storages);
+        let decoder = zstd::stream::read::Decoder::new(File::open(file.path()).unwrap()).unwrap();
+
+        let mut archive = tar::Archive::new(decoder);
+        let paths: Vec<_> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect();
+        let mut expected = vec!["version".to_string()];
+        expected.extend(storages.iter().map(|(path, _)| path.to_string()));
+        assert_eq!(paths, expected);
+    }
+
+    #[test]
+    fn test_partitions_are_tar_streams() {
+        let storages = test_storages();
+        let file = write_archive(&storages);
+        let archive = SeekableArchive::open(file.path()).unwrap();
+
+        for num_partitions in 1..=6 {
+            let partitions = archive.partitions(num_partitions);
+            assert!(!partitions.is_empty() && partitions.len() <= num_partitions);
+            assert_eq!(partitions[0].start, 0);
+            assert!(partitions.windows(2).all(|w| w[0].end == w[1].start));
+
+            let mut paths = Vec::new();
+            for partition in partitions {
+                let mut tar = tar::Archive::new(archive.range_reader(partition).unwrap());
+                for entry in tar.entries().unwrap() {
+                    paths.push(entry.unwrap().path().unwrap().display().to_string());
+                }
+            }
+            let mut expected = vec!["version".to_string()];
+            expected.extend(storages.iter().map(|(path, _)| path.to_string()));
+            assert_eq!(paths, expected);
+        }
+    }
+
+    #[test]
+    fn test_range_reader_out_of_bounds() {
+        let file = write_archive(&test_storages());
+        let archive = SeekableArchive::open(file.path()).unwrap();
+        let len = archive.decompressed_len();
+        assert!(archive.range_reader(0..len).is_ok());
+        assert!(archive.range_reader(len..len).is_ok());
+        assert!(archive.range_reader(0..len + 1).is_err());
+    }
+
+    #[test]
+    fn test_open_rejects_missing_toc() {
+        let mut file = NamedTempFile::new().unwrap();
+        let mut encoder = MultiFrameZstdWriter::new(Vec::new(), 1, TEST_FRAME_SIZE).unwrap();
+        encoder.write_all(&[1; 1024]).unwrap();
+        file.write_all(&encoder.finish().unwrap()).unwrap();
+        assert_eq!(
+            SeekableArchive::open(file.path()).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn test_open_rejects_corrupt_toc() {
+        let file = write_archive(&test_storages());
+        let mut bytes = std::fs::read(file.path()).unwrap();
+        // Corrupt the header offset of the last entry
+        let header_offset_pos = bytes.len() - TOC_FOOTER_LEN as usize - 24;
+        bytes[header_offset_pos] ^= 0xff;
+
+        let mut corrupt = NamedTempFile::new().unwrap();
+        corrupt.write_all(&bytes).unwrap();
+        assert_eq!(
+            SeekableArchive::open(corrupt.path()).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn test_toc_roundtrip() {
+        let frames = vec![
+            ZstdFrame {
+                compressed_size: 10,
+                decompressed_size: 100,
+            },
+            ZstdFrame {
+                compressed_size: 20,
+                decompressed_size: 40,
+            },
+        ];
+        let mut toc = SeekableArchiveToc::default();
+        toc.set_meta_len(1024);
+        toc.push_entry("accounts/1.0", 1024, 10, 2048);
+
+        let mut encoded = Vec::new();
+        write_toc(&mut encoded, &frames, &toc).unwrap();
+        let payload = &encoded[SKIPPABLE_FRAME_HEADER_LEN as usize..];
+        let toc_len = payload.len() - TOC_FOOTER_LEN as usize;
+        assert_eq!(
+            SeekableArchiveToc::decode(&payload[..toc_len]).unwrap(),
+            (frames, toc)
+        );
+        assert!(SeekableArchiveToc::decode(&payload[..toc_len - 1]).is_err());
+    }
+}
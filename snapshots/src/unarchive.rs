@@ -2,14 +2,16 @@ use {
     crate::{
         ArchiveFormat, ArchiveFormatDecompressor,
         error::SnapshotError,
-        hardened_unpack::{self, UnpackError},
+        hardened_unpack::{self, UnpackError, UnpackTotals},
+        seekable_archive::SeekableArchive,
     },
     agave_fs::{FileInfo, buffered_reader, file_io::file_creator, io_setup::IoSetupState},
     bzip2::bufread::BzDecoder,
     crossbeam_channel::{SendError, Sender},
     std::{
-        fs,
+        fs::{self, File},
         io::{self, BufRead, BufReader},
+        num::NonZeroUsize,
         path::{Path, PathBuf},
         sync::OnceLock,
         thread::{self, Scope, ScopedJoinHandle},
@@ -25,6 +27,8 @@ const MAX_SNAPSHOT_READER_BUF_SIZE: usize = 128 * 1024 * 1024;
 // - Large files: their data may accumulate in backlog buffers while waiting for file open
 //   operations to complete.
 const MAX_UNPACK_WRITE_BUF_SIZE: usize = 512 * 1024 * 1024;
+// Max number of threads unpacking partitions of a seekable archive concurrently
+const MAX_SEEKABLE_UNPACK_THREADS: usize = 8;
 
 /// Streams unpacked files across channel
 pub fn streaming_unarchive_snapshot<'scope, 'env: 'scope>(
@@ -37,6 +41,15 @@ pub fn streaming_unarchive_snapshot<'scope, 'env: 'scope>(
     io_setup: &'env IoSetupState,
 ) -> ScopedJoinHandle<'scope, Result<(), SnapshotError>> {
     let do_unpack = move |archive_path: &Path| {
+        if let ArchiveFormat::TarZstdSeekable { .. } = archive_format {
+            return unpack_seekable_snapshot(
+                archive_path,
+                file_sender,
+                &ledger_dir,
+                &account_paths,
+                io_setup,
+            );
+        }
         let first_failed_send = OnceLock::<PathBuf>::new();
         let first_failed_send_ref = &first_failed_send;
         let (decompressor, file_creator) = {
@@ -50,17 +63,11 @@ pub fn streaming_unarchive_snapshot<'scope, 'env: 'scope>(
                 decompressed_tar_reader(archive_format, archive_path, read_buf_size, io_setup)?;
             (
                 decompressor,
-                file_creator(write_buf_size, io_setup, move |file_info| {
-                    match file_sender.send(file_info) {
-                        // Channel owns the file now — don't pass it back for closing.
-                        Ok(()) => None,
-                        Err(SendError(FileInfo { file, path, .. })) => {
-                            let _ = first_failed_send_ref.set(path);
-                            // Hand the file back so the creator closes it.
-                            Some(file)
-                        }
-                    }
-                })?,
+                file_creator(
+                    write_buf_size,
+                    io_setup,
+                    send_unpacked_file(file_sender, first_failed_send_ref),
+                )?,
             )
         };
 
@@ -69,6 +76,7 @@ pub fn streaming_unarchive_snapshot<'scope, 'env: 'scope>(
             file_creator,
             ledger_dir.as_path(),
             &account_paths,
+            &UnpackTotals::new_for_snapshot(),
         )
         .map(|()| first_failed_send.into_inner())
     };
@@ -84,6 +92,91 @@ pub fn streaming_unarchive_snapshot<'scope, 'env: 'scope>(
         .unwrap()
 }
 
+/// Unpacks a seekable snapshot archive, splitting it into partitions that are decompressed and
+/// unpacked in parallel
+///
+/// Returns the path of the first file that could not be sent, if any.
+fn unpack_seekable_snapshot(
+    archive_path: &Path,
+    file_sender: Sender<FileInfo>,
+    ledger_dir: &Path,
+    account_paths: &[PathBuf],
+    io_setup: &IoSetupState,
+) -> Result<Option<PathBuf>, UnpackError> {
+    let archive = SeekableArchive::open(archive_path)?;
+    hardened_unpack::check_seekable_snapshot_archive(&archive)?;
+
+    let num_threads = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(MAX_SEEKABLE_UNPACK_THREADS);
+    let partitions = archive.partitions(num_threads);
+    // Split the write buffer budget between partitions, bounded like for streamed archives
+    let archive_size = fs::metadata(archive_path)?.len() as usize;
+    let write_buf_size = MAX_UNPACK_WRITE_BUF_SIZE
+        .min(archive_size)
+        .saturating_div(partitions.len());
+
+    // The TOC was only checked up front, enforce the limits on the entries actually unpacked
+    // across all partitions as well
+    let totals = UnpackTotals::new_for_snapshot();
+    let first_failed_send = OnceLock::<PathBuf>::new();
+    thread::scope(|scope| {
+        let handles: Vec<_> = partitions
+            .into_iter()
+            .enumerate()
+            .map(|(index, partition)| {
+                let archive = &archive;
+                let file_sender = file_sender.clone();
+                let first_failed_send = &first_failed_send;
+                let totals = &totals;
+                thread::Builder::new()
+                    .name(format!("solTarUnpack{index:02}"))
+                    .spawn_scoped(scope, move || -> Result<(), UnpackError> {
+                        let reader = archive.range_reader(partition)?;
+                        let file_creator = file_creator(
+                            write_buf_size,
+                            io_setup,
+                            send_unpacked_file(file_sender, first_failed_send),
+                        )?;
+                        hardened_unpack::streaming_unpack_snapshot(
+                            reader,
+                            file_creator,
+                            ledger_dir,
+                            account_paths,
+                            totals,
+                        )
+                    })
+                    .unwrap()
+            })
+            .collect();
+        handles.into_iter().try_for_each(|handle| {
+            handle
+                .join()
+                .expect("must join seekable archive unpack thread")
+        })
+    })?;
+    Ok(first_failed_send.into_inner())
+}
+
+/// Returns a `file_creator` completion callback that sends unpacked files through `file_sender`,
+/// recording the path of the first file that failed to send in `first_failed_send`
+fn send_unpacked_file(
+    file_sender: Sender<FileInfo>,
+    first_failed_send: &OnceLock<PathBuf>,
+) -> impl FnMut(FileInfo) -> Option<File> + '_ {
+    move |file_info| {
+        match file_sender.send(file_info) {
+            // Channel owns the file now — don't pass it back for closing.
+            Ok(()) => None,
+            Err(SendError(FileInfo { file, path, .. })) => {
+                let _ = first_failed_send.set(path);
+                // Hand the file back so the creator closes it.
+                Some(file)
+            }
+        }
+    }
+}
+
 pub fn unpack_genesis_archive(
     archive_filename: &Path,
     destination_dir: &Path,
@@ -123,3 +216,72 @@ fn decompressed_tar_reader(
     let buf_reader = buffered_reader::large_file_buf_reader(archive_path, buf_size, io_setup)?;
     ArchiveFormatDecompressor::new(archive_format, buf_reader)
 }
+
+#[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            ZstdConfig,
+            seekable_archive::tests::{test_storages, write_archive},
+        },
+        crossbeam_channel::unbounded,
+        std::collections::HashMap,
+        tempfile::TempDir,
+    };
+
+    /// Unpacks the snapshot archive at `archive_path` as `archive_format` and returns the contents
+    /// of the unpacked files by their path relative to the unpack directory
+    fn unpack_snapshot(
+        archive_path: &Path,
+        archive_format: ArchiveFormat,
+    ) -> HashMap<PathBuf, Vec<u8>> {
+        let unpack_dir = TempDir::new().unwrap();
+        let io_setup = IoSetupState::default();
+        let (file_sender, file_receiver) = unbounded();
+        thread::scope(|scope| {
+            streaming_unarchive_snapshot(
+                scope,
+                file_sender,
+                vec![unpack_dir.path().join("accounts")],
+                unpack_dir.path().to_path_buf(),
+                archive_path.to_path_buf(),
+                archive_format,
+                &io_setup,
+            )
+            .join()
+            .unwrap()
+            .unwrap();
+        });
+        file_receiver
+            .into_iter()
+            .map(|file_info| {
+                let contents = fs::read(&file_info.path).unwrap();
+                let path = file_info.path.strip_prefix(unpack_dir.path()).unwrap();
+                (path.to_path_buf(), contents)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_seekable_unpack_matches_sequential_unpack() {
+        let storages = test_storages();
+        let archive = write_archive(&storages);
+        let config = ZstdConfig::default();
+
+        let unpacked_in_parallel =
+            unpack_snapshot(archive.path(), ArchiveFormat::TarZstdSeekable { config });
+        // Seekable archives are regular zstd streams as well, so they can be unpacked sequentially
+        let unpacked_sequentially =
+            unpack_snapshot(archive.path(), ArchiveFormat::TarZstd { config });
+
+        // the version file and all storages
+        assert_eq!(unpacked_in_parallel.len(), storages.len() + 1);
+        for (path, data) in &storages {
+            let path = Path::new(path);
+            assert_eq!(unpacked_in_parallel.get(path), Some(data));
+        }
+        assert_eq!(unpacked_in_parallel, unpacked_sequentially);
+    }
+}
@@ -22,8 +22,7 @@ use {
     solana_pubkey::Pubkey,
     solana_rent::Rent,
     solana_sdk_ids::{
-        bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, loader_v4, native_loader,
-        sysvar::{self, slot_history},
+        bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, loader_v4, native_loader, sysvar,
     },
     solana_svm_callback::{AccountState, TransactionProcessingCallback},
     solana_svm_feature_set::SVMFeatureSet,
@@ -185,6 +184,7 @@ pub struct NoOpTransaction {
 #[cfg_attr(feature = "dev-context-only-utils", qualifiers(pub))]
 pub(crate) struct AccountLoader<'a, CB: TransactionProcessingCallback> {
     loaded_accounts: AHashMap<Pubkey, (AccountSharedData, Slot)>,
+    account_overrides: Option<&'a AccountOverrides>,
     callbacks: &'a CB,
     pub(crate) feature_set: &'a SVMFeatureSet,
 }
@@ -198,18 +198,9 @@ impl<'a, CB: TransactionProcessingCallback> AccountLoader<'a, CB> {
         feature_set: &'a SVMFeatureSet,
         capacity: usize,
     ) -> AccountLoader<'a, CB> {
-        let mut loaded_accounts = AHashMap::with_capacity(capacity);
-
-        // SlotHistory may be overridden for simulation.
-        // No other uses of AccountOverrides are expected.
-        if let Some(slot_history) =
-            account_overrides.and_then(|overrides| overrides.get(&slot_history::id()))
-        {
-            loaded_accounts.insert(slot_history::id(), (slot_history.clone(), 0));
-        }
-
         Self {
-            loaded_accounts,
+            loaded_accounts: AHashMap::with_capacity(capacity),
+            account_overrides,
             callbacks,
             feature_set,
         }
@@ -288,6 +279,13 @@ impl<'a, CB: TransactionProcessingCallback> AccountLoader<'a, CB> {
             };
 
             (option_account, false)
+        } else if let Some(account) = self
+            .account_overrides
+            .and_then(|overrides| overrides.get(account_key))
+        {
+            // Overrides are only expected for simulation, where they take the place of the
+            // accounts stored in accounts-db.
+            (Some((account.clone(), 0)), true)
         } else if let Some((account, slot)) = self.callbacks.get_account_shared_data(account_key) {
             (Some((account, slot)), true)
         } else {
@@ -1187,6 +1185,9 @@ mod tests {
         let slot_history_id = sysvar::slot_history::id();
         let account = AccountSharedData::new(42, 0, &Pubkey::default());
         account_overrides.set_slot_history(Some(account));
+        let overridden_key = Pubkey::new_unique();
+        let account = AccountSharedData::new(7, 0, &Pubkey::default());
+        account_overrides.set_account(&overridden_key, Some(account));
 
         let keypair = Keypair::new();
         let account = AccountSharedData::new(1_000_000, 0, &Pubkey::default());
@@ -1196,10 +1197,10 @@ mod tests {
         program_account.set_executable(true);
         program_account.set_owner(native_loader::id());
 
-        let instructions = vec![CompiledInstruction::new(2, &(), vec![0])];
+        let instructions = vec![CompiledInstruction::new(3, &(), vec![0])];
         let tx = Transaction::new_with_compiled_instructions(
             &[&keypair],
-            &[slot_history_id],
+            &[slot_history_id, overridden_key],
             Hash::default(),
             vec![bpf_loader::id()],
             instructions,
@@ -1208,6 +1209,10 @@ mod tests {
         let loaded_accounts = load_accounts_no_store(
             &[
                 (keypair.pubkey(), account),
+                (
+                    overridden_key,
+                    AccountSharedData::new(1, 0, &Pubkey::default()),
+                ),
                 (bpf_loader::id(), program_account),
             ],
             tx,
@@ -1218,6 +1223,8 @@ mod tests {
                 assert_eq!(loaded_transaction.accounts[0].0, keypair.pubkey());
                 assert_eq!(loaded_transaction.accounts[1].0, slot_history_id);
                 assert_eq!(loaded_transaction.accounts[1].1.lamports(), 42);
+                assert_eq!(loaded_transaction.accounts[2].0, overridden_key);
+                assert_eq!(loaded_transaction.accounts[2].1.lamports(), 7);
             }
             TransactionLoadResult::FeesOnly(fees_only_tx) => panic!("{}", fees_only_tx.load_error),
             TransactionLoadResult::NoOp(no_op_tx) => panic!("{}", no_op_tx.validation_error),
@@ -1225,6 +1232,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_overrides_are_loaded_lazily() {
+        let overridden_key = Pubkey::new_unique();
+        let unused_key = Pubkey::new_unique();
+        let mut account_overrides = AccountOverrides::default();
+        account_overrides.set_account(
+            &overridden_key,
+            Some(AccountSharedData::new(7, 0, &Pubkey::default())),
+        );
+        account_overrides.set_account(
+            &unused_key,
+            Some(AccountSharedData::new(8, 0, &Pubkey::default())),
+        );
+
+        let mut accounts_map = HashMap::new();
+        accounts_map.insert(
+            overridden_key,
+            (AccountSharedData::new(1, 0, &Pubkey::default()), 1),
+        );
+        let callbacks = TestCallbacks {
+            accounts_map,
+            ..Default::default()
+        };
+        let feature_set = SVMFeatureSet::all_enabled();
+        let mut account_loader = AccountLoader::new_with_loaded_accounts_capacity(
+            Some(&account_overrides),
+            &callbacks,
+            &feature_set,
+            0,
+        );
+        assert!(account_loader.loaded_accounts.is_empty());
+
+        let account = account_loader.load_account(&overridden_key).unwrap();
+        assert_eq!(account.lamports(), 7);
+        assert!(account_loader.loaded_accounts.contains_key(&overridden_key));
+        assert!(!account_loader.loaded_accounts.contains_key(&unused_key));
+    }
+
     #[test]
     fn test_increase_calculated_data_size() {
         let mut error_metrics = TransactionErrorMetrics::default();
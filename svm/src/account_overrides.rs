@@ -1,5 +1,8 @@
 use {
-    solana_account::AccountSharedData, solana_pubkey::Pubkey, solana_sdk_ids::sysvar,
+    crate::account_loader::PROGRAM_OWNERS,
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::sysvar,
     std::collections::HashMap,
 };
 
@@ -13,7 +16,7 @@ pub struct AccountOverrides {
 
 impl AccountOverrides {
     /// Insert or remove an account with a given pubkey to/from the list of overrides.
-    fn set_account(&mut self, pubkey: &Pubkey, account: Option<AccountSharedData>) {
+    pub fn set_account(&mut self, pubkey: &Pubkey, account: Option<AccountSharedData>) {
         match account {
             Some(account) => self.accounts.insert(*pubkey, account),
             None => self.accounts.remove(pubkey),
@@ -31,13 +34,23 @@ impl AccountOverrides {
     pub(crate) fn get(&self, pubkey: &Pubkey) -> Option<&AccountSharedData> {
         self.accounts.get(pubkey)
     }
+
+    /// Whether any of the overridden accounts is owned by a program loader, in which case the
+    /// programs in the global program cache may not match the overridden state
+    pub(crate) fn contains_program_accounts(&self) -> bool {
+        self.accounts
+            .values()
+            .any(|account| PROGRAM_OWNERS.contains(account.owner()))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use {
-        crate::account_overrides::AccountOverrides, solana_account::AccountSharedData,
-        solana_pubkey::Pubkey, solana_sdk_ids::sysvar,
+        crate::account_overrides::AccountOverrides,
+        solana_account::AccountSharedData,
+        solana_pubkey::Pubkey,
+        solana_sdk_ids::{bpf_loader_upgradeable, sysvar},
     };
 
     #[test]
@@ -62,4 +75,23 @@ mod test {
 
         assert_eq!(accounts.get(&sysvar::slot_history::id()), Some(&data));
     }
+
+    #[test]
+    fn test_contains_program_accounts() {
+        let mut accounts = AccountOverrides::default();
+        assert!(!accounts.contains_program_accounts());
+
+        accounts.set_slot_history(Some(AccountSharedData::default()));
+        assert!(!accounts.contains_program_accounts());
+
+        let key = Pubkey::new_unique();
+        accounts.set_account(
+            &key,
+            Some(AccountSharedData::new(1, 0, &bpf_loader_upgradeable::id())),
+        );
+        assert!(accounts.contains_program_accounts());
+
+        accounts.set_account(&key, None);
+        assert!(!accounts.contains_program_accounts());
+    }
 }
@@ -118,6 +118,9 @@ pub struct TransactionProcessingConfig<'a> {
     /// Encapsulates overridden accounts, typically used for transaction
     /// simulation.
     pub account_overrides: Option<&'a AccountOverrides>,
+    /// Sysvar cache to execute against instead of the processor's own,
+    /// typically used for transaction simulation with overridden sysvars.
+    pub sysvar_cache_override: Option<&'a SysvarCache>,
     /// Whether or not to check a program's deployment slot when replenishing
     /// a program cache instance.
     pub check_program_deployment_slot: bool,
@@ -148,6 +151,14 @@ pub struct TransactionProcessingConfig<'a> {
     ///
     /// This is a leader-side filtering policy. It must not be enabled for replay.
     pub drop_noop_transactions: bool,
+    /// Record an execution trace of every program invocation, typically used for transaction
+    /// simulation.
+    ///
+    /// Programs are then loaded for the execution environment outside of the global program
+    /// cache, so that an environment with register tracing enabled can be used to record the
+    /// executed sBPF instructions without evicting the cached programs. The execution
+    /// environment must have register tracing enabled for the program counters to be recorded.
+    pub enable_execution_tracing: bool,
 }
 
 /// Runtime environment for transaction batch processing.
@@ -443,6 +454,16 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             .enable_transaction_balance_recording
             .then(|| BalanceCollector::new_with_transaction_count(sanitized_txs.len()));
 
+        debug_assert!(
+            !config.enable_execution_tracing
+                || environment
+                    .program_runtime_environments
+                    .get_env_for_execution()
+                    .get_config()
+                    .enable_register_tracing,
+            "execution tracing requires an environment with register tracing enabled"
+        );
+
         // Clone the batch-local program cache (builtins already populated in new_from()).
         // User-deployed programs are loaded per-transaction via replenish_program_cache
         // in the transaction loop below.
@@ -462,7 +483,14 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         }
 
         let (mut load_us, mut execution_us): (u64, u64) = (0, 0);
-        let sysvar_cache = self.sysvar_cache();
+        let processor_sysvar_cache;
+        let sysvar_cache = match config.sysvar_cache_override {
+            Some(sysvar_cache) => sysvar_cache,
+            None => {
+                processor_sysvar_cache = self.sysvar_cache();
+                &*processor_sysvar_cache
+            }
+        };
 
         // Validate, execute, and collect results from each transaction in order.
         // With SIMD83, transactions must be executed in order, because transactions
@@ -542,18 +570,36 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                         filter_executable_us,
                     );
 
+                    // Overridden program accounts would be shadowed by the programs cached
+                    // for the bank, so those are loaded from the overridden state instead
+                    let bypass_program_cache = config.enable_execution_tracing
+                        || config
+                            .account_overrides
+                            .is_some_and(AccountOverrides::contains_program_accounts);
                     let ((), program_cache_us) = measure_us!({
-                        self.replenish_program_cache(
-                            &account_loader,
-                            missing_programs,
-                            environment
-                                .program_runtime_environments
-                                .get_env_for_execution(),
-                            &mut program_cache_for_tx_batch,
-                            &mut execute_timings,
-                            config.limit_to_load_programs,
-                            true, // increment_usage_counter
-                        );
+                        if bypass_program_cache {
+                            self.load_programs_bypassing_cache(
+                                &account_loader,
+                                missing_programs,
+                                environment
+                                    .program_runtime_environments
+                                    .get_env_for_execution(),
+                                &mut program_cache_for_tx_batch,
+                                &mut execute_timings,
+                            );
+                        } else {
+                            self.replenish_program_cache(
+                                &account_loader,
+                                missing_programs,
+                                environment
+                                    .program_runtime_environments
+                                    .get_env_for_execution(),
+                                &mut program_cache_for_tx_batch,
+                                &mut execute_timings,
+                                config.limit_to_load_programs,
+                                true, // increment_usage_counter
+                            );
+                        }
                     });
                     execute_timings.saturating_add_in_place(
                         ExecuteTimingType::ProgramCacheUs,
@@ -576,7 +622,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                     let executed_tx = self.execute_loaded_transaction(
                         callbacks,
                         tx,
-                        &sysvar_cache,
+                        sysvar_cache,
                         loaded_transaction,
                         &mut execute_timings,
                         &mut error_metrics,
@@ -971,6 +1017,41 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         }
     }
 
+    /// Loads the missing programs into the batch-local program cache without going through the
+    /// global program cache, so that programs compiled for a non-standard environment neither
+    /// evict nor get mixed up with its entries.
+    fn load_programs_bypassing_cache<CB: TransactionProcessingCallback>(
+        &self,
+        account_loader: &AccountLoader<CB>,
+        missing_programs: Vec<ProgramToLoad>,
+        program_runtime_environment_for_execution: &ProgramRuntimeEnvironment,
+        program_cache_for_tx_batch: &mut ProgramCacheForTxBatch,
+        execute_timings: &mut ExecuteTimings,
+    ) {
+        for ProgramToLoad { program_id, .. } in missing_programs {
+            let Some((program, _last_modification_slot)) = load_program_with_pubkey(
+                account_loader,
+                program_runtime_environment_for_execution,
+                program_id,
+                self.slot,
+                execute_timings,
+            ) else {
+                continue;
+            };
+            // Mirror the global cache, which hides programs deployed too recently
+            let program = if self.slot < program.effective_slot() {
+                Arc::new(ProgramCacheEntry::new_delay_visibility_tombstone(
+                    program.deployment_slot,
+                    program.account_owner,
+                    Arc::clone(&program.stats),
+                ))
+            } else {
+                program
+            };
+            program_cache_for_tx_batch.replenish(*program_id, program);
+        }
+    }
+
     /// Similar to replenish_program_cache() but only used in Bank::prepare_program_cache_for_upcoming_feature_set().
     pub fn prepare_one_program_for_upcoming_feature_set<CB: TransactionProcessingCallback>(
         &self,
@@ -1108,6 +1189,9 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             compute_budget,
             self.execution_cost,
         );
+        if config.enable_execution_tracing {
+            invoke_context.enable_execution_trace();
+        }
 
         let mut process_message_time = Measure::start("process_message_time");
         let process_result = invoke_context
@@ -1115,6 +1199,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             .map_err(|(index, err)| TransactionError::InstructionError(index, err));
         process_message_time.stop();
 
+        let execution_trace = invoke_context.take_execution_trace();
         drop(invoke_context);
 
         execute_timings.execute_accessories.process_message_us += process_message_time.as_us();
@@ -1225,6 +1310,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                 return_data,
                 executed_units,
                 accounts_deltas,
+                execution_trace,
             },
             loaded_transaction,
             programs_modified_by_tx: program_cache_for_tx_batch.drain_modified_entries(),
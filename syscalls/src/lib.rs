@@ -316,6 +316,37 @@ pub fn create_program_runtime_environment(
     compute_budget: &SVMTransactionExecutionBudget,
     reject_deployment_of_broken_elfs: bool,
     debugging_features: bool,
+) -> Result<ProgramRuntimeEnvironment, Error> {
+    create_program_runtime_environment_with_register_tracing(
+        feature_set,
+        compute_budget,
+        reject_deployment_of_broken_elfs,
+        debugging_features,
+        debugging_features,
+    )
+}
+
+/// Creates the program runtime environment for recording execution traces, in which the VM
+/// records the registers of every executed instruction
+pub fn create_program_runtime_environment_for_tracing(
+    feature_set: &SVMFeatureSet,
+    compute_budget: &SVMTransactionExecutionBudget,
+) -> Result<ProgramRuntimeEnvironment, Error> {
+    create_program_runtime_environment_with_register_tracing(
+        feature_set,
+        compute_budget,
+        false, /* reject_deployment_of_broken_elfs */
+        false, /* debugging_features */
+        true,  /* enable_register_tracing */
+    )
+}
+
+fn create_program_runtime_environment_with_register_tracing(
+    feature_set: &SVMFeatureSet,
+    compute_budget: &SVMTransactionExecutionBudget,
+    reject_deployment_of_broken_elfs: bool,
+    debugging_features: bool,
+    enable_register_tracing: bool,
 ) -> Result<ProgramRuntimeEnvironment, Error> {
     let enable_alt_bn128_syscall = feature_set.enable_alt_bn128_syscall;
     let enable_alt_bn128_compression_syscall = feature_set.enable_alt_bn128_compression_syscall;
@@ -347,7 +378,7 @@ pub fn create_program_runtime_environment(
         enable_stack_frame_gaps: !feature_set.virtual_address_space_adjustments,
         instruction_meter_checkpoint_distance: 10000,
         enable_instruction_meter: true,
-        enable_register_tracing: debugging_features,
+        enable_register_tracing,
         enable_symbol_and_section_labels: debugging_features,
         reject_broken_elfs: reject_deployment_of_broken_elfs,
         noop_instruction_rate: 256,
@@ -1,6 +1,9 @@
 use {
     agave_votor::{
-        event::VotorEvent, vote_history::VoteHistory, vote_history_storage::VoteHistoryStorage,
+        consensus_timeline::{ReceivedVotes, SlotTimeline, TimelineEvent, TimelineEventKind},
+        event::VotorEvent,
+        vote_history::VoteHistory,
+        vote_history_storage::VoteHistoryStorage,
     },
     crossbeam_channel::Sender,
     jsonrpc_core::{BoxFuture, ErrorCode, MetaIoHandler, Metadata, Result},
@@ -18,7 +21,13 @@ use {
             BankingControlMsg, BankingStage,
             transaction_scheduler::scheduler_controller::SchedulerConfig,
         },
-        consensus::{Tower, tower_storage::TowerStorage},
+        consensus::{
+            Tower,
+            fork_tree_snapshot::{
+                ForkTreeNode, ForkTreeSnapshot, TowerLockoutSnapshot,
+            },
+            tower_storage::TowerStorage,
+        },
         repair::repair_service,
         validator::{
             BlockProductionMethod, SchedulerPacing, TransactionStructure, ValidatorStartProgress,
@@ -30,6 +39,8 @@ use {
     solana_keypair::{Keypair, read_keypair_file},
     solana_metrics::{datapoint_info, datapoint_warn},
     solana_pubkey::Pubkey,
+    solana_rpc::equivocation_proofs::get_equivocation_proofs,
+    solana_rpc_client_api::response::RpcEquivocationProof,
     solana_runtime::{bank::VATHealthError, snapshot_controller::SnapshotController},
     solana_signer::Signer,
     solana_validator_exit::Exit,
@@ -50,6 +61,9 @@ use {
     tokio::runtime::Runtime,
 };
 
+/// How long to wait for replay to answer a fork tree snapshot request
+const FORK_TREE_SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(Clone)]
 pub struct AdminRpcRequestMetadata {
     pub rpc_addr: Option<SocketAddr>,
@@ -124,6 +138,225 @@ pub struct AdminRpcValidatorAdmissionTicketStatus {
     pub next_epoch_vat_failure_reason: Option<VATHealthError>,
 }
 
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AdminRpcSlotTimeline {
+    pub slot: Slot,
+    pub events: Vec<AdminRpcTimelineEvent>,
+    pub received_votes: Vec<AdminRpcReceivedVotes>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AdminRpcTimelineEvent {
+    /// Microseconds since the unix epoch
+    pub timestamp_us: u64,
+    pub event: String,
+    /// The vote or certificate type, or which timeout fired
+    pub detail: Option<String>,
+    pub block_id: Option<String>,
+    pub parent_slot: Option<Slot>,
+    pub stake: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AdminRpcReceivedVotes {
+    pub vote: String,
+    pub block_id: Option<String>,
+    pub count: u64,
+    pub stake: u64,
+    /// Microseconds since the unix epoch
+    pub first_received_us: u64,
+    /// Microseconds since the unix epoch
+    pub last_received_us: u64,
+}
+
+impl From<SlotTimeline> for AdminRpcSlotTimeline {
+    fn from(slot_timeline: SlotTimeline) -> Self {
+        Self {
+            slot: slot_timeline.slot,
+            events: slot_timeline
+                .events
+                .into_iter()
+                .map(AdminRpcTimelineEvent::from)
+                .collect(),
+            received_votes: slot_timeline
+                .received_votes
+                .into_iter()
+                .map(AdminRpcReceivedVotes::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<TimelineEvent> for AdminRpcTimelineEvent {
+    fn from(TimelineEvent { timestamp_us, kind }: TimelineEvent) -> Self {
+        let event = Self {
+            timestamp_us,
+            event: String::new(),
+            detail: None,
+            block_id: None,
+            parent_slot: None,
+            stake: None,
+        };
+        match kind {
+            TimelineEventKind::FirstShred => Self {
+                event: "first_shred".to_string(),
+                ..event
+            },
+            TimelineEventKind::BlockReplayed { block_id } => Self {
+                event: "block_replayed".to_string(),
+                block_id: Some(block_id.to_string()),
+                ..event
+            },
+            TimelineEventKind::VoteSent {
+                vote,
+                block_id,
+                stake,
+            } => Self {
+                event: "vote_sent".to_string(),
+                detail: Some(vote.as_str().to_string()),
+                block_id: block_id.map(|block_id| block_id.to_string()),
+                stake: Some(stake),
+                ..event
+            },
+            TimelineEventKind::CertificateFormed {
+                certificate,
+                block_id,
+            } => Self {
+                event: "certificate_formed".to_string(),
+                detail: Some(certificate.as_str().to_string()),
+                block_id: block_id.map(|block_id| block_id.to_string()),
+                ..event
+            },
+            TimelineEventKind::Timeout { crashed_leader } => Self {
+                event: "timeout".to_string(),
+                detail: Some(if crashed_leader { "crashed_leader" } else { "skip" }.to_string()),
+                ..event
+            },
+            TimelineEventKind::ParentReady {
+                parent_slot,
+                parent_block_id,
+            } => Self {
+                event: "parent_ready".to_string(),
+                block_id: Some(parent_block_id.to_string()),
+                parent_slot: Some(parent_slot),
+                ..event
+            },
+        }
+    }
+}
+
+impl From<ReceivedVotes> for AdminRpcReceivedVotes {
+    fn from(received_votes: ReceivedVotes) -> Self {
+        Self {
+            vote: received_votes.vote.as_str().to_string(),
+            block_id: received_votes
+                .block_id
+                .map(|block_id| block_id.to_string()),
+            count: received_votes.count,
+            stake: received_votes.stake,
+            first_received_us: received_votes.first_received_us,
+            last_received_us: received_votes.last_received_us,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AdminRpcForkTree {
+    pub root: Slot,
+    pub heaviest_slot: Option<Slot>,
+    pub last_voted_slot: Option<Slot>,
+    pub tower: Vec<AdminRpcTowerLockout>,
+    pub nodes: Vec<AdminRpcForkTreeNode>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AdminRpcTowerLockout {
+    pub slot: Slot,
+    pub confirmation_count: u32,
+    pub last_locked_out_slot: Slot,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AdminRpcForkTreeNode {
+    pub slot: Slot,
+    pub parent_slot: Option<Slot>,
+    pub bank_hash: Option<String>,
+    pub block_id: Option<String>,
+    pub leader: String,
+    pub is_frozen: bool,
+    pub is_dead: bool,
+    pub is_hard_dead: bool,
+    pub stake_voted_at: Option<u64>,
+    pub stake_voted_subtree: Option<u64>,
+    pub vote_count: usize,
+    pub is_duplicate_confirmed: bool,
+    pub is_unconfirmed_duplicate: bool,
+    pub latest_invalid_ancestor: Option<Slot>,
+    pub is_locked_out: bool,
+    pub passed_vote_threshold: bool,
+    pub has_voted: bool,
+    pub is_propagated: Option<bool>,
+    pub fork_stake: u64,
+    pub total_stake: u64,
+}
+
+impl From<ForkTreeSnapshot> for AdminRpcForkTree {
+    fn from(snapshot: ForkTreeSnapshot) -> Self {
+        Self {
+            root: snapshot.root,
+            heaviest_slot: snapshot.heaviest_slot,
+            last_voted_slot: snapshot.last_voted_slot,
+            tower: snapshot
+                .tower
+                .into_iter()
+                .map(AdminRpcTowerLockout::from)
+                .collect(),
+            nodes: snapshot
+                .nodes
+                .into_iter()
+                .map(AdminRpcForkTreeNode::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<TowerLockoutSnapshot> for AdminRpcTowerLockout {
+    fn from(lockout: TowerLockoutSnapshot) -> Self {
+        Self {
+            slot: lockout.slot,
+            confirmation_count: lockout.confirmation_count,
+            last_locked_out_slot: lockout.last_locked_out_slot,
+        }
+    }
+}
+
+impl From<ForkTreeNode> for AdminRpcForkTreeNode {
+    fn from(node: ForkTreeNode) -> Self {
+        Self {
+            slot: node.slot,
+            parent_slot: node.parent_slot,
+            bank_hash: node.bank_hash.map(|hash| hash.to_string()),
+            block_id: node.block_id.map(|block_id| block_id.to_string()),
+            leader: node.leader.to_string(),
+            is_frozen: node.is_frozen,
+            is_dead: node.is_dead,
+            is_hard_dead: node.is_hard_dead,
+            stake_voted_at: node.stake_voted_at,
+            stake_voted_subtree: node.stake_voted_subtree,
+            vote_count: node.vote_count,
+            is_duplicate_confirmed: node.is_duplicate_confirmed,
+            is_unconfirmed_duplicate: node.is_unconfirmed_duplicate,
+            latest_invalid_ancestor: node.latest_invalid_ancestor,
+            is_locked_out: node.is_locked_out,
+            passed_vote_threshold: node.passed_vote_threshold,
+            has_voted: node.has_voted,
+            is_propagated: node.is_propagated,
+            fork_stake: node.fork_stake,
+            total_stake: node.total_stake,
+        }
+    }
+}
+
 impl From<ContactInfo> for AdminRpcContactInfo {
     fn from(node: ContactInfo) -> Self {
         macro_rules! unwrap_socket {
@@ -211,6 +444,14 @@ pub trait AdminRpc {
     #[rpc(meta, name = "listPlugins")]
     fn list_plugins(&self, meta: Self::Metadata) -> BoxFuture<Result<Vec<String>>>;
 
+    #[rpc(meta, name = "reloadPluginFilter")]
+    fn reload_plugin_filter(
+        &self,
+        meta: Self::Metadata,
+        name: String,
+        config_file: String,
+    ) -> BoxFuture<Result<()>>;
+
     #[rpc(meta, name = "rpcAddress")]
     fn rpc_addr(&self, meta: Self::Metadata) -> Result<Option<SocketAddr>>;
 
@@ -314,6 +555,24 @@ pub trait AdminRpc {
 
     #[rpc(meta, name = "blockstorePurge")]
     fn blockstore_purge(&self, meta: Self::Metadata, maximum_purge_slot: Slot) -> Result<()>;
+
+    #[rpc(meta, name = "equivocationProofs")]
+    fn equivocation_proofs(
+        &self,
+        meta: Self::Metadata,
+        start_slot: Option<Slot>,
+    ) -> Result<Vec<RpcEquivocationProof>>;
+
+    #[rpc(meta, name = "consensusTimeline")]
+    fn consensus_timeline(
+        &self,
+        meta: Self::Metadata,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> Result<Vec<AdminRpcSlotTimeline>>;
+
+    #[rpc(meta, name = "forkTree")]
+    fn fork_tree(&self, meta: Self::Metadata) -> Result<AdminRpcForkTree>;
 }
 
 pub struct AdminRpcImpl;
@@ -527,6 +786,40 @@ impl AdminRpc for AdminRpcImpl {
         })
     }
 
+    fn reload_plugin_filter(
+        &self,
+        meta: Self::Metadata,
+        name: String,
+        config_file: String,
+    ) -> BoxFuture<Result<()>> {
+        Box::pin(async move {
+            // Construct channel for plugin to respond to this particular rpc request instance
+            let (response_sender, response_receiver) = oneshot_channel();
+
+            // Send request to plugin manager if there is a geyser service
+            if let Some(ref rpc_to_manager_sender) = meta.rpc_to_plugin_manager_sender {
+                rpc_to_manager_sender
+                    .send(GeyserPluginManagerRequest::ReloadPluginFilter {
+                        name,
+                        config_file,
+                        response_sender,
+                    })
+                    .expect("GeyerPluginService should never drop request receiver");
+            } else {
+                return Err(jsonrpc_core::Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: "No geyser plugin service".to_string(),
+                    data: None,
+                });
+            }
+
+            // Await response from plugin manager
+            response_receiver
+                .await
+                .expect("GeyerPluginService's oneshot sender shouldn't drop early")
+        })
+    }
+
     fn rpc_addr(&self, meta: Self::Metadata) -> Result<Option<SocketAddr>> {
         debug!("rpc_addr admin rpc request received");
         Ok(meta.rpc_addr)
@@ -920,6 +1213,58 @@ impl AdminRpc for AdminRpcImpl {
                 })
         })
     }
+
+    fn equivocation_proofs(
+        &self,
+        meta: Self::Metadata,
+        start_slot: Option<Slot>,
+    ) -> Result<Vec<RpcEquivocationProof>> {
+        debug!("equivocation_proofs rpc request received: {start_slot:?}");
+        meta.with_post_init(|post_init| {
+            get_equivocation_proofs(&post_init.blockstore, start_slot.unwrap_or_default()).map_err(
+                |err| jsonrpc_core::Error {
+                    code: ErrorCode::InternalError,
+                    message: format!("{err}"),
+                    data: None,
+                },
+            )
+        })
+    }
+
+    fn consensus_timeline(
+        &self,
+        meta: Self::Metadata,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> Result<Vec<AdminRpcSlotTimeline>> {
+        debug!("consensus_timeline rpc request received: {start_slot:?}..={end_slot:?}");
+        meta.with_post_init(|post_init| {
+            Ok(post_init
+                .consensus_timeline
+                .get(
+                    start_slot.unwrap_or_default(),
+                    end_slot.unwrap_or(Slot::MAX),
+                )
+                .into_iter()
+                .map(AdminRpcSlotTimeline::from)
+                .collect())
+        })
+    }
+
+    fn fork_tree(&self, meta: Self::Metadata) -> Result<AdminRpcForkTree> {
+        debug!("fork_tree rpc request received");
+        meta.with_post_init(|post_init| {
+            post_init
+                .fork_tree_snapshot_requester
+                .request(FORK_TREE_SNAPSHOT_TIMEOUT)
+                .map(AdminRpcForkTree::from)
+                .map_err(|err| jsonrpc_core::Error {
+                    code: ErrorCode::InternalError,
+                    message: format!("{err}"),
+                    data: None,
+                })
+        })
+    }
 }
 
 impl AdminRpcImpl {
@@ -1149,7 +1494,10 @@ mod tests {
     use {
         super::*,
         agave_snapshots::snapshot_config::SnapshotConfig,
-        agave_votor::event::VotorEventSender,
+        agave_votor::{
+            consensus_timeline::{ConsensusTimeline, DEFAULT_CONSENSUS_TIMELINE_SLOTS},
+            event::VotorEventSender,
+        },
         assert_matches::assert_matches,
         crossbeam_channel::bounded,
         serde_json::Value,
@@ -1159,10 +1507,14 @@ mod tests {
         },
         solana_core::{
             admin_rpc_post_init::{KeyUpdaterType, KeyUpdaters},
-            consensus::tower_storage::NullTowerStorage,
+            consensus::{
+                fork_tree_snapshot::{ForkTreeSnapshotRequestReceiver, ForkTreeSnapshotRequester},
+                tower_storage::NullTowerStorage,
+            },
             validator::{Validator, ValidatorConfig, ValidatorTpuConfig},
         },
         solana_gossip::{cluster_info::ClusterInfo, node::Node},
+        solana_hash::Hash,
         solana_ledger::{
             blockstore::Blockstore,
             create_new_tmp_ledger,
@@ -1190,6 +1542,7 @@ mod tests {
     struct RpcHandler {
         io: MetaIoHandler<AdminRpcRequestMetadata>,
         meta: AdminRpcRequestMetadata,
+        fork_tree_snapshot_request_receiver: ForkTreeSnapshotRequestReceiver,
     }
 
     impl RpcHandler {
@@ -1234,6 +1587,8 @@ mod tests {
                 let (votor_event_sender, _) = bounded(1024);
                 votor_event_sender
             });
+            let (fork_tree_snapshot_requester, fork_tree_snapshot_request_receiver) =
+                ForkTreeSnapshotRequester::new();
             let meta = AdminRpcRequestMetadata {
                 rpc_addr: None,
                 start_time: SystemTime::now(),
@@ -1263,6 +1618,10 @@ mod tests {
                     snapshot_controller,
                     blockstore,
                     votor_event_sender,
+                    consensus_timeline: Arc::new(ConsensusTimeline::new(
+                        DEFAULT_CONSENSUS_TIMELINE_SLOTS,
+                    )),
+                    fork_tree_snapshot_requester,
                 }))),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
                 rpc_to_plugin_manager_sender: None,
@@ -1270,7 +1629,11 @@ mod tests {
             let mut io = MetaIoHandler::default();
             io.extend_with(AdminRpcImpl.to_delegate());
 
-            Self { io, meta }
+            Self {
+                io,
+                meta,
+                fork_tree_snapshot_request_receiver,
+            }
         }
     }
 
@@ -1552,6 +1915,133 @@ mod tests {
         assert_eq!(actual_parsed_response, expected_parsed_response);
     }
 
+    #[test]
+    fn test_consensus_timeline() {
+        let rpc = RpcHandler::_start();
+        let RpcHandler { io, meta, .. } = rpc;
+        let post_init = meta.post_init.read().unwrap().clone().unwrap();
+        let block_id = Hash::new_unique();
+        for slot in [3, 4, 5] {
+            post_init
+                .consensus_timeline
+                .record(slot, 1_000 + slot, TimelineEventKind::BlockReplayed {
+                    block_id,
+                });
+        }
+        post_init.consensus_timeline.record(
+            4,
+            2_000,
+            TimelineEventKind::Timeout {
+                crashed_leader: true,
+            },
+        );
+
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"consensusTimeline","params":[4, 10]}"#;
+        let response = io.handle_request_sync(request, meta.clone());
+        let result: Value = serde_json::from_str(&response.expect("actual response"))
+            .expect("actual response deserialization");
+        let timelines: Vec<AdminRpcSlotTimeline> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+
+        assert_eq!(
+            timelines.iter().map(|t| t.slot).collect::<Vec<_>>(),
+            vec![4, 5]
+        );
+        assert_eq!(
+            timelines[0].events,
+            vec![
+                AdminRpcTimelineEvent {
+                    timestamp_us: 1_004,
+                    event: "block_replayed".to_string(),
+                    detail: None,
+                    block_id: Some(block_id.to_string()),
+                    parent_slot: None,
+                    stake: None,
+                },
+                AdminRpcTimelineEvent {
+                    timestamp_us: 2_000,
+                    event: "timeout".to_string(),
+                    detail: Some("crashed_leader".to_string()),
+                    block_id: None,
+                    parent_slot: None,
+                    stake: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fork_tree() {
+        let rpc = RpcHandler::_start();
+        let RpcHandler {
+            io,
+            meta,
+            fork_tree_snapshot_request_receiver,
+        } = rpc;
+        let bank_hash = Hash::new_unique();
+        let leader = Pubkey::new_unique();
+        let snapshot = ForkTreeSnapshot {
+            root: 0,
+            heaviest_slot: Some(2),
+            last_voted_slot: Some(2),
+            tower: vec![TowerLockoutSnapshot {
+                slot: 2,
+                confirmation_count: 1,
+                last_locked_out_slot: 4,
+            }],
+            nodes: vec![ForkTreeNode {
+                slot: 2,
+                parent_slot: Some(0),
+                bank_hash: Some(bank_hash),
+                block_id: None,
+                leader,
+                is_frozen: true,
+                is_dead: false,
+                is_hard_dead: false,
+                stake_voted_at: Some(100),
+                stake_voted_subtree: Some(100),
+                vote_count: 1,
+                is_duplicate_confirmed: false,
+                is_unconfirmed_duplicate: false,
+                latest_invalid_ancestor: None,
+                is_locked_out: false,
+                passed_vote_threshold: true,
+                has_voted: true,
+                is_propagated: None,
+                fork_stake: 100,
+                total_stake: 300,
+            }],
+        };
+        let responder = {
+            let snapshot = snapshot.clone();
+            thread::spawn(move || {
+                let response_sender = fork_tree_snapshot_request_receiver.recv().unwrap();
+                response_sender.send(snapshot).unwrap();
+                fork_tree_snapshot_request_receiver
+            })
+        };
+
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"forkTree","params":[]}"#;
+        let response = io.handle_request_sync(request, meta.clone());
+        let result: Value = serde_json::from_str(&response.expect("actual response"))
+            .expect("actual response deserialization");
+        let fork_tree: AdminRpcForkTree =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(fork_tree, AdminRpcForkTree::from(snapshot));
+        assert_eq!(fork_tree.nodes[0].bank_hash, Some(bank_hash.to_string()));
+        assert_eq!(fork_tree.nodes[0].leader, leader.to_string());
+
+        // Once replay has gone away the request fails instead of hanging
+        drop(responder.join().unwrap());
+        let response = io.handle_request_sync(request, meta);
+        let result: Value = serde_json::from_str(&response.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(
+            result["error"]["code"],
+            Value::from(ErrorCode::InternalError.code())
+        );
+    }
+
     #[test]
     fn test_is_generating_snapshots() {
         // Test with snapshots enabled
@@ -523,6 +523,7 @@ fn main() {
         rpc_bigtable_config,
         faucet_addr: Some(faucet_addr),
         account_indexes,
+        enable_simulation_tracing: true,
         ..JsonRpcConfig::default_for_test()
     });
 
@@ -74,10 +74,13 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
         .subcommand(commands::set_identity::command())
         .subcommand(commands::set_log_filter::command())
         .subcommand(commands::staked_nodes_overrides::command())
+        .subcommand(commands::vote_storage_server::command())
         .subcommand(commands::wait_for_restart_window::command())
         .subcommand(commands::set_public_address::command())
         .subcommand(commands::manage_block_production::command(default_args))
-        .subcommand(commands::blockstore::command());
+        .subcommand(commands::blockstore::command())
+        .subcommand(commands::consensus_timeline::command())
+        .subcommand(commands::fork_tree::command());
 
     commands::run::add_args(app, default_args)
         .args(&thread_args(&default_args.thread_args))
@@ -0,0 +1,267 @@
+use {
+    crate::{
+        admin_rpc_service::{self, AdminRpcSlotTimeline},
+        commands::{Error, FromClapArgMatches, Result},
+    },
+    clap::{App, Arg, ArgMatches, SubCommand, value_t},
+    serde_json::{Value, json},
+    solana_clap_utils::input_validators::is_parsable,
+    solana_clock::Slot,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+const COMMAND: &str = "consensus-timeline";
+
+/// All events are attributed to a single process, with one thread per slot
+const CHROME_TRACE_PID: u64 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimelineFormat {
+    Json,
+    ChromeTrace,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ConsensusTimelineArgs {
+    pub start_slot: Option<Slot>,
+    pub end_slot: Option<Slot>,
+    pub format: TimelineFormat,
+    pub output_file: Option<PathBuf>,
+}
+
+impl FromClapArgMatches for ConsensusTimelineArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> Result<Self> {
+        let format = match matches.value_of("format") {
+            Some("chrome-trace") => TimelineFormat::ChromeTrace,
+            _ => TimelineFormat::Json,
+        };
+        Ok(ConsensusTimelineArgs {
+            start_slot: value_t!(matches, "start_slot", Slot).ok(),
+            end_slot: value_t!(matches, "end_slot", Slot).ok(),
+            format,
+            output_file: matches.value_of("output_file").map(PathBuf::from),
+        })
+    }
+}
+
+pub fn command<'a>() -> App<'a, 'a> {
+    SubCommand::with_name(COMMAND)
+        .about("Display the consensus events the validator recorded for recent slots")
+        .arg(
+            Arg::with_name("start_slot")
+                .long("start-slot")
+                .value_name("SLOT")
+                .takes_value(true)
+                .validator(is_parsable::<Slot>)
+                .help("First slot to display [default: the lowest retained slot]"),
+        )
+        .arg(
+            Arg::with_name("end_slot")
+                .long("end-slot")
+                .value_name("SLOT")
+                .takes_value(true)
+                .validator(is_parsable::<Slot>)
+                .help("Last slot to display [default: the highest retained slot]"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(&["json", "chrome-trace"])
+                .default_value("json")
+                .help(
+                    "Output format. chrome-trace can be loaded in chrome://tracing or Perfetto, \
+                     with one track per slot",
+                ),
+        )
+        .arg(
+            Arg::with_name("output_file")
+                .long("output-file")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Write the timeline to FILE instead of stdout"),
+        )
+}
+
+pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<()> {
+    let ConsensusTimelineArgs {
+        start_slot,
+        end_slot,
+        format,
+        output_file,
+    } = ConsensusTimelineArgs::from_clap_arg_match(matches)?;
+
+    let admin_client = admin_rpc_service::connect(ledger_path);
+    let timelines = admin_rpc_service::runtime().block_on(async move {
+        admin_client
+            .await?
+            .consensus_timeline(start_slot, end_slot)
+            .await
+    })?;
+
+    let value = match format {
+        TimelineFormat::Json => json!(timelines),
+        TimelineFormat::ChromeTrace => to_chrome_trace(&timelines),
+    };
+    let output =
+        serde_json::to_string_pretty(&value).map_err(|err| Error::Dynamic(Box::new(err)))?;
+    match output_file {
+        Some(output_file) => fs::write(output_file, output)?,
+        None => println!("{output}"),
+    }
+    Ok(())
+}
+
+/// Converts `timelines` to the Chrome trace event format.
+///
+/// Each slot gets its own track. Events this node observed become instant
+/// events, received votes become a span from the first to the last vote.
+fn to_chrome_trace(timelines: &[AdminRpcSlotTimeline]) -> Value {
+    let mut trace_events = vec![];
+    for timeline in timelines {
+        trace_events.push(json!({
+            "name": "thread_name",
+            "ph": "M",
+            "pid": CHROME_TRACE_PID,
+            "tid": timeline.slot,
+            "args": { "name": format!("slot {}", timeline.slot) },
+        }));
+        for event in &timeline.events {
+            let name = match &event.detail {
+                Some(detail) => format!("{}: {detail}", event.event),
+                None => event.event.clone(),
+            };
+            trace_events.push(json!({
+                "name": name,
+                "cat": "consensus",
+                "ph": "i",
+                "s": "t",
+                "ts": event.timestamp_us,
+                "pid": CHROME_TRACE_PID,
+                "tid": timeline.slot,
+                "args": {
+                    "block_id": event.block_id,
+                    "parent_slot": event.parent_slot,
+                    "stake": event.stake,
+                },
+            }));
+        }
+        for received_votes in &timeline.received_votes {
+            trace_events.push(json!({
+                "name": format!("votes_received: {}", received_votes.vote),
+                "cat": "consensus",
+                "ph": "X",
+                "ts": received_votes.first_received_us,
+                "dur": received_votes
+                    .last_received_us
+                    .saturating_sub(received_votes.first_received_us),
+                "pid": CHROME_TRACE_PID,
+                "tid": timeline.slot,
+                "args": {
+                    "block_id": received_votes.block_id,
+                    "count": received_votes.count,
+                    "stake": received_votes.stake,
+                },
+            }));
+        }
+    }
+    json!({
+        "traceEvents": trace_events,
+        "displayTimeUnit": "ms",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            admin_rpc_service::{AdminRpcReceivedVotes, AdminRpcTimelineEvent},
+            commands::tests::verify_args_struct_by_command,
+        },
+    };
+
+    #[test]
+    fn verify_args_struct_by_command_consensus_timeline_default() {
+        verify_args_struct_by_command(
+            command(),
+            vec![COMMAND],
+            ConsensusTimelineArgs {
+                start_slot: None,
+                end_slot: None,
+                format: TimelineFormat::Json,
+                output_file: None,
+            },
+        );
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_consensus_timeline_with_args() {
+        verify_args_struct_by_command(
+            command(),
+            vec![
+                COMMAND,
+                "--start-slot",
+                "10",
+                "--end-slot",
+                "20",
+                "--format",
+                "chrome-trace",
+                "--output-file",
+                "timeline.json",
+            ],
+            ConsensusTimelineArgs {
+                start_slot: Some(10),
+                end_slot: Some(20),
+                format: TimelineFormat::ChromeTrace,
+                output_file: Some(PathBuf::from("timeline.json")),
+            },
+        );
+    }
+
+    #[test]
+    fn test_to_chrome_trace() {
+        let timelines = vec![AdminRpcSlotTimeline {
+            slot: 7,
+            events: vec![AdminRpcTimelineEvent {
+                timestamp_us: 1_000,
+                event: "vote_sent".to_string(),
+                detail: Some("notarize".to_string()),
+                block_id: None,
+                parent_slot: None,
+                stake: Some(42),
+            }],
+            received_votes: vec![AdminRpcReceivedVotes {
+                vote: "skip".to_string(),
+                block_id: None,
+                count: 3,
+                stake: 300,
+                first_received_us: 1_100,
+                last_received_us: 1_400,
+            }],
+        }];
+
+        let trace = to_chrome_trace(&timelines);
+        let trace_events = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(trace_events.len(), 3);
+
+        assert_eq!(trace_events[0]["ph"], "M");
+        assert_eq!(trace_events[0]["args"]["name"], "slot 7");
+
+        assert_eq!(trace_events[1]["name"], "vote_sent: notarize");
+        assert_eq!(trace_events[1]["ph"], "i");
+        assert_eq!(trace_events[1]["ts"], 1_000);
+        assert_eq!(trace_events[1]["tid"], 7);
+        assert_eq!(trace_events[1]["args"]["stake"], 42);
+
+        assert_eq!(trace_events[2]["name"], "votes_received: skip");
+        assert_eq!(trace_events[2]["ph"], "X");
+        assert_eq!(trace_events[2]["ts"], 1_100);
+        assert_eq!(trace_events[2]["dur"], 300);
+        assert_eq!(trace_events[2]["args"]["count"], 3);
+    }
+}
@@ -0,0 +1,357 @@
+use {
+    crate::{
+        admin_rpc_service::{self, AdminRpcForkTree, AdminRpcForkTreeNode},
+        commands::{Error, FromClapArgMatches, Result},
+    },
+    clap::{App, Arg, ArgMatches, SubCommand},
+    solana_clock::Slot,
+    std::{
+        collections::HashMap,
+        fmt::Write,
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+const COMMAND: &str = "fork-tree";
+
+/// Number of base58 characters of a bank hash shown in each DOT node
+const DOT_HASH_PREFIX_LEN: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ForkTreeFormat {
+    Json,
+    Dot,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ForkTreeArgs {
+    pub format: ForkTreeFormat,
+    pub output_file: Option<PathBuf>,
+}
+
+impl FromClapArgMatches for ForkTreeArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> Result<Self> {
+        let format = match matches.value_of("format") {
+            Some("dot") => ForkTreeFormat::Dot,
+            _ => ForkTreeFormat::Json,
+        };
+        Ok(ForkTreeArgs {
+            format,
+            output_file: matches.value_of("output_file").map(PathBuf::from),
+        })
+    }
+}
+
+pub fn command<'a>() -> App<'a, 'a> {
+    SubCommand::with_name(COMMAND)
+        .about("Display the validator's live fork tree as seen by replay")
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(&["json", "dot"])
+                .default_value("json")
+                .help(
+                    "Output format. dot can be rendered with Graphviz, e.g. `dot -Tsvg`, with \
+                     dead banks in red, duplicate banks in orange and the heaviest fork in bold",
+                ),
+        )
+        .arg(
+            Arg::with_name("output_file")
+                .long("output-file")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Write the fork tree to FILE instead of stdout"),
+        )
+}
+
+pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<()> {
+    let ForkTreeArgs {
+        format,
+        output_file,
+    } = ForkTreeArgs::from_clap_arg_match(matches)?;
+
+    let admin_client = admin_rpc_service::connect(ledger_path);
+    let fork_tree = admin_rpc_service::runtime()
+        .block_on(async move { admin_client.await?.fork_tree().await })?;
+
+    let output = match format {
+        ForkTreeFormat::Json => serde_json::to_string_pretty(&fork_tree)
+            .map_err(|err| Error::Dynamic(Box::new(err)))?,
+        ForkTreeFormat::Dot => to_dot(&fork_tree),
+    };
+    match output_file {
+        Some(output_file) => fs::write(output_file, output)?,
+        None => println!("{output}"),
+    }
+    Ok(())
+}
+
+/// Renders `fork_tree` as a Graphviz digraph.
+///
+/// Each bank is labeled with its slot, stake and vote counts and a prefix of
+/// its hash. Banks on the path from the root to the heaviest bank are drawn
+/// in bold and votes still held in the tower are listed with their lockouts.
+fn to_dot(fork_tree: &AdminRpcForkTree) -> String {
+    let heaviest_fork = heaviest_fork(fork_tree);
+    let lockouts = fork_tree
+        .tower
+        .iter()
+        .map(|lockout| (lockout.slot, lockout))
+        .collect::<HashMap<_, _>>();
+
+    let mut dot = String::new();
+    writeln!(dot, "digraph fork_tree {{").unwrap();
+    writeln!(dot, "  rankdir=TB;").unwrap();
+    writeln!(dot, "  node [shape=box, fontname=monospace];").unwrap();
+    for node in &fork_tree.nodes {
+        let mut label = vec![format!("slot {}", node.slot)];
+        if node.slot == fork_tree.root {
+            label.push("root".to_string());
+        }
+        match &node.bank_hash {
+            Some(bank_hash) => label.push(format!(
+                "hash {}",
+                bank_hash
+                    .get(..DOT_HASH_PREFIX_LEN)
+                    .unwrap_or(bank_hash.as_str())
+            )),
+            None => label.push("not frozen".to_string()),
+        }
+        label.push(format!(
+            "stake {} / subtree {}",
+            node.stake_voted_at.unwrap_or_default(),
+            node.stake_voted_subtree.unwrap_or_default(),
+        ));
+        label.push(format!("votes {}", node.vote_count));
+        if node.total_stake > 0 {
+            label.push(format!("fork stake {}/{}", node.fork_stake, node.total_stake));
+        }
+        if let Some(lockout) = lockouts.get(&node.slot) {
+            label.push(format!(
+                "tower conf {} locks until {}",
+                lockout.confirmation_count, lockout.last_locked_out_slot
+            ));
+        }
+        label.extend(node_markers(node, fork_tree.last_voted_slot));
+
+        let mut attributes = vec![format!("label=\"{}\"", label.join("\\n"))];
+        let mut styles = vec![];
+        if node.is_dead {
+            attributes.push("color=red".to_string());
+            attributes.push("fontcolor=red".to_string());
+        } else if node.is_unconfirmed_duplicate || node.latest_invalid_ancestor.is_some() {
+            attributes.push("color=orange".to_string());
+            styles.push("dashed");
+        } else if node.is_duplicate_confirmed {
+            attributes.push("color=darkgreen".to_string());
+        }
+        if heaviest_fork.contains(&node.slot) {
+            styles.push("bold");
+        }
+        if !styles.is_empty() {
+            attributes.push(format!("style=\"{}\"", styles.join(",")));
+        }
+        writeln!(dot, "  \"{}\" [{}];", node.slot, attributes.join(", ")).unwrap();
+    }
+    for node in &fork_tree.nodes {
+        if let Some(parent_slot) = node.parent_slot {
+            let style = if heaviest_fork.contains(&node.slot) {
+                " [style=bold]"
+            } else {
+                ""
+            };
+            writeln!(dot, "  \"{parent_slot}\" -> \"{}\"{style};", node.slot).unwrap();
+        }
+    }
+    writeln!(dot, "}}").unwrap();
+    dot
+}
+
+/// Short flags describing the state of `node`, one per label line.
+fn node_markers(node: &AdminRpcForkTreeNode, last_voted_slot: Option<Slot>) -> Vec<String> {
+    let mut markers = vec![];
+    if node.is_dead {
+        markers.push(if node.is_hard_dead { "DEAD" } else { "DEAD (soft)" }.to_string());
+    }
+    if node.is_unconfirmed_duplicate {
+        markers.push("DUPLICATE".to_string());
+    }
+    if let Some(latest_invalid_ancestor) = node.latest_invalid_ancestor
+        && latest_invalid_ancestor != node.slot
+    {
+        markers.push(format!("invalid ancestor {latest_invalid_ancestor}"));
+    }
+    if node.is_duplicate_confirmed {
+        markers.push("duplicate confirmed".to_string());
+    }
+    if node.is_locked_out {
+        markers.push("locked out".to_string());
+    }
+    if last_voted_slot == Some(node.slot) {
+        markers.push("last vote".to_string());
+    }
+    if node.is_propagated == Some(false) {
+        markers.push("not propagated".to_string());
+    }
+    markers
+}
+
+/// Slots on the path from the root to the heaviest bank.
+fn heaviest_fork(fork_tree: &AdminRpcForkTree) -> Vec<Slot> {
+    let parents = fork_tree
+        .nodes
+        .iter()
+        .map(|node| (node.slot, node.parent_slot))
+        .collect::<HashMap<_, _>>();
+    let mut fork = vec![];
+    let mut next = fork_tree.heaviest_slot;
+    while let Some(slot) = next {
+        fork.push(slot);
+        next = parents.get(&slot).copied().flatten();
+    }
+    fork
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            admin_rpc_service::AdminRpcTowerLockout,
+            commands::tests::verify_args_struct_by_command,
+        },
+    };
+
+    fn new_node(slot: Slot, parent_slot: Option<Slot>) -> AdminRpcForkTreeNode {
+        AdminRpcForkTreeNode {
+            slot,
+            parent_slot,
+            bank_hash: Some("4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM".to_string()),
+            block_id: None,
+            leader: "11111111111111111111111111111111".to_string(),
+            is_frozen: true,
+            is_dead: false,
+            is_hard_dead: false,
+            stake_voted_at: Some(0),
+            stake_voted_subtree: Some(0),
+            vote_count: 0,
+            is_duplicate_confirmed: false,
+            is_unconfirmed_duplicate: false,
+            latest_invalid_ancestor: None,
+            is_locked_out: false,
+            passed_vote_threshold: false,
+            has_voted: false,
+            is_propagated: None,
+            fork_stake: 0,
+            total_stake: 0,
+        }
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_fork_tree_default() {
+        verify_args_struct_by_command(
+            command(),
+            vec![COMMAND],
+            ForkTreeArgs {
+                format: ForkTreeFormat::Json,
+                output_file: None,
+            },
+        );
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_fork_tree_with_args() {
+        verify_args_struct_by_command(
+            command(),
+            vec![COMMAND, "--format", "dot", "--output-file", "forks.dot"],
+            ForkTreeArgs {
+                format: ForkTreeFormat::Dot,
+                output_file: Some(PathBuf::from("forks.dot")),
+            },
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        // 0 -> 1 -> 3 (heaviest)
+        //   \-> 2 (dead)
+        //   \-> 4 (duplicate)
+        let fork_tree = AdminRpcForkTree {
+            root: 0,
+            heaviest_slot: Some(3),
+            last_voted_slot: Some(3),
+            tower: vec![AdminRpcTowerLockout {
+                slot: 3,
+                confirmation_count: 1,
+                last_locked_out_slot: 5,
+            }],
+            nodes: vec![
+                new_node(0, None),
+                AdminRpcForkTreeNode {
+                    stake_voted_subtree: Some(300),
+                    ..new_node(1, Some(0))
+                },
+                AdminRpcForkTreeNode {
+                    bank_hash: None,
+                    is_frozen: false,
+                    is_dead: true,
+                    is_hard_dead: true,
+                    ..new_node(2, Some(0))
+                },
+                AdminRpcForkTreeNode {
+                    stake_voted_at: Some(300),
+                    stake_voted_subtree: Some(300),
+                    vote_count: 3,
+                    ..new_node(3, Some(1))
+                },
+                AdminRpcForkTreeNode {
+                    is_unconfirmed_duplicate: true,
+                    latest_invalid_ancestor: Some(4),
+                    ..new_node(4, Some(0))
+                },
+            ],
+        };
+
+        let dot = to_dot(&fork_tree);
+        assert!(dot.starts_with("digraph fork_tree {"));
+        assert!(dot.contains(
+            "\"3\" [label=\"slot 3\\nhash 4uQeVj5t\\nstake 300 / subtree 300\\nvotes 3\\ntower \
+             conf 1 locks until 5\\nlast vote\", style=\"bold\"];"
+        ));
+        assert!(dot.contains("\"2\" [label=\"slot 2\\nnot frozen"));
+        assert!(dot.contains("DEAD\", color=red, fontcolor=red];"));
+        assert!(dot.contains("DUPLICATE\", color=orange, style=\"dashed\"];"));
+        assert!(dot.contains("\"0\" -> \"1\" [style=bold];"));
+        assert!(dot.contains("\"1\" -> \"3\" [style=bold];"));
+        assert!(dot.contains("\"0\" -> \"2\";"));
+        assert!(!dot.contains("\"0\" -> \"0\""));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn test_heaviest_fork() {
+        let fork_tree = AdminRpcForkTree {
+            root: 0,
+            heaviest_slot: Some(3),
+            last_voted_slot: None,
+            tower: vec![],
+            nodes: vec![
+                new_node(0, None),
+                new_node(1, Some(0)),
+                new_node(2, Some(0)),
+                new_node(3, Some(1)),
+            ],
+        };
+        assert_eq!(heaviest_fork(&fork_tree), vec![3, 1, 0]);
+        assert!(
+            heaviest_fork(&AdminRpcForkTree {
+                heaviest_slot: None,
+                ..fork_tree
+            })
+            .is_empty()
+        );
+    }
+}
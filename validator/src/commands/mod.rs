@@ -1,7 +1,9 @@
 pub mod authorized_voter;
 pub mod blockstore;
+pub mod consensus_timeline;
 pub mod contact_info;
 pub mod exit;
+pub mod fork_tree;
 pub mod manage_block_production;
 pub mod monitor;
 pub mod plugin;
@@ -12,6 +14,7 @@ pub mod set_identity;
 pub mod set_log_filter;
 pub mod set_public_address;
 pub mod staked_nodes_overrides;
+pub mod vote_storage_server;
 pub mod wait_for_restart_window;
 
 use thiserror::Error;
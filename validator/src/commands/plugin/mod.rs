@@ -73,6 +73,16 @@ pub fn command<'a>() -> App<'a, 'a> {
                 .arg(&name_arg)
                 .arg(&config_arg),
         )
+        .subcommand(
+            SubCommand::with_name("reload-filter")
+                .about(
+                    "Reload the notification filter of a particular geyser plugin without \
+                     restarting it. You must specify the geyser plugin name and the config path \
+                     to read the filter from",
+                )
+                .arg(&name_arg)
+                .arg(&config_arg),
+        )
         .subcommand(
             SubCommand::with_name("load")
                 .about(
@@ -129,6 +139,19 @@ pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<()> {
             })?;
             println!("Successfully reloaded plugin: {name}");
         }
+        ("reload-filter", Some(subcommand_matches)) => {
+            let PluginReloadArgs { name, config } =
+                PluginReloadArgs::from_clap_arg_match(subcommand_matches)?;
+
+            let admin_client = admin_rpc_service::connect(ledger_path);
+            admin_rpc_service::runtime().block_on(async {
+                admin_client
+                    .await?
+                    .reload_plugin_filter(name.clone(), config.clone())
+                    .await
+            })?;
+            println!("Successfully reloaded filter of plugin: {name}");
+        }
         _ => unreachable!(),
     }
 
@@ -210,4 +233,20 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn verify_args_struct_by_command_plugin_reload_filter_with_name_and_config() {
+        let app = command();
+        let matches =
+            app.get_matches_from(vec![COMMAND, "reload-filter", "testname", "testconfig"]);
+        let subcommand_matches = matches.subcommand_matches("reload-filter").unwrap();
+        let args = PluginReloadArgs::from_clap_arg_match(subcommand_matches).unwrap();
+        assert_eq!(
+            args,
+            PluginReloadArgs {
+                name: "testname".to_string(),
+                config: "testconfig".to_string(),
+            }
+        );
+    }
 }
@@ -7,7 +7,9 @@ use {
     agave_snapshots::{SUPPORTED_ARCHIVE_COMPRESSION, SnapshotVersion},
     bytesize::ByteSize,
     clap::{App, Arg, ArgMatches, values_t},
-    solana_accounts_db::utils::create_and_canonicalize_directory,
+    solana_accounts_db::{
+        accounts_index::DataSliceIndex, utils::create_and_canonicalize_directory,
+    },
     solana_clap_utils::{
         hidden_unless_forced,
         input_parsers::keypair_of,
@@ -307,6 +309,28 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             .takes_value(true)
             .help("Use DIR as file tower storage location [default: --ledger value]"),
     )
+    .arg(
+        Arg::with_name("remote_vote_storage")
+            .long("remote-vote-storage")
+            .value_name("HOST:PORT")
+            .takes_value(true)
+            .conflicts_with("tower")
+            .requires("remote_vote_storage_secret_file")
+            .validator(solana_net_utils::is_host_port)
+            .help(
+                "Keep the tower and vote history on the vote storage server at HOST:PORT instead \
+                 of on disk, so that a primary and a hot spare validator can safely hand over \
+                 their identity. See `agave-validator vote-storage-server`",
+            ),
+    )
+    .arg(
+        Arg::with_name("remote_vote_storage_secret_file")
+            .long("remote-vote-storage-secret-file")
+            .value_name("FILE")
+            .takes_value(true)
+            .requires("remote_vote_storage")
+            .help("File holding the secret shared with the --remote-vote-storage server"),
+    )
     .arg(
         Arg::with_name("gossip_port")
             .long("gossip-port")
@@ -914,6 +938,21 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             .value_name("INDEX")
             .help("Enable an accounts index, indexed by the selected account field"),
     )
+    .arg(
+        Arg::with_name("account_index_data_slice")
+            .long("account-index-data-slice")
+            .takes_value(true)
+            .multiple(true)
+            .validator(is_parsable::<DataSliceIndex>)
+            .value_name("PROGRAM_ID:OFFSET:LENGTH")
+            .help(
+                "Enable an accounts index of the accounts owned by PROGRAM_ID, keyed on the \
+                 LENGTH bytes at OFFSET in their data. LENGTH must be at most 32. \
+                 getProgramAccounts uses the index when given a memcmp filter at exactly OFFSET \
+                 with LENGTH bytes. --account-index-include-key and \
+                 --account-index-exclude-key do not apply to these indexes.",
+            ),
+    )
     .arg(
         Arg::with_name("account_index_exclude_key")
             .long(EXCLUDE_KEY)
@@ -989,6 +1028,32 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             .help("The smallest size of ideal ancient storage.")
             .hidden(hidden_unless_forced()),
     )
+    .arg(
+        Arg::with_name("accounts_db_ancient_storage_format")
+            .long("accounts-db-ancient-storage-format")
+            .value_name("FORMAT")
+            .takes_value(true)
+            .possible_values(&["append-vec", "compressed"])
+            .help(
+                "Storage format of the storages written when packing ancient slots. \"compressed\" \
+                 stores accounts in read-only, block-compressed files with a pubkey index, trading \
+                 CPU on account loads for disk space. Existing storages of either format are \
+                 always readable.",
+            )
+            .hidden(hidden_unless_forced()),
+    )
+    .arg(
+        Arg::with_name("accounts_db_historical_retention_slots")
+            .long("accounts-db-historical-retention-slots")
+            .value_name("SLOTS")
+            .validator(is_parsable::<u64>)
+            .takes_value(true)
+            .help(
+                "Keep account state as of the roots within SLOTS of the highest root, so \
+                 getAccountInfo and getMultipleAccounts can serve them with the `atSlot` \
+                 parameter. Increases the amount of account data kept on disk.",
+            ),
+    )
     .arg(
         Arg::with_name("accounts_db_max_ancient_storages")
             .long("accounts-db-max-ancient-storages")
@@ -1257,6 +1322,30 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             .conflicts_with("no_xdp")
             .help("Enable XDP zero copy mode. Requires hardware and driver support"),
     )
+    .arg(
+        Arg::with_name("xdp_rx_cpu_cores")
+            .long("xdp-rx-cpu-cores")
+            .takes_value(true)
+            .value_name("CPU_LIST")
+            .conflicts_with("no_xdp")
+            .validator(|value| validate_cpu_ranges(value, "--xdp-rx-cpu-cores"))
+            .help(
+                "CPU cores to reserve for receiving turbine and repair shreds via XDP (e.g. \
+                 \"5-6\"). Each core receives from one NIC queue, starting after the queues used \
+                 for XDP transmit. Use flow steering to direct the TVU and repair ports to those \
+                 queues, packets arriving on other queues are received through UDP sockets",
+            ),
+    )
+    .arg(
+        Arg::with_name("xdp_rx_generic_mode")
+            .long("xdp-rx-generic-mode")
+            .takes_value(false)
+            .requires("xdp_rx_cpu_cores")
+            .help(
+                "Attach the XDP receive program in generic mode, for drivers without native XDP \
+                 support",
+            ),
+    )
     .args(&pub_sub_config::args(/*test_validator:*/ false))
     .args(&json_rpc_config::args())
     .args(&rpc_bigtable_config::args())
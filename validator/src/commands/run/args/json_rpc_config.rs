@@ -63,6 +63,7 @@ impl FromClapArgMatches for JsonRpcConfig {
             .ok()
             .map(|mb| mb * MB),
             disable_health_check: false,
+            enable_simulation_tracing: matches.is_present("rpc_enable_simulation_tracing"),
         })
     }
 }
@@ -185,6 +186,13 @@ pub(crate) fn args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
                 "How large accumulated results from an accounts index scan can become. If this is \
                  exceeded, the scan aborts.",
             ),
+        Arg::with_name("rpc_enable_simulation_tracing")
+            .long("rpc-enable-simulation-tracing")
+            .takes_value(false)
+            .help(
+                "Allow simulateTransaction requests to record an execution trace of the \
+                 simulated programs",
+            ),
     ]
 }
 
@@ -475,6 +483,25 @@ pub(super) mod tests {
         }
     }
 
+    #[test]
+    fn verify_args_struct_by_command_run_with_rpc_enable_simulation_tracing() {
+        {
+            let default_run_args = RunArgs::default();
+            let expected_args = RunArgs {
+                json_rpc_config: JsonRpcConfig {
+                    enable_simulation_tracing: true,
+                    ..default_run_args.json_rpc_config.clone()
+                },
+                ..default_run_args.clone()
+            };
+            verify_args_struct_by_command_run_with_identity_setup(
+                default_run_args,
+                vec!["--rpc-enable-simulation-tracing"],
+                expected_args,
+            );
+        }
+    }
+
     #[test]
     fn test_default_health_check_slot_distance_unchanged() {
         assert_eq!(*DEFAULT_HEALTH_CHECK_SLOT_DISTANCE, "128");
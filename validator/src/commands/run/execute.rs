@@ -11,7 +11,10 @@ use {
         paths::BANK_SNAPSHOTS_DIR,
         snapshot_config::{SnapshotConfig, SnapshotUsage},
     },
-    agave_votor::vote_history_storage,
+    agave_votor::{
+        remote_vote_storage::{RemoteVoteStorageClient, SharedSecret},
+        vote_history_storage,
+    },
     agave_votor_transport::MAX_ENDPOINTS,
     bytesize::ByteSize,
     clap::{ArgMatches, crate_name, value_t, value_t_or_exit, values_t, values_t_or_exit},
@@ -86,7 +89,10 @@ use {
 #[cfg(target_os = "linux")]
 use {
     agave_cpu_utils::cpu_affinity,
-    agave_xdp::transmitter::{QueueCpuBinding, XdpConfig},
+    agave_xdp::{
+        receiver::XdpRxConfig,
+        transmitter::{QueueCpuBinding, XdpConfig},
+    },
     solana_clap_utils::input_parsers::parse_cpu_ranges,
 };
 
@@ -174,6 +180,9 @@ pub fn execute(
     #[cfg(target_os = "linux")]
     let xdp_transmit_config: Option<XdpConfig> =
         build_xdp_config(matches, &operation, &bind_addresses)?;
+    #[cfg(target_os = "linux")]
+    let xdp_receive_config: Option<XdpRxConfig> =
+        build_xdp_rx_config(matches, xdp_transmit_config.as_ref())?;
 
     let dynamic_port_range =
         solana_net_utils::parse_port_range(matches.value_of("dynamic_port_range").unwrap())
@@ -285,7 +294,7 @@ pub fn execute(
     #[cfg(target_os = "linux")]
     let (xdp_transmit_setup, xdp_network_config_report) = {
         use {
-            agave_xdp::transmitter::TransmitterBuilder,
+            agave_xdp::{receiver::ReceiverBuilder, transmitter::TransmitterBuilder},
             caps::{
                 CapSet,
                 Capability::{CAP_BPF, CAP_NET_ADMIN, CAP_NET_RAW, CAP_PERFMON, CAP_SYS_NICE},
@@ -313,7 +322,7 @@ pub fn execute(
         if let Some(xdp_config) = xdp_transmit_config.as_ref() {
             required_caps.insert(CAP_NET_ADMIN);
             required_caps.insert(CAP_NET_RAW);
-            if xdp_config.zero_copy {
+            if xdp_config.zero_copy || xdp_receive_config.is_some() {
                 required_caps.insert(CAP_BPF);
                 required_caps.insert(CAP_PERFMON);
             }
@@ -386,6 +395,26 @@ pub fn execute(
                 // uses the same interface name, with bond-master fallback.
                 xdp_config.interface = Some(xdp_interface.clone());
                 let zero_copy = xdp_config.zero_copy;
+
+                // The receive program also does what zero copy transmit needs from the transmit
+                // program, so when it's attached the transmitter must not replace it.
+                let receiver_builder = xdp_receive_config.clone().and_then(|mut rx_config| {
+                    rx_config.interface = Some(xdp_interface.clone());
+                    rx_config.ports = vec![
+                        node.sockets.tvu[0].local_addr().unwrap().port(),
+                        node.sockets.repair.local_addr().unwrap().port(),
+                    ];
+                    ReceiverBuilder::new(rx_config)
+                        .inspect_err(|err| {
+                            warn!(
+                                "failed to set up XDP receive, receiving shreds via UDP sockets: \
+                                 {err}"
+                            )
+                        })
+                        .ok()
+                });
+                xdp_config.attach_program = receiver_builder.is_none();
+
                 let src_ip = match node.bind_ip_addrs.active() {
                     IpAddr::V4(ip) if !ip.is_unspecified() => ip,
                     IpAddr::V4(_unspecified) => interface_ipv4(&xdp_interface).expect(
@@ -398,6 +427,7 @@ pub fn execute(
                         transmitter_builder: TransmitterBuilder::new(xdp_config, exit.clone())
                             .expect("failed to create xdp transmitter"),
                         src_ip,
+                        receiver_builder,
                     },
                     XdpNetworkConfigReport {
                         zero_copy,
@@ -554,15 +584,31 @@ pub fn execute(
         .ok()
         .or_else(|| get_cluster_shred_version(&entrypoint_addrs, bind_addresses.active()));
 
-    let tower_path = value_t!(matches, "tower", PathBuf)
-        .ok()
-        .unwrap_or_else(|| ledger_path.clone());
-    let tower_storage: Arc<dyn tower_storage::TowerStorage> =
-        Arc::new(tower_storage::FileTowerStorage::new(tower_path));
-
-    let vote_history_storage: Arc<dyn vote_history_storage::VoteHistoryStorage> = Arc::new(
-        vote_history_storage::FileVoteHistoryStorage::new(ledger_path.clone()),
-    );
+    let (tower_storage, vote_history_storage): (
+        Arc<dyn tower_storage::TowerStorage>,
+        Arc<dyn vote_history_storage::VoteHistoryStorage>,
+    ) = if let Some(remote_vote_storage) = matches.value_of("remote_vote_storage") {
+        let server_addr = solana_net_utils::parse_host_port(remote_vote_storage)
+            .map_err(|err| format!("failed to parse --remote-vote-storage: {err}"))?;
+        let secret_file = value_t_or_exit!(matches, "remote_vote_storage_secret_file", PathBuf);
+        let secret = SharedSecret::read_from_file(secret_file)
+            .map_err(|err| format!("failed to read --remote-vote-storage-secret-file: {err}"))?;
+        let client = Arc::new(RemoteVoteStorageClient::new(server_addr, secret));
+        (
+            Arc::new(tower_storage::RemoteTowerStorage::new(client.clone())),
+            Arc::new(vote_history_storage::RemoteVoteHistoryStorage::new(client)),
+        )
+    } else {
+        let tower_path = value_t!(matches, "tower", PathBuf)
+            .ok()
+            .unwrap_or_else(|| ledger_path.clone());
+        (
+            Arc::new(tower_storage::FileTowerStorage::new(tower_path)),
+            Arc::new(vote_history_storage::FileVoteHistoryStorage::new(
+                ledger_path.clone(),
+            )),
+        )
+    };
 
     let accounts_index_limit =
         value_t!(matches, "accounts_index_limit", String).unwrap_or_else(|err| err.exit());
@@ -683,6 +729,18 @@ pub fn execute(
         })
         .unwrap_or_default();
 
+    let ancient_accounts_file_provider = matches
+        .value_of("accounts_db_ancient_storage_format")
+        .map(|format| match format {
+            "append-vec" => AccountsFileProvider::AppendVec,
+            "compressed" => AccountsFileProvider::Compressed,
+            _ => {
+                // clap will enforce one of the above values is given
+                unreachable!("invalid value given to accounts_db_ancient_storage_format")
+            }
+        })
+        .unwrap_or_default();
+
     let accounts_db_config = AccountsDbConfig {
         index: Some(accounts_index_config),
         account_indexes: Some(account_indexes.clone()),
@@ -707,6 +765,13 @@ pub fn execute(
         num_background_threads: Some(accounts_db_background_threads),
         num_foreground_threads: Some(accounts_db_foreground_threads),
         accounts_file_provider: AccountsFileProvider::AppendVec,
+        ancient_accounts_file_provider,
+        historical_retention_slots: value_t!(
+            matches,
+            "accounts_db_historical_retention_slots",
+            u64
+        )
+        .ok(),
     };
 
     let on_start_geyser_plugin_config_files = if matches.is_present("geyser_plugin_config") {
@@ -1323,7 +1388,9 @@ fn new_snapshot_config(
         let archive_format_str = value_t_or_exit!(matches, "snapshot_archive_format", String);
         let mut archive_format = ArchiveFormat::from_cli_arg(&archive_format_str)
             .unwrap_or_else(|| panic!("Archive format not recognized: {archive_format_str}"));
-        if let ArchiveFormat::TarZstd { config } = &mut archive_format {
+        if let ArchiveFormat::TarZstd { config } | ArchiveFormat::TarZstdSeekable { config } =
+            &mut archive_format
+        {
             config.compression_level =
                 value_t_or_exit!(matches, "snapshot_zstd_compression_level", i32);
         }
@@ -1460,6 +1527,64 @@ fn build_xdp_config(
     }))
 }
 
+#[cfg(target_os = "linux")]
+fn build_xdp_rx_config(
+    matches: &ArgMatches,
+    xdp_transmit_config: Option<&XdpConfig>,
+) -> Result<Option<XdpRxConfig>, String> {
+    let Some(cpu_str) = matches.value_of("xdp_rx_cpu_cores") else {
+        return Ok(None);
+    };
+    // XDP receive shares the interface and AF_XDP mode of XDP transmit
+    let Some(xdp_transmit_config) = xdp_transmit_config else {
+        return Ok(None);
+    };
+    let poh_pinned_cpu_core = value_of(matches, "poh_pinned_cpu_core")
+        .or_else(|| value_of(matches, "experimental_poh_pinned_cpu_core"))
+        .or(poh_service::DEFAULT_PINNED_CPU_CORE);
+    let cpus = parse_cpu_ranges(cpu_str).expect("clap validator already accepted this CPU list");
+    if let Some(poh_core) = poh_pinned_cpu_core
+        && cpus.contains(&poh_core)
+    {
+        return Err(format!(
+            "--xdp-rx-cpu-cores includes PoH core {poh_core}; XDP and PoH must not share a CPU \
+             core"
+        ));
+    }
+    if let Some(cpu) = cpus.iter().find(|cpu| {
+        xdp_transmit_config
+            .queues
+            .iter()
+            .any(|binding| binding.cpu == **cpu)
+    }) {
+        return Err(format!(
+            "--xdp-rx-cpu-cores includes XDP transmit core {cpu}; XDP receive and transmit must \
+             not share a CPU core"
+        ));
+    }
+    info!("XDP receive enabled on CPU cores: {cpus:?}");
+    // Only one AF_XDP socket can be bound to a queue, so receive on the queues following the ones
+    // used for transmit.
+    let first_queue = xdp_transmit_config
+        .queues
+        .iter()
+        .map(|binding| binding.queue.saturating_add(1))
+        .max()
+        .unwrap_or(0);
+    let queues = (first_queue..)
+        .zip(cpus)
+        .map(|(queue, cpu)| QueueCpuBinding { queue, cpu })
+        .collect();
+    Ok(Some(XdpRxConfig {
+        interface: xdp_transmit_config.interface.clone(),
+        queues,
+        zero_copy: xdp_transmit_config.zero_copy,
+        generic_mode: matches.is_present("xdp_rx_generic_mode"),
+        // filled in once the sockets are bound
+        ports: vec![],
+    }))
+}
+
 #[cfg(all(target_os = "linux", test))]
 mod xdp_tests {
     use {
@@ -1525,4 +1650,60 @@ mod xdp_tests {
             "XDP core overlapping PoH core must produce an error"
         );
     }
+
+    #[test]
+    fn test_xdp_rx_disabled_by_default() {
+        let default_args = DefaultArgs::default();
+        let app = add_args(clap::App::new("agave-validator"), &default_args);
+        let matches = app.get_matches_from(vec!["agave-validator", "--xdp-cpu-cores", "1"]);
+        let tx_config = build_xdp_config(&matches, &Operation::Run, &single_ip_bind()).unwrap();
+        let result = build_xdp_rx_config(&matches, tx_config.as_ref());
+        assert!(result.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_xdp_rx_queues_follow_tx_queues() {
+        let default_args = DefaultArgs::default();
+        let app = add_args(clap::App::new("agave-validator"), &default_args);
+        let matches = app.get_matches_from(vec![
+            "agave-validator",
+            "--xdp-cpu-cores",
+            "1-2",
+            "--xdp-rx-cpu-cores",
+            "3-4",
+            "--xdp-rx-generic-mode",
+        ]);
+        let tx_config = build_xdp_config(&matches, &Operation::Run, &single_ip_bind()).unwrap();
+        let rx_config = build_xdp_rx_config(&matches, tx_config.as_ref())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            rx_config
+                .queues
+                .iter()
+                .map(|binding| (binding.queue, binding.cpu))
+                .collect::<Vec<_>>(),
+            vec![(2, 3), (3, 4)]
+        );
+        assert!(rx_config.generic_mode);
+    }
+
+    #[test]
+    fn test_xdp_rx_core_conflicts_with_tx_core_is_error() {
+        let default_args = DefaultArgs::default();
+        let app = add_args(clap::App::new("agave-validator"), &default_args);
+        let matches = app.get_matches_from(vec![
+            "agave-validator",
+            "--xdp-cpu-cores",
+            "1-2",
+            "--xdp-rx-cpu-cores",
+            "2-3",
+        ]);
+        let tx_config = build_xdp_config(&matches, &Operation::Run, &single_ip_bind()).unwrap();
+        let result = build_xdp_rx_config(&matches, tx_config.as_ref());
+        assert!(
+            result.unwrap_err().contains("XDP transmit core 2"),
+            "XDP receive core overlapping XDP transmit core must produce an error"
+        );
+    }
 }
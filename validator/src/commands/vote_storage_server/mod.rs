@@ -0,0 +1,122 @@
+use {
+    crate::commands::{FromClapArgMatches, Result},
+    agave_votor::remote_vote_storage::{RemoteVoteStorageServer, SharedSecret},
+    clap::{App, Arg, ArgMatches, SubCommand, value_t},
+    log::*,
+    std::{
+        net::{SocketAddr, TcpListener},
+        path::{Path, PathBuf},
+    },
+};
+
+const COMMAND: &str = "vote-storage-server";
+
+#[derive(Debug, PartialEq)]
+pub struct VoteStorageServerArgs {
+    pub bind_address: SocketAddr,
+    pub storage_dir: PathBuf,
+    pub secret_file: PathBuf,
+}
+
+impl FromClapArgMatches for VoteStorageServerArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> Result<Self> {
+        Ok(VoteStorageServerArgs {
+            bind_address: value_t!(matches, "bind_address", SocketAddr)?,
+            storage_dir: value_t!(matches, "storage_dir", PathBuf)?,
+            secret_file: value_t!(matches, "secret_file", PathBuf)?,
+        })
+    }
+}
+
+pub fn command<'a>() -> App<'a, 'a> {
+    SubCommand::with_name(COMMAND)
+        .about("Serve towers and vote histories to validators started with --remote-vote-storage")
+        .arg(
+            Arg::with_name("bind_address")
+                .long("bind-address")
+                .value_name("IP:PORT")
+                .takes_value(true)
+                .required(true)
+                .help("Address to listen for validators on"),
+        )
+        .arg(
+            Arg::with_name("storage_dir")
+                .long("storage-dir")
+                .value_name("DIR")
+                .takes_value(true)
+                .required(true)
+                .help("Directory to persist towers and vote histories in, created if missing"),
+        )
+        .arg(
+            Arg::with_name("secret_file")
+                .long("secret-file")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(true)
+                .help(
+                    "File holding the secret validators must know to use the server, at least 16 \
+                     bytes. Pass the same file to the validators with \
+                     --remote-vote-storage-secret-file",
+                ),
+        )
+        .after_help(
+            "Point the primary and the hot spare validators at the same server. A validator can \
+             only store a tower or vote history it loaded the latest version of, and never one \
+             with fewer votes than the stored one, so once the spare took over an identity with \
+             `set-identity`, the validator it replaced can no longer vote with it.",
+        )
+}
+
+pub fn execute(matches: &ArgMatches, _ledger_path: &Path) -> Result<()> {
+    let VoteStorageServerArgs {
+        bind_address,
+        storage_dir,
+        secret_file,
+    } = VoteStorageServerArgs::from_clap_arg_match(matches)?;
+
+    agave_logger::setup_with_default_filter();
+    let secret = SharedSecret::read_from_file(secret_file)?;
+    let server =
+        RemoteVoteStorageServer::new(TcpListener::bind(bind_address)?, Some(storage_dir), secret)?;
+    info!("Vote storage server listening on {}", server.local_addr());
+    server
+        .join()
+        .map_err(|_| Box::<dyn std::error::Error>::from("vote storage server panicked"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::commands::tests::{
+            verify_args_struct_by_command, verify_args_struct_by_command_is_error,
+        },
+    };
+
+    #[test]
+    fn verify_args_struct_by_command_vote_storage_server_default() {
+        verify_args_struct_by_command_is_error::<VoteStorageServerArgs>(command(), vec![COMMAND]);
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_vote_storage_server_with_args() {
+        verify_args_struct_by_command(
+            command(),
+            vec![
+                COMMAND,
+                "--bind-address",
+                "127.0.0.1:8100",
+                "--storage-dir",
+                "/vote-storage",
+                "--secret-file",
+                "/vote-storage.secret",
+            ],
+            VoteStorageServerArgs {
+                bind_address: "127.0.0.1:8100".parse().unwrap(),
+                storage_dir: PathBuf::from("/vote-storage"),
+                secret_file: PathBuf::from("/vote-storage.secret"),
+            },
+        );
+    }
+}
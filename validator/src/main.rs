@@ -129,6 +129,15 @@ pub fn main() {
         ("blockstore", Some(subcommand_matches)) => {
             commands::blockstore::execute(subcommand_matches, &ledger_path)
         }
+        ("consensus-timeline", Some(subcommand_matches)) => {
+            commands::consensus_timeline::execute(subcommand_matches, &ledger_path)
+        }
+        ("fork-tree", Some(subcommand_matches)) => {
+            commands::fork_tree::execute(subcommand_matches, &ledger_path)
+        }
+        ("vote-storage-server", Some(subcommand_matches)) => {
+            commands::vote_storage_server::execute(subcommand_matches, &ledger_path)
+        }
         _ => unreachable!(),
     }
     .unwrap_or_else(|err| {
@@ -0,0 +1,269 @@
+//! Evidence that a validator signed conflicting votes.
+//!
+//! An [`EquivocationProof`] holds two signed votes for the same slot that an honest validator
+//! would never cast together (see [`Vote::conflicts_with`]).  The proof is self-contained: anyone
+//! who knows the offender's BLS pubkey can check it with [`EquivocationProof::verify`], e.g. when
+//! it is submitted on-chain.
+
+use {
+    crate::{
+        unverified_vote_message::UnverifiedVoteMessage,
+        vote::Vote,
+        wire::{VotePayloadToSign, get_vote_payload_to_sign},
+    },
+    solana_bls_signatures::{
+        Signature as BLSSignature, VerifySignature,
+        pubkey::{PopVerified, PubkeyAffine as BLSPubkeyAffine},
+    },
+    solana_clock::Slot,
+    solana_pubkey::Pubkey,
+    thiserror::Error,
+    wincode::{SchemaRead, SchemaWrite, pod_wrapper},
+};
+
+pod_wrapper! {
+    // Use `BLSSignature` directly once `BLSSignature` wincode support
+    // is released in solana-sdk.
+    unsafe struct PodBLSSignature(BLSSignature);
+}
+
+/// Reasons an [`EquivocationProof`] can fail verification.
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum EquivocationProofError {
+    /// The votes were signed for different cluster instances
+    #[error("votes have different shred versions: {0} and {1}")]
+    ShredVersionMismatch(u16, u16),
+    /// An honest validator may cast both votes
+    #[error("votes do not conflict")]
+    NotConflicting,
+    /// The signature on the first vote is not from the offender
+    #[error("invalid signature on the first vote")]
+    InvalidFirstSignature,
+    /// The signature on the second vote is not from the offender
+    #[error("invalid signature on the second vote")]
+    InvalidSecondSignature,
+}
+
+/// A vote together with the signature its sender put on it.
+#[derive(Clone, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+pub struct SignedVote {
+    /// The signed payload, including the shred version
+    pub payload: VotePayloadToSign,
+    /// The sender's signature on the payload
+    #[wincode(with = "PodBLSSignature")]
+    pub signature: BLSSignature,
+}
+
+impl SignedVote {
+    /// Returns the vote, without the shred version.
+    pub fn vote(&self) -> Vote {
+        Vote::from(self.payload)
+    }
+
+    /// Returns the shred version the vote was signed for.
+    pub fn shred_version(&self) -> u16 {
+        match self.payload {
+            VotePayloadToSign::Notar { shred_version, .. }
+            | VotePayloadToSign::Finalize { shred_version, .. }
+            | VotePayloadToSign::Skip { shred_version, .. }
+            | VotePayloadToSign::NotarFallback { shred_version, .. }
+            | VotePayloadToSign::SkipFallback { shred_version, .. }
+            | VotePayloadToSign::Genesis { shred_version, .. } => shred_version,
+        }
+    }
+
+    fn verify(&self, bls_pubkey: &PopVerified<BLSPubkeyAffine>) -> bool {
+        let payload = get_vote_payload_to_sign(self.vote(), self.shred_version());
+        bls_pubkey
+            .verify_signature(&self.signature, &payload)
+            .is_ok()
+    }
+}
+
+impl From<&UnverifiedVoteMessage> for SignedVote {
+    fn from(msg: &UnverifiedVoteMessage) -> Self {
+        Self {
+            payload: VotePayloadToSign::new_from_vote(msg.vote, msg.shred_version),
+            signature: msg.signature,
+        }
+    }
+}
+
+/// Two conflicting votes signed by the same validator.
+#[derive(Clone, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+pub struct EquivocationProof {
+    /// The vote account of the validator that signed both votes
+    pub vote_account: Pubkey,
+    /// The vote that was observed first
+    pub first: SignedVote,
+    /// The vote that was observed second
+    pub second: SignedVote,
+}
+
+impl EquivocationProof {
+    /// Returns the slot both votes are for.
+    pub fn slot(&self) -> Slot {
+        self.first.payload.slot()
+    }
+
+    /// Checks that the two votes conflict and that both were signed by `bls_pubkey`, which must
+    /// be the BLS pubkey of `vote_account` in the epoch of the slot.
+    pub fn verify(
+        &self,
+        bls_pubkey: &PopVerified<BLSPubkeyAffine>,
+    ) -> Result<(), EquivocationProofError> {
+        let (first_shred_version, second_shred_version) =
+            (self.first.shred_version(), self.second.shred_version());
+        if first_shred_version != second_shred_version {
+            return Err(EquivocationProofError::ShredVersionMismatch(
+                first_shred_version,
+                second_shred_version,
+            ));
+        }
+        if !self.first.vote().conflicts_with(&self.second.vote()) {
+            return Err(EquivocationProofError::NotConflicting);
+        }
+        if !self.first.verify(bls_pubkey) {
+            return Err(EquivocationProofError::InvalidFirstSignature);
+        }
+        if !self.second.verify(bls_pubkey) {
+            return Err(EquivocationProofError::InvalidSecondSignature);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::consensus_message::Block,
+        solana_bls_signatures::keypair::Keypair as BLSKeypair, solana_hash::Hash,
+    };
+
+    fn sign(bls_keypair: &BLSKeypair, vote: Vote, shred_version: u16) -> SignedVote {
+        let payload = get_vote_payload_to_sign(vote, shred_version);
+        SignedVote {
+            payload: VotePayloadToSign::new_from_vote(vote, shred_version),
+            signature: bls_keypair.sign(&payload).into(),
+        }
+    }
+
+    fn block(slot: Slot) -> Block {
+        Block {
+            slot,
+            block_id: Hash::new_unique(),
+        }
+    }
+
+    #[test]
+    fn test_conflicting_votes() {
+        let (a, b) = (block(5), block(5));
+        let conflicting = [
+            (
+                Vote::new_notarization_vote(a),
+                Vote::new_notarization_vote(b),
+            ),
+            (Vote::new_notarization_vote(a), Vote::new_skip_vote(5)),
+            (
+                Vote::new_notarization_vote(a),
+                Vote::new_notarization_fallback_vote(a),
+            ),
+            (Vote::new_finalization_vote(5), Vote::new_skip_vote(5)),
+            (
+                Vote::new_finalization_vote(5),
+                Vote::new_skip_fallback_vote(5),
+            ),
+            (
+                Vote::new_finalization_vote(5),
+                Vote::new_notarization_fallback_vote(b),
+            ),
+            (Vote::new_skip_vote(5), Vote::new_skip_fallback_vote(5)),
+            (Vote::new_genesis_vote(a), Vote::new_genesis_vote(b)),
+            (Vote::new_genesis_vote(a), Vote::new_finalization_vote(5)),
+        ];
+        for (first, second) in conflicting {
+            assert!(first.conflicts_with(&second), "{first:?} {second:?}");
+            assert!(second.conflicts_with(&first), "{second:?} {first:?}");
+        }
+
+        let compatible = [
+            (
+                Vote::new_notarization_vote(a),
+                Vote::new_notarization_vote(a),
+            ),
+            (
+                Vote::new_notarization_vote(a),
+                Vote::new_finalization_vote(5),
+            ),
+            (
+                Vote::new_notarization_vote(a),
+                Vote::new_notarization_fallback_vote(b),
+            ),
+            (
+                Vote::new_notarization_vote(a),
+                Vote::new_skip_fallback_vote(5),
+            ),
+            (
+                Vote::new_skip_vote(5),
+                Vote::new_notarization_fallback_vote(a),
+            ),
+            (Vote::new_genesis_vote(a), Vote::new_genesis_vote(a)),
+            (
+                Vote::new_skip_vote(5),
+                Vote::new_notarization_vote(block(6)),
+            ),
+        ];
+        for (first, second) in compatible {
+            assert!(!first.conflicts_with(&second), "{first:?} {second:?}");
+            assert!(!second.conflicts_with(&first), "{second:?} {first:?}");
+        }
+    }
+
+    #[test]
+    fn test_verify_proof() {
+        let bls_keypair = BLSKeypair::new();
+        let proof = EquivocationProof {
+            vote_account: Pubkey::new_from_array([1; 32]),
+            first: sign(&bls_keypair, Vote::new_notarization_vote(block(7)), 42),
+            second: sign(&bls_keypair, Vote::new_skip_vote(7), 42),
+        };
+        assert_eq!(proof.slot(), 7);
+        assert_eq!(proof.verify(&bls_keypair.public), Ok(()));
+
+        let bytes = wincode::serialize(&proof).unwrap();
+        assert_eq!(
+            wincode::deserialize::<EquivocationProof>(&bytes).unwrap(),
+            proof
+        );
+
+        let other_keypair = BLSKeypair::new();
+        assert_eq!(
+            proof.verify(&other_keypair.public),
+            Err(EquivocationProofError::InvalidFirstSignature)
+        );
+        let forged = EquivocationProof {
+            second: sign(&other_keypair, Vote::new_skip_vote(7), 42),
+            ..proof.clone()
+        };
+        assert_eq!(
+            forged.verify(&bls_keypair.public),
+            Err(EquivocationProofError::InvalidSecondSignature)
+        );
+        let compatible = EquivocationProof {
+            second: sign(&bls_keypair, Vote::new_skip_vote(8), 42),
+            ..proof.clone()
+        };
+        assert_eq!(
+            compatible.verify(&bls_keypair.public),
+            Err(EquivocationProofError::NotConflicting)
+        );
+        let other_cluster = EquivocationProof {
+            second: sign(&bls_keypair, Vote::new_skip_vote(7), 43),
+            ..proof
+        };
+        assert_eq!(
+            other_cluster.verify(&bls_keypair.public),
+            Err(EquivocationProofError::ShredVersionMismatch(42, 43))
+        );
+    }
+}
@@ -1,9 +1,10 @@
 //! Definitions related to consensus metrics collection.
 
 use {
-    crate::vote::Vote,
+    crate::{certificate::CertificateType, consensus_message::Block, vote::Vote},
     crossbeam_channel::{Receiver, Sender},
     solana_clock::Slot,
+    solana_hash::Hash,
     solana_pubkey::Pubkey,
     std::time::Instant,
 };
@@ -17,6 +18,46 @@ pub enum ConsensusMetricsEvent {
         id: Pubkey,
         /// The type of vote.
         vote: Vote,
+        /// The stake of the validator that voted.
+        stake: u64,
+    },
+    /// This node sent its own `vote`.
+    VoteSent {
+        /// The vote that was sent.
+        vote: Vote,
+        /// The stake of this node.
+        stake: u64,
+    },
+    /// The first shred for `slot` was received.
+    FirstShred {
+        /// The slot the shred belongs to.
+        slot: Slot,
+    },
+    /// The block for `slot` completed replay.
+    BlockReplayed {
+        /// The slot of the block.
+        slot: Slot,
+        /// The block id of the replayed block.
+        block_id: Hash,
+    },
+    /// A certificate was observed by the event handler.
+    CertificateFormed {
+        /// The type of the certificate.
+        cert_type: CertificateType,
+    },
+    /// A timeout fired for `slot`.
+    Timeout {
+        /// The slot that timed out.
+        slot: Slot,
+        /// Whether this was the crashed leader timeout rather than the skip timeout.
+        crashed_leader: bool,
+    },
+    /// `slot` became ready to be built on top of `parent_block`.
+    ParentReady {
+        /// The slot that is now ready.
+        slot: Slot,
+        /// The parent block.
+        parent_block: Block,
     },
     /// A block hash was seen for `slot` and the `leader` is responsible for producing it.
     BlockHashSeen {
@@ -130,6 +130,28 @@ impl Vote {
             Vote::Genesis(_) => VoteType::Genesis,
         }
     }
+
+    /// Whether an honest validator can never cast both `self` and `other`.
+    ///
+    /// A validator that has signed two conflicting votes has equivocated.
+    pub fn conflicts_with(&self, other: &Vote) -> bool {
+        if self.slot() != other.slot() {
+            return false;
+        }
+        match (self, other) {
+            (Self::Notarize(a), Self::Notarize(b)) => a.block != b.block,
+            (Self::Genesis(a), Self::Genesis(b)) => a.block != b.block,
+            (Self::Genesis(_), _) | (_, Self::Genesis(_)) => true,
+            (Self::Skip(_), Self::Notarize(_) | Self::Finalize(_) | Self::SkipFallback(_))
+            | (Self::Notarize(_) | Self::Finalize(_) | Self::SkipFallback(_), Self::Skip(_))
+            | (Self::SkipFallback(_), Self::Finalize(_))
+            | (Self::Finalize(_), Self::SkipFallback(_) | Self::NotarizeFallback(_))
+            | (Self::NotarizeFallback(_), Self::Finalize(_)) => true,
+            (Self::Notarize(a), Self::NotarizeFallback(b))
+            | (Self::NotarizeFallback(b), Self::Notarize(a)) => a.block == b.block,
+            _ => false,
+        }
+    }
 }
 
 impl From<NotarizationVote> for Vote {
@@ -3,11 +3,11 @@ use {
     crate::{
         CONN_EVENT_CHANNEL_CAP, HANDSHAKE_BURST, HANDSHAKE_GLOBAL_RATE,
         HANDSHAKE_WORKERS_PER_ENDPOINT, MAX_ALPENGLOW_VOTE_ACCOUNTS, MAX_ENDPOINTS,
-        MAX_INFLIGHT_HANDSHAKES, PeerListReceiver,
+        MAX_INFLIGHT_HANDSHAKES, PeerListReceiver, PeerStakesReceiver,
         client::OutboundLoop,
         error::Error,
         server::{AcceptLoop, InboundLoop},
-        stats::ServerStats,
+        stats::{PeerStatsTable, ServerStats},
         transport::{new_client_config, new_server_config},
     },
     bytes::Bytes,
@@ -52,6 +52,8 @@ pub struct QuicDatagramEndpoint {
     /// Identity rotation sender kept here so we control when the channel closes.
     key_updater: Arc<KeyUpdater>,
     ban_sender: BanSender,
+    /// Per-peer inbound stats, refreshed by the inbound loop.
+    peer_stats: watch::Receiver<PeerStatsTable>,
     /// Inbound stats, exposed so integration tests can assert on them.
     #[cfg(any(test, feature = "dev-context-only-utils"))]
     pub server_stats: Arc<ServerStats>,
@@ -67,7 +69,8 @@ impl QuicDatagramEndpoint {
     /// `outbound_socket` backs the outbound (send-only) direction bound to its
     /// own port.
     /// Received datagrams flow into `inbound_datagrams`, per-peer receive rate is
-    /// capped by `max_datagrams_per_second_per_peer`.
+    /// capped by `max_datagrams_per_second_per_peer`, scaled up for peers with
+    /// above-average stake in `peer_stakes`.
     /// `peer_list` carries desired peer set: inbound closes connections to
     /// peers no longer in the set, outbound connects to peers in it.
     /// `cancel` controls when the endpoint should terminate.
@@ -78,6 +81,7 @@ impl QuicDatagramEndpoint {
         outbound_socket: UdpSocket,
         inbound_datagrams: Sender<Datagram>,
         peer_list: PeerListReceiver,
+        peer_stakes: PeerStakesReceiver,
         max_datagrams_per_second_per_peer: usize,
         cancel: CancellationToken,
     ) -> Result<(mpsc::Sender<Bytes>, Self), Error> {
@@ -88,6 +92,7 @@ impl QuicDatagramEndpoint {
         );
 
         let server_stats = Arc::new(ServerStats::default());
+        let (peer_stats_sender, peer_stats) = watch::channel(PeerStatsTable::default());
         // Egress channel carries *distinct* messages to be sent, so we can pick the size
         // based on the max allowed per-peer rate. 5 seconds is way more than necessary.
         // Message drops in this channel are not fatal as votor does not require 100%
@@ -186,6 +191,8 @@ impl QuicDatagramEndpoint {
             inbound_datagrams,
             ban_commands,
             peer_list.clone(),
+            peer_stakes,
+            peer_stats_sender,
             inbound_endpoints,
             inbound_events_sender,
             inbound_events_receiver,
@@ -201,6 +208,7 @@ impl QuicDatagramEndpoint {
             task_handles,
             key_updater,
             ban_sender,
+            peer_stats,
             #[cfg(any(test, feature = "dev-context-only-utils"))]
             runtime_handle: runtime.clone(),
             #[cfg(any(test, feature = "dev-context-only-utils"))]
@@ -218,6 +226,13 @@ impl QuicDatagramEndpoint {
         self.ban_sender.clone()
     }
 
+    /// Latest per-peer inbound stats, refreshed every reporting period. The
+    /// same counters are also emitted as `votor_datagram_server_peer`
+    /// datapoints, tagged with the peer.
+    pub fn peer_stats(&self) -> PeerStatsTable {
+        self.peer_stats.borrow().clone()
+    }
+
     /// Signal all loops to stop without waiting for them. Idempotent.
     /// Callers that must join the egress producer should call this first so
     /// the outbound loop stops before its egress sender is dropped.
@@ -330,7 +345,7 @@ pub fn stub_ban_channel_for_tests(capacity: usize) -> (BanSender, mpsc::Receiver
 mod tests {
     use {
         super::{BanSender, Datagram, QuicDatagramEndpoint},
-        crate::{METRICS_INTERVAL, PeerListSender, transport::MAX_IDLE_TIMEOUT},
+        crate::{METRICS_INTERVAL, PeerListSender, PeerStakesSender, transport::MAX_IDLE_TIMEOUT},
         bytes::Bytes,
         crossbeam_channel::{Receiver, bounded},
         solana_keypair::{Keypair, Signer},
@@ -377,6 +392,7 @@ mod tests {
         addr: SocketAddr,
         keypair: Keypair,
         peer_list_sender: PeerListSender,
+        peer_stakes_sender: PeerStakesSender,
         ban_sender: BanSender,
     }
 
@@ -426,6 +442,8 @@ mod tests {
             // `MAX_ALPENGLOW_PACKET_NUM`.
             let (ingress_sender, ingress_receiver) = bounded(INGRESS_CAP);
             let (peer_list_sender, peer_list_receiver) = watch::channel(Arc::new(peer_list));
+            // Every peer gets the base rate.
+            let (peer_stakes_sender, peer_stakes_receiver) = watch::channel(Arc::default());
             let (egress, endpoint) = QuicDatagramEndpoint::spawn(
                 rt.handle(),
                 &keypair,
@@ -433,6 +451,7 @@ mod tests {
                 client_socket,
                 ingress_sender,
                 peer_list_receiver,
+                peer_stakes_receiver,
                 max_pps,
                 cancel,
             )
@@ -445,6 +464,7 @@ mod tests {
                 addr,
                 keypair,
                 peer_list_sender,
+                peer_stakes_sender,
                 ban_sender,
             }
         }
@@ -874,6 +894,111 @@ mod tests {
         });
     }
 
+    /// Votes above the vote budget are dropped without starving certificates,
+    /// and the drops show up in the per-peer stats.
+    #[test]
+    fn test_server_vote_budget_and_peer_stats() {
+        // pps=20 => vote burst = ceil(20*60%)=12, certificate burst = 16.
+        const PPS: usize = 20;
+        let rt = make_runtime_for_tests();
+        let client_keypair = Keypair::new();
+        let client_pubkey = client_keypair.pubkey();
+        let server = Node::spawn_node(
+            &rt,
+            Keypair::new(),
+            peer_list_with_unknown_addr(client_pubkey),
+            PPS,
+        );
+        // Keep `vote_rate_limited` cumulative across report ticks.
+        server
+            .endpoint
+            .server_stats
+            .report_frozen
+            .store(true, Ordering::Relaxed);
+        let client = Node::spawn_node(
+            &rt,
+            client_keypair,
+            peer_list_of(server.pubkey(), server.addr),
+            PPS,
+        );
+
+        // Wire header of a v1 notarization certificate.
+        let probe = Bytes::from_static(&[1, 9, 0]);
+        send_until_received(&client, &probe, &server.ingress_receiver, |d| {
+            (d.message == probe).then_some(())
+        })
+        .expect("first datagram never arrived");
+        drain_backlog(&server.ingress_receiver);
+
+        rt.block_on(async {
+            for i in 0..40u8 {
+                // Wire header of a v1 notarization vote.
+                let _ = client.egress.send(Bytes::from(vec![1, 1, i])).await;
+            }
+        });
+        std::thread::sleep(Duration::from_secs(1));
+        assert!(
+            server
+                .endpoint
+                .server_stats
+                .vote_rate_limited
+                .load(Ordering::Relaxed)
+                > 0,
+            "votes above the vote budget should be dropped"
+        );
+        drain_backlog(&server.ingress_receiver);
+
+        let certificate = Bytes::from_static(&[1, 9, 1]);
+        send_until_received(&client, &certificate, &server.ingress_receiver, |d| {
+            (d.message == certificate).then_some(())
+        })
+        .expect("votes must not starve certificates");
+
+        // Per-peer stats are published every reporting period.
+        let start = Instant::now();
+        let peer_stats = loop {
+            if let Some(peer_stats) = server
+                .endpoint
+                .peer_stats()
+                .get(&client_pubkey)
+                .filter(|peer_stats| peer_stats.certificates_received >= 2)
+            {
+                break peer_stats.clone();
+            }
+            assert!(
+                start.elapsed() < METRICS_INTERVAL * 3,
+                "per-peer stats were never published"
+            );
+            std::thread::sleep(Duration::from_millis(50));
+        };
+        assert!(peer_stats.votes_received > 0);
+        assert!(peer_stats.votes_rate_limited > 0);
+        assert_eq!(peer_stats.flood_disconnects, 0);
+        assert_eq!(peer_stats.max_datagrams_per_second, PPS as f64);
+
+        // A stake change applies to the connected peer without reconnecting.
+        // Average stake is 2 => the client gets 1.5x the base rate.
+        server
+            .peer_stakes_sender
+            .send_replace(Arc::new(HashMap::from([
+                (client_pubkey, 3),
+                (server.pubkey(), 1),
+            ])));
+        let start = Instant::now();
+        while server
+            .endpoint
+            .peer_stats()
+            .get(&client_pubkey)
+            .is_none_or(|peer_stats| peer_stats.max_datagrams_per_second != PPS as f64 * 1.5)
+        {
+            assert!(
+                start.elapsed() < METRICS_INTERVAL * 3,
+                "stake change was never applied"
+            );
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
     /// Changing the client identity closes its outbound connections and
     /// reconnects to all peers.
     #[test]
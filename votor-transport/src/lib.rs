@@ -5,8 +5,9 @@ pub mod error;
 pub(crate) mod client;
 pub(crate) use error::close_codes;
 pub mod endpoint;
+pub(crate) mod qos;
 pub(crate) mod server;
-pub(crate) mod stats;
+pub mod stats;
 pub(crate) mod transport;
 
 use {
@@ -26,6 +27,14 @@ pub type PeerListSender = watch::Sender<PeerListSnapshot>;
 
 pub type PeerListReceiver = watch::Receiver<PeerListSnapshot>;
 
+/// Snapshot of the admitted peers' stakes, used to weight per-peer ingress
+/// rate limits. Peers missing from the map get the base rate.
+type PeerStakesSnapshot = Arc<HashMap<Pubkey, u64>>;
+
+pub type PeerStakesSender = watch::Sender<PeerStakesSnapshot>;
+
+pub type PeerStakesReceiver = watch::Receiver<PeerStakesSnapshot>;
+
 /// Maximum number of unique peer pubkeys we expect in steady state.
 /// Used to size buffers and channels, actual peer count is controlled
 /// by the peer list.
@@ -90,6 +99,10 @@ pub(crate) const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
 /// How often endpoint metrics are reported.
 pub(crate) const METRICS_INTERVAL: Duration = Duration::from_secs(1);
 
+/// How often per-peer metrics are reported. Longer than [`METRICS_INTERVAL`]
+/// as every active peer emits its own datapoint.
+pub(crate) const PEER_METRICS_INTERVAL: Duration = Duration::from_secs(10);
+
 /// ALPN protocol identifier for the Alpenglow votor datagram transport.
 pub(crate) const ALPENGLOW_ALPN: &[u8] = b"alpenglow-v1";
 
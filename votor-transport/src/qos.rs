@@ -0,0 +1,326 @@
+//! Message-level ingress QoS: per-peer, per-message-class rate limits.
+use {
+    crate::{PEER_RATE_LIMIT_BURST_WINDOW, PEER_RATE_LIMIT_DOS_WINDOW},
+    solana_net_utils::token_bucket::TokenBucket,
+    solana_pubkey::Pubkey,
+    std::{
+        collections::HashMap,
+        ops::{Range, RangeInclusive},
+        sync::atomic::{AtomicU64, Ordering},
+        time::Duration,
+    },
+};
+
+/// Largest multiple of the base per-peer rate a peer can earn through stake.
+pub(crate) const MAX_STAKE_RATE_MULTIPLIER: f64 = 4.0;
+
+/// Share of a peer's rate its votes may use, in percent. In the worst case a
+/// slot sees notarize/skip, finalize, skip fallback and several notarize
+/// fallback votes, which at 5 slots per second is ~60% of the honest rate.
+pub(crate) const VOTE_BUDGET_PERCENT: u64 = 60;
+
+/// Share of a peer's rate its certificates may use, in percent. Certificates
+/// dominate steady state traffic (4 per slot) and standstill refreshes
+/// re-broadcast old certificates on top of that.
+pub(crate) const CERTIFICATE_BUDGET_PERCENT: u64 = 80;
+
+/// Share of a peer's rate its certificate repairs may use, in percent. Repairs
+/// are the certificates a peer re-broadcasts for old slots during standstill,
+/// budgeting them separately keeps a standstill from starving fresh ones.
+pub(crate) const CERTIFICATE_REPAIR_BUDGET_PERCENT: u64 = 40;
+
+/// Certificates more than this many slots behind the newest certificate a peer
+/// sent are classified as repairs.
+pub(crate) const CERTIFICATE_REPAIR_SLOT_LAG: u64 = 16;
+
+/// Wire version and message kind tags, deliberately copied from
+/// `agave_votor_messages::wire` to avoid depending on the message crate.
+/// The first byte of a datagram is the version, the second the message kind.
+const WIRE_VERSION_V1: u8 = 1;
+const WIRE_VOTE_KINDS: RangeInclusive<u8> = 1..=6;
+const WIRE_CERTIFICATE_KINDS: RangeInclusive<u8> = 7..=12;
+/// Every certificate kind starts with its little-endian slot.
+const WIRE_CERTIFICATE_SLOT: Range<usize> = 2..10;
+
+/// Consensus message classes that get separate ingress budgets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MessageClass {
+    Vote,
+    Certificate,
+    /// A certificate well behind the newest one from the same peer.
+    CertificateRepair,
+    /// Anything else. Bounded only by the peer's overall budget, the
+    /// sig-verifier deals with malformed messages.
+    Unclassified,
+}
+
+impl MessageClass {
+    /// Classifies a datagram by its wire header without decoding the message.
+    /// Never returns [`Self::CertificateRepair`], telling repairs apart needs
+    /// the sender's history, see [`PeerRateLimiter::classify`].
+    pub(crate) fn of(datagram: &[u8]) -> Self {
+        match datagram {
+            [WIRE_VERSION_V1, kind, ..] if WIRE_VOTE_KINDS.contains(kind) => Self::Vote,
+            [WIRE_VERSION_V1, kind, ..] if WIRE_CERTIFICATE_KINDS.contains(kind) => {
+                Self::Certificate
+            }
+            _ => Self::Unclassified,
+        }
+    }
+}
+
+/// Outcome of charging a datagram against a peer's budgets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Admission {
+    Accept,
+    /// The peer exceeded its burst allowance, the datagram is dropped.
+    Drop,
+    /// The peer drained a budget dry and must be disconnected.
+    Flood,
+}
+
+/// A token bucket together with the level below which we start dropping.
+struct Budget {
+    bucket: TokenBucket,
+    /// Bucket capacity; draining it dry trips flood control.
+    capacity: u64,
+    /// Tokens that may remain before shaping kicks in (burst headroom).
+    watermark: u64,
+}
+
+impl Budget {
+    /// Budget for a sustained `rate` of datagrams per second.
+    fn new(rate: f64) -> Self {
+        let tokens_over = |window: Duration| (rate * window.as_secs_f64()).ceil() as u64;
+        let burst = tokens_over(PEER_RATE_LIMIT_BURST_WINDOW).max(1);
+        let capacity = tokens_over(PEER_RATE_LIMIT_DOS_WINDOW).max(burst.saturating_add(1));
+        Self {
+            bucket: TokenBucket::new(capacity, capacity, rate),
+            capacity,
+            watermark: capacity.saturating_sub(burst),
+        }
+    }
+
+    fn charge(&self) -> Admission {
+        match self.bucket.consume_tokens(1) {
+            // normal operation
+            Ok(remaining) if remaining >= self.watermark => Admission::Accept,
+            // drop excess packets if peer exceeds normal rate
+            Ok(_) => Admission::Drop,
+            // peer drained bucket dry
+            Err(_) => Admission::Flood,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.bucket.current_tokens() >= self.capacity
+    }
+}
+
+/// Ingress rate limiter shared by all connections of one peer.
+pub(crate) struct PeerRateLimiter {
+    /// Sustained datagrams per second this peer may send across all classes.
+    rate: f64,
+    total: Budget,
+    votes: Budget,
+    certificates: Budget,
+    certificate_repairs: Budget,
+    /// Newest certificate slot this peer sent.
+    highest_certificate_slot: AtomicU64,
+}
+
+impl PeerRateLimiter {
+    pub(crate) fn new(rate: f64) -> Self {
+        let share = |percent: u64| rate * percent as f64 / 100.0;
+        Self {
+            rate,
+            total: Budget::new(rate),
+            votes: Budget::new(share(VOTE_BUDGET_PERCENT)),
+            certificates: Budget::new(share(CERTIFICATE_BUDGET_PERCENT)),
+            certificate_repairs: Budget::new(share(CERTIFICATE_REPAIR_BUDGET_PERCENT)),
+            highest_certificate_slot: AtomicU64::new(0),
+        }
+    }
+
+    /// Sustained datagrams per second this peer may send.
+    pub(crate) fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Classifies a datagram from this peer, telling certificate repairs apart
+    /// from fresh certificates by the newest certificate slot seen so far.
+    pub(crate) fn classify(&self, datagram: &[u8]) -> MessageClass {
+        let class = MessageClass::of(datagram);
+        if class != MessageClass::Certificate {
+            return class;
+        }
+        let Some(slot) = datagram
+            .get(WIRE_CERTIFICATE_SLOT)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u64::from_le_bytes)
+        else {
+            // Truncated, left for the sig-verifier to reject.
+            return class;
+        };
+        let highest = self
+            .highest_certificate_slot
+            .fetch_max(slot, Ordering::Relaxed)
+            .max(slot);
+        if slot.saturating_add(CERTIFICATE_REPAIR_SLOT_LAG) < highest {
+            MessageClass::CertificateRepair
+        } else {
+            MessageClass::Certificate
+        }
+    }
+
+    /// Charges one datagram of `class` against its class budget and, if the
+    /// class admits it, against the overall budget.
+    pub(crate) fn charge(&self, class: MessageClass) -> Admission {
+        let class_admission = match class {
+            MessageClass::Vote => self.votes.charge(),
+            MessageClass::Certificate => self.certificates.charge(),
+            MessageClass::CertificateRepair => self.certificate_repairs.charge(),
+            MessageClass::Unclassified => Admission::Accept,
+        };
+        match class_admission {
+            Admission::Accept => self.total.charge(),
+            admission => admission,
+        }
+    }
+
+    /// Whether every budget refilled, i.e. forgetting this peer loses nothing.
+    pub(crate) fn is_full(&self) -> bool {
+        [
+            &self.total,
+            &self.votes,
+            &self.certificates,
+            &self.certificate_repairs,
+        ]
+        .into_iter()
+        .all(Budget::is_full)
+    }
+}
+
+/// Returns the multiple of the base per-peer rate `peer` may send at.
+///
+/// Honest validators pace themselves to the base rate regardless of stake, so
+/// peers at or below the average stake get exactly that. Heavier peers, whose
+/// messages weigh more in consensus, get proportionally more headroom up to
+/// [`MAX_STAKE_RATE_MULTIPLIER`].
+pub(crate) fn stake_rate_multiplier(peer_stakes: &HashMap<Pubkey, u64>, peer: &Pubkey) -> f64 {
+    let total_stake = peer_stakes
+        .values()
+        .fold(0u64, |total, stake| total.saturating_add(*stake));
+    let Some(stake) = peer_stakes.get(peer).filter(|_| total_stake > 0) else {
+        return 1.0;
+    };
+    let average_stake = total_stake as f64 / peer_stakes.len() as f64;
+    (*stake as f64 / average_stake).clamp(1.0, MAX_STAKE_RATE_MULTIPLIER)
+}
+
+#[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_class() {
+        assert_eq!(MessageClass::of(&[1, 1, 0, 0]), MessageClass::Vote);
+        assert_eq!(MessageClass::of(&[1, 6]), MessageClass::Vote);
+        assert_eq!(MessageClass::of(&[1, 7, 0]), MessageClass::Certificate);
+        assert_eq!(MessageClass::of(&[1, 12]), MessageClass::Certificate);
+        assert_eq!(MessageClass::of(&[1, 13]), MessageClass::Unclassified);
+        assert_eq!(MessageClass::of(&[2, 1]), MessageClass::Unclassified);
+        assert_eq!(MessageClass::of(&[1]), MessageClass::Unclassified);
+        assert_eq!(MessageClass::of(b"probe"), MessageClass::Unclassified);
+    }
+
+    #[test]
+    fn test_certificate_repair_class() {
+        let certificate = |slot: u64| [&[1, 7][..], &slot.to_le_bytes()].concat();
+        let limiter = PeerRateLimiter::new(10.0);
+        assert_eq!(limiter.classify(&[1, 1]), MessageClass::Vote);
+        assert_eq!(limiter.classify(&[1, 7, 0]), MessageClass::Certificate);
+        assert_eq!(
+            limiter.classify(&certificate(100)),
+            MessageClass::Certificate
+        );
+        let lagging = 100 - CERTIFICATE_REPAIR_SLOT_LAG;
+        assert_eq!(
+            limiter.classify(&certificate(lagging)),
+            MessageClass::Certificate
+        );
+        assert_eq!(
+            limiter.classify(&certificate(lagging - 1)),
+            MessageClass::CertificateRepair
+        );
+        // rate=10 => repair burst 4, independent of the certificate budget.
+        let admitted = (0..10)
+            .filter(|_| limiter.charge(MessageClass::CertificateRepair) == Admission::Accept)
+            .count();
+        assert_eq!(admitted, 4);
+        assert_eq!(limiter.charge(MessageClass::Certificate), Admission::Accept);
+    }
+
+    #[test]
+    fn test_stake_rate_multiplier() {
+        let (light, average, heavy, whale, unstaked) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        // Average stake is 100.
+        let stakes = HashMap::from([(light, 10), (average, 100), (heavy, 200), (whale, 90)]);
+        assert_eq!(stake_rate_multiplier(&stakes, &light), 1.0);
+        assert_eq!(stake_rate_multiplier(&stakes, &average), 1.0);
+        assert_eq!(stake_rate_multiplier(&stakes, &heavy), 2.0);
+        assert_eq!(stake_rate_multiplier(&stakes, &unstaked), 1.0);
+        let stakes = HashMap::from([(whale, 1_000_000), (light, 1), (average, 1)]);
+        assert_eq!(
+            stake_rate_multiplier(&stakes, &whale),
+            MAX_STAKE_RATE_MULTIPLIER
+        );
+        assert_eq!(stake_rate_multiplier(&HashMap::new(), &whale), 1.0);
+    }
+
+    #[test]
+    fn test_class_budgets() {
+        const VOTE: &[u8] = &[1, 1];
+        const CERTIFICATE: &[u8] = &[1, 9];
+        // rate=10 => total burst 10, vote burst 6, certificate burst 8.
+        let limiter = PeerRateLimiter::new(10.0);
+        let admitted = |class| {
+            (0..20)
+                .filter(|_| limiter.charge(class) == Admission::Accept)
+                .count()
+        };
+        // Votes alone can not use the whole budget...
+        assert_eq!(admitted(MessageClass::of(VOTE)), 6);
+        assert!(!limiter.is_full());
+        // ...and the total budget is spent after 10 datagrams of any class.
+        let limiter = PeerRateLimiter::new(10.0);
+        assert_eq!(
+            (0..5)
+                .map(|_| limiter.charge(MessageClass::of(VOTE)))
+                .chain((0..5).map(|_| limiter.charge(MessageClass::of(CERTIFICATE))))
+                .filter(|admission| *admission == Admission::Accept)
+                .count(),
+            10
+        );
+        assert_eq!(limiter.charge(MessageClass::Unclassified), Admission::Drop);
+    }
+
+    #[test]
+    fn test_class_flood() {
+        // rate=10 => vote capacity 60 of which 6 are burst, total capacity 100.
+        let limiter = PeerRateLimiter::new(10.0);
+        let admissions: Vec<_> = (0..61)
+            .map(|_| limiter.charge(MessageClass::Vote))
+            .collect();
+        assert_eq!(admissions[..6], [Admission::Accept; 6]);
+        assert!(admissions[6..60].iter().all(|a| *a == Admission::Drop));
+        assert_eq!(admissions[60], Admission::Flood);
+    }
+}
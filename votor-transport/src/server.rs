@@ -2,12 +2,16 @@
 use {
     crate::{
         HANDSHAKE_TIMEOUT, MAX_INBOUND_CONNECTIONS_PER_PEER, METRICS_INTERVAL,
-        PEER_RATE_LIMIT_BURST_WINDOW, PEER_RATE_LIMIT_DOS_WINDOW, PeerListReceiver, close_codes,
+        PEER_METRICS_INTERVAL, PeerListReceiver, PeerStakesReceiver, close_codes,
         endpoint::{BanCommand, Datagram},
         error::Error,
-        stats::{self, ServerStats, record_server_error},
+        qos::{Admission, PeerRateLimiter, stake_rate_multiplier},
+        stats::{
+            self, PeerStats, PeerStatsSnapshot, PeerStatsTable, ServerStats, record_server_error,
+        },
         transport::new_server_config,
     },
+    arc_swap::ArcSwap,
     arrayvec::ArrayVec,
     crossbeam_channel::{Sender, TrySendError},
     log::{debug, error, info, warn},
@@ -33,8 +37,13 @@ use {
 /// Tracks resource use by one peer
 pub(crate) struct PeerEntry {
     connections: ArrayVec<Connection, MAX_INBOUND_CONNECTIONS_PER_PEER>,
-    /// Shared ingress data ratelimiter for all connections of this peer.
-    rate_limiter: Arc<TokenBucket>,
+    /// Shared ingress data ratelimiter for all connections of this peer,
+    /// replaced when a stake change moves the peer's rate.
+    rate_limiter: Arc<ArcSwap<PeerRateLimiter>>,
+    /// Shared ingress counters for all connections of this peer.
+    stats: Arc<PeerStats>,
+    /// Counters as of the last per-peer datapoint.
+    reported_stats: PeerStatsSnapshot,
 }
 
 /// Event reported to the InboundLoop.
@@ -212,11 +221,10 @@ pub(crate) struct ConnectionReader {
     peer: Pubkey,
     remote_addr: SocketAddr,
     ingress: Sender<Datagram>,
-    rate_limiter: Arc<TokenBucket>,
-    /// Tokens that may remain before shaping kicks in (burst headroom).
-    rate_limit_watermark: u64,
+    rate_limiter: Arc<ArcSwap<PeerRateLimiter>>,
     events_sender: mpsc::Sender<InboundConnectionEvent>,
     stats: Arc<ServerStats>,
+    peer_stats: Arc<PeerStats>,
 }
 
 impl ConnectionReader {
@@ -227,24 +235,29 @@ impl ConnectionReader {
             remote_addr,
             ingress,
             rate_limiter,
-            rate_limit_watermark,
             events_sender,
             stats,
+            peer_stats,
         } = self;
         let stable_id = connection.stable_id();
         loop {
             match connection.read_datagram().await {
                 Ok(bytes) => {
-                    match rate_limiter.consume_tokens(1) {
-                        // normal operation
-                        Ok(remaining) if remaining >= rate_limit_watermark => {}
-                        // drop excess packets if peer exceeds normal rate
-                        Ok(_) => {
-                            stats.datagram_rate_limited.fetch_add(1, Ordering::Relaxed);
+                    let (class, admission) = {
+                        let rate_limiter = rate_limiter.load();
+                        let class = rate_limiter.classify(&bytes);
+                        (class, rate_limiter.charge(class))
+                    };
+                    match admission {
+                        Admission::Accept => peer_stats.record_received(class, bytes.len()),
+                        // drop excess packets if peer exceeds its budgets
+                        Admission::Drop => {
+                            stats.record_rate_limited(class);
+                            peer_stats.record_rate_limited(class);
                             continue;
                         }
-                        // peer drained bucket dry - kick them
-                        Err(_) => {
+                        // peer drained a budget dry - kick them
+                        Admission::Flood => {
                             let _ = events_sender
                                 .send(InboundConnectionEvent::FloodDetected { peer })
                                 .await;
@@ -295,6 +308,10 @@ pub(crate) struct InboundLoop {
     ban_receiver: mpsc::Receiver<BanCommand>,
     /// Latest version of the admitted peer list.
     peer_list_receiver: PeerListReceiver,
+    /// Latest stakes of the admitted peers, weighting their rate limits.
+    peer_stakes_receiver: PeerStakesReceiver,
+    /// Publishes per-peer stats every reporting period.
+    peer_stats_sender: watch::Sender<PeerStatsTable>,
     /// Identity-rotation notification channel.
     identity_receiver: watch::Receiver<Keypair>,
     /// Endpoints that handle connections. On identity rotation we need to
@@ -310,12 +327,9 @@ pub(crate) struct InboundLoop {
     events_receiver: mpsc::Receiver<InboundConnectionEvent>,
     stats: Arc<ServerStats>,
     cancel: CancellationToken,
-    /// Sustained datagrams-per-second each peer is allowed to send.
+    /// Sustained datagrams-per-second each peer is allowed to send, before
+    /// stake weighting.
     max_datagrams_per_second_per_peer: usize,
-    /// Burst headroom above the sustained rate, in tokens.
-    peer_rate_limit_burst: u64,
-    /// Bucket capacity; draining it dry trips flood control.
-    peer_rate_limit_burst_dos: u64,
 }
 
 impl InboundLoop {
@@ -324,6 +338,8 @@ impl InboundLoop {
         ingress: Sender<Datagram>,
         ban_receiver: mpsc::Receiver<BanCommand>,
         peer_list_receiver: PeerListReceiver,
+        peer_stakes_receiver: PeerStakesReceiver,
+        peer_stats_sender: watch::Sender<PeerStatsTable>,
         endpoints: Vec<Endpoint>,
         inbound_events_sender: mpsc::Sender<InboundConnectionEvent>,
         inbound_events_receiver: mpsc::Receiver<InboundConnectionEvent>,
@@ -332,17 +348,13 @@ impl InboundLoop {
         cancel: CancellationToken,
         max_datagrams_per_second_per_peer: usize,
     ) -> Self {
-        let tokens_over = |window: Duration| {
-            (max_datagrams_per_second_per_peer as f64 * window.as_secs_f64()).ceil() as u64
-        };
-        let peer_rate_limit_burst = tokens_over(PEER_RATE_LIMIT_BURST_WINDOW).max(1);
-        let peer_rate_limit_burst_dos =
-            tokens_over(PEER_RATE_LIMIT_DOS_WINDOW).max(peer_rate_limit_burst.saturating_add(1));
         Self {
             ingress,
             banlist: Banlist::default(),
             ban_receiver,
             peer_list_receiver,
+            peer_stakes_receiver,
+            peer_stats_sender,
             identity_receiver,
             endpoints,
             peer_state: HashMap::with_hasher(PubkeyHasherBuilder::default()),
@@ -352,8 +364,6 @@ impl InboundLoop {
             stats,
             cancel,
             max_datagrams_per_second_per_peer,
-            peer_rate_limit_burst,
-            peer_rate_limit_burst_dos,
         }
     }
 
@@ -370,7 +380,11 @@ impl InboundLoop {
         let mut metrics = interval(METRICS_INTERVAL);
         metrics.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
+        let mut peer_metrics = interval(PEER_METRICS_INTERVAL);
+        peer_metrics.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
         let mut peer_list_receiver = self.peer_list_receiver.clone();
+        let mut peer_stakes_receiver = self.peer_stakes_receiver.clone();
         let mut identity_receiver = self.identity_receiver.clone();
 
         info!("Votor QUIC transport server ready.");
@@ -416,18 +430,28 @@ impl InboundLoop {
                     }
                     self.close_not_allowed();
                 }
+                // Peer stakes changed, typically at an epoch boundary.
+                changed = peer_stakes_receiver.changed() => {
+                    if changed.is_err() {
+                        // Unreachable: PeerListService exits when we drop the receiver.
+                        error!("InboundLoop: peer_stakes channel closed while running, exiting.");
+                        debug_assert!(false, "peer_stakes channel closed while running");
+                        break;
+                    }
+                    self.refresh_rate_limiters();
+                }
                 // Take care of metrics and bookkeeping that does not affect liveness.
                 _ = metrics.tick() => {
                     debug!("InboundLoop: running bookkeeping tasks");
                     self.stats.report(self.total_peers());
                     self.banlist.prune();
                     // Reclaim empty connection slots
-                    let burst_dos = self.peer_rate_limit_burst_dos;
                     self.peer_state.retain(|_, e| {
-                        !e.connections.is_empty()
-                            || e.rate_limiter.current_tokens() < burst_dos
+                        !e.connections.is_empty() || !e.rate_limiter.load().is_full()
                     });
+                    self.publish_peer_stats();
                 }
+                _ = peer_metrics.tick() => self.report_peer_stats(),
                 _ = self.cancel.cancelled() => {
                     break
                 },
@@ -440,6 +464,44 @@ impl InboundLoop {
         self.close_all(close_codes::NORMAL_CLOSE);
     }
 
+    /// Publish a snapshot of every tracked peer's stats.
+    fn publish_peer_stats(&self) {
+        let peer_stats = self
+            .peer_state
+            .iter()
+            .map(|(peer, entry)| {
+                let snapshot = entry.stats.snapshot(entry.rate_limiter.load().rate());
+                (*peer, snapshot)
+            })
+            .collect();
+        self.peer_stats_sender.send_replace(Arc::new(peer_stats));
+    }
+
+    /// Emit a datapoint for every peer that sent traffic since the last report.
+    fn report_peer_stats(&mut self) {
+        for (peer, entry) in self.peer_state.iter_mut() {
+            let snapshot = entry.stats.snapshot(entry.rate_limiter.load().rate());
+            snapshot.report(peer, &entry.reported_stats);
+            entry.reported_stats = snapshot;
+        }
+    }
+
+    /// Replace the rate limiters of peers whose stake weighted rate changed.
+    /// The new limiter starts with full budgets.
+    fn refresh_rate_limiters(&self) {
+        let peer_stakes = self.peer_stakes_receiver.borrow().clone();
+        for (peer, entry) in self.peer_state.iter() {
+            let rate = self.max_datagrams_per_second_per_peer as f64
+                * stake_rate_multiplier(&peer_stakes, peer);
+            if entry.rate_limiter.load().rate() != rate {
+                debug!("InboundLoop: rate limit of {peer} changed to {rate} datagrams/s");
+                entry
+                    .rate_limiter
+                    .store(Arc::new(PeerRateLimiter::new(rate)));
+            }
+        }
+    }
+
     /// Close every inbound connection and return how many were closed.
     fn close_all(&self, close_code: close_codes::Spec) -> u64 {
         self.peer_state
@@ -514,6 +576,7 @@ impl InboundLoop {
                 match self.peer_state.get_mut(&peer) {
                     Some(entry) => {
                         warn!("Peer {peer} is flooding packets, closing their connections.");
+                        entry.stats.record_flood_disconnect();
                         let closed = entry.connections.len() as u64;
                         for connection in entry.connections.iter() {
                             close_codes::FLOODING.close(connection);
@@ -544,25 +607,28 @@ impl InboundLoop {
             record_server_error(&Error::NotAdmitted(peer), &self.stats);
             return;
         }
-        let rate_limiter = match self.peer_state.entry(peer) {
+        let (rate_limiter, peer_stats) = match self.peer_state.entry(peer) {
             Entry::Vacant(slot) => {
-                let rate_limiter = Arc::new(TokenBucket::new(
-                    self.peer_rate_limit_burst_dos,
-                    self.peer_rate_limit_burst_dos,
-                    self.max_datagrams_per_second_per_peer as f64,
-                ));
+                let stake_multiplier =
+                    stake_rate_multiplier(&self.peer_stakes_receiver.borrow(), &peer);
+                let rate_limiter = Arc::new(ArcSwap::from_pointee(PeerRateLimiter::new(
+                    self.max_datagrams_per_second_per_peer as f64 * stake_multiplier,
+                )));
+                let peer_stats = Arc::new(PeerStats::default());
                 let mut connections = ArrayVec::new();
                 connections.push(connection.clone());
                 slot.insert(PeerEntry {
                     connections,
                     rate_limiter: rate_limiter.clone(),
+                    stats: peer_stats.clone(),
+                    reported_stats: PeerStatsSnapshot::default(),
                 });
-                rate_limiter
+                (rate_limiter, peer_stats)
             }
             Entry::Occupied(mut slot) => {
                 let entry = slot.get_mut();
                 match entry.connections.try_push(connection.clone()) {
-                    Ok(()) => Arc::clone(&entry.rate_limiter),
+                    Ok(()) => (Arc::clone(&entry.rate_limiter), Arc::clone(&entry.stats)),
                     Err(_) => {
                         debug!(
                             "Could not admit a connection from {peer} ({remote_addr}) - all slots \
@@ -586,11 +652,9 @@ impl InboundLoop {
                 remote_addr,
                 ingress: self.ingress.clone(),
                 rate_limiter,
-                rate_limit_watermark: self
-                    .peer_rate_limit_burst_dos
-                    .saturating_sub(self.peer_rate_limit_burst),
                 events_sender: self.events_sender.clone(),
                 stats: self.stats.clone(),
+                peer_stats,
             }
             .run(),
         );
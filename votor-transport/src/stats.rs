@@ -1,10 +1,17 @@
 #[cfg(test)]
 use std::sync::atomic::AtomicBool;
 use {
-    crate::error::Error,
+    crate::{error::Error, qos::MessageClass},
     quinn::ConnectionError,
     solana_metrics::datapoint_info,
-    std::sync::atomic::{AtomicU64, Ordering},
+    solana_pubkey::Pubkey,
+    std::{
+        collections::HashMap,
+        sync::{
+            Arc,
+            atomic::{AtomicU64, Ordering},
+        },
+    },
 };
 
 /// Counters for the outbound (we-connect, send-only) direction.
@@ -66,6 +73,12 @@ pub struct ServerStats {
     pub(crate) datagram_ingress_dropped_channel_full: AtomicU64,
     /// Peer's incoming datagram exceeded the per-connection rate.
     pub(crate) datagram_rate_limited: AtomicU64,
+    /// Of `datagram_rate_limited`, the datagrams that were votes.
+    pub(crate) vote_rate_limited: AtomicU64,
+    /// Of `datagram_rate_limited`, the datagrams that were certificates.
+    pub(crate) certificate_rate_limited: AtomicU64,
+    /// Of `datagram_rate_limited`, the datagrams that were certificate repairs.
+    pub(crate) certificate_repair_rate_limited: AtomicU64,
     /// When set, `report_server` skips its periodic emit-and-reset,
     /// so counters stay cumulative for assertions that need
     /// totals across reporting ticks.
@@ -73,6 +86,179 @@ pub struct ServerStats {
     pub(crate) report_frozen: AtomicBool,
 }
 
+/// Counters for one inbound peer, shared by all of its connections.
+#[derive(Default)]
+pub(crate) struct PeerStats {
+    votes_received: AtomicU64,
+    certificates_received: AtomicU64,
+    certificate_repairs_received: AtomicU64,
+    unclassified_received: AtomicU64,
+    bytes_received: AtomicU64,
+    votes_rate_limited: AtomicU64,
+    certificates_rate_limited: AtomicU64,
+    certificate_repairs_rate_limited: AtomicU64,
+    unclassified_rate_limited: AtomicU64,
+    flood_disconnects: AtomicU64,
+}
+
+/// Point-in-time copy of an inbound peer's counters. Counters are cumulative
+/// for as long as the endpoint tracks the peer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PeerStatsSnapshot {
+    /// Sustained datagrams per second the peer may send, including its stake weight.
+    pub max_datagrams_per_second: f64,
+    /// Votes that passed rate limiting.
+    pub votes_received: u64,
+    /// Certificates that passed rate limiting.
+    pub certificates_received: u64,
+    /// Certificate repairs that passed rate limiting.
+    pub certificate_repairs_received: u64,
+    /// Other datagrams that passed rate limiting.
+    pub unclassified_received: u64,
+    /// Total size of the datagrams that passed rate limiting.
+    pub bytes_received: u64,
+    /// Votes dropped for exceeding the peer's budgets.
+    pub votes_rate_limited: u64,
+    /// Certificates dropped for exceeding the peer's budgets.
+    pub certificates_rate_limited: u64,
+    /// Certificate repairs dropped for exceeding the peer's budgets.
+    pub certificate_repairs_rate_limited: u64,
+    /// Other datagrams dropped for exceeding the peer's budgets.
+    pub unclassified_rate_limited: u64,
+    /// Times the peer's connections were closed for flooding.
+    pub flood_disconnects: u64,
+}
+
+/// Latest per-peer stats of an endpoint, refreshed every reporting period.
+pub type PeerStatsTable = Arc<HashMap<Pubkey, PeerStatsSnapshot>>;
+
+impl PeerStats {
+    pub(crate) fn record_received(&self, class: MessageClass, bytes: usize) {
+        let received = match class {
+            MessageClass::Vote => &self.votes_received,
+            MessageClass::Certificate => &self.certificates_received,
+            MessageClass::CertificateRepair => &self.certificate_repairs_received,
+            MessageClass::Unclassified => &self.unclassified_received,
+        };
+        received.fetch_add(1, Ordering::Relaxed);
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_rate_limited(&self, class: MessageClass) {
+        let rate_limited = match class {
+            MessageClass::Vote => &self.votes_rate_limited,
+            MessageClass::Certificate => &self.certificates_rate_limited,
+            MessageClass::CertificateRepair => &self.certificate_repairs_rate_limited,
+            MessageClass::Unclassified => &self.unclassified_rate_limited,
+        };
+        rate_limited.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_flood_disconnect(&self) {
+        self.flood_disconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self, max_datagrams_per_second: f64) -> PeerStatsSnapshot {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        PeerStatsSnapshot {
+            max_datagrams_per_second,
+            votes_received: load(&self.votes_received),
+            certificates_received: load(&self.certificates_received),
+            certificate_repairs_received: load(&self.certificate_repairs_received),
+            unclassified_received: load(&self.unclassified_received),
+            bytes_received: load(&self.bytes_received),
+            votes_rate_limited: load(&self.votes_rate_limited),
+            certificates_rate_limited: load(&self.certificates_rate_limited),
+            certificate_repairs_rate_limited: load(&self.certificate_repairs_rate_limited),
+            unclassified_rate_limited: load(&self.unclassified_rate_limited),
+            flood_disconnects: load(&self.flood_disconnects),
+        }
+    }
+}
+
+impl PeerStatsSnapshot {
+    /// Emit the counters accumulated since `previous`, the snapshot reported
+    /// last time for the same peer. Idle peers are skipped.
+    pub(crate) fn report(&self, peer: &Pubkey, previous: &PeerStatsSnapshot) {
+        if self == previous {
+            return;
+        }
+        let delta = |current: u64, previous: u64| current.saturating_sub(previous) as i64;
+        datapoint_info!(
+            "votor_datagram_server_peer",
+            "peer" => peer.to_string(),
+            (
+                "max_datagrams_per_second",
+                self.max_datagrams_per_second,
+                f64
+            ),
+            (
+                "votes_received",
+                delta(self.votes_received, previous.votes_received),
+                i64
+            ),
+            (
+                "certificates_received",
+                delta(self.certificates_received, previous.certificates_received),
+                i64
+            ),
+            (
+                "certificate_repairs_received",
+                delta(
+                    self.certificate_repairs_received,
+                    previous.certificate_repairs_received
+                ),
+                i64
+            ),
+            (
+                "unclassified_received",
+                delta(self.unclassified_received, previous.unclassified_received),
+                i64
+            ),
+            (
+                "bytes_received",
+                delta(self.bytes_received, previous.bytes_received),
+                i64
+            ),
+            (
+                "votes_rate_limited",
+                delta(self.votes_rate_limited, previous.votes_rate_limited),
+                i64
+            ),
+            (
+                "certificates_rate_limited",
+                delta(
+                    self.certificates_rate_limited,
+                    previous.certificates_rate_limited
+                ),
+                i64
+            ),
+            (
+                "certificate_repairs_rate_limited",
+                delta(
+                    self.certificate_repairs_rate_limited,
+                    previous.certificate_repairs_rate_limited
+                ),
+                i64
+            ),
+            (
+                "unclassified_rate_limited",
+                delta(
+                    self.unclassified_rate_limited,
+                    previous.unclassified_rate_limited
+                ),
+                i64
+            ),
+            (
+                "flood_disconnects",
+                delta(self.flood_disconnects, previous.flood_disconnects),
+                i64
+            ),
+        );
+    }
+}
+
 /// Raise a peak-occupancy high-water mark to `count` if it is higher.
 pub(crate) fn record_connection_count(peak: &AtomicU64, count: u64) {
     peak.fetch_max(count, Ordering::Relaxed);
@@ -194,6 +380,18 @@ impl ClientStats {
 }
 
 impl ServerStats {
+    /// Count a datagram dropped by the per-peer rate limiter.
+    pub(crate) fn record_rate_limited(&self, class: MessageClass) {
+        self.datagram_rate_limited.fetch_add(1, Ordering::Relaxed);
+        let class_rate_limited = match class {
+            MessageClass::Vote => &self.vote_rate_limited,
+            MessageClass::Certificate => &self.certificate_rate_limited,
+            MessageClass::CertificateRepair => &self.certificate_repair_rate_limited,
+            MessageClass::Unclassified => return,
+        };
+        class_rate_limited.fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Emit and reset the inbound counters.
     pub(crate) fn report(&self, live_connections: u64) {
         #[cfg(test)]
@@ -210,6 +408,9 @@ impl ServerStats {
         let connection_failed = swap(&self.connection_failed);
         let connection_lost = swap(&self.connection_lost);
         let datagram_rate_limited = swap(&self.datagram_rate_limited);
+        let vote_rate_limited = swap(&self.vote_rate_limited);
+        let certificate_rate_limited = swap(&self.certificate_rate_limited);
+        let certificate_repair_rate_limited = swap(&self.certificate_repair_rate_limited);
         let datagram_ingress_dropped_channel_full =
             swap(&self.datagram_ingress_dropped_channel_full);
         let handshake_rejected_unauthorized = swap(&self.handshake_rejected_unauthorized);
@@ -228,6 +429,13 @@ impl ServerStats {
             ("connection_failed", connection_failed, i64),
             ("connection_lost", connection_lost, i64),
             ("datagram_rate_limited", datagram_rate_limited, i64),
+            ("vote_rate_limited", vote_rate_limited, i64),
+            ("certificate_rate_limited", certificate_rate_limited, i64),
+            (
+                "certificate_repair_rate_limited",
+                certificate_repair_rate_limited,
+                i64
+            ),
             (
                 "datagram_ingress_dropped_channel_full",
                 datagram_ingress_dropped_channel_full,
@@ -1,4 +1,5 @@
 use {
+    crate::consensus_timeline::{ConsensusTimeline, TimelineEventKind, unix_timestamp_us},
     agave_math_utils::welford_stats::WelfordStats,
     agave_votor_messages::{
         metric_types::{ConsensusMetricsEvent, ConsensusMetricsEventReceiver},
@@ -23,6 +24,9 @@ use {
 /// Number of epochs to retain metrics for (current + previous).
 const EPOCHS_TO_RETAIN: u64 = 2;
 
+/// How often the consensus timeline is written to disk.
+const TIMELINE_PERSIST_INTERVAL: Duration = Duration::from_secs(10);
+
 /// Tracks all [`Vote`] metrics for a given node.
 #[derive(Debug, Default)]
 struct NodeVoteMetrics {
@@ -93,29 +97,43 @@ pub struct ConsensusMetrics {
 
     /// Receiver for events.
     receiver: ConsensusMetricsEventReceiver,
+
+    /// Per-slot record of the received events.
+    timeline: Arc<ConsensusTimeline>,
+
+    /// When the timeline was last written to disk.
+    timeline_persisted: Instant,
 }
 
 impl ConsensusMetrics {
-    fn new(epoch_schedule: EpochSchedule, receiver: ConsensusMetricsEventReceiver) -> Self {
+    fn new(
+        epoch_schedule: EpochSchedule,
+        receiver: ConsensusMetricsEventReceiver,
+        timeline: Arc<ConsensusTimeline>,
+    ) -> Self {
         Self {
             epoch_metrics: BTreeMap::default(),
             emitted_epochs: BTreeSet::default(),
             highest_finalized_slot: None,
             epoch_schedule,
             receiver,
+            timeline,
+            timeline_persisted: Instant::now(),
         }
     }
 
     pub fn start_metrics_loop(
         epoch_schedule: EpochSchedule,
         receiver: ConsensusMetricsEventReceiver,
+        timeline: Arc<ConsensusTimeline>,
         exit: Arc<AtomicBool>,
     ) -> JoinHandle<()> {
         Builder::new()
             .name("solVotorMetrics".into())
             .spawn(move || {
-                let mut metrics = Self::new(epoch_schedule, receiver);
+                let mut metrics = Self::new(epoch_schedule, receiver, timeline);
                 metrics.run(exit);
+                metrics.persist_timeline();
             })
             .expect("Failed to start consensus metrics thread")
     }
@@ -125,20 +143,7 @@ impl ConsensusMetrics {
             match self.receiver.recv_timeout(Duration::from_secs(1)) {
                 Ok((received, events)) => {
                     for event in events {
-                        match event {
-                            ConsensusMetricsEvent::Vote { id, vote } => {
-                                self.record_vote(id, &vote, received);
-                            }
-                            ConsensusMetricsEvent::BlockHashSeen { leader, slot } => {
-                                self.record_block_hash_seen(leader, slot, received);
-                            }
-                            ConsensusMetricsEvent::StartOfSlot { slot } => {
-                                self.record_start_of_slot(slot, received);
-                            }
-                            ConsensusMetricsEvent::SlotFinalized { slot } => {
-                                self.handle_slot_finalized(slot);
-                            }
-                        }
+                        self.handle_event(event, received);
                     }
                 }
                 Err(err) => match err {
@@ -149,9 +154,91 @@ impl ConsensusMetrics {
                     }
                 },
             }
+            if self.timeline_persisted.elapsed() >= TIMELINE_PERSIST_INTERVAL {
+                self.persist_timeline();
+            }
         }
     }
 
+    fn handle_event(&mut self, event: ConsensusMetricsEvent, received: Instant) {
+        let timestamp_us = unix_timestamp_us(received);
+        match event {
+            ConsensusMetricsEvent::Vote { id, vote, stake } => {
+                self.record_vote(id, &vote, received);
+                self.timeline
+                    .record_received_vote(&vote, stake, timestamp_us);
+            }
+            ConsensusMetricsEvent::VoteSent { vote, stake } => {
+                self.timeline.record(
+                    vote.slot(),
+                    timestamp_us,
+                    TimelineEventKind::VoteSent {
+                        vote: (&vote).into(),
+                        block_id: vote.block_id().copied(),
+                        stake,
+                    },
+                );
+            }
+            ConsensusMetricsEvent::FirstShred { slot } => {
+                self.timeline
+                    .record(slot, timestamp_us, TimelineEventKind::FirstShred);
+            }
+            ConsensusMetricsEvent::BlockReplayed { slot, block_id } => {
+                self.timeline.record(
+                    slot,
+                    timestamp_us,
+                    TimelineEventKind::BlockReplayed { block_id },
+                );
+            }
+            ConsensusMetricsEvent::CertificateFormed { cert_type } => {
+                self.timeline.record(
+                    cert_type.slot(),
+                    timestamp_us,
+                    TimelineEventKind::CertificateFormed {
+                        certificate: (&cert_type).into(),
+                        block_id: cert_type.to_block().map(|block| block.block_id),
+                    },
+                );
+            }
+            ConsensusMetricsEvent::Timeout {
+                slot,
+                crashed_leader,
+            } => {
+                self.timeline.record(
+                    slot,
+                    timestamp_us,
+                    TimelineEventKind::Timeout { crashed_leader },
+                );
+            }
+            ConsensusMetricsEvent::ParentReady { slot, parent_block } => {
+                self.timeline.record(
+                    slot,
+                    timestamp_us,
+                    TimelineEventKind::ParentReady {
+                        parent_slot: parent_block.slot,
+                        parent_block_id: parent_block.block_id,
+                    },
+                );
+            }
+            ConsensusMetricsEvent::BlockHashSeen { leader, slot } => {
+                self.record_block_hash_seen(leader, slot, received);
+            }
+            ConsensusMetricsEvent::StartOfSlot { slot } => {
+                self.record_start_of_slot(slot, received);
+            }
+            ConsensusMetricsEvent::SlotFinalized { slot } => {
+                self.handle_slot_finalized(slot);
+            }
+        }
+    }
+
+    fn persist_timeline(&mut self) {
+        if let Err(err) = self.timeline.persist() {
+            warn!("Unable to persist consensus timeline: {err}");
+        }
+        self.timeline_persisted = Instant::now();
+    }
+
     fn epoch_metrics_for_slot(&mut self, slot: Slot) -> &mut EpochMetrics {
         let epoch = self.epoch_schedule.get_epoch(slot);
         self.epoch_metrics.entry(epoch).or_default()
@@ -305,8 +392,14 @@ impl ConsensusMetrics {
 mod tests {
     use {
         super::*,
-        agave_votor_messages::vote::{SkipVote, Vote},
+        crate::consensus_timeline::{TimelineCertificate, TimelineVote},
+        agave_votor_messages::{
+            certificate::CertificateType,
+            consensus_message::Block,
+            vote::{SkipVote, Vote},
+        },
         crossbeam_channel::bounded,
+        solana_hash::Hash,
         solana_keypair::Keypair,
         solana_signer::Signer,
         std::thread::sleep,
@@ -314,7 +407,11 @@ mod tests {
 
     fn new_metrics() -> ConsensusMetrics {
         let (_, rx) = bounded(1024);
-        ConsensusMetrics::new(EpochSchedule::custom(100, 100, false), rx) // 100 slots/epoch
+        ConsensusMetrics::new(
+            EpochSchedule::custom(100, 100, false), // 100 slots/epoch
+            rx,
+            Arc::new(ConsensusTimeline::new(16)),
+        )
     }
 
     #[test]
@@ -429,4 +526,61 @@ mod tests {
 
         assert_eq!(metrics.epoch_metrics[&0].leader_metrics[&leader].count(), 1);
     }
+
+    #[test]
+    fn test_timeline_events() {
+        let mut metrics = new_metrics();
+        let block = Block {
+            slot: 42,
+            block_id: Hash::new_unique(),
+        };
+        let received = Instant::now();
+
+        metrics.handle_event(ConsensusMetricsEvent::FirstShred { slot: 42 }, received);
+        metrics.handle_event(
+            ConsensusMetricsEvent::VoteSent {
+                vote: Vote::new_notarization_vote(block),
+                stake: 100,
+            },
+            received,
+        );
+        metrics.handle_event(
+            ConsensusMetricsEvent::Vote {
+                id: Keypair::new().pubkey(),
+                vote: Vote::new_notarization_vote(block),
+                stake: 200,
+            },
+            received,
+        );
+        metrics.handle_event(
+            ConsensusMetricsEvent::CertificateFormed {
+                cert_type: CertificateType::Notarize(block),
+            },
+            received,
+        );
+
+        let slot_timeline = &metrics.timeline.get(42, 42)[0];
+        let kinds: Vec<_> = slot_timeline
+            .events
+            .iter()
+            .map(|event| event.kind.clone())
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TimelineEventKind::FirstShred,
+                TimelineEventKind::VoteSent {
+                    vote: TimelineVote::Notarize,
+                    block_id: Some(block.block_id),
+                    stake: 100,
+                },
+                TimelineEventKind::CertificateFormed {
+                    certificate: TimelineCertificate::Notarize,
+                    block_id: Some(block.block_id),
+                },
+            ]
+        );
+        assert_eq!(slot_timeline.received_votes.len(), 1);
+        assert_eq!(slot_timeline.received_votes[0].stake, 200);
+    }
 }
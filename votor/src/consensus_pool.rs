@@ -151,7 +151,10 @@ impl ConsensusPool {
                 self.parent_ready_tracker
                     .add_new_notar_fallback_or_stronger(block, events);
             }
-            CertificateType::Skip(slot) => self.parent_ready_tracker.add_new_skip(slot, events),
+            CertificateType::Skip(slot) => {
+                events.push(VotorEvent::SlotSkipped(slot));
+                self.parent_ready_tracker.add_new_skip(slot, events);
+            }
             CertificateType::Notarize(block) => {
                 events.push(VotorEvent::BlockNotarized(block));
                 self.parent_ready_tracker
@@ -31,6 +31,7 @@ use {
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::{blockstore::Blockstore, leader_schedule_cache::LeaderScheduleCache},
     solana_pubkey::Pubkey,
+    solana_rpc::rpc_subscriptions::RpcSubscriptions,
     solana_runtime::{
         bank::Bank, bank_forks::SharableBanks,
         leader_schedule_utils::last_of_consecutive_leader_slots,
@@ -95,6 +96,7 @@ pub(crate) struct ConsensusPoolContext {
     pub(crate) bls_sender: Sender<BLSOp>,
     pub(crate) event_sender: VotorEventSender,
     pub(crate) repair_event_sender: RepairEventSender,
+    pub(crate) rpc_subscriptions: Option<Arc<RpcSubscriptions>>,
 
     /// Used to communicate the highest finalization cert the pool has observed to the block creation loop.
     pub(crate) highest_finalized: Arc<RwLock<Option<ValidatedBlockFinalizationCert>>>,
@@ -203,6 +205,9 @@ impl ConsensusPoolService {
         let bank = ctx.sharable_banks.root();
         consensus_pool.maybe_prune(bank.slot());
         stats.prune_old_state_called += 1;
+        if let Some(rpc_subscriptions) = &ctx.rpc_subscriptions {
+            rpc_subscriptions.notify_certificates(new_certificates_to_send.iter().map(Arc::as_ref));
+        }
         // Send new certificates to peers
         Self::send_certificates(
             ctx,
@@ -826,6 +831,7 @@ mod tests {
                 bls_sender,
                 event_sender,
                 repair_event_sender,
+                rpc_subscriptions: None,
                 highest_finalized: Arc::new(RwLock::new(None)),
             };
             let consensus_pool = ctx.new_consensus_pool();
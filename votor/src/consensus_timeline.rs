@@ -0,0 +1,448 @@
+//! A bounded, per-slot record of the consensus events observed by this node.
+//!
+//! [`ConsensusMetrics`](crate::consensus_metrics::ConsensusMetrics) only keeps per-epoch
+//! aggregates, which is not enough to reconstruct what happened in a given slot. The timeline
+//! keeps the individual events of the most recent slots instead, so they can be inspected over
+//! admin RPC and survive a restart.
+use {
+    agave_votor_messages::{certificate::CertificateType, vote::Vote},
+    solana_clock::Slot,
+    solana_hash::Hash,
+    std::{
+        collections::BTreeMap,
+        fs::{self, File},
+        io::{self, Write},
+        path::{Path, PathBuf},
+        sync::RwLock,
+        time::{Instant, SystemTime, UNIX_EPOCH},
+    },
+    thiserror::Error,
+    wincode::{SchemaRead, SchemaWrite},
+};
+
+/// Number of slots retained by the validator's timeline, a little over half an hour
+pub const DEFAULT_CONSENSUS_TIMELINE_SLOTS: usize = 4096;
+/// File in the ledger directory the timeline is persisted to
+pub const CONSENSUS_TIMELINE_FILE_NAME: &str = "consensus_timeline.bin";
+/// Received votes are aggregated per vote and block id, this bounds the entries
+/// equivocating validators can create for a single slot
+const MAX_RECEIVED_VOTE_ENTRIES_PER_SLOT: usize = 64;
+/// Bounds the events recorded for a slot that keeps being refreshed during standstill
+const MAX_EVENTS_PER_SLOT: usize = 256;
+
+pub type Result<T> = std::result::Result<T, ConsensusTimelineError>;
+
+#[derive(Error, Debug)]
+pub enum ConsensusTimelineError {
+    #[error("IO Error: {0}")]
+    IoError(#[from] io::Error),
+
+    #[error("Serialization Error: {0}")]
+    SerializeError(#[from] wincode::WriteError),
+
+    #[error("Deserialization Error: {0}")]
+    DeserializeError(#[from] wincode::ReadError),
+}
+
+/// The type of a vote recorded in the timeline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, SchemaRead, SchemaWrite)]
+pub enum TimelineVote {
+    Notarize,
+    NotarizeFallback,
+    Skip,
+    SkipFallback,
+    Finalize,
+    Genesis,
+}
+
+impl TimelineVote {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Notarize => "notarize",
+            Self::NotarizeFallback => "notarize_fallback",
+            Self::Skip => "skip",
+            Self::SkipFallback => "skip_fallback",
+            Self::Finalize => "finalize",
+            Self::Genesis => "genesis",
+        }
+    }
+}
+
+impl From<&Vote> for TimelineVote {
+    fn from(vote: &Vote) -> Self {
+        match vote {
+            Vote::Notarize(_) => Self::Notarize,
+            Vote::NotarizeFallback(_) => Self::NotarizeFallback,
+            Vote::Skip(_) => Self::Skip,
+            Vote::SkipFallback(_) => Self::SkipFallback,
+            Vote::Finalize(_) => Self::Finalize,
+            Vote::Genesis(_) => Self::Genesis,
+        }
+    }
+}
+
+/// The type of a certificate recorded in the timeline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, SchemaRead, SchemaWrite)]
+pub enum TimelineCertificate {
+    Notarize,
+    NotarizeFallback,
+    Skip,
+    Finalize,
+    FinalizeFast,
+    Genesis,
+}
+
+impl TimelineCertificate {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Notarize => "notarize",
+            Self::NotarizeFallback => "notarize_fallback",
+            Self::Skip => "skip",
+            Self::Finalize => "finalize",
+            Self::FinalizeFast => "finalize_fast",
+            Self::Genesis => "genesis",
+        }
+    }
+}
+
+impl From<&CertificateType> for TimelineCertificate {
+    fn from(cert_type: &CertificateType) -> Self {
+        match cert_type {
+            CertificateType::Notarize(_) => Self::Notarize,
+            CertificateType::NotarizeFallback(_) => Self::NotarizeFallback,
+            CertificateType::Skip(_) => Self::Skip,
+            CertificateType::Finalize(_) => Self::Finalize,
+            CertificateType::FinalizeFast(_) => Self::FinalizeFast,
+            CertificateType::Genesis(_) => Self::Genesis,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+pub enum TimelineEventKind {
+    /// The first shred of the slot was received
+    FirstShred,
+    /// The block completed replay
+    BlockReplayed { block_id: Hash },
+    /// This node sent a vote for the slot
+    VoteSent {
+        vote: TimelineVote,
+        block_id: Option<Hash>,
+        stake: u64,
+    },
+    /// A certificate for the slot was observed
+    CertificateFormed {
+        certificate: TimelineCertificate,
+        block_id: Option<Hash>,
+    },
+    /// The skip timeout, or the crashed leader timeout, fired for the slot
+    Timeout { crashed_leader: bool },
+    /// The slot became ready to be built on top of the parent
+    ParentReady {
+        parent_slot: Slot,
+        parent_block_id: Hash,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+pub struct TimelineEvent {
+    /// Microseconds since the unix epoch
+    pub timestamp_us: u64,
+    pub kind: TimelineEventKind,
+}
+
+/// Votes received from other validators for one vote type and block
+#[derive(Clone, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+pub struct ReceivedVotes {
+    pub vote: TimelineVote,
+    pub block_id: Option<Hash>,
+    pub count: u64,
+    pub stake: u64,
+    /// Microseconds since the unix epoch
+    pub first_received_us: u64,
+    /// Microseconds since the unix epoch
+    pub last_received_us: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+pub struct SlotTimeline {
+    pub slot: Slot,
+    /// Events in the order they were recorded
+    pub events: Vec<TimelineEvent>,
+    pub received_votes: Vec<ReceivedVotes>,
+}
+
+impl SlotTimeline {
+    fn new(slot: Slot) -> Self {
+        Self {
+            slot,
+            events: Vec::new(),
+            received_votes: Vec::new(),
+        }
+    }
+}
+
+/// Ring buffer of the [`SlotTimeline`]s of the `capacity` highest slots seen.
+///
+/// Recording into a full timeline evicts the lowest slot, events for slots below
+/// every retained slot are dropped.
+#[derive(Debug)]
+pub struct ConsensusTimeline {
+    capacity: usize,
+    path: Option<PathBuf>,
+    slots: RwLock<BTreeMap<Slot, SlotTimeline>>,
+}
+
+impl ConsensusTimeline {
+    /// Creates a timeline that is kept in memory only.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            path: None,
+            slots: RwLock::default(),
+        }
+    }
+
+    /// Creates a timeline persisted to `path`, starting from the previously
+    /// persisted timeline if there is one.
+    pub fn open(path: PathBuf, capacity: usize) -> Self {
+        let mut slots = match Self::load(&path) {
+            Ok(slots) => slots,
+            Err(ConsensusTimelineError::IoError(err)) if err.kind() == io::ErrorKind::NotFound => {
+                BTreeMap::new()
+            }
+            Err(err) => {
+                warn!(
+                    "Unable to load consensus timeline from {}, starting empty: {err}",
+                    path.display()
+                );
+                BTreeMap::new()
+            }
+        };
+        while slots.len() > capacity {
+            slots.pop_first();
+        }
+        Self {
+            capacity,
+            path: Some(path),
+            slots: RwLock::new(slots),
+        }
+    }
+
+    fn load(path: &Path) -> Result<BTreeMap<Slot, SlotTimeline>> {
+        Ok(wincode::deserialize(&fs::read(path)?)?)
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Writes the timeline to its file, does nothing for in-memory timelines.
+    pub fn persist(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let data = wincode::serialize(&*self.slots.read().unwrap())?;
+        let new_path = path.with_extension("bin.new");
+        {
+            let mut file = File::create(&new_path)?;
+            file.write_all(&data)?;
+        }
+        fs::rename(&new_path, path)?;
+        Ok(())
+    }
+
+    /// Records an event this node observed for `slot`.
+    pub fn record(&self, slot: Slot, timestamp_us: u64, kind: TimelineEventKind) {
+        let mut slots = self.slots.write().unwrap();
+        let Some(slot_timeline) = Self::slot_timeline_mut(&mut slots, self.capacity, slot) else {
+            return;
+        };
+        if slot_timeline.events.len() < MAX_EVENTS_PER_SLOT {
+            slot_timeline
+                .events
+                .push(TimelineEvent { timestamp_us, kind });
+        }
+    }
+
+    /// Folds a vote received from another validator into the totals for its slot.
+    pub fn record_received_vote(&self, vote: &Vote, stake: u64, timestamp_us: u64) {
+        let mut slots = self.slots.write().unwrap();
+        let Some(slot_timeline) = Self::slot_timeline_mut(&mut slots, self.capacity, vote.slot())
+        else {
+            return;
+        };
+        let timeline_vote = TimelineVote::from(vote);
+        let block_id = vote.block_id().copied();
+        if let Some(received) = slot_timeline
+            .received_votes
+            .iter_mut()
+            .find(|received| received.vote == timeline_vote && received.block_id == block_id)
+        {
+            received.count = received.count.saturating_add(1);
+            received.stake = received.stake.saturating_add(stake);
+            received.first_received_us = received.first_received_us.min(timestamp_us);
+            received.last_received_us = received.last_received_us.max(timestamp_us);
+        } else if slot_timeline.received_votes.len() < MAX_RECEIVED_VOTE_ENTRIES_PER_SLOT {
+            slot_timeline.received_votes.push(ReceivedVotes {
+                vote: timeline_vote,
+                block_id,
+                count: 1,
+                stake,
+                first_received_us: timestamp_us,
+                last_received_us: timestamp_us,
+            });
+        }
+    }
+
+    /// Returns the timelines of the retained slots in `start_slot..=end_slot`.
+    pub fn get(&self, start_slot: Slot, end_slot: Slot) -> Vec<SlotTimeline> {
+        if start_slot > end_slot {
+            return vec![];
+        }
+        self.slots
+            .read()
+            .unwrap()
+            .range(start_slot..=end_slot)
+            .map(|(_, slot_timeline)| slot_timeline.clone())
+            .collect()
+    }
+
+    fn slot_timeline_mut(
+        slots: &mut BTreeMap<Slot, SlotTimeline>,
+        capacity: usize,
+        slot: Slot,
+    ) -> Option<&mut SlotTimeline> {
+        if !slots.contains_key(&slot) && slots.len() >= capacity {
+            let (&lowest_slot, _) = slots.first_key_value()?;
+            if slot < lowest_slot {
+                return None;
+            }
+            slots.pop_first();
+        }
+        Some(slots.entry(slot).or_insert_with(|| SlotTimeline::new(slot)))
+    }
+}
+
+/// Converts `instant` to microseconds since the unix epoch.
+pub fn unix_timestamp_us(instant: Instant) -> u64 {
+    SystemTime::now()
+        .checked_sub(instant.elapsed())
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| {
+            u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, agave_votor_messages::consensus_message::Block, tempfile::TempDir};
+
+    #[test]
+    fn test_record_evicts_lowest_slot() {
+        let timeline = ConsensusTimeline::new(2);
+        timeline.record(5, 1, TimelineEventKind::FirstShred);
+        timeline.record(6, 2, TimelineEventKind::FirstShred);
+        timeline.record(7, 3, TimelineEventKind::FirstShred);
+        // Lower than every retained slot, dropped
+        timeline.record(4, 4, TimelineEventKind::FirstShred);
+
+        let slots: Vec<_> = timeline.get(0, 10).iter().map(|t| t.slot).collect();
+        assert_eq!(slots, vec![6, 7]);
+
+        // Recording into a retained slot does not evict
+        timeline.record(
+            6,
+            5,
+            TimelineEventKind::Timeout {
+                crashed_leader: false,
+            },
+        );
+        let slot_6 = &timeline.get(6, 6)[0];
+        assert_eq!(slot_6.events.len(), 2);
+        assert_eq!(timeline.get(7, 6), vec![]);
+    }
+
+    #[test]
+    fn test_record_received_vote() {
+        let timeline = ConsensusTimeline::new(16);
+        let block = Block {
+            slot: 3,
+            block_id: Hash::new_unique(),
+        };
+        let other_block = Block {
+            slot: 3,
+            block_id: Hash::new_unique(),
+        };
+        timeline.record_received_vote(&Vote::new_notarization_vote(block), 10, 200);
+        timeline.record_received_vote(&Vote::new_notarization_vote(block), 5, 100);
+        timeline.record_received_vote(&Vote::new_notarization_vote(other_block), 1, 300);
+        timeline.record_received_vote(&Vote::new_skip_vote(3), 7, 400);
+
+        let slot_3 = &timeline.get(3, 3)[0];
+        assert_eq!(
+            slot_3.received_votes,
+            vec![
+                ReceivedVotes {
+                    vote: TimelineVote::Notarize,
+                    block_id: Some(block.block_id),
+                    count: 2,
+                    stake: 15,
+                    first_received_us: 100,
+                    last_received_us: 200,
+                },
+                ReceivedVotes {
+                    vote: TimelineVote::Notarize,
+                    block_id: Some(other_block.block_id),
+                    count: 1,
+                    stake: 1,
+                    first_received_us: 300,
+                    last_received_us: 300,
+                },
+                ReceivedVotes {
+                    vote: TimelineVote::Skip,
+                    block_id: None,
+                    count: 1,
+                    stake: 7,
+                    first_received_us: 400,
+                    last_received_us: 400,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_persist_and_open() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(CONSENSUS_TIMELINE_FILE_NAME);
+
+        let timeline = ConsensusTimeline::open(path.clone(), 4);
+        assert!(timeline.get(0, Slot::MAX).is_empty());
+        for slot in 0..4 {
+            timeline.record(
+                slot,
+                slot,
+                TimelineEventKind::ParentReady {
+                    parent_slot: slot.saturating_sub(1),
+                    parent_block_id: Hash::new_unique(),
+                },
+            );
+        }
+        timeline.persist().unwrap();
+
+        let reopened = ConsensusTimeline::open(path.clone(), 4);
+        assert_eq!(reopened.get(0, Slot::MAX), timeline.get(0, Slot::MAX));
+
+        // Shrinking the capacity keeps the highest slots
+        let shrunk = ConsensusTimeline::open(path.clone(), 2);
+        assert_eq!(shrunk.get(0, Slot::MAX), timeline.get(2, 3));
+
+        // A corrupt file is ignored
+        fs::write(&path, [0xff; 3]).unwrap();
+        assert!(
+            ConsensusTimeline::open(path, 4)
+                .get(0, Slot::MAX)
+                .is_empty()
+        );
+    }
+}
@@ -42,6 +42,9 @@ pub enum VotorEvent {
     /// The block has received a notar-fallback certificate
     BlockNotarFallback(Block),
 
+    /// The slot has received a skip certificate
+    SlotSkipped(Slot),
+
     /// Received the first shred for the slot.
     FirstShred(Slot),
 
@@ -84,6 +87,7 @@ impl VotorEvent {
             VotorEvent::Block(completed_block) => completed_block.slot <= root,
             VotorEvent::Timeout(s)
             | VotorEvent::SafeToSkip(s)
+            | VotorEvent::SlotSkipped(s)
             | VotorEvent::TimeoutCrashedLeader(s)
             | VotorEvent::FirstShred(s)
             | VotorEvent::SafeToNotar(Block {
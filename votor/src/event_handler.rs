@@ -21,8 +21,8 @@ use {
         votor::SharedContext,
     },
     agave_votor_messages::{
-        consensus_message::Block, metric_types::ConsensusMetricsEvent, migration::MigrationStatus,
-        vote::Vote,
+        certificate::CertificateType, consensus_message::Block,
+        metric_types::ConsensusMetricsEvent, migration::MigrationStatus, vote::Vote,
     },
     crossbeam_channel::select,
     parking_lot::RwLock,
@@ -70,7 +70,7 @@ pub(crate) struct EventHandlerContext {
 }
 
 #[derive(Debug, Error)]
-enum EventLoopError {
+pub(crate) enum EventLoopError {
     #[error("Channel {0} disconnected")]
     ChannelDisconnected(&'static str),
     #[error("Error generating and inserting vote")]
@@ -83,7 +83,7 @@ pub(crate) struct EventHandler {
     t_event_handler: JoinHandle<()>,
 }
 
-struct LocalContext {
+pub(crate) struct LocalContext {
     pub(crate) my_pubkey: Pubkey,
     pub(crate) genesis_slot: Slot,
     pub(crate) pending_blocks: PendingBlocks,
@@ -96,6 +96,20 @@ struct LocalContext {
     pub(crate) standstill_slot: Option<Slot>,
 }
 
+impl LocalContext {
+    pub(crate) fn new(my_pubkey: Pubkey) -> Self {
+        Self {
+            my_pubkey,
+            genesis_slot: 0,
+            pending_blocks: PendingBlocks::default(),
+            finalized_blocks: BTreeSet::default(),
+            received_shred: BTreeSet::default(),
+            stats: EventHandlerStats::new(),
+            standstill_slot: None,
+        }
+    }
+}
+
 impl EventHandler {
     pub(crate) fn new(ctx: EventHandlerContext) -> Self {
         let exit = ctx.exit.clone();
@@ -122,15 +136,7 @@ impl EventHandler {
             voting_context: mut vctx,
             root_context: rctx,
         } = context;
-        let mut local_context = LocalContext {
-            my_pubkey: ctx.cluster_info.keypair().pubkey(),
-            genesis_slot: 0,
-            pending_blocks: PendingBlocks::default(),
-            finalized_blocks: BTreeSet::default(),
-            received_shred: BTreeSet::default(),
-            stats: EventHandlerStats::new(),
-            standstill_slot: None,
-        };
+        let mut local_context = LocalContext::new(ctx.cluster_info.keypair().pubkey());
 
         // Wait until migration has completed
         info!("{}: Event loop initialized", local_context.my_pubkey);
@@ -201,6 +207,16 @@ impl EventHandler {
                 .incr_event_with_timing(stats_event, event_processing_time.as_us());
 
             let mut send_votes_batch_time = Measure::start("send_votes_batch");
+            let votes_sent: Vec<_> = votes
+                .iter()
+                .filter_map(|vote| match vote {
+                    BLSOp::PushVote { vote } => Some(ConsensusMetricsEvent::VoteSent {
+                        vote: vote.vote,
+                        stake: vote.stake.get(),
+                    }),
+                    _ => None,
+                })
+                .collect();
             for vote in votes {
                 local_context.stats.incr_vote(&vote);
                 nonblocking_send(
@@ -211,6 +227,15 @@ impl EventHandler {
                 )
                 .map_err(EventLoopError::ChannelDisconnected)?;
             }
+            if !votes_sent.is_empty() {
+                nonblocking_send(
+                    &local_context.my_pubkey,
+                    &vctx.consensus_metrics_sender,
+                    (Instant::now(), votes_sent),
+                    "consensus_metrics_sender",
+                )
+                .map_err(EventLoopError::ChannelDisconnected)?;
+            }
             send_votes_batch_time.stop();
             local_context.stats.send_votes_batch_time_us = local_context
                 .stats
@@ -263,7 +288,7 @@ impl EventHandler {
         Ok(())
     }
 
-    fn handle_event(
+    pub(crate) fn handle_event(
         event: VotorEvent,
         timer_manager: &RwLock<TimerManager>,
         ctx: &SharedContext,
@@ -277,8 +302,14 @@ impl EventHandler {
             VotorEvent::Block(CompletedBlock { slot, bank }) => {
                 debug_assert!(bank.is_frozen());
                 let now = Instant::now();
-                let mut consensus_metrics_events =
-                    vec![ConsensusMetricsEvent::StartOfSlot { slot }];
+                let (block, parent_block) = Self::get_block_parent_block(&bank);
+                let mut consensus_metrics_events = vec![
+                    ConsensusMetricsEvent::StartOfSlot { slot },
+                    ConsensusMetricsEvent::BlockReplayed {
+                        slot,
+                        block_id: block.block_id,
+                    },
+                ];
                 if slot == first_of_consecutive_leader_slots(slot) {
                     // all slots except the first in the window would typically start when the block is seen so the recording would essentially record 0.
                     // hence we skip it.
@@ -294,7 +325,6 @@ impl EventHandler {
                     "consensus_metrics_sender",
                 )
                 .map_err(EventLoopError::ChannelDisconnected)?;
-                let (block, parent_block) = Self::get_block_parent_block(&bank);
                 info!(
                     "{}: Block {block:?} parent {parent_block:?}",
                     local_context.my_pubkey
@@ -348,6 +378,18 @@ impl EventHandler {
             // Block has received a notarization certificate
             VotorEvent::BlockNotarized(block) => {
                 info!("{}: Block Notarized {block:?}", local_context.my_pubkey);
+                nonblocking_send(
+                    &local_context.my_pubkey,
+                    &vctx.consensus_metrics_sender,
+                    (
+                        Instant::now(),
+                        vec![ConsensusMetricsEvent::CertificateFormed {
+                            cert_type: CertificateType::Notarize(block),
+                        }],
+                    ),
+                    "consensus_metrics_sender",
+                )
+                .map_err(EventLoopError::ChannelDisconnected)?;
                 vctx.vote_history.add_block_notarized(block);
                 Self::try_final(&local_context.my_pubkey, block, vctx, &mut votes)?;
                 request_repair(&ctx.repair_event_sender, &local_context.my_pubkey, block)?;
@@ -358,11 +400,49 @@ impl EventHandler {
                     "{}: Block notar-fallback {block:?}",
                     local_context.my_pubkey
                 );
+                nonblocking_send(
+                    &local_context.my_pubkey,
+                    &vctx.consensus_metrics_sender,
+                    (
+                        Instant::now(),
+                        vec![ConsensusMetricsEvent::CertificateFormed {
+                            cert_type: CertificateType::NotarizeFallback(block),
+                        }],
+                    ),
+                    "consensus_metrics_sender",
+                )
+                .map_err(EventLoopError::ChannelDisconnected)?;
                 request_repair(&ctx.repair_event_sender, &local_context.my_pubkey, block)?;
             }
 
+            VotorEvent::SlotSkipped(slot) => {
+                info!("{}: Slot skipped {slot}", local_context.my_pubkey);
+                nonblocking_send(
+                    &local_context.my_pubkey,
+                    &vctx.consensus_metrics_sender,
+                    (
+                        Instant::now(),
+                        vec![ConsensusMetricsEvent::CertificateFormed {
+                            cert_type: CertificateType::Skip(slot),
+                        }],
+                    ),
+                    "consensus_metrics_sender",
+                )
+                .map_err(EventLoopError::ChannelDisconnected)?;
+            }
+
             VotorEvent::FirstShred(slot) => {
                 info!("{}: First shred {slot}", local_context.my_pubkey);
+                nonblocking_send(
+                    &local_context.my_pubkey,
+                    &vctx.consensus_metrics_sender,
+                    (
+                        Instant::now(),
+                        vec![ConsensusMetricsEvent::FirstShred { slot }],
+                    ),
+                    "consensus_metrics_sender",
+                )
+                .map_err(EventLoopError::ChannelDisconnected)?;
                 local_context.received_shred.insert(slot);
             }
 
@@ -373,7 +453,10 @@ impl EventHandler {
                     &vctx.consensus_metrics_sender,
                     (
                         Instant::now(),
-                        vec![ConsensusMetricsEvent::StartOfSlot { slot }],
+                        vec![
+                            ConsensusMetricsEvent::StartOfSlot { slot },
+                            ConsensusMetricsEvent::ParentReady { slot, parent_block },
+                        ],
                     ),
                     "consensus_metrics_sender",
                 )
@@ -391,6 +474,19 @@ impl EventHandler {
 
             VotorEvent::TimeoutCrashedLeader(slot) => {
                 info!("{}: TimeoutCrashedLeader {slot}", local_context.my_pubkey);
+                nonblocking_send(
+                    &local_context.my_pubkey,
+                    &vctx.consensus_metrics_sender,
+                    (
+                        Instant::now(),
+                        vec![ConsensusMetricsEvent::Timeout {
+                            slot,
+                            crashed_leader: true,
+                        }],
+                    ),
+                    "consensus_metrics_sender",
+                )
+                .map_err(EventLoopError::ChannelDisconnected)?;
                 if vctx.vote_history.voted(slot) || local_context.received_shred.contains(&slot) {
                     return Ok(votes);
                 }
@@ -400,20 +496,22 @@ impl EventHandler {
             // Skip timer for the slot has fired
             VotorEvent::Timeout(slot) => {
                 info!("{}: Timeout {slot}", local_context.my_pubkey);
+                let mut consensus_metrics_events = vec![ConsensusMetricsEvent::Timeout {
+                    slot,
+                    crashed_leader: false,
+                }];
                 if slot != last_of_consecutive_leader_slots(slot) {
-                    nonblocking_send(
-                        &local_context.my_pubkey,
-                        &vctx.consensus_metrics_sender,
-                        (
-                            Instant::now(),
-                            vec![ConsensusMetricsEvent::StartOfSlot {
-                                slot: slot.saturating_add(1),
-                            }],
-                        ),
-                        "consensus_metrics_sender",
-                    )
-                    .map_err(EventLoopError::ChannelDisconnected)?;
+                    consensus_metrics_events.push(ConsensusMetricsEvent::StartOfSlot {
+                        slot: slot.saturating_add(1),
+                    });
                 }
+                nonblocking_send(
+                    &local_context.my_pubkey,
+                    &vctx.consensus_metrics_sender,
+                    (Instant::now(), consensus_metrics_events),
+                    "consensus_metrics_sender",
+                )
+                .map_err(EventLoopError::ChannelDisconnected)?;
                 if vctx.vote_history.voted(slot) {
                     return Ok(votes);
                 }
@@ -522,7 +620,16 @@ impl EventHandler {
                     &vctx.consensus_metrics_sender,
                     (
                         Instant::now(),
-                        vec![ConsensusMetricsEvent::SlotFinalized { slot: block.slot }],
+                        vec![
+                            ConsensusMetricsEvent::CertificateFormed {
+                                cert_type: if is_fast_finalization {
+                                    CertificateType::FinalizeFast(block)
+                                } else {
+                                    CertificateType::Finalize(block.slot)
+                                },
+                            },
+                            ConsensusMetricsEvent::SlotFinalized { slot: block.slot },
+                        ],
                     ),
                     "consensus_metrics_sender",
                 )
@@ -1306,6 +1413,19 @@ mod tests {
             self.bls_ops.append(&mut new_ops);
         }
 
+        fn send_slot_skipped_event(&mut self, slot: Slot) {
+            let mut new_ops = EventHandler::handle_event(
+                VotorEvent::SlotSkipped(slot),
+                &self.timer_manager,
+                &self.shared_context,
+                &mut self.voting_context,
+                &self.root_context,
+                &mut self.local_context,
+            )
+            .unwrap();
+            self.bls_ops.append(&mut new_ops);
+        }
+
         fn send_timeout_crashed_leader_event(&mut self, slot: Slot) {
             let mut new_ops = EventHandler::handle_event(
                 VotorEvent::TimeoutCrashedLeader(slot),
@@ -1818,6 +1938,18 @@ mod tests {
         test_context.check_no_vote_or_commitment();
     }
 
+    #[test]
+    fn test_received_slot_skipped() {
+        let mut test_context = setup();
+
+        // A skip certificate is recorded in the metrics and needs no vote
+        test_context.send_slot_skipped_event(5);
+        test_context.check_for_metrics_event(ConsensusMetricsEvent::CertificateFormed {
+            cert_type: CertificateType::Skip(5),
+        });
+        test_context.check_no_vote_or_commitment();
+    }
+
     #[test]
     fn test_try_skip_window_starts_after_unaligned_genesis() {
         let mut test_context = setup();
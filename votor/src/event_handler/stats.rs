@@ -95,6 +95,7 @@ pub enum StatsEvent {
     Block,
     BlockNotarized,
     BlockNotarFallback,
+    SlotSkipped,
     FirstShred,
     ParentReady,
     TimeoutCrashedLeader,
@@ -113,6 +114,7 @@ impl StatsEvent {
             VotorEvent::Block(_) => StatsEvent::Block,
             VotorEvent::BlockNotarized(_) => StatsEvent::BlockNotarized,
             VotorEvent::BlockNotarFallback(_) => StatsEvent::BlockNotarFallback,
+            VotorEvent::SlotSkipped(_) => StatsEvent::SlotSkipped,
             VotorEvent::FirstShred(_) => StatsEvent::FirstShred,
             VotorEvent::ParentReady { .. } => StatsEvent::ParentReady,
             VotorEvent::TimeoutCrashedLeader(_) => StatsEvent::TimeoutCrashedLeader,
@@ -10,10 +10,14 @@ pub mod common;
 pub mod consensus_metrics;
 pub mod consensus_pool;
 mod consensus_pool_service;
+pub mod consensus_timeline;
 pub mod event;
 mod event_handler;
 pub mod peer_list_updater;
+pub mod remote_vote_storage;
 pub mod root_utils;
+#[cfg(test)]
+mod simulator;
 mod timer_manager;
 pub mod vote_history;
 pub mod vote_history_storage;
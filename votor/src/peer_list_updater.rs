@@ -3,7 +3,7 @@ use {crate::voting_service::VotingServiceOverride, arc_swap::ArcSwap};
 use {
     agave_bls_sigverify::bls_sigverifier::NUM_SLOTS_FOR_VERIFY,
     agave_votor_messages::reward_certificate::NUM_SLOTS_FOR_REWARD,
-    agave_votor_transport::PeerListSender,
+    agave_votor_transport::{PeerListSender, PeerStakesSender},
     crossbeam_channel::{RecvTimeoutError, Sender, bounded},
     solana_gossip::cluster_info::ClusterInfo,
     solana_keypair::Keypair,
@@ -12,7 +12,7 @@ use {
     solana_signer::Signer,
     solana_tls_utils::NotifyKeyUpdate,
     std::{
-        collections::{HashMap, HashSet},
+        collections::HashMap,
         error::Error,
         net::SocketAddr,
         sync::Arc,
@@ -30,10 +30,13 @@ const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
 /// staked validators (merged across an epoch boundary) paired with each peer's
 /// votor server sockets from gossip. The transport endpoint uses it to filter
 /// inbound connections, initiate outbound connections and to fan out packets.
+/// The peers' stakes are published alongside to weight ingress rate limits.
 pub struct PeerListUpdater {
     sharable_banks: SharableBanks,
     /// Publisher for the endpoint's peer_list snapshot.
     peer_list_sender: PeerListSender,
+    /// Publisher for the stakes of the peers in the peer_list.
+    peer_stakes_sender: PeerStakesSender,
     /// Live, shared override of the (pubkey -> socket) set
     #[cfg(feature = "dev-context-only-utils")]
     test_overrides: Arc<ArcSwap<HashMap<Pubkey, SocketAddr>>>,
@@ -43,6 +46,7 @@ impl PeerListUpdater {
     pub fn new(
         sharable_banks: SharableBanks,
         peer_list_sender: PeerListSender,
+        peer_stakes_sender: PeerStakesSender,
         #[cfg(feature = "dev-context-only-utils")] test_overrides: Arc<
             ArcSwap<HashMap<Pubkey, SocketAddr>>,
         >,
@@ -50,6 +54,7 @@ impl PeerListUpdater {
         Self {
             sharable_banks,
             peer_list_sender,
+            peer_stakes_sender,
             #[cfg(feature = "dev-context-only-utils")]
             test_overrides,
         }
@@ -76,7 +81,8 @@ impl PeerListUpdater {
     ///   `NUM_SLOTS_FOR_VERIFY` is the furthest ahead of root a vote/cert is accepted.
     ///
     /// Each peer's votor socket is resolved from gossip (or a test override).
-    /// Peers that have no address yet are added with `None`.
+    /// Peers that have no address yet are added with `None`. A peer staked in
+    /// several of these epochs is published with its largest stake.
     pub fn refresh_peer_list(&self, cluster_info: &ClusterInfo, my_identity: &Pubkey) {
         let root = self.sharable_banks.root();
         let epoch_schedule = root.epoch_schedule();
@@ -86,44 +92,51 @@ impl PeerListUpdater {
         let trailing_epoch =
             epoch_schedule.get_epoch(root.slot().saturating_sub(NUM_SLOTS_FOR_REWARD));
 
-        // Only the pubkeys matter here; stake weights are unused.
-        let mut staked_nodes: HashSet<Pubkey> = root
-            .epoch_staked_nodes(root_epoch)
-            .expect("Root bank retains epoch_stakes for its own epoch")
-            .keys()
-            .copied()
-            .collect();
+        let mut staked_nodes: HashMap<Pubkey, u64> = HashMap::clone(
+            &root
+                .epoch_staked_nodes(root_epoch)
+                .expect("Root bank retains epoch_stakes for its own epoch"),
+        );
+        let mut merge = |nodes: &HashMap<Pubkey, u64>| {
+            for (pubkey, stake) in nodes {
+                let merged = staked_nodes.entry(*pubkey).or_default();
+                *merged = (*merged).max(*stake);
+            }
+        };
         // Admit the peers from the trailing_epoch (they can be different for the
         // first few slots after a boundary). Trailing epoch staked nodes may not be available
         // after warp, so we have to allow for that here.
         if root_epoch != trailing_epoch
             && let Some(nodes) = root.epoch_staked_nodes(trailing_epoch)
         {
-            staked_nodes.extend(nodes.keys());
+            merge(&nodes);
         }
 
         // Near the epoch end, admit the upcoming epoch's set so new voters can connect in advance.
         let slots_in_epoch = epoch_schedule.get_slots_in_epoch(root_epoch);
         let near_end = slot_index >= slots_in_epoch.saturating_sub(NUM_SLOTS_FOR_VERIFY);
         if near_end && let Some(next) = root.epoch_staked_nodes(root_epoch.saturating_add(1)) {
-            staked_nodes.extend(next.keys());
+            merge(&next);
         }
 
         // Participate in votor only if this node is itself in the merged peer set.
         // An unstaked node publishes an empty peer_list, so the transport neither
         // connects to staked peers nor admits inbound connections.
-        if !staked_nodes.contains(my_identity) {
+        if !staked_nodes.contains_key(my_identity) {
             self.peer_list_sender.send_replace(Arc::new(HashMap::new()));
+            self.peer_stakes_sender
+                .send_replace(Arc::new(HashMap::new()));
             return;
         }
 
         let mut new_peer_list = cluster_info
-            .query_contact_infos(staked_nodes.iter(), |node| node.alpenglow())
+            .query_contact_infos(staked_nodes.keys(), |node| node.alpenglow())
             .into_iter()
             .map(|(pubkey, socket)| (pubkey, socket.flatten()))
             .collect();
         self.apply_test_overrides(&mut new_peer_list);
         // Publish the latest version - this neither blocks nor fails.
+        self.peer_stakes_sender.send_replace(Arc::new(staked_nodes));
         self.peer_list_sender.send_replace(Arc::new(new_peer_list));
     }
 }
@@ -151,6 +164,7 @@ impl PeerListService {
     pub fn new(
         cluster_info: Arc<ClusterInfo>,
         peer_list: PeerListSender,
+        peer_stakes: PeerStakesSender,
         sharable_banks: SharableBanks,
         #[cfg(feature = "dev-context-only-utils")] test_override: Option<VotingServiceOverride>,
     ) -> Self {
@@ -164,6 +178,7 @@ impl PeerListService {
                 let peer_list_updater = PeerListUpdater::new(
                     sharable_banks,
                     peer_list,
+                    peer_stakes,
                     #[cfg(feature = "dev-context-only-utils")]
                     test_override
                         .map(|v| v.override_listeners)
@@ -361,9 +376,11 @@ mod tests {
             Pubkey::new_unique(),
             Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8000)),
         )])));
+        let (peer_stakes_sender, _peer_stakes_receiver) = watch::channel(Arc::default());
         let svc = PeerListUpdater::new(
             bank_forks.read().unwrap().sharable_banks(),
             sender,
+            peer_stakes_sender,
             Arc::new(ArcSwap::default()),
         );
 
@@ -385,9 +402,11 @@ mod tests {
             create_bank_forks_and_cluster_info(num_nodes, num_zero_stake_nodes, slot_num);
 
         let (peerlist_sender, peerlist_receiver) = watch::channel(Arc::new(HashMap::new()));
+        let (peer_stakes_sender, peer_stakes_receiver) = watch::channel(Arc::default());
         let svc = PeerListUpdater::new(
             bank_forks.read().unwrap().sharable_banks(),
             peerlist_sender,
+            peer_stakes_sender,
             Arc::new(ArcSwap::default()),
         );
 
@@ -407,5 +426,12 @@ mod tests {
             snapshot.values().all(|addr| addr.is_some()),
             "every staked peer resolves to its gossip socket"
         );
+        let stakes = peer_stakes_receiver.borrow().clone();
+        let root = bank_forks.read().unwrap().root_bank();
+        assert_eq!(
+            *stakes,
+            *root.epoch_staked_nodes(root.epoch()).unwrap(),
+            "every peer is published with its stake"
+        );
     }
 }
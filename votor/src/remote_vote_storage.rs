@@ -0,0 +1,679 @@
+//! Tower and vote history storage shared over the network, so that a primary
+//! and a hot spare validator can hand over their voting identity safely.
+//!
+//! A [`RemoteVoteStorageServer`] keeps the latest record of each identity.
+//! Clients replace a record with a compare-and-swap on its version: once a
+//! validator stored a record, any other validator that has not loaded it since
+//! can no longer overwrite it. Each record also carries a watermark, the
+//! highest slot it holds a vote for, which the server never lets move
+//! backwards. Since validators persist their votes before sending them, the
+//! validator taking over with `set-identity` resumes from every vote the other
+//! one may have sent, and the validator that was replaced can no longer vote.
+//!
+//! Clients authenticate every connection by proving they know the server's
+//! [`SharedSecret`]: the server sends a random challenge, which the client
+//! answers with the hash of the secret and the challenge, and the server
+//! replies whether it accepted the answer before the client sends a request.
+use {
+    log::*,
+    rand::Rng,
+    solana_clock::Slot,
+    solana_hash::{HASH_BYTES, Hash},
+    solana_pubkey::Pubkey,
+    solana_sha256_hasher::hashv,
+    std::{
+        collections::HashMap,
+        fmt, fs,
+        io::{self, ErrorKind, Read, Write},
+        net::{SocketAddr, TcpListener, TcpStream},
+        path::{Path, PathBuf},
+        sync::{
+            Arc, Mutex,
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+        },
+        thread::{self, Builder, JoinHandle},
+        time::Duration,
+    },
+    subtle::ConstantTimeEq,
+    thiserror::Error,
+    wincode::{SchemaRead, SchemaWrite},
+};
+
+/// Records hold a single tower or vote history, which stay far below this
+const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+/// Clients wait on the server while persisting a vote, keep this short
+const IO_TIMEOUT: Duration = Duration::from_secs(2);
+/// Server side connections are closed after being idle for this long
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Connections beyond this are closed right away, a primary and a hot spare
+/// need two at a time
+const MAX_CONNECTIONS: usize = 32;
+/// Shorter secrets are rejected, see [`SharedSecret::new`]
+const MIN_SECRET_LEN: usize = 16;
+const AUTH_ACCEPTED: u8 = 1;
+const AUTH_REJECTED: u8 = 0;
+
+pub type Result<T> = std::result::Result<T, RemoteVoteStorageError>;
+
+#[derive(Error, Debug)]
+pub enum RemoteVoteStorageError {
+    #[error("IO Error: {0}")]
+    IoError(#[from] io::Error),
+
+    #[error("Serialization Error: {0}")]
+    SerializeError(#[from] wincode::WriteError),
+
+    #[error("Deserialization Error: {0}")]
+    DeserializeError(#[from] wincode::ReadError),
+
+    #[error("Frame of {0} bytes exceeds the limit")]
+    FrameTooLarge(usize),
+
+    #[error("{0} was stored by another validator since it was last loaded")]
+    VersionMismatch(RecordKey),
+
+    #[error("{0} already holds votes up to slot {1}")]
+    WatermarkRegression(RecordKey, Slot),
+
+    #[error("The vote storage server failed to persist {0}")]
+    Unavailable(RecordKey),
+
+    #[error("The vote storage server rejected the shared secret")]
+    Unauthorized,
+
+    #[error("Unexpected response from the vote storage server")]
+    UnexpectedResponse,
+}
+
+/// Secret shared by a [`RemoteVoteStorageServer`] and its clients
+#[derive(Clone)]
+pub struct SharedSecret(Vec<u8>);
+
+impl SharedSecret {
+    pub fn new(secret: Vec<u8>) -> io::Result<Self> {
+        if secret.len() < MIN_SECRET_LEN {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("the shared secret must be at least {MIN_SECRET_LEN} bytes"),
+            ));
+        }
+        Ok(Self(secret))
+    }
+
+    /// Reads the secret from `path`, ignoring surrounding whitespace
+    pub fn read_from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let secret = fs::read(path.as_ref())?;
+        Self::new(secret.trim_ascii().to_vec()).map_err(|err| {
+            io::Error::new(err.kind(), format!("{}: {err}", path.as_ref().display()))
+        })
+    }
+
+    fn answer(&self, challenge: &Hash) -> Hash {
+        hashv(&[self.0.as_slice(), challenge.as_ref()])
+    }
+}
+
+impl fmt::Debug for SharedSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedSecret(..)")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, SchemaRead, SchemaWrite)]
+pub enum RecordKind {
+    Tower,
+    VoteHistory,
+}
+
+/// Identifies the tower or vote history of one identity
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, SchemaRead, SchemaWrite)]
+pub struct RecordKey {
+    pub kind: RecordKind,
+    pub node_pubkey: Pubkey,
+}
+
+impl RecordKey {
+    pub fn new(kind: RecordKind, node_pubkey: Pubkey) -> Self {
+        Self { kind, node_pubkey }
+    }
+
+    fn file_name(&self) -> String {
+        match self.kind {
+            RecordKind::Tower => format!("tower-{}.bin", self.node_pubkey),
+            RecordKind::VoteHistory => format!("vote_history-{}.bin", self.node_pubkey),
+        }
+    }
+}
+
+impl fmt::Display for RecordKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            RecordKind::Tower => write!(f, "tower of {}", self.node_pubkey),
+            RecordKind::VoteHistory => write!(f, "vote history of {}", self.node_pubkey),
+        }
+    }
+}
+
+#[derive(Debug, SchemaRead, SchemaWrite)]
+enum Request {
+    Load {
+        key: RecordKey,
+    },
+    CompareAndSwap {
+        key: RecordKey,
+        /// Version the client last saw, 0 if it saw no record
+        expected_version: u64,
+        watermark: Slot,
+        data: Vec<u8>,
+    },
+}
+
+#[derive(Debug, SchemaRead, SchemaWrite)]
+enum Response {
+    Loaded { version: u64, data: Vec<u8> },
+    NotFound,
+    Stored { version: u64 },
+    VersionMismatch,
+    WatermarkRegression { watermark: Slot },
+    Unavailable,
+}
+
+#[derive(Clone, Debug, SchemaRead, SchemaWrite)]
+struct Record {
+    key: RecordKey,
+    version: u64,
+    watermark: Slot,
+    data: Vec<u8>,
+}
+
+fn read_frame(stream: &mut TcpStream) -> Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME_SIZE {
+        return Err(RemoteVoteStorageError::FrameTooLarge(len));
+    }
+    let mut frame = vec![0u8; len];
+    stream.read_exact(&mut frame)?;
+    Ok(frame)
+}
+
+fn write_frame(stream: &mut TcpStream, frame: &[u8]) -> Result<()> {
+    if frame.len() > MAX_FRAME_SIZE {
+        return Err(RemoteVoteStorageError::FrameTooLarge(frame.len()));
+    }
+    stream.write_all(&(frame.len() as u32).to_le_bytes())?;
+    stream.write_all(frame)?;
+    Ok(())
+}
+
+/// Client side of the vote storage protocol
+///
+/// Remembers the version of each record it loaded or stored, which its next
+/// store of that record is conditioned on.
+#[derive(Debug)]
+pub struct RemoteVoteStorageClient {
+    server_addr: SocketAddr,
+    secret: SharedSecret,
+    versions: Mutex<HashMap<RecordKey, u64>>,
+}
+
+impl RemoteVoteStorageClient {
+    pub fn new(server_addr: SocketAddr, secret: SharedSecret) -> Self {
+        Self {
+            server_addr,
+            secret,
+            versions: Mutex::default(),
+        }
+    }
+
+    pub fn server_addr(&self) -> SocketAddr {
+        self.server_addr
+    }
+
+    /// Loads the record for `key`, `None` if nothing was stored for it yet
+    pub fn load(&self, key: RecordKey) -> Result<Option<Vec<u8>>> {
+        let mut versions = self.versions.lock().unwrap();
+        match self.request(&Request::Load { key })? {
+            Response::Loaded { version, data } => {
+                versions.insert(key, version);
+                Ok(Some(data))
+            }
+            Response::NotFound => {
+                versions.insert(key, 0);
+                Ok(None)
+            }
+            _ => Err(RemoteVoteStorageError::UnexpectedResponse),
+        }
+    }
+
+    /// Replaces the record for `key` with `data`, unless another client stored
+    /// it since this one last loaded or stored it, or the record already holds
+    /// votes past `watermark`
+    pub fn store(&self, key: RecordKey, watermark: Slot, data: Vec<u8>) -> Result<()> {
+        let mut versions = self.versions.lock().unwrap();
+        let expected_version = versions.get(&key).copied().unwrap_or_default();
+        let request = Request::CompareAndSwap {
+            key,
+            expected_version,
+            watermark,
+            data,
+        };
+        match self.request(&request)? {
+            Response::Stored { version } => {
+                versions.insert(key, version);
+                Ok(())
+            }
+            Response::VersionMismatch => Err(RemoteVoteStorageError::VersionMismatch(key)),
+            Response::WatermarkRegression { watermark } => {
+                Err(RemoteVoteStorageError::WatermarkRegression(key, watermark))
+            }
+            Response::Unavailable => Err(RemoteVoteStorageError::Unavailable(key)),
+            _ => Err(RemoteVoteStorageError::UnexpectedResponse),
+        }
+    }
+
+    fn request(&self, request: &Request) -> Result<Response> {
+        let mut stream = TcpStream::connect_timeout(&self.server_addr, CONNECT_TIMEOUT)?;
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+        stream.set_nodelay(true)?;
+        let mut challenge = [0u8; HASH_BYTES];
+        stream.read_exact(&mut challenge)?;
+        let answer = self.secret.answer(&Hash::new_from_array(challenge));
+        stream.write_all(answer.as_ref())?;
+        let mut verdict = [AUTH_REJECTED];
+        stream.read_exact(&mut verdict)?;
+        if verdict != [AUTH_ACCEPTED] {
+            return Err(RemoteVoteStorageError::Unauthorized);
+        }
+        write_frame(&mut stream, &wincode::serialize(request)?)?;
+        Ok(wincode::deserialize(&read_frame(&mut stream)?)?)
+    }
+}
+
+/// The records served by a [`RemoteVoteStorageServer`], optionally persisted
+/// to a directory
+struct RecordStore {
+    records: HashMap<RecordKey, Record>,
+    storage_dir: Option<PathBuf>,
+}
+
+impl RecordStore {
+    fn open(storage_dir: Option<PathBuf>) -> io::Result<Self> {
+        let mut records = HashMap::new();
+        if let Some(storage_dir) = &storage_dir {
+            fs::create_dir_all(storage_dir)?;
+            for entry in fs::read_dir(storage_dir)? {
+                let path = entry?.path();
+                if path.extension().is_none_or(|extension| extension != "bin") {
+                    continue;
+                }
+                let record: Record = wincode::deserialize(&fs::read(&path)?).map_err(|err| {
+                    io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!("failed to read {}: {err}", path.display()),
+                    )
+                })?;
+                records.insert(record.key, record);
+            }
+        }
+        Ok(Self {
+            records,
+            storage_dir,
+        })
+    }
+
+    fn persist(storage_dir: &Path, record: &Record) -> Result<()> {
+        let filename = storage_dir.join(record.key.file_name());
+        let new_filename = filename.with_extension("bin.new");
+        {
+            let mut file = fs::File::create(&new_filename)?;
+            file.write_all(&wincode::serialize(record)?)?;
+            // Unlike the validator's own files, records are synced: the server
+            // vouches for them to whichever validator loads them next
+            file.sync_all()?;
+        }
+        fs::rename(&new_filename, &filename)?;
+        Ok(())
+    }
+
+    fn process(&mut self, request: Request) -> Response {
+        match request {
+            Request::Load { key } => match self.records.get(&key) {
+                Some(record) => Response::Loaded {
+                    version: record.version,
+                    data: record.data.clone(),
+                },
+                None => Response::NotFound,
+            },
+            Request::CompareAndSwap {
+                key,
+                expected_version,
+                watermark,
+                data,
+            } => {
+                let current = self.records.get(&key);
+                if current.map_or(0, |record| record.version) != expected_version {
+                    return Response::VersionMismatch;
+                }
+                if let Some(current) = current
+                    && watermark < current.watermark
+                {
+                    return Response::WatermarkRegression {
+                        watermark: current.watermark,
+                    };
+                }
+                let record = Record {
+                    key,
+                    version: expected_version.saturating_add(1),
+                    watermark,
+                    data,
+                };
+                if let Some(storage_dir) = &self.storage_dir
+                    && let Err(err) = Self::persist(storage_dir, &record)
+                {
+                    error!("Failed to persist the {key}: {err}");
+                    return Response::Unavailable;
+                }
+                let version = record.version;
+                self.records.insert(key, record);
+                Response::Stored { version }
+            }
+        }
+    }
+}
+
+/// Serves towers and vote histories to [`RemoteVoteStorageClient`]s
+pub struct RemoteVoteStorageServer {
+    local_addr: SocketAddr,
+    exit: Arc<AtomicBool>,
+    thread_hdl: JoinHandle<()>,
+}
+
+impl RemoteVoteStorageServer {
+    /// Serves the records in `storage_dir` on `listener` to clients that know
+    /// `secret`, keeping them in memory only if `storage_dir` is `None`
+    pub fn new(
+        listener: TcpListener,
+        storage_dir: Option<PathBuf>,
+        secret: SharedSecret,
+    ) -> io::Result<Self> {
+        let store = Arc::new(Mutex::new(RecordStore::open(storage_dir)?));
+        let local_addr = listener.local_addr()?;
+        listener.set_nonblocking(true)?;
+        let exit = Arc::new(AtomicBool::new(false));
+        let thread_hdl = Builder::new().name("solVoteStoreSrv".to_string()).spawn({
+            let exit = exit.clone();
+            move || Self::run(listener, store, secret, exit)
+        })?;
+        Ok(Self {
+            local_addr,
+            exit,
+            thread_hdl,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn exit(&self) {
+        self.exit.store(true, Ordering::Relaxed);
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+
+    fn run(
+        listener: TcpListener,
+        store: Arc<Mutex<RecordStore>>,
+        secret: SharedSecret,
+        exit: Arc<AtomicBool>,
+    ) {
+        let connections = Arc::new(AtomicUsize::new(0));
+        while !exit.load(Ordering::Relaxed) {
+            let stream = match listener.accept() {
+                Ok((stream, _peer_addr)) => stream,
+                Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                    thread::sleep(ACCEPT_POLL_INTERVAL);
+                    continue;
+                }
+                Err(err) => {
+                    warn!("Failed to accept a vote storage connection: {err}");
+                    continue;
+                }
+            };
+            if connections.fetch_add(1, Ordering::Relaxed) >= MAX_CONNECTIONS {
+                connections.fetch_sub(1, Ordering::Relaxed);
+                warn!("Closed a vote storage connection, {MAX_CONNECTIONS} are open already");
+                continue;
+            }
+            let store = store.clone();
+            let secret = secret.clone();
+            let spawned = Builder::new().name("solVoteStoreCon".to_string()).spawn({
+                let connections = connections.clone();
+                move || {
+                    if let Err(err) = Self::serve_connection(stream, &store, &secret) {
+                        debug!("Vote storage connection closed: {err}");
+                    }
+                    connections.fetch_sub(1, Ordering::Relaxed);
+                }
+            });
+            if let Err(err) = spawned {
+                connections.fetch_sub(1, Ordering::Relaxed);
+                warn!("Failed to spawn a vote storage connection thread: {err}");
+            }
+        }
+    }
+
+    fn serve_connection(
+        mut stream: TcpStream,
+        store: &Mutex<RecordStore>,
+        secret: &SharedSecret,
+    ) -> Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+        stream.set_nodelay(true)?;
+        let challenge = Hash::new_from_array(rand::rng().random());
+        stream.write_all(challenge.as_ref())?;
+        let mut answer = [0u8; HASH_BYTES];
+        stream.read_exact(&mut answer)?;
+        // Compare in constant time so that the timing does not leak the expected answer
+        if !bool::from(answer.as_slice().ct_eq(secret.answer(&challenge).as_ref())) {
+            warn!(
+                "Rejected a vote storage client at {}: wrong shared secret",
+                stream.peer_addr()?
+            );
+            stream.write_all(&[AUTH_REJECTED])?;
+            return Err(RemoteVoteStorageError::Unauthorized);
+        }
+        stream.write_all(&[AUTH_ACCEPTED])?;
+        stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+        loop {
+            let frame = match read_frame(&mut stream) {
+                Ok(frame) => frame,
+                Err(RemoteVoteStorageError::IoError(err))
+                    if err.kind() == ErrorKind::UnexpectedEof =>
+                {
+                    return Ok(());
+                }
+                Err(err) => return Err(err),
+            };
+            let request = wincode::deserialize(&frame)?;
+            let response = store.lock().unwrap().process(request);
+            write_frame(&mut stream, &wincode::serialize(&response)?)?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    fn secret() -> SharedSecret {
+        SharedSecret::new(b"vote storage test secret".to_vec()).unwrap()
+    }
+
+    fn start_server(storage_dir: Option<PathBuf>) -> RemoteVoteStorageServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        RemoteVoteStorageServer::new(listener, storage_dir, secret()).unwrap()
+    }
+
+    #[test]
+    fn test_compare_and_swap() {
+        let server = start_server(None);
+        let key = RecordKey::new(RecordKind::VoteHistory, Pubkey::new_unique());
+        let primary = RemoteVoteStorageClient::new(server.local_addr(), secret());
+        let spare = RemoteVoteStorageClient::new(server.local_addr(), secret());
+
+        assert_eq!(primary.load(key).unwrap(), None);
+        primary.store(key, 10, vec![1]).unwrap();
+        primary.store(key, 11, vec![2]).unwrap();
+
+        // The spare has to load the record before it may replace it
+        assert!(matches!(
+            spare.store(key, 12, vec![3]),
+            Err(RemoteVoteStorageError::VersionMismatch(_))
+        ));
+        assert_eq!(spare.load(key).unwrap(), Some(vec![2]));
+        spare.store(key, 12, vec![3]).unwrap();
+
+        // After which the primary is fenced off
+        assert!(matches!(
+            primary.store(key, 13, vec![4]),
+            Err(RemoteVoteStorageError::VersionMismatch(_))
+        ));
+        assert_eq!(primary.load(key).unwrap(), Some(vec![3]));
+
+        // Records of other identities and kinds are independent
+        let tower_key = RecordKey::new(RecordKind::Tower, key.node_pubkey);
+        assert_eq!(primary.load(tower_key).unwrap(), None);
+        spare.store(tower_key, 0, vec![5]).unwrap();
+
+        server.exit();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_watermark_regression() {
+        let server = start_server(None);
+        let key = RecordKey::new(RecordKind::Tower, Pubkey::new_unique());
+        let client = RemoteVoteStorageClient::new(server.local_addr(), secret());
+
+        client.store(key, 10, vec![1]).unwrap();
+        client.store(key, 10, vec![2]).unwrap();
+        assert!(matches!(
+            client.store(key, 9, vec![3]),
+            Err(RemoteVoteStorageError::WatermarkRegression(_, 10))
+        ));
+        assert_eq!(client.load(key).unwrap(), Some(vec![2]));
+
+        server.exit();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_records_persist_across_restarts() {
+        let storage_dir = TempDir::new().unwrap();
+        let key = RecordKey::new(RecordKind::VoteHistory, Pubkey::new_unique());
+
+        let server = start_server(Some(storage_dir.path().to_path_buf()));
+        let client = RemoteVoteStorageClient::new(server.local_addr(), secret());
+        client.store(key, 7, vec![1, 2, 3]).unwrap();
+        server.exit();
+        server.join().unwrap();
+
+        let server = start_server(Some(storage_dir.path().to_path_buf()));
+        let client = RemoteVoteStorageClient::new(server.local_addr(), secret());
+        assert_eq!(client.load(key).unwrap(), Some(vec![1, 2, 3]));
+        // Versions and watermarks survive the restart too
+        assert!(matches!(
+            RemoteVoteStorageClient::new(server.local_addr(), secret()).store(key, 8, vec![]),
+            Err(RemoteVoteStorageError::VersionMismatch(_))
+        ));
+        assert!(matches!(
+            client.store(key, 6, vec![]),
+            Err(RemoteVoteStorageError::WatermarkRegression(_, 7))
+        ));
+        server.exit();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_wrong_secret() {
+        let server = start_server(None);
+        let key = RecordKey::new(RecordKind::Tower, Pubkey::new_unique());
+        let client = RemoteVoteStorageClient::new(
+            server.local_addr(),
+            SharedSecret::new(b"not the test secret".to_vec()).unwrap(),
+        );
+        assert!(matches!(
+            client.load(key),
+            Err(RemoteVoteStorageError::Unauthorized)
+        ));
+        assert!(matches!(
+            client.store(key, 0, vec![]),
+            Err(RemoteVoteStorageError::Unauthorized)
+        ));
+        assert!(SharedSecret::new(b"short".to_vec()).is_err());
+
+        server.exit();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_connection_limit() {
+        let server = start_server(None);
+        let key = RecordKey::new(RecordKind::Tower, Pubkey::new_unique());
+        let client = RemoteVoteStorageClient::new(server.local_addr(), secret());
+
+        // Connections that never answer the challenge hold their slot until
+        // they time out
+        let idle_connections: Vec<_> = (0..MAX_CONNECTIONS)
+            .map(|_| {
+                let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+                stream.read_exact(&mut [0u8; HASH_BYTES]).unwrap();
+                stream
+            })
+            .collect();
+        assert!(matches!(
+            client.load(key),
+            Err(RemoteVoteStorageError::IoError(_))
+        ));
+
+        drop(idle_connections);
+        let start = std::time::Instant::now();
+        while client.load(key).is_err() {
+            assert!(
+                start.elapsed() < IO_TIMEOUT,
+                "connection slots were not released"
+            );
+            thread::sleep(ACCEPT_POLL_INTERVAL);
+        }
+
+        server.exit();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_server_unreachable() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        drop(listener);
+        let client = RemoteVoteStorageClient::new(server_addr, secret());
+        let key = RecordKey::new(RecordKind::Tower, Pubkey::new_unique());
+        assert!(matches!(
+            client.load(key),
+            Err(RemoteVoteStorageError::IoError(_))
+        ));
+        assert!(matches!(
+            client.store(key, 0, vec![]),
+            Err(RemoteVoteStorageError::IoError(_))
+        ));
+    }
+}
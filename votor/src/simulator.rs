@@ -0,0 +1,596 @@
+//! A deterministic, discrete-event simulator for Alpenglow consensus.
+//!
+//! Every simulated node runs the real event handler with its own bank forks, consensus pool and
+//! skip timers on a virtual clock. Leaders produce their windows as soon as they see a ready
+//! parent, nodes repair the certified blocks they are missing from their leaders, and all messages
+//! between nodes go through a simulated network that can delay, drop and partition them. Nodes can
+//! be configured to misbehave, see [`Byzantine`].
+//!
+//! All randomness, including the node keys and the leader schedule, is drawn from a single seed,
+//! so a run can be reproduced exactly from its [`SimulatorConfig`]. After a run,
+//! [`Simulator::check_safety`] and [`Simulator::check_liveness`] check the invariants honest
+//! nodes must uphold.
+
+mod network;
+mod node;
+
+pub(crate) use network::{NetworkConfig, Partition};
+use {
+    crate::consensus_pool::parent_ready_tracker::BlockProductionParent,
+    agave_votor_messages::consensus_message::Block,
+    node::{Input, Node},
+    rand::{Rng, SeedableRng, rngs::StdRng},
+    solana_clock::Slot,
+    solana_epoch_schedule::EpochSchedule,
+    solana_hash::Hash,
+    solana_keypair::{Keypair, keypair_from_seed},
+    solana_leader_schedule::NUM_CONSECUTIVE_LEADER_SLOTS,
+    solana_runtime::{
+        genesis_utils::{
+            ValidatorVoteKeypairs, create_genesis_config_with_alpenglow_vote_accounts,
+        },
+        leader_schedule_utils::last_of_consecutive_leader_slots,
+    },
+    std::{
+        cmp::{Ordering, Reverse},
+        collections::{BTreeMap, BinaryHeap, HashMap, btree_map::Entry},
+        time::{Duration, Instant},
+    },
+    thiserror::Error,
+};
+
+/// Number of leader windows in the leader schedule, which repeats after that
+const LEADER_SCHEDULE_WINDOWS: usize = 256;
+
+/// Ways in which a node can deviate from the protocol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Byzantine {
+    /// Also votes to skip every slot it votes to notarize, and notarizes every version of a block
+    /// it receives
+    DoubleVote,
+    /// Votes as usual, but never sends its votes to other nodes
+    WithholdVotes,
+    /// As leader, sends one version of its window to the even nodes and another to the odd nodes
+    EquivocatingLeader,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct SimulatorConfig {
+    /// Seed for every random choice made during the simulation
+    pub(crate) seed: u64,
+    /// Stake of each node, which must be non-zero
+    pub(crate) stakes: Vec<u64>,
+    /// Misbehaving nodes, by index into `stakes`
+    pub(crate) byzantine: HashMap<usize, Byzantine>,
+    pub(crate) network: NetworkConfig,
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            stakes: vec![100; 4],
+            byzantine: HashMap::new(),
+            network: NetworkConfig::default(),
+        }
+    }
+}
+
+/// A block a node has seen finalized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct FinalizedBlock {
+    pub(crate) block: Block,
+    /// Whether the block was fast finalized
+    pub(crate) fast: bool,
+    /// Virtual time at which the node saw the block finalized
+    pub(crate) at: Duration,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub(crate) enum InvariantViolation {
+    #[error("blocks {first:?} and {second:?} were both finalized in slot {slot}")]
+    ConflictingFinalization {
+        slot: Slot,
+        first: Block,
+        second: Block,
+    },
+    #[error("finalized block {descendant:?} does not descend from finalized block {ancestor:?}")]
+    FinalizedFork { ancestor: Block, descendant: Block },
+    #[error("node {node} finalized no new block within {bound:?} of {since:?}")]
+    Stalled {
+        node: usize,
+        since: Duration,
+        bound: Duration,
+    },
+}
+
+/// An input scheduled for a node at a virtual time.
+struct Scheduled {
+    at: Duration,
+    /// Breaks ties between inputs scheduled at the same time in the order they were scheduled
+    seq: u64,
+    node: usize,
+    input: Input,
+}
+
+impl PartialEq for Scheduled {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scheduled {}
+
+impl PartialOrd for Scheduled {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scheduled {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.at, self.seq).cmp(&(other.at, other.seq))
+    }
+}
+
+pub(crate) struct Simulator {
+    rng: StdRng,
+    network: NetworkConfig,
+    byzantine: HashMap<usize, Byzantine>,
+    nodes: Vec<Node>,
+    /// Leader of each window, repeating after `LEADER_SCHEDULE_WINDOWS`
+    leader_schedule: Vec<usize>,
+    /// Time a leader takes to produce each block
+    slot_duration: Duration,
+    now: Duration,
+    queue: BinaryHeap<Reverse<Scheduled>>,
+    next_seq: u64,
+    /// Pending timer wake up of each node
+    timer_wakeups: Vec<Option<Duration>>,
+    /// Pending standstill check of each node
+    standstill_wakeups: Vec<Option<Duration>>,
+    /// Every block produced, mapped to its parent
+    blocks: HashMap<Block, Block>,
+}
+
+impl Simulator {
+    pub(crate) fn new(config: SimulatorConfig) -> Self {
+        assert!(config.stakes.iter().all(|&stake| stake > 0));
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let validator_keypairs: Vec<_> = config
+            .stakes
+            .iter()
+            .map(|_| {
+                ValidatorVoteKeypairs::new(
+                    new_keypair(&mut rng),
+                    new_keypair(&mut rng),
+                    new_keypair(&mut rng),
+                )
+            })
+            .collect();
+        let mut genesis = create_genesis_config_with_alpenglow_vote_accounts(
+            1_000_000_000,
+            &validator_keypairs,
+            config.stakes.clone(),
+        );
+        // Keep the simulation within the epochs whose stakes the root bank knows
+        genesis.genesis_config.epoch_schedule = EpochSchedule::without_warmup();
+
+        let epoch = Instant::now();
+        let nodes: Vec<_> = validator_keypairs
+            .iter()
+            .enumerate()
+            .map(|(index, keypairs)| {
+                Node::new(
+                    keypairs,
+                    config.byzantine.get(&index).copied(),
+                    &genesis,
+                    epoch,
+                )
+            })
+            .collect();
+        let slot_duration = nodes[0].slot_duration(0);
+        let leader_schedule = new_leader_schedule(&config.stakes, &mut rng);
+
+        let num_nodes = nodes.len();
+        let mut simulator = Self {
+            rng,
+            network: config.network,
+            byzantine: config.byzantine,
+            nodes,
+            leader_schedule,
+            slot_duration,
+            now: Duration::ZERO,
+            queue: BinaryHeap::new(),
+            next_seq: 0,
+            timer_wakeups: vec![None; num_nodes],
+            standstill_wakeups: vec![None; num_nodes],
+            blocks: HashMap::new(),
+        };
+        for node in 0..num_nodes {
+            simulator.schedule(Duration::ZERO, node, Input::Start);
+        }
+        simulator
+    }
+
+    /// Runs the simulation up to virtual time `end`
+    pub(crate) fn run_until(&mut self, end: Duration) {
+        while self
+            .queue
+            .peek()
+            .is_some_and(|Reverse(scheduled)| scheduled.at <= end)
+        {
+            let Reverse(scheduled) = self.queue.pop().unwrap();
+            self.step(scheduled);
+        }
+        self.now = self.now.max(end);
+    }
+
+    /// The blocks `node` has seen finalized, in the order it saw them
+    pub(crate) fn finalized(&self, node: usize) -> &[FinalizedBlock] {
+        self.nodes[node].finalized()
+    }
+
+    pub(crate) fn highest_finalized_slot(&self, node: usize) -> Option<Slot> {
+        self.finalized(node)
+            .iter()
+            .map(|finalized| finalized.block.slot)
+            .max()
+    }
+
+    /// Checks that honest nodes never finalized conflicting blocks
+    pub(crate) fn check_safety(&self) -> Result<(), InvariantViolation> {
+        let mut finalized = BTreeMap::new();
+        for node in self.honest_nodes() {
+            for &FinalizedBlock { block, .. } in self.finalized(node) {
+                match finalized.entry(block.slot) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(block);
+                    }
+                    Entry::Occupied(entry) if *entry.get() != block => {
+                        return Err(InvariantViolation::ConflictingFinalization {
+                            slot: block.slot,
+                            first: *entry.get(),
+                            second: block,
+                        });
+                    }
+                    Entry::Occupied(_) => (),
+                }
+            }
+        }
+        // Finalizing a block finalizes its ancestors, so all finalized blocks form a single chain
+        for (&ancestor, &descendant) in finalized.values().zip(finalized.values().skip(1)) {
+            if !self.descends_from(descendant, ancestor) {
+                return Err(InvariantViolation::FinalizedFork {
+                    ancestor,
+                    descendant,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every honest node finalized a new block within `bound` of `since`
+    ///
+    /// The simulation must have run past `since + bound`.
+    pub(crate) fn check_liveness(
+        &self,
+        since: Duration,
+        bound: Duration,
+    ) -> Result<(), InvariantViolation> {
+        let deadline = since.saturating_add(bound);
+        assert!(self.now >= deadline);
+        for node in self.honest_nodes() {
+            let finalized = self.finalized(node);
+            let highest_before = finalized
+                .iter()
+                .filter(|finalized| finalized.at < since)
+                .map(|finalized| finalized.block.slot)
+                .max();
+            if !finalized.iter().any(|finalized| {
+                (since..=deadline).contains(&finalized.at)
+                    && Some(finalized.block.slot) > highest_before
+            }) {
+                return Err(InvariantViolation::Stalled { node, since, bound });
+            }
+        }
+        Ok(())
+    }
+
+    fn honest_nodes(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).filter(|node| !self.byzantine.contains_key(node))
+    }
+
+    fn descends_from(&self, mut block: Block, ancestor: Block) -> bool {
+        while block.slot > ancestor.slot {
+            match self.blocks.get(&block) {
+                Some(&parent) => block = parent,
+                None => return false,
+            }
+        }
+        block == ancestor
+    }
+
+    fn leader(&self, slot: Slot) -> usize {
+        let window = slot
+            .checked_div(NUM_CONSECUTIVE_LEADER_SLOTS.get() as Slot)
+            .expect("NUM_CONSECUTIVE_LEADER_SLOTS is non-zero");
+        let index = usize::try_from(window)
+            .unwrap()
+            .checked_rem(self.leader_schedule.len())
+            .expect("leader schedule is non-empty");
+        self.leader_schedule[index]
+    }
+
+    fn schedule(&mut self, at: Duration, node: usize, input: Input) {
+        let seq = self.next_seq;
+        self.next_seq = self.next_seq.saturating_add(1);
+        self.queue.push(Reverse(Scheduled {
+            at,
+            seq,
+            node,
+            input,
+        }));
+    }
+
+    /// Sends a message created by `make_input` from `from` to every other node
+    fn broadcast(&mut self, from: usize, make_input: impl Fn() -> Input) {
+        for to in 0..self.nodes.len() {
+            if to == from {
+                continue;
+            }
+            if let Some(at) = self.network.arrival(from, to, self.now, &mut self.rng) {
+                self.schedule(at, to, make_input());
+            }
+        }
+    }
+
+    fn step(
+        &mut self,
+        Scheduled {
+            at, node, input, ..
+        }: Scheduled,
+    ) {
+        self.now = at;
+        match input {
+            Input::Timers if self.timer_wakeups[node] == Some(at) => {
+                self.timer_wakeups[node] = None;
+            }
+            Input::Standstill => self.standstill_wakeups[node] = None,
+            _ => (),
+        }
+
+        let output = self.nodes[node].handle(input, at);
+        for vote in output.votes {
+            self.broadcast(node, || Input::Vote(vote.clone()));
+        }
+        for certificate in output.certificates {
+            self.broadcast(node, || Input::Certificate(certificate.clone()));
+        }
+        for block in output.repairs {
+            self.repair(node, block);
+        }
+        if let Some(slot) = output.parent_ready
+            && self.leader(slot) == node
+            && let BlockProductionParent::Parent(parent) =
+                self.nodes[node].block_production_parent(slot)
+        {
+            self.produce_window(node, slot, parent);
+        }
+
+        if let Some(next_timeout) = self.nodes[node].next_timeout()
+            && self.timer_wakeups[node].is_none_or(|wakeup| next_timeout < wakeup)
+        {
+            self.timer_wakeups[node] = Some(next_timeout);
+            self.schedule(next_timeout, node, Input::Timers);
+        }
+        if self.standstill_wakeups[node].is_none() {
+            let deadline = self.nodes[node].standstill_deadline();
+            self.standstill_wakeups[node] = Some(deadline);
+            self.schedule(deadline, node, Input::Standstill);
+        }
+    }
+
+    /// Sends `block` from its leader to `node`, if it was ever produced
+    fn repair(&mut self, node: usize, block: Block) {
+        let Some(&parent) = self.blocks.get(&block) else {
+            return;
+        };
+        let leader = self.leader(block.slot);
+        if let Some(at) = self.network.arrival(leader, node, self.now, &mut self.rng) {
+            self.schedule(at, node, Input::RepairedBlock { block, parent });
+        }
+    }
+
+    /// Produces the leader window starting at `start_slot` on top of `parent`, sending each block
+    /// out as it is produced
+    fn produce_window(&mut self, leader: usize, start_slot: Slot, parent: Block) {
+        let equivocating = self.byzantine.get(&leader) == Some(&Byzantine::EquivocatingLeader);
+        let num_versions = if equivocating { 2 } else { 1 };
+        let mut versions = vec![];
+        for _ in 0..num_versions {
+            let mut parent = parent;
+            let mut blocks = vec![];
+            for slot in start_slot..=last_of_consecutive_leader_slots(start_slot) {
+                let block = Block {
+                    slot,
+                    block_id: Hash::new_from_array(self.rng.random()),
+                };
+                self.blocks.insert(block, parent);
+                blocks.push((block, parent));
+                parent = block;
+            }
+            versions.push(blocks);
+        }
+
+        for to in 0..self.nodes.len() {
+            let version = usize::from(equivocating && to != leader && to & 1 == 1);
+            for (offset, &(block, parent)) in (0u32..).zip(&versions[version]) {
+                let started_at = self
+                    .now
+                    .saturating_add(self.slot_duration.saturating_mul(offset));
+                let completed_at = started_at.saturating_add(self.slot_duration);
+                if let Some(at) = self.network.arrival(leader, to, started_at, &mut self.rng) {
+                    self.schedule(at, to, Input::FirstShred(block.slot));
+                }
+                if let Some(at) = self
+                    .network
+                    .arrival(leader, to, completed_at, &mut self.rng)
+                {
+                    self.schedule(at, to, Input::Block { block, parent });
+                }
+            }
+        }
+    }
+}
+
+fn new_keypair(rng: &mut StdRng) -> Keypair {
+    keypair_from_seed(&rng.random::<[u8; 32]>()).unwrap()
+}
+
+/// Picks the leader of each window with probability proportional to its stake
+fn new_leader_schedule(stakes: &[u64], rng: &mut StdRng) -> Vec<usize> {
+    let total_stake = stakes
+        .iter()
+        .fold(0u64, |total, &stake| total.saturating_add(stake));
+    (0..LEADER_SCHEDULE_WINDOWS)
+        .map(|_| {
+            let mut point = rng.random_range(0..total_stake);
+            stakes
+                .iter()
+                .position(|&stake| {
+                    if point < stake {
+                        return true;
+                    }
+                    point = point.saturating_sub(stake);
+                    false
+                })
+                .unwrap()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::common::DELTA_STANDSTILL, test_case::test_case};
+
+    fn run(config: SimulatorConfig, end: Duration) -> Simulator {
+        let mut simulator = Simulator::new(config);
+        simulator.run_until(end);
+        simulator
+    }
+
+    #[test_case(vec![100; 4]; "uniform_stake")]
+    #[test_case(vec![70, 10, 10, 10]; "skewed_stake")]
+    fn test_honest_cluster(stakes: Vec<u64>) {
+        let num_nodes = stakes.len();
+        let simulator = run(
+            SimulatorConfig {
+                stakes,
+                ..SimulatorConfig::default()
+            },
+            Duration::from_secs(10),
+        );
+        assert_eq!(simulator.check_safety(), Ok(()));
+        assert_eq!(
+            simulator.check_liveness(Duration::ZERO, Duration::from_secs(2)),
+            Ok(())
+        );
+        for node in 0..num_nodes {
+            assert!(simulator.highest_finalized_slot(node) >= Some(12));
+            assert!(
+                simulator
+                    .finalized(node)
+                    .iter()
+                    .any(|finalized| finalized.fast)
+            );
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_run() {
+        let config = SimulatorConfig {
+            seed: 42,
+            stakes: vec![100; 5],
+            network: NetworkConfig {
+                drop_rate: 0.1,
+                ..NetworkConfig::default()
+            },
+            ..SimulatorConfig::default()
+        };
+        let first = run(config.clone(), Duration::from_secs(5));
+        let second = run(config, Duration::from_secs(5));
+        for node in 0..5 {
+            assert!(!first.finalized(node).is_empty());
+            assert_eq!(first.finalized(node), second.finalized(node));
+        }
+    }
+
+    #[test]
+    fn test_partition_heals() {
+        let partition = Partition {
+            during: Duration::from_secs(2)..Duration::from_secs(6),
+            groups: vec![vec![0, 1, 2], vec![3, 4, 5]],
+        };
+        let network = NetworkConfig {
+            partitions: vec![partition],
+            ..NetworkConfig::default()
+        };
+        let healed_at = network.healed_at();
+        // Votes lost during the partition are only resent on standstill
+        let bound = DELTA_STANDSTILL.saturating_add(Duration::from_secs(5));
+        let simulator = run(
+            SimulatorConfig {
+                seed: 1,
+                stakes: vec![100; 6],
+                network,
+                ..SimulatorConfig::default()
+            },
+            healed_at.saturating_add(bound),
+        );
+        assert_eq!(simulator.check_safety(), Ok(()));
+        assert_eq!(simulator.check_liveness(healed_at, bound), Ok(()));
+    }
+
+    #[test]
+    fn test_message_drops() {
+        let simulator = run(
+            SimulatorConfig {
+                seed: 2,
+                stakes: vec![100; 6],
+                network: NetworkConfig {
+                    drop_rate: 0.05,
+                    ..NetworkConfig::default()
+                },
+                ..SimulatorConfig::default()
+            },
+            Duration::from_secs(30),
+        );
+        assert_eq!(simulator.check_safety(), Ok(()));
+        assert_eq!(
+            simulator.check_liveness(Duration::from_secs(15), Duration::from_secs(15)),
+            Ok(())
+        );
+    }
+
+    #[test_case(Byzantine::DoubleVote; "double_vote")]
+    #[test_case(Byzantine::WithholdVotes; "withhold_votes")]
+    #[test_case(Byzantine::EquivocatingLeader; "equivocating_leader")]
+    fn test_byzantine_node(behavior: Byzantine) {
+        // The byzantine node holds less than 20% of the stake
+        let simulator = run(
+            SimulatorConfig {
+                seed: 3,
+                stakes: vec![15, 17, 17, 17, 17, 17],
+                byzantine: HashMap::from([(0, behavior)]),
+                ..SimulatorConfig::default()
+            },
+            Duration::from_secs(20),
+        );
+        assert_eq!(simulator.check_safety(), Ok(()));
+        assert_eq!(
+            simulator.check_liveness(Duration::from_secs(10), Duration::from_secs(10)),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! The simulated network between nodes.
+
+use {
+    rand::{Rng, rngs::StdRng},
+    std::{ops::Range, time::Duration},
+};
+
+/// A period during which nodes in different groups cannot reach each other.
+#[derive(Clone, Debug)]
+pub(crate) struct Partition {
+    /// Virtual time during which the partition is in place
+    pub(crate) during: Range<Duration>,
+    /// Node indices of each side of the partition. Nodes that are not in any group are isolated
+    /// from everyone else.
+    pub(crate) groups: Vec<Vec<usize>>,
+}
+
+impl Partition {
+    fn separates(&self, a: usize, b: usize, at: Duration) -> bool {
+        self.during.contains(&at)
+            && !self
+                .groups
+                .iter()
+                .any(|group| group.contains(&a) && group.contains(&b))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct NetworkConfig {
+    /// Minimum one-way delay of a message
+    pub(crate) min_delay: Duration,
+    /// Maximum one-way delay of a message
+    pub(crate) max_delay: Duration,
+    /// Probability that a message is lost
+    pub(crate) drop_rate: f64,
+    /// Messages sent across a partition are lost
+    pub(crate) partitions: Vec<Partition>,
+}
+
+impl Default for NetworkConfig {
+    /// A synchronous network, delivering every message within `DELTA`
+    fn default() -> Self {
+        Self {
+            min_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(100),
+            drop_rate: 0.0,
+            partitions: vec![],
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// Returns when a message sent from `from` to `to` at `sent_at` arrives, or `None` if it is
+    /// lost
+    pub(super) fn arrival(
+        &self,
+        from: usize,
+        to: usize,
+        sent_at: Duration,
+        rng: &mut StdRng,
+    ) -> Option<Duration> {
+        if from == to {
+            return Some(sent_at);
+        }
+        if self
+            .partitions
+            .iter()
+            .any(|partition| partition.separates(from, to, sent_at))
+        {
+            return None;
+        }
+        if self.drop_rate > 0.0 && rng.random_bool(self.drop_rate) {
+            return None;
+        }
+        let delay = rng.random_range(self.min_delay..=self.max_delay);
+        Some(sent_at.saturating_add(delay))
+    }
+
+    /// The time from which no partition is in place
+    pub(crate) fn healed_at(&self) -> Duration {
+        self.partitions
+            .iter()
+            .map(|partition| partition.during.end)
+            .max()
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,653 @@
+//! A simulated node.
+//!
+//! Each node runs the real event handler on top of its own [`BankForks`], [`ConsensusPool`] and a
+//! manually clocked [`TimerManager`]. Instead of channels and background threads, the node is
+//! stepped by the simulator with an input and the current virtual time: it does the work of the
+//! consensus pool service and replay itself, hands every resulting event to
+//! [`EventHandler::handle_event`] and returns the messages it wants to send.
+
+use {
+    super::{Byzantine, FinalizedBlock},
+    crate::{
+        commitment::CommitmentAggregationData,
+        common::DELTA_STANDSTILL,
+        consensus_pool::{ConsensusPool, parent_ready_tracker::BlockProductionParent},
+        consensus_pool_service::{PoolMessage, PoolVote},
+        event::{
+            CompletedBlock, LatestSwitchRequest, LeaderWindowInfo, RepairEvent,
+            RepairEventReceiver, VotorEvent,
+        },
+        event_handler::{EventHandler, LocalContext},
+        root_utils::RootContext,
+        tests::get_cluster_info,
+        timer_manager::TimerManager,
+        vote_history::VoteHistory,
+        vote_history_storage::NullVoteHistoryStorage,
+        voting_service::BLSOp,
+        voting_utils::VotingContext,
+        votor::SharedContext,
+    },
+    agave_bls_sigverify::{generated_cert_types::GeneratedCertTypes, rewards::RewardInput},
+    agave_votor_messages::{
+        certificate::Certificate,
+        consensus_message::{BLS_KEYPAIR_DERIVE_SEED, Block, VoteMessage},
+        metric_types::ConsensusMetricsEventReceiver,
+        migration::MigrationStatus,
+        sig_verified_messages::VoteAggregate,
+        vote::Vote,
+        wire::get_vote_payload_to_sign,
+    },
+    crossbeam_channel::{Receiver, unbounded},
+    parking_lot::RwLock as PlRwLock,
+    solana_bls_signatures::{keypair::Keypair as BLSKeypair, signature::Signature as BLSSignature},
+    solana_clock::Slot,
+    solana_leader_schedule::SlotLeader,
+    solana_ledger::{
+        blockstore::Blockstore, get_tmp_ledger_path_auto_delete,
+        leader_schedule_cache::LeaderScheduleCache,
+    },
+    solana_pubkey::Pubkey,
+    solana_runtime::{
+        bank::Bank,
+        bank_forks::BankForks,
+        bank_forks_controller::{BankForksController, BankForksControllerError},
+        genesis_utils::{GenesisConfigInfo, ValidatorVoteKeypairs},
+        installed_scheduler_pool::BankWithScheduler,
+    },
+    solana_signer::Signer,
+    solana_streamer::evicting_sender::EvictingSender,
+    std::{
+        collections::{BTreeSet, HashMap, VecDeque},
+        mem,
+        sync::{Arc, RwLock},
+        time::{Duration, Instant},
+    },
+    tempfile::TempDir,
+};
+
+pub(super) enum Input {
+    /// Kicks off voting from the root
+    Start,
+    /// The first shred of the block in `slot` has been received
+    FirstShred(Slot),
+    /// The block has been received from its leader
+    Block {
+        block: Block,
+        parent: Block,
+    },
+    /// A block the node asked to repair has been received
+    RepairedBlock {
+        block: Block,
+        parent: Block,
+    },
+    Vote(VoteMessage),
+    Certificate(Certificate),
+    /// Skip timers may have expired
+    Timers,
+    /// No block may have been finalized for `DELTA_STANDSTILL`
+    Standstill,
+}
+
+/// What a node wants to send after handling an input.
+#[derive(Default)]
+pub(super) struct Output {
+    pub(super) votes: Vec<VoteMessage>,
+    pub(super) certificates: Vec<Certificate>,
+    /// The new highest slot with a ready parent, if it advanced
+    pub(super) parent_ready: Option<Slot>,
+    /// Blocks the node is missing and wants to repair
+    pub(super) repairs: Vec<Block>,
+}
+
+/// Applies bank forks commands right away, as replay would on its next iteration.
+struct SimulatedBankForksController {
+    bank_forks: Arc<RwLock<BankForks>>,
+}
+
+impl BankForksController for SimulatedBankForksController {
+    fn insert_bank(&self, bank: Bank) -> Result<BankWithScheduler, BankForksControllerError> {
+        Ok(self.bank_forks.write().unwrap().insert(bank))
+    }
+
+    fn enqueue_set_root(&self, new_root: Block) {
+        self.bank_forks
+            .write()
+            .unwrap()
+            .set_root(new_root.slot, None, None);
+    }
+
+    fn clear_bank(&self, slot: Slot) -> Result<(), BankForksControllerError> {
+        self.bank_forks.write().unwrap().clear_bank(slot, false);
+        Ok(())
+    }
+}
+
+/// Receiving ends of the channels the event handler sends on.
+struct Receivers {
+    own_votes: Receiver<VoteMessage>,
+    repair_events: RepairEventReceiver,
+    commitment: Receiver<CommitmentAggregationData>,
+    consensus_metrics: ConsensusMetricsEventReceiver,
+    own_rewards: Receiver<RewardInput>,
+    bls_ops: Receiver<BLSOp>,
+    leader_window_info: Receiver<LeaderWindowInfo>,
+}
+
+impl Receivers {
+    /// Drops everything sent to services that are not part of the simulation
+    fn discard_unused(&self) {
+        self.commitment.try_iter().for_each(drop);
+        self.consensus_metrics.try_iter().for_each(drop);
+        self.own_rewards.try_iter().for_each(drop);
+        self.bls_ops.try_iter().for_each(drop);
+        self.leader_window_info.try_iter().for_each(drop);
+    }
+}
+
+pub(super) struct Node {
+    behavior: Option<Byzantine>,
+    vote_account_pubkey: Pubkey,
+    bls_keypair: BLSKeypair,
+    shred_version: u16,
+    root_block: Block,
+    pool: ConsensusPool,
+    timer_manager: PlRwLock<TimerManager>,
+    shared_context: SharedContext,
+    voting_context: VotingContext,
+    root_context: RootContext,
+    local_context: LocalContext,
+    receivers: Receivers,
+    // Keep the temp directory alive for the blockstore.
+    _ledger_path: TempDir,
+    /// Virtual time zero
+    epoch: Instant,
+    /// Current virtual time
+    now: Duration,
+    /// Events still to be handled in the current step
+    events: VecDeque<VotorEvent>,
+    output: Output,
+    /// Blocks that have been received, mapped to their parents
+    blocks: HashMap<Block, Block>,
+    /// Received blocks that cannot be replayed until their parent is, along with whether they
+    /// were repaired
+    orphans: HashMap<Block, Vec<(Block, bool)>>,
+    /// Intrawindow blocks that are safe to notarize once their parent is notarized-fallback
+    pending_safe_to_notar: BTreeSet<Block>,
+    highest_parent_ready: Slot,
+    next_timeout: Option<Instant>,
+    /// When the standstill timer was last reset
+    standstill_timer: Duration,
+    finalized: Vec<FinalizedBlock>,
+}
+
+impl Node {
+    pub(super) fn new(
+        keypairs: &ValidatorVoteKeypairs,
+        behavior: Option<Byzantine>,
+        genesis: &GenesisConfigInfo,
+        epoch: Instant,
+    ) -> Self {
+        let node_pubkey = keypairs.node_keypair.pubkey();
+        let cluster_info = get_cluster_info(keypairs.node_keypair.insecure_clone());
+        let shred_version = cluster_info.my_shred_version();
+        let bank_forks = BankForks::new_rw_arc(Bank::new_for_tests(&genesis.genesis_config));
+        let sharable_banks = bank_forks.read().unwrap().sharable_banks();
+        let root_bank = sharable_banks.root();
+        let root_block = Block {
+            slot: root_bank.slot(),
+            block_id: root_bank.block_id().unwrap_or_default(),
+        };
+        let pool = ConsensusPool::new(
+            cluster_info.clone(),
+            &root_bank,
+            Arc::new(GeneratedCertTypes::default()),
+            Arc::new(MigrationStatus::post_migration_status()),
+            (root_block.slot.checked_add(1).unwrap(), root_block),
+        );
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let vote_history_storage = Arc::new(NullVoteHistoryStorage::default());
+        let (own_vote_sender, own_votes) = EvictingSender::new_bounded(1024);
+        let (repair_event_sender, repair_events) = unbounded();
+        let (commitment_sender, commitment) = unbounded();
+        let (consensus_metrics_sender, consensus_metrics) = unbounded();
+        let (own_reward_sender, own_rewards) = unbounded();
+        let (bls_sender, bls_ops) = unbounded();
+        let (leader_window_info_sender, leader_window_info) = unbounded();
+
+        let shared_context = SharedContext {
+            blockstore,
+            bank_forks: bank_forks.clone(),
+            cluster_info: cluster_info.clone(),
+            leader_window_info_sender,
+            highest_parent_ready: Arc::new(RwLock::new((root_block.slot, root_block))),
+            vote_history_storage: vote_history_storage.clone(),
+            repair_event_sender,
+            latest_switch_request: LatestSwitchRequest::default(),
+        };
+        let mut vote_history = VoteHistory::new(node_pubkey, root_block.slot);
+        vote_history.initialize_genesis(root_block.slot);
+        let voting_context = VotingContext {
+            cluster_info,
+            leader_schedule: Arc::new(LeaderScheduleCache::new_from_bank(&root_bank)),
+            vote_history,
+            vote_account_pubkey: keypairs.vote_keypair.pubkey(),
+            identity_keypair: Arc::new(keypairs.node_keypair.insecure_clone()),
+            authorized_voter_keypairs: Arc::new(RwLock::new(vec![Arc::new(
+                keypairs.vote_keypair.insecure_clone(),
+            )])),
+            vote_history_storage,
+            derived_bls_keypairs: HashMap::new(),
+            own_vote_sender,
+            own_reward_sender,
+            bls_sender,
+            commitment_sender,
+            wait_to_vote_slot: None,
+            sharable_banks,
+            consensus_metrics_sender,
+        };
+        let root_context = RootContext {
+            bank_notification_sender: None,
+            bank_forks_controller: Arc::new(SimulatedBankForksController { bank_forks }),
+        };
+
+        Self {
+            behavior,
+            vote_account_pubkey: keypairs.vote_keypair.pubkey(),
+            bls_keypair: BLSKeypair::derive_from_signer(
+                &keypairs.vote_keypair,
+                BLS_KEYPAIR_DERIVE_SEED,
+            )
+            .unwrap(),
+            shred_version,
+            root_block,
+            pool,
+            timer_manager: PlRwLock::new(TimerManager::new_manual(epoch)),
+            shared_context,
+            voting_context,
+            root_context,
+            local_context: LocalContext::new(node_pubkey),
+            receivers: Receivers {
+                own_votes,
+                repair_events,
+                commitment,
+                consensus_metrics,
+                own_rewards,
+                bls_ops,
+                leader_window_info,
+            },
+            _ledger_path: ledger_path,
+            epoch,
+            now: Duration::ZERO,
+            events: VecDeque::new(),
+            output: Output::default(),
+            blocks: HashMap::new(),
+            orphans: HashMap::new(),
+            pending_safe_to_notar: BTreeSet::new(),
+            highest_parent_ready: root_block.slot,
+            next_timeout: None,
+            standstill_timer: Duration::ZERO,
+            finalized: vec![],
+        }
+    }
+
+    /// Handles `input` at virtual time `now`, along with every event it leads to
+    pub(super) fn handle(&mut self, input: Input, now: Duration) -> Output {
+        self.now = now;
+        self.progress_timers();
+        match input {
+            Input::Start => self.events.push_back(VotorEvent::ParentReady {
+                slot: self.root_block.slot.checked_add(1).unwrap(),
+                parent_block: self.root_block,
+            }),
+            Input::FirstShred(slot) => self.events.push_back(VotorEvent::FirstShred(slot)),
+            Input::Block { block, parent } => self.on_block(block, parent, false),
+            Input::RepairedBlock { block, parent } => self.on_block(block, parent, true),
+            Input::Vote(vote_message) => {
+                let max_validators = self
+                    .root_bank()
+                    .get_rank_map(vote_message.vote.slot())
+                    .expect("simulated slots must be in an epoch known to the root bank")
+                    .len();
+                self.add_pool_msg(PoolMessage::Votes(vec![PoolVote::External(
+                    VoteAggregate::new_from_verified_vote(max_validators, vote_message),
+                )]));
+            }
+            Input::Certificate(certificate) => {
+                self.add_pool_msg(PoolMessage::Certificates(vec![certificate]))
+            }
+            // Expired timers were collected above
+            Input::Timers => (),
+            Input::Standstill => {
+                if self.now >= self.standstill_deadline() {
+                    self.standstill_timer = self.now;
+                    let highest_finalized_slot = self
+                        .pool
+                        .highest_finalized_slot()
+                        .map_or(self.root_block.slot, |slot| slot.slot());
+                    self.events
+                        .push_back(VotorEvent::Standstill(highest_finalized_slot));
+                    self.output.certificates.extend(
+                        self.pool
+                            .get_certs_for_standstill()
+                            .iter()
+                            .map(|certificate| Certificate::clone(certificate)),
+                    );
+                }
+            }
+        }
+
+        while !self.events.is_empty() {
+            self.process_events();
+            self.progress_timers();
+        }
+        mem::take(&mut self.output)
+    }
+
+    /// When the earliest skip timer may expire
+    pub(super) fn next_timeout(&self) -> Option<Duration> {
+        self.next_timeout
+            .map(|next_timeout| next_timeout.saturating_duration_since(self.epoch))
+    }
+
+    /// When the node declares a standstill unless it sees a new finalization first
+    pub(super) fn standstill_deadline(&self) -> Duration {
+        self.standstill_timer.saturating_add(DELTA_STANDSTILL)
+    }
+
+    /// For a leader window starting in `slot`, which block the node would build on
+    pub(super) fn block_production_parent(&self, slot: Slot) -> BlockProductionParent {
+        self.pool.parent_ready_tracker.block_production_parent(slot)
+    }
+
+    /// Time a leader takes to produce the block in `slot`
+    pub(super) fn slot_duration(&self, slot: Slot) -> Duration {
+        Duration::from_nanos_u128(self.root_bank().ns_per_slot_at_slot(slot))
+    }
+
+    pub(super) fn finalized(&self) -> &[FinalizedBlock] {
+        &self.finalized
+    }
+
+    fn instant(&self) -> Instant {
+        self.epoch
+            .checked_add(self.now)
+            .expect("virtual time must fit in an Instant")
+    }
+
+    fn root_bank(&self) -> Arc<Bank> {
+        self.voting_context.sharable_banks.root()
+    }
+
+    /// Moves the timer manager's clock to the current virtual time, queueing the events of every
+    /// timer that expired
+    fn progress_timers(&mut self) {
+        let (next_timeout, events) = self.timer_manager.read().progress_to(self.instant());
+        self.next_timeout = next_timeout;
+        self.events.extend(events);
+    }
+
+    fn process_events(&mut self) {
+        loop {
+            self.check_pending_safe_to_notar();
+            let Some(event) = self.events.pop_front() else {
+                break;
+            };
+            self.observe(&event);
+            let root = self
+                .root_bank()
+                .slot()
+                .max(self.voting_context.vote_history.root());
+            if event.should_ignore(root) {
+                continue;
+            }
+            let bls_ops = EventHandler::handle_event(
+                event,
+                &self.timer_manager,
+                &self.shared_context,
+                &mut self.voting_context,
+                &self.root_context,
+                &mut self.local_context,
+            )
+            .expect("simulated nodes must handle every event");
+            // The consensus pool service picks up our own votes before they are sent out
+            let own_votes: Vec<_> = self
+                .receivers
+                .own_votes
+                .try_iter()
+                .map(PoolVote::Own)
+                .collect();
+            if !own_votes.is_empty() {
+                self.add_pool_msg(PoolMessage::Votes(own_votes));
+            }
+            for bls_op in bls_ops {
+                self.send_bls_op(bls_op);
+            }
+            self.collect_repairs();
+            self.receivers.discard_unused();
+            self.track_parent_ready();
+        }
+    }
+
+    /// Records what the simulator checks about the node
+    fn observe(&mut self, event: &VotorEvent) {
+        if let VotorEvent::Finalized(block, fast) = *event
+            && !self
+                .finalized
+                .iter()
+                .any(|finalized| finalized.block == block)
+        {
+            self.finalized.push(FinalizedBlock {
+                block,
+                fast,
+                at: self.now,
+            });
+        }
+    }
+
+    fn add_pool_msg(&mut self, msg: PoolMessage) {
+        let mut events = vec![];
+        let (new_finalized_slot, new_certificates) =
+            self.pool.add_pool_msg(&self.root_bank(), msg, &mut events);
+        if new_finalized_slot.is_some() {
+            self.standstill_timer = self.now;
+        }
+        self.output.certificates.extend(
+            new_certificates
+                .iter()
+                .map(|certificate| Certificate::clone(certificate)),
+        );
+        self.pending_safe_to_notar
+            .extend(self.pool.take_pending_safe_to_notar());
+        self.events.extend(events);
+    }
+
+    fn send_bls_op(&mut self, bls_op: BLSOp) {
+        match bls_op {
+            BLSOp::PushVote { vote } => {
+                if self.behavior == Some(Byzantine::DoubleVote)
+                    && matches!(vote.vote, Vote::Notarize(_))
+                {
+                    let skip_vote_message = self.sign(Vote::new_skip_vote(vote.vote.slot()));
+                    self.send_vote(skip_vote_message);
+                }
+                self.send_vote(VoteMessage::clone(&vote));
+            }
+            BLSOp::RefreshVotes { mut votes } => {
+                // Keep the refresh order independent of the vote history's hash maps
+                votes.sort_by_key(|vote| (vote.vote.slot(), vote.vote.get_type()));
+                for vote in votes {
+                    self.send_vote(VoteMessage::clone(&vote));
+                }
+            }
+            bls_op @ (BLSOp::PushCertificates { .. } | BLSOp::RefreshCertificates { .. }) => {
+                unreachable!("{bls_op:?} is not returned by the event handler")
+            }
+        }
+    }
+
+    /// Turns the repair and switch requests of the event handler into blocks to repair
+    fn collect_repairs(&mut self) {
+        let requested = self
+            .receivers
+            .repair_events
+            .try_iter()
+            .map(|RepairEvent::FetchBlock { block }| block)
+            .chain(
+                self.shared_context
+                    .latest_switch_request
+                    .take()
+                    .map(|switch| switch.block()),
+            );
+        let bank_forks = self.shared_context.bank_forks.read().unwrap();
+        let missing: Vec<_> = requested
+            .filter(|block| {
+                bank_forks
+                    .get(block.slot)
+                    .is_none_or(|bank| bank.block_id() != Some(block.block_id))
+            })
+            .collect();
+        drop(bank_forks);
+        self.output.repairs.extend(missing);
+    }
+
+    /// Reports a new highest parent ready slot, so that its leader can produce the window
+    fn track_parent_ready(&mut self) {
+        let (slot, _) = *self.shared_context.highest_parent_ready.read().unwrap();
+        if slot > self.highest_parent_ready {
+            self.highest_parent_ready = slot;
+            self.output.parent_ready = Some(slot);
+        }
+    }
+
+    /// Emits `SafeToNotar` for pending intrawindow blocks that were received and whose parent is
+    /// notarized-fallback
+    fn check_pending_safe_to_notar(&mut self) {
+        let highest_finalized_slot = self
+            .pool
+            .highest_finalized_slot()
+            .map_or(self.root_block.slot, |slot| slot.slot());
+        let mut safe_to_notar = vec![];
+        self.pending_safe_to_notar.retain(|block| {
+            if block.slot <= highest_finalized_slot {
+                return false;
+            }
+            match self.blocks.get(block) {
+                Some(parent) if self.pool.block_has_notar_fallback_or_stronger(*parent) => {
+                    safe_to_notar.push(*block);
+                    false
+                }
+                _ => true,
+            }
+        });
+        self.events
+            .extend(safe_to_notar.into_iter().map(VotorEvent::SafeToNotar));
+    }
+
+    fn on_block(&mut self, block: Block, parent: Block, repaired: bool) {
+        self.blocks.insert(block, parent);
+        if self.behavior == Some(Byzantine::DoubleVote)
+            && self
+                .voting_context
+                .vote_history
+                .voted_notar(block.slot)
+                .is_some_and(|block_id| block_id != block.block_id)
+        {
+            let vote_message = self.sign(Vote::new_notarization_vote(block));
+            self.send_vote(vote_message);
+        }
+        self.replay(block, parent, repaired);
+    }
+
+    /// Replays `block` on top of `parent`, followed by every received descendant that was waiting
+    /// for it
+    ///
+    /// Like replay, only one version of a slot is kept: a repaired block replaces the version
+    /// that was replayed before, since the cluster certified it.
+    fn replay(&mut self, block: Block, parent: Block, repaired: bool) {
+        let mut to_replay = vec![(block, parent, repaired)];
+        while let Some((block, parent, repaired)) = to_replay.pop() {
+            let bank_forks = self.shared_context.bank_forks.clone();
+            let root_slot = bank_forks.read().unwrap().root();
+            if block.slot <= root_slot || parent.slot < root_slot {
+                continue;
+            }
+            let replayed = bank_forks.read().unwrap().get(block.slot);
+            if let Some(replayed) = replayed {
+                if !repaired || replayed.block_id() == Some(block.block_id) {
+                    continue;
+                }
+                self.root_context
+                    .bank_forks_controller
+                    .clear_bank(block.slot)
+                    .unwrap();
+            }
+            let parent_bank = bank_forks
+                .read()
+                .unwrap()
+                .get(parent.slot)
+                .filter(|bank| bank.block_id().unwrap_or_default() == parent.block_id);
+            let Some(parent_bank) = parent_bank else {
+                self.orphans
+                    .entry(parent)
+                    .or_default()
+                    .push((block, repaired));
+                // Only follow the ancestry of certified blocks, which is what repair fetches
+                if repaired {
+                    self.output.repairs.push(parent);
+                }
+                continue;
+            };
+            let bank = Bank::new_from_parent(parent_bank, SlotLeader::default(), block.slot);
+            bank.set_block_id(Some(block.block_id));
+            bank.freeze();
+            let bank = self
+                .root_context
+                .bank_forks_controller
+                .insert_bank(bank)
+                .unwrap()
+                .clone_without_scheduler();
+            self.events.push_back(VotorEvent::Block(CompletedBlock {
+                slot: block.slot,
+                bank,
+            }));
+            if let Some(children) = self.orphans.remove(&block) {
+                to_replay.extend(
+                    children
+                        .into_iter()
+                        .map(|(child, repaired)| (child, block, repaired)),
+                );
+            }
+        }
+    }
+
+    fn send_vote(&mut self, vote_message: VoteMessage) {
+        if self.behavior != Some(Byzantine::WithholdVotes) {
+            self.output.votes.push(vote_message);
+        }
+    }
+
+    /// Signs `vote` outside of the event handler, for votes only a byzantine node would cast
+    fn sign(&self, vote: Vote) -> VoteMessage {
+        let root_bank = self.root_bank();
+        let rank_map = root_bank
+            .get_rank_map(vote.slot())
+            .expect("simulated slots must be in an epoch known to the root bank");
+        let rank = *rank_map
+            .get_rank_for_vote_pubkey(&self.vote_account_pubkey)
+            .expect("simulated nodes must be staked");
+        let stake = rank_map
+            .get_pubkey_stake_entry(usize::from(rank))
+            .unwrap()
+            .stake;
+        let payload = get_vote_payload_to_sign(vote, self.shred_version);
+        let signature: BLSSignature = self.bls_keypair.sign(&payload).into();
+        VoteMessage {
+            vote,
+            signature,
+            rank,
+            stake,
+        }
+    }
+}
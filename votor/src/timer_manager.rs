@@ -29,7 +29,11 @@ use {
 /// timers and send events.
 pub(crate) struct TimerManager {
     timers: Arc<PlRwLock<Timers>>,
-    handle: JoinHandle<()>,
+    /// `None` for a manually clocked manager, which has no background thread.
+    handle: Option<JoinHandle<()>>,
+    /// Clock used instead of `Instant::now()` by a manually clocked manager.
+    #[cfg(test)]
+    manual_clock: Option<PlRwLock<Instant>>,
 }
 
 impl TimerManager {
@@ -70,7 +74,43 @@ impl TimerManager {
             })
         };
 
-        Self { timers, handle }
+        Self {
+            timers,
+            handle: Some(handle),
+            #[cfg(test)]
+            manual_clock: None,
+        }
+    }
+
+    /// Creates a manager without a background thread whose clock only moves
+    /// when [`Self::progress_to`] is called, so timers fire deterministically.
+    #[cfg(test)]
+    pub(crate) fn new_manual(now: Instant) -> Self {
+        Self {
+            timers: Arc::new(PlRwLock::new(Timers::new(DELTA_TIMEOUT))),
+            handle: None,
+            manual_clock: Some(PlRwLock::new(now)),
+        }
+    }
+
+    /// Advances the clock of a manually clocked manager to `now` and returns
+    /// the timer events that became ready along with the next fire time.
+    #[cfg(test)]
+    pub(crate) fn progress_to(&self, now: Instant) -> (Option<Instant>, Vec<VotorEvent>) {
+        let manual_clock = self
+            .manual_clock
+            .as_ref()
+            .expect("progress_to requires a manually clocked timer manager");
+        *manual_clock.write() = now;
+        self.timers.write().progress(now)
+    }
+
+    fn now(&self) -> Instant {
+        #[cfg(test)]
+        if let Some(manual_clock) = &self.manual_clock {
+            return *manual_clock.read();
+        }
+        Instant::now()
     }
 
     pub(crate) fn set_timeouts(
@@ -82,20 +122,22 @@ impl TimerManager {
     ) -> bool {
         let timeout_inserted = self.timers.write().set_timeouts(
             slot,
-            Instant::now(),
+            self.now(),
             standstill_slot,
             delta_first_fec_set,
             delta_block,
         );
-        if timeout_inserted {
-            self.handle.thread().unpark();
+        if timeout_inserted && let Some(handle) = &self.handle {
+            handle.thread().unpark();
         }
         timeout_inserted
     }
 
     pub(crate) fn join(self) {
-        self.handle.thread().unpark();
-        self.handle.join().unwrap();
+        if let Some(handle) = self.handle {
+            handle.thread().unpark();
+            handle.join().unwrap();
+        }
     }
 
     #[cfg(test)]
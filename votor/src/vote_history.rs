@@ -1,6 +1,9 @@
 use {
-    super::vote_history_storage::{
-        Result, SavedVoteHistory, SavedVoteHistoryVersions, VoteHistoryStorage,
+    super::{
+        remote_vote_storage::RemoteVoteStorageError,
+        vote_history_storage::{
+            Result, SavedVoteHistory, SavedVoteHistoryVersions, VoteHistoryStorage,
+        },
     },
     agave_votor_messages::{consensus_message::Block, vote::Vote, wire::VotePayloadToSign},
     solana_clock::Slot,
@@ -179,6 +182,11 @@ impl VoteHistory {
         self.root
     }
 
+    /// The highest slot this node cast a vote in since the root
+    pub fn highest_voted_slot(&self) -> Option<Slot> {
+        self.votes_cast.keys().max().copied()
+    }
+
     /// Add a new vote to the voting history
     pub fn add_vote(&mut self, vote: Vote) {
         assert!(vote.slot() >= self.root);
@@ -325,6 +333,9 @@ pub enum VoteHistoryError {
 
     #[error("The vote history is useless because of new hard fork: {0}")]
     HardFork(Slot),
+
+    #[error("Remote vote storage error: {0}")]
+    RemoteStorage(#[from] RemoteVoteStorageError),
 }
 
 impl VoteHistoryError {
@@ -1,7 +1,10 @@
 #[cfg(feature = "frozen-abi")]
 use serde::{Deserialize, Serialize};
 use {
-    super::vote_history::*,
+    super::{
+        remote_vote_storage::{RecordKey, RecordKind, RemoteVoteStorageClient},
+        vote_history::*,
+    },
     log::trace,
     solana_pubkey::Pubkey,
     solana_signature::Signature,
@@ -9,7 +12,8 @@ use {
     std::{
         fs::{self, File},
         io::{self, BufReader, BufWriter},
-        path::PathBuf,
+        path::{Path, PathBuf},
+        sync::Arc,
     },
     wincode::{SchemaRead, SchemaWrite},
 };
@@ -31,19 +35,16 @@ pub enum SavedVoteHistoryVersions {
 }
 
 impl SavedVoteHistoryVersions {
-    fn try_into_vote_history(&self, node_pubkey: &Pubkey) -> Result<VoteHistory> {
+    /// Verifies that the vote history was signed by `node_pubkey` and belongs
+    /// to it, and decodes it.
+    pub fn try_into_vote_history(&self, node_pubkey: &Pubkey) -> Result<VoteHistory> {
         // This method assumes that `self` was just deserialized
         assert_eq!(self.pubkey(), Pubkey::default());
 
-        let vote_history = match self {
-            SavedVoteHistoryVersions::Current(t) => {
-                if !t.signature.verify(node_pubkey.as_ref(), &t.data) {
-                    return Err(VoteHistoryError::InvalidSignature);
-                }
-                wincode::deserialize(&t.data).map(VoteHistoryVersions::Current)?
-            }
-        };
-        let vote_history = vote_history.convert_to_current();
+        if !self.verify_signature(node_pubkey) {
+            return Err(VoteHistoryError::InvalidSignature);
+        }
+        let vote_history = self.decode_unverified()?;
         if vote_history.node_pubkey != *node_pubkey {
             return Err(VoteHistoryError::WrongVoteHistory(format!(
                 "node_pubkey is {:?} but found vote history for {:?}",
@@ -53,6 +54,27 @@ impl SavedVoteHistoryVersions {
         Ok(vote_history)
     }
 
+    /// Whether the saved vote history data was signed by `node_pubkey`
+    pub fn verify_signature(&self, node_pubkey: &Pubkey) -> bool {
+        match self {
+            SavedVoteHistoryVersions::Current(t) => {
+                t.signature.verify(node_pubkey.as_ref(), &t.data)
+            }
+        }
+    }
+
+    /// Decodes the vote history without checking who signed it. Only meant
+    /// for offline inspection, use [`Self::try_into_vote_history`] to load a
+    /// vote history.
+    pub fn decode_unverified(&self) -> Result<VoteHistory> {
+        let vote_history = match self {
+            SavedVoteHistoryVersions::Current(t) => {
+                wincode::deserialize(&t.data).map(VoteHistoryVersions::Current)?
+            }
+        };
+        Ok(vote_history.convert_to_current())
+    }
+
     fn serialize_into(&self, file: &mut File) -> Result<()> {
         wincode::serialize_into(BufWriter::new(file), self)?;
         Ok(())
@@ -149,6 +171,13 @@ impl FileVoteHistoryStorage {
             .join(format!("vote_history-{node_pubkey}"))
             .with_extension("bin")
     }
+
+    /// Reads the saved vote history at `path` without verifying it, for
+    /// offline inspection
+    pub fn read_saved_vote_history(path: &Path) -> Result<SavedVoteHistoryVersions> {
+        let mut stream = BufReader::new(File::open(path)?);
+        Ok(wincode::deserialize_from(&mut stream)?)
+    }
 }
 
 impl VoteHistoryStorage for FileVoteHistoryStorage {
@@ -185,10 +214,57 @@ impl VoteHistoryStorage for FileVoteHistoryStorage {
     }
 }
 
+/// Vote history storage shared with other validators through a vote storage
+/// server, see [`crate::remote_vote_storage`]
+#[derive(Debug, Clone)]
+pub struct RemoteVoteHistoryStorage {
+    client: Arc<RemoteVoteStorageClient>,
+}
+
+impl RemoteVoteHistoryStorage {
+    pub fn new(client: Arc<RemoteVoteStorageClient>) -> Self {
+        Self { client }
+    }
+}
+
+impl VoteHistoryStorage for RemoteVoteHistoryStorage {
+    fn load(&self, node_pubkey: &Pubkey) -> Result<VoteHistory> {
+        let key = RecordKey::new(RecordKind::VoteHistory, *node_pubkey);
+        trace!("load {key} from {}", self.client.server_addr());
+        let Some(data) = self.client.load(key)? else {
+            return Err(VoteHistoryError::IoError(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no {key} on {}", self.client.server_addr()),
+            )));
+        };
+        let saved_vote_history: SavedVoteHistoryVersions = wincode::deserialize(&data)?;
+        saved_vote_history.try_into_vote_history(node_pubkey)
+    }
+
+    fn store(&self, saved_vote_history: &SavedVoteHistoryVersions) -> Result<()> {
+        let key = RecordKey::new(RecordKind::VoteHistory, saved_vote_history.pubkey());
+        trace!("store {key} to {}", self.client.server_addr());
+        let vote_history = saved_vote_history.decode_unverified()?;
+        let watermark = vote_history
+            .highest_voted_slot()
+            .unwrap_or_default()
+            .max(vote_history.root());
+        self.client
+            .store(key, watermark, wincode::serialize(saved_vote_history)?)?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use {
-        super::*, agave_votor_messages::vote::Vote, solana_keypair::Keypair, solana_signer::Signer,
+        super::*,
+        crate::remote_vote_storage::{
+            RemoteVoteStorageError, RemoteVoteStorageServer, SharedSecret,
+        },
+        agave_votor_messages::vote::Vote,
+        solana_keypair::Keypair,
+        solana_signer::Signer,
         tempfile::TempDir,
     };
 
@@ -241,6 +317,83 @@ mod test {
         assert!(matches!(error, VoteHistoryError::InvalidSignature));
     }
 
+    #[test]
+    fn test_read_saved_vote_history() {
+        let tmp_dir = TempDir::new().unwrap();
+        let storage = FileVoteHistoryStorage::new(tmp_dir.path().to_path_buf());
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+        let mut vote_history = VoteHistory::new(pubkey, 3);
+        vote_history.add_vote(Vote::new_skip_vote(4));
+        vote_history.save(&storage, &keypair).unwrap();
+
+        let saved_vote_history =
+            FileVoteHistoryStorage::read_saved_vote_history(&storage.filename(&pubkey)).unwrap();
+        assert!(saved_vote_history.verify_signature(&pubkey));
+        assert!(!saved_vote_history.verify_signature(&Pubkey::new_unique()));
+        assert_eq!(
+            saved_vote_history.decode_unverified().unwrap(),
+            vote_history
+        );
+        assert_eq!(
+            saved_vote_history.try_into_vote_history(&pubkey).unwrap(),
+            vote_history
+        );
+    }
+
+    #[test]
+    fn test_remote_vote_history_storage_failover() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let secret = SharedSecret::new(vec![7; 32]).unwrap();
+        let server = RemoteVoteStorageServer::new(listener, None, secret.clone()).unwrap();
+        let connect = || {
+            RemoteVoteHistoryStorage::new(Arc::new(RemoteVoteStorageClient::new(
+                server.local_addr(),
+                secret.clone(),
+            )))
+        };
+        let primary = connect();
+        let spare = connect();
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+
+        // Nothing stored yet reads like a missing file
+        assert!(primary.load(&pubkey).unwrap_err().is_file_missing());
+        let mut primary_vote_history = VoteHistory::new(pubkey, 0);
+        primary_vote_history.add_vote(Vote::new_skip_vote(1));
+        primary_vote_history.save(&primary, &keypair).unwrap();
+        primary_vote_history.add_vote(Vote::new_skip_vote(2));
+        primary_vote_history.save(&primary, &keypair).unwrap();
+
+        // A spare that starts from scratch can not clobber the primary's votes
+        let spare_vote_history = VoteHistory::new(pubkey, 0);
+        assert!(matches!(
+            spare_vote_history.save(&spare, &keypair),
+            Err(VoteHistoryError::RemoteStorage(
+                RemoteVoteStorageError::VersionMismatch(_)
+            ))
+        ));
+
+        // The spare takes over with everything the primary voted for...
+        let mut spare_vote_history = VoteHistory::restore(&spare, &pubkey).unwrap();
+        assert_eq!(spare_vote_history, primary_vote_history);
+        assert!(spare_vote_history.voted(2));
+        spare_vote_history.add_vote(Vote::new_skip_vote(3));
+        spare_vote_history.save(&spare, &keypair).unwrap();
+
+        // ...after which the primary can no longer persist, and so cast, votes
+        primary_vote_history.add_vote(Vote::new_skip_vote(3));
+        assert!(matches!(
+            primary_vote_history.save(&primary, &keypair),
+            Err(VoteHistoryError::RemoteStorage(
+                RemoteVoteStorageError::VersionMismatch(_)
+            ))
+        ));
+
+        server.exit();
+        server.join().unwrap();
+    }
+
     #[test]
     fn test_null_vote_history_storage() {
         let storage = NullVoteHistoryStorage::default();
@@ -511,6 +511,8 @@ mod tests {
             client_socket,
             ingress_sender,
             peer_list_receiver,
+            // Every peer gets the base rate.
+            watch::channel(Arc::default()).1,
             VOTOR_RATE_LIMIT_PPS,
             CancellationToken::new(),
         )
@@ -552,6 +554,7 @@ mod tests {
         let peer_list_service = PeerListService::new(
             cluster_info.clone(),
             peer_list,
+            watch::channel(Arc::default()).0,
             bank_forks.read().unwrap().sharable_banks(),
             Some(VotingServiceOverride {
                 override_listeners: Arc::new(ArcSwap::from_pointee(HashMap::from_iter([
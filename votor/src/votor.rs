@@ -48,6 +48,7 @@ use {
         commitment::CommitmentAggregationData,
         consensus_metrics::ConsensusMetrics,
         consensus_pool_service::{ConsensusPoolContext, ConsensusPoolService},
+        consensus_timeline::ConsensusTimeline,
         event::{
             LatestSwitchRequest, LeaderWindowInfo, RepairEventSender, VotorEventReceiver,
             VotorEventSender,
@@ -75,7 +76,10 @@ use {
     solana_keypair::Keypair,
     solana_ledger::{blockstore::Blockstore, leader_schedule_cache::LeaderScheduleCache},
     solana_pubkey::Pubkey,
-    solana_rpc::optimistically_confirmed_bank_tracker::BankNotificationSenderConfig,
+    solana_rpc::{
+        optimistically_confirmed_bank_tracker::BankNotificationSenderConfig,
+        rpc_subscriptions::RpcSubscriptions,
+    },
     solana_runtime::{
         bank_forks::BankForks, bank_forks_controller::BankForksController,
         validated_block_finalization::ValidatedBlockFinalizationCert,
@@ -106,6 +110,7 @@ pub struct VotorConfig {
     pub cluster_info: Arc<ClusterInfo>,
     pub leader_schedule_cache: Arc<LeaderScheduleCache>,
     pub consensus_metrics_sender: ConsensusMetricsEventSender,
+    pub consensus_timeline: Arc<ConsensusTimeline>,
     pub highest_finalized: Arc<RwLock<Option<ValidatedBlockFinalizationCert>>>,
     pub bank_forks_controller: Arc<dyn BankForksController>,
 
@@ -113,6 +118,7 @@ pub struct VotorConfig {
     pub bls_sender: Sender<BLSOp>,
     pub commitment_sender: Sender<CommitmentAggregationData>,
     pub bank_notification_sender: Option<BankNotificationSenderConfig>,
+    pub rpc_subscriptions: Option<Arc<RpcSubscriptions>>,
     pub leader_window_info_sender: Sender<LeaderWindowInfo>,
     pub highest_parent_ready: Arc<RwLock<(Slot, Block)>>,
     pub event_sender: VotorEventSender,
@@ -164,6 +170,7 @@ impl Votor {
             bls_sender,
             commitment_sender,
             bank_notification_sender,
+            rpc_subscriptions,
             leader_window_info_sender,
             highest_parent_ready,
             event_sender,
@@ -175,6 +182,7 @@ impl Votor {
             consensus_message_receiver,
             consensus_metrics_sender,
             consensus_metrics_receiver,
+            consensus_timeline,
             generated_cert_types,
             highest_finalized,
             bank_forks_controller,
@@ -257,12 +265,14 @@ impl Votor {
             bls_sender,
             event_sender,
             repair_event_sender,
+            rpc_subscriptions,
             highest_finalized,
         };
 
         let metrics = ConsensusMetrics::start_metrics_loop(
             epoch_schedule,
             consensus_metrics_receiver,
+            consensus_timeline,
             exit.clone(),
         );
         let event_handler = EventHandler::new(event_handler_context);
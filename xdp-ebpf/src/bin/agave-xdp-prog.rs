@@ -4,17 +4,38 @@
 use {
     aya_ebpf::{
         bindings::xdp_action::{XDP_DROP, XDP_PASS},
-        helpers::r#gen::bpf_xdp_get_buff_len,
-        macros::xdp,
+        helpers::generated::bpf_xdp_get_buff_len,
+        macros::{map, xdp},
+        maps::{Array, XskMap},
         programs::XdpContext,
     },
-    core::ptr,
+    core::{mem, ptr},
 };
 
 #[unsafe(no_mangle)]
 // Set to 1 from user space at load time to control whether we must drop multi-frags packets
 static AGAVE_XDP_DROP_MULTI_FRAGS: u8 = 0;
 
+// Upper bound on the RX queue index an AF_XDP socket can be bound to
+const MAX_RX_QUEUES: u32 = 256;
+
+#[map]
+// AF_XDP sockets indexed by the RX queue they're bound to
+static AGAVE_XDP_RX_SOCKETS: XskMap = XskMap::with_max_entries(MAX_RX_QUEUES, 0);
+
+#[map]
+// Indexed by UDP destination port, non-zero for ports steered to AF_XDP sockets
+static AGAVE_XDP_RX_PORTS: Array<u8> = Array::with_max_entries(1 << 16, 0);
+
+const ETH_HEADER_SIZE: usize = 14;
+const VLAN_TAG_SIZE: usize = 4;
+const IP_HEADER_SIZE: usize = 20;
+const ETH_P_IP: u16 = 0x0800;
+const ETH_P_8021Q: u16 = 0x8100;
+const IPPROTO_UDP: u8 = 17;
+// more-fragments flag and fragment offset
+const IP_FRAGMENT_MASK: u16 = 0x3fff;
+
 #[xdp]
 pub fn agave_xdp(ctx: XdpContext) -> u32 {
     if drop_frags() && has_frags(&ctx) {
@@ -27,6 +48,73 @@ pub fn agave_xdp(ctx: XdpContext) -> u32 {
     }
 }
 
+#[xdp]
+pub fn agave_xdp_rx(ctx: XdpContext) -> u32 {
+    if drop_frags() && has_frags(&ctx) {
+        return XDP_DROP;
+    }
+    match udp_dst_port(&ctx) {
+        Some(port) if is_steered_port(port) => {
+            // Safety: static verifier guarantees ctx.ctx is valid
+            let queue = unsafe { (*ctx.ctx).rx_queue_index };
+            // falls back to XDP_PASS if no socket is bound to the queue, so that the packet still
+            // reaches the regular UDP socket
+            match AGAVE_XDP_RX_SOCKETS.redirect(queue, u64::from(XDP_PASS)) {
+                Ok(action) | Err(action) => action,
+            }
+        }
+        _ => XDP_PASS,
+    }
+}
+
+#[inline]
+fn is_steered_port(port: u16) -> bool {
+    AGAVE_XDP_RX_PORTS
+        .get(u32::from(port))
+        .is_some_and(|steered| *steered != 0)
+}
+
+/// Returns the destination port of an unfragmented IPv4 UDP packet, optionally 802.1Q tagged.
+#[inline]
+#[allow(clippy::arithmetic_side_effects)]
+fn udp_dst_port(ctx: &XdpContext) -> Option<u16> {
+    let mut ip_off = ETH_HEADER_SIZE;
+    let mut ether_type = u16::from_be(read_at(ctx, ETH_HEADER_SIZE - 2)?);
+    if ether_type == ETH_P_8021Q {
+        ip_off += VLAN_TAG_SIZE;
+        ether_type = u16::from_be(read_at(ctx, ip_off - 2)?);
+    }
+    if ether_type != ETH_P_IP {
+        return None;
+    }
+
+    let version_ihl: u8 = read_at(ctx, ip_off)?;
+    let ihl = usize::from(version_ihl & 0x0f) * 4;
+    if version_ihl >> 4 != 4 || ihl < IP_HEADER_SIZE {
+        return None;
+    }
+    let frag: u16 = u16::from_be(read_at(ctx, ip_off + 6)?);
+    let protocol: u8 = read_at(ctx, ip_off + 9)?;
+    if protocol != IPPROTO_UDP || frag & IP_FRAGMENT_MASK != 0 {
+        return None;
+    }
+
+    // dst port is the second field of the UDP header
+    read_at::<u16>(ctx, ip_off + ihl + 2).map(u16::from_be)
+}
+
+/// Reads a `T` at `offset` bytes into the packet, if the packet is long enough.
+#[inline(always)]
+#[allow(clippy::arithmetic_side_effects)]
+fn read_at<T: Copy>(ctx: &XdpContext, offset: usize) -> Option<T> {
+    let start = ctx.data() + offset;
+    if start + mem::size_of::<T>() > ctx.data_end() {
+        return None;
+    }
+    // Safety: the bounds check above is what the verifier needs to allow the access
+    Some(unsafe { ptr::read_unaligned(start as *const T) })
+}
+
 #[inline]
 fn drop_frags() -> bool {
     // SAFETY: This variable is only ever modified at load time, we need the volatile read to
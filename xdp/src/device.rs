@@ -6,7 +6,7 @@ use {
     },
     libc::{
         AF_INET, IF_NAMESIZE, SIOCETHTOOL, SIOCGIFADDR, SIOCGIFHWADDR, SOCK_DGRAM, SYS_ioctl,
-        ifreq, mmap, munmap, socket, syscall, xdp_ring_offset,
+        XDP_RING_NEED_WAKEUP, ifreq, mmap, munmap, socket, syscall, xdp_ring_offset,
     },
     std::{
         ffi::{CStr, CString, c_char},
@@ -423,6 +423,18 @@ impl<F: Frame> RxFillRing<F> {
         Ok(())
     }
 
+    pub fn needs_wakeup(&self) -> bool {
+        unsafe { (*self.mmap.flags).load(Ordering::Relaxed) & XDP_RING_NEED_WAKEUP != 0 }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.size as usize
+    }
+
+    pub fn available(&self) -> usize {
+        self.producer.available() as usize
+    }
+
     pub fn commit(&mut self) {
         self.producer.commit();
     }
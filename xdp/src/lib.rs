@@ -19,6 +19,8 @@ pub mod route;
 #[cfg(target_os = "linux")]
 pub mod route_monitor;
 #[cfg(target_os = "linux")]
+pub mod rx_loop;
+#[cfg(target_os = "linux")]
 pub mod socket;
 #[cfg(target_os = "linux")]
 pub mod tx_loop;
@@ -27,10 +29,12 @@ pub mod umem;
 
 pub mod ecn_codepoint;
 
+pub mod receiver;
+
 pub mod transmitter;
 
 #[cfg(target_os = "linux")]
-pub use program::load_xdp_program;
+pub use program::{load_xdp_program, load_xdp_rx_program};
 use std::{io, net::Ipv4Addr};
 
 /// Returns the IPv4 address of the specified network interface.
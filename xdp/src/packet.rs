@@ -3,7 +3,10 @@
 use {
     crate::ecn_codepoint::EcnCodepoint,
     libc::{ETH_P_IP, IPPROTO_UDP},
-    std::net::Ipv4Addr,
+    std::{
+        net::{Ipv4Addr, SocketAddrV4},
+        ops::Range,
+    },
 };
 
 pub const ETH_HEADER_SIZE: usize = 14;
@@ -18,6 +21,58 @@ pub const VLAN_PACKET_HEADER_SIZE: usize = VLAN_ETH_HEADER_SIZE + IP_HEADER_SIZE
 const IP_DONT_FRAGMENT: u16 = 0x4000;
 /// EtherType identifying an 802.1Q tagged frame.
 const ETH_P_8021Q: u16 = 0x8100;
+/// Mask of the IPv4 more-fragments flag and fragment offset.
+const IP_FRAGMENT_MASK: u16 = 0x3fff;
+
+/// Header fields of a received IPv4 UDP frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UdpPacketInfo {
+    pub src_addr: SocketAddrV4,
+    pub dst_addr: SocketAddrV4,
+    /// Range of the UDP payload within the frame.
+    pub payload: Range<usize>,
+}
+
+/// Parse an untagged or 802.1Q tagged Ethernet frame carrying an unfragmented IPv4 UDP datagram.
+///
+/// Returns `None` for any other frame, or if the headers are truncated or inconsistent.
+pub fn parse_udp_packet(frame: &[u8]) -> Option<UdpPacketInfo> {
+    let mut ip_off = ETH_HEADER_SIZE;
+    let mut ether_type = u16::from_be_bytes(frame.get(12..14)?.try_into().ok()?);
+    if ether_type == ETH_P_8021Q {
+        ip_off = VLAN_ETH_HEADER_SIZE;
+        ether_type = u16::from_be_bytes(frame.get(16..18)?.try_into().ok()?);
+    }
+    if ether_type != ETH_P_IP as u16 {
+        return None;
+    }
+
+    let ip = frame.get(ip_off..ip_off + IP_HEADER_SIZE)?;
+    let ihl = usize::from(ip[0] & 0x0f) * 4;
+    if ip[0] >> 4 != 4 || ihl < IP_HEADER_SIZE || ip[9] != IPPROTO_UDP as u8 {
+        return None;
+    }
+    if u16::from_be_bytes([ip[6], ip[7]]) & IP_FRAGMENT_MASK != 0 {
+        return None;
+    }
+    let src_ip = Ipv4Addr::new(ip[12], ip[13], ip[14], ip[15]);
+    let dst_ip = Ipv4Addr::new(ip[16], ip[17], ip[18], ip[19]);
+
+    let udp_off = ip_off + ihl;
+    let udp = frame.get(udp_off..udp_off + UDP_HEADER_SIZE)?;
+    let src_port = u16::from_be_bytes([udp[0], udp[1]]);
+    let dst_port = u16::from_be_bytes([udp[2], udp[3]]);
+    let udp_len = usize::from(u16::from_be_bytes([udp[4], udp[5]]));
+    if udp_len < UDP_HEADER_SIZE || frame.len() < udp_off + udp_len {
+        return None;
+    }
+
+    Some(UdpPacketInfo {
+        src_addr: SocketAddrV4::new(src_ip, src_port),
+        dst_addr: SocketAddrV4::new(dst_ip, dst_port),
+        payload: udp_off + UDP_HEADER_SIZE..udp_off + udp_len,
+    })
+}
 
 pub fn write_eth_header(packet: &mut [u8], src_mac: &[u8; 6], dst_mac: &[u8; 6]) {
     packet[0..6].copy_from_slice(dst_mac);
@@ -430,4 +485,78 @@ mod tests {
         // Buffer must not be partially written.
         assert!(tiny.iter().all(|&b| b == 0));
     }
+
+    #[test]
+    fn test_parse_udp_packet() {
+        let src_ip = Ipv4Addr::new(10, 228, 0, 5);
+        let dst_ip = Ipv4Addr::new(10, 228, 0, 9);
+        let payload = b"hello-world";
+        let expected = |header_size| UdpPacketInfo {
+            src_addr: SocketAddrV4::new(src_ip, 7000),
+            dst_addr: SocketAddrV4::new(dst_ip, 7733),
+            payload: header_size..header_size + payload.len(),
+        };
+
+        // trailing bytes (e.g. ethernet padding) are not part of the payload
+        let mut buf = vec![0u8; PACKET_HEADER_SIZE + payload.len() + 4];
+        assert!(construct_packet(
+            &mut buf, &[0; 6], &[0; 6], &src_ip, &dst_ip, 7000, 7733, payload, None,
+        ));
+        let info = parse_udp_packet(&buf).unwrap();
+        assert_eq!(info, expected(PACKET_HEADER_SIZE));
+        assert_eq!(&buf[info.payload], payload);
+
+        let mut buf = vec![0u8; VLAN_PACKET_HEADER_SIZE + payload.len()];
+        assert!(construct_vlan_packet(
+            &mut buf, &[0; 6], &[0; 6], &src_ip, &dst_ip, 7000, 7733, 900, 0, payload, None,
+        ));
+        let info = parse_udp_packet(&buf).unwrap();
+        assert_eq!(info, expected(VLAN_PACKET_HEADER_SIZE));
+        assert_eq!(&buf[info.payload], payload);
+    }
+
+    #[test]
+    fn test_parse_udp_packet_rejects_invalid_frames() {
+        let src_ip = Ipv4Addr::new(10, 228, 0, 5);
+        let dst_ip = Ipv4Addr::new(10, 228, 0, 9);
+        let payload = b"hello-world";
+        let mut packet = vec![0u8; PACKET_HEADER_SIZE + payload.len()];
+        assert!(construct_packet(
+            &mut packet,
+            &[0; 6],
+            &[0; 6],
+            &src_ip,
+            &dst_ip,
+            7000,
+            7733,
+            payload,
+            None,
+        ));
+
+        // truncated payload
+        assert!(parse_udp_packet(&packet[..packet.len() - 1]).is_none());
+        // truncated headers
+        assert!(parse_udp_packet(&packet[..PACKET_HEADER_SIZE - 1]).is_none());
+
+        // not IPv4
+        let mut buf = packet.clone();
+        buf[12..14].copy_from_slice(&0x86ddu16.to_be_bytes());
+        assert!(parse_udp_packet(&buf).is_none());
+
+        // not UDP
+        let mut buf = packet.clone();
+        buf[ETH_HEADER_SIZE + 9] = libc::IPPROTO_TCP as u8;
+        assert!(parse_udp_packet(&buf).is_none());
+
+        // fragmented
+        let mut buf = packet.clone();
+        buf[ETH_HEADER_SIZE + 6..ETH_HEADER_SIZE + 8].copy_from_slice(&0x2000u16.to_be_bytes());
+        assert!(parse_udp_packet(&buf).is_none());
+
+        // UDP length shorter than the UDP header
+        let mut buf = packet;
+        let udp_off = ETH_HEADER_SIZE + IP_HEADER_SIZE;
+        buf[udp_off + 4..udp_off + 6].copy_from_slice(&4u16.to_be_bytes());
+        assert!(parse_udp_packet(&buf).is_none());
+    }
 }
@@ -1,12 +1,16 @@
 #![allow(clippy::arithmetic_side_effects)]
 
 use {
-    crate::device::NetworkDevice,
+    crate::device::{NetworkDevice, QueueId},
     aya::{
         Ebpf, EbpfLoader,
+        maps::{Array, XskMap},
         programs::{Xdp, xdp::XdpMode},
     },
-    std::io::{Cursor, Write},
+    std::{
+        io::{Cursor, Write},
+        os::fd::BorrowedFd,
+    },
 };
 
 macro_rules! write_fields {
@@ -60,6 +64,57 @@ pub fn load_xdp_program(dev: &NetworkDevice) -> Result<Ebpf, Box<dyn std::error:
     Ok(ebpf)
 }
 
+/// Loads the RX program and attaches it to `dev`.
+///
+/// IPv4 UDP packets destined to one of `ports` are redirected to the AF_XDP socket bound to the
+/// RX queue they arrive on. Everything else, including packets arriving on queues without a
+/// socket in `sockets`, goes through the regular network stack.
+pub fn load_xdp_rx_program(
+    dev: &NetworkDevice,
+    ports: &[u16],
+    sockets: &[(QueueId, BorrowedFd<'_>)],
+    generic_mode: bool,
+) -> Result<Ebpf, Box<dyn std::error::Error>> {
+    let mut loader = EbpfLoader::new();
+    // the RX program replaces the one loaded for TX, so it must drop multi-frags packets too.
+    // Virtual devices such as veth have no driver link.
+    if dev.driver().is_ok_and(|driver| driver == "i40e") {
+        loader.override_global("AGAVE_XDP_DROP_MULTI_FRAGS", &1u8, true);
+    }
+    let mut ebpf = loader.load(agave_xdp_ebpf::AGAVE_XDP_EBPF_PROGRAM)?;
+
+    let mut port_map: Array<_, u8> = ebpf
+        .map_mut("AGAVE_XDP_RX_PORTS")
+        .ok_or("AGAVE_XDP_RX_PORTS map not found")?
+        .try_into()?;
+    for port in ports {
+        port_map.set(u32::from(*port), 1, 0)?;
+    }
+
+    let mut socket_map: XskMap<_> = ebpf
+        .map_mut("AGAVE_XDP_RX_SOCKETS")
+        .ok_or("AGAVE_XDP_RX_SOCKETS map not found")?
+        .try_into()?;
+    for &(queue_id, fd) in sockets {
+        socket_map.set(queue_id.0 as u32, fd, 0)?;
+    }
+
+    let p: &mut Xdp = ebpf
+        .program_mut("agave_xdp_rx")
+        .ok_or("agave_xdp_rx program not found")?
+        .try_into()?;
+    p.load()?;
+
+    let mode = if generic_mode {
+        XdpMode::Skb
+    } else {
+        XdpMode::Driver
+    };
+    p.attach_to_if_index(dev.if_index(), mode)?;
+
+    Ok(ebpf)
+}
+
 fn generate_xdp_elf() -> Vec<u8> {
     let mut buffer = vec![0u8; 4096];
     let mut cursor = Cursor::new(&mut buffer);
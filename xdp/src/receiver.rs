@@ -0,0 +1,176 @@
+pub use crate::transmitter::QueueCpuBinding;
+#[cfg(target_os = "linux")]
+use {
+    crate::{
+        device::{NetworkDevice, QueueId},
+        load_xdp_rx_program,
+        rx_loop::{RxLoop, RxLoopBuilder},
+        transmitter::CapGuard,
+        umem::OwnedUmem,
+    },
+    agave_cpu_utils::{CpuId, cpu_affinity, set_cpu_affinity},
+    aya::Ebpf,
+    std::{os::fd::AsFd, thread::Builder},
+};
+use {
+    bytes::Bytes,
+    std::{
+        error::Error,
+        net::SocketAddrV4,
+        sync::{Arc, atomic::AtomicBool},
+        thread,
+    },
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct XdpRxConfig {
+    pub interface: Option<String>,
+    /// NIC-queue -> CPU-core bindings. One RX worker is created per entry, receiving the packets
+    /// steered to that hardware queue.
+    pub queues: Vec<QueueCpuBinding>,
+    pub zero_copy: bool,
+    /// Attach the XDP program in generic mode, for drivers without native XDP support.
+    pub generic_mode: bool,
+    /// UDP destination ports whose packets are received via XDP.
+    pub ports: Vec<u16>,
+}
+
+/// A UDP packet received via XDP.
+pub struct RxPacket {
+    pub src_addr: SocketAddrV4,
+    pub dst_addr: SocketAddrV4,
+    /// The UDP payload, usually backed by the UMEM frame the packet was received into.
+    ///
+    /// The frame is given back to the NIC once the payload and all its clones are dropped, so
+    /// holding on to payloads for long reduces the number of packets that can be received. When
+    /// free frames run low, payloads are copied out instead and their frames reused immediately.
+    pub payload: Bytes,
+}
+
+pub struct Receiver {
+    threads: Vec<thread::JoinHandle<()>>,
+    // keeps the program attached until the RX threads have exited
+    #[cfg(target_os = "linux")]
+    _ebpf: Ebpf,
+}
+
+#[cfg(not(target_os = "linux"))]
+pub struct ReceiverBuilder {}
+
+#[cfg(target_os = "linux")]
+pub struct ReceiverBuilder {
+    rx_loops: Vec<RxLoop<OwnedUmem>>,
+    ebpf: Ebpf,
+}
+
+impl ReceiverBuilder {
+    #[cfg(not(target_os = "linux"))]
+    pub fn new(_config: XdpRxConfig) -> Result<Self, Box<dyn Error>> {
+        Err("XDP is only supported on Linux".into())
+    }
+
+    /// Creates the AF_XDP sockets and attaches the XDP program that steers `config.ports` to
+    /// them.
+    ///
+    /// Until the sockets are bound and the program is attached, packets keep being delivered to
+    /// regular UDP sockets, so an error here leaves the regular receive path untouched.
+    #[cfg(target_os = "linux")]
+    pub fn new(config: XdpRxConfig) -> Result<Self, Box<dyn Error>> {
+        use caps::Capability::{CAP_BPF, CAP_NET_ADMIN, CAP_NET_RAW, CAP_PERFMON};
+
+        let XdpRxConfig {
+            interface: maybe_interface,
+            queues,
+            zero_copy,
+            generic_mode,
+            ports,
+        } = config;
+
+        if queues.is_empty() {
+            return Err("no queues configured for XDP receive".into());
+        }
+
+        let dev = if let Some(interface) = maybe_interface {
+            NetworkDevice::new(interface)?
+        } else {
+            NetworkDevice::new_from_default_route()?
+        };
+
+        let original_cores = cpu_affinity(None)?;
+        let mut rx_loop_builders = Vec::with_capacity(queues.len());
+        for binding in queues {
+            // allocate the Umem region from the target cpu so that it's on the correct numa node
+            set_cpu_affinity(None, [CpuId::new(binding.cpu)?])?;
+            let rx_loop_builder =
+                RxLoopBuilder::new(binding.cpu, QueueId(binding.queue as u64), zero_copy, &dev);
+            set_cpu_affinity(None, original_cores.iter().copied())?;
+            rx_loop_builders.push(rx_loop_builder?);
+        }
+
+        let _setup_caps = CapGuard::raise([CAP_NET_ADMIN, CAP_NET_RAW])?;
+
+        let rx_loops = rx_loop_builders
+            .into_iter()
+            .map(|rx_loop_builder| rx_loop_builder.build())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let ebpf = {
+            let _ebpf_caps = CapGuard::raise([CAP_BPF, CAP_PERFMON])?;
+            let sockets = rx_loops
+                .iter()
+                .map(|rx_loop| (rx_loop.queue_id(), rx_loop.as_fd()))
+                .collect::<Vec<_>>();
+            load_xdp_rx_program(&dev, &ports, &sockets, generic_mode)
+                .map_err(|e| format!("failed to attach xdp rx program: {e}"))?
+        };
+
+        Ok(Self { rx_loops, ebpf })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn build<F>(self, _exit: Arc<AtomicBool>, _on_packets: F) -> Receiver
+    where
+        F: FnMut(Vec<RxPacket>) + Clone + Send + 'static,
+    {
+        Receiver { threads: vec![] }
+    }
+
+    /// Spawns one thread per RX queue, each passing the packets it receives to a clone of
+    /// `on_packets`.
+    #[cfg(target_os = "linux")]
+    pub fn build<F>(self, exit: Arc<AtomicBool>, on_packets: F) -> Receiver
+    where
+        F: FnMut(Vec<RxPacket>) + Clone + Send + 'static,
+    {
+        let Self { rx_loops, ebpf } = self;
+
+        let threads = rx_loops
+            .into_iter()
+            .enumerate()
+            .map(|(i, rx_loop)| {
+                let exit = Arc::clone(&exit);
+                let on_packets = on_packets.clone();
+                Builder::new()
+                    .name(format!("solXdpRecvIO{i:02}"))
+                    .spawn(move || rx_loop.run(&exit, on_packets))
+                    .unwrap()
+            })
+            .collect();
+
+        Receiver {
+            threads,
+            _ebpf: ebpf,
+        }
+    }
+}
+
+impl Receiver {
+    /// Waits for the RX threads to exit, then detaches the XDP program so that packets are
+    /// delivered to regular UDP sockets again.
+    pub fn join(self) -> thread::Result<()> {
+        for handle in self.threads {
+            handle.join()?;
+        }
+        Ok(())
+    }
+}
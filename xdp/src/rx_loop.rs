@@ -0,0 +1,293 @@
+#![allow(clippy::arithmetic_side_effects)]
+
+use {
+    crate::{
+        device::{DeviceQueue, NetworkDevice, QueueId, RingSizes, RxFillRing},
+        packet::{UdpPacketInfo, parse_udp_packet},
+        receiver::RxPacket,
+        socket::{Rx, RxDesc, RxRing, Socket},
+        umem::{OwnedUmem, PageAlignedMemory, RxFrame, Umem},
+    },
+    agave_cpu_utils::set_cpu_affinity,
+    bytes::Bytes,
+    libc::{_SC_PAGESIZE, POLLIN, poll, pollfd, sysconf},
+    std::{
+        io, mem,
+        os::fd::{AsFd, AsRawFd as _, BorrowedFd},
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+        time::{Duration, Instant},
+    },
+};
+
+pub struct RxLoopBuilder<U: Umem> {
+    cpu_id: usize,
+    zero_copy: bool,
+    queue: DeviceQueue,
+    rx_size: usize,
+    umem: Arc<U>,
+}
+
+impl RxLoopBuilder<OwnedUmem> {
+    pub fn new(
+        cpu_id: usize,
+        queue_id: QueueId,
+        zero_copy: bool,
+        dev: &NetworkDevice,
+    ) -> Result<Self, io::Error> {
+        log::info!(
+            "starting xdp rx loop on {} queue {queue_id:?} cpu {cpu_id}",
+            dev.name()
+        );
+
+        // some drivers require frame_size=page_size
+        let frame_size = unsafe { sysconf(_SC_PAGESIZE) } as usize;
+
+        let queue = dev.open_queue(queue_id)?;
+        let RingSizes { rx: rx_size, .. } = queue.ring_sizes().unwrap_or_else(|| {
+            log::info!(
+                "using default ring sizes for {} queue {queue_id:?}",
+                dev.name()
+            );
+            RingSizes::default()
+        });
+
+        // Received frames are handed out without copying and only come back once the packets are
+        // dropped downstream. Size the UMEM so that the fill ring can be kept full while that
+        // happens.
+        let frame_count = rx_size * 4;
+
+        const HUGE_2MB: usize = 2 * 1024 * 1024;
+        let memory =
+            PageAlignedMemory::alloc_with_page_size(frame_size, frame_count, HUGE_2MB, true)
+                .or_else(|_| {
+                    log::warn!("huge page alloc failed, falling back to regular page size");
+                    PageAlignedMemory::alloc(frame_size, frame_count)
+                })
+                .map_err(|_| io::Error::other("failed to allocate UMEM for AF_XDP RX socket"))?;
+        let umem = OwnedUmem::new(memory, frame_size as u32)?;
+
+        Ok(RxLoopBuilder {
+            cpu_id,
+            zero_copy,
+            queue,
+            rx_size,
+            umem: Arc::new(umem),
+        })
+    }
+
+    pub fn build(self) -> Result<RxLoop<OwnedUmem>, io::Error> {
+        let RxLoopBuilder {
+            cpu_id,
+            zero_copy,
+            queue,
+            rx_size,
+            umem,
+        } = self;
+
+        let queue_id = queue.id();
+        let (socket, rx) =
+            Socket::rx(queue, umem, zero_copy, rx_size * 2, rx_size).map_err(|err| {
+                log::error!(
+                    "failed to create AF_XDP RX socket for queue {queue_id:?} on CPU {cpu_id}: \
+                     {err}"
+                );
+                err
+            })?;
+
+        let Rx {
+            // this is where we hand frames to the kernel to receive packets into
+            mut fill,
+            // this is where we get frames back once packets have been received into them
+            ring,
+        } = rx;
+        let ring = ring.unwrap();
+
+        // the XDP program starts redirecting packets before the loop runs, give the kernel frames
+        // to receive them into right away
+        refill(&mut fill, socket.umem().as_ref());
+
+        Ok(RxLoop {
+            cpu_id,
+            socket,
+            fill,
+            ring,
+            // keep enough free frames around to refill the kernel's RX ring
+            copy_watermark: rx_size,
+        })
+    }
+}
+
+pub struct RxLoop<U: Umem> {
+    cpu_id: usize,
+    socket: Socket<Arc<U>>,
+    fill: RxFillRing<U::Frame>,
+    ring: RxRing,
+    copy_watermark: usize,
+}
+
+impl<U: Umem> RxLoop<U> {
+    pub fn queue_id(&self) -> QueueId {
+        self.socket.queue().id()
+    }
+}
+
+impl<U: Umem> AsFd for RxLoop<U> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.socket.as_fd()
+    }
+}
+
+impl<U: Umem + Send + Sync + 'static> RxLoop<U> {
+    /// Receives packets until `exit` is set, passing them to `on_packets` in batches.
+    ///
+    /// Frames that don't contain an IPv4 UDP packet are dropped. Payloads normally point into the
+    /// UMEM, but are copied out when fewer than `copy_watermark` frames are free so that slow
+    /// consumers can't starve the fill ring.
+    pub fn run<F>(self, exit: &AtomicBool, mut on_packets: F)
+    where
+        F: FnMut(Vec<RxPacket>),
+    {
+        const BATCH_SIZE: usize = 64;
+
+        // How long we wait for packets before checking exit again.
+        const POLL_TIMEOUT_MS: i32 = 10;
+
+        const METRICS_INTERVAL: Duration = Duration::from_secs(1);
+
+        let RxLoop {
+            cpu_id,
+            socket,
+            mut fill,
+            mut ring,
+            copy_watermark,
+        } = self;
+
+        // each queue is bound to its own CPU core
+        set_cpu_affinity(None, [agave_cpu_utils::CpuId::new(cpu_id).unwrap()]).unwrap();
+
+        let umem = Arc::clone(socket.umem());
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        let mut stats = RxLoopStats::default();
+        let mut last_report = Instant::now();
+
+        while !exit.load(Ordering::Relaxed) {
+            if last_report.elapsed() >= METRICS_INTERVAL {
+                stats.report(socket.queue().id(), umem.available());
+                last_report = Instant::now();
+            }
+
+            if refill(&mut fill, umem.as_ref()) {
+                stats.fill_ring_starved += 1;
+            }
+            if fill.needs_wakeup() {
+                let _ = ring.wake();
+            }
+
+            ring.sync(false);
+            if ring.available() == 0 {
+                // nothing received, wait for the next packet. This also kicks the driver if it
+                // needs a wakeup.
+                wait_readable(&socket, POLL_TIMEOUT_MS);
+                continue;
+            }
+
+            // downstream is holding on to too many frames, stop handing out zero-copy payloads
+            // until it catches up
+            let copy = umem.available() < copy_watermark;
+            while let Some(RxDesc { offset, len }) = ring.read() {
+                let frame = RxFrame::new(Arc::clone(&umem), offset, len);
+                // dropping the frame hands it back to the UMEM
+                let Some(UdpPacketInfo {
+                    src_addr,
+                    dst_addr,
+                    payload,
+                }) = parse_udp_packet(frame.as_ref())
+                else {
+                    continue;
+                };
+                let payload = if copy {
+                    stats.copied_packets += 1;
+                    Bytes::copy_from_slice(&frame.as_ref()[payload])
+                } else {
+                    Bytes::from_owner(frame).slice(payload)
+                };
+                stats.packets += 1;
+                batch.push(RxPacket {
+                    src_addr,
+                    dst_addr,
+                    payload,
+                });
+                if batch.len() == BATCH_SIZE {
+                    on_packets(mem::replace(&mut batch, Vec::with_capacity(BATCH_SIZE)));
+                }
+            }
+            // let the kernel reuse the descriptors we've read
+            ring.commit();
+
+            if !batch.is_empty() {
+                on_packets(mem::replace(&mut batch, Vec::with_capacity(BATCH_SIZE)));
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct RxLoopStats {
+    packets: u64,
+    copied_packets: u64,
+    fill_ring_starved: u64,
+}
+
+impl RxLoopStats {
+    fn report(&mut self, queue_id: QueueId, free_frames: usize) {
+        let RxLoopStats {
+            packets,
+            copied_packets,
+            fill_ring_starved,
+        } = mem::take(self);
+        solana_metrics::datapoint_info!(
+            "xdp-rx",
+            "queue" => queue_id.0.to_string(),
+            ("packets", packets, i64),
+            ("copied_packets", copied_packets, i64),
+            ("fill_ring_starved", fill_ring_starved, i64),
+            ("free_frames", free_frames, i64),
+        );
+    }
+}
+
+/// Hands free frames to the kernel. Returns true if the fill ring had space left but there were
+/// no free frames to put in it.
+fn refill<U: Umem>(fill: &mut RxFillRing<U::Frame>, umem: &U) -> bool {
+    fill.sync(false);
+    let mut written = 0;
+    let mut starved = false;
+    while fill.available() > 0 {
+        let Some(frame) = umem.reserve() else {
+            // all the frames are either owned by the kernel or still held downstream
+            starved = true;
+            break;
+        };
+        fill.write(frame).expect("fill ring has space");
+        written += 1;
+    }
+    if written > 0 {
+        fill.commit();
+    }
+    starved
+}
+
+fn wait_readable(fd: &impl AsFd, timeout_ms: i32) {
+    let mut pfd = pollfd {
+        fd: fd.as_fd().as_raw_fd(),
+        events: POLLIN,
+        revents: 0,
+    };
+    // Safety: just a libc wrapper, pfd is a valid pollfd
+    unsafe {
+        poll(&mut pfd, 1, timeout_ms);
+    }
+}
@@ -4,15 +4,15 @@ use {
             DeviceQueue, RingConsumer, RingMmap, RingProducer, RxFillRing, TxCompletionRing,
             XdpDesc, mmap_ring,
         },
-        umem::{Frame, Umem},
+        umem::{Frame, FrameOffset, Umem},
     },
     libc::{
         AF_XDP, SOCK_RAW, SOL_XDP, XDP_COPY, XDP_MMAP_OFFSETS, XDP_PGOFF_RX_RING,
         XDP_PGOFF_TX_RING, XDP_RING_NEED_WAKEUP, XDP_RX_RING, XDP_TX_RING,
         XDP_UMEM_COMPLETION_RING, XDP_UMEM_FILL_RING, XDP_UMEM_PGOFF_COMPLETION_RING,
-        XDP_UMEM_PGOFF_FILL_RING, XDP_USE_NEED_WAKEUP, XDP_ZEROCOPY, bind, getsockopt, sa_family_t,
-        sendto, setsockopt, sockaddr, sockaddr_xdp, socket, socklen_t, xdp_mmap_offsets,
-        xdp_umem_reg,
+        XDP_UMEM_PGOFF_FILL_RING, XDP_USE_NEED_WAKEUP, XDP_ZEROCOPY, bind, getsockopt, recvfrom,
+        sa_family_t, sendto, setsockopt, sockaddr, sockaddr_xdp, socket, socklen_t,
+        xdp_mmap_offsets, xdp_umem_reg,
     },
     std::{
         io,
@@ -82,8 +82,8 @@ impl<U: Umem> Socket<U> {
                 (XDP_TX_RING, tx_ring_size),
                 (XDP_RX_RING, rx_ring_size),
             ] {
-                if ring == XDP_RX_RING && size == 0 {
-                    // tx only
+                if (ring == XDP_RX_RING || ring == XDP_TX_RING) && size == 0 {
+                    // tx or rx only
                     continue;
                 }
 
@@ -161,17 +161,21 @@ impl<U: Umem> Socket<U> {
                 rx_fill_ring.commit();
             }
 
-            let tx_ring = Some(TxRing::new(
-                mmap_ring(
+            let tx_ring = if tx_ring_size > 0 {
+                Some(TxRing::new(
+                    mmap_ring(
+                        fd.as_raw_fd(),
+                        tx_ring_size.saturating_mul(mem::size_of::<XdpDesc>()),
+                        &offsets.tx,
+                        XDP_PGOFF_TX_RING as u64,
+                    )
+                    .map_err(|source| Error::syscall("mmap tx ring failed", source))?,
+                    tx_ring_size as u32,
                     fd.as_raw_fd(),
-                    tx_ring_size.saturating_mul(mem::size_of::<XdpDesc>()),
-                    &offsets.tx,
-                    XDP_PGOFF_TX_RING as u64,
-                )
-                .map_err(|source| Error::syscall("mmap tx ring failed", source))?,
-                tx_ring_size as u32,
-                fd.as_raw_fd(),
-            ));
+                ))
+            } else {
+                None
+            };
 
             let rx_ring = if rx_ring_size > 0 {
                 Some(RxRing::new(
@@ -271,7 +275,8 @@ impl<U: Umem> Socket<U> {
         fill_size: usize,
         ring_size: usize,
     ) -> Result<(Self, Rx<U::Frame>), io::Error> {
-        let (socket, rx, _) = Self::new(queue, umem, zero_copy, fill_size, ring_size, 0, 0)?;
+        // the kernel requires a completion ring even if nothing is ever transmitted
+        let (socket, rx, _) = Self::new(queue, umem, zero_copy, fill_size, ring_size, 1, 0)?;
         Ok((socket, rx))
     }
 
@@ -368,12 +373,17 @@ impl<F: Frame> TxRing<F> {
     }
 }
 
+/// A frame the kernel has written a received packet into.
+#[derive(Debug)]
+pub struct RxDesc {
+    pub offset: FrameOffset,
+    pub len: usize,
+}
+
 pub struct RxRing {
-    #[allow(dead_code)]
     mmap: RingMmap<XdpDesc>,
     consumer: RingConsumer,
     size: u32,
-    #[allow(dead_code)]
     fd: RawFd,
 }
 
@@ -388,6 +398,36 @@ impl RxRing {
         }
     }
 
+    pub fn read(&mut self) -> Option<RxDesc> {
+        let index = self.consumer.consume()? & self.size.saturating_sub(1);
+        // Safety: index is within the ring so the pointer is valid
+        let desc = unsafe { self.mmap.desc.add(index as usize).read() };
+        Some(RxDesc {
+            offset: FrameOffset(desc.addr as usize),
+            len: desc.len as usize,
+        })
+    }
+
+    /// Kicks the driver to refill its RX queue from the fill ring.
+    ///
+    /// Should be called when [`RxFillRing::needs_wakeup`] returns true.
+    pub fn wake(&self) -> Result<u64, io::Error> {
+        let result = unsafe {
+            recvfrom(
+                self.fd,
+                ptr::null_mut(),
+                0,
+                libc::MSG_DONTWAIT,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(result as u64)
+    }
+
     pub fn capacity(&self) -> usize {
         self.size as usize
     }
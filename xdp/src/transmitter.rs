@@ -60,6 +60,9 @@ pub struct XdpConfig {
     // The capacity of the channel that sits between senders and each XDP thread that enqueues
     // packets to the NIC.
     pub tx_channel_cap: usize,
+    /// Whether to attach an XDP program when zero copy requires one. Disable if another program,
+    /// such as the one attached by `ReceiverBuilder`, is already attached to the interface.
+    pub attach_program: bool,
 }
 
 impl XdpConfig {
@@ -74,6 +77,7 @@ impl Default for XdpConfig {
             queues: vec![],
             zero_copy: false,
             tx_channel_cap: Self::DEFAULT_TX_CHANNEL_CAP,
+            attach_program: true,
         }
     }
 }
@@ -89,6 +93,7 @@ impl XdpConfig {
             queues,
             zero_copy,
             tx_channel_cap: XdpConfig::DEFAULT_TX_CHANNEL_CAP,
+            attach_program: true,
         }
     }
 
@@ -104,6 +109,7 @@ impl XdpConfig {
             queues,
             zero_copy,
             tx_channel_cap,
+            attach_program: true,
         }
     }
 }
@@ -334,6 +340,7 @@ impl TransmitterBuilder {
             queues,
             zero_copy,
             tx_channel_cap,
+            attach_program,
         } = config;
 
         let dev = Arc::new(if let Some(interface) = maybe_interface {
@@ -382,7 +389,7 @@ impl TransmitterBuilder {
         let _setup_caps =
             CapGuard::raise([CAP_NET_ADMIN, CAP_NET_RAW]).expect("raise net capabilities");
 
-        let maybe_ebpf_result = if zero_copy {
+        let maybe_ebpf_result = if zero_copy && attach_program {
             let _ebpf_caps =
                 CapGuard::raise([CAP_BPF, CAP_PERFMON]).expect("raise ebpf capabilities");
 
@@ -588,13 +595,13 @@ const CAP_GUARD_CAPACITY: usize = 2;
 
 #[cfg(target_os = "linux")]
 #[must_use = "capabilities are dropped when the guard goes out of scope"]
-struct CapGuard {
+pub(crate) struct CapGuard {
     capabilities: ArrayVec<caps::Capability, CAP_GUARD_CAPACITY>,
 }
 
 #[cfg(target_os = "linux")]
 impl CapGuard {
-    fn raise(
+    pub(crate) fn raise(
         raised_capabilities: impl IntoIterator<Item = caps::Capability>,
     ) -> Result<Self, caps::errors::CapsError> {
         let mut capabilities = ArrayVec::new();
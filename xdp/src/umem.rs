@@ -110,6 +110,44 @@ impl<F> DerefMut for MappedFrameMut<'_, F> {
     }
 }
 
+/// A frame the kernel received a packet into.
+///
+/// The frame is only returned to its UMEM once dropped, so the packet can be handed out without
+/// being copied, e.g. as the owner of a `Bytes`.
+pub struct RxFrame<U: Umem> {
+    umem: Arc<U>,
+    // offset of the packet data, which starts after the RX headroom of the frame
+    offset: usize,
+    len: usize,
+}
+
+impl<U: Umem> RxFrame<U> {
+    pub(crate) fn new(umem: Arc<U>, offset: FrameOffset, len: usize) -> Self {
+        debug_assert!(offset.0 + len <= umem.len());
+        Self {
+            umem,
+            offset: offset.0,
+            len,
+        }
+    }
+}
+
+impl<U: Umem> AsRef<[u8]> for RxFrame<U> {
+    fn as_ref(&self) -> &[u8] {
+        // Safety: the kernel wrote `len` bytes at `offset`, and the frame can't be handed back to
+        // the kernel until it's released to the UMEM on drop.
+        unsafe { slice::from_raw_parts(self.umem.as_ptr().add(self.offset), self.len) }
+    }
+}
+
+impl<U: Umem> Drop for RxFrame<U> {
+    fn drop(&mut self) {
+        let start = self.offset - self.offset % self.umem.frame_size();
+        self.umem
+            .release_completed(CompletedFrameOffset(FrameOffset(start)));
+    }
+}
+
 pub struct SliceUmemFrame<'a> {
     offset: usize,
     len: usize,
@@ -397,8 +435,8 @@ impl DerefMut for PageAlignedMemory {
 #[cfg(test)]
 mod tests {
     use {
-        crate::umem::{CompletedFrameOffset, Frame, SliceUmem, Umem},
-        std::slice,
+        crate::umem::{CompletedFrameOffset, Frame, FrameOffset, RxFrame, SliceUmem, Umem},
+        std::{slice, sync::Arc},
     };
 
     #[test]
@@ -434,4 +472,28 @@ mod tests {
 
         assert_eq!(umem.available(), umem.capacity());
     }
+
+    #[test]
+    fn test_rx_frame() {
+        let mut buffer = [0; 16];
+        let umem = Arc::new(SliceUmem::new(&mut buffer, 8).unwrap());
+        let mut frame = umem.reserve().unwrap();
+        let offset = frame.offset().0;
+        frame.set_len(8);
+        umem.map_frame_mut(frame)
+            .copy_from_slice(&[0, 0, 1, 2, 3, 0, 0, 0]);
+
+        // received packets start past the frame headroom
+        let rx_frame = RxFrame::new(Arc::clone(&umem), FrameOffset(offset + 2), 3);
+        assert_eq!(rx_frame.as_ref(), &[1, 2, 3]);
+        assert_eq!(umem.available(), umem.capacity() - 1);
+
+        drop(rx_frame);
+        assert_eq!(umem.available(), umem.capacity());
+        // the start of the frame is what gets released
+        let frames = (0..umem.capacity())
+            .map(|_| umem.reserve().unwrap().offset().0)
+            .collect::<Vec<_>>();
+        assert!(frames.contains(&offset));
+    }
 }
@@ -0,0 +1,188 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use {
+    agave_cpu_utils::cpu_affinity,
+    agave_xdp::{
+        packet::{PACKET_HEADER_SIZE, construct_packet},
+        receiver::{QueueCpuBinding, Receiver, ReceiverBuilder, RxPacket, XdpRxConfig},
+    },
+    nix::sys::socket::{
+        AddressFamily, MsgFlags, SockFlag, SockProtocol, SockType, SockaddrLike, SockaddrStorage,
+        bind, send, socket,
+    },
+    std::{
+        fs, io, mem,
+        net::{SocketAddrV4, UdpSocket},
+        os::fd::{AsRawFd, OwnedFd},
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+            mpsc,
+        },
+        time::Duration,
+    },
+};
+
+fn receiver_cpu() -> usize {
+    let cores = cpu_affinity(None).expect("linux provides affine cores");
+    *cores[0]
+}
+
+/// Injects raw frames into an interface, so that they're received by its veth peer.
+struct PacketInjector {
+    fd: OwnedFd,
+}
+
+impl PacketInjector {
+    fn bind(if_index: u32) -> io::Result<Self> {
+        let fd = socket(
+            AddressFamily::Packet,
+            SockType::Raw,
+            SockFlag::SOCK_CLOEXEC,
+            SockProtocol::EthAll,
+        )
+        .map_err(io::Error::from)?;
+        let addr = libc::sockaddr_ll {
+            sll_family: libc::AF_PACKET as u16,
+            sll_protocol: (libc::ETH_P_ALL as u16).to_be(),
+            sll_ifindex: if_index as i32,
+            sll_hatype: 0,
+            sll_pkttype: 0,
+            sll_halen: 0,
+            sll_addr: [0; 8],
+        };
+        let addr = unsafe {
+            SockaddrStorage::from_raw(
+                (&addr as *const libc::sockaddr_ll).cast(),
+                Some(mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t),
+            )
+        }
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid packet address"))?;
+        bind(fd.as_raw_fd(), &addr).map_err(io::Error::from)?;
+        Ok(Self { fd })
+    }
+
+    fn send_udp(
+        &self,
+        links: &common::TestLinks,
+        src_port: u16,
+        dst_port: u16,
+        payload: &[u8],
+    ) -> io::Result<()> {
+        let mut frame = vec![0u8; PACKET_HEADER_SIZE.saturating_add(payload.len())];
+        assert!(construct_packet(
+            &mut frame,
+            &links.left_mac.0,
+            &links.right_mac.0,
+            &links.left_ip,
+            &links.right_ip,
+            src_port,
+            dst_port,
+            payload,
+            None,
+        ));
+        send(self.fd.as_raw_fd(), &frame, MsgFlags::empty()).map_err(io::Error::from)?;
+        Ok(())
+    }
+}
+
+struct ReceiverGuard {
+    receiver: Option<Receiver>,
+    exit: Arc<AtomicBool>,
+}
+
+impl Drop for ReceiverGuard {
+    fn drop(&mut self) {
+        self.exit.store(true, Ordering::Relaxed);
+        let Some(receiver) = self.receiver.take() else {
+            return;
+        };
+        if let Err(err) = receiver.join() {
+            if std::thread::panicking() {
+                eprintln!("receiver thread panicked: {err:?}");
+            } else {
+                std::panic::resume_unwind(err);
+            }
+        }
+    }
+}
+
+#[test]
+#[ignore = "requires root and network namespace privileges"]
+fn receiver_steers_configured_ports_in_generic_mode() {
+    let cpu_id = receiver_cpu();
+
+    let _netns = common::NetNsGuard::new().expect("create network namespace");
+    let links = common::setup_veth_pair_with_tx_queue_count(1);
+
+    // both veth endpoints are local, let the peer accept packets from a local source address
+    fs::write("/proc/sys/net/ipv4/conf/axdp1/accept_local", "1")
+        .expect("enable accept_local on veth peer");
+
+    let steered_port = 45_690;
+    let unsteered_port = 45_691;
+    let src_port = 12_350;
+
+    // create the sockets before the receiver drops our effective capabilities
+    let injector = PacketInjector::bind(links.left_if_index).expect("bind raw packet injector");
+    // a plain socket on the veth peer, so the socket configuration of solana-net-utils isn't needed
+    #[allow(clippy::disallowed_methods)]
+    let udp_socket = UdpSocket::bind(SocketAddrV4::new(links.right_ip, unsteered_port))
+        .expect("bind udp socket for unsteered port");
+    udp_socket
+        .set_read_timeout(Some(Duration::from_secs(3)))
+        .expect("set udp socket read timeout");
+
+    let config = XdpRxConfig {
+        interface: Some(common::RIGHT_IFACE.to_string()),
+        queues: vec![QueueCpuBinding {
+            queue: 0,
+            cpu: cpu_id,
+        }],
+        zero_copy: false,
+        generic_mode: true,
+        ports: vec![steered_port],
+    };
+    let exit = Arc::new(AtomicBool::new(false));
+    let (packet_sender, packet_receiver) = mpsc::channel::<RxPacket>();
+    let receiver = ReceiverBuilder::new(config)
+        .expect("build generic mode receiver")
+        .build(Arc::clone(&exit), move |packets| {
+            for packet in packets {
+                let _ = packet_sender.send(packet);
+            }
+        });
+    let _receiver = ReceiverGuard {
+        receiver: Some(receiver),
+        exit,
+    };
+
+    let payload = b"agave-xdp-receiver-smoke";
+    injector
+        .send_udp(&links, src_port, steered_port, payload)
+        .expect("inject steered packet");
+    let packet = packet_receiver
+        .recv_timeout(Duration::from_secs(3))
+        .expect("receive steered packet via AF_XDP");
+    assert_eq!(packet.src_addr, SocketAddrV4::new(links.left_ip, src_port));
+    assert_eq!(
+        packet.dst_addr,
+        SocketAddrV4::new(links.right_ip, steered_port)
+    );
+    assert_eq!(packet.payload.as_ref(), payload);
+
+    // ports that aren't steered keep going through the kernel stack
+    let payload = b"agave-xdp-receiver-passthrough";
+    injector
+        .send_udp(&links, src_port, unsteered_port, payload)
+        .expect("inject unsteered packet");
+    let mut buf = [0u8; 2048];
+    let (len, src_addr) = udp_socket
+        .recv_from(&mut buf)
+        .expect("receive unsteered packet via udp socket");
+    assert_eq!(&buf[..len], payload);
+    assert_eq!(src_addr, (links.left_ip, src_port).into());
+    assert!(packet_receiver.try_recv().is_err());
+}